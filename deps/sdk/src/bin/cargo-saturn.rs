@@ -0,0 +1,53 @@
+//! `cargo saturn` subcommand.
+//!
+//! Usage: `cargo saturn new <name> [--error-offset <offset>]`
+
+use arch_sdk::{ProgramScaffold, DEFAULT_PROGRAM_ERROR_OFFSET};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: cargo saturn new <name> [--error-offset <offset>]";
+
+fn main() -> ExitCode {
+    // When invoked through cargo the first argument is the subcommand name itself.
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("saturn") {
+        args.remove(0);
+    }
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> anyhow::Result<()> {
+    let (command, rest) = args.split_first().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+    if command != "new" {
+        return Err(anyhow::anyhow!("unknown command `{command}`\n{USAGE}"));
+    }
+
+    let mut name = None;
+    let mut error_offset = DEFAULT_PROGRAM_ERROR_OFFSET;
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--error-offset" => {
+                let value = iter.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+                error_offset = value.parse()?;
+            }
+            _ if name.is_none() => name = Some(arg.as_str()),
+            _ => return Err(anyhow::anyhow!("unexpected argument `{arg}`\n{USAGE}")),
+        }
+    }
+
+    let name = name.ok_or_else(|| anyhow::anyhow!(USAGE))?;
+    let root = ProgramScaffold::new(name)?
+        .with_error_offset(error_offset)
+        .write_to(&std::env::current_dir()?)?;
+
+    println!("Created Saturn program `{}` in {}", name, root.display());
+    Ok(())
+}
@@ -1,11 +1,13 @@
 mod bip322;
 mod keys;
 mod program_deployment;
+mod scaffold;
 mod transaction_building;
 mod utxo;
 
 pub use bip322::*;
 pub use keys::*;
 pub use program_deployment::*;
+pub use scaffold::*;
 pub use transaction_building::*;
 pub use utxo::*;
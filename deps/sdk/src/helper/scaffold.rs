@@ -0,0 +1,349 @@
+use crate::arch_program::pubkey::Pubkey;
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::with_secret_key_file;
+
+/// Default error offset for program-defined errors. Offsets below this value
+/// are reserved for the Saturn crates themselves (utxo parser, bitcoin
+/// transactions, account parser, ...).
+pub const DEFAULT_PROGRAM_ERROR_OFFSET: u32 = 6000;
+
+/// Name of the file (relative to the program directory) holding the program keypair.
+pub const PROGRAM_KEYPAIR_FILE: &str = "program.key";
+
+/// A single file emitted by [`ProgramScaffold`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScaffoldFile {
+    /// Path relative to the program directory.
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Generator for a new Saturn program crate.
+///
+/// The skeleton declares the program id, one `Accounts` struct, one
+/// `UtxoParser` struct, a `#[saturn_error]` enum with a reserved offset and an
+/// integration-test harness that deploys the program to a local validator.
+/// The generated manifest resolves the Saturn crates through
+/// `workspace = true`, so the crate is meant to be created inside a workspace
+/// that declares them in `[workspace.dependencies]`.
+#[derive(Debug, Clone)]
+pub struct ProgramScaffold {
+    name: String,
+    program_id: Option<Pubkey>,
+    error_offset: u32,
+}
+
+impl ProgramScaffold {
+    /// Create a new scaffold for the crate `name`.
+    ///
+    /// The name must be a valid cargo package name made of ASCII
+    /// alphanumerics, `-` and `_`, starting with a letter.
+    pub fn new(name: &str) -> Result<Self> {
+        validate_name(name)?;
+        Ok(Self {
+            name: name.to_string(),
+            program_id: None,
+            error_offset: DEFAULT_PROGRAM_ERROR_OFFSET,
+        })
+    }
+
+    /// Use `program_id` in `declare_id!` instead of generating a fresh keypair.
+    pub fn with_program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = Some(program_id);
+        self
+    }
+
+    /// Override the offset used by the generated error enum.
+    pub fn with_error_offset(mut self, offset: u32) -> Self {
+        self.error_offset = offset;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Rust identifier of the generated library (`-` replaced by `_`).
+    pub fn lib_name(&self) -> String {
+        self.name.replace('-', "_")
+    }
+
+    /// Render every file of the skeleton using `program_id` for `declare_id!`.
+    pub fn render(&self, program_id: &Pubkey) -> Vec<ScaffoldFile> {
+        let program_id = bs58::encode(program_id.serialize()).into_string();
+        let type_prefix = to_pascal_case(&self.name);
+
+        vec![
+            ScaffoldFile {
+                path: PathBuf::from("Cargo.toml"),
+                contents: render_manifest(&self.name),
+            },
+            ScaffoldFile {
+                path: PathBuf::from(".gitignore"),
+                contents: format!("/target\n{}\n", PROGRAM_KEYPAIR_FILE),
+            },
+            ScaffoldFile {
+                path: PathBuf::from("src/lib.rs"),
+                contents: render_lib(&program_id, &type_prefix, self.error_offset),
+            },
+            ScaffoldFile {
+                path: PathBuf::from("tests/integration.rs"),
+                contents: render_integration_tests(&self.name, &self.lib_name()),
+            },
+        ]
+    }
+
+    /// Write the skeleton to `parent_dir/<name>` and return the program directory.
+    ///
+    /// Fails if the target directory already exists. When no program id was
+    /// supplied a keypair is generated and stored in [`PROGRAM_KEYPAIR_FILE`]
+    /// so that the integration tests deploy under the declared id.
+    pub fn write_to(&self, parent_dir: &Path) -> Result<PathBuf> {
+        let root = parent_dir.join(&self.name);
+        if root.exists() {
+            return Err(anyhow!("Destination {} already exists", root.display()));
+        }
+        fs::create_dir_all(&root)?;
+
+        let program_id = match self.program_id {
+            Some(program_id) => program_id,
+            None => {
+                let key_path = root.join(PROGRAM_KEYPAIR_FILE);
+                let key_path = key_path
+                    .to_str()
+                    .ok_or_else(|| anyhow!("Non UTF-8 path {}", key_path.display()))?;
+                with_secret_key_file(key_path)?.1
+            }
+        };
+
+        for file in self.render(&program_id) {
+            let path = root.join(&file.path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, file.contents)?;
+        }
+
+        Ok(root)
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let starts_with_letter = chars.next().is_some_and(|c| c.is_ascii_alphabetic());
+    if !starts_with_letter || !chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(anyhow!(
+            "Invalid program name `{}`: expected ASCII letters, digits, `-` or `_`, starting with a letter",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn to_pascal_case(name: &str) -> String {
+    name.split(['-', '_'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn render_manifest(name: &str) -> String {
+    format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+crate-type = ["cdylib", "lib"]
+
+[dependencies]
+arch_program = {{ workspace = true }}
+mempool-oracle-sdk = {{ workspace = true }}
+
+saturn-account-discriminator-derive = {{ workspace = true }}
+saturn-account-macros = {{ workspace = true }}
+saturn-account-parser = {{ workspace = true }}
+saturn-bitcoin-transactions = {{ workspace = true, features = ["runes"] }}
+saturn-program-macros = {{ workspace = true }}
+saturn-utxo-parser = {{ workspace = true }}
+saturn-utxo-parser-derive = {{ workspace = true }}
+
+# Saturn unified error handling
+saturn-error = {{ workspace = true }}
+saturn-error-derive = {{ workspace = true }}
+
+borsh = {{ workspace = true }}
+bytemuck = {{ workspace = true }}
+
+[dev-dependencies]
+arch_sdk = {{ workspace = true }}
+"#
+    )
+}
+
+fn render_lib(program_id: &str, type_prefix: &str, error_offset: u32) -> String {
+    format!(
+        r#"use arch_program::utxo::UtxoMeta;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_error::saturn_error;
+use saturn_program_macros::{{declare_id, saturn_program}};
+use saturn_utxo_parser::UtxoParser;
+
+declare_id!("{program_id}");
+
+/// Program-specific errors. Offsets below {error_offset} are reserved for the
+/// Saturn crates, keep new variants at the end of the enum.
+#[saturn_error(offset = {error_offset})]
+pub enum {type_prefix}Error {{
+    #[error("The supplied UTXO does not match the expected state")]
+    UnexpectedUtxo,
+}}
+
+#[derive(Accounts)]
+pub struct InitializeAccounts<'info> {{
+    #[account(signer, mut)]
+    pub payer: Account<'info, u64>,
+
+    #[account(
+        init_if_needed,
+        mut,
+        payer = payer,
+        seeds = &[b"state"],
+        program_id = id()
+    )]
+    pub state: Account<'info, UtxoMeta>,
+}}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(InitializeAccounts)]
+pub struct InitializeUtxos {{
+    #[utxo(runes = "none")]
+    pub fee_utxo: UtxoInfo,
+
+    #[utxo(rest)]
+    pub others: Vec<UtxoInfo>,
+}}
+
+#[saturn_program(btc_tx_cfg(max_inputs_to_sign = 4, max_modified_accounts = 4, rune_capacity = 1))]
+mod handlers {{
+    use arch_program::{{program_error::ProgramError, utxo::UtxoMeta}};
+    use saturn_utxo_parser::TryFromUtxos;
+
+    use super::*;
+
+    pub fn initialize(
+        ctx: Context<'info, InitializeAccounts<'info>>,
+        params: Vec<UtxoMeta>,
+    ) -> Result<(), ProgramError> {{
+        let utxos = InitializeUtxos::try_utxos(&ctx.accounts, &params)?;
+
+        if utxos.fee_utxo.value == 0 {{
+            return Err({type_prefix}Error::UnexpectedUtxo.into());
+        }}
+
+        Ok(())
+    }}
+}}
+"#
+    )
+}
+
+fn render_integration_tests(name: &str, lib_name: &str) -> String {
+    format!(
+        r#"//! Integration tests against a local validator (see `arch_sdk::Config::localnet`).
+//!
+//! Build the program with `cargo build-sbf` and start the local validator, then
+//! run `cargo test -- --ignored`. Set `PROGRAM_ELF` to override the path of the
+//! compiled program.
+
+use arch_sdk::{{with_secret_key_file, Config, ProgramDeployer}};
+
+const PROGRAM_KEYPAIR_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/{keypair}");
+const AUTHORITY_KEYPAIR_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/authority.key");
+
+fn program_elf_path() -> String {{
+    std::env::var("PROGRAM_ELF").unwrap_or_else(|_| {{
+        format!("{{}}/target/deploy/{lib_name}.so", env!("CARGO_MANIFEST_DIR"))
+    }})
+}}
+
+#[test]
+#[ignore = "requires a running local validator"]
+fn deploys_under_declared_id() {{
+    let config = Config::localnet();
+
+    let (program_keypair, program_pubkey) =
+        with_secret_key_file(PROGRAM_KEYPAIR_PATH).expect("program keypair");
+    assert_eq!(program_pubkey, {lib_name}::id());
+
+    std::fs::create_dir_all(concat!(env!("CARGO_MANIFEST_DIR"), "/target")).unwrap();
+    let (authority_keypair, _) =
+        with_secret_key_file(AUTHORITY_KEYPAIR_PATH).expect("authority keypair");
+
+    let deployer = ProgramDeployer::new(&config.arch_node_url, config.network);
+    let deployed = deployer
+        .try_deploy_program(
+            "{name}".to_string(),
+            program_keypair,
+            authority_keypair,
+            &program_elf_path(),
+        )
+        .expect("deployment failed");
+
+    assert_eq!(deployed, {lib_name}::id());
+}}
+"#,
+        keypair = PROGRAM_KEYPAIR_FILE,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_names() {
+        assert!(ProgramScaffold::new("").is_err());
+        assert!(ProgramScaffold::new("1program").is_err());
+        assert!(ProgramScaffold::new("my program").is_err());
+        assert!(ProgramScaffold::new("my-program_2").is_ok());
+    }
+
+    #[test]
+    fn renders_declared_id_and_error_offset() {
+        let program_id = Pubkey::from_slice(&[7u8; 32]);
+        let files = ProgramScaffold::new("my-program")
+            .unwrap()
+            .with_error_offset(7000)
+            .render(&program_id);
+
+        let lib = files
+            .iter()
+            .find(|f| f.path == Path::new("src/lib.rs"))
+            .unwrap();
+        let encoded = bs58::encode([7u8; 32]).into_string();
+        assert!(lib
+            .contents
+            .contains(&format!("declare_id!(\"{}\");", encoded)));
+        assert!(lib.contents.contains("#[saturn_error(offset = 7000)]"));
+        assert!(lib.contents.contains("pub enum MyProgramError"));
+
+        let tests = files
+            .iter()
+            .find(|f| f.path == Path::new("tests/integration.rs"))
+            .unwrap();
+        assert!(tests.contents.contains("my_program::id()"));
+    }
+}
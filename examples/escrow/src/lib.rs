@@ -81,9 +81,7 @@ struct WithdrawUtxos {
 #[saturn_program(btc_tx_cfg(max_inputs_to_sign = 4, max_modified_accounts = 4, rune_capacity = 1))]
 mod handlers {
     use arch_program::{
-        bitcoin::{Amount, ScriptBuf, TxOut},
-        program::get_bitcoin_tx_output_value,
-        program_error::ProgramError,
+        bitcoin::ScriptBuf, program::get_bitcoin_tx_output_value, program_error::ProgramError,
         utxo::UtxoMeta,
     };
     use mempool_oracle_sdk::TxStatus;
@@ -129,10 +127,7 @@ mod handlers {
 
         let fee = 1_000;
 
-        ctx.btc_tx.transaction.output.push(TxOut {
-            script_pubkey: ScriptBuf::new(),
-            value: Amount::from_sat(utxo_info.value - fee),
-        });
+        ctx.btc_tx.add_output(ScriptBuf::new(), utxo_info.value - fee);
 
         Ok(())
     }
@@ -0,0 +1,127 @@
+//! `#[derive(AccountEnum)]`: account fields accepting one of several
+//! zero-copy account types.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+/// Implements `saturn_account_parser::AccountEnum` (and `AsRef<AccountInfo>`)
+/// for an enum whose variants each wrap one account type, e.g.
+/// `Config(AccountLoader<'info, Config>)`.
+pub(crate) fn derive(input: &DeriveInput) -> Result<TokenStream, syn::Error> {
+    let enum_ident = &input.ident;
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            enum_ident,
+            "#[derive(AccountEnum)] can only be used with enums",
+        ));
+    };
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            enum_ident,
+            "#[derive(AccountEnum)] requires at least one variant",
+        ));
+    }
+
+    let lifetime = input
+        .generics
+        .lifetimes()
+        .find(|lt_def| lt_def.lifetime.ident == "info")
+        .map(|lt_def| lt_def.lifetime.clone())
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                enum_ident,
+                "Enum deriving AccountEnum must declare a lifetime parameter named `'info`",
+            )
+        })?;
+
+    let mut variant_idents = Vec::with_capacity(data.variants.len());
+    let mut variant_tys = Vec::with_capacity(data.variants.len());
+    for variant in &data.variants {
+        let Fields::Unnamed(fields) = &variant.fields else {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "AccountEnum variants must wrap exactly one account type, e.g. `Config(AccountLoader<'info, Config>)`",
+            ));
+        };
+        if fields.unnamed.len() != 1 {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "AccountEnum variants must wrap exactly one account type, e.g. `Config(AccountLoader<'info, Config>)`",
+            ));
+        }
+        variant_idents.push(&variant.ident);
+        variant_tys.push(&fields.unnamed[0].ty);
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics saturn_account_parser::AccountEnum<#lifetime> for #enum_ident #ty_generics #where_clause {
+            fn try_from_info(
+                info: &#lifetime arch_program::account::AccountInfo<#lifetime>,
+            ) -> Result<Self, arch_program::program_error::ProgramError> {
+                let discriminator = saturn_account_parser::read_discriminator(info)?;
+                #(
+                    if discriminator == <#variant_tys as saturn_account_parser::AccountVariant<#lifetime>>::DISCRIMINATOR {
+                        return Ok(Self::#variant_idents(
+                            <#variant_tys as saturn_account_parser::AccountVariant<#lifetime>>::wrap(info),
+                        ));
+                    }
+                )*
+                Err(arch_program::program_error::ProgramError::Custom(
+                    saturn_account_parser::error::ErrorCode::UnknownAccountDiscriminator.into(),
+                ))
+            }
+
+            fn account_info(&self) -> &#lifetime arch_program::account::AccountInfo<#lifetime> {
+                match self {
+                    #(
+                        Self::#variant_idents(inner) => {
+                            saturn_account_parser::AccountVariant::account_info(inner)
+                        }
+                    )*
+                }
+            }
+        }
+
+        impl #impl_generics AsRef<arch_program::account::AccountInfo<#lifetime>> for #enum_ident #ty_generics #where_clause {
+            fn as_ref(&self) -> &arch_program::account::AccountInfo<#lifetime> {
+                saturn_account_parser::AccountEnum::account_info(self)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn dispatches_on_variant_discriminators() {
+        let di: DeriveInput = parse_quote! {
+            enum PoolAccount<'info> {
+                Config(AccountLoader<'info, Config>),
+                Shard(AccountLoader<'info, Shard>),
+            }
+        };
+        let rendered = derive(&di).expect("derive ok").to_string();
+        assert!(rendered.contains("impl < 'info > saturn_account_parser :: AccountEnum < 'info > for PoolAccount < 'info >"));
+        assert!(rendered.contains(
+            "< AccountLoader < 'info , Shard > as saturn_account_parser :: AccountVariant < 'info >> :: DISCRIMINATOR"
+        ));
+        assert!(rendered.contains("UnknownAccountDiscriminator"));
+    }
+
+    #[test]
+    fn rejects_struct_variants() {
+        let di: DeriveInput = parse_quote! {
+            enum PoolAccount<'info> {
+                Config { loader: AccountLoader<'info, Config> },
+            }
+        };
+        let err = derive(&di).unwrap_err();
+        assert!(err.to_string().contains("exactly one account type"));
+    }
+}
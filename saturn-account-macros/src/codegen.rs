@@ -1,7 +1,12 @@
+mod bumps;
+mod client;
+mod close;
+mod cpi;
 mod fixed_slice;
+mod idl;
 mod orchestrator;
 mod shards;
 mod single;
-mod utils;
+pub(crate) mod utils;
 
 pub(crate) use orchestrator::generate;
@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::model::{FieldCfg, FieldKind};
+
+/// Name of the bumps struct generated for `struct_ident` (`<Struct>Bumps`).
+pub(crate) fn bumps_ident(struct_ident: &Ident) -> Ident {
+    format_ident!("{}Bumps", struct_ident)
+}
+
+/// Generate the `<Struct>Bumps` struct holding the bump of every single PDA
+/// field and `bump` placeholder, plus the bumps of `nested` fields. Their
+/// seeds are kept in a hidden `__seeds` field for `Context::signer_seeds`.
+pub(crate) fn generate_bumps_struct(struct_ident: &Ident, fields: &[FieldCfg]) -> TokenStream {
+    let bumps_ident = bumps_ident(struct_ident);
+
+    let bump_fields: Vec<TokenStream> = fields
+        .iter()
+        .filter_map(|cfg| {
+            let ident = &cfg.ident;
+            let ty = match &cfg.kind {
+                FieldKind::Single if cfg.seeds.is_some() => {
+                    if cfg.optional.is_some() {
+                        quote! { Option<u8> }
+                    } else {
+                        quote! { u8 }
+                    }
+                }
+                FieldKind::Bump => quote! { u8 },
+                FieldKind::Nested => nested_bumps_ty(&cfg.base_ty),
+                _ => return None,
+            };
+            Some(quote! { pub #ident: #ty })
+        })
+        .collect();
+
+    let doc = format!("Bump seeds of the PDAs in `{}`.", struct_ident);
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Default, Clone)]
+        pub struct #bumps_ident {
+            #(#bump_fields,)*
+            #[doc(hidden)]
+            pub __seeds: saturn_account_parser::SignerSeeds,
+        }
+
+        impl saturn_account_parser::PdaSeeds for #bumps_ident {
+            fn signer_seeds(&self) -> &saturn_account_parser::SignerSeeds {
+                &self.__seeds
+            }
+        }
+    }
+}
+
+/// `<Nested>Bumps` for a nested `Accounts` struct of type `ty`, keeping its
+/// module path but dropping the generic arguments.
+fn nested_bumps_ty(ty: &syn::Type) -> TokenStream {
+    let syn::Type::Path(type_path) = ty else {
+        return syn::Error::new_spanned(ty, "`nested` field must name an `Accounts` struct")
+            .to_compile_error();
+    };
+
+    let mut path = type_path.path.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.ident = bumps_ident(&last.ident);
+        last.arguments = syn::PathArguments::None;
+    }
+    quote! { #path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{parse_quote, Data, DeriveInput, Fields};
+
+    fn extract_named_fields(
+        di: &DeriveInput,
+    ) -> &syn::punctuated::Punctuated<syn::Field, syn::token::Comma> {
+        match &di.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(named) => &named.named,
+                _ => panic!("named"),
+            },
+            _ => panic!("struct"),
+        }
+    }
+
+    #[test]
+    fn generates_bumps_struct() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer)]
+                user: arch_program::account::AccountInfo<'info>,
+                #[account(seeds = &[b"vault"], program_id = crate::ID)]
+                vault: arch_program::account::AccountInfo<'info>,
+                #[account(bump, seeds = &[b"config"], program_id = crate::ID)]
+                config_bump: u8,
+                #[account(nested)]
+                pool: pool::PoolAccounts<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let rendered = generate_bumps_struct(&di.ident, &parsed).to_string();
+        assert!(rendered.contains("pub struct AccsBumps"));
+        assert!(rendered.contains("pub vault : u8"));
+        assert!(rendered.contains("pub config_bump : u8"));
+        assert!(rendered.contains("pub pool : pool :: PoolAccountsBumps"));
+        assert!(rendered.contains("pub __seeds : saturn_account_parser :: SignerSeeds"));
+        assert!(rendered.contains("impl saturn_account_parser :: PdaSeeds for AccsBumps"));
+        assert!(!rendered.contains("user"));
+    }
+}
@@ -0,0 +1,175 @@
+//! Off-chain `AccountMeta` builder (`client` feature).
+//!
+//! `metas(..)` lists the accounts of the struct in declaration order with the
+//! signer / writable flags the on-chain parser expects, deriving the `seeds`
+//! PDAs itself so clients cannot drift from the program.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident};
+
+use super::utils::{is_writable_meta, pda_program_id};
+use crate::model::{FieldCfg, FieldKind, InstructionArg, OptionalMode};
+
+/// Generate `impl <Struct> { pub fn metas(..) -> Vec<AccountMeta> }`.
+///
+/// Parameters are the `#[instruction(..)]` arguments followed by one key per
+/// account the caller chooses: a `Pubkey` per single account (`Option<Pubkey>`
+/// when optional), a `&[Pubkey]` per slice or shard vector and the
+/// `Vec<AccountMeta>` of each `nested` struct. Accounts pinned by `address` or
+/// derived from `seeds` take no parameter.
+pub(crate) fn generate_metas(
+    struct_ident: &Ident,
+    generics: &Generics,
+    fields: &[FieldCfg],
+    instruction_args: &[InstructionArg],
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut params: Vec<TokenStream> = instruction_args
+        .iter()
+        .map(|arg| {
+            let (ident, ty) = (&arg.ident, &arg.ty);
+            quote! { #ident: #ty }
+        })
+        .collect();
+    let mut pushes: Vec<TokenStream> = Vec::with_capacity(fields.len());
+
+    for cfg in fields {
+        let ident = &cfg.ident;
+        let is_signer = cfg.is_signer == Some(true);
+        let is_writable = is_writable_meta(cfg);
+        let meta = |key: TokenStream| {
+            quote! {
+                arch_program::account::AccountMeta {
+                    pubkey: #key,
+                    is_signer: #is_signer,
+                    is_writable: #is_writable,
+                }
+            }
+        };
+
+        match &cfg.kind {
+            FieldKind::Single => {
+                if let Some(mode) = cfg.optional {
+                    params.push(quote! { #ident: Option<arch_program::pubkey::Pubkey> });
+                    let present = meta(quote! { key });
+                    let omitted = match mode {
+                        OptionalMode::Sentinel => quote! {
+                            metas.push(arch_program::account::AccountMeta {
+                                pubkey: crate::ID,
+                                is_signer: false,
+                                is_writable: false,
+                            });
+                        },
+                        OptionalMode::Trailing => TokenStream::new(),
+                    };
+                    pushes.push(quote! {
+                        match #ident {
+                            Some(key) => metas.push(#present),
+                            None => { #omitted }
+                        }
+                    });
+                    continue;
+                }
+
+                // Key of the account, from the caller unless it is fixed.
+                let key = if let Some(seeds) = &cfg.seeds {
+                    let program_id = pda_program_id(cfg);
+                    quote! {
+                        let #ident: arch_program::pubkey::Pubkey =
+                            arch_program::pubkey::Pubkey::find_program_address(#seeds, &#program_id).0;
+                    }
+                } else if let Some(address) = &cfg.address {
+                    quote! { let #ident: arch_program::pubkey::Pubkey = #address; }
+                } else {
+                    params.push(quote! { #ident: arch_program::pubkey::Pubkey });
+                    TokenStream::new()
+                };
+                let push = meta(quote! { #ident });
+                pushes.push(quote! {
+                    #key
+                    metas.push(#push);
+                    // Later `seeds` read the key as `#ident.key`, as on-chain.
+                    let #ident = saturn_account_parser::client::KeyRef { key: &#ident };
+                });
+            }
+            FieldKind::FixedSlice(_) | FieldKind::Shards(..) => {
+                params.push(quote! { #ident: &[arch_program::pubkey::Pubkey] });
+                let push = meta(quote! { *key });
+                pushes.push(quote! {
+                    metas.extend(#ident.iter().map(|key| #push));
+                });
+            }
+            FieldKind::Nested => {
+                params.push(quote! { #ident: Vec<arch_program::account::AccountMeta> });
+                pushes.push(quote! {
+                    metas.extend(#ident);
+                });
+            }
+            FieldKind::Bump | FieldKind::Phantom | FieldKind::Sysvar => {}
+        }
+    }
+
+    let doc = format!(
+        "`AccountMeta`s of `{}` in the order, and with the flags, its parser expects.",
+        struct_ident
+    );
+
+    quote! {
+        impl #impl_generics #struct_ident #ty_generics #where_clause {
+            #[doc = #doc]
+            #[allow(unused_variables, clippy::too_many_arguments)]
+            pub fn metas(#(#params),*) -> Vec<arch_program::account::AccountMeta> {
+                let mut metas = Vec::new();
+                #(#pushes)*
+                metas
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{parse_quote, Data, DeriveInput, Fields};
+
+    fn extract_named_fields(
+        di: &DeriveInput,
+    ) -> &syn::punctuated::Punctuated<syn::Field, syn::token::Comma> {
+        match &di.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(named) => &named.named,
+                _ => panic!("named"),
+            },
+            _ => panic!("struct"),
+        }
+    }
+
+    #[test]
+    fn generates_metas() {
+        let di: DeriveInput = parse_quote! {
+            #[instruction(params: OpenParams)]
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                owner: arch_program::account::AccountInfo<'info>,
+                #[account(mut, seeds = &[b"position", owner.key.as_ref()], program_id = crate::ID)]
+                position: arch_program::account::AccountInfo<'info>,
+                #[account(address = TOKEN_PROGRAM_ID)]
+                token_program: arch_program::account::AccountInfo<'info>,
+                #[account(len = 2)]
+                oracles: Vec<arch_program::account::AccountInfo<'info>>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let args = crate::parser::parse_instruction_args(&di.attrs).expect("args ok");
+        let rendered = generate_metas(&di.ident, &di.generics, &parsed, &args).to_string();
+
+        assert!(rendered.contains(
+            "pub fn metas (params : OpenParams , owner : arch_program :: pubkey :: Pubkey , oracles : & [arch_program :: pubkey :: Pubkey])"
+        ));
+        assert!(rendered.contains("find_program_address (& [b\"position\" , owner . key . as_ref ()] , & crate :: ID) . 0"));
+        assert!(rendered.contains("= TOKEN_PROGRAM_ID ;"));
+        assert!(rendered.contains("is_signer : true , is_writable : true"));
+    }
+}
@@ -0,0 +1,105 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::model::{FieldCfg, FieldKind};
+
+/// Generate the `Accounts::exit` override closing every field marked
+/// `#[account(close = destination)]` and running the `exit` of `nested`
+/// fields, or nothing when there is neither so the trait's no-op default
+/// applies.
+pub(crate) fn generate_exit(fields: &[FieldCfg]) -> TokenStream {
+    let nested_exits = fields
+        .iter()
+        .filter(|cfg| matches!(cfg.kind, FieldKind::Nested))
+        .map(|cfg| {
+            let ident = &cfg.ident;
+            quote! {
+                saturn_account_parser::Accounts::exit(&self.#ident)?;
+            }
+        });
+
+    let closes: Vec<TokenStream> = nested_exits
+        .chain(fields.iter().filter_map(|cfg| {
+            let dest_expr = cfg.close.as_ref()?;
+            let ident = &cfg.ident;
+            Some(quote! {
+                saturn_account_parser::close_account(
+                    &saturn_account_parser::ToAccountInfo::to_account_info(&self.#ident),
+                    &saturn_account_parser::ToAccountInfo::to_account_info(&self.#dest_expr),
+                )?;
+            })
+        }))
+        .collect();
+
+    if closes.is_empty() {
+        return TokenStream::new();
+    }
+
+    quote! {
+        fn exit(&self) -> Result<(), arch_program::program_error::ProgramError> {
+            #(#closes)*
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{parse_quote, Data, DeriveInput, Fields};
+
+    fn extract_named_fields(
+        di: &DeriveInput,
+    ) -> &syn::punctuated::Punctuated<syn::Field, syn::token::Comma> {
+        match &di.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(named) => &named.named,
+                _ => panic!("named"),
+            },
+            _ => panic!("struct"),
+        }
+    }
+
+    #[test]
+    fn generates_exit_for_closed_accounts() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                user: arch_program::account::AccountInfo<'info>,
+                #[account(mut, close = user)]
+                escrow: saturn_account_parser::codec::Account<'info, u64>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let rendered = generate_exit(&parsed).to_string();
+        assert!(rendered.contains("fn exit"));
+        assert!(rendered.contains("self . escrow"));
+        assert!(rendered.contains("self . user"));
+        assert!(rendered.contains("saturn_account_parser :: close_account"));
+    }
+
+    #[test]
+    fn generates_exit_for_nested_accounts() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(nested)]
+                pool: PoolAccounts<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let rendered = generate_exit(&parsed).to_string();
+        assert!(rendered.contains("Accounts :: exit (& self . pool)"));
+    }
+
+    #[test]
+    fn no_exit_without_close() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer)]
+                user: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        assert!(generate_exit(&parsed).is_empty());
+    }
+}
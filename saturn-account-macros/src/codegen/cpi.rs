@@ -0,0 +1,158 @@
+//! `ToAccountInfos` / `ToAccountMetas` impls, so a struct can be passed to
+//! another program through `saturn_account_parser::cpi::CpiContext`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident, Lifetime};
+
+use super::utils::is_writable_meta;
+use crate::model::{FieldCfg, FieldKind, OptionalMode};
+
+/// Generate the `ToAccountInfos<'info>` and `ToAccountMetas` impls listing the
+/// accounts of the struct in declaration order.
+pub(crate) fn generate_cpi_impls(
+    struct_ident: &Ident,
+    generics: &Generics,
+    lifetime: &Lifetime,
+    fields: &[FieldCfg],
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut infos: Vec<TokenStream> = Vec::with_capacity(fields.len());
+    let mut metas: Vec<TokenStream> = Vec::with_capacity(fields.len());
+
+    for cfg in fields {
+        let ident = &cfg.ident;
+        let is_signer = cfg.is_signer == Some(true);
+        let is_writable = is_writable_meta(cfg);
+        let meta = |info: TokenStream| {
+            quote! {
+                arch_program::account::AccountMeta {
+                    pubkey: *saturn_account_parser::ToAccountInfo::to_account_info(#info).key,
+                    is_signer: #is_signer,
+                    is_writable: #is_writable,
+                }
+            }
+        };
+
+        match &cfg.kind {
+            FieldKind::Single => {
+                if let Some(mode) = cfg.optional {
+                    let present = meta(quote! { acc });
+                    // The parser expects the program id in the slot of an
+                    // omitted `Sentinel` account.
+                    let omitted = match mode {
+                        OptionalMode::Sentinel => quote! {
+                            metas.push(arch_program::account::AccountMeta {
+                                pubkey: crate::ID,
+                                is_signer: false,
+                                is_writable: false,
+                            });
+                        },
+                        OptionalMode::Trailing => TokenStream::new(),
+                    };
+                    infos.push(quote! {
+                        if let Some(acc) = &self.#ident {
+                            infos.push(saturn_account_parser::ToAccountInfo::to_account_info(acc));
+                        }
+                    });
+                    metas.push(quote! {
+                        match &self.#ident {
+                            Some(acc) => metas.push(#present),
+                            None => { #omitted }
+                        }
+                    });
+                } else {
+                    let push = meta(quote! { &self.#ident });
+                    infos.push(quote! {
+                        infos.push(saturn_account_parser::ToAccountInfo::to_account_info(&self.#ident));
+                    });
+                    metas.push(quote! {
+                        metas.push(#push);
+                    });
+                }
+            }
+            FieldKind::FixedSlice(_) | FieldKind::Shards(..) => {
+                let push = meta(quote! { acc });
+                infos.push(quote! {
+                    infos.extend(self.#ident.iter().map(saturn_account_parser::ToAccountInfo::to_account_info));
+                });
+                metas.push(quote! {
+                    metas.extend(self.#ident.iter().map(|acc| #push));
+                });
+            }
+            FieldKind::Nested => {
+                infos.push(quote! {
+                    infos.extend(saturn_account_parser::ToAccountInfos::to_account_infos(&self.#ident));
+                });
+                metas.push(quote! {
+                    metas.extend(saturn_account_parser::ToAccountMetas::to_account_metas(&self.#ident));
+                });
+            }
+            FieldKind::Bump | FieldKind::Phantom | FieldKind::Sysvar => {}
+        }
+    }
+
+    quote! {
+        impl #impl_generics saturn_account_parser::ToAccountInfos<#lifetime> for #struct_ident #ty_generics #where_clause {
+            fn to_account_infos(&self) -> Vec<arch_program::account::AccountInfo<#lifetime>> {
+                #[allow(unused_mut)]
+                let mut infos = Vec::new();
+                #(#infos)*
+                infos
+            }
+        }
+
+        impl #impl_generics saturn_account_parser::ToAccountMetas for #struct_ident #ty_generics #where_clause {
+            fn to_account_metas(&self) -> Vec<arch_program::account::AccountMeta> {
+                #[allow(unused_mut)]
+                let mut metas = Vec::new();
+                #(#metas)*
+                metas
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{parse_quote, Data, DeriveInput, Fields};
+
+    fn extract_named_fields(
+        di: &DeriveInput,
+    ) -> &syn::punctuated::Punctuated<syn::Field, syn::token::Comma> {
+        match &di.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(named) => &named.named,
+                _ => panic!("named"),
+            },
+            _ => panic!("struct"),
+        }
+    }
+
+    #[test]
+    fn generates_cpi_impls() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                owner: arch_program::account::AccountInfo<'info>,
+                #[account(len = 2)]
+                oracles: Vec<arch_program::account::AccountInfo<'info>>,
+                referrer: Option<arch_program::account::AccountInfo<'info>>,
+                #[account(nested)]
+                pool: PoolAccounts<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let lifetime: Lifetime = parse_quote!('info);
+        let rendered = generate_cpi_impls(&di.ident, &di.generics, &lifetime, &parsed).to_string();
+
+        assert!(rendered.contains("ToAccountInfos < 'info > for Accs < 'info >"));
+        assert!(rendered.contains("is_signer : true , is_writable : true"));
+        assert!(rendered.contains("self . oracles . iter ()"));
+        assert!(rendered.contains("if let Some (acc) = & self . referrer"));
+        assert!(rendered.contains("pubkey : crate :: ID"));
+        assert!(rendered.contains("to_account_metas (& self . pool)"));
+    }
+}
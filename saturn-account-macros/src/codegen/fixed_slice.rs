@@ -1,4 +1,6 @@
-use super::utils::is_account_info_path;
+use super::utils::{
+    account_checks, fetch_error_overrides, is_account_info_path, max_len_check, pda_program_id,
+};
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
@@ -24,11 +26,13 @@ pub(crate) fn generate_fixed_slice_binding(
     let is_acc_info_elem = is_account_info_path(&cfg.base_ty);
 
     // Helper preamble reused across branches.
+    let max_len_check = max_len_check(cfg);
     let common_preamble = quote! {
         let len_val: usize = (#len_ts as usize);
         if len_val > 0xFFFF {
             return Err(arch_program::program_error::ProgramError::InvalidAccountData);
         }
+        #max_len_check
 
         let slice_start = idx;
         let slice_end = idx + len_val;
@@ -37,14 +41,18 @@ pub(crate) fn generate_fixed_slice_binding(
         }
     };
 
+    // Per-element `owner = ...` / `executable` checks on the `acc_info_tmp` in scope.
+    let account_checks_snip = account_checks(cfg, quote! { acc_info_tmp });
+    // `AccountInfo` elements are cloned from the slice afterwards, so the
+    // fetched reference is only bound when it has to be checked.
+    let bind_elem = (!account_checks_snip.is_empty()).then(|| quote! { let acc_info_tmp = });
+    let fetch_errors = fetch_error_overrides(cfg);
+
     if is_acc_info_elem {
         // ------------------- Element type is AccountInfo --------------------
         if cfg.seeds.is_some() {
             let seeds_expr = cfg.seeds.as_ref().unwrap();
-            let program_id_expr = cfg
-                .program_id
-                .as_ref()
-                .expect("program_id required when seeds provided");
+            let program_id_expr = pda_program_id(cfg);
             // Pre-compute seeds & owner references once (avoid repeating the expressions in every
             // loop iteration). Use ident-scoped variable names so we do not clash between fields.
             let seeds_ident = syn::Ident::new(&format!("__{}_seeds", ident), ident.span());
@@ -58,7 +66,7 @@ pub(crate) fn generate_fixed_slice_binding(
 
                 // Validate each element against the derived PDA address
                 for i in 0..len_val {
-                    saturn_account_parser::get_indexed_pda_account(
+                    #bind_elem saturn_account_parser::get_indexed_pda_account(
                         accounts,
                         slice_start + i,
                         #signer_tok,
@@ -66,7 +74,8 @@ pub(crate) fn generate_fixed_slice_binding(
                         #seeds_ident,
                         i as u16,
                         #owner_ident,
-                    )?;
+                    )#fetch_errors?;
+                    #account_checks_snip
                 }
 
                 let mut vec_tmp: Vec<#element_ty_ts> = Vec::with_capacity(len_val);
@@ -82,13 +91,14 @@ pub(crate) fn generate_fixed_slice_binding(
                 #common_preamble
 
                 for i in 0..len_val {
-                    saturn_account_parser::get_account(
+                    #bind_elem saturn_account_parser::get_account(
                         accounts,
                         slice_start + i,
                         #signer_tok,
                         #writable_tok,
                         #address_tok,
-                    )?;
+                    )#fetch_errors?;
+                    #account_checks_snip
                 }
 
                 let mut vec_tmp: Vec<#element_ty_ts> = Vec::with_capacity(len_val);
@@ -160,10 +170,7 @@ pub(crate) fn generate_fixed_slice_binding(
 
         if cfg.seeds.is_some() {
             let seeds_expr = cfg.seeds.as_ref().unwrap();
-            let program_id_expr = cfg
-                .program_id
-                .as_ref()
-                .expect("program_id required when seeds provided");
+            let program_id_expr = pda_program_id(cfg);
             let seeds_ident = syn::Ident::new(&format!("__{}_seeds", ident), ident.span());
             let owner_ident = syn::Ident::new(&format!("__{}_owner", ident), ident.span());
             quote! {
@@ -182,7 +189,8 @@ pub(crate) fn generate_fixed_slice_binding(
                         #seeds_ident,
                         i as u16,
                         #owner_ident,
-                    )?;
+                    )#fetch_errors?;
+                    #account_checks_snip
                     #push_element_snip
                 }
 
@@ -201,7 +209,8 @@ pub(crate) fn generate_fixed_slice_binding(
                         #signer_tok,
                         #writable_tok,
                         #address_tok,
-                    )?;
+                    )#fetch_errors?;
+                    #account_checks_snip
                     #push_element_snip
                 }
 
@@ -283,6 +292,34 @@ mod tests {
         assert!(rendered.contains("get_indexed_pda_account"));
     }
 
+    #[test]
+    fn generates_fixed_slice_owner_check() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(len = 2, owner = arch_program::pubkey::Pubkey::default())]
+                vec_accs: Vec<arch_program::account::AccountInfo<'info>>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let len_ts = match &cfg.kind {
+            crate::model::FieldKind::FixedSlice(l) => l,
+            _ => panic!("expected fixed slice"),
+        };
+        let ts = generate_fixed_slice_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            len_ts,
+        );
+        let rendered = ts.to_string();
+        // Every element is bound and checked against the expected owner.
+        assert!(rendered.contains("let acc_info_tmp = saturn_account_parser :: get_account"));
+        assert!(rendered.contains("InvalidAccountOwner"));
+    }
+
     #[test]
     fn generates_fixed_slice_non_account_info() {
         let di: DeriveInput = parse_quote! {
@@ -0,0 +1,131 @@
+//! `__idl_accounts()` listing the accounts of the struct for the JSON IDL
+//! built by `#[saturn_program]` (`idl-build` feature).
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident};
+
+use super::utils::is_writable_meta;
+use crate::model::{FieldCfg, FieldKind};
+
+/// Generate `impl <Struct> { pub fn __idl_accounts() -> String }`, returning a
+/// JSON array with one entry per account field in declaration order.
+///
+/// Single accounts, slices and shard vectors are described at macro time;
+/// `nested` structs are inlined through their own `__idl_accounts()`.
+pub(crate) fn generate_idl_accounts(
+    struct_ident: &Ident,
+    generics: &Generics,
+    fields: &[FieldCfg],
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let entries = fields.iter().filter_map(|cfg| {
+        let name = json_string(&cfg.ident.to_string());
+        let flags = format!(
+            "\"writable\":{},\"signer\":{}",
+            is_writable_meta(cfg),
+            cfg.is_signer == Some(true)
+        );
+        let entry = match &cfg.kind {
+            FieldKind::Single => {
+                let mut entry = format!("{{\"name\":{name},{flags}");
+                if cfg.optional.is_some() {
+                    entry.push_str(",\"optional\":true");
+                }
+                if cfg.seeds.is_some() {
+                    entry.push_str(",\"pda\":true");
+                }
+                entry.push('}');
+                quote! { String::from(#entry) }
+            }
+            FieldKind::FixedSlice(len) | FieldKind::Shards(len, _) => {
+                let len = json_string(&len.to_string());
+                let entry = format!("{{\"name\":{name},{flags},\"len\":{len}}}");
+                quote! { String::from(#entry) }
+            }
+            FieldKind::Nested => {
+                let base_ty = &cfg.base_ty;
+                let prefix = format!("{{\"name\":{name},\"accounts\":");
+                quote! { format!("{}{}}}", #prefix, <#base_ty>::__idl_accounts()) }
+            }
+            FieldKind::Bump | FieldKind::Phantom | FieldKind::Sysvar => return None,
+        };
+        Some(entry)
+    });
+
+    quote! {
+        impl #impl_generics #struct_ident #ty_generics #where_clause {
+            #[doc(hidden)]
+            pub fn __idl_accounts() -> String {
+                saturn_account_parser::idl::json_array([#(#entries),*])
+            }
+        }
+    }
+}
+
+/// `value` as a JSON string literal, quotes included.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{parse_quote, Data, DeriveInput, Fields};
+
+    fn extract_named_fields(
+        di: &DeriveInput,
+    ) -> &syn::punctuated::Punctuated<syn::Field, syn::token::Comma> {
+        match &di.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(named) => &named.named,
+                _ => panic!("named"),
+            },
+            _ => panic!("struct"),
+        }
+    }
+
+    #[test]
+    fn generates_idl_accounts() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                owner: arch_program::account::AccountInfo<'info>,
+                #[account(len = 2)]
+                oracles: Vec<arch_program::account::AccountInfo<'info>>,
+                referrer: Option<arch_program::account::AccountInfo<'info>>,
+                #[account(nested)]
+                pool: PoolAccounts<'info>,
+                #[account(bump, seeds = &[b"pool"], program_id = ID)]
+                pool_bump: u8,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let rendered = generate_idl_accounts(&di.ident, &di.generics, &parsed).to_string();
+
+        assert!(rendered.contains("pub fn __idl_accounts () -> String"));
+        assert!(rendered.contains(r#"{\"name\":\"owner\",\"writable\":true,\"signer\":true}"#));
+        assert!(rendered.contains(r#"\"name\":\"oracles\",\"writable\":false"#));
+        assert!(rendered.contains(r#"\"len\":\"2\"}"#));
+        assert!(rendered.contains(r#"\"signer\":false,\"optional\":true}"#));
+        assert!(rendered.contains("< PoolAccounts < 'info > > :: __idl_accounts ()"));
+        assert!(!rendered.contains("pool_bump"));
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(json_string(r#"a"b\c"#), r#""a\"b\\c""#);
+    }
+}
@@ -2,15 +2,17 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Expr, ExprPath, Generics, Ident, Lifetime};
 
-use crate::model::{FieldCfg, FieldKind};
+use crate::model::{AccountsCfg, FieldCfg, FieldKind, InstructionArg, OptionalMode};
 
-use super::{fixed_slice, shards, single};
+use super::{bumps, client, close, cpi, fixed_slice, idl, shards, single, utils};
 
 /// Generate the final implementation `TokenStream` for a struct deriving `Accounts`.
 pub(crate) fn generate(
     struct_ident: &Ident,
     generics: &Generics,
     fields: &[FieldCfg],
+    instruction_args: &[InstructionArg],
+    accounts_cfg: &AccountsCfg,
 ) -> Result<TokenStream, syn::Error> {
     // Generate per-field extraction snippets ---------------------------------
     let field_bindings: Vec<TokenStream> = fields
@@ -21,6 +23,59 @@ pub(crate) fn generate(
 
     let field_initialisers: Vec<_> = fields.iter().map(|cfg| &cfg.ident).collect();
 
+    // `exit` override closing the `close = ..` fields (empty when there are none).
+    let exit_fn = close::generate_exit(fields);
+
+    // Leading handler parameters declared with `#[instruction(..)]`, decoded
+    // from the instruction data so the field attributes can use them.
+    let decode_instruction_args = if instruction_args.is_empty() {
+        TokenStream::new()
+    } else {
+        let arg_idents = instruction_args.iter().map(|arg| &arg.ident);
+        let arg_tys = instruction_args.iter().map(|arg| &arg.ty);
+        quote! {
+            #[allow(unused_variables)]
+            let (#(#arg_idents,)*): (#(#arg_tys,)*) = {
+                let mut ix_args_slice: &[u8] = ix_args;
+                saturn_account_parser::__private::borsh::BorshDeserialize::deserialize(&mut ix_args_slice)
+                    .map_err(|_| arch_program::program_error::ProgramError::InvalidInstructionData)?
+            };
+        }
+    };
+
+    // `#[accounts(deny_duplicates)]`: every account consumed so far, nested
+    // structs included, must have its own key. The program id left in the slot
+    // of an omitted optional account is not an account of the struct.
+    let duplicates_check = accounts_cfg.deny_duplicates.then(|| {
+        let has_sentinel = fields
+            .iter()
+            .any(|cfg| cfg.optional == Some(OptionalMode::Sentinel));
+        let placeholder = if has_sentinel {
+            quote! { Some(&crate::ID) }
+        } else {
+            quote! { None }
+        };
+        quote! {
+            saturn_account_parser::ensure_unique_keys(&accounts[..idx], #placeholder)?;
+        }
+    });
+
+    // Build-time layout checks of the `zero_copy` data types.
+    let layout_asserts = fields.iter().filter_map(utils::layout_asserts);
+
+    // `<Struct>Bumps` collecting the PDA bumps found while parsing.
+    let bumps_ident = bumps::bumps_ident(struct_ident);
+    let bumps_struct = bumps::generate_bumps_struct(struct_ident, fields);
+
+    // Off-chain `metas(..)` builder, only with the `client` feature.
+    let metas_impl = cfg!(feature = "client")
+        .then(|| client::generate_metas(struct_ident, generics, fields, instruction_args));
+
+    // `__idl_accounts()` for the `#[saturn_program]` IDL, only with the
+    // `idl-build` feature.
+    let idl_impl = cfg!(feature = "idl-build")
+        .then(|| idl::generate_idl_accounts(struct_ident, generics, fields));
+
     // Find the `'info` lifetime parameter (required by convention).
     let lifetime_ident_opt = generics
         .lifetimes()
@@ -36,26 +91,67 @@ pub(crate) fn generate(
         ));
     };
 
+    // `ToAccountInfos` / `ToAccountMetas` for passing the struct to a CPI.
+    let cpi_impls = cpi::generate_cpi_impls(struct_ident, generics, &lifetime_ident, fields);
+
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let output = quote! {
+        #bumps_struct
+
+        #metas_impl
+
+        #idl_impl
+
+        #cpi_impls
+
+        #(#layout_asserts)*
+
         impl #impl_generics saturn_account_parser::Accounts<#lifetime_ident> for #struct_ident #ty_generics #where_clause {
             fn try_accounts(
                 accounts: &#lifetime_ident [arch_program::account::AccountInfo<#lifetime_ident>],
             ) -> Result<Self, arch_program::program_error::ProgramError> {
+                <Self as saturn_account_parser::Bumps>::try_accounts_with_args(accounts, &[])
+                    .map(|(accs, _bumps)| accs)
+            }
+
+            fn try_accounts_prefix(
+                accounts: &#lifetime_ident [arch_program::account::AccountInfo<#lifetime_ident>],
+            ) -> Result<(Self, usize), arch_program::program_error::ProgramError> {
+                <Self as saturn_account_parser::Bumps>::try_accounts_prefix_with_args(accounts, &[])
+                    .map(|(accs, _bumps, consumed)| (accs, consumed))
+            }
+
+            #exit_fn
+        }
+
+        impl #impl_generics saturn_account_parser::Bumps<#lifetime_ident> for #struct_ident #ty_generics #where_clause {
+            type Bumps = #bumps_ident;
+
+            // `ix_args` is only read by `#[instruction(..)]` and `nested` fields.
+            #[allow(unused_variables)]
+            fn try_accounts_prefix_with_args(
+                accounts: &#lifetime_ident [arch_program::account::AccountInfo<#lifetime_ident>],
+                ix_args: &[u8],
+            ) -> Result<(Self, Self::Bumps, usize), arch_program::program_error::ProgramError> {
+                #decode_instruction_args
+
                 let mut idx: usize = 0;
+                #[allow(unused_mut)]
+                let mut __bumps = #bumps_ident::default();
 
                 // Field-by-field extraction
                 #(#field_bindings)*
 
-                // Ensure we've consumed exactly all provided accounts
-                if idx != accounts.len() {
-                    return Err(arch_program::program_error::ProgramError::InvalidAccountData);
-                }
+                #duplicates_check
 
-                Ok(Self {
-                    #(#field_initialisers),*
-                })
+                Ok((
+                    Self {
+                        #(#field_initialisers),*
+                    },
+                    __bumps,
+                    idx,
+                ))
             }
         }
     };
@@ -108,15 +204,21 @@ fn generate_field_binding(cfg: &FieldCfg, field_idx: usize, fields: &[FieldCfg])
     let owner_tok_opt = cfg.program_id.as_ref().map(|e| quote! { #e });
 
     match &cfg.kind {
-        FieldKind::Single => single::generate_single_binding(
-            cfg,
-            ident,
-            signer_tok,
-            writable_tok,
-            address_tok,
-            payer_tok_opt,
-            owner_tok_opt,
-        ),
+        FieldKind::Single => {
+            let binding = single::generate_single_binding(
+                cfg,
+                ident,
+                signer_tok,
+                writable_tok,
+                address_tok,
+                payer_tok_opt,
+                owner_tok_opt,
+            );
+            match cfg.optional {
+                Some(mode) => generate_optional_binding(ident, binding, mode),
+                None => binding,
+            }
+        }
         FieldKind::FixedSlice(len_ts) => fixed_slice::generate_fixed_slice_binding(
             cfg,
             ident,
@@ -134,18 +236,44 @@ fn generate_field_binding(cfg: &FieldCfg, field_idx: usize, fields: &[FieldCfg])
             len_ts,
             element_ty_ts,
         ),
+        FieldKind::Nested => {
+            // The nested struct parses its own accounts from the rest of the slice.
+            let base_ty = &cfg.base_ty;
+            quote! {
+                let (#ident, nested_bumps, consumed) = <#base_ty as saturn_account_parser::Bumps>::try_accounts_prefix_with_args(&accounts[idx..], ix_args)?;
+                __bumps.__seeds.record_nested(stringify!(#ident), &nested_bumps.__seeds);
+                __bumps.#ident = nested_bumps;
+                idx += consumed;
+            }
+        }
         FieldKind::Phantom => {
             // For marker fields we simply create a default PhantomData value (does not consume accounts).
             quote! {
                 let #ident = core::marker::PhantomData;
             }
         }
+        FieldKind::Sysvar => {
+            // Read from the runtime (does not consume accounts).
+            let base_ty = &cfg.base_ty;
+            quote! {
+                let #ident = <#base_ty>::get()?;
+            }
+        }
         FieldKind::Bump => {
             let seeds_expr = cfg.seeds.as_ref().expect("seeds required for bump");
-            let program_id_expr = cfg
-                .program_id
-                .as_ref()
-                .expect("program_id required for bump");
+            let program_id_expr = super::utils::pda_program_id(cfg);
+
+            // `bump = <expr>`: a bump provided e.g. by the client must be the
+            // canonical one rather than any byte yielding a valid PDA.
+            let provided_bump_check = cfg.bump.as_ref().map(|bump_expr| {
+                let error = super::utils::constraint_error(cfg, "bump", quote!(InvalidBump));
+                quote! {
+                    let provided_bump: u8 = #bump_expr;
+                    if provided_bump != bump_seed_tmp {
+                        return Err(#error);
+                    }
+                }
+            });
 
             // Detect if the declared type is `[u8; 1]` (value) instead of primitive `u8`.
             let is_array1 = matches!(&cfg.base_ty, syn::Type::Array(arr)
@@ -155,18 +283,54 @@ fn generate_field_binding(cfg: &FieldCfg, field_idx: usize, fields: &[FieldCfg])
             if is_array1 {
                 quote! {
                     let (_pda_key, bump_seed_tmp) = arch_program::pubkey::Pubkey::find_program_address(#seeds_expr, &#program_id_expr);
+                    #provided_bump_check
                     let #ident: [u8; 1] = [bump_seed_tmp];
+                    __bumps.#ident = bump_seed_tmp;
+                    __bumps.__seeds.record(stringify!(#ident), #seeds_expr, bump_seed_tmp);
                 }
             } else {
                 quote! {
                     let (_pda_key, bump_seed_tmp) = arch_program::pubkey::Pubkey::find_program_address(#seeds_expr, &#program_id_expr);
+                    #provided_bump_check
                     let #ident: u8 = bump_seed_tmp;
+                    __bumps.#ident = bump_seed_tmp;
+                    __bumps.__seeds.record(stringify!(#ident), #seeds_expr, bump_seed_tmp);
                 }
             }
         }
     }
 }
 
+/// Wrap the `binding` of an `Option<..>` field so that it runs, and its
+/// constraints are checked, only when the account was provided.
+fn generate_optional_binding(
+    ident: &Ident,
+    binding: TokenStream,
+    mode: OptionalMode,
+) -> TokenStream {
+    let omitted = match mode {
+        // The placeholder slot is consumed.
+        OptionalMode::Sentinel => quote! {
+            if accounts.get(idx).map_or(false, |acc| *acc.key == crate::ID) {
+                idx += 1;
+                true
+            } else {
+                false
+            }
+        },
+        OptionalMode::Trailing => quote! { idx >= accounts.len() },
+    };
+
+    quote! {
+        let #ident = if #omitted {
+            None
+        } else {
+            #binding
+            Some(#ident)
+        };
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use syn::{parse_quote, Data, DeriveInput, Fields};
@@ -194,7 +358,8 @@ mod tests {
             }
         };
         let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
-        let ts = super::generate(&di.ident, &di.generics, &cfgs).expect("generate ok");
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &[], &Default::default())
+            .expect("generate ok");
         let compact = ts.to_string();
         assert!(compact.contains("impl"));
         assert!(compact.contains("try_accounts"));
@@ -212,7 +377,8 @@ mod tests {
         };
 
         let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
-        let ts = super::generate(&di.ident, &di.generics, &cfgs).expect("generate ok");
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &[], &Default::default())
+            .expect("generate ok");
         let rendered = ts.to_string();
         // Ensure bump derivation call is present and the bump variable is initialised.
         assert!(rendered.contains("find_program_address"));
@@ -231,8 +397,149 @@ mod tests {
         };
 
         let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
-        let ts = super::generate(&di.ident, &di.generics, &cfgs).expect("generate ok");
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &[], &Default::default())
+            .expect("generate ok");
         let rendered = ts.to_string();
         assert!(rendered.contains("[bump_seed_tmp]"));
     }
+
+    #[test]
+    fn generates_optional_bindings() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer)]
+                user: arch_program::account::AccountInfo<'info>,
+                referrer: Option<arch_program::account::AccountInfo<'info>>,
+                #[account(optional = trailing)]
+                fee_vault: Option<saturn_account_parser::codec::Account<'info, u64>>,
+            }
+        };
+
+        let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &[], &Default::default())
+            .expect("generate ok");
+        let rendered = ts.to_string();
+        assert!(rendered.contains("* acc . key == crate :: ID"));
+        assert!(rendered.contains("idx >= accounts . len ()"));
+        assert!(rendered.contains("Some (fee_vault)"));
+    }
+
+    #[test]
+    fn generates_sysvar_binding() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer)]
+                user: arch_program::account::AccountInfo<'info>,
+                clock: saturn_account_parser::Sysvar<Clock>,
+            }
+        };
+
+        let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        assert!(matches!(cfgs[1].kind, crate::model::FieldKind::Sysvar));
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &[], &Default::default())
+            .expect("generate ok");
+        let rendered = ts.to_string();
+        assert!(rendered.contains("let clock = < saturn_account_parser :: Sysvar < Clock > >"));
+    }
+
+    #[test]
+    fn generates_nested_binding() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer)]
+                user: arch_program::account::AccountInfo<'info>,
+                #[account(nested)]
+                pool: PoolAccounts<'info>,
+            }
+        };
+
+        let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &[], &Default::default())
+            .expect("generate ok");
+        let rendered = ts.to_string();
+        assert!(rendered.contains("fn try_accounts_prefix"));
+        assert!(rendered.contains("try_accounts_prefix_with_args (& accounts [idx ..] , ix_args)"));
+        assert!(rendered.contains("__bumps . pool = nested_bumps"));
+        assert!(rendered.contains("record_nested (stringify ! (pool) , & nested_bumps . __seeds)"));
+        assert!(rendered.contains("idx += consumed"));
+    }
+
+    #[test]
+    fn decodes_instruction_args() {
+        let di: DeriveInput = parse_quote! {
+            #[instruction(params: OpenPositionParams)]
+            struct Accs<'info> {
+                #[account(seeds = &[b"position", params.position_id.to_le_bytes().as_ref()], program_id = crate::ID)]
+                position: arch_program::account::AccountInfo<'info>,
+            }
+        };
+
+        let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let args = crate::parser::parse_instruction_args(&di.attrs).expect("args ok");
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &args, &Default::default())
+            .expect("generate ok");
+        let rendered = ts.to_string();
+        assert!(rendered.contains("let (params ,) : (OpenPositionParams ,)"));
+        assert!(rendered.contains("deserialize (& mut ix_args_slice)"));
+    }
+
+    #[test]
+    fn generates_canonical_bump_check() {
+        let di: DeriveInput = parse_quote! {
+            #[instruction(vault_bump: u8)]
+            struct Accs<'info> {
+                #[account(seeds = &[b"vault"], program_id = crate::ID, bump = vault_bump)]
+                bump: u8,
+            }
+        };
+
+        let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let args = crate::parser::parse_instruction_args(&di.attrs).expect("args ok");
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &args, &Default::default())
+            .expect("generate ok");
+        let rendered = ts.to_string();
+        assert!(rendered.contains("let provided_bump : u8 = vault_bump ;"));
+        assert!(rendered.contains("if provided_bump != bump_seed_tmp"));
+        assert!(rendered.contains("InvalidBump"));
+    }
+
+    #[test]
+    fn generates_layout_asserts() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(mut, zero_copy, of = MarketState, layout::size = 256)]
+                market: AccountLoader<'info, MarketState>,
+            }
+        };
+
+        let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &[], &Default::default())
+            .expect("generate ok");
+        let rendered = ts.to_string();
+        assert!(rendered.contains("8 % core :: mem :: align_of :: < MarketState > () == 0"));
+        assert!(rendered.contains("core :: mem :: size_of :: < MarketState > () == (256) as usize"));
+    }
+
+    #[test]
+    fn generates_duplicate_check() {
+        let di: DeriveInput = parse_quote! {
+            #[accounts(deny_duplicates)]
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                from: arch_program::account::AccountInfo<'info>,
+                #[account(mut)]
+                to: arch_program::account::AccountInfo<'info>,
+                #[account(len = 2)]
+                shards: Vec<arch_program::account::AccountInfo<'info>>,
+            }
+        };
+
+        let cfgs = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let accounts_cfg = crate::parser::parse_accounts_cfg(&di.attrs).expect("cfg ok");
+        assert!(accounts_cfg.deny_duplicates);
+        let ts = super::generate(&di.ident, &di.generics, &cfgs, &[], &accounts_cfg)
+            .expect("generate ok");
+        let rendered = ts.to_string();
+        assert!(rendered.contains("ensure_unique_keys (& accounts [.. idx] , None)"));
+    }
 }
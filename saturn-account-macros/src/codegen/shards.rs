@@ -2,6 +2,10 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
 
+use super::utils::{
+    extract_inner_data_type, fetch_error_overrides, init_lamports, is_account_info_path,
+    max_len_check, resize_account,
+};
 use crate::model::FieldCfg;
 
 // Public so orchestrator can call.
@@ -28,65 +32,63 @@ pub(crate) fn generate_shards_binding(
             .expect("`space` is required for realloc")
             .clone();
 
-        if cfg.seeds.is_some() {
+        let payer_ts = cfg.payer.as_ref().map(|payer_expr| quote! { #payer_expr });
+
+        let allocate_cpi = if cfg.seeds.is_some() {
             // PDA path – relies on `#seeds_ident`, `#owner_ident`, and loop variable `i`.
             quote! {
-                let new_len: usize = (#space_expr) as usize;
-                if new_len > acc_info_tmp.data_len() {
-                    // Build signer seeds = base_seeds + idx_le + bump
-                    let idx_le: [u8; 2] = (i as u16).to_le_bytes();
-
-                    let mut seed_vec: Vec<&[u8]> = {
-                        let mut v = Vec::with_capacity(#seeds_ident.len() + 2);
-                        v.extend_from_slice(#seeds_ident);
-                        v.push(&idx_le);
-                        v
-                    };
-
-                    let (_expected, bump_seed) = arch_program::pubkey::Pubkey::find_program_address(&seed_vec, #owner_ident);
-                    let bump_slice: &[u8] = &[bump_seed];
-                    seed_vec.push(bump_slice);
-
-                    let signer_seeds: &[&[&[u8]]] = &[&seed_vec];
-
-                    arch_program::program::invoke_signed(
-                        &arch_program::system_instruction::allocate(acc_info_tmp.key, new_len as u64),
-                        &[acc_info_tmp.clone()],
-                        signer_seeds,
-                    )?;
-                }
+                // Build signer seeds = base_seeds + idx_le + bump
+                let idx_le: [u8; 2] = (i as u16).to_le_bytes();
 
-                if acc_info_tmp.data_len() != new_len {
-                    acc_info_tmp.realloc(new_len, true)?;
-                }
+                let mut seed_vec: Vec<&[u8]> = {
+                    let mut v = Vec::with_capacity(#seeds_ident.len() + 2);
+                    v.extend_from_slice(#seeds_ident);
+                    v.push(&idx_le);
+                    v
+                };
+
+                let (_expected, bump_seed) = arch_program::pubkey::Pubkey::find_program_address(&seed_vec, #owner_ident);
+                let bump_slice: &[u8] = &[bump_seed];
+                seed_vec.push(bump_slice);
+
+                let signer_seeds: &[&[&[u8]]] = &[&seed_vec];
+
+                arch_program::program::invoke_signed(
+                    &arch_program::system_instruction::allocate(acc_info_tmp.key, new_len as u64),
+                    &[acc_info_tmp.clone()],
+                    signer_seeds,
+                )?;
             }
         } else {
             // Non-PDA path – account signs directly.
             quote! {
-                let new_len: usize = (#space_expr) as usize;
-                if new_len > acc_info_tmp.data_len() {
-                    arch_program::program::invoke(
-                        &arch_program::system_instruction::allocate(acc_info_tmp.key, new_len as u64),
-                        &[acc_info_tmp.clone()],
-                    )?;
-                }
-
-                if acc_info_tmp.data_len() != new_len {
-                    acc_info_tmp.realloc(new_len, true)?;
-                }
+                arch_program::program::invoke(
+                    &arch_program::system_instruction::allocate(acc_info_tmp.key, new_len as u64),
+                    &[acc_info_tmp.clone()],
+                )?;
             }
-        }
+        };
+
+        resize_account(cfg, quote! { #space_expr }, allocate_cpi, payer_ts.as_ref())
     } else {
         TokenStream::new()
     };
 
-    let is_acc_info_elem = crate::codegen::utils::is_account_info_path(&cfg.base_ty);
+    // `base_ty` is the whole `Vec<..>`: look at the element instead, and at the
+    // zero-copy type an `AccountLoader<'info, T>` element wraps.
+    let element_ty: syn::Type =
+        syn::parse2(element_ty_ts.clone()).expect("shard element must be a type");
+    let is_acc_info_elem = is_account_info_path(&element_ty);
+    let shard_ty = extract_inner_data_type(&element_ty).unwrap_or(element_ty);
+    let max_len_check = max_len_check(cfg);
+    let fetch_errors = fetch_error_overrides(cfg);
 
     // -------------------------------------------------------------------------------------
-    // Detect whether we need initialisation logic for the shard accounts.  We only support
-    // initialising **zero-copy** (AccountLoader) shard vectors; AccountInfo shard vectors
-    // remain load-only.  The parser already guarantees that `payer` and `program_id` are
-    // present whenever `init` / `init_if_needed` are set.
+    // Detect whether we need initialisation logic for the shard accounts.  Every element
+    // not yet owned by `program_id` is created; zero-copy (AccountLoader) shards then get
+    // their discriminator written.  The parser already guarantees that `payer` and
+    // `program_id` (plus `space` for AccountInfo shards) are present whenever `init` /
+    // `init_if_needed` are set.
     // -------------------------------------------------------------------------------------
     let needs_init: bool = cfg.is_init || cfg.is_init_if_needed;
 
@@ -114,13 +116,15 @@ pub(crate) fn generate_shards_binding(
     let space_ts: proc_macro2::TokenStream = if let Some(space_expr) = &cfg.space {
         quote! { (#space_expr as u64) }
     } else if is_acc_info_elem {
-        // For raw AccountInfo shards default to zero data – user must specify `space` if needed.
+        // Unused: the parser requires `space` to create `AccountInfo` shards.
         quote! { 0u64 }
     } else {
-        // Zero-copy shards default to the size of their inner type.
-        quote! { core::mem::size_of::<#element_ty_ts>() as u64 }
+        // Zero-copy shards default to their inner type plus the 8-byte discriminator.
+        quote! { (core::mem::size_of::<#shard_ty>() + 8) as u64 }
     };
 
+    let lamports_ts = init_lamports(cfg);
+
     // Owner token stream for account initialization (non-PDA path). Only needed when `needs_init` is true.
     let owner_ts: proc_macro2::TokenStream = if needs_init {
         let owner_expr = cfg
@@ -149,11 +153,12 @@ pub(crate) fn generate_shards_binding(
     let init_account_snip: proc_macro2::TokenStream = if needs_init {
         quote! {
             let owner_expected = { #owner_ts }; // program_id verified by parser
-            let already_initialised = acc_info_tmp.owner == &owner_expected;
+            // Captured before `create_account` hands the shard to `owner_expected`.
+            let shard_was_initialised = acc_info_tmp.owner == &owner_expected;
 
-            if !already_initialised {
+            if !shard_was_initialised {
                 let space: u64 = #space_ts;
-                let lamports: u64 = arch_program::account::MIN_ACCOUNT_LAMPORTS;
+                let lamports: u64 = #lamports_ts;
 
                 let create_ix = arch_program::system_instruction::create_account(
                     saturn_account_parser::ToAccountInfo::to_account_info(&#payer_ts).key,
@@ -177,9 +182,7 @@ pub(crate) fn generate_shards_binding(
 
     let loader_init_snip: proc_macro2::TokenStream = if needs_init {
         quote! {
-            let owner_expected = { #owner_ts };
-            let was_initialised = acc_info_tmp.owner == &owner_expected;
-            if !was_initialised {
+            if !shard_was_initialised {
                 loader.load_init()?;
             }
         }
@@ -194,10 +197,11 @@ pub(crate) fn generate_shards_binding(
     let pda_init_account_snip: proc_macro2::TokenStream = if needs_init {
         quote! {
             // -------------------------------- Initialization --------------------------------
-            let already_initialised = acc_info_tmp.owner == #owner_ident;
-            if !already_initialised {
+            // Captured before `create_account` hands the shard to the program.
+            let shard_was_initialised = acc_info_tmp.owner == #owner_ident;
+            if !shard_was_initialised {
                 let space: u64 = #space_ts;
-                let lamports: u64 = arch_program::account::MIN_ACCOUNT_LAMPORTS;
+                let lamports: u64 = #lamports_ts;
 
                 // signer seeds = base + idx_le + bump
                 let idx_le: [u8; 2] = (i as u16).to_le_bytes();
@@ -235,8 +239,7 @@ pub(crate) fn generate_shards_binding(
 
     let pda_loader_init_snip: proc_macro2::TokenStream = if needs_init {
         quote! {
-            let already_initialised = acc_info_tmp.owner == #owner_ident;
-            if !already_initialised {
+            if !shard_was_initialised {
                 loader.load_init()?;
             }
         }
@@ -258,6 +261,7 @@ pub(crate) fn generate_shards_binding(
                 if len_val > 0xFFFF {
                     return Err(arch_program::program_error::ProgramError::InvalidAccountData);
                 }
+                #max_len_check
 
                 let slice_start = idx;
                 let slice_end = idx + len_val;
@@ -279,7 +283,9 @@ pub(crate) fn generate_shards_binding(
                         #seeds_ident,
                         i as u16,
                         #owner_ident,
-                    )?;
+                    )#fetch_errors?;
+
+                    #pda_init_account_snip
 
                     #realloc_snip
                     vec_tmp.push(acc_info_tmp.clone());
@@ -294,6 +300,7 @@ pub(crate) fn generate_shards_binding(
                 if len_val > 0xFFFF {
                     return Err(arch_program::program_error::ProgramError::InvalidAccountData);
                 }
+                #max_len_check
 
                 let slice_start = idx;
                 let slice_end = idx + len_val;
@@ -304,7 +311,7 @@ pub(crate) fn generate_shards_binding(
                 let #seeds_ident: &[&[u8]] = #seeds_expr;
                 let #owner_ident = &#program_id_expr;
 
-                let mut vec_tmp: Vec<saturn_account_parser::codec::AccountLoader<'_, #element_ty_ts>> = Vec::with_capacity(len_val);
+                let mut vec_tmp: Vec<saturn_account_parser::codec::AccountLoader<'_, #shard_ty>> = Vec::with_capacity(len_val);
                 for i in 0..len_val {
                     let acc_info_tmp = saturn_account_parser::get_indexed_pda_account(
                         accounts,
@@ -314,13 +321,13 @@ pub(crate) fn generate_shards_binding(
                         #seeds_ident,
                         i as u16,
                         #owner_ident,
-                    )?;
+                    )#fetch_errors?;
 
                     #pda_init_account_snip
 
                     #realloc_snip
 
-                    let loader = saturn_account_parser::codec::AccountLoader::<#element_ty_ts>::new(acc_info_tmp);
+                    let loader = saturn_account_parser::codec::AccountLoader::<#shard_ty>::new(acc_info_tmp);
                     #pda_loader_init_snip
                     vec_tmp.push(loader);
                 }
@@ -336,6 +343,7 @@ pub(crate) fn generate_shards_binding(
                 if len_val > 0xFFFF {
                     return Err(arch_program::program_error::ProgramError::InvalidAccountData);
                 }
+                #max_len_check
 
                 let slice_start = idx;
                 let slice_end = idx + len_val;
@@ -351,7 +359,10 @@ pub(crate) fn generate_shards_binding(
                         #signer_tok,
                         #writable_tok,
                         #address_tok,
-                    )?;
+                    )#fetch_errors?;
+
+                    #init_account_snip
+
                     vec_tmp.push(acc_info_tmp.clone());
                 }
 
@@ -364,6 +375,7 @@ pub(crate) fn generate_shards_binding(
                 if len_val > 0xFFFF {
                     return Err(arch_program::program_error::ProgramError::InvalidAccountData);
                 }
+                #max_len_check
 
                 let slice_start = idx;
                 let slice_end = idx + len_val;
@@ -371,7 +383,7 @@ pub(crate) fn generate_shards_binding(
                     return Err(arch_program::program_error::ProgramError::NotEnoughAccountKeys);
                 }
 
-                let mut vec_tmp: Vec<saturn_account_parser::codec::AccountLoader<'_, #element_ty_ts>> = Vec::with_capacity(len_val);
+                let mut vec_tmp: Vec<saturn_account_parser::codec::AccountLoader<'_, #shard_ty>> = Vec::with_capacity(len_val);
                 for i in 0..len_val {
                     let acc_info_tmp = saturn_account_parser::get_account(
                         accounts,
@@ -379,14 +391,14 @@ pub(crate) fn generate_shards_binding(
                         #signer_tok,
                         #writable_tok,
                         #address_tok,
-                    )?;
+                    )#fetch_errors?;
 
                     // ----------------------------- optional initialisation -----------------------------
                     #init_account_snip
 
                     #realloc_snip
 
-                    let loader = saturn_account_parser::codec::AccountLoader::<#element_ty_ts>::new(acc_info_tmp);
+                    let loader = saturn_account_parser::codec::AccountLoader::<#shard_ty>::new(acc_info_tmp);
 
                     // Optional zero-copy struct initialisation
                     #loader_init_snip
@@ -446,6 +458,101 @@ mod tests {
         assert!(rendered.contains("len_val"));
     }
 
+    #[test]
+    fn generates_shards_init_if_needed() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                caller: arch_program::account::AccountInfo<'info>,
+                #[account(mut, shards, init_if_needed, payer = caller, len = 4, seeds = &[b"shard"], program_id = crate::ID)]
+                shards: Vec<saturn_account_parser::codec::AccountLoader<'info, Shard>>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[1];
+        let (len_ts, elem_ts) = match &cfg.kind {
+            crate::model::FieldKind::Shards(l, e) => (l, e),
+            _ => panic!("expected shards kind"),
+        };
+        let ts = generate_shards_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(Some(true)),
+            quote!(None),
+            len_ts,
+            elem_ts,
+        );
+        let rendered = ts.to_string();
+        // Loaders wrap the shard type itself, sized with its discriminator.
+        assert!(rendered.contains("AccountLoader :: < Shard > :: new (acc_info_tmp)"));
+        assert!(rendered.contains("(core :: mem :: size_of :: < Shard > () + 8) as u64"));
+        // Creation state is read before `create_account` changes the owner.
+        let state = rendered.find("let shard_was_initialised").expect("state");
+        assert!(state < rendered.find("create_account").expect("creation"));
+        assert!(rendered.contains("if ! shard_was_initialised { loader . load_init () ? ; }"));
+    }
+
+    #[test]
+    fn generates_account_info_shards_init_if_needed() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                caller: arch_program::account::AccountInfo<'info>,
+                #[account(mut, shards, init_if_needed, payer = caller, len = 2, space = 64, seeds = &[b"shard"], program_id = crate::ID)]
+                shards: Vec<arch_program::account::AccountInfo<'info>>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[1];
+        let (len_ts, elem_ts) = match &cfg.kind {
+            crate::model::FieldKind::Shards(l, e) => (l, e),
+            _ => panic!("expected shards kind"),
+        };
+        let ts = generate_shards_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(Some(true)),
+            quote!(None),
+            len_ts,
+            elem_ts,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("create_account"));
+        assert!(rendered.contains("vec_tmp . push (acc_info_tmp . clone ())"));
+        assert!(!rendered.contains("AccountLoader"));
+    }
+
+    #[test]
+    fn generates_runtime_len_bound() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(shards, len = config.load()?.shard_count, max_len = MAX_SHARDS)]
+                shards: Vec<arch_program::account::AccountInfo<'info>>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let (len_ts, elem_ts) = match &cfg.kind {
+            crate::model::FieldKind::Shards(l, e) => (l, e),
+            _ => panic!("expected shards kind"),
+        };
+        let ts = generate_shards_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            len_ts,
+            elem_ts,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("(config . load () ? . shard_count as usize)"));
+        assert!(rendered.contains("len_val > (MAX_SHARDS as usize)"));
+        assert!(rendered.contains("AccountLenExceedsMax"));
+    }
+
     #[test]
     fn generates_shards_binding_pda_account_info() {
         let di: DeriveInput = parse_quote! {
@@ -1,4 +1,7 @@
-use super::utils::is_account_info_path;
+use super::utils::{
+    account_checks, constraint_error, create_account_ix, discriminator_len, fetch_error_overrides,
+    init_lamports, is_account_info_path, is_path_named, pda_program_id, resize_account,
+};
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
@@ -16,21 +19,48 @@ pub(crate) fn generate_single_binding(
     payer_tok_opt: Option<TokenStream>,
     owner_tok_opt: Option<TokenStream>,
 ) -> TokenStream {
+    let fetch_errors = fetch_error_overrides(cfg);
     let fetch_account = if cfg.seeds.is_some() {
         let seeds_expr = cfg.seeds.as_ref().unwrap();
-        let program_id_expr = cfg
-            .program_id
-            .as_ref()
-            .expect("program_id required when seeds provided");
+        let program_id_expr = pda_program_id(cfg);
+        // Record the bump and the seeds in the generated `<Struct>Bumps`.
+        let bump_value = if cfg.optional.is_some() {
+            quote! { Some(bump) }
+        } else {
+            quote! { bump }
+        };
+        // A known `bump = <expr>` is checked directly instead of searching for
+        // the canonical bump.
+        let find_pda = match &cfg.bump {
+            Some(bump_expr) => quote! {
+                let bump: u8 = #bump_expr;
+                let acc = saturn_account_parser::get_pda_account_with_known_bump(
+                    accounts,
+                    idx,
+                    #signer_tok,
+                    #writable_tok,
+                    pda_seeds,
+                    bump,
+                    &#program_id_expr,
+                )#fetch_errors?;
+            },
+            None => quote! {
+                let (acc, bump) = saturn_account_parser::get_pda_account_with_bump(
+                    accounts,
+                    idx,
+                    #signer_tok,
+                    #writable_tok,
+                    pda_seeds,
+                    &#program_id_expr,
+                )#fetch_errors?;
+            },
+        };
         quote! {
-            saturn_account_parser::get_pda_account(
-                accounts,
-                idx,
-                #signer_tok,
-                #writable_tok,
-                #seeds_expr,
-                &#program_id_expr,
-            )?
+            let pda_seeds: &[&[u8]] = #seeds_expr;
+            #find_pda
+            __bumps.#ident = #bump_value;
+            __bumps.__seeds.record(stringify!(#ident), pda_seeds, bump);
+            acc
         }
     } else {
         quote! {
@@ -40,7 +70,7 @@ pub(crate) fn generate_single_binding(
                 #signer_tok,
                 #writable_tok,
                 #address_tok,
-            )?
+            )#fetch_errors?
         }
     };
 
@@ -53,8 +83,9 @@ pub(crate) fn generate_single_binding(
     let space_ts = if let Some(space_expr) = &cfg.space {
         quote! { (#space_expr as u64) }
     } else if cfg.is_zero_copy {
-        // Reserve extra 8 bytes for the discriminator when zero-copy
-        quote! { (core::mem::size_of::<#inner_ty_ts>() + 8) as u64 }
+        // Reserve room for the discriminator when zero-copy
+        let discriminator_len = discriminator_len(cfg);
+        quote! { (core::mem::size_of::<#inner_ty_ts>() + #discriminator_len) as u64 }
     } else {
         quote! { core::mem::size_of::<#inner_ty_ts>() as u64 }
     };
@@ -62,18 +93,16 @@ pub(crate) fn generate_single_binding(
     // Treat both `init` and `init_if_needed` as requiring initialisation logic.
     let is_any_init = cfg.is_init || cfg.is_init_if_needed;
 
-    // Convenience snippet that validates explicit `owner = ...`, if provided.
-    let owner_check_snip: proc_macro2::TokenStream = if let Some(owner_expr) = &cfg.owner {
-        quote! {
-            if acc_info_tmp.owner != &#owner_expr {
-                return Err(arch_program::program_error::ProgramError::IncorrectProgramId);
-            }
-        }
-    } else {
-        proc_macro2::TokenStream::new()
+    // `owner = ..` on an init field names the program the account is created for.
+    let owner_tok_opt = match &cfg.owner {
+        Some(owner_expr) if is_any_init => Some(quote! { #owner_expr }),
+        _ => owner_tok_opt,
     };
 
-    if cfg.is_realloc {
+    // Convenience snippet that validates explicit `owner = ...` / `executable`, if provided.
+    let account_checks_snip = account_checks(cfg, quote! { acc_info_tmp });
+
+    let binding = if cfg.is_realloc {
         generate_single_realloc(
             cfg,
             ident,
@@ -81,7 +110,7 @@ pub(crate) fn generate_single_binding(
             inner_ty_ts,
             space_ts,
             payer_tok_opt,
-            owner_check_snip.clone(),
+            account_checks_snip.clone(),
         )
     } else if cfg.is_zero_copy {
         generate_single_zero_copy(
@@ -92,7 +121,7 @@ pub(crate) fn generate_single_binding(
             space_ts,
             payer_tok_opt,
             owner_tok_opt,
-            owner_check_snip.clone(),
+            account_checks_snip.clone(),
         )
     } else if is_any_init {
         generate_single_borsh_init(
@@ -103,20 +132,90 @@ pub(crate) fn generate_single_binding(
             space_ts,
             payer_tok_opt,
             owner_tok_opt,
-            owner_check_snip.clone(),
+            account_checks_snip.clone(),
         )
     } else {
-        generate_single_default(
-            cfg,
-            ident,
-            fetch_account,
-            inner_ty_ts,
-            signer_tok,
-            writable_tok,
-            address_tok,
-            owner_check_snip,
-        )
+        generate_single_default(cfg, ident, fetch_account, inner_ty_ts, account_checks_snip)
+    };
+
+    // `rent_exempt` is checked last so it sees the balance and length left by
+    // any creation or reallocation above.
+    let rent_exempt_snip = if cfg.is_rent_exempt {
+        let error = constraint_error(cfg, "rent_exempt", quote!(NotRentExempt));
+        quote! {
+            saturn_account_parser::check_rent_exempt(
+                &saturn_account_parser::ToAccountInfo::to_account_info(&#ident),
+            )
+            .map_err(|_| #error)?;
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    let token_checks_snip = token_checks(cfg, ident);
+
+    quote! {
+        #binding
+        #rent_exempt_snip
+        #token_checks_snip
+    }
+}
+
+/// Checks of the `token::` / `mint::` constraints on the decoded `ident`.
+fn token_checks(cfg: &FieldCfg, ident: &Ident) -> TokenStream {
+    let fail = |constraint: &str, code: TokenStream| {
+        let error = constraint_error(cfg, constraint, code);
+        quote! {
+            return Err(#error);
+        }
+    };
+    let key_of = |expr: &syn::Expr| {
+        quote! { *saturn_account_parser::ToAccountInfo::to_account_info(&#expr).key }
+    };
+
+    let mut checks = Vec::new();
+    if let Some(mint_expr) = &cfg.token_mint {
+        let (key, fail) = (
+            key_of(mint_expr),
+            fail("token::mint", quote!(InvalidTokenMint)),
+        );
+        checks.push(quote! {
+            if #ident.mint != #key {
+                #fail
+            }
+        });
+    }
+    if let Some(authority_expr) = &cfg.token_authority {
+        let (key, fail) = (
+            key_of(authority_expr),
+            fail("token::authority", quote!(InvalidTokenAuthority)),
+        );
+        checks.push(quote! {
+            if #ident.owner != #key {
+                #fail
+            }
+        });
+    }
+    if let Some(authority_expr) = &cfg.mint_authority {
+        let (key, fail) = (
+            key_of(authority_expr),
+            fail("mint::authority", quote!(InvalidMintAuthority)),
+        );
+        checks.push(quote! {
+            if #ident.mint_authority != Some(#key) {
+                #fail
+            }
+        });
+    }
+    if let Some(decimals_expr) = &cfg.mint_decimals {
+        let fail = fail("mint::decimals", quote!(InvalidMintDecimals));
+        checks.push(quote! {
+            if #ident.decimals != (#decimals_expr) as u8 {
+                #fail
+            }
+        });
     }
+    quote! { #(#checks)* }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -128,16 +227,23 @@ fn generate_single_zero_copy(
     space_ts: TokenStream,
     payer_tok_opt: Option<TokenStream>,
     owner_tok_opt: Option<TokenStream>,
-    owner_check_snip: TokenStream,
+    account_checks_snip: TokenStream,
 ) -> TokenStream {
     let loader_ty_ts: TokenStream =
         quote! { saturn_account_parser::codec::AccountLoader::<#inner_ty_ts> };
 
-    let loader_expr = quote! { #loader_ty_ts ::new(acc_info_tmp) };
+    let loader_expr = match &cfg.discriminator {
+        Some(discriminator_expr) => {
+            quote! { #loader_ty_ts ::with_discriminator(acc_info_tmp, #discriminator_expr) }
+        }
+        None => quote! { #loader_ty_ts ::new(acc_info_tmp) },
+    };
 
     if cfg.is_init || cfg.is_init_if_needed {
         let payer_expr = payer_tok_opt.as_ref().expect("payer required");
         let owner_expr = owner_tok_opt.as_ref().expect("program_id required");
+        let lamports_ts = init_lamports(cfg);
+        let create_ix = create_account_ix(cfg, payer_expr, owner_expr);
 
         let already_init_guard = if cfg.is_init {
             quote! {
@@ -153,21 +259,15 @@ fn generate_single_zero_copy(
             let program_id_expr = cfg.program_id.as_ref().unwrap();
             quote! {
                 let acc_info_tmp = { #fetch_account };
-                #owner_check_snip
+                #account_checks_snip
                 idx += 1;
 
                 let already_initialised = *acc_info_tmp.owner == #owner_expr;
 
                 if !already_initialised {
                     let space: u64 = #space_ts;
-                    let lamports: u64 = arch_program::account::MIN_ACCOUNT_LAMPORTS;
-                    let create_ix = arch_program::system_instruction::create_account(
-                        saturn_account_parser::ToAccountInfo::to_account_info(&#payer_expr).key,
-                        acc_info_tmp.key,
-                        lamports,
-                        space,
-                        &#owner_expr,
-                    );
+                    let lamports: u64 = #lamports_ts;
+                    let create_ix = #create_ix;
 
                     // Build signer seeds (base + bump)
                     let base_seeds: &[&[u8]] = #seeds_expr;
@@ -203,21 +303,15 @@ fn generate_single_zero_copy(
             // ---------------- Non-PDA + zero-copy + init ----------------
             quote! {
                 let acc_info_tmp = { #fetch_account };
-                #owner_check_snip
+                #account_checks_snip
                 idx += 1;
 
                 let already_initialised = *acc_info_tmp.owner == #owner_expr;
 
                 if !already_initialised {
                     let space: u64 = #space_ts;
-                    let lamports: u64 = arch_program::account::MIN_ACCOUNT_LAMPORTS;
-                    let create_ix = arch_program::system_instruction::create_account(
-                        saturn_account_parser::ToAccountInfo::to_account_info(&#payer_expr).key,
-                        acc_info_tmp.key,
-                        lamports,
-                        space,
-                        &#owner_expr,
-                    );
+                    let lamports: u64 = #lamports_ts;
+                    let create_ix = #create_ix;
 
                     arch_program::program::invoke(
                         &create_ix,
@@ -239,11 +333,23 @@ fn generate_single_zero_copy(
         }
     } else {
         // ---------------- zero-copy (no init) ----------------
+        // `migrate_from`: accounts in an older layout are upgraded right away.
+        let migrate_snip = cfg.migrate_from.as_ref().map(|min_version_expr| {
+            quote! {
+                if #ident.version()? < (#min_version_expr) as u8 {
+                    return Err(arch_program::program_error::ProgramError::Custom(
+                        saturn_account_parser::error::ErrorCode::UnsupportedAccountVersion.into(),
+                    ));
+                }
+                drop(#ident.load_or_migrate()?);
+            }
+        });
         quote! {
             let acc_info_tmp = { #fetch_account };
-            #owner_check_snip
+            #account_checks_snip
             idx += 1;
             let #ident = #loader_expr;
+            #migrate_snip
         }
     }
 }
@@ -257,10 +363,12 @@ fn generate_single_borsh_init(
     space_ts: TokenStream,
     payer_tok_opt: Option<TokenStream>,
     owner_tok_opt: Option<TokenStream>,
-    owner_check_snip: TokenStream,
+    account_checks_snip: TokenStream,
 ) -> TokenStream {
     let payer_expr = payer_tok_opt.as_ref().expect("payer required");
     let owner_expr = owner_tok_opt.as_ref().expect("program_id required");
+    let lamports_ts = init_lamports(cfg);
+    let create_ix = create_account_ix(cfg, payer_expr, owner_expr);
 
     let already_init_guard = if cfg.is_init {
         quote! {
@@ -270,27 +378,37 @@ fn generate_single_borsh_init(
         quote! {}
     };
 
+    // Raw `AccountInfo` fields are only created; their data is left to the
+    // owner program (e.g. one named with `owner = ..`).
+    let bind_created = if is_account_info_path(&cfg.base_ty) {
+        quote! {
+            let #ident: #inner_ty_ts = (*acc_info_tmp).clone();
+        }
+    } else {
+        quote! {
+            let #ident = if already_initialised {
+                saturn_account_parser::codec::Account::<#inner_ty_ts>::load(acc_info_tmp)?
+            } else {
+                saturn_account_parser::codec::Account::<#inner_ty_ts>::init(acc_info_tmp)?
+            };
+        }
+    };
+
     if cfg.seeds.is_some() {
         // ---------------- PDA + Borsh + init ----------------
         let seeds_expr = cfg.seeds.as_ref().unwrap();
         let program_id_expr = cfg.program_id.as_ref().unwrap();
         quote! {
             let acc_info_tmp = { #fetch_account };
-            #owner_check_snip
+            #account_checks_snip
             idx += 1;
 
             let already_initialised = *acc_info_tmp.owner == #owner_expr;
 
             if !already_initialised {
                 let space: u64 = #space_ts;
-                let lamports: u64 = arch_program::account::MIN_ACCOUNT_LAMPORTS;
-                let create_ix = arch_program::system_instruction::create_account(
-                    saturn_account_parser::ToAccountInfo::to_account_info(&#payer_expr).key,
-                    acc_info_tmp.key,
-                    lamports,
-                    space,
-                    &#owner_expr,
-                );
+                let lamports: u64 = #lamports_ts;
+                let create_ix = #create_ix;
 
                 let base_seeds: &[&[u8]] = #seeds_expr;
                 let (_expected, bump_seed) = arch_program::pubkey::Pubkey::find_program_address(base_seeds, &#program_id_expr);
@@ -313,31 +431,21 @@ fn generate_single_borsh_init(
             if already_initialised {
                 #already_init_guard
             }
-            let #ident = if already_initialised {
-                saturn_account_parser::codec::Account::<#inner_ty_ts>::load(acc_info_tmp)?
-            } else {
-                saturn_account_parser::codec::Account::<#inner_ty_ts>::init(acc_info_tmp)?
-            };
+            #bind_created
         }
     } else {
         // ---------------- Non-PDA + Borsh + init ----------------
         quote! {
             let acc_info_tmp = { #fetch_account };
-            #owner_check_snip
+            #account_checks_snip
             idx += 1;
 
             let already_initialised = *acc_info_tmp.owner == #owner_expr;
 
             if !already_initialised {
                 let space: u64 = #space_ts;
-                let lamports: u64 = arch_program::account::MIN_ACCOUNT_LAMPORTS;
-                let create_ix = arch_program::system_instruction::create_account(
-                    saturn_account_parser::ToAccountInfo::to_account_info(&#payer_expr).key,
-                    acc_info_tmp.key,
-                    lamports,
-                    space,
-                    &#owner_expr,
-                );
+                let lamports: u64 = #lamports_ts;
+                let create_ix = #create_ix;
 
                 arch_program::program::invoke(
                     &create_ix,
@@ -348,11 +456,7 @@ fn generate_single_borsh_init(
             if already_initialised {
                 #already_init_guard
             }
-            let #ident = if already_initialised {
-                saturn_account_parser::codec::Account::<#inner_ty_ts>::load(acc_info_tmp)?
-            } else {
-                saturn_account_parser::codec::Account::<#inner_ty_ts>::init(acc_info_tmp)?
-            };
+            #bind_created
         }
     }
 }
@@ -365,7 +469,7 @@ fn generate_single_realloc(
     inner_ty_ts: TokenStream,
     space_ts: TokenStream,
     payer_tok_opt: Option<TokenStream>,
-    owner_check_snip: TokenStream,
+    account_checks_snip: TokenStream,
 ) -> TokenStream {
     // Common snippet: CPI to system_program::allocate before local pointer change.
     let allocate_cpi_ts = if cfg.seeds.is_some() {
@@ -405,22 +509,38 @@ fn generate_single_realloc(
         }
     };
 
-    // Generate final tokens depending on zero-copy vs Borsh.
-    if cfg.is_zero_copy {
-        quote! {
+    // With a payer, zero-copy accounts are resized by `AccountLoader::realloc`,
+    // which also keeps their discriminator, rejects a length too small for
+    // the type and leaves them rent exempt.
+    if let (true, Some(payer_expr)) = (cfg.is_zero_copy, &payer_tok_opt) {
+        return quote! {
             let acc_info_tmp = { #_fetch_account };
-            #owner_check_snip
+            #account_checks_snip
             idx += 1;
 
-            let new_len: usize = #space_ts as usize;
+            let new_len: usize = (#space_ts) as usize;
             if new_len > acc_info_tmp.data_len() {
                 #allocate_cpi_ts
             }
 
-            // Update local slice regardless of grow/shrink.
-            if acc_info_tmp.data_len() != new_len {
-                acc_info_tmp.realloc(new_len, true)?;
-            }
+            let #ident = saturn_account_parser::codec::AccountLoader::<#inner_ty_ts>::new(acc_info_tmp);
+            #ident.realloc(
+                new_len,
+                &saturn_account_parser::ToAccountInfo::to_account_info(&#payer_expr),
+            )?;
+        };
+    }
+
+    let resize_snip = resize_account(cfg, space_ts, allocate_cpi_ts, payer_tok_opt.as_ref());
+
+    // Generate final tokens depending on zero-copy vs Borsh.
+    if cfg.is_zero_copy {
+        quote! {
+            let acc_info_tmp = { #_fetch_account };
+            #account_checks_snip
+            idx += 1;
+
+            #resize_snip
 
             let #ident = {
                 let loader = saturn_account_parser::codec::AccountLoader::<#inner_ty_ts>::new(acc_info_tmp);
@@ -430,107 +550,62 @@ fn generate_single_realloc(
     } else {
         quote! {
             let acc_info_tmp = { #_fetch_account };
-            #owner_check_snip
+            #account_checks_snip
             idx += 1;
 
-            let new_len: usize = #space_ts as usize;
-            if new_len > acc_info_tmp.data_len() {
-                #allocate_cpi_ts
-            }
-
-            if acc_info_tmp.data_len() != new_len {
-                acc_info_tmp.realloc(new_len, true)?;
-            }
+            #resize_snip
 
             let #ident = saturn_account_parser::codec::Account::<#inner_ty_ts>::load(acc_info_tmp)?;
         }
     }
 }
 
-#[allow(clippy::too_many_arguments)]
 fn generate_single_default(
     cfg: &FieldCfg,
     ident: &Ident,
-    _fetch_account: TokenStream,
+    fetch_account: TokenStream,
     inner_ty_ts: TokenStream,
-    signer_tok: TokenStream,
-    writable_tok: TokenStream,
-    address_tok: TokenStream,
-    owner_check_snip: TokenStream,
+    account_checks_snip: TokenStream,
 ) -> TokenStream {
     // Detect if type is AccountInfo path.
     let is_acc_info_ty = is_account_info_path(&cfg.base_ty);
 
     if is_acc_info_ty {
-        if cfg.seeds.is_some() {
-            let seeds_expr = cfg.seeds.as_ref().unwrap();
-            let program_id_expr = cfg
-                .program_id
-                .as_ref()
-                .expect("program_id required when seeds provided");
-            quote! {
-                let acc_info_tmp = saturn_account_parser::get_pda_account(
-                    accounts,
-                    idx,
-                    #signer_tok,
-                    #writable_tok,
-                    #seeds_expr,
-                    &#program_id_expr,
-                )?;
-                #owner_check_snip
-                idx += 1;
-                // Return the account **by value** (clone) so the user can declare `AccountInfo<'info>` directly.
-                let #ident: #inner_ty_ts = (*acc_info_tmp).clone();
-            }
-        } else {
-            quote! {
-                let acc_info_tmp = saturn_account_parser::get_account(
-                    accounts,
-                    idx,
-                    #signer_tok,
-                    #writable_tok,
-                    #address_tok,
-                )?;
-                #owner_check_snip
-                idx += 1;
-                let #ident: #inner_ty_ts = (*acc_info_tmp).clone();
-            }
+        quote! {
+            let acc_info_tmp = { #fetch_account };
+            #account_checks_snip
+            idx += 1;
+            // Return the account **by value** (clone) so the user can declare `AccountInfo<'info>` directly.
+            let #ident: #inner_ty_ts = (*acc_info_tmp).clone();
         }
     } else {
-        // Borsh default: fetch account and deserialize
-        let fetch_tok = if cfg.seeds.is_some() {
-            let seeds_expr = cfg.seeds.as_ref().unwrap();
-            let program_id_expr = cfg
-                .program_id
-                .as_ref()
-                .expect("program_id required when seeds provided");
-            quote! {
-                saturn_account_parser::get_pda_account(
-                    accounts,
-                    idx,
-                    #signer_tok,
-                    #writable_tok,
-                    #seeds_expr,
-                    &#program_id_expr,
-                )?
-            }
+        // Token program wrappers and `ZeroCopySlice` check their own layout
+        // instead of decoding Borsh, `Lazy` fields are only decoded when the
+        // handler accesses them, `UncheckedAccount` fields are never decoded
+        // and `one_of` enums pick their variant from the stored discriminator.
+        let load_tok = if cfg.is_one_of {
+            let base_ty = &cfg.base_ty;
+            quote! { <#base_ty as saturn_account_parser::AccountEnum>::try_from_info(acc_info_tmp)? }
+        } else if is_path_named(&cfg.base_ty, "TokenAccount")
+            || is_path_named(&cfg.base_ty, "Mint")
+            || is_path_named(&cfg.base_ty, "ZeroCopySlice")
+        {
+            let base_ty = &cfg.base_ty;
+            quote! { <#base_ty>::load(acc_info_tmp)? }
+        } else if is_path_named(&cfg.base_ty, "Lazy")
+            || is_path_named(&cfg.base_ty, "UncheckedAccount")
+        {
+            let base_ty = &cfg.base_ty;
+            quote! { <#base_ty>::new(acc_info_tmp) }
         } else {
-            quote! {
-                saturn_account_parser::get_account(
-                    accounts,
-                    idx,
-                    #signer_tok,
-                    #writable_tok,
-                    #address_tok,
-                )?
-            }
+            quote! { saturn_account_parser::codec::Account::<#inner_ty_ts>::load(acc_info_tmp)? }
         };
 
         quote! {
-            let acc_info_tmp = { #fetch_tok };
-            #owner_check_snip
+            let acc_info_tmp = { #fetch_account };
+            #account_checks_snip
             idx += 1;
-            let #ident = saturn_account_parser::codec::Account::<#inner_ty_ts>::load(acc_info_tmp)?;
+            let #ident = #load_tok;
         }
     }
 }
@@ -579,158 +654,708 @@ mod tests {
     }
 
     #[test]
-    fn generates_zero_copy_init_path() {
+    fn generates_rent_exempt_check() {
         let di: DeriveInput = parse_quote! {
             struct Accs<'info> {
-                #[account(zero_copy, init, payer = payer, program_id = arch_program::pubkey::Pubkey::default())]
-                data: saturn_account_parser::codec::AccountLoader<'info, u64>,
-                #[account(signer)]
-                payer: arch_program::account::AccountInfo<'info>,
+                #[account(rent_exempt)]
+                config: saturn_account_parser::codec::Account<'info, u64>,
             }
         };
         let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
         let cfg = &parsed[0];
+
         let ts = generate_single_binding(
             cfg,
             &cfg.ident,
-            quote!(Some(true)),
             quote!(None),
             quote!(None),
-            Some(quote!(payer)),
-            Some(quote!(arch_program::pubkey::Pubkey::default())),
+            quote!(None),
+            None,
+            None,
         );
         let rendered = ts.to_string();
-        assert!(rendered.contains("AccountLoader"));
-        assert!(rendered.contains("load_init"));
+        assert!(rendered.contains("check_rent_exempt"));
     }
 
     #[test]
-    fn generates_zero_copy_init_pda_path() {
+    fn generates_lazy_binding() {
         let di: DeriveInput = parse_quote! {
             struct Accs<'info> {
-                #[account(zero_copy, init, payer = payer, program_id = arch_program::pubkey::Pubkey::default(), seeds = &[b"seed"])]
-                data: saturn_account_parser::codec::AccountLoader<'info, u64>,
-                #[account(signer)]
-                payer: arch_program::account::AccountInfo<'info>,
+                #[account(owner = crate::ID)]
+                config: Lazy<Account<'info, Config>>,
             }
         };
         let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
         let cfg = &parsed[0];
+
         let ts = generate_single_binding(
             cfg,
             &cfg.ident,
-            quote!(None), // signer
-            quote!(None), // writable
-            quote!(None), // address override
-            Some(quote!(payer)),
-            Some(quote!(arch_program::pubkey::Pubkey::default())),
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
         );
         let rendered = ts.to_string();
-        assert!(rendered.contains("invoke_signed"));
-        assert!(rendered.contains("AccountLoader"));
-        assert!(rendered.contains("load_init"));
+        assert!(rendered.contains("< Lazy < Account < 'info , Config > > > :: new (acc_info_tmp)"));
+        assert!(!rendered.contains(":: load (acc_info_tmp)"));
     }
 
     #[test]
-    fn generates_zero_copy_no_init_path() {
+    fn generates_unchecked_account_binding() {
         let di: DeriveInput = parse_quote! {
             struct Accs<'info> {
-                #[account(zero_copy)]
-                data: saturn_account_parser::codec::AccountLoader<'info, u64>,
+                #[account(mut)]
+                unchecked_feed: UncheckedAccount<'info>,
             }
         };
         let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
         let cfg = &parsed[0];
+
         let ts = generate_single_binding(
             cfg,
             &cfg.ident,
             quote!(None),
-            quote!(None),
+            quote!(Some(true)),
             quote!(None),
             None,
             None,
         );
         let rendered = ts.to_string();
-        assert!(rendered.contains("AccountLoader"));
-        // Ensure we do NOT eagerly call load_init
-        assert!(!rendered.contains("load_init"));
+        assert!(rendered.contains("< UncheckedAccount < 'info > > :: new (acc_info_tmp)"));
+        assert!(!rendered.contains("Account :: <"));
     }
 
     #[test]
-    fn generates_borsh_init_pda_path() {
+    fn generates_migration_on_load() {
         let di: DeriveInput = parse_quote! {
             struct Accs<'info> {
-                #[account(init, payer = payer, program_id = arch_program::pubkey::Pubkey::default(), seeds = &[b"seed"])]
-                data: saturn_account_parser::codec::Account<'info, u64>,
-                #[account(signer)]
-                payer: arch_program::account::AccountInfo<'info>,
+                #[account(mut, zero_copy, migrate_from = 1)]
+                pool: saturn_account_parser::codec::AccountLoader<'info, Pool>,
             }
         };
         let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
         let cfg = &parsed[0];
+
         let ts = generate_single_binding(
             cfg,
             &cfg.ident,
             quote!(None),
+            quote!(Some(true)),
             quote!(None),
-            quote!(None),
-            Some(quote!(payer)),
-            Some(quote!(arch_program::pubkey::Pubkey::default())),
+            None,
+            None,
         );
         let rendered = ts.to_string();
-        assert!(rendered.contains("invoke_signed"));
-        assert!(rendered.contains("Account"));
-        assert!(rendered.contains("init"));
+        assert!(rendered.contains("if pool . version () ? < (1) as u8"));
+        assert!(rendered.contains("UnsupportedAccountVersion"));
+        assert!(rendered.contains("drop (pool . load_or_migrate () ?) ;"));
     }
 
     #[test]
-    fn generates_borsh_init_non_pda_path() {
+    fn generates_custom_discriminator_loader() {
         let di: DeriveInput = parse_quote! {
             struct Accs<'info> {
-                #[account(init, payer = payer, program_id = arch_program::pubkey::Pubkey::default())]
-                data: saturn_account_parser::codec::Account<'info, u64>,
-                #[account(signer)]
-                payer: arch_program::account::AccountInfo<'info>,
+                #[account(zero_copy, discriminator = none)]
+                pool: saturn_account_parser::codec::AccountLoader<'info, ForeignPool>,
             }
         };
         let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
         let cfg = &parsed[0];
+
         let ts = generate_single_binding(
             cfg,
             &cfg.ident,
             quote!(None),
             quote!(None),
             quote!(None),
-            Some(quote!(payer)),
-            Some(quote!(arch_program::pubkey::Pubkey::default())),
+            None,
+            None,
         );
         let rendered = ts.to_string();
-        assert!(rendered.contains("invoke(") || rendered.contains("invoke ("));
-        assert!(rendered.contains("Account"));
-        assert!(rendered.contains("init"));
+        assert!(rendered.contains(":: with_discriminator (acc_info_tmp , b\"\")"));
+        assert!(!rendered.contains(":: new (acc_info_tmp)"));
     }
 
     #[test]
-    fn generates_account_info_pda_path() {
+    fn generates_custom_constraint_errors() {
         let di: DeriveInput = parse_quote! {
             struct Accs<'info> {
-                #[account(seeds = &[b"seed"], program_id = arch_program::pubkey::Pubkey::default())]
-                pda_ai: arch_program::account::AccountInfo<'info>,
+                #[account(signer @ MyError::MissingAdminSignature, owner = crate::ID @ MyError::WrongOwner, rent_exempt)]
+                admin: arch_program::account::AccountInfo<'info>,
             }
         };
         let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
         let cfg = &parsed[0];
+
         let ts = generate_single_binding(
             cfg,
             &cfg.ident,
-            quote!(None),
+            quote!(Some(true)),
             quote!(None),
             quote!(None),
             None,
             None,
         );
         let rendered = ts.to_string();
-        assert!(rendered.contains("get_pda_account"));
+        // Overridden constraints return the program's errors ...
+        assert!(rendered.contains("ErrorCode :: IncorrectIsSignerFlag) =>"));
+        assert!(rendered.contains("into (MyError :: MissingAdminSignature)"));
+        assert!(rendered.contains("into (MyError :: WrongOwner)"));
+        assert!(!rendered.contains("InvalidAccountOwner"));
+        // ... the others keep the parser's.
+        assert!(!rendered.contains("IncorrectIsWritableFlag"));
+        assert!(rendered.contains("check_rent_exempt"));
+        assert!(!rendered.contains("NotRentExempt"));
+    }
+
+    #[test]
+    fn generates_funded_anchored_init() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: arch_program::account::AccountInfo<'info>,
+                #[account(init, payer = payer, space = 64, funding = DUST_LIMIT, anchor = utxo)]
+                state: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[1];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            Some(quote!(payer)),
+            Some(quote!(crate::ID)),
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("let lamports : u64 = (DUST_LIMIT) as u64 ;"));
+        assert!(rendered.contains("create_account_with_anchor"));
+        assert!(rendered.contains("anchor_utxo . txid_big_endian ()"));
+        assert!(!rendered.contains("minimum_balance"));
+    }
+
+    #[test]
+    fn generates_known_bump_pda_lookup() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(seeds = &[b"vault"], bump = VAULT_BUMP)]
+                vault: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("let bump : u8 = VAULT_BUMP ;"));
+        assert!(rendered.contains("get_pda_account_with_known_bump"));
+        assert!(!rendered.contains("get_pda_account_with_bump"));
+    }
+
+    #[test]
+    fn generates_signer_or_pda_check() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(authority, allow_pda(seeds = &[b"router"], program_id = ROUTER_ID))]
+                authority: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("if ! acc_info_tmp . is_signer"));
+        assert!(rendered.contains("find_program_address (& [b\"router\"] , & ROUTER_ID) . 0"));
+        assert!(rendered.contains("InvalidAuthority"));
+    }
+
+    #[test]
+    fn generates_one_of_dispatch() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(mut, one_of)]
+                target: PoolAccount<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains(
+            "< PoolAccount < 'info > as saturn_account_parser :: AccountEnum > :: try_from_info (acc_info_tmp) ?"
+        ));
+    }
+
+    #[test]
+    fn generates_zero_copy_slice_load() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(mut, owner = crate::ID)]
+                orders: ZeroCopySlice<'info, Order>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(Some(true)),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("< ZeroCopySlice < 'info , Order > > :: load (acc_info_tmp) ?"));
+    }
+
+    #[test]
+    fn generates_interface_check() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(interface = [TOKEN_V1, TOKEN_V2])]
+                vault: TokenAccount<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("([TOKEN_V1 , TOKEN_V2]) . iter ()"));
+        assert!(rendered.contains("InvalidAccountOwner"));
+    }
+
+    #[test]
+    fn generates_address_in_check() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(address_in = [ORACLE_A, ORACLE_B])]
+                oracle: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("([ORACLE_A , ORACLE_B]) . iter ()"));
+        assert!(rendered.contains("InvalidAccountKey"));
+    }
+
+    #[test]
+    fn generates_executable_check() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(executable)]
+                token_program: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("! acc_info_tmp . is_executable"));
+        assert!(rendered.contains("AccountNotExecutable"));
+    }
+
+    #[test]
+    fn generates_token_account_checks() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(token::mint = usdc_mint, token::authority = vault)]
+                vault_usdc: saturn_account_parser::TokenAccount<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("< saturn_account_parser :: TokenAccount < 'info > > :: load"));
+        assert!(rendered.contains("vault_usdc . mint !="));
+        assert!(rendered.contains("InvalidTokenAuthority"));
+        assert!(!rendered.contains("codec :: Account"));
+    }
+
+    #[test]
+    fn generates_owner_check() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(owner = arch_program::pubkey::Pubkey::default())]
+                config: saturn_account_parser::codec::Account<'info, u64>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("acc_info_tmp . owner !="));
+        assert!(rendered.contains("arch_program :: msg !"));
+        assert!(rendered.contains("InvalidAccountOwner"));
+    }
+
+    #[test]
+    fn generates_zero_copy_init_path() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(zero_copy, init, payer = payer, program_id = arch_program::pubkey::Pubkey::default())]
+                data: saturn_account_parser::codec::AccountLoader<'info, u64>,
+                #[account(signer)]
+                payer: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(Some(true)),
+            quote!(None),
+            quote!(None),
+            Some(quote!(payer)),
+            Some(quote!(arch_program::pubkey::Pubkey::default())),
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("AccountLoader"));
+        assert!(rendered.contains("load_init"));
+    }
+
+    #[test]
+    fn generates_zero_copy_init_pda_path() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(zero_copy, init, payer = payer, program_id = arch_program::pubkey::Pubkey::default(), seeds = &[b"seed"])]
+                data: saturn_account_parser::codec::AccountLoader<'info, u64>,
+                #[account(signer)]
+                payer: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None), // signer
+            quote!(None), // writable
+            quote!(None), // address override
+            Some(quote!(payer)),
+            Some(quote!(arch_program::pubkey::Pubkey::default())),
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("invoke_signed"));
+        assert!(rendered.contains("AccountLoader"));
+        assert!(rendered.contains("load_init"));
+    }
+
+    #[test]
+    fn generates_zero_copy_no_init_path() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(zero_copy)]
+                data: saturn_account_parser::codec::AccountLoader<'info, u64>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("AccountLoader"));
+        // Ensure we do NOT eagerly call load_init
+        assert!(!rendered.contains("load_init"));
+    }
+
+    #[test]
+    fn generates_borsh_init_pda_path() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(init, payer = payer, program_id = arch_program::pubkey::Pubkey::default(), seeds = &[b"seed"])]
+                data: saturn_account_parser::codec::Account<'info, u64>,
+                #[account(signer)]
+                payer: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            Some(quote!(payer)),
+            Some(quote!(arch_program::pubkey::Pubkey::default())),
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("invoke_signed"));
+        assert!(rendered.contains("Account"));
+        assert!(rendered.contains("init"));
+    }
+
+    #[test]
+    fn generates_borsh_init_non_pda_path() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(init, payer = payer, program_id = arch_program::pubkey::Pubkey::default())]
+                data: saturn_account_parser::codec::Account<'info, u64>,
+                #[account(signer)]
+                payer: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            Some(quote!(payer)),
+            Some(quote!(arch_program::pubkey::Pubkey::default())),
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("invoke(") || rendered.contains("invoke ("));
+        assert!(rendered.contains("Account"));
+        assert!(rendered.contains("init"));
+    }
+
+    #[test]
+    fn generates_init_with_custom_owner() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: arch_program::account::AccountInfo<'info>,
+                #[account(mut, init, payer = payer, owner = REGISTRY_ID, space = 64)]
+                entry: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[1];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(Some(true)),
+            quote!(None),
+            Some(quote!(payer)),
+            Some(quote!(crate::ID)),
+        );
+        let rendered = ts.to_string();
+        // Created for the registry program, no owner check and no data init.
+        assert!(rendered.contains("& REGISTRY_ID ,"));
+        assert!(!rendered.contains("InvalidAccountOwner"));
+        assert!(!rendered.contains("Account :: < "));
+    }
+
+    #[test]
+    fn generates_realloc_resize() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: arch_program::account::AccountInfo<'info>,
+                #[account(mut, realloc, realloc::zero = true, payer = payer, space = 16)]
+                data: saturn_account_parser::codec::Account<'info, u64>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[1];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(Some(true)),
+            quote!(None),
+            Some(quote!(payer)),
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("acc_info_tmp . realloc (new_len , true)"));
+        // Top-up when growing, refund when shrinking.
+        assert!(rendered.contains("system_instruction :: transfer"));
+        assert!(rendered.contains("new_len < old_len"));
+    }
+
+    #[test]
+    fn realloc_zeroes_added_bytes_by_default() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: arch_program::account::AccountInfo<'info>,
+                #[account(mut, realloc, payer = payer, space = 16)]
+                data: saturn_account_parser::codec::Account<'info, u64>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[1];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(Some(true)),
+            quote!(None),
+            Some(quote!(payer)),
+            None,
+        );
+        assert!(ts
+            .to_string()
+            .contains("acc_info_tmp . realloc (new_len , true)"));
+    }
+
+    #[test]
+    fn generates_zero_copy_realloc_through_loader() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: arch_program::account::AccountInfo<'info>,
+                #[account(mut, zero_copy, realloc, payer = payer, space = 8 + 64)]
+                book: saturn_account_parser::codec::AccountLoader<'info, Book>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[1];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(Some(true)),
+            quote!(None),
+            Some(quote!(payer)),
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("book . realloc (new_len"));
+        assert!(!rendered.contains("acc_info_tmp . realloc"));
+    }
+
+    #[test]
+    fn generates_account_info_pda_path() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(seeds = &[b"seed"], program_id = arch_program::pubkey::Pubkey::default())]
+                pda_ai: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("get_pda_account"));
+        assert!(rendered.contains("__bumps . pda_ai = bump"));
+        assert!(rendered.contains("__bumps . __seeds . record (stringify ! (pda_ai) , pda_seeds , bump)"));
+    }
+
+    #[test]
+    fn generates_pda_path_with_seeds_program() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(seeds = &[b"metadata"], seeds_program = METADATA_ID)]
+                metadata: arch_program::account::AccountInfo<'info>,
+            }
+        };
+        let parsed = crate::parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        let cfg = &parsed[0];
+        let ts = generate_single_binding(
+            cfg,
+            &cfg.ident,
+            quote!(None),
+            quote!(None),
+            quote!(None),
+            None,
+            None,
+        );
+        let rendered = ts.to_string();
+        assert!(rendered.contains("& METADATA_ID"));
+        assert!(!rendered.contains("crate :: ID"));
     }
 
     #[test]
@@ -1,4 +1,326 @@
-use syn::Type;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Expr, Type};
+
+use crate::model::{AllowPda, FieldCfg};
+
+/// Error returned when the field's `constraint` fails: its `@ <error>`
+/// override converted into a `ProgramError`, else the parser's `ErrorCode::code`.
+pub(crate) fn constraint_error(cfg: &FieldCfg, constraint: &str, code: TokenStream) -> TokenStream {
+    match custom_error(cfg, constraint) {
+        Some(error) => quote! {
+            ::core::convert::Into::<arch_program::program_error::ProgramError>::into(#error)
+        },
+        None => quote! {
+            arch_program::program_error::ProgramError::Custom(
+                saturn_account_parser::error::ErrorCode::#code.into(),
+            )
+        },
+    }
+}
+
+/// `.map_err(..)` for the `get_*account` call fetching the field, replacing
+/// the signer, writable, address and PDA errors it returns with the field's
+/// `@ <error>` overrides. Empty when the field has none of them.
+pub(crate) fn fetch_error_overrides(cfg: &FieldCfg) -> TokenStream {
+    // A known `bump = <expr>` is verified together with the `seeds`.
+    let pda_error = custom_error(cfg, "seeds").or_else(|| custom_error(cfg, "bump"));
+    let overrides = [
+        (custom_error(cfg, "signer"), quote!(IncorrectIsSignerFlag)),
+        (custom_error(cfg, "mut"), quote!(IncorrectIsWritableFlag)),
+        (custom_error(cfg, "address"), quote!(InvalidAccountKey)),
+        (pda_error, quote!(InvalidPda)),
+    ];
+    let arms: Vec<TokenStream> = overrides
+        .into_iter()
+        .filter_map(|(error, code)| {
+            let error = error?;
+            Some(quote! {
+                arch_program::program_error::ProgramError::Custom(code)
+                    if code == u32::from(saturn_account_parser::error::ErrorCode::#code) =>
+                {
+                    ::core::convert::Into::<arch_program::program_error::ProgramError>::into(#error)
+                }
+            })
+        })
+        .collect();
+    if arms.is_empty() {
+        return TokenStream::new();
+    }
+    quote! {
+        .map_err(|err| match err {
+            #(#arms)*
+            err => err,
+        })
+    }
+}
+
+fn custom_error<'a>(cfg: &'a FieldCfg, constraint: &str) -> Option<&'a Expr> {
+    cfg.constraint_errors
+        .iter()
+        .find(|(name, _)| name == constraint)
+        .map(|(_, error)| error)
+}
+
+/// Lamports an `init` / `init_if_needed` field is created with: its
+/// `funding = <expr>` when given, else the minimum balance for `space` (a
+/// `u64` in scope where the tokens are used).
+pub(crate) fn init_lamports(cfg: &FieldCfg) -> TokenStream {
+    match &cfg.funding {
+        Some(funding_expr) => quote! { (#funding_expr) as u64 },
+        None => quote! { saturn_account_parser::minimum_balance(space as usize) },
+    }
+}
+
+/// The system instruction creating the account in `acc_info_tmp`, with
+/// `lamports` and `space` in scope. Fields with `anchor = <utxo meta>` are
+/// created anchored to that UTXO.
+pub(crate) fn create_account_ix(
+    cfg: &FieldCfg,
+    payer_expr: &TokenStream,
+    owner_expr: &TokenStream,
+) -> TokenStream {
+    match &cfg.anchor {
+        Some(anchor_expr) => quote! {
+            {
+                let anchor_utxo: &arch_program::utxo::UtxoMeta = &#anchor_expr;
+                arch_program::system_instruction::create_account_with_anchor(
+                    saturn_account_parser::ToAccountInfo::to_account_info(&#payer_expr).key,
+                    acc_info_tmp.key,
+                    lamports,
+                    space,
+                    &#owner_expr,
+                    anchor_utxo.txid_big_endian(),
+                    anchor_utxo.vout(),
+                )
+            }
+        },
+        None => quote! {
+            arch_program::system_instruction::create_account(
+                saturn_account_parser::ToAccountInfo::to_account_info(&#payer_expr).key,
+                acc_info_tmp.key,
+                lamports,
+                space,
+                &#owner_expr,
+            )
+        },
+    }
+}
+
+/// Length of the prefix before the data of a `zero_copy` field: its
+/// `discriminator = <bytes>`, else the 8-byte `Discriminator`.
+pub(crate) fn discriminator_len(cfg: &FieldCfg) -> TokenStream {
+    match &cfg.discriminator {
+        Some(discriminator_expr) => quote! { (#discriminator_expr).len() },
+        None => quote! { 8 },
+    }
+}
+
+/// Compile-time checks of the `of = T` type of a `zero_copy` field: `T` must
+/// not need more than the alignment its data gets after the discriminator
+/// (at most 8) and, with `layout::size = N`, `size_of::<T>()` must be `N`.
+/// `None` for other fields.
+pub(crate) fn layout_asserts(cfg: &FieldCfg) -> Option<TokenStream> {
+    let data_ty = cfg.of_type.as_ref().filter(|_| cfg.is_zero_copy)?;
+    let align_msg = format!(
+        "`{}`: zero-copy data starts after the discriminator and cannot need a larger alignment",
+        cfg.ident
+    );
+    // A custom discriminator also has to keep the data aligned.
+    let discriminator_assert = cfg.discriminator.as_ref().map(|_| {
+        let discriminator_len = discriminator_len(cfg);
+        quote! {
+            assert!((#discriminator_len) % core::mem::align_of::<#data_ty>() == 0, #align_msg);
+        }
+    });
+    let size_assert = cfg.layout_size.as_ref().map(|size_expr| {
+        let size_msg = format!(
+            "`{}`: size of the zero-copy type differs from `layout::size`",
+            cfg.ident
+        );
+        quote! {
+            assert!(core::mem::size_of::<#data_ty>() == (#size_expr) as usize, #size_msg);
+        }
+    });
+    Some(quote! {
+        const _: () = {
+            assert!(8 % core::mem::align_of::<#data_ty>() == 0, #align_msg);
+            #discriminator_assert
+            #size_assert
+        };
+    })
+}
+
+/// Statements rejecting `acc_info` (an `&AccountInfo` expression) when it is
+/// not owned by the field's `owner = <expr>` (or one of its
+/// `interface = [..]` programs), an `authority` neither signed nor is its
+/// `allow_pda` PDA, its key is not one of the `address_in = [..]` keys or,
+/// for `executable` fields, it is not an executable program account. Empty
+/// when the field has none of them.
+///
+/// On `init` / `init_if_needed` fields `owner` is the owner the account is
+/// created with instead, so it is not checked here.
+pub(crate) fn account_checks(cfg: &FieldCfg, acc_info: TokenStream) -> TokenStream {
+    let is_any_init = cfg.is_init || cfg.is_init_if_needed;
+    let checked_owner = cfg.owner.as_ref().filter(|_| !is_any_init);
+    let owner_check = checked_owner.map(|owner_expr| {
+        let error = constraint_error(cfg, "owner", quote!(InvalidAccountOwner));
+        quote! {
+            if #acc_info.owner != &#owner_expr {
+                arch_program::msg!(
+                    "account {}: owned by {}, expected {}",
+                    #acc_info.key,
+                    #acc_info.owner,
+                    #owner_expr
+                );
+                return Err(#error);
+            }
+        }
+    });
+    let interface_check = cfg.interface.as_ref().map(|programs_expr| {
+        let error = constraint_error(cfg, "interface", quote!(InvalidAccountOwner));
+        quote! {
+            if !(#programs_expr).iter().any(|program| program == #acc_info.owner) {
+                return Err(#error);
+            }
+        }
+    });
+    let authority_check = cfg.is_authority.then(|| match &cfg.allow_pda {
+        Some(AllowPda { seeds, program_id }) => {
+            let error = constraint_error(cfg, "authority", quote!(InvalidAuthority));
+            quote! {
+                if !#acc_info.is_signer
+                    && *#acc_info.key
+                        != arch_program::pubkey::Pubkey::find_program_address(#seeds, &#program_id).0
+                {
+                    return Err(#error);
+                }
+            }
+        }
+        None => {
+            let error = constraint_error(cfg, "authority", quote!(IncorrectIsSignerFlag));
+            quote! {
+                if !#acc_info.is_signer {
+                    return Err(#error);
+                }
+            }
+        }
+    });
+    let address_in_check = cfg.address_in.as_ref().map(|keys_expr| {
+        let error = constraint_error(cfg, "address_in", quote!(InvalidAccountKey));
+        quote! {
+            if !(#keys_expr).iter().any(|allowed_key| allowed_key == #acc_info.key) {
+                return Err(#error);
+            }
+        }
+    });
+    let executable_check = cfg.is_executable.then(|| {
+        let error = constraint_error(cfg, "executable", quote!(AccountNotExecutable));
+        quote! {
+            if !#acc_info.is_executable {
+                return Err(#error);
+            }
+        }
+    });
+    quote! {
+        #owner_check
+        #interface_check
+        #authority_check
+        #address_in_check
+        #executable_check
+    }
+}
+
+/// Statement rejecting a slice or shard vector whose runtime length `len_val`
+/// exceeds its `max_len = <expr>`. Empty without `max_len`.
+pub(crate) fn max_len_check(cfg: &FieldCfg) -> TokenStream {
+    let Some(max_len) = &cfg.max_len else {
+        return TokenStream::new();
+    };
+    let error = constraint_error(cfg, "max_len", quote!(AccountLenExceedsMax));
+    quote! {
+        if len_val > (#max_len as usize) {
+            return Err(#error);
+        }
+    }
+}
+
+/// Statements resizing `acc_info_tmp` to `new_len` bytes for `realloc`.
+///
+/// `allocate_cpi` runs first when the account grows. The added bytes are
+/// zeroed unless `realloc::zero = false`. With a `payer`, the account is then
+/// topped up to `minimum_balance(new_len)` when it grows, and the lamports it
+/// no longer needs go back to the payer when it shrinks.
+pub(crate) fn resize_account(
+    cfg: &FieldCfg,
+    new_len: TokenStream,
+    allocate_cpi: TokenStream,
+    payer: Option<&TokenStream>,
+) -> TokenStream {
+    let zero_init = match &cfg.realloc_zero {
+        Some(expr) => quote! { #expr },
+        None => quote! { true },
+    };
+
+    let rebalance = payer.map(|payer_expr| {
+        quote! {
+            let payer_info = saturn_account_parser::ToAccountInfo::to_account_info(&#payer_expr);
+            let required = saturn_account_parser::minimum_balance(new_len);
+            let current = acc_info_tmp.lamports();
+            if new_len > old_len && current < required {
+                arch_program::program::invoke(
+                    &arch_program::system_instruction::transfer(
+                        payer_info.key,
+                        acc_info_tmp.key,
+                        required - current,
+                    ),
+                    &[payer_info.clone(), acc_info_tmp.clone()],
+                )?;
+            } else if new_len < old_len && current > required {
+                let payer_balance = payer_info
+                    .lamports()
+                    .checked_add(current - required)
+                    .ok_or(arch_program::program_error::ProgramError::ArithmeticOverflow)?;
+                **payer_info.try_borrow_mut_lamports()? = payer_balance;
+                **acc_info_tmp.try_borrow_mut_lamports()? = required;
+            }
+        }
+    });
+
+    quote! {
+        let new_len: usize = (#new_len) as usize;
+        let old_len: usize = acc_info_tmp.data_len();
+        if new_len > old_len {
+            #allocate_cpi
+        }
+
+        // Update local slice regardless of grow/shrink.
+        if acc_info_tmp.data_len() != new_len {
+            acc_info_tmp.realloc(new_len, #zero_init)?;
+        }
+
+        #rebalance
+    }
+}
+
+/// Whether an instruction passing the field's account must mark it writable:
+/// it is `mut`, or `init`, `realloc` or `close` write it.
+pub(crate) fn is_writable_meta(cfg: &FieldCfg) -> bool {
+    cfg.is_writable == Some(true)
+        || cfg.is_init
+        || cfg.is_init_if_needed
+        || cfg.is_realloc
+        || cfg.close.is_some()
+}
+
+/// Program the field's `seeds` PDA is derived from: `seeds_program` when
+/// given, `program_id` otherwise.
+pub(crate) fn pda_program_id(cfg: &FieldCfg) -> &Expr {
+    cfg.seeds_program
+        .as_ref()
+        .or(cfg.program_id.as_ref())
+        .expect("program_id required when seeds provided")
+}
 
 /// Returns `true` if the provided `syn::Type` (with any level of references)
 /// ultimately resolves to a path whose last segment is `AccountInfo`.
@@ -14,6 +336,12 @@ pub(crate) fn is_account_info_path(ty: &Type) -> bool {
     }
 }
 
+/// Returns `true` if `ty` is a path whose last segment is `name`, e.g. the
+/// `TokenAccount` / `Mint` wrappers of `saturn_account_parser`.
+pub(crate) fn is_path_named(ty: &Type, name: &str) -> bool {
+    matches!(ty, Type::Path(tp) if tp.path.segments.last().map_or(false, |seg| seg.ident == name))
+}
+
 /// If `ty` is a `saturn_account_parser::codec::Account<'info, T>` or
 /// `saturn_account_parser::codec::AccountLoader<'info, T>` path, this helper
 /// returns the **inner** generic type `T`. When `ty` does not match either
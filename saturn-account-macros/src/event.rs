@@ -0,0 +1,55 @@
+//! `#[derive(Event)]`: structs logged with `saturn_account_parser::emit!`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use sha2::{Digest, Sha256};
+use syn::DeriveInput;
+
+/// Implements `saturn_account_parser::event::Event`, with the discriminator
+/// `sha256("event:<Name>")[0..8]` that Anchor uses for events.
+pub(crate) fn derive(input: &DeriveInput) -> TokenStream {
+    let ident = &input.ident;
+    let discriminator = event_discriminator(&ident.to_string());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics saturn_account_parser::event::Event for #ident #ty_generics #where_clause {
+            const DISCRIMINATOR: [u8; 8] = [#(#discriminator),*];
+        }
+    }
+}
+
+fn event_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("event:{name}").as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn implements_event() {
+        let di: DeriveInput = parse_quote! {
+            struct Deposited {
+                amount: u64,
+            }
+        };
+        let rendered = derive(&di).to_string();
+        assert!(rendered.contains("impl saturn_account_parser :: event :: Event for Deposited"));
+        assert!(rendered.contains("const DISCRIMINATOR : [u8 ; 8]"));
+    }
+
+    #[test]
+    fn discriminator_uses_event_namespace() {
+        let hash = Sha256::digest(b"event:Deposited");
+        assert_eq!(event_discriminator("Deposited")[..], hash[..8]);
+        assert_ne!(
+            event_discriminator("Deposited"),
+            event_discriminator("Withdrawn")
+        );
+    }
+}
@@ -4,7 +4,9 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields};
 mod model;
 // `FieldKind` and `FieldCfg` are now used only by submodules (`parser`, `codegen`).
 
+mod account_enum;
 mod codegen;
+mod event;
 mod parser;
 mod validator;
 
@@ -56,17 +58,38 @@ mod validator;
 /// | `signer` | The account **must** sign the transaction. | `#[account(signer)]` |
 /// | `writable` / `mut` | The account must be writable. | `#[account(mut)]` |
 /// | `address = <expr>` | Enforce an **absolute** `Pubkey` for this account. | `#[account(address = token_program::ID)]` |
+/// | `address_in = <expr>` | The account key **must** be one of the given keys (an array or slice of `Pubkey`), else `ErrorCode::InvalidAccountKey`. Checked on every element of a `len` slice. | `#[account(address_in = [ORACLE_A, ORACLE_B, ORACLE_C])]` |
+/// | `owner = <expr>` | The account **must** be owned by the given program, else `ErrorCode::InvalidAccountOwner`. Checked on every element of a `len` slice. With `init` / `init_if_needed` the account is instead created owned by that program (`program_id` still derives and signs for PDA seeds). | `#[account(owner = token_program::ID)]` |
+/// | `interface = <expr>` | The account **must** be owned by one of the given programs (an array or slice of `Pubkey`), else `ErrorCode::InvalidAccountOwner`, e.g. for either of two compatible token programs. Checked on every element of a `len` slice. | `#[account(interface = [token_v1::ID, token_v2::ID])]` |
+/// | `authority` | The account must sign, else `ErrorCode::IncorrectIsSignerFlag`. | `#[account(authority)]` |
+/// | `allow_pda(seeds = &[..], program_id = <expr>)` | With `authority`: the canonical PDA of these seeds (`program_id` defaults to `crate::ID`) is accepted without a signature, so the instruction can be driven by a user or by a program via CPI. Otherwise fails with `ErrorCode::InvalidAuthority`. | `#[account(authority, allow_pda(seeds = &[b"router"], program_id = ROUTER_ID))]` |
+/// | `executable` | The account **must** be an executable program account, else `ErrorCode::AccountNotExecutable`. Use it on programs passed for CPI. | `#[account(executable, address = token_program::ID)]` |
 /// | `seeds = &[..], program_id = <expr>` | Marks the account as a **Program Derived Address**. | `#[account(seeds = &[b"vault", payer.key()], program_id = crate::ID)]` |
-/// | `len = <expr>` | Enforce the exact length of a fixed-size slice or vector. | `#[account(len = 3)]` |
+/// | `seeds_program = <expr>` | Verify the `seeds` PDA (and its bump) against another program, e.g. a metadata program, instead of `program_id`. Not valid with `init`, `init_if_needed`, `realloc` or `shards`. | `#[account(seeds = &[b"metadata", mint.key.as_ref()], seeds_program = METADATA_ID)]` |
+/// | `len = <expr>` | Enforce the exact length of a fixed-size slice or vector. Evaluated while parsing, so it may read earlier fields. | `#[account(len = 3)]` |
+/// | `max_len = <expr>` | Upper bound of a runtime `len`, else `ErrorCode::AccountLenExceedsMax`. | `#[account(shards, len = config.load()?.shard_count, max_len = 16)]` |
 /// | `shards` | Indicates a `Vec<AccountInfo>` that stores PDA shards. | `#[account(shards)]` |
 /// | `of = Type` | Asserts that the account data deserialises into `Type`. | `#[account(of = TokenAccount)]` |
-/// | `zero_copy` | Read the account data via zero-copy. Must be combined with `of`. | `#[account(zero_copy, of = MarketState)]` |
+/// | `zero_copy` | Read the account data via zero-copy. Must be combined with `of`. The build fails when `of`'s alignment exceeds the 8-byte discriminator offset. | `#[account(zero_copy, of = MarketState)]` |
+/// | `migrate_from = <version>` | Accept a `zero_copy` account of a `saturn_account_parser::Migratable` type stored in any layout version from this one on, and upgrade older ones in place (`AccountLoader::load_or_migrate`) before the handler runs. Requires `mut`. | `#[account(mut, zero_copy, migrate_from = 1)]` |
+/// | `discriminator = none \| <bytes>` | Prefix of a `zero_copy` account's data in place of the type's 8-byte `Discriminator`, for accounts of foreign programs: `none` for the bare struct, or e.g. `b"POOL"`. The loader checks (and `init` writes) that prefix instead. | `#[account(zero_copy, of = ForeignPool, discriminator = none)]` |
+/// | `layout::size = <const expr>` | Fail the build unless `size_of` of the `zero_copy` field's `of` type is exactly this many bytes (discriminator excluded), catching layout drift before it shows up as `InvalidAccountData`. | `#[account(zero_copy, of = MarketState, layout::size = 256)]` |
 /// | `init` | Create a brand-new account. Requires `payer` & `program_id`; optional `space`. | `#[account(init, payer = payer, program_id = crate::ID, space = 8 + State::SIZE)]` |
 /// | `init_if_needed` | Same as `init` but skips creation if the account already exists. | `#[account(init_if_needed, payer = payer, program_id = crate::ID, space = 72)]` |
-/// | `realloc` | Reallocate/extend an existing account. Requires `payer` & `space`. | `#[account(realloc, payer = payer, space = new_len)]` |
+/// | `realloc` | Grow or shrink an existing account to `space` bytes. With a `payer`, the account is topped up to `saturn_account_parser::minimum_balance` when it grows and the excess lamports go back to the payer when it shrinks. `zero_copy` accounts with a `payer` are resized by `AccountLoader::realloc`, which keeps their discriminator, zeroes the added bytes and rejects a `space` too small for the type. | `#[account(realloc, payer = payer, space = new_len)]` |
+/// | `realloc::zero = <bool>` | Zero the bytes added by `realloc` (default `true`). Only opt out when the account cannot have shrunk earlier in the same instruction, or stale data reappears. | `#[account(realloc, realloc::zero = false, payer = payer, space = new_len)]` |
+/// | `funding = <expr>` | Lamports (satoshis) an `init` / `init_if_needed` account is created with, instead of the minimum balance for its `space`. | `#[account(init, payer = payer, funding = DUST_LIMIT)]` |
+/// | `anchor = <expr>` | `UtxoMeta` an `init` account is created anchored to (`create_account_with_anchor`). The handler still adds the account to its Bitcoin transaction, e.g. with `ctx.btc_tx.add_state_transition(..)`. | `#[account(init, payer = payer, anchor = params.utxo)]` |
 /// | `space = <expr>` | Byte length for `init`, `init_if_needed` or `realloc`. | `#[account(space = 8 + Config::SIZE)]` |
+/// | `rent_exempt` | The account must hold at least `saturn_account_parser::minimum_balance` for its data length, checked after any `init`/`realloc`. `init` always funds exactly that amount. | `#[account(mut, realloc, payer = payer, space = new_len, rent_exempt)]` |
 /// | `payer = <ident>` | Designates the account that pays rent for creation or resize. Must be a `signer`. | `#[account(init, payer = payer, …)]` |
-/// | `bump` | Declares a *non-account* `u8` field that stores the PDA bump. | `bump: u8 #[account(bump)]` |
+/// | `close = <ident>` | After the handler returns, close it with `saturn_account_parser::close_account`: move its balance to the given field, zero its data, and write a tombstone discriminator so it cannot be revived. The account stays owned by the program. Both accounts must be `mut`. | `#[account(mut, close = user)]` |
+/// | `one_of` | The field's type is an enum deriving [`AccountEnum`]; the variant is chosen by the account's discriminator, else `ErrorCode::UnknownAccountDiscriminator`. | `#[account(one_of)] target: PoolAccount<'info>` |
+/// | `nested` | The field's type itself derives `Accounts`; its accounts are parsed in place from the remaining slice and its `exit` runs after the handler. Takes no other flag. | `#[account(nested)] pool: PoolAccounts<'info>` |
+/// | `bump` | Declares a *non-account* `u8` field that stores the canonical bump of its `seeds`. Every PDA bump is also available as `ctx.bumps.<field>`. | `bump: u8 #[account(bump)]` |
+/// | `bump = <expr>` | On a `bump` field: a provided bump (e.g. an instruction argument) that must be the canonical one, else `ErrorCode::InvalidBump`. On a `seeds` account: its known bump, e.g. stored in its data, checked with `create_program_address` instead of searching with `find_program_address`. | `#[account(seeds = &[b"vault"], bump = params.vault_bump)]` |
+/// | `token::mint = <ident>` / `token::authority = <ident>` | On a `TokenAccount<'info>`: its mint / owner must be the key of the given (earlier) field. | `#[account(token::mint = usdc_mint, token::authority = vault_pda)]` |
+/// | `mint::authority = <ident>` / `mint::decimals = <expr>` | On a `Mint<'info>`: its mint authority must be the key of the given (earlier) field / its decimals must match. | `#[account(mint::authority = minter, mint::decimals = 6)]` |
 ///
 /// ### Sharded PDA vectors
 ///
@@ -82,6 +105,246 @@ mod validator;
 /// }
 /// ```
 ///
+/// With `init_if_needed` (plus `payer`), shards that do not exist yet are
+/// created, each PDA signed with its own index seed, while existing ones are
+/// loaded as usual. Zero-copy shards are created with `space` defaulting to
+/// their type plus the 8-byte discriminator, which is written on creation:
+///
+/// ```ignore
+/// #[account(mut, shards, init_if_needed, payer = caller, len = 4, seeds = &[b"shard"])]
+/// shards: Vec<AccountLoader<'info, Shard>>,
+/// ```
+///
+/// The number of shards can come from the accounts themselves, e.g. a pool
+/// config parsed earlier in the struct, so pools can add shards without a new
+/// `Accounts` struct. `max_len` bounds it:
+///
+/// ```ignore
+/// #[account(zero_copy)]
+/// config: AccountLoader<'info, PoolConfig>,
+/// #[account(shards, len = config.load()?.shard_count, max_len = MAX_SHARDS)]
+/// shards: Vec<AccountLoader<'info, Shard>>,
+/// ```
+///
+/// ### Optional accounts
+///
+/// A single account declared as `Option<..>` is `None` when the client passes
+/// the program id (`crate::ID`) in its slot; the slot is still consumed and no
+/// constraint of the field is checked. With `#[account(optional = trailing)]`
+/// the field is `None` when the account slice ends before it instead, so only
+/// other trailing optional fields may follow it.
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// pub struct Swap<'info> {
+///     #[account(signer)]
+///     user: AccountInfo<'info>,
+///     referrer: Option<AccountInfo<'info>>,
+///     #[account(mut, optional = trailing)]
+///     fee_vault: Option<Account<'info, FeeVault>>,
+/// }
+/// ```
+///
+/// ### Nested accounts
+///
+/// A group of accounts shared by several instructions can be declared once as
+/// its own `#[derive(Accounts)]` struct and embedded with `#[account(nested)]`.
+/// The nested struct consumes as many accounts as it declares, starting at its
+/// position in the parent, and checks its own constraints. Its fields are not
+/// visible to the parent's attributes, so `payer`, `close` and `token::*` must
+/// name fields of the same struct.
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// pub struct PoolAccounts<'info> {
+///     #[account(seeds = &[b"config"], program_id = crate::ID)]
+///     config: Account<'info, PoolConfig>,
+///     #[account(seeds = &[b"config"], program_id = crate::ID, bump)]
+///     config_bump: u8,
+/// }
+///
+/// #[derive(Accounts)]
+/// pub struct Deposit<'info> {
+///     #[account(signer)]
+///     user: AccountInfo<'info>,
+///     #[account(nested)]
+///     pool: PoolAccounts<'info>,
+/// }
+/// ```
+///
+/// ### Instruction arguments
+///
+/// `#[instruction(name: Type, ..)]` on the struct lists the leading parameters
+/// of the handlers taking it, in order. They are decoded from the instruction
+/// data before the accounts are parsed, so `seeds` (or any other attribute
+/// expression) can refer to them, e.g. for one PDA per item:
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// #[instruction(params: OpenPositionParams)]
+/// pub struct OpenPosition<'info> {
+///     #[account(signer, mut)]
+///     owner: AccountInfo<'info>,
+///     #[account(mut, init, payer = owner, seeds = &[b"position", params.position_id.to_le_bytes().as_ref()])]
+///     position: Account<'info, Position>,
+/// }
+///
+/// // in the `#[saturn_program]` module
+/// pub fn open_position<'info>(
+///     ctx: Context<'info, OpenPosition<'info>>,
+///     params: OpenPositionParams,
+/// ) -> Result<(), ProgramError> { .. }
+/// ```
+///
+/// `#[saturn_program]` passes the instruction data through
+/// `saturn_account_parser::Bumps::try_accounts_with_args`; `Accounts::try_accounts`
+/// has no instruction data and fails with `InvalidInstructionData` for such
+/// structs. `nested` fields receive the same instruction data.
+///
+/// ### Unchecked accounts
+///
+/// A bare `AccountInfo<'info>` field silently skips every check not written on
+/// it. `saturn_account_parser::UncheckedAccount<'info>` makes that choice
+/// explicit: the field must be named `unchecked_..` or carry a `/// CHECK:`
+/// doc comment explaining why no check is needed, so each escape hatch can be
+/// audited. Only the flags and constraints written on the field are checked.
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// pub struct Relay<'info> {
+///     /// CHECK: only forwarded to the oracle program, which validates it.
+///     #[account(mut)]
+///     feed: UncheckedAccount<'info>,
+///     unchecked_memo: UncheckedAccount<'info>,
+/// }
+/// ```
+///
+/// ### Lazily decoded accounts
+///
+/// `Account<'info, T>` fields are Borsh-decoded while parsing, whether the
+/// handler reads them or not. Declaring the field as
+/// `Lazy<Account<'info, T>>` still runs every key and flag check but defers
+/// decoding to the first `get()` / `get_mut()`, which return the decoded
+/// `Account`. Handlers touching few of many declared accounts only pay for
+/// those they use. `Lazy` fields cannot be combined with `init`,
+/// `init_if_needed`, `realloc` or `zero_copy`.
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// pub struct Swap<'info> {
+///     config: Lazy<Account<'info, Config>>,
+///     ..
+/// }
+///
+/// if ctx.accounts.config.get()?.paused { .. }
+/// ```
+///
+/// ### Accounts of several types
+///
+/// A field that may be one of several zero-copy account kinds, e.g. a router
+/// instruction acting on either a pool config or one of its shards, is
+/// declared as an enum deriving [`AccountEnum`] with one `AccountLoader`
+/// variant per kind. With `one_of`, the variant whose discriminator prefixes
+/// the account data is selected:
+///
+/// ```ignore
+/// #[derive(AccountEnum)]
+/// pub enum PoolAccount<'info> {
+///     Config(AccountLoader<'info, Config>),
+///     Shard(AccountLoader<'info, Shard>),
+/// }
+///
+/// #[derive(Accounts)]
+/// pub struct Touch<'info> {
+///     #[account(mut, one_of, owner = crate::ID)]
+///     target: PoolAccount<'info>,
+/// }
+///
+/// match &ctx.accounts.target {
+///     PoolAccount::Config(config) => config.load_mut()?.touched += 1,
+///     PoolAccount::Shard(shard) => shard.load_mut()?.touched += 1,
+/// }
+/// ```
+///
+/// ### Zero-copy slices
+///
+/// A `saturn_account_parser::ZeroCopySlice<'info, T>` field holds a variable
+/// number of `T`s laid out as `[T::DISCRIMINATOR][u32 len][T; capacity]`, e.g.
+/// an order book. Parsing checks its discriminator and length prefix; the
+/// handler then calls `push`, `pop`, `retain` and `iter` on it. Size it with
+/// `ZeroCopySlice::<T>::space(capacity)` and write its header with `init()`
+/// once created.
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// pub struct PlaceOrder<'info> {
+///     #[account(signer)]
+///     trader: AccountInfo<'info>,
+///     #[account(mut, owner = crate::ID)]
+///     orders: ZeroCopySlice<'info, Order>,
+/// }
+/// ```
+///
+/// ### Custom errors
+///
+/// A failing constraint returns the matching `saturn_account_parser` error
+/// code. Following the constraint with `@ <error>` returns the program's own
+/// error instead, converted with `Into<ProgramError>` (e.g. a
+/// `#[saturn_error]` enum), so clients can tell the failures apart:
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// pub struct SetFee<'info> {
+///     #[account(signer @ MyError::MissingAdminSignature, address = ADMIN @ MyError::NotAdmin)]
+///     admin: AccountInfo<'info>,
+///     #[account(mut @ MyError::ConfigNotWritable, seeds = &[b"config"], program_id = crate::ID)]
+///     config: Account<'info, Config>,
+/// }
+/// ```
+///
+/// `@ <error>` is accepted after `signer`, `mut`, `address`, `address_in`,
+/// `owner`, `interface`, `authority`, `executable`, `seeds`, `bump = <expr>`,
+/// `max_len`, `rent_exempt`, `token::mint`, `token::authority`,
+/// `mint::authority` and `mint::decimals`.
+///
+/// ### Duplicate accounts
+///
+/// By default nothing stops a caller from passing the same account for two
+/// fields, e.g. `from` and `to` of a transfer. `#[accounts(deny_duplicates)]`
+/// on the struct rejects the instruction with `ErrorCode::DuplicateAccount`
+/// when two accounts parsed by the struct share a key, counting every element
+/// of slices and shard vectors and the accounts of `nested` structs. The
+/// program id standing in for an omitted optional account is not compared.
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// #[accounts(deny_duplicates)]
+/// pub struct Transfer<'info> {
+///     #[account(signer, mut)]
+///     from: AccountInfo<'info>,
+///     #[account(mut)]
+///     to: AccountInfo<'info>,
+/// }
+/// ```
+///
+/// ### Runtime values
+///
+/// A `saturn_account_parser::Sysvar<T>` field is read from the runtime while
+/// the struct is parsed and takes no account of the instruction: `Clock`,
+/// `Rent` and `BitcoinBlockHeight` (from `saturn_account_parser::sysvar`).
+/// Handlers then read `ctx.accounts.clock.unix_timestamp` rather than calling
+/// syscalls themselves. Such fields take no `#[account(..)]` attribute.
+///
+/// ```ignore
+/// #[derive(Accounts)]
+/// pub struct Claim<'info> {
+///     #[account(signer)]
+///     user: AccountInfo<'info>,
+///     clock: Sysvar<Clock>,
+///     height: Sysvar<BitcoinBlockHeight>,
+/// }
+/// ```
+///
 /// ### Ignored helper fields
 ///
 /// Marker fields such as `PhantomData<&'info ()>` are ignored by the macro,
@@ -91,12 +354,56 @@ mod validator;
 /// ## Validation rules
 ///
 /// * `address` **cannot** be combined with `seeds`.
+/// * `UncheckedAccount` fields must be named `unchecked_..` or documented with
+///   `/// CHECK:`, and cannot be combined with `init`, `init_if_needed`,
+///   `realloc` or `zero_copy`.
+/// * `max_len` is only valid on `len = ..` slices and `shards` vectors.
+/// * `layout::size` requires `zero_copy`.
+/// * `Sysvar` fields take no `#[account(..)]` attribute and cannot be
+///   `Option<..>`.
+/// * `discriminator` requires `zero_copy` on a single account field.
+/// * `migrate_from` requires `mut` and `zero_copy` on a single account field,
+///   and cannot be combined with `init`, `init_if_needed` or `realloc`.
+/// * `funding` requires `init` or `init_if_needed`; `anchor` requires `init` on
+///   a single account field.
+/// * `@ <error>` only follows the constraints listed under
+///   [Custom errors](#custom-errors); `owner @ ..` cannot be combined with
+///   `init` / `init_if_needed`, and `bump @ ..` needs `bump = <expr>`.
+/// * `bump = <expr>` on an account requires `seeds` on a single account field
+///   and **cannot** be combined with `init` or `init_if_needed`.
+/// * `allow_pda` requires `authority`, which **cannot** be combined with
+///   `signer`, `address`, `seeds`, `init`, `init_if_needed` or `shards`.
+/// * `one_of` is only valid on single fields and **cannot** be combined with
+///   `init`, `init_if_needed`, `realloc`, `zero_copy` or `of`.
+/// * `Lazy<..>` fields **cannot** be combined with `init`, `init_if_needed`,
+///   `realloc` or `zero_copy`.
+/// * `interface` **cannot** be combined with `owner`, `init`, `init_if_needed`
+///   or `shards`.
+/// * `address_in` **cannot** be combined with `address`, `seeds`, `init`,
+///   `init_if_needed` or `shards`.
+/// * `owner` combined with `init` / `init_if_needed` requires an
+///   `AccountInfo<'info>` field, whose data is left to the owner program, and
+///   `owner` cannot be used on `shards` vectors.
+/// * `init` / `init_if_needed` on an `AccountInfo<'info>` field, or on a
+///   `Vec<AccountInfo<'info>>` shard vector, **requires** `space`.
 /// * `seeds` **requires** `program_id`.
+/// * `seeds_program` **requires** `seeds` and cannot be combined with `init`,
+///   `init_if_needed`, `realloc` or `shards`.
 /// * `init`, `init_if_needed`, and `realloc` are **mutually exclusive**.
 /// * `realloc` **requires** `space`; `init` & `init_if_needed` accept it
 ///   optionally.
 /// * `payer` must reference a **signer** field.
 /// * The `signer` flag is invalid on `shards` vectors.
+/// * `executable` **cannot** be combined with `init`, `init_if_needed`,
+///   `realloc` or `shards`.
+/// * `token::*` constraints require a `TokenAccount` field and `mint::*` ones a
+///   `Mint` field; neither wrapper accepts `init`, `realloc` or `zero_copy`.
+/// * `Option<..>` fields must be single accounts without `init`,
+///   `init_if_needed`, `realloc` or `close`, and cannot be referenced by
+///   `payer`, `close` or `token::*` / `mint::*` constraints.
+/// * `nested` **cannot** be combined with any other flag.
+/// * `close` is only valid on single `mut` accounts and must name another
+///   single `mut` field.
 ///
 /// Violations are reported at **compile-time** whenever possible; otherwise they
 /// surface as runtime errors.
@@ -108,7 +415,47 @@ mod validator;
 ///   elements and phantom fields).
 /// * `fn read<'a>(accs: &'a [AccountInfo<'a>]) -> Result<Self>` – convenience
 ///   wrapper around `Accounts::try_from`.
-#[proc_macro_derive(Accounts, attributes(account))]
+/// * `fn exit(&self)` – overrides `Accounts::exit` to close the `close = ..`
+///   fields; `#[saturn_program]` calls it once the handler returned.
+/// * `struct <Struct>Bumps` – the bump of every single PDA field (`u8`, or
+///   `Option<u8>` when the field is optional), of every `bump` field, and the
+///   `<Nested>Bumps` of every `nested` field. It implements the
+///   `saturn_account_parser::Bumps` trait and `#[saturn_program]` hands it to
+///   the handler as `ctx.bumps`, so seeds need not be re-derived:
+///
+/// ```ignore
+/// let bump = ctx.bumps.vault;
+/// let signer_seeds: &[&[u8]] = &[b"vault", &[bump]];
+/// ```
+///
+/// * `ToAccountInfos` and `ToAccountMetas` impls listing the accounts in
+///   declaration order with their `signer` / `mut` flags, so the struct of
+///   another program's instruction can be built from the caller's accounts and
+///   passed to it with `saturn_account_parser::cpi`:
+///
+/// ```ignore
+/// let accounts = VaultDeposit { authority: user.clone(), vault: vault.clone() };
+/// let ctx = CpiContext::new_with_signer(vault_program.clone(), accounts, &[seeds]);
+/// cpi::invoke_signed(ctx, data)?;
+/// ```
+///
+/// * With the `client` feature, `fn metas(..) -> Vec<AccountMeta>` – the
+///   accounts in declaration order with their `signer` / `mut` flags (`init`,
+///   `realloc` and `close` fields are writable), for off-chain code building
+///   the instruction. It takes the `#[instruction(..)]` arguments, then one
+///   `Pubkey` per single account (`Option<Pubkey>` when optional), a
+///   `&[Pubkey]` per slice or shard vector and the `metas(..)` of each
+///   `nested` struct. `address` and `seeds` accounts are filled in, deriving
+///   the PDAs with the same seeds as on-chain:
+///
+/// ```ignore
+/// let metas = OpenPosition::metas(params, owner_key);
+/// ```
+///
+/// * With the `idl-build` feature, a hidden `fn __idl_accounts() -> String`
+///   describing the accounts as JSON for the IDL of `#[saturn_program]`
+///   (see `saturn_account_parser::idl`).
+#[proc_macro_derive(Accounts, attributes(account, accounts, instruction))]
 pub fn derive_accounts(input: TokenStream) -> TokenStream {
     // Parse the struct definition.
     let input: DeriveInput = parse_macro_input!(input);
@@ -147,12 +494,68 @@ pub fn derive_accounts(input: TokenStream) -> TokenStream {
         return e.to_compile_error().into();
     }
 
-    match codegen::generate(struct_ident, &input.generics, &parsed_fields) {
+    let instruction_args = match parser::parse_instruction_args(&input.attrs) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let accounts_cfg = match parser::parse_accounts_cfg(&input.attrs) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    match codegen::generate(
+        struct_ident,
+        &input.generics,
+        &parsed_fields,
+        &instruction_args,
+        &accounts_cfg,
+    ) {
         Ok(ts) => return TokenStream::from(ts),
         Err(e) => return e.to_compile_error().into(),
     }
 }
 
+/// # `AccountEnum` derive macro
+///
+/// Implements `saturn_account_parser::AccountEnum` for an enum with an
+/// `'info` lifetime whose variants each wrap one account type (currently
+/// `AccountLoader<'info, T>`), so `#[account(one_of)]` fields of the enum can
+/// pick their variant from the account's discriminator. Variants are tried in
+/// declaration order. The enum also implements `AsRef<AccountInfo<'info>>`.
+///
+/// See "Accounts of several types" in [`Accounts`].
+#[proc_macro_derive(AccountEnum)]
+pub fn derive_account_enum(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    account_enum::derive(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// # `Event` derive macro
+///
+/// Implements `saturn_account_parser::event::Event` for a struct that also
+/// derives `BorshSerialize` and `BorshDeserialize`, so it can be logged with
+/// `saturn_account_parser::emit!` and decoded by clients with
+/// `saturn_account_parser::event::decode_events`. The discriminator is
+/// `sha256("event:<Name>")[0..8]`, as in Anchor.
+///
+/// ```ignore
+/// #[derive(BorshSerialize, BorshDeserialize, Event)]
+/// pub struct Deposited {
+///     pub user: Pubkey,
+///     pub amount: u64,
+/// }
+///
+/// emit!(Deposited { user, amount });
+/// ```
+#[proc_macro_derive(Event)]
+pub fn derive_event(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    event::derive(&input).into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,6 +17,44 @@ pub enum FieldKind {
     Phantom,
     /// A bump value (u8) derived from PDA seeds. Does **not** consume an account.
     Bump,
+    /// A struct that itself derives `Accounts` (`#[account(nested)]`), consuming
+    /// its own accounts in place.
+    Nested,
+    /// A `Sysvar<T>` read from the runtime. Does **not** consume an account.
+    Sysvar,
+}
+
+/// How an `Option<..>` field detects that its account was omitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionalMode {
+    /// The slot holds the program id (`crate::ID`) in place of the account.
+    Sentinel,
+    /// The slot is missing at the end of the account slice.
+    Trailing,
+}
+
+/// `allow_pda(seeds = .., program_id = ..)` of an `authority` field: the PDA
+/// accepted in place of a signer.
+#[derive(Debug, Clone)]
+pub struct AllowPda {
+    pub seeds: Expr,
+    /// Program the PDA is derived from (`crate::ID` unless given).
+    pub program_id: Expr,
+}
+
+/// A handler parameter declared with `#[instruction(name: Type, ..)]` on the
+/// struct, made available to the field attributes (e.g. `seeds`).
+#[derive(Debug, Clone)]
+pub struct InstructionArg {
+    pub ident: Ident,
+    pub ty: Type,
+}
+
+/// Struct-level options set with `#[accounts(..)]`.
+#[derive(Debug, Clone, Default)]
+pub struct AccountsCfg {
+    /// `deny_duplicates`: no two accounts of the struct may share a key.
+    pub deny_duplicates: bool,
 }
 
 /// Configuration collected for every field while parsing the user-declared struct.
@@ -29,19 +67,77 @@ pub struct FieldCfg {
     pub is_signer: Option<bool>,
     pub is_writable: Option<bool>,
     pub address: Option<Expr>,
+    /// `address_in = [..]`: keys the account's key must be one of.
+    pub address_in: Option<Expr>,
     pub seeds: Option<Expr>,
+    /// `seeds_program = <expr>`: program the `seeds` PDA is derived from when
+    /// it differs from `program_id`.
+    pub seeds_program: Option<Expr>,
+    /// `bump = <expr>`: on a `bump` field, a provided bump that must be the
+    /// canonical one; on a `seeds` account, its known (e.g. stored) bump,
+    /// verified without searching for the canonical bump.
+    pub bump: Option<Expr>,
     pub program_id: Option<Expr>,
     pub payer: Option<Expr>,
     pub is_shards: bool,
+    /// `max_len = <expr>`: upper bound of the runtime `len` of a slice or
+    /// shard vector.
+    pub max_len: Option<Expr>,
     pub kind: FieldKind,
     pub is_zero_copy: bool,
     pub is_init: bool,
     pub is_realloc: bool,
+    /// `realloc::zero = <expr>`: whether `realloc` zeroes the added bytes
+    /// (`true` when absent).
+    pub realloc_zero: Option<Expr>,
     pub is_init_if_needed: bool,
     pub base_ty: Type,
     pub space: Option<Expr>,
+    /// `funding = <expr>`: lamports an `init` / `init_if_needed` account is
+    /// created with, instead of the minimum balance for its `space`.
+    pub funding: Option<Expr>,
+    /// `anchor = <expr>`: `UtxoMeta` an `init` account is created anchored to.
+    pub anchor: Option<Expr>,
     /// Optional type specified via `of = MyShard` inside the `#[account(shards)]` attribute.
     pub of_type: Option<Type>,
+    /// `discriminator = <bytes>` of a `zero_copy` field: prefix of the data in
+    /// place of the type's `Discriminator` (`b""` for `discriminator = none`).
+    pub discriminator: Option<Expr>,
+    /// `migrate_from = <version>` of a `zero_copy` field: oldest layout version
+    /// of its `Migratable` type accepted and upgraded while parsing.
+    pub migrate_from: Option<Expr>,
+    /// `layout::size = <const expr>`: expected `size_of` of a `zero_copy`
+    /// field's `of` type, asserted at compile time.
+    pub layout_size: Option<Expr>,
     /// Optional explicit owner (`owner = <expr>`) that the account must be owned by.
     pub owner: Option<Expr>,
+    /// `interface = [..]`: programs one of which must own the account.
+    pub interface: Option<Expr>,
+    /// Optional destination (`close = <field>`) receiving the balance when the
+    /// account is closed after the handler ran.
+    pub close: Option<Expr>,
+    /// `rent_exempt` – the account must hold the minimum balance for its data length.
+    pub is_rent_exempt: bool,
+    /// `one_of` – the field is a `#[derive(AccountEnum)]` enum whose variant is
+    /// chosen by the account's discriminator.
+    pub is_one_of: bool,
+    /// `authority` – the account must sign, or be the `allow_pda` PDA.
+    pub is_authority: bool,
+    pub allow_pda: Option<AllowPda>,
+    /// `executable` – the account must be an executable program account.
+    pub is_executable: bool,
+    /// `token::mint = <field>` – mint a `TokenAccount` field must hold.
+    pub token_mint: Option<Expr>,
+    /// `token::authority = <field>` – owner a `TokenAccount` field must have.
+    pub token_authority: Option<Expr>,
+    /// `mint::authority = <field>` – mint authority a `Mint` field must have.
+    pub mint_authority: Option<Expr>,
+    /// `mint::decimals = <expr>` – decimals a `Mint` field must have.
+    pub mint_decimals: Option<Expr>,
+    /// Set for `Option<..>` fields; `base_ty` then holds the inner type.
+    pub optional: Option<OptionalMode>,
+    /// `<constraint> @ <error>` overrides: the program error returned instead
+    /// of the parser's `ErrorCode` when that constraint (`signer`, `mut`,
+    /// `token::mint`, ..) fails.
+    pub constraint_errors: Vec<(String, Expr)>,
 }
@@ -1,8 +1,8 @@
-use crate::model::{FieldCfg, FieldKind};
+use crate::model::{AccountsCfg, FieldCfg, FieldKind, InstructionArg, OptionalMode};
 mod attr_ast;
 mod defaults;
 mod helpers;
-use syn::{spanned::Spanned, Expr, Field};
+use syn::{punctuated::Punctuated, spanned::Spanned, Attribute, Expr, Field, Token};
 
 /// Parse the `fields` of a struct annotated with `#[derive(Accounts)]` and
 /// return a vector with one [`FieldCfg`] per field.
@@ -28,19 +28,42 @@ pub fn parse_fields(
             is_signer: None,
             is_writable: None,
             address: None,
+            address_in: None,
             seeds: None,
+            seeds_program: None,
+            bump: None,
             program_id: None,
             payer: None,
             is_shards: false,
+            max_len: None,
             kind: FieldKind::Single,
             is_zero_copy: false,
             is_init: false,
             is_realloc: false,
+            realloc_zero: None,
             is_init_if_needed: false,
             base_ty: syn::parse_quote! { () },
             space: None,
+            funding: None,
+            anchor: None,
             of_type: None,
+            discriminator: None,
+            migrate_from: None,
+            layout_size: None,
             owner: None,
+            interface: None,
+            close: None,
+            is_rent_exempt: false,
+            is_one_of: false,
+            is_authority: false,
+            allow_pda: None,
+            is_executable: false,
+            token_mint: None,
+            token_authority: None,
+            mint_authority: None,
+            mint_decimals: None,
+            optional: None,
+            constraint_errors: Vec::new(),
         };
 
         // Determine the underlying base type (strip reference if present)
@@ -66,6 +89,31 @@ pub fn parse_fields(
             raw_attr.apply_to_cfg(&mut cfg);
         }
 
+        // Optional accounts: parse `Option<T>` as `T` and remember how an omitted
+        // account is recognised (program id sentinel unless configured otherwise).
+        if let Some(inner_ty) = helpers::extract_option_inner(&cfg.base_ty).cloned() {
+            cfg.base_ty = inner_ty;
+            cfg.optional.get_or_insert(OptionalMode::Sentinel);
+        } else if cfg.optional.is_some() {
+            return Err(syn::Error::new(
+                field.span(),
+                "`optional = ...` can only be used on `Option<..>` fields",
+            ));
+        }
+
+        // `Sysvar<T>` fields are read from the runtime, not from an account.
+        if crate::codegen::utils::is_path_named(&cfg.base_ty, "Sysvar") {
+            if has_account_attr || cfg.optional.is_some() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "`Sysvar` fields are read from the runtime: they take no `#[account(..)]` attribute and cannot be optional",
+                ));
+            }
+            cfg.kind = FieldKind::Sysvar;
+            parsed_fields.push(cfg);
+            continue;
+        }
+
         // We'll need the optional `len = ...` expression later for kind detection.
         let slice_len_expr: Option<Expr> = raw_attr_opt.as_ref().and_then(|r| r.len.clone());
 
@@ -85,7 +133,10 @@ pub fn parse_fields(
         }
 
         // ===== Phase 3: Determine collection kind (fixed slice, shards, …) =====
-        if !matches!(cfg.kind, FieldKind::Phantom | FieldKind::Bump) {
+        if !matches!(
+            cfg.kind,
+            FieldKind::Phantom | FieldKind::Bump | FieldKind::Nested
+        ) {
             cfg.kind = helpers::detect_field_kind(
                 &cfg.base_ty,
                 cfg.is_shards,
@@ -97,6 +148,17 @@ pub fn parse_fields(
         // ===== Phase 4: Apply implicit defaults =====
         defaults::fill_defaults(&mut cfg);
 
+        // `UncheckedAccount` escape hatches must be visible when auditing.
+        if crate::codegen::utils::is_path_named(&cfg.base_ty, "UncheckedAccount")
+            && !cfg.ident.to_string().starts_with("unchecked_")
+            && !helpers::has_check_doc(&field.attrs)
+        {
+            return Err(syn::Error::new(
+                field.span(),
+                "`UncheckedAccount` field must be named `unchecked_..` or documented with `/// CHECK: <why no checks are needed>`",
+            ));
+        }
+
         // ===== Phase 5: Per-field syntactic validation =====
         crate::validator::syntax::validate_field(&cfg, field.span())?;
 
@@ -106,6 +168,61 @@ pub fn parse_fields(
     Ok(parsed_fields)
 }
 
+/// Parse the struct-level `#[instruction(name: Type, ..)]` attribute, listing
+/// the leading parameters of the handlers using the struct. Returns an empty
+/// list when the attribute is absent.
+pub fn parse_instruction_args(attrs: &[Attribute]) -> Result<Vec<InstructionArg>, syn::Error> {
+    let Some(attr) = attrs.iter().find(|a| a.path().is_ident("instruction")) else {
+        return Ok(Vec::new());
+    };
+
+    let args = attr.parse_args_with(|input: syn::parse::ParseStream| {
+        Punctuated::<InstructionArgSyntax, Token![,]>::parse_terminated(input)
+    })?;
+    Ok(args
+        .into_iter()
+        .map(|arg| InstructionArg {
+            ident: arg.ident,
+            ty: arg.ty,
+        })
+        .collect())
+}
+
+/// Parse the struct-level `#[accounts(..)]` options. Returns the defaults when
+/// the attribute is absent.
+pub fn parse_accounts_cfg(attrs: &[Attribute]) -> Result<AccountsCfg, syn::Error> {
+    let mut accounts_cfg = AccountsCfg::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("accounts")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("deny_duplicates") {
+                if accounts_cfg.deny_duplicates {
+                    return Err(meta.error("duplicate `deny_duplicates` flag"));
+                }
+                accounts_cfg.deny_duplicates = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown option in #[accounts(..)] attribute"))
+            }
+        })?;
+    }
+    Ok(accounts_cfg)
+}
+
+/// `name: Type` inside `#[instruction(..)]`.
+struct InstructionArgSyntax {
+    ident: syn::Ident,
+    ty: syn::Type,
+}
+
+impl syn::parse::Parse for InstructionArgSyntax {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        Ok(Self { ident, ty })
+    }
+}
+
 /// Returns true if `ty` is exactly `core::marker::PhantomData<_>` or `std::marker::PhantomData<_>`.
 /// This mirrors Anchor's behaviour where only those two canonical paths are treated as zero-sized
 /// marker fields and therefore ignored by the macro. Any other type that merely ends with
@@ -1,5 +1,5 @@
 use syn::{spanned::Spanned, Expr, Type, Attribute};
-use crate::model::{FieldCfg, FieldKind};
+use crate::model::{AllowPda, FieldCfg, FieldKind, OptionalMode};
 
 /// Internal representation of the flags/values found inside an `#[account(..)]` attribute.
 ///
@@ -17,6 +17,7 @@ pub struct RawAccountAttr {
     pub program_id: Option<Expr>,
     pub payer: Option<Expr>,
     pub owner: Option<Expr>,
+    pub close: Option<Expr>,
     pub is_shards: bool,
     pub of_type: Option<Type>,
     pub zero_copy: bool,
@@ -24,7 +25,31 @@ pub struct RawAccountAttr {
     pub init_if_needed: bool,
     pub realloc: bool,
     pub space: Option<Expr>,
+    pub funding: Option<Expr>,
+    pub anchor: Option<Expr>,
     pub bump: bool,
+    pub bump_value: Option<Expr>,
+    pub rent_exempt: bool,
+    pub executable: bool,
+    pub token_mint: Option<Expr>,
+    pub token_authority: Option<Expr>,
+    pub mint_authority: Option<Expr>,
+    pub mint_decimals: Option<Expr>,
+    pub optional: Option<OptionalMode>,
+    pub nested: bool,
+    pub realloc_zero: Option<Expr>,
+    pub seeds_program: Option<Expr>,
+    pub address_in: Option<Expr>,
+    pub max_len: Option<Expr>,
+    pub interface: Option<Expr>,
+    pub layout_size: Option<Expr>,
+    pub discriminator: Option<Expr>,
+    pub migrate_from: Option<Expr>,
+    pub one_of: bool,
+    pub authority: bool,
+    pub allow_pda: Option<AllowPda>,
+    /// `<constraint> @ <error>` overrides, keyed by [`constraint_name`].
+    pub constraint_errors: Vec<(String, Expr)>,
 }
 
 impl RawAccountAttr {
@@ -54,6 +79,21 @@ impl RawAccountAttr {
                 }
                 let expr: Expr = meta.value()?.parse()?;
                 raw.address = Some(expr);
+            } else if meta.path.is_ident("address_in") {
+                if raw.address_in.is_some() {
+                    return Err(meta.error("duplicate `address_in` attribute"));
+                }
+                raw.address_in = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("interface") {
+                if raw.interface.is_some() {
+                    return Err(meta.error("duplicate `interface` attribute"));
+                }
+                raw.interface = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("max_len") {
+                if raw.max_len.is_some() {
+                    return Err(meta.error("duplicate `max_len` attribute"));
+                }
+                raw.max_len = Some(meta.value()?.parse()?);
             } else if meta.path.is_ident("len") {
                 if raw.len.is_some() {
                     return Err(meta.error("duplicate `len` attribute"));
@@ -84,6 +124,12 @@ impl RawAccountAttr {
                 }
                 let expr: Expr = meta.value()?.parse()?;
                 raw.owner = Some(expr);
+            } else if meta.path.is_ident("close") {
+                if raw.close.is_some() {
+                    return Err(meta.error("duplicate `close` attribute"));
+                }
+                let expr: Expr = meta.value()?.parse()?;
+                raw.close = Some(expr);
             } else if meta.path.is_ident("shards") {
                 if raw.is_shards {
                     return Err(meta.error("duplicate `shards` flag"));
@@ -126,9 +172,149 @@ impl RawAccountAttr {
                     return Err(meta.error("duplicate `bump` flag"));
                 }
                 raw.bump = true;
+                if meta.input.peek(syn::Token![=]) {
+                    raw.bump_value = Some(meta.value()?.parse()?);
+                }
+            } else if meta.path.is_ident("rent_exempt") {
+                if raw.rent_exempt {
+                    return Err(meta.error("duplicate `rent_exempt` flag"));
+                }
+                raw.rent_exempt = true;
+            } else if meta.path.is_ident("seeds_program") {
+                if raw.seeds_program.is_some() {
+                    return Err(meta.error("duplicate `seeds_program` attribute"));
+                }
+                raw.seeds_program = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("discriminator") {
+                if raw.discriminator.is_some() {
+                    return Err(meta.error("duplicate `discriminator` attribute"));
+                }
+                let expr: Expr = meta.value()?.parse()?;
+                // `none`: the data starts at the first byte.
+                let is_none = matches!(&expr, Expr::Path(p) if p.path.is_ident("none"));
+                raw.discriminator = Some(if is_none {
+                    syn::parse_quote! { b"" }
+                } else {
+                    expr
+                });
+            } else if meta.path.is_ident("migrate_from") {
+                if raw.migrate_from.is_some() {
+                    return Err(meta.error("duplicate `migrate_from` attribute"));
+                }
+                raw.migrate_from = Some(meta.value()?.parse()?);
+            } else if is_namespaced(&meta.path, "layout", "size") {
+                if raw.layout_size.is_some() {
+                    return Err(meta.error("duplicate `layout::size` attribute"));
+                }
+                raw.layout_size = Some(meta.value()?.parse()?);
+            } else if is_namespaced(&meta.path, "realloc", "zero") {
+                if raw.realloc_zero.is_some() {
+                    return Err(meta.error("duplicate `realloc::zero` attribute"));
+                }
+                raw.realloc_zero = Some(meta.value()?.parse()?);
+            } else if is_namespaced(&meta.path, "token", "mint") {
+                if raw.token_mint.is_some() {
+                    return Err(meta.error("duplicate `token::mint` attribute"));
+                }
+                raw.token_mint = Some(meta.value()?.parse()?);
+            } else if is_namespaced(&meta.path, "token", "authority") {
+                if raw.token_authority.is_some() {
+                    return Err(meta.error("duplicate `token::authority` attribute"));
+                }
+                raw.token_authority = Some(meta.value()?.parse()?);
+            } else if is_namespaced(&meta.path, "mint", "authority") {
+                if raw.mint_authority.is_some() {
+                    return Err(meta.error("duplicate `mint::authority` attribute"));
+                }
+                raw.mint_authority = Some(meta.value()?.parse()?);
+            } else if is_namespaced(&meta.path, "mint", "decimals") {
+                if raw.mint_decimals.is_some() {
+                    return Err(meta.error("duplicate `mint::decimals` attribute"));
+                }
+                raw.mint_decimals = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("optional") {
+                if raw.optional.is_some() {
+                    return Err(meta.error("duplicate `optional` attribute"));
+                }
+                let mode: syn::Ident = meta.value()?.parse()?;
+                raw.optional = Some(if mode == "sentinel" {
+                    OptionalMode::Sentinel
+                } else if mode == "trailing" {
+                    OptionalMode::Trailing
+                } else {
+                    return Err(syn::Error::new(
+                        mode.span(),
+                        "expected `optional = sentinel` or `optional = trailing`",
+                    ));
+                });
+            } else if meta.path.is_ident("funding") {
+                if raw.funding.is_some() {
+                    return Err(meta.error("duplicate `funding` attribute"));
+                }
+                raw.funding = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("anchor") {
+                if raw.anchor.is_some() {
+                    return Err(meta.error("duplicate `anchor` attribute"));
+                }
+                raw.anchor = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("authority") {
+                if raw.authority {
+                    return Err(meta.error("duplicate `authority` flag"));
+                }
+                raw.authority = true;
+            } else if meta.path.is_ident("allow_pda") {
+                if raw.allow_pda.is_some() {
+                    return Err(meta.error("duplicate `allow_pda` attribute"));
+                }
+                let mut seeds: Option<Expr> = None;
+                let mut program_id: Option<Expr> = None;
+                meta.parse_nested_meta(|inner| {
+                    if inner.path.is_ident("seeds") && seeds.is_none() {
+                        seeds = Some(inner.value()?.parse()?);
+                    } else if inner.path.is_ident("program_id") && program_id.is_none() {
+                        program_id = Some(inner.value()?.parse()?);
+                    } else {
+                        return Err(inner
+                            .error("expected `allow_pda(seeds = <expr>, program_id = <expr>)`"));
+                    }
+                    Ok(())
+                })?;
+                let seeds =
+                    seeds.ok_or_else(|| meta.error("`allow_pda(..)` requires `seeds = ..`"))?;
+                raw.allow_pda = Some(AllowPda {
+                    seeds,
+                    program_id: program_id.unwrap_or_else(|| syn::parse_quote! { crate::ID }),
+                });
+            } else if meta.path.is_ident("one_of") {
+                if raw.one_of {
+                    return Err(meta.error("duplicate `one_of` flag"));
+                }
+                raw.one_of = true;
+            } else if meta.path.is_ident("nested") {
+                if raw.nested {
+                    return Err(meta.error("duplicate `nested` flag"));
+                }
+                raw.nested = true;
+            } else if meta.path.is_ident("executable") {
+                if raw.executable {
+                    return Err(meta.error("duplicate `executable` flag"));
+                }
+                raw.executable = true;
             } else {
                 return Err(meta.error("Unknown flag in #[account] attribute"));
             }
+
+            // `<constraint> @ <error>`: program error returned when it fails.
+            if meta.input.peek(syn::Token![@]) {
+                let constraint = constraint_name(&meta.path);
+                if !CONSTRAINTS.contains(&constraint.as_str()) {
+                    return Err(meta.error(format!(
+                        "`@ <error>` is not supported on `{constraint}`; only constraints that are checked can return a custom error"
+                    )));
+                }
+                meta.input.parse::<syn::Token![@]>()?;
+                raw.constraint_errors.push((constraint, meta.input.parse()?));
+            }
             Ok(())
         })?;
 
@@ -154,21 +340,94 @@ impl RawAccountAttr {
 
         // Direct option copies
         cfg.address = self.address.clone();
+        cfg.address_in = self.address_in.clone();
+        cfg.max_len = self.max_len.clone();
         cfg.seeds = self.seeds.clone();
         cfg.program_id = self.program_id.clone();
         cfg.payer = self.payer.clone();
         cfg.owner = self.owner.clone();
+        cfg.interface = self.interface.clone();
+        cfg.close = self.close.clone();
         cfg.is_shards = self.is_shards;
         cfg.of_type = self.of_type.clone();
         cfg.is_zero_copy = self.zero_copy;
+        cfg.layout_size = self.layout_size.clone();
+        cfg.discriminator = self.discriminator.clone();
+        cfg.migrate_from = self.migrate_from.clone();
         cfg.is_init = self.init;
         cfg.is_init_if_needed = self.init_if_needed;
         cfg.is_realloc = self.realloc;
+        cfg.seeds_program = self.seeds_program.clone();
+        cfg.realloc_zero = self.realloc_zero.clone();
         cfg.space = self.space.clone();
+        cfg.funding = self.funding.clone();
+        cfg.anchor = self.anchor.clone();
+        cfg.is_rent_exempt = self.rent_exempt;
+        cfg.is_one_of = self.one_of;
+        cfg.is_authority = self.authority;
+        cfg.allow_pda = self.allow_pda.clone();
+        cfg.is_executable = self.executable;
+        cfg.token_mint = self.token_mint.clone();
+        cfg.token_authority = self.token_authority.clone();
+        cfg.mint_authority = self.mint_authority.clone();
+        cfg.mint_decimals = self.mint_decimals.clone();
+        cfg.optional = self.optional;
+        cfg.constraint_errors = self.constraint_errors.clone();
 
-        // Special-case bump placeholder.
-        if self.bump {
+        // Special-case bump placeholder. `bump = <expr>` on an account field
+        // is the known bump of its `seeds` instead.
+        cfg.bump = self.bump_value.clone();
+        if self.bump
+            && (self.bump_value.is_none() || crate::validator::bump::is_bump_type(&cfg.base_ty))
+        {
             cfg.kind = FieldKind::Bump;
         }
+        if self.nested {
+            cfg.kind = FieldKind::Nested;
+        }
     }
-} 
\ No newline at end of file
+}
+
+/// Constraints that can be followed by `@ <error>`.
+const CONSTRAINTS: &[&str] = &[
+    "signer",
+    "mut",
+    "address",
+    "address_in",
+    "owner",
+    "interface",
+    "authority",
+    "executable",
+    "seeds",
+    "bump",
+    "max_len",
+    "rent_exempt",
+    "token::mint",
+    "token::authority",
+    "mint::authority",
+    "mint::decimals",
+];
+
+/// Name of the constraint `path` sets, as keyed in
+/// [`FieldCfg::constraint_errors`] (`writable` is spelled `mut`).
+fn constraint_name(path: &syn::Path) -> String {
+    let name = path
+        .segments
+        .iter()
+        .map(|seg| seg.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    if name == "writable" {
+        "mut".to_string()
+    } else {
+        name
+    }
+}
+
+/// `true` when `path` is exactly `namespace::key` (e.g. `token::mint`).
+fn is_namespaced(path: &syn::Path, namespace: &str, key: &str) -> bool {
+    path.leading_colon.is_none()
+        && path.segments.len() == 2
+        && path.segments[0].ident == namespace
+        && path.segments[1].ident == key
+}
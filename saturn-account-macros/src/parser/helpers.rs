@@ -56,6 +56,25 @@ pub(super) fn detect_field_kind(
     Ok(FieldKind::Single)
 }
 
+/// Attempts to extract the inner type `T` from `Option<T>` (last path segment
+/// `Option`, so `core::option::Option<T>` works as well).
+pub(super) fn extract_option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    let seg = path.segments.last()?;
+    if seg.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &seg.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(inner_ty) => Some(inner_ty),
+        _ => None,
+    })
+}
+
 /// Attempts to extract the element type `T` from `Vec<T>`.
 /// Returns `Some(&Type)` if `ty` is recognised as a `Vec<T>`; otherwise `None`.
 fn extract_vec_elem(ty: &Type) -> Option<&Type> {
@@ -80,4 +99,22 @@ fn extract_vec_elem(ty: &Type) -> Option<&Type> {
         }
         _ => None,
     }
-} 
\ No newline at end of file
+} 
+
+/// Returns `true` if `attrs` hold a `/// CHECK: ..` doc comment explaining why
+/// an `UncheckedAccount` field needs no validation.
+pub(super) fn has_check_doc(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let syn::Meta::NameValue(name_value) = &attr.meta else {
+            return false;
+        };
+        if !name_value.path.is_ident("doc") {
+            return false;
+        }
+        matches!(
+            &name_value.value,
+            Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(doc), .. })
+                if doc.value().trim_start().starts_with("CHECK:")
+        )
+    })
+}
@@ -364,4 +364,386 @@ mod parser_tests {
         let cfgs = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
         assert!(matches!(cfgs[0].kind, model::FieldKind::FixedSlice(..)));
     }
+
+    /// 1.xx – `owner` on an account created by the macro is rejected.
+    #[test]
+    fn parser_rejects_owner_with_init_on_typed_account() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: Account<'info, u64>,
+                #[account(mut, init, payer = payer, program_id = arch_program::pubkey::Pubkey::default(), owner = arch_program::pubkey::Pubkey::default())]
+                acc: Account<'info, u64>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains(
+            "`owner` with `init` or `init_if_needed` requires an `AccountInfo<'info>` field"
+        ));
+    }
+
+    /// 1.xx – `#[instruction(..)]` lists typed handler parameters.
+    #[test]
+    fn parser_reads_instruction_args() {
+        let di: DeriveInput = parse_quote! {
+            #[instruction(position_id: u64, params: OpenParams)]
+            struct Accs<'info> {
+                caller: AccountInfo<'info>,
+            }
+        };
+        let args = parser::parse_instruction_args(&di.attrs).expect("args ok");
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0].ident, "position_id");
+        assert_eq!(args[1].ident, "params");
+
+        let di: DeriveInput = parse_quote! {
+            #[instruction(position_id)]
+            struct Accs<'info> {
+                caller: AccountInfo<'info>,
+            }
+        };
+        assert!(parser::parse_instruction_args(&di.attrs).is_err());
+    }
+
+    /// 1.xx – foreign PDAs cannot be created by this program.
+    #[test]
+    fn parser_rejects_seeds_program_with_init() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: Account<'info, u64>,
+                #[account(mut, init, payer = payer, seeds = &[b"meta"], seeds_program = arch_program::pubkey::Pubkey::default())]
+                meta: Account<'info, u64>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`seeds_program` cannot be combined with `init`"));
+    }
+
+    /// 1.xx – `realloc::zero` only applies to `realloc` fields.
+    #[test]
+    fn parser_rejects_realloc_zero_without_realloc() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(mut, realloc::zero = true)]
+                data: Account<'info, u64>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`realloc::zero` requires the `realloc` flag"));
+    }
+
+    /// 1.xx – `nested` fields take no other flag.
+    #[test]
+    fn parser_rejects_nested_with_other_flags() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(nested, mut)]
+                pool: PoolAccounts<'info>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`nested` field cannot combine other account-specific flags"));
+    }
+
+    /// 1.xx – `rent_exempt` is limited to single accounts.
+    #[test]
+    fn parser_rejects_rent_exempt_on_slice() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(len = 2, rent_exempt)]
+                pdas: Vec<AccountInfo<'static>>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`rent_exempt` is only supported"));
+    }
+
+    /// 1.xx – `Sysvar` fields take no account, so no account constraints.
+    #[test]
+    fn parser_rejects_account_attr_on_sysvar() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer)]
+                user: AccountInfo<'info>,
+                clock: Sysvar<Clock>,
+                #[account(mut)]
+                rent: Sysvar<Rent>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("read from the runtime"));
+    }
+
+    /// 1.xx – migrating an account rewrites it, so it must be writable.
+    #[test]
+    fn parser_rejects_migrate_from_without_mut() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(zero_copy, migrate_from = 1)]
+                pool: AccountLoader<'info, Pool>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`migrate_from` requires `mut`"));
+    }
+
+    /// 1.xx – `discriminator` only applies to zero-copy accounts.
+    #[test]
+    fn parser_rejects_discriminator_without_zero_copy() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(discriminator = b"POOL")]
+                pool: AccountInfo<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`discriminator` requires"));
+    }
+
+    /// 1.xx – `@ <error>` only follows a constraint that is checked.
+    #[test]
+    fn parser_rejects_custom_error_on_non_constraint() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut, init @ MyError::Exists, payer = payer)]
+                state: AccountInfo<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("not supported on `init`"));
+    }
+
+    /// 1.xx – only accounts created by the instruction can be funded.
+    #[test]
+    fn parser_rejects_funding_without_init() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(mut, funding = 546)]
+                state: AccountInfo<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`funding` requires `init`"));
+    }
+
+    /// 1.xx – a known `bump = <expr>` on an account needs the `seeds` it belongs to.
+    #[test]
+    fn parser_rejects_account_bump_without_seeds() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(bump = 254)]
+                vault: AccountInfo<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("requires `seeds = ...`"));
+    }
+
+    /// 1.xx – `allow_pda` only relaxes an `authority`.
+    #[test]
+    fn parser_rejects_allow_pda_without_authority() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(allow_pda(seeds = &[b"router"]))]
+                authority: AccountInfo<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("requires `authority`"));
+    }
+
+    /// 1.xx – `one_of` enums name their account types in their variants.
+    #[test]
+    fn parser_rejects_one_of_with_zero_copy() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(one_of, zero_copy, of = Config)]
+                target: PoolAccount<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("cannot be combined"));
+    }
+
+    /// 1.xx – `layout::size` only describes `zero_copy` data.
+    #[test]
+    fn parser_rejects_layout_size_without_zero_copy() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(layout::size = 64)]
+                data: Account<'static, Data>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("requires `zero_copy`"));
+    }
+
+    /// 1.xx – `interface` replaces `owner` rather than adding to it.
+    #[test]
+    fn parser_rejects_interface_with_owner() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(owner = TOKEN_V1, interface = [TOKEN_V1, TOKEN_V2])]
+                vault: AccountInfo<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`interface` cannot be combined"));
+    }
+
+    /// 1.xx – `address_in` is an alternative to `address`, not an addition.
+    #[test]
+    fn parser_rejects_address_in_with_address() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(address = ORACLE_A, address_in = [ORACLE_A, ORACLE_B])]
+                oracle: AccountInfo<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`address_in` cannot be combined"));
+    }
+
+    /// 1.xx – `UncheckedAccount` fields must be visibly marked.
+    #[test]
+    fn parser_requires_unchecked_account_marker() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                feed: UncheckedAccount<'info>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("must be named `unchecked_..`"));
+
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                /// CHECK: forwarded to the oracle program, which validates it.
+                feed: UncheckedAccount<'info>,
+                unchecked_memo: UncheckedAccount<'info>,
+            }
+        };
+        assert!(parser::parse_fields(extract_named_fields(&di)).is_ok());
+    }
+
+    /// 1.xx – raw `AccountInfo` shards have no type to size them from.
+    #[test]
+    fn parser_rejects_account_info_shards_init_without_space() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                caller: AccountInfo<'info>,
+                #[account(mut, shards, init_if_needed, payer = caller, len = 2, seeds = &[b"shard"])]
+                shards: Vec<AccountInfo<'info>>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("shards requires `space"));
+    }
+
+    /// 1.xx – `max_len` bounds vector lengths only.
+    #[test]
+    fn parser_rejects_max_len_on_single() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(max_len = 4)]
+                config: AccountInfo<'info>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`max_len` is only supported"));
+    }
+
+    /// 1.xx – `Lazy` accounts are only read, never created by the instruction.
+    #[test]
+    fn parser_rejects_lazy_with_init() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: AccountInfo<'info>,
+                #[account(mut, init, payer = payer, program_id = crate::ID)]
+                config: Lazy<Account<'info, u64>>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`Lazy<..>` fields cannot"));
+    }
+
+    /// 1.xx – unknown struct-level `#[accounts(..)]` options are rejected.
+    #[test]
+    fn parser_rejects_unknown_accounts_option() {
+        let di: DeriveInput = parse_quote! {
+            #[accounts(deny_dupes)]
+            struct Accs<'info> {
+                caller: AccountInfo<'info>,
+            }
+        };
+        let err = parser::parse_accounts_cfg(&di.attrs).unwrap_err();
+        assert!(err.to_string().contains("unknown option"));
+    }
+
+    /// 1.xx – `executable` program accounts cannot be created by the instruction.
+    #[test]
+    fn parser_rejects_executable_with_init() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(signer, mut)]
+                payer: Account<'info, u64>,
+                #[account(mut, init, executable, payer = payer, program_id = arch_program::pubkey::Pubkey::default())]
+                program: AccountInfo<'static>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err.to_string().contains("`executable` cannot be combined"));
+    }
+
+    /// 1.xx – `token::*` constraints need a `TokenAccount` field.
+    #[test]
+    fn parser_rejects_token_constraint_on_other_type() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                mint: AccountInfo<'static>,
+                #[account(token::mint = mint)]
+                vault: Account<'info, u64>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("require a `TokenAccount<'info>` field"));
+    }
+
+    /// 1.xx – `Option<T>` fields are parsed as `T` with the sentinel mode.
+    #[test]
+    fn parser_accepts_option_field() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                referrer: Option<Account<'info, u64>>,
+            }
+        };
+        let cfgs = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+        assert_eq!(cfgs[0].optional, Some(model::OptionalMode::Sentinel));
+        assert!(matches!(cfgs[0].kind, model::FieldKind::Single));
+    }
+
+    /// 1.xx – `optional = ...` needs an `Option<T>` field.
+    #[test]
+    fn parser_rejects_optional_on_required_field() {
+        let di: DeriveInput = parse_quote! {
+            struct Accs<'info> {
+                #[account(optional = trailing)]
+                referrer: Account<'info, u64>,
+            }
+        };
+        let err = parser::parse_fields(extract_named_fields(&di)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("only be used on `Option<..>` fields"));
+    }
 }
@@ -12,23 +12,7 @@ pub(crate) fn validate_bump(cfg: &FieldCfg, span: proc_macro2::Span) -> Result<(
     // ------------------------------------------------------------------
     // 1. Type must be `u8`, `[u8; 1]`, or reference to `[u8; 1]`.
     // ------------------------------------------------------------------
-    let is_u8_primitive = matches!(&cfg.base_ty, Type::Path(tp)
-        if tp.path.segments.last().map_or(false, |seg| seg.ident == "u8"));
-
-    // `[u8; 1]` literal array type
-    let is_u8_array1 = matches!(&cfg.base_ty, Type::Array(arr)
-        if matches!(&*arr.elem, Type::Path(tp)
-            if tp.path.segments.last().map_or(false, |seg| seg.ident == "u8"))
-            && is_len_one(&arr.len));
-
-    // Reference to `[u8; 1]` (any lifetime)
-    let is_ref_u8_array1 = matches!(&cfg.base_ty, Type::Reference(ref_ty)
-        if matches!(&*ref_ty.elem, Type::Array(arr)
-            if matches!(&*arr.elem, Type::Path(tp)
-                if tp.path.segments.last().map_or(false, |seg| seg.ident == "u8"))
-                && is_len_one(&arr.len)));
-
-    if !(is_u8_primitive || is_u8_array1 || is_ref_u8_array1) {
+    if !is_bump_type(&cfg.base_ty) {
         return Err(syn::Error::new(
             span,
             "`bump` field must have type `u8`, `[u8; 1]`, or reference to `[u8; 1]`",
@@ -62,6 +46,7 @@ pub(crate) fn validate_bump(cfg: &FieldCfg, span: proc_macro2::Span) -> Result<(
         || cfg.is_realloc
         || cfg.is_zero_copy
         || cfg.is_shards
+        || cfg.close.is_some()
     {
         return Err(syn::Error::new(
             span,
@@ -72,6 +57,28 @@ pub(crate) fn validate_bump(cfg: &FieldCfg, span: proc_macro2::Span) -> Result<(
     Ok(())
 }
 
+/// `true` for the types a `bump` field may have: `u8`, `[u8; 1]`, or a
+/// reference to `[u8; 1]`.
+pub(crate) fn is_bump_type(ty: &Type) -> bool {
+    let is_u8_primitive = matches!(ty, Type::Path(tp)
+        if tp.path.segments.last().map_or(false, |seg| seg.ident == "u8"));
+
+    // `[u8; 1]` literal array type
+    let is_u8_array1 = matches!(ty, Type::Array(arr)
+        if matches!(&*arr.elem, Type::Path(tp)
+            if tp.path.segments.last().map_or(false, |seg| seg.ident == "u8"))
+            && is_len_one(&arr.len));
+
+    // Reference to `[u8; 1]` (any lifetime)
+    let is_ref_u8_array1 = matches!(ty, Type::Reference(ref_ty)
+        if matches!(&*ref_ty.elem, Type::Array(arr)
+            if matches!(&*arr.elem, Type::Path(tp)
+                if tp.path.segments.last().map_or(false, |seg| seg.ident == "u8"))
+                && is_len_one(&arr.len)));
+
+    is_u8_primitive || is_u8_array1 || is_ref_u8_array1
+}
+
 /// Helper that recognises the literal length `1` in an array type expression.
 fn is_len_one(expr: &Expr) -> bool {
     matches!(expr, Expr::Lit(lit) if matches!(&lit.lit, Lit::Int(int_lit) if int_lit.base10_parse::<u64>().map_or(false, |v| v == 1)))
@@ -1,4 +1,4 @@
-use crate::model::FieldKind;
+use crate::model::{FieldKind, OptionalMode};
 use crate::validator::{ValidationCtx, Validator};
 use quote::quote;
 use std::collections::HashSet;
@@ -158,6 +158,182 @@ impl Validator for FlagConsistencyRule {
     }
 }
 
+// -----------------------------------------------
+// Close destination rule
+// -----------------------------------------------
+pub struct CloseRule;
+
+impl Validator for CloseRule {
+    fn validate(&self, ctx: &ValidationCtx) -> Result<(), syn::Error> {
+        for f in ctx.fields {
+            let Some(dest_expr) = &f.close else { continue };
+
+            if !matches!(f.kind, FieldKind::Single) {
+                return Err(syn::Error::new(
+                    dest_expr.span(),
+                    "`close` is only supported on single account fields",
+                ));
+            }
+            if !f.is_writable.unwrap_or(false) {
+                return Err(syn::Error::new(
+                    f.ident.span(),
+                    "accounts with `close` must be marked `mut`/`writable`",
+                ));
+            }
+
+            // Only support simple identifier paths.
+            let Some(ident) = (match dest_expr {
+                Expr::Path(ExprPath { path, .. }) => path.get_ident(),
+                _ => None,
+            }) else {
+                return Err(syn::Error::new(
+                    dest_expr.span(),
+                    "`close = ...` must be a single identifier referring to another field",
+                ));
+            };
+
+            if *ident == f.ident {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "an account cannot be closed into itself",
+                ));
+            }
+            let Some((_, dest_cfg)) = ctx.by_ident.get(&ident.to_string()) else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("`close` points to unknown field `{}`", ident),
+                ));
+            };
+            if !matches!(dest_cfg.kind, FieldKind::Single) {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "field `{}` used as close destination must be a single account",
+                        ident
+                    ),
+                ));
+            }
+            if !dest_cfg.is_writable.unwrap_or(false) {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "field `{}` used as close destination must be marked `mut`/`writable`",
+                        ident
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// -----------------------------------------------
+// Token / mint constraint ordering rule
+// -----------------------------------------------
+pub struct TokenConstraintRule;
+
+impl Validator for TokenConstraintRule {
+    fn validate(&self, ctx: &ValidationCtx) -> Result<(), syn::Error> {
+        for (field_idx, f) in ctx.fields.iter().enumerate() {
+            let constraints = [
+                ("token::mint", &f.token_mint),
+                ("token::authority", &f.token_authority),
+                ("mint::authority", &f.mint_authority),
+            ];
+            for (key, expr) in constraints {
+                let Some(expr) = expr else { continue };
+
+                // The constraint compares against the key of another account field.
+                let Some(ident) = (match expr {
+                    Expr::Path(ExprPath { path, .. }) => path.get_ident(),
+                    _ => None,
+                }) else {
+                    return Err(syn::Error::new(
+                        expr.span(),
+                        format!(
+                            "`{} = ...` must be a single identifier referring to another field",
+                            key
+                        ),
+                    ));
+                };
+                let Some((target_idx, _)) = ctx.by_ident.get(&ident.to_string()) else {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("`{}` points to unknown field `{}`", key, ident),
+                    ));
+                };
+                if *target_idx >= field_idx {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!(
+                            "field `{}` (`{}`) must be declared before the account it constrains",
+                            ident, key
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// -----------------------------------------------
+// Optional account rule
+// -----------------------------------------------
+pub struct OptionalRule;
+
+impl Validator for OptionalRule {
+    fn validate(&self, ctx: &ValidationCtx) -> Result<(), syn::Error> {
+        // Once a trailing account is omitted nothing can follow it in the slice.
+        let mut first_trailing: Option<&Ident> = None;
+        for f in ctx.fields {
+            if matches!(f.kind, FieldKind::Phantom | FieldKind::Bump | FieldKind::Sysvar) {
+                continue;
+            }
+            if f.optional == Some(OptionalMode::Trailing) {
+                first_trailing.get_or_insert(&f.ident);
+            } else if let Some(trailing) = first_trailing {
+                return Err(syn::Error::new(
+                    f.ident.span(),
+                    format!(
+                        "field `{}` cannot follow `{}`: only other `optional = trailing` fields may come after it",
+                        f.ident, trailing
+                    ),
+                ));
+            }
+        }
+
+        // Fields referring to another account need it to be present.
+        for f in ctx.fields {
+            let references = [
+                ("payer", &f.payer),
+                ("close", &f.close),
+                ("token::mint", &f.token_mint),
+                ("token::authority", &f.token_authority),
+                ("mint::authority", &f.mint_authority),
+            ];
+            for (key, expr) in references {
+                let Some(Expr::Path(ExprPath { path, .. })) = expr else {
+                    continue;
+                };
+                let Some(ident) = path.get_ident() else {
+                    continue;
+                };
+                let Some((_, target)) = ctx.by_ident.get(&ident.to_string()) else {
+                    continue;
+                };
+                if target.optional.is_some() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("`{}` cannot refer to the optional account `{}`", key, ident),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 // -----------------------------------------------
 // Zero copy & of=<Type> coupling rule
 // -----------------------------------------------
@@ -251,6 +427,9 @@ pub const ALL_VALIDATORS: &[&dyn Validator] = &[
     &DuplicateIdentRule,
     &PayerRule,
     &FlagConsistencyRule,
+    &CloseRule,
+    &TokenConstraintRule,
+    &OptionalRule,
     &ZeroCopyRule,
     &PdaBumpRule,
 ];
@@ -1,3 +1,4 @@
+use crate::codegen::utils::{is_account_info_path, is_path_named};
 use crate::model::{FieldCfg, FieldKind};
 use syn::spanned::Spanned;
 
@@ -6,6 +7,55 @@ use syn::spanned::Spanned;
 /// are now isolated so they can be unit-tested independently and reused by
 /// other tooling.
 pub(crate) fn validate_field(cfg: &FieldCfg, span: proc_macro2::Span) -> Result<(), syn::Error> {
+    // -----------------------------------------------------------------
+    // Nested `Accounts` structs carry their own constraints
+    // -----------------------------------------------------------------
+    if matches!(cfg.kind, FieldKind::Nested) {
+        let has_other_flags = cfg.is_signer.is_some()
+            || cfg.is_writable.is_some()
+            || cfg.address.is_some()
+            || cfg.address_in.is_some()
+            || cfg.seeds.is_some()
+            || cfg.seeds_program.is_some()
+            || cfg.bump.is_some()
+            || cfg.program_id.is_some()
+            || cfg.payer.is_some()
+            || cfg.is_shards
+            || cfg.max_len.is_some()
+            || cfg.is_zero_copy
+            || cfg.is_init
+            || cfg.is_realloc
+            || cfg.realloc_zero.is_some()
+            || cfg.is_init_if_needed
+            || cfg.space.is_some()
+            || cfg.funding.is_some()
+            || cfg.anchor.is_some()
+            || cfg.of_type.is_some()
+            || cfg.layout_size.is_some()
+            || cfg.discriminator.is_some()
+            || cfg.migrate_from.is_some()
+            || cfg.owner.is_some()
+            || cfg.interface.is_some()
+            || cfg.close.is_some()
+            || cfg.is_rent_exempt
+            || cfg.is_one_of
+            || cfg.is_authority
+            || cfg.allow_pda.is_some()
+            || cfg.is_executable
+            || cfg.token_mint.is_some()
+            || cfg.token_authority.is_some()
+            || cfg.mint_authority.is_some()
+            || cfg.mint_decimals.is_some()
+            || cfg.optional.is_some();
+        if has_other_flags {
+            return Err(syn::Error::new(
+                span,
+                "`nested` field cannot combine other account-specific flags; declare the constraints on the nested struct instead",
+            ));
+        }
+        return Ok(());
+    }
+
     // -----------------------------------------------------------------
     // Seeds / address exclusivity & dependency rules
     // -----------------------------------------------------------------
@@ -16,6 +66,21 @@ pub(crate) fn validate_field(cfg: &FieldCfg, span: proc_macro2::Span) -> Result<
         ));
     }
 
+    if cfg.address_in.is_some() {
+        if cfg.address.is_some() || cfg.seeds.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`address_in` cannot be combined with `address` or `seeds`; use a single way to pin the account key",
+            ));
+        }
+        if cfg.is_init || cfg.is_init_if_needed || cfg.is_shards {
+            return Err(syn::Error::new(
+                span,
+                "`address_in` cannot be combined with `init`, `init_if_needed` or `shards`",
+            ));
+        }
+    }
+
     if cfg.seeds.is_some() && cfg.program_id.is_none() {
         return Err(syn::Error::new(
             span,
@@ -23,6 +88,36 @@ pub(crate) fn validate_field(cfg: &FieldCfg, span: proc_macro2::Span) -> Result<
         ));
     }
 
+    if cfg.seeds_program.is_some() {
+        if cfg.seeds.is_none() {
+            return Err(syn::Error::new(
+                span,
+                "`seeds_program` requires `seeds = ...` to be specified",
+            ));
+        }
+        if cfg.is_init || cfg.is_init_if_needed || cfg.is_realloc || cfg.is_shards {
+            return Err(syn::Error::new(
+                span,
+                "`seeds_program` cannot be combined with `init`, `init_if_needed`, `realloc` or `shards`; only PDAs of `program_id` can be signed for",
+            ));
+        }
+    }
+
+    if cfg.bump.is_some() && !matches!(cfg.kind, FieldKind::Bump) {
+        if cfg.seeds.is_none() || !matches!(cfg.kind, FieldKind::Single) {
+            return Err(syn::Error::new(
+                span,
+                "`bump = <expr>` on an account requires `seeds = ...` and a single account field",
+            ));
+        }
+        if cfg.is_init || cfg.is_init_if_needed {
+            return Err(syn::Error::new(
+                span,
+                "`bump = <expr>` cannot be combined with `init` or `init_if_needed`; the canonical bump is found when creating the account",
+            ));
+        }
+    }
+
     if cfg.program_id.is_some() && cfg.seeds.is_none() && !(cfg.is_init || cfg.is_realloc) {
         return Err(syn::Error::new(
             span,
@@ -52,11 +147,310 @@ pub(crate) fn validate_field(cfg: &FieldCfg, span: proc_macro2::Span) -> Result<
                 "`init` field requires `program_id = <id>` in #[account] attribute to set the owner",
             ));
         }
+        if cfg.space.is_none() && is_account_info_path(&cfg.base_ty) {
+            return Err(syn::Error::new(
+                span,
+                "`init` on an `AccountInfo<'info>` field requires `space = <expr>`",
+            ));
+        }
+    }
+
+    if cfg.interface.is_some() {
+        if cfg.owner.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`interface` cannot be combined with `owner`; list every allowed program in `interface`",
+            ));
+        }
+        if cfg.is_init || cfg.is_init_if_needed || cfg.is_shards {
+            return Err(syn::Error::new(
+                span,
+                "`interface` cannot be combined with `init`, `init_if_needed` or `shards`",
+            ));
+        }
+    }
+
+    // Shard vectors: creating raw `AccountInfo` shards needs an explicit size too.
+    if let FieldKind::Shards(_, element_ty_ts) = &cfg.kind {
+        let creates_shards = cfg.is_init || cfg.is_init_if_needed;
+        let is_acc_info_elem = syn::parse2::<syn::Type>(element_ty_ts.clone())
+            .map_or(false, |element_ty| is_account_info_path(&element_ty));
+        if creates_shards && is_acc_info_elem && cfg.space.is_none() {
+            return Err(syn::Error::new(
+                span,
+                "`init` / `init_if_needed` on `Vec<AccountInfo<'info>>` shards requires `space = <expr>`",
+            ));
+        }
+    }
+
+    // -----------------------------------------------------------------
+    // Owner constraint
+    // -----------------------------------------------------------------
+    if let Some(owner_expr) = &cfg.owner {
+        // With `init` the account is created for `owner`; this program cannot
+        // write the data of an account it does not own.
+        if (cfg.is_init || cfg.is_init_if_needed)
+            && (cfg.is_zero_copy || !is_account_info_path(&cfg.base_ty))
+        {
+            return Err(syn::Error::new(
+                owner_expr.span(),
+                "`owner` with `init` or `init_if_needed` requires an `AccountInfo<'info>` field; the data of the created account is initialised by its owner program",
+            ));
+        }
+        if cfg.is_shards {
+            return Err(syn::Error::new(
+                owner_expr.span(),
+                "`owner` is not supported on shard vectors; shards are always owned by `program_id`",
+            ));
+        }
+    }
+
+    if cfg.is_rent_exempt && !matches!(cfg.kind, FieldKind::Single) {
+        return Err(syn::Error::new(
+            span,
+            "`rent_exempt` is only supported on single account fields",
+        ));
+    }
+
+    if cfg.is_executable {
+        if cfg.is_init || cfg.is_init_if_needed || cfg.is_realloc {
+            return Err(syn::Error::new(
+                span,
+                "`executable` cannot be combined with `init`, `init_if_needed` or `realloc`; program accounts are not created or resized by the instruction",
+            ));
+        }
+        if cfg.is_shards {
+            return Err(syn::Error::new(
+                span,
+                "`executable` is not supported on shard vectors",
+            ));
+        }
+    }
+
+    // -----------------------------------------------------------------
+    // Token program wrappers
+    // -----------------------------------------------------------------
+    let is_token_account = is_path_named(&cfg.base_ty, "TokenAccount");
+    let is_mint = is_path_named(&cfg.base_ty, "Mint");
+    if (cfg.token_mint.is_some() || cfg.token_authority.is_some()) && !is_token_account {
+        return Err(syn::Error::new(
+            span,
+            "`token::mint` / `token::authority` require a `TokenAccount<'info>` field",
+        ));
+    }
+    if (cfg.mint_authority.is_some() || cfg.mint_decimals.is_some()) && !is_mint {
+        return Err(syn::Error::new(
+            span,
+            "`mint::authority` / `mint::decimals` require a `Mint<'info>` field",
+        ));
+    }
+    if is_token_account || is_mint {
+        if !matches!(cfg.kind, FieldKind::Single) {
+            return Err(syn::Error::new(
+                span,
+                "`TokenAccount` / `Mint` are only supported on single account fields",
+            ));
+        }
+        if cfg.is_init || cfg.is_init_if_needed || cfg.is_realloc || cfg.is_zero_copy {
+            return Err(syn::Error::new(
+                span,
+                "`TokenAccount` / `Mint` fields are created by the token program and cannot be combined with `init`, `init_if_needed`, `realloc` or `zero_copy`",
+            ));
+        }
+    }
+
+    // -----------------------------------------------------------------
+    // Funding of created accounts
+    // -----------------------------------------------------------------
+    if cfg.funding.is_some() && !(cfg.is_init || cfg.is_init_if_needed) {
+        return Err(syn::Error::new(
+            span,
+            "`funding` requires `init` or `init_if_needed`",
+        ));
+    }
+    if cfg.anchor.is_some() && !(cfg.is_init && matches!(cfg.kind, FieldKind::Single)) {
+        return Err(syn::Error::new(
+            span,
+            "`anchor` requires `init` on a single account field",
+        ));
+    }
+
+    // -----------------------------------------------------------------
+    // Custom constraint errors
+    // -----------------------------------------------------------------
+    let has_custom_error = |constraint: &str| {
+        cfg.constraint_errors
+            .iter()
+            .any(|(name, _)| name == constraint)
+    };
+    if has_custom_error("owner") && (cfg.is_init || cfg.is_init_if_needed) {
+        return Err(syn::Error::new(
+            span,
+            "`owner @ <error>` has no effect with `init` / `init_if_needed`: the account is created with that owner rather than checked",
+        ));
+    }
+    if has_custom_error("bump") && cfg.bump.is_none() {
+        return Err(syn::Error::new(
+            span,
+            "`bump @ <error>` requires a provided `bump = <expr>` to check",
+        ));
+    }
+
+    // -----------------------------------------------------------------
+    // Zero-copy layout
+    // -----------------------------------------------------------------
+    if cfg.layout_size.is_some() && !cfg.is_zero_copy {
+        return Err(syn::Error::new(
+            span,
+            "`layout::size` requires `zero_copy` (with `of = <Type>`)",
+        ));
+    }
+    if cfg.discriminator.is_some() && !(cfg.is_zero_copy && matches!(cfg.kind, FieldKind::Single)) {
+        return Err(syn::Error::new(
+            span,
+            "`discriminator` requires `zero_copy` on a single account field",
+        ));
+    }
+    if cfg.migrate_from.is_some() {
+        if !(cfg.is_zero_copy && matches!(cfg.kind, FieldKind::Single)) {
+            return Err(syn::Error::new(
+                span,
+                "`migrate_from` requires `zero_copy` on a single account field",
+            ));
+        }
+        if cfg.is_writable != Some(true) {
+            return Err(syn::Error::new(
+                span,
+                "`migrate_from` requires `mut`: older layouts are rewritten in place",
+            ));
+        }
+        if cfg.is_init || cfg.is_init_if_needed || cfg.is_realloc {
+            return Err(syn::Error::new(
+                span,
+                "`migrate_from` cannot be combined with `init`, `init_if_needed` or `realloc`",
+            ));
+        }
+    }
+
+    // -----------------------------------------------------------------
+    // Runtime-length vectors
+    // -----------------------------------------------------------------
+    if cfg.max_len.is_some()
+        && !matches!(cfg.kind, FieldKind::FixedSlice(_) | FieldKind::Shards(..))
+    {
+        return Err(syn::Error::new(
+            span,
+            "`max_len` is only supported on `len = ..` slices and `shards` vectors",
+        ));
+    }
+
+    // -----------------------------------------------------------------
+    // Unchecked accounts
+    // -----------------------------------------------------------------
+    if is_path_named(&cfg.base_ty, "UncheckedAccount")
+        && (cfg.is_init || cfg.is_init_if_needed || cfg.is_realloc || cfg.is_zero_copy)
+    {
+        return Err(syn::Error::new(
+            span,
+            "`UncheckedAccount` fields cannot be combined with `init`, `init_if_needed`, `realloc` or `zero_copy`; use `AccountInfo<'info>` to create accounts",
+        ));
+    }
+
+    // -----------------------------------------------------------------
+    // Lazily decoded accounts
+    // -----------------------------------------------------------------
+    if is_path_named(&cfg.base_ty, "Lazy")
+        && (cfg.is_init || cfg.is_init_if_needed || cfg.is_realloc || cfg.is_zero_copy)
+    {
+        return Err(syn::Error::new(
+            span,
+            "`Lazy<..>` fields cannot be combined with `init`, `init_if_needed`, `realloc` or `zero_copy`",
+        ));
+    }
+
+    // -----------------------------------------------------------------
+    // Signer-or-PDA authorities
+    // -----------------------------------------------------------------
+    if cfg.allow_pda.is_some() && !cfg.is_authority {
+        return Err(syn::Error::new(span, "`allow_pda(..)` requires `authority`"));
+    }
+    if cfg.is_authority {
+        if cfg.is_signer == Some(true) {
+            return Err(syn::Error::new(
+                span,
+                "`authority` already requires a signature (unless `allow_pda` matches); remove `signer`",
+            ));
+        }
+        if cfg.address.is_some()
+            || cfg.seeds.is_some()
+            || cfg.is_init
+            || cfg.is_init_if_needed
+            || cfg.is_shards
+        {
+            return Err(syn::Error::new(
+                span,
+                "`authority` cannot be combined with `address`, `seeds`, `init`, `init_if_needed` or `shards`",
+            ));
+        }
+    }
+
+    // -----------------------------------------------------------------
+    // Enum-discriminated accounts
+    // -----------------------------------------------------------------
+    if cfg.is_one_of {
+        if !matches!(cfg.kind, FieldKind::Single) {
+            return Err(syn::Error::new(
+                span,
+                "`one_of` is only supported on single account fields",
+            ));
+        }
+        if cfg.is_init
+            || cfg.is_init_if_needed
+            || cfg.is_realloc
+            || cfg.is_zero_copy
+            || cfg.of_type.is_some()
+        {
+            return Err(syn::Error::new(
+                span,
+                "`one_of` fields cannot be combined with `init`, `init_if_needed`, `realloc`, `zero_copy` or `of`; the enum variants name the account types",
+            ));
+        }
+    }
+
+    // -----------------------------------------------------------------
+    // Optional accounts
+    // -----------------------------------------------------------------
+    if cfg.optional.is_some() {
+        if !matches!(cfg.kind, FieldKind::Single) {
+            return Err(syn::Error::new(
+                span,
+                "`Option<..>` is only supported on single account fields",
+            ));
+        }
+        if cfg.is_init || cfg.is_init_if_needed || cfg.is_realloc {
+            return Err(syn::Error::new(
+                span,
+                "`Option<..>` accounts cannot be combined with `init`, `init_if_needed` or `realloc`",
+            ));
+        }
+        if cfg.close.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`Option<..>` accounts cannot be combined with `close`",
+            ));
+        }
     }
 
     // -----------------------------------------------------------------
     // Rules specific to realloc
     // -----------------------------------------------------------------
+    if cfg.realloc_zero.is_some() && !cfg.is_realloc {
+        return Err(syn::Error::new(
+            span,
+            "`realloc::zero` requires the `realloc` flag",
+        ));
+    }
+
     if cfg.is_realloc {
         if cfg.space.is_none() {
             return Err(syn::Error::new(
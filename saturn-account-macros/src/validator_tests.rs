@@ -283,3 +283,138 @@ fn validator_rejects_realloc_payer_not_signer() {
     let err = validator::validate(&parsed).unwrap_err();
     assert!(err.to_string().contains("must be marked `signer`"));
 }
+
+/// 2.x – validator rejects `close` on an account that is not writable.
+#[test]
+fn validator_rejects_close_without_mut() {
+    let di: DeriveInput = parse_quote! {
+        struct Accs<'info> {
+            #[account(signer, mut)]
+            user: AccountInfo<'info>,
+            #[account(close = user)]
+            escrow: Account<'info, u64>,
+        }
+    };
+
+    let parsed = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+    let err = validator::validate(&parsed).unwrap_err();
+    assert!(err.to_string().contains("`close` must be marked `mut`"));
+}
+
+/// 2.x – validator rejects a close destination that is not writable.
+#[test]
+fn validator_rejects_close_destination_not_mut() {
+    let di: DeriveInput = parse_quote! {
+        struct Accs<'info> {
+            #[account(signer)]
+            user: AccountInfo<'info>,
+            #[account(mut, close = user)]
+            escrow: Account<'info, u64>,
+        }
+    };
+
+    let parsed = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+    let err = validator::validate(&parsed).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("close destination must be marked `mut`"));
+}
+
+/// 2.x – validator rejects closing an account into itself.
+#[test]
+fn validator_rejects_close_into_itself() {
+    let di: DeriveInput = parse_quote! {
+        struct Accs<'info> {
+            #[account(mut, close = escrow)]
+            escrow: Account<'info, u64>,
+        }
+    };
+
+    let parsed = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+    let err = validator::validate(&parsed).unwrap_err();
+    assert!(err.to_string().contains("closed into itself"));
+}
+
+/// 2.x – validator allows closing into a later writable field.
+#[test]
+fn validator_allows_close_with_writable_destination() {
+    let di: DeriveInput = parse_quote! {
+        struct Accs<'info> {
+            #[account(mut, close = user)]
+            escrow: Account<'info, u64>,
+            #[account(signer, mut)]
+            user: AccountInfo<'info>,
+        }
+    };
+
+    let parsed = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+    validator::validate(&parsed).expect("validator should allow close into a writable field");
+}
+
+/// 2.x – validator rejects a `token::mint` target declared after the token account.
+#[test]
+fn validator_rejects_token_mint_declared_later() {
+    let di: DeriveInput = parse_quote! {
+        struct Accs<'info> {
+            #[account(token::mint = usdc_mint)]
+            vault_usdc: saturn_account_parser::TokenAccount<'info>,
+            usdc_mint: saturn_account_parser::Mint<'info>,
+        }
+    };
+
+    let parsed = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+    let err = validator::validate(&parsed).unwrap_err();
+    assert!(err.to_string().contains("must be declared before"));
+}
+
+/// 2.x – validator allows token / mint constraints on earlier fields.
+#[test]
+fn validator_allows_token_constraints() {
+    let di: DeriveInput = parse_quote! {
+        struct Accs<'info> {
+            #[account(signer)]
+            minter: AccountInfo<'info>,
+            #[account(mint::authority = minter, mint::decimals = 6)]
+            usdc_mint: saturn_account_parser::Mint<'info>,
+            #[account(token::mint = usdc_mint, token::authority = minter)]
+            vault_usdc: saturn_account_parser::TokenAccount<'info>,
+        }
+    };
+
+    let parsed = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+    validator::validate(&parsed).expect("validator should allow token constraints");
+}
+
+/// 2.x – validator rejects a required account after a trailing optional one.
+#[test]
+fn validator_rejects_required_after_trailing_optional() {
+    let di: DeriveInput = parse_quote! {
+        struct Accs<'info> {
+            #[account(optional = trailing)]
+            referrer: Option<AccountInfo<'info>>,
+            #[account(signer)]
+            user: AccountInfo<'info>,
+        }
+    };
+
+    let parsed = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+    let err = validator::validate(&parsed).unwrap_err();
+    assert!(err.to_string().contains("cannot follow `referrer`"));
+}
+
+/// 2.x – validator rejects an optional account used as payer.
+#[test]
+fn validator_rejects_optional_payer() {
+    let di: DeriveInput = parse_quote! {
+        struct Accs<'info> {
+            #[account(signer, mut)]
+            payer: Option<AccountInfo<'info>>,
+            #[account(mut, init, payer = payer, seeds = &[b"seed"], program_id = arch_program::pubkey::Pubkey::default())]
+            data: Account<'info, u64>,
+        }
+    };
+
+    let parsed = parser::parse_fields(extract_named_fields(&di)).expect("parse ok");
+    let err = validator::validate(&parsed).unwrap_err();
+    assert!(err.to_string().contains("optional account `payer`"));
+}
@@ -0,0 +1,20 @@
+use arch_program::account::AccountInfo;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+
+const ORACLE_A: Pubkey = Pubkey([1u8; 32]);
+const ORACLE_B: Pubkey = Pubkey([2u8; 32]);
+const ORACLE_C: Pubkey = Pubkey([3u8; 32]);
+const ADMINS: [Pubkey; 2] = [Pubkey([4u8; 32]), Pubkey([5u8; 32])];
+
+#[derive(Accounts)]
+struct UpdatePrice<'info> {
+    // any of the configured oracles may push a price
+    #[account(signer, address_in = [ORACLE_A, ORACLE_B, ORACLE_C])]
+    oracle: AccountInfo<'info>,
+
+    #[account(len = 2, address_in = ADMINS)]
+    admins: Vec<AccountInfo<'info>>,
+}
+
+fn main() {}
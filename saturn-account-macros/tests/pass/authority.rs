@@ -0,0 +1,16 @@
+use arch_program::{account::AccountInfo, pubkey::Pubkey};
+use saturn_account_macros::Accounts;
+
+const ROUTER_ID: Pubkey = Pubkey([7u8; 32]);
+
+#[derive(Accounts)]
+struct Rebalance<'info> {
+    // a user signing directly, or the router program signing for its PDA via CPI
+    #[account(authority, allow_pda(seeds = &[b"router"], program_id = ROUTER_ID))]
+    authority: AccountInfo<'info>,
+
+    #[account(authority)]
+    keeper: AccountInfo<'info>,
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+use arch_program::account::AccountInfo;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::PdaSeeds;
+
+const ID: Pubkey = Pubkey([1u8; 32]);
+
+#[derive(Accounts)]
+struct Pool<'info> {
+    #[account(seeds = &[b"pool"], program_id = ID)]
+    pool: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+struct Swap<'info> {
+    #[account(signer)]
+    user: AccountInfo<'info>,
+
+    #[account(seeds = &[b"vault"], program_id = ID)]
+    vault: AccountInfo<'info>,
+
+    #[account(bump, seeds = &[b"config"], program_id = ID)]
+    config_bump: u8,
+
+    #[account(nested)]
+    pool: Pool<'info>,
+}
+
+fn main() {
+    let bumps = SwapBumps::default();
+    let _: u8 = bumps.vault;
+    let _: u8 = bumps.config_bump;
+    // Nothing parsed yet, so no seeds were recorded.
+    assert!(bumps.signer_seeds().get("vault").is_none());
+    let _: PoolBumps = bumps.pool;
+}
@@ -0,0 +1,19 @@
+use arch_program::{account::AccountInfo, pubkey::Pubkey};
+use saturn_account_macros::Accounts;
+
+const ID: Pubkey = Pubkey([1u8; 32]);
+const VAULT_BUMP: u8 = 255;
+
+#[derive(Accounts)]
+#[instruction(config_bump: u8)]
+struct Withdraw<'info> {
+    // client-provided bump, rejected unless canonical
+    #[account(seeds = &[b"config"], program_id = ID, bump = config_bump)]
+    config_bump_checked: u8,
+
+    // known bump, verified without searching for it
+    #[account(mut, seeds = &[b"vault"], program_id = ID, bump = VAULT_BUMP)]
+    vault: AccountInfo<'info>,
+}
+
+fn main() {}
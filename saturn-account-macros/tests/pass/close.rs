@@ -0,0 +1,22 @@
+use arch_program::account::AccountInfo;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_account_parser::Accounts as _;
+
+#[derive(Accounts)]
+struct CloseEscrow<'info> {
+    #[account(signer, mut)]
+    user: AccountInfo<'info>,
+
+    // closed into `user` once the handler returned
+    #[account(mut, close = user)]
+    escrow: Account<'info, u64>,
+}
+
+fn finish(accs: &CloseEscrow<'_>) -> Result<(), arch_program::program_error::ProgramError> {
+    accs.exit()
+}
+
+fn main() {
+    let _ = finish;
+}
@@ -0,0 +1,41 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::cpi::{self, CpiContext};
+
+// Accounts of the `deposit` instruction of the vault program.
+#[derive(Accounts)]
+struct VaultDeposit<'info> {
+    #[account(signer)]
+    authority: AccountInfo<'info>,
+
+    #[account(mut)]
+    vault: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+struct Deposit<'info> {
+    #[account(signer)]
+    user: AccountInfo<'info>,
+
+    #[account(mut)]
+    vault: AccountInfo<'info>,
+
+    vault_program: AccountInfo<'info>,
+}
+
+fn deposit(accs: &Deposit<'_>, bump: u8, data: Vec<u8>) -> Result<(), ProgramError> {
+    let accounts = VaultDeposit {
+        authority: accs.user.clone(),
+        vault: accs.vault.clone(),
+    };
+    let bump = [bump];
+    let seeds: &[&[u8]] = &[b"vault", &bump];
+    let signer_seeds = [seeds];
+    let ctx = CpiContext::new_with_signer(accs.vault_program.clone(), accounts, &signer_seeds);
+    cpi::invoke_signed(ctx, data)
+}
+
+fn main() {
+    let _ = deposit;
+}
@@ -0,0 +1,32 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+
+const ID: Pubkey = Pubkey([1u8; 32]);
+const ADMIN: Pubkey = Pubkey([2u8; 32]);
+
+#[derive(Debug, Clone, Copy)]
+enum MyError {
+    MissingAdminSignature = 6000,
+    NotAdmin = 6001,
+    ConfigNotWritable = 6002,
+    WrongConfig = 6003,
+}
+
+impl From<MyError> for ProgramError {
+    fn from(e: MyError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(Accounts)]
+struct SetFee<'info> {
+    #[account(signer @ MyError::MissingAdminSignature, address = ADMIN @ MyError::NotAdmin)]
+    admin: AccountInfo<'info>,
+
+    #[account(mut @ MyError::ConfigNotWritable, seeds = &[b"config"] @ MyError::WrongConfig, program_id = ID)]
+    config: AccountInfo<'info>,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use arch_program::account::AccountInfo;
+use saturn_account_macros::Accounts;
+
+#[derive(Accounts)]
+#[accounts(deny_duplicates)]
+struct Transfer<'info> {
+    #[account(signer, mut)]
+    from: AccountInfo<'info>,
+
+    #[account(mut)]
+    to: AccountInfo<'info>,
+
+    #[account(len = 2, mut)]
+    fee_receivers: Vec<AccountInfo<'info>>,
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+use arch_program::pubkey::Pubkey;
+use borsh::{BorshDeserialize, BorshSerialize};
+use saturn_account_macros::Event;
+use saturn_account_parser::emit;
+use saturn_account_parser::event::{decode_events, log_line, Event as _, EVENT_LOG_PREFIX};
+
+#[derive(BorshSerialize, BorshDeserialize, Event, Debug, PartialEq)]
+struct Deposited {
+    user: Pubkey,
+    amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Event)]
+struct Withdrawn {
+    amount: u64,
+}
+
+fn main() {
+    let event = Deposited {
+        user: Pubkey::default(),
+        amount: 42,
+    };
+    assert_eq!(event.data()[..8], Deposited::DISCRIMINATOR);
+    assert_ne!(Deposited::DISCRIMINATOR, Withdrawn::DISCRIMINATOR);
+
+    // What `emit!` logs, as seen by a client next to unrelated lines.
+    let line = log_line(&event);
+    assert!(line.starts_with(EVENT_LOG_PREFIX));
+    let logs = [
+        "Program log: hello".to_string(),
+        format!("Program log: {line}"),
+    ];
+    assert_eq!(decode_events::<Deposited, _>(&logs), vec![event]);
+    assert!(decode_events::<Withdrawn, _>(&logs).is_empty());
+
+    emit!(Withdrawn { amount: 7 });
+}
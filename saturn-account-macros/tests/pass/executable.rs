@@ -0,0 +1,20 @@
+use arch_program::account::AccountInfo;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+
+const TOKEN_PROGRAM_ID: Pubkey = Pubkey([7u8; 32]);
+
+#[derive(Accounts)]
+struct Cpi<'info> {
+    #[account(signer)]
+    caller: AccountInfo<'info>,
+
+    // program account invoked through CPI
+    #[account(executable, address = TOKEN_PROGRAM_ID)]
+    token_program: AccountInfo<'info>,
+
+    #[account(len = 2, executable)]
+    plugins: Vec<AccountInfo<'info>>,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::AccountLoader;
+
+// Written by another program as the bare struct; no `Discriminator` needed.
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Copy, Clone)]
+#[repr(C)]
+pub struct ForeignPool {
+    pub reserve: u64,
+    pub fee_bps: u64,
+}
+
+// Prefixed by a 4-byte tag of its own.
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Copy, Clone)]
+#[repr(C)]
+pub struct ForeignOracle {
+    pub price: u32,
+    pub expo: u32,
+}
+
+#[derive(Accounts)]
+struct ReadForeign<'info> {
+    #[account(zero_copy, of = ForeignPool, discriminator = none)]
+    pool: AccountLoader<'info, ForeignPool>,
+
+    #[account(zero_copy, of = ForeignOracle, discriminator = b"ORCL")]
+    oracle: AccountLoader<'info, ForeignOracle>,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use arch_program::account::AccountInfo;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+
+const ID: Pubkey = Pubkey([1u8; 32]);
+const REGISTRY_ID: Pubkey = Pubkey([2u8; 32]);
+
+#[derive(Accounts)]
+struct RegisterEntry<'info> {
+    #[account(signer, mut)]
+    payer: AccountInfo<'info>,
+
+    // PDA of this program, created for the registry program to initialise
+    #[account(mut, init, payer = payer, owner = REGISTRY_ID, seeds = &[b"entry"], program_id = ID, space = 64)]
+    entry: AccountInfo<'info>,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use arch_program::account::AccountInfo;
+use arch_program::pubkey::Pubkey;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_macros::Accounts;
+
+const ID: Pubkey = Pubkey([1u8; 32]);
+const DUST_LIMIT: u64 = 546;
+
+#[derive(Accounts)]
+#[instruction(utxo: UtxoMeta)]
+struct CreateAnchored<'info> {
+    #[account(signer, mut)]
+    payer: AccountInfo<'info>,
+
+    // Funded with the dust limit and anchored to the UTXO passed in the params
+    #[account(mut, init, payer = payer, seeds = &[b"anchored"], program_id = ID, space = 64, funding = DUST_LIMIT, anchor = utxo)]
+    state: AccountInfo<'info>,
+}
+
+fn main() {}
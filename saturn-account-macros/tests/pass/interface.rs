@@ -0,0 +1,17 @@
+use arch_program::{account::AccountInfo, pubkey::Pubkey};
+use saturn_account_macros::Accounts;
+use saturn_account_parser::TokenAccount;
+
+const TOKEN_V1: Pubkey = Pubkey([1u8; 32]);
+const TOKEN_V2: Pubkey = Pubkey([2u8; 32]);
+
+#[derive(Accounts)]
+struct Deposit<'info> {
+    #[account(signer)]
+    user: AccountInfo<'info>,
+
+    #[account(mut, interface = [TOKEN_V1, TOKEN_V2])]
+    user_tokens: TokenAccount<'info>,
+}
+
+fn main() {}
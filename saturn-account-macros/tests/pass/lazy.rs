@@ -0,0 +1,30 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::{Account, Lazy};
+
+#[derive(Accounts)]
+struct Swap<'info> {
+    #[account(signer)]
+    caller: AccountInfo<'info>,
+
+    // only decoded when the handler reads it
+    #[account(owner = Pubkey::default())]
+    config: Lazy<Account<'info, u64>>,
+
+    #[account(mut)]
+    pool: Lazy<Account<'info, u64>>,
+}
+
+fn swap(accounts: &mut Swap<'_>) -> Result<(), ProgramError> {
+    if **accounts.config.get()? == 0 {
+        return Ok(());
+    }
+    **accounts.pool.get_mut()? += 1;
+    Ok(())
+}
+
+fn main() {
+    let _ = swap;
+}
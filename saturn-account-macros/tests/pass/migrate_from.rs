@@ -0,0 +1,48 @@
+use arch_program::program_error::ProgramError;
+use saturn_account_discriminator_derive::Discriminator;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::AccountLoader;
+use saturn_account_parser::{migrate_pod, Migratable, Migration};
+
+// Layout of version 1, kept for the migration only.
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Copy, Clone)]
+#[repr(C)]
+pub struct PoolV1 {
+    pub version: u8,
+    pub _padding: [u8; 7],
+    pub reserve: u64,
+}
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct Pool {
+    pub version: u8,
+    pub _padding: [u8; 7],
+    pub reserve: u64,
+    pub fee_bps: u64,
+}
+
+fn v1_to_v2(data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+    migrate_pod(data, |old: PoolV1| Pool {
+        version: 2,
+        _padding: [0; 7],
+        reserve: old.reserve,
+        fee_bps: 30,
+    })
+}
+
+impl Migratable for Pool {
+    const VERSION: u8 = 2;
+    const MIGRATIONS: &'static [Migration] = &[Migration {
+        from: 1,
+        migrate: v1_to_v2,
+    }];
+}
+
+#[derive(Accounts)]
+struct Swap<'info> {
+    #[account(mut, zero_copy, migrate_from = 1)]
+    pool: AccountLoader<'info, Pool>,
+}
+
+fn main() {}
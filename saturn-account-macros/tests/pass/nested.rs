@@ -0,0 +1,34 @@
+use arch_program::account::AccountInfo;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_account_parser::Accounts as _;
+
+#[derive(Accounts)]
+struct PoolAccounts<'info> {
+    #[account(mut)]
+    config: Account<'info, u64>,
+
+    // closed into `config` once the handler returned
+    #[account(mut, close = config)]
+    scratch: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+struct Deposit<'info> {
+    #[account(signer)]
+    user: AccountInfo<'info>,
+
+    // parsed in place from the accounts following `user`
+    #[account(nested)]
+    pool: PoolAccounts<'info>,
+
+    vault: AccountInfo<'info>,
+}
+
+fn finish(accs: &Deposit<'_>) -> Result<(), arch_program::program_error::ProgramError> {
+    accs.exit()
+}
+
+fn main() {
+    let _ = finish;
+}
@@ -0,0 +1,31 @@
+use saturn_account_discriminator_derive::Discriminator;
+use saturn_account_macros::{AccountEnum, Accounts};
+use saturn_account_parser::codec::AccountLoader;
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct Config {
+    pub touched: u64,
+}
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct Shard {
+    pub touched: u64,
+    pub liquidity: u64,
+}
+
+#[derive(AccountEnum)]
+pub enum PoolAccount<'info> {
+    Config(AccountLoader<'info, Config>),
+    Shard(AccountLoader<'info, Shard>),
+}
+
+#[derive(Accounts)]
+struct Touch<'info> {
+    // either the pool config or one of its shards
+    #[account(mut, one_of)]
+    target: PoolAccount<'info>,
+}
+
+fn main() {}
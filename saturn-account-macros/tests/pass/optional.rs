@@ -0,0 +1,22 @@
+use arch_program::account::AccountInfo;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+
+// Sentinel passed by clients in place of an omitted optional account.
+pub const ID: Pubkey = Pubkey([1u8; 32]);
+
+#[derive(Accounts)]
+struct Swap<'info> {
+    #[account(signer)]
+    user: AccountInfo<'info>,
+
+    // `None` when the slot holds `crate::ID`
+    referrer: Option<AccountInfo<'info>>,
+
+    // `None` when the account slice ends here
+    #[account(mut, optional = trailing)]
+    fee_vault: Option<Account<'info, u64>>,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use arch_program::account::AccountInfo;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+
+const TOKEN_PROGRAM_ID: Pubkey = Pubkey([7u8; 32]);
+
+#[derive(Accounts)]
+struct Owned<'info> {
+    #[account(signer)]
+    caller: AccountInfo<'info>,
+
+    // single account owned by another program
+    #[account(owner = TOKEN_PROGRAM_ID)]
+    token_account: AccountInfo<'info>,
+
+    // decoded account owned by this program
+    #[account(owner = Pubkey::default())]
+    config: Account<'info, u64>,
+
+    // every element of the slice is checked
+    #[account(len = 2, owner = TOKEN_PROGRAM_ID)]
+    mints: Vec<AccountInfo<'info>>,
+}
+
+fn main() {}
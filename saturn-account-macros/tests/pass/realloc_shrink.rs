@@ -0,0 +1,16 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+
+#[derive(Accounts)]
+struct ReallocShrinkPass<'info> {
+    #[account(signer, mut)]
+    payer: Account<'info, u64>,
+    // shrinking hands the excess lamports back to `payer`
+    #[account(mut, realloc, payer = payer, space = 8)]
+    smaller: Account<'info, u64>,
+    // re-zero the grown region in case it shrank earlier in the instruction
+    #[account(mut, realloc, realloc::zero = true, payer = payer, space = 64)]
+    bigger: Account<'info, u64>,
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use arch_program::account::AccountInfo;
+use saturn_account_discriminator_derive::Discriminator;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::AccountLoader;
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct Book {
+    pub orders: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(extra: u64)]
+struct GrowBook<'info> {
+    #[account(signer, mut)]
+    payer: AccountInfo<'info>,
+
+    // resized by `AccountLoader::realloc`, keeping the discriminator
+    #[account(mut, zero_copy, realloc, payer = payer, space = 16 + extra as usize)]
+    book: AccountLoader<'info, Book>,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use arch_program::account::AccountInfo;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+
+#[derive(Accounts)]
+struct Resize<'info> {
+    #[account(signer, mut)]
+    payer: AccountInfo<'info>,
+
+    // checked after the resize so it cannot fall below the minimum balance
+    #[account(mut, realloc, payer = payer, space = 64, rent_exempt)]
+    data: Account<'info, u64>,
+
+    #[account(rent_exempt)]
+    config: Account<'info, u64>,
+}
+
+fn main() {}
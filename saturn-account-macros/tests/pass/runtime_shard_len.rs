@@ -0,0 +1,29 @@
+use saturn_account_discriminator_derive::Discriminator;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::AccountLoader;
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct PoolConfig {
+    pub shard_count: u64,
+}
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct Shard {
+    pub value: u64,
+}
+
+const MAX_SHARDS: usize = 16;
+
+#[derive(Accounts)]
+struct Pool<'info> {
+    #[account(zero_copy, of = PoolConfig)]
+    config: AccountLoader<'info, PoolConfig>,
+
+    // as many shards as the pool currently has, up to `MAX_SHARDS`
+    #[account(shards, len = config.load()?.shard_count, max_len = MAX_SHARDS)]
+    shards: Vec<AccountLoader<'info, Shard>>,
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use arch_program::account::AccountInfo;
+use arch_program::pubkey::Pubkey;
+use saturn_account_macros::Accounts;
+
+const ID: Pubkey = Pubkey([1u8; 32]);
+const METADATA_ID: Pubkey = Pubkey([3u8; 32]);
+
+#[derive(Accounts)]
+struct ReadMetadata<'info> {
+    mint: AccountInfo<'info>,
+
+    // PDA of the metadata program rather than of this one
+    #[account(seeds = &[b"metadata", mint.key.as_ref()], seeds_program = METADATA_ID)]
+    metadata: AccountInfo<'info>,
+
+    #[account(bump, seeds = &[b"metadata", mint.key.as_ref()], seeds_program = METADATA_ID)]
+    metadata_bump: u8,
+
+    #[account(seeds = &[b"config"], program_id = ID)]
+    config: AccountInfo<'info>,
+}
+
+fn main() {}
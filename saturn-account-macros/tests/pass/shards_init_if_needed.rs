@@ -0,0 +1,25 @@
+use arch_program::account::AccountInfo;
+use saturn_account_discriminator_derive::Discriminator;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::AccountLoader;
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct Shard {
+    pub value: u64,
+}
+
+#[derive(Accounts)]
+struct AddShards<'info> {
+    #[account(signer, mut)]
+    caller: AccountInfo<'info>,
+
+    // missing shards are created, existing ones loaded
+    #[account(mut, shards, init_if_needed, payer = caller, len = 4, seeds = &[b"shard"], program_id = arch_program::pubkey::Pubkey::default())]
+    shards: Vec<AccountLoader<'info, Shard>>,
+
+    #[account(mut, shards, init_if_needed, payer = caller, len = 2, space = 64, seeds = &[b"raw_shard"], program_id = arch_program::pubkey::Pubkey::default())]
+    raw_shards: Vec<AccountInfo<'info>>,
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+use arch_program::account::AccountInfo;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::sysvar::{BitcoinBlockHeight, Clock, Rent};
+use saturn_account_parser::Sysvar;
+
+#[derive(Accounts)]
+struct Claim<'info> {
+    #[account(signer)]
+    user: AccountInfo<'info>,
+
+    // read from the runtime, no account taken
+    clock: Sysvar<Clock>,
+    rent: Sysvar<Rent>,
+    height: Sysvar<BitcoinBlockHeight>,
+
+    #[account(mut)]
+    vault: AccountInfo<'info>,
+}
+
+fn unlocked(accs: &Claim<'_>, unlock_at: i64, min_height: u64) -> bool {
+    accs.clock.unix_timestamp >= unlock_at
+        && *accs.height >= BitcoinBlockHeight(min_height)
+        && accs
+            .rent
+            .is_exempt(accs.vault.lamports(), accs.vault.data_len())
+}
+
+fn main() {
+    let _ = unlocked;
+}
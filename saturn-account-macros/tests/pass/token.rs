@@ -0,0 +1,17 @@
+use arch_program::account::AccountInfo;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+struct Deposit<'info> {
+    #[account(signer)]
+    user: AccountInfo<'info>,
+
+    #[account(mint::decimals = 6)]
+    usdc_mint: Mint<'info>,
+
+    #[account(mut, token::mint = usdc_mint, token::authority = user)]
+    user_usdc: TokenAccount<'info>,
+}
+
+fn main() {}
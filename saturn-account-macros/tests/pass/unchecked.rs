@@ -0,0 +1,17 @@
+use arch_program::account::AccountInfo;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::UncheckedAccount;
+
+#[derive(Accounts)]
+struct Relay<'info> {
+    #[account(signer)]
+    caller: AccountInfo<'info>,
+
+    /// CHECK: only forwarded to the oracle program, which validates it.
+    #[account(mut)]
+    feed: UncheckedAccount<'info>,
+
+    unchecked_memo: UncheckedAccount<'info>,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use saturn_account_discriminator_derive::Discriminator;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::AccountLoader;
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct MarketState {
+    pub base_reserve: u64,
+    pub quote_reserve: u64,
+    pub fee_bps: u64,
+    pub authority: [u8; 32],
+}
+
+#[derive(Accounts)]
+struct Swap<'info> {
+    // the build fails if `MarketState` stops being 56 bytes
+    #[account(mut, zero_copy, of = MarketState, layout::size = 56)]
+    market: AccountLoader<'info, MarketState>,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use saturn_account_discriminator_derive::Discriminator;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::ZeroCopySlice;
+
+#[derive(bytemuck::Pod, bytemuck::Zeroable, Discriminator, Copy, Clone)]
+#[repr(C)]
+pub struct Order {
+    pub price: u64,
+    pub size: u64,
+}
+
+#[derive(Accounts)]
+struct PlaceOrder<'info> {
+    #[account(signer)]
+    trader: AccountInfo<'info>,
+
+    // discriminator and length prefix checked while parsing
+    #[account(mut)]
+    orders: ZeroCopySlice<'info, Order>,
+}
+
+fn place(accs: &PlaceOrder<'_>, order: Order) -> Result<Option<u64>, ProgramError> {
+    accs.orders.push(order)?;
+    accs.orders.retain(|order| order.size > 0)?;
+    Ok(accs.orders.iter()?.map(|order| order.price).max())
+}
+
+fn main() {
+    let _ = place;
+}
@@ -0,0 +1,61 @@
+//! Accounts that may hold one of several zero-copy layouts.
+//!
+//! `#[derive(AccountEnum)]` on an enum with one single-field variant per
+//! accepted account type implements [`AccountEnum`] for it: the variant is
+//! picked by the discriminator stored at the start of the account data.
+//! `#[account(one_of)]` fields of such an enum are decoded this way, so a
+//! router-style instruction can take "any of" several account kinds.
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use bytemuck::{Pod, Zeroable};
+
+use crate::codec::zero_copy::{Discriminator, DISCRIMINATOR_LEN};
+use crate::codec::AccountLoader;
+
+/// Account wrapper that can be a variant of an [`AccountEnum`].
+pub trait AccountVariant<'a>: Sized {
+    /// Discriminator the account data must start with to select this variant.
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Wraps `info` once its discriminator matched.
+    fn wrap(info: &'a AccountInfo<'a>) -> Self;
+
+    /// Returns a reference to the underlying `AccountInfo` object.
+    fn account_info(&self) -> &'a AccountInfo<'a>;
+}
+
+impl<'a, T> AccountVariant<'a> for AccountLoader<'a, T>
+where
+    T: Pod + Zeroable + Discriminator + 'static,
+{
+    const DISCRIMINATOR: [u8; 8] = T::DISCRIMINATOR;
+
+    fn wrap(info: &'a AccountInfo<'a>) -> Self {
+        AccountLoader::new(info)
+    }
+
+    fn account_info(&self) -> &'a AccountInfo<'a> {
+        self.info()
+    }
+}
+
+/// Enum over several [`AccountVariant`]s, usually derived with
+/// `#[derive(AccountEnum)]`.
+pub trait AccountEnum<'a>: Sized {
+    /// Selects the variant whose discriminator prefixes the data of `info`.
+    ///
+    /// Fails with `ErrorCode::UnknownAccountDiscriminator` when none does.
+    fn try_from_info(info: &'a AccountInfo<'a>) -> Result<Self, ProgramError>;
+
+    /// Returns a reference to the underlying `AccountInfo` object.
+    fn account_info(&self) -> &'a AccountInfo<'a>;
+}
+
+/// Reads the 8-byte discriminator at the start of the account data.
+pub fn read_discriminator(info: &AccountInfo<'_>) -> Result<[u8; 8], ProgramError> {
+    let data = info.try_borrow_data()?;
+    data.get(..DISCRIMINATOR_LEN)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ProgramError::InvalidAccountData)
+}
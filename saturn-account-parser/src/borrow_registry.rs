@@ -0,0 +1,124 @@
+//! Per-transaction registry of zero-copy borrows, keyed by account key.
+//!
+//! The same account can reach a program through several `AccountInfo`s, e.g.
+//! once in the typed accounts and again in `remaining_accounts`. Off-chain,
+//! each copy built by a test has its own `RefCell`, so overlapping
+//! `load_mut()` calls through them are not caught by the cell and would hand
+//! out aliasing references to the same data.
+//! [`AccountLoader`](crate::AccountLoader) records its borrows here,
+//! checks the table before lending the data, and fails with
+//! `ErrorCode::AccountBorrowConflict` instead.
+//!
+//! On-chain the entrypoint hands every duplicate of an account a clone of the
+//! same `AccountInfo`, so the shared `RefCell` already is the per-account
+//! borrow flag. The table is therefore only kept off-chain; SBF programs do
+//! not support mutable global state. There the loaders report a failed
+//! `try_borrow(_mut)_data` as the same `ErrorCode::AccountBorrowConflict`.
+
+use arch_program::program_error::ProgramError;
+use arch_program::pubkey::Pubkey;
+#[cfg(not(target_os = "solana"))]
+use std::cell::RefCell;
+
+use crate::error::ErrorCode;
+
+/// Live borrows of one account.
+#[cfg(not(target_os = "solana"))]
+enum BorrowState {
+    /// Number of immutable borrows.
+    Shared(usize),
+    Exclusive,
+}
+
+#[cfg(not(target_os = "solana"))]
+thread_local! {
+    static BORROWS: RefCell<Vec<(Pubkey, BorrowState)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Registration of a borrow of the account with `key`, released on drop.
+#[must_use = "the borrow is released as soon as the guard is dropped"]
+pub struct BorrowGuard {
+    #[cfg(not(target_os = "solana"))]
+    key: Pubkey,
+}
+
+impl BorrowGuard {
+    /// Registers an immutable borrow. Fails while the account is mutably
+    /// borrowed through any `AccountInfo`.
+    #[cfg(not(target_os = "solana"))]
+    pub fn shared(key: &Pubkey) -> Result<Self, ProgramError> {
+        BORROWS.with(|borrows| {
+            let mut borrows = borrows.borrow_mut();
+            match borrows.iter_mut().find(|(k, _)| k == key) {
+                Some((_, BorrowState::Exclusive)) => return Err(conflict()),
+                Some((_, BorrowState::Shared(count))) => *count += 1,
+                None => borrows.push((*key, BorrowState::Shared(1))),
+            }
+            Ok(Self { key: *key })
+        })
+    }
+
+    /// Registers a mutable borrow. Fails while the account is borrowed at all
+    /// through any `AccountInfo`.
+    #[cfg(not(target_os = "solana"))]
+    pub fn exclusive(key: &Pubkey) -> Result<Self, ProgramError> {
+        BORROWS.with(|borrows| {
+            let mut borrows = borrows.borrow_mut();
+            if borrows.iter().any(|(k, _)| k == key) {
+                return Err(conflict());
+            }
+            borrows.push((*key, BorrowState::Exclusive));
+            Ok(Self { key: *key })
+        })
+    }
+
+    /// On-chain the account's `RefCell` enforces the borrow.
+    #[cfg(target_os = "solana")]
+    pub fn shared(_key: &Pubkey) -> Result<Self, ProgramError> {
+        Ok(Self {})
+    }
+
+    /// On-chain the account's `RefCell` enforces the borrow.
+    #[cfg(target_os = "solana")]
+    pub fn exclusive(_key: &Pubkey) -> Result<Self, ProgramError> {
+        Ok(Self {})
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+impl Drop for BorrowGuard {
+    fn drop(&mut self) {
+        BORROWS.with(|borrows| {
+            let mut borrows = borrows.borrow_mut();
+            let Some(pos) = borrows.iter().position(|(k, _)| *k == self.key) else {
+                return;
+            };
+            match &mut borrows[pos].1 {
+                BorrowState::Shared(count) if *count > 1 => *count -= 1,
+                _ => {
+                    borrows.swap_remove(pos);
+                }
+            }
+        });
+    }
+}
+
+/// Whether the account with `key` is currently borrowed through an
+/// [`AccountLoader`](crate::AccountLoader).
+#[cfg(not(target_os = "solana"))]
+pub fn is_borrowed(key: &Pubkey) -> bool {
+    BORROWS.with(|borrows| borrows.borrow().iter().any(|(k, _)| k == key))
+}
+
+/// Maps `ProgramError::AccountBorrowFailed`, the failure of the account's own
+/// `RefCell`, to `ErrorCode::AccountBorrowConflict`.
+pub(crate) fn conflict_on_borrow_failure(err: ProgramError) -> ProgramError {
+    match err {
+        ProgramError::AccountBorrowFailed => conflict(),
+        err => err,
+    }
+}
+
+fn conflict() -> ProgramError {
+    ProgramError::Custom(ErrorCode::AccountBorrowConflict.into())
+}
@@ -0,0 +1,11 @@
+//! Helpers for the off-chain code generated by `#[derive(Accounts)]` with the
+//! `client` feature of `saturn-account-macros`.
+
+use arch_program::pubkey::Pubkey;
+
+/// Stand-in for an `AccountInfo` while building `AccountMeta`s, so that
+/// `seeds` expressions reading `other.key` evaluate the same off-chain.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyRef<'a> {
+    pub key: &'a Pubkey,
+}
@@ -23,7 +23,15 @@ impl BorshCodec {
         S: BorshDeserialize + BorshSerialize,
     {
         let data = account.try_borrow_data()?;
-        S::try_from_slice(&data).map_err(|_| ProgramError::InvalidAccountData)
+        S::try_from_slice(&data).map_err(|err| {
+            arch_program::msg!(
+                "account {}: cannot decode {}: {}",
+                account.key,
+                core::any::type_name::<S>(),
+                err
+            );
+            ProgramError::InvalidAccountData
+        })
     }
 
     /// Serialises `shard` back into `account.data` using Borsh.
@@ -45,6 +53,10 @@ impl BorshCodec {
 }
 
 /// Anchor-style Borsh account wrapper mirroring Anchor's `Account<'info, T>`.
+///
+/// Changes are written back on drop unless they were
+/// [`abort`](Self::abort)ed. A write on drop cannot report a failure, so it is
+/// only logged; call [`commit`](Self::commit) to handle it.
 #[allow(clippy::module_name_repetitions)]
 pub struct Account<'a, T>
 where
@@ -52,6 +64,8 @@ where
 {
     account: &'a AccountInfo<'a>,
     data: T,
+    /// Whether `data` may differ from what the account holds.
+    pending: bool,
 }
 
 impl<'a, T> Account<'a, T>
@@ -60,7 +74,57 @@ where
 {
     pub fn load(account: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
         let data = BorshCodec::load::<T>(account)?;
-        Ok(Self { account, data })
+        Ok(Self {
+            account,
+            data,
+            pending: true,
+        })
+    }
+
+    /// Wraps `account` without checking its owner, decoding a `T` from the
+    /// start of its data and ignoring the bytes that follow.
+    ///
+    /// Meant for accounts in the middle of their initialization (allocated
+    /// with more `space` than their value takes yet) or owned by a
+    /// cooperating program. The caller vouches for the account: any data a
+    /// `T` decodes from is accepted, whoever owns it. Changes are still
+    /// written back on drop, which the runtime only allows on accounts this
+    /// program owns.
+    pub fn try_from_unchecked(account: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
+        let data = {
+            let bytes = account.try_borrow_data()?;
+            T::deserialize(&mut &bytes[..]).map_err(|err| {
+                arch_program::msg!(
+                    "account {}: cannot decode {}: {}",
+                    account.key,
+                    core::any::type_name::<T>(),
+                    err
+                );
+                ProgramError::InvalidAccountData
+            })?
+        };
+        Ok(Self {
+            account,
+            data,
+            pending: true,
+        })
+    }
+
+    /// Writes the changes back to the account now. Later changes are written
+    /// back on drop as before.
+    pub fn commit(&mut self) -> Result<(), ProgramError> {
+        BorshCodec::store::<T>(self.account, &self.data)?;
+        self.pending = false;
+        Ok(())
+    }
+
+    /// Discards the changes made since the account was loaded or last
+    /// committed, decoding the value from the account again.
+    pub fn abort(&mut self) -> Result<(), ProgramError> {
+        self.data = T::deserialize(&mut &self.account.try_borrow_data()?[..])
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        self.pending = false;
+        Ok(())
     }
 
     /// Returns a reference to the underlying `AccountInfo` object.
@@ -84,6 +148,7 @@ where
         Ok(Self {
             account,
             data: default_val,
+            pending: true,
         })
     }
 }
@@ -104,6 +169,7 @@ where
     T: BorshSerialize + BorshDeserialize,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.pending = true;
         &mut self.data
     }
 }
@@ -113,7 +179,18 @@ where
     T: BorshSerialize + BorshDeserialize,
 {
     fn drop(&mut self) {
-        let _ = BorshCodec::store::<T>(self.account, &self.data);
+        // Writing back would revive an account closed by `close_account`.
+        if !self.pending || crate::is_closed(self.account) {
+            return;
+        }
+        if let Err(err) = BorshCodec::store::<T>(self.account, &self.data) {
+            arch_program::msg!(
+                "account {}: cannot write back {}: {}",
+                self.account.key,
+                core::any::type_name::<T>(),
+                err
+            );
+        }
     }
 }
 
@@ -126,3 +203,48 @@ where
         self.account
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::account_info;
+    use arch_program::pubkey::Pubkey;
+
+    fn u64_account(value: u64) -> &'static AccountInfo<'static> {
+        account_info(
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            &value.to_le_bytes(),
+            arch_program::account::MIN_ACCOUNT_LAMPORTS,
+            0,
+        )
+    }
+
+    #[test]
+    fn commit_writes_now_and_abort_restores_the_stored_value() {
+        let info = u64_account(1);
+        let mut account = Account::<u64>::load(info).unwrap();
+
+        *account = 2;
+        account.commit().unwrap();
+        assert_eq!(*info.data.borrow(), 2u64.to_le_bytes());
+
+        *account = 3;
+        account.abort().unwrap();
+        assert_eq!(*account, 2);
+
+        drop(account);
+        assert_eq!(*info.data.borrow(), 2u64.to_le_bytes());
+    }
+
+    #[test]
+    fn changes_are_written_back_on_drop() {
+        let info = u64_account(1);
+        let mut account = Account::<u64>::load(info).unwrap();
+        account.commit().unwrap();
+
+        *account = 5;
+        drop(account);
+        assert_eq!(*info.data.borrow(), 5u64.to_le_bytes());
+    }
+}
@@ -0,0 +1,150 @@
+//! Deferred account deserialization.
+//!
+//! [`Lazy`] keeps the account around undecoded and only runs the wrapped
+//! codec the first time the data is accessed. Instructions declaring many
+//! accounts but touching few of them on a given path then only pay for the
+//! ones they read.
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+
+use super::borsh::Account;
+
+/// Account wrappers that can be loaded on demand by [`Lazy`].
+pub trait LazyLoad: Sized {
+    /// Handle the wrapper is loaded from.
+    type Info: Copy;
+
+    /// Decodes the wrapper from `info`.
+    fn load(info: Self::Info) -> Result<Self, ProgramError>;
+}
+
+impl<'a, T> LazyLoad for Account<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    type Info = &'a AccountInfo<'a>;
+
+    fn load(info: Self::Info) -> Result<Self, ProgramError> {
+        Account::load(info)
+    }
+}
+
+/// Account whose data is decoded on first access, e.g.
+/// `Lazy<Account<'info, T>>`.
+///
+/// Nothing is decoded until [`get`](Self::get) or [`get_mut`](Self::get_mut)
+/// is called; the decoded value is then kept for later calls. Wrappers writing
+/// their data back on drop (like [`Account`]) only do so once loaded.
+pub struct Lazy<A: LazyLoad> {
+    info: A::Info,
+    loaded: OnceCell<A>,
+}
+
+impl<A: LazyLoad> Lazy<A> {
+    /// Wraps `info` without decoding it.
+    pub fn new(info: A::Info) -> Self {
+        Self {
+            info,
+            loaded: OnceCell::new(),
+        }
+    }
+
+    /// Returns the decoded account, decoding it on the first call.
+    pub fn get(&self) -> Result<&A, ProgramError> {
+        match self.loaded.get() {
+            Some(loaded) => Ok(loaded),
+            None => {
+                let loaded = A::load(self.info)?;
+                Ok(self.loaded.get_or_init(|| loaded))
+            }
+        }
+    }
+
+    /// Mutable counterpart of [`get`](Self::get).
+    pub fn get_mut(&mut self) -> Result<&mut A, ProgramError> {
+        if self.loaded.get().is_none() {
+            let loaded = A::load(self.info)?;
+            let _ = self.loaded.set(loaded);
+        }
+        self.loaded
+            .get_mut()
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Whether the account has been decoded yet.
+    pub fn is_loaded(&self) -> bool {
+        self.loaded.get().is_some()
+    }
+}
+
+impl<'a, A> Lazy<A>
+where
+    A: LazyLoad<Info = &'a AccountInfo<'a>>,
+{
+    /// Returns a reference to the underlying `AccountInfo` object.
+    pub fn info(&self) -> &'a AccountInfo<'a> {
+        self.info
+    }
+}
+
+// Allow treating `Lazy` as an `AccountInfo` via `AsRef` without decoding it.
+impl<'a, A> AsRef<AccountInfo<'a>> for Lazy<A>
+where
+    A: LazyLoad<Info = &'a AccountInfo<'a>>,
+{
+    fn as_ref(&self) -> &AccountInfo<'a> {
+        self.info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::account_info;
+    use arch_program::pubkey::Pubkey;
+
+    fn account(data: &[u8]) -> &'static AccountInfo<'static> {
+        account_info(
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            data,
+            arch_program::account::MIN_ACCOUNT_LAMPORTS,
+            0,
+        )
+    }
+
+    #[test]
+    fn decodes_on_first_access_only() {
+        // Three bytes cannot hold a `u64`: only decoding can fail.
+        let lazy = Lazy::<Account<u64>>::new(account(&[1, 2, 3]));
+        assert!(!lazy.is_loaded());
+        assert_eq!(lazy.get().err(), Some(ProgramError::InvalidAccountData));
+        assert!(!lazy.is_loaded());
+
+        let info = account(&9u64.to_le_bytes());
+        let lazy = Lazy::<Account<u64>>::new(info);
+        assert_eq!(**lazy.get().unwrap(), 9);
+        assert!(lazy.is_loaded());
+
+        // Later accesses reuse the decoded value.
+        info.data.borrow_mut().copy_from_slice(&10u64.to_le_bytes());
+        assert_eq!(**lazy.get().unwrap(), 9);
+    }
+
+    #[test]
+    fn writes_back_only_once_loaded() {
+        let info = account(&1u64.to_le_bytes());
+        let lazy = Lazy::<Account<u64>>::new(info);
+        info.data.borrow_mut().copy_from_slice(&2u64.to_le_bytes());
+        drop(lazy);
+        assert_eq!(*info.data.borrow(), 2u64.to_le_bytes());
+
+        let mut lazy = Lazy::<Account<u64>>::new(info);
+        **lazy.get_mut().unwrap() = 3;
+        drop(lazy);
+        assert_eq!(*info.data.borrow(), 3u64.to_le_bytes());
+    }
+}
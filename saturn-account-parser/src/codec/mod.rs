@@ -4,9 +4,15 @@
 //! * [`borsh`] – copy-based Borsh (de)serialization.
 //! * [`zero_copy`] – zero-copy reinterpretation into Plain-Old-Data structs for
 //!   maximum performance on-chain.
+//! * [`zero_copy_slice`] – a length-prefixed array of zero-copy elements.
+//! * [`lazy`] – defers another codec until the account is first accessed.
 
 pub mod borsh;
+pub mod lazy;
 pub mod zero_copy;
+pub mod zero_copy_slice;
 
 pub use borsh::{Account, BorshCodec};
-pub use zero_copy::{AccountLoader, ZeroCopyCodec};
+pub use lazy::{Lazy, LazyLoad};
+pub use zero_copy::{AccountLoader, AccountRef, AccountRefMut, AccountWriteGuard, ZeroCopyCodec};
+pub use zero_copy_slice::{ZeroCopySlice, ZeroCopySliceIter};
@@ -7,17 +7,24 @@
 //!
 //! The primary entry points are [`ZeroCopyCodec`] for bare loading/storing and
 //! [`AccountLoader`] for an Anchor-style wrapper that
-//! tracks borrows at runtime.
+//! tracks borrows at runtime, across every `AccountInfo` of the same account
+//! (see [`crate::borrow_registry`]).
 
 use arch_program::account::AccountInfo;
 use arch_program::program_error::ProgramError;
+use arch_program::pubkey::Pubkey;
 use bytemuck::{Pod, Zeroable};
+use core::any::type_name;
 use core::mem::{align_of, size_of};
+use core::ops::Range;
 use std::cell::{Ref, RefMut};
 
+use crate::borrow_registry::{conflict_on_borrow_failure, BorrowGuard};
+use crate::error::{account_error, ErrorCode};
+
 /// Length in bytes of the account discriminator that prefixes every
 /// zero-copy account.
-const DISCRIMINATOR_LEN: usize = 8;
+pub(crate) const DISCRIMINATOR_LEN: usize = 8;
 
 /// Every zero-copy account type must provide an 8-byte discriminator that
 /// uniquely identifies its layout on-chain.  It mirrors Anchor’s convention
@@ -30,6 +37,10 @@ pub trait Discriminator {
 
 /// Zero-copy codec: re-interprets the account data buffer as a `T` without
 /// performing any heap allocations or copies.
+///
+/// The data of `S` is expected right after its `Discriminator`; the
+/// `*_with_discriminator` variants expect it after another prefix instead,
+/// e.g. none at all for accounts of foreign programs.
 pub struct ZeroCopyCodec;
 
 impl ZeroCopyCodec {
@@ -38,35 +49,42 @@ impl ZeroCopyCodec {
     where
         S: Pod + Zeroable + Clone + Discriminator,
     {
-        let data = account.try_borrow_data()?;
-        if data.len() < DISCRIMINATOR_LEN + size_of::<S>() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        // Verify discriminator matches.
-        if &data[..DISCRIMINATOR_LEN] != &<S as Discriminator>::DISCRIMINATOR {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        Self::load_copy_with_discriminator(account, &S::DISCRIMINATOR)
+    }
 
-        // SAFETY: bounds checked above + `S` is Pod.
-        let bytes = &data[DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + size_of::<S>()];
-        Ok(*bytemuck::from_bytes(bytes))
+    /// [`load_copy`](Self::load_copy) of data prefixed by `discriminator`.
+    pub fn load_copy_with_discriminator<S>(
+        account: &AccountInfo<'_>,
+        discriminator: &[u8],
+    ) -> Result<S, ProgramError>
+    where
+        S: Pod + Zeroable + Clone,
+    {
+        let data = account.try_borrow_data()?;
+        let range = data_range::<S>(account.key, &data, discriminator)?;
+        Ok(bytemuck::pod_read_unaligned(&data[range]))
     }
 
     /// Stores an owned value back into the account's data buffer.
     pub fn store_copy<S>(account: &AccountInfo<'_>, shard: &S) -> Result<(), ProgramError>
     where
         S: Pod + Zeroable + Discriminator,
+    {
+        Self::store_copy_with_discriminator(account, &S::DISCRIMINATOR, shard)
+    }
+
+    /// [`store_copy`](Self::store_copy) of data prefixed by `discriminator`.
+    pub fn store_copy_with_discriminator<S>(
+        account: &AccountInfo<'_>,
+        discriminator: &[u8],
+        shard: &S,
+    ) -> Result<(), ProgramError>
+    where
+        S: Pod + Zeroable,
     {
         let mut data = account.try_borrow_mut_data()?;
-        if data.len() < DISCRIMINATOR_LEN + size_of::<S>() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if &data[..DISCRIMINATOR_LEN] != &<S as Discriminator>::DISCRIMINATOR {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        // SAFETY: same as in `load_copy`.
-        let bytes = &mut data[DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + size_of::<S>()];
-        bytes.copy_from_slice(bytemuck::bytes_of(shard));
+        let range = data_range::<S>(account.key, &data, discriminator)?;
+        data[range].copy_from_slice(bytemuck::bytes_of(shard));
         Ok(())
     }
 
@@ -74,32 +92,34 @@ impl ZeroCopyCodec {
     pub fn load_mut_ref<'a, S>(account: &'a AccountInfo<'a>) -> Result<RefMut<'a, S>, ProgramError>
     where
         S: Pod + Zeroable + Discriminator + 'static,
+    {
+        Self::load_mut_ref_with_discriminator(account, &S::DISCRIMINATOR)
+    }
+
+    /// [`load_mut_ref`](Self::load_mut_ref) of data prefixed by `discriminator`.
+    pub fn load_mut_ref_with_discriminator<'a, S>(
+        account: &'a AccountInfo<'a>,
+        discriminator: &[u8],
+    ) -> Result<RefMut<'a, S>, ProgramError>
+    where
+        S: Pod + Zeroable + 'static,
     {
         // Disallow mutable access when the account was not marked writable by the caller.
         if !account.is_writable {
             return Err(ProgramError::Custom(
-                crate::error::ErrorCode::IncorrectIsWritableFlag.into(),
+                ErrorCode::IncorrectIsWritableFlag.into(),
             ));
         }
 
         let data = account.try_borrow_mut_data()?;
-
-        if data.len() < DISCRIMINATOR_LEN + size_of::<S>() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        if &data[..DISCRIMINATOR_LEN] != &<S as Discriminator>::DISCRIMINATOR {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        let range = data_range::<S>(account.key, &data, discriminator)?;
 
         // Ensure proper alignment (after discriminator offset).
-        if (data[DISCRIMINATOR_LEN..].as_ptr() as usize) % align_of::<S>() != 0 {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_alignment::<S>(account.key, &data[range.start..])?;
 
         // SAFETY: alignment + size checks above guarantee safe reinterpretation.
         let ref_mut = RefMut::map(data, |slice| {
-            let slice = &mut slice[DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + size_of::<S>()];
+            let slice = &mut slice[range];
             unsafe { &mut *(slice.as_mut_ptr() as *mut S) }
         });
 
@@ -111,30 +131,83 @@ impl ZeroCopyCodec {
     where
         S: Pod + Zeroable + Discriminator + 'static,
     {
-        let data = account.try_borrow_data()?;
-
-        if data.len() < DISCRIMINATOR_LEN + size_of::<S>() {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        Self::load_ref_with_discriminator(account, &S::DISCRIMINATOR)
+    }
 
-        if &data[..DISCRIMINATOR_LEN] != &<S as Discriminator>::DISCRIMINATOR {
-            return Err(ProgramError::InvalidAccountData);
-        }
+    /// [`load_ref`](Self::load_ref) of data prefixed by `discriminator`.
+    pub fn load_ref_with_discriminator<'a, S>(
+        account: &'a AccountInfo<'a>,
+        discriminator: &[u8],
+    ) -> Result<Ref<'a, S>, ProgramError>
+    where
+        S: Pod + Zeroable + 'static,
+    {
+        let data = account.try_borrow_data()?;
+        let range = data_range::<S>(account.key, &data, discriminator)?;
 
         // Ensure proper alignment.
-        if (data[DISCRIMINATOR_LEN..].as_ptr() as usize) % align_of::<S>() != 0 {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_alignment::<S>(account.key, &data[range.start..])?;
 
         // SAFETY: same guarantees as `load_mut_ref`, but immutable.
         let ref_imm = Ref::map(data, |slice| {
-            let slice = &slice[DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + size_of::<S>()];
+            let slice = &slice[range];
             unsafe { &*(slice.as_ptr() as *const S) }
         });
         Ok(ref_imm)
     }
 }
 
+/// Range of `data` holding the `S` that follows `discriminator`, once `data`
+/// is long enough and starts with `discriminator`.
+///
+/// Fails with `ErrorCode::WrongDiscriminator` or
+/// `ErrorCode::AccountTooSmallForType`, logging the account `key`.
+fn data_range<S>(
+    key: &Pubkey,
+    data: &[u8],
+    discriminator: &[u8],
+) -> Result<Range<usize>, ProgramError> {
+    let start = discriminator.len();
+    let end = start + size_of::<S>();
+    if data.len() < end {
+        return Err(account_error(
+            key,
+            ErrorCode::AccountTooSmallForType,
+            format_args!("{} bytes, {} needs {}", data.len(), type_name::<S>(), end),
+        ));
+    }
+    if &data[..start] != discriminator {
+        return Err(account_error(
+            key,
+            ErrorCode::WrongDiscriminator,
+            format_args!(
+                "expected discriminator {:?} of {}, found {:?}",
+                discriminator,
+                type_name::<S>(),
+                &data[..start]
+            ),
+        ));
+    }
+    Ok(start..end)
+}
+
+/// Fails with `ErrorCode::MisalignedAccountData`, logging the account `key`,
+/// when `data` cannot be reinterpreted in place as an `S`.
+fn check_alignment<S>(key: &Pubkey, data: &[u8]) -> Result<(), ProgramError> {
+    if (data.as_ptr() as usize) % align_of::<S>() != 0 {
+        return Err(account_error(
+            key,
+            ErrorCode::MisalignedAccountData,
+            format_args!(
+                "data of {} not aligned to {} bytes",
+                type_name::<S>(),
+                align_of::<S>()
+            ),
+        ));
+    }
+    Ok(())
+}
+
 // -----------------------------------------------------------------------------
 // Anchor-style zero-copy account loader
 // -----------------------------------------------------------------------------
@@ -142,13 +215,15 @@ impl ZeroCopyCodec {
 #[allow(clippy::module_name_repetitions)]
 pub struct AccountLoader<'a, T>
 where
-    T: Pod + Zeroable + Discriminator + 'static,
+    T: Pod + Zeroable + 'static,
 {
     account: &'a AccountInfo<'a>,
+    /// Prefix of the data: `T::DISCRIMINATOR` unless created
+    /// [`with_discriminator`](Self::with_discriminator).
+    prefix: DataPrefix,
     _phantom: core::marker::PhantomData<T>,
 }
 
-// ---------------- Generic helper methods ----------------
 impl<'a, T> AccountLoader<'a, T>
 where
     T: Pod + Zeroable + Discriminator + 'static,
@@ -157,13 +232,71 @@ where
     pub fn new(account: &'a AccountInfo<'a>) -> Self {
         Self {
             account,
+            prefix: DataPrefix::Discriminator(T::DISCRIMINATOR),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Wraps `account` without checking its owner or discriminator: loads read
+    /// the `T` after whatever [`DISCRIMINATOR_LEN`] bytes the data starts with.
+    ///
+    /// Meant for accounts whose discriminator is not written yet (in the
+    /// middle of their initialization) or written by a cooperating program.
+    /// The caller vouches for the content: any account of the right length
+    /// loads, so an account of another type, or owned by an attacker, is read
+    /// as a `T`. Fails with `ErrorCode::AccountTooSmallForType` when the data
+    /// cannot hold the prefix and a `T`; alignment is still checked by loads,
+    /// and [`load_init`](Self::load_init) still writes `T::DISCRIMINATOR`.
+    pub fn try_from_unchecked(account: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
+        let loader = Self {
+            account,
+            prefix: DataPrefix::Unchecked(T::DISCRIMINATOR),
+            _phantom: core::marker::PhantomData,
+        };
+        loader.prefix.with_expected(account, |prefix| {
+            data_range::<T>(account.key, &account.try_borrow_data()?, prefix).map(drop)
+        })?;
+        Ok(loader)
+    }
+}
+
+// ---------------- Generic helper methods ----------------
+impl<'a, T> AccountLoader<'a, T>
+where
+    T: Pod + Zeroable + 'static,
+{
+    /// Creates a loader for data prefixed by `discriminator` rather than
+    /// `T::DISCRIMINATOR`, e.g. `&[]` for accounts of foreign programs that
+    /// store the bare struct.
+    pub fn with_discriminator(account: &'a AccountInfo<'a>, discriminator: &'static [u8]) -> Self {
+        Self {
+            account,
+            prefix: DataPrefix::Custom(discriminator),
             _phantom: core::marker::PhantomData,
         }
     }
 
     /// Immutable borrow of the underlying zero-copy struct.
-    pub fn load(&self) -> Result<Ref<'a, T>, ProgramError> {
-        ZeroCopyCodec::load_ref::<T>(self.account)
+    ///
+    /// Fails with `ErrorCode::AccountBorrowConflict` while the account is
+    /// mutably borrowed, even through another `AccountInfo` with the same key.
+    pub fn load(&self) -> Result<AccountRef<'a, T>, ProgramError> {
+        let guard = BorrowGuard::shared(self.account.key)?;
+        let value = self
+            .prefix
+            .with_expected(self.account, |prefix| {
+                ZeroCopyCodec::load_ref_with_discriminator::<T>(self.account, prefix)
+            })
+            .map_err(conflict_on_borrow_failure)?;
+        Ok(AccountRef {
+            value,
+            _guard: guard,
+        })
+    }
+
+    /// Bytes the data of the account starts with.
+    pub(crate) fn data_prefix(&self) -> &[u8] {
+        self.prefix.bytes()
     }
 
     /// Direct access to the wrapped `AccountInfo`.
@@ -172,29 +305,132 @@ where
     }
 
     /// Mutable borrow of the underlying zero-copy struct.
-    pub fn load_mut(&self) -> Result<RefMut<'a, T>, ProgramError> {
-        ZeroCopyCodec::load_mut_ref::<T>(self.account)
+    ///
+    /// Fails with `ErrorCode::AccountBorrowConflict` while the account is
+    /// borrowed, even through another `AccountInfo` with the same key.
+    pub fn load_mut(&self) -> Result<AccountRefMut<'a, T>, ProgramError> {
+        let guard = BorrowGuard::exclusive(self.account.key)?;
+        let value = self
+            .prefix
+            .with_expected(self.account, |prefix| {
+                ZeroCopyCodec::load_mut_ref_with_discriminator::<T>(self.account, prefix)
+            })
+            .map_err(conflict_on_borrow_failure)?;
+        Ok(AccountRefMut {
+            value,
+            _guard: guard,
+        })
+    }
+
+    /// Copy of the underlying struct that is written back to the account when
+    /// the returned guard is dropped or [`AccountWriteGuard::commit`]ted, and
+    /// discarded by [`AccountWriteGuard::abort`].
+    ///
+    /// The account stays exclusively borrowed while the guard lives, and other
+    /// borrows of it fail with `ErrorCode::AccountBorrowConflict` as for
+    /// [`load_mut`](Self::load_mut).
+    pub fn load_mut_guard(&self) -> Result<AccountWriteGuard<'a, T>, ProgramError> {
+        if !self.account.is_writable {
+            return Err(ProgramError::Custom(
+                ErrorCode::IncorrectIsWritableFlag.into(),
+            ));
+        }
+        let guard = BorrowGuard::exclusive(self.account.key)?;
+        let value = self
+            .prefix
+            .with_expected(self.account, |prefix| {
+                ZeroCopyCodec::load_copy_with_discriminator::<T>(self.account, prefix)
+            })
+            .map_err(conflict_on_borrow_failure)?;
+        Ok(AccountWriteGuard {
+            account: self.account,
+            prefix: self.prefix,
+            value,
+            pending: true,
+            _guard: guard,
+        })
     }
 
     /// Initialises a brand-new zero-copy account (resize + zero-fill) and returns a mutable reference to it.
-    pub fn load_init(&self) -> Result<RefMut<'a, T>, ProgramError>
+    pub fn load_init(&self) -> Result<AccountRefMut<'a, T>, ProgramError>
     where
         T: Default,
     {
-        Self::init_zero_copy_account(self.account)
+        let guard = BorrowGuard::exclusive(self.account.key)?;
+        Ok(AccountRefMut {
+            value: self
+                .init_zero_copy_account()
+                .map_err(conflict_on_borrow_failure)?,
+            _guard: guard,
+        })
+    }
+
+    /// Resizes the account to `new_space` bytes of data (prefix included),
+    /// e.g. to make room for data stored after `T`.
+    ///
+    /// Added bytes are zeroed and the discriminator is kept. When growing,
+    /// `payer` tops the account up to the minimum balance of its new length;
+    /// when shrinking, the lamports it no longer needs go back to `payer`. The
+    /// account must then be rent exempt. Fails with
+    /// `ProgramError::AccountDataTooSmall` when `new_space` cannot hold the
+    /// prefix and a `T`.
+    pub fn realloc(&self, new_space: usize, payer: &AccountInfo<'a>) -> Result<(), ProgramError> {
+        let account = self.account;
+        if !account.is_writable {
+            return Err(ProgramError::Custom(
+                ErrorCode::IncorrectIsWritableFlag.into(),
+            ));
+        }
+        let prefix = self.prefix.bytes();
+        if new_space < prefix.len() + size_of::<T>() {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        let _guard = BorrowGuard::exclusive(account.key)?;
+        self.prefix
+            .with_expected(account, |expected| {
+                data_range::<T>(account.key, &account.try_borrow_data()?, expected).map(drop)
+            })
+            .map_err(conflict_on_borrow_failure)?;
+
+        let old_len = account.data_len();
+        if new_space != old_len {
+            account.realloc(new_space, true)?;
+        }
+
+        let required = crate::minimum_balance(new_space);
+        let current = account.lamports();
+        if new_space > old_len && current < required {
+            arch_program::program::invoke(
+                &arch_program::system_instruction::transfer(
+                    payer.key,
+                    account.key,
+                    required - current,
+                ),
+                &[payer.clone(), account.clone()],
+            )?;
+        } else if new_space < old_len && current > required {
+            let payer_balance = payer
+                .lamports()
+                .checked_add(current - required)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            **payer.try_borrow_mut_lamports()? = payer_balance;
+            **account.try_borrow_mut_lamports()? = required;
+        }
+
+        crate::check_rent_exempt(account)
     }
 
     /// Allocates and zero-initialises an account for zero-copy usage and
     /// returns a mutable reference to the freshly created struct.
-    fn init_zero_copy_account<'info>(
-        account_info: &'info AccountInfo<'info>,
-    ) -> Result<RefMut<'info, T>, ProgramError>
+    fn init_zero_copy_account(&self) -> Result<RefMut<'a, T>, ProgramError>
     where
         T: Default,
     {
+        let account_info = self.account;
+        let discriminator_len = self.prefix.bytes().len();
         let size = core::mem::size_of::<T>();
 
-        let total_size = size + DISCRIMINATOR_LEN;
+        let total_size = size + discriminator_len;
 
         // (Re)allocate the account to the exact size and make it rent-exempt.
         account_info.realloc(total_size, true)?;
@@ -207,16 +443,16 @@ where
             }
 
             // write discriminator
-            data[..DISCRIMINATOR_LEN].copy_from_slice(&<T as Discriminator>::DISCRIMINATOR);
+            data[..discriminator_len].copy_from_slice(self.prefix.bytes());
 
             // zero-fill struct bytes
-            for byte in &mut data[DISCRIMINATOR_LEN..total_size] {
+            for byte in &mut data[discriminator_len..total_size] {
                 *byte = 0;
             }
         }
 
         // Return a mutable zero-copy reference.
-        ZeroCopyCodec::load_mut_ref::<T>(account_info)
+        ZeroCopyCodec::load_mut_ref_with_discriminator::<T>(account_info, self.prefix.bytes())
     }
 
     /// Adjusts the account's data buffer to exactly `size_of::<T>()` bytes and
@@ -234,12 +470,336 @@ where
     }
 }
 
+/// Bytes an [`AccountLoader`]'s data starts with.
+#[derive(Clone, Copy)]
+enum DataPrefix {
+    /// The `Discriminator` of the loaded type.
+    Discriminator([u8; DISCRIMINATOR_LEN]),
+    /// Set with [`AccountLoader::with_discriminator`].
+    Custom(&'static [u8]),
+    /// Set with [`AccountLoader::try_from_unchecked`]: the `Discriminator` of
+    /// the loaded type, written but never checked.
+    Unchecked([u8; DISCRIMINATOR_LEN]),
+}
+
+impl DataPrefix {
+    /// Bytes written in front of the data.
+    fn bytes(&self) -> &[u8] {
+        match self {
+            DataPrefix::Discriminator(bytes) => bytes,
+            DataPrefix::Custom(bytes) => bytes,
+            DataPrefix::Unchecked(bytes) => bytes,
+        }
+    }
+
+    /// Runs `f` with the bytes the data of `account` must start with: those
+    /// it already starts with when unchecked.
+    fn with_expected<R>(
+        &self,
+        account: &AccountInfo<'_>,
+        f: impl FnOnce(&[u8]) -> Result<R, ProgramError>,
+    ) -> Result<R, ProgramError> {
+        let DataPrefix::Unchecked(_) = self else {
+            return f(self.bytes());
+        };
+        let mut found = [0u8; DISCRIMINATOR_LEN];
+        {
+            let data = account.try_borrow_data()?;
+            let len = data.len().min(DISCRIMINATOR_LEN);
+            found[..len].copy_from_slice(&data[..len]);
+        }
+        f(&found)
+    }
+}
+
+/// Immutable borrow returned by [`AccountLoader::load`], registered with the
+/// borrow registry until dropped.
+pub struct AccountRef<'a, T> {
+    // Declared first so the data borrow ends before the registration.
+    value: Ref<'a, T>,
+    _guard: BorrowGuard,
+}
+
+impl<T> core::ops::Deref for AccountRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// Mutable borrow returned by [`AccountLoader::load_mut`] and
+/// [`AccountLoader::load_init`], registered with the borrow registry until
+/// dropped.
+pub struct AccountRefMut<'a, T> {
+    // Declared first so the data borrow ends before the registration.
+    value: RefMut<'a, T>,
+    _guard: BorrowGuard,
+}
+
+impl<T> core::ops::Deref for AccountRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> core::ops::DerefMut for AccountRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+/// Guard returned by [`AccountLoader::load_mut_guard`]: a copy of the account
+/// data, written back when the guard is dropped unless it was aborted.
+///
+/// A write on drop cannot report a failure, so it is only logged; call
+/// [`commit`](Self::commit) to handle it.
+pub struct AccountWriteGuard<'a, T>
+where
+    T: Pod + Zeroable + 'static,
+{
+    account: &'a AccountInfo<'a>,
+    prefix: DataPrefix,
+    value: T,
+    /// Whether `value` still has to be written back.
+    pending: bool,
+    _guard: BorrowGuard,
+}
+
+impl<T> AccountWriteGuard<'_, T>
+where
+    T: Pod + Zeroable + 'static,
+{
+    /// Writes the changes back to the account now.
+    pub fn commit(mut self) -> Result<(), ProgramError> {
+        self.pending = false;
+        self.prefix.with_expected(self.account, |prefix| {
+            ZeroCopyCodec::store_copy_with_discriminator::<T>(self.account, prefix, &self.value)
+        })
+    }
+
+    /// Discards the changes, leaving the account data untouched.
+    pub fn abort(mut self) {
+        self.pending = false;
+    }
+}
+
+impl<T> core::ops::Deref for AccountWriteGuard<'_, T>
+where
+    T: Pod + Zeroable + 'static,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> core::ops::DerefMut for AccountWriteGuard<'_, T>
+where
+    T: Pod + Zeroable + 'static,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> Drop for AccountWriteGuard<'_, T>
+where
+    T: Pod + Zeroable + 'static,
+{
+    fn drop(&mut self) {
+        if !self.pending {
+            return;
+        }
+        let stored = self.prefix.with_expected(self.account, |prefix| {
+            ZeroCopyCodec::store_copy_with_discriminator::<T>(self.account, prefix, &self.value)
+        });
+        if let Err(err) = stored {
+            arch_program::msg!(
+                "account {}: cannot write back {}: {}",
+                self.account.key,
+                core::any::type_name::<T>(),
+                err
+            );
+        }
+    }
+}
+
 // Allow treating `AccountLoader` (any mutability) as an `AccountInfo` via `AsRef`.
 impl<'a, T> AsRef<arch_program::account::AccountInfo<'a>> for AccountLoader<'a, T>
 where
-    T: Pod + Zeroable + Discriminator + 'static,
+    T: Pod + Zeroable + 'static,
 {
     fn as_ref(&self) -> &arch_program::account::AccountInfo<'a> {
         self.account
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{account_info, counter_account, counter_data, Counter};
+
+    fn conflict() -> ProgramError {
+        ProgramError::Custom(ErrorCode::AccountBorrowConflict.into())
+    }
+
+    /// Second `AccountInfo` for the account behind `info`, with its own cell.
+    fn alias(info: &AccountInfo<'static>, value: u64) -> &'static AccountInfo<'static> {
+        account_info(
+            *info.key,
+            Pubkey::default(),
+            &counter_data(value),
+            info.lamports(),
+            0,
+        )
+    }
+
+    #[test]
+    fn double_load_mut_through_aliases_conflicts() {
+        let info = counter_account(1);
+        let first = AccountLoader::<Counter>::new(info);
+        let second = AccountLoader::<Counter>::new(alias(info, 1));
+
+        let borrow = first.load_mut().unwrap();
+        assert_eq!(second.load_mut().err(), Some(conflict()));
+        assert_eq!(first.load_mut().err(), Some(conflict()));
+
+        drop(borrow);
+        assert_eq!(second.load_mut().unwrap().value, 1);
+    }
+
+    #[test]
+    fn load_while_mutably_borrowed_conflicts() {
+        let info = counter_account(7);
+        let first = AccountLoader::<Counter>::new(info);
+        let second = AccountLoader::<Counter>::new(alias(info, 7));
+
+        let mut borrow = first.load_mut().unwrap();
+        borrow.value = 8;
+        assert_eq!(second.load().err(), Some(conflict()));
+
+        drop(borrow);
+        assert_eq!(first.load().unwrap().value, 8);
+        assert_eq!(second.load().unwrap().value, 7);
+    }
+
+    #[test]
+    fn load_mut_while_loaded_through_an_alias_conflicts() {
+        let info = counter_account(3);
+        let first = AccountLoader::<Counter>::new(info);
+        let second = AccountLoader::<Counter>::new(alias(info, 3));
+
+        let borrow = first.load().unwrap();
+        assert_eq!(second.load().unwrap().value, 3);
+        assert_eq!(second.load_mut().err(), Some(conflict()));
+        assert_eq!(second.load_mut_guard().err(), Some(conflict()));
+
+        drop(borrow);
+        second.load_mut().unwrap().value = 4;
+        assert_eq!(second.load().unwrap().value, 4);
+    }
+
+    #[test]
+    fn borrow_failure_of_the_account_cell_reports_a_conflict() {
+        let info = counter_account(1);
+        let loader = AccountLoader::<Counter>::new(info);
+
+        // Borrowed around the loader, as the shared cell of every duplicate
+        // of an account is on-chain.
+        let data = info.try_borrow_mut_data().unwrap();
+        assert_eq!(loader.load().err(), Some(conflict()));
+        drop(data);
+        let data = info.try_borrow_data().unwrap();
+        assert_eq!(loader.load_mut().err(), Some(conflict()));
+        drop(data);
+
+        assert_eq!(loader.load().unwrap().value, 1);
+    }
+
+    #[test]
+    fn write_guard_commits_aborts_and_writes_back_on_drop() {
+        let info = counter_account(1);
+        let loader = AccountLoader::<Counter>::new(info);
+
+        let mut guard = loader.load_mut_guard().unwrap();
+        guard.value = 2;
+        // The copy is only written back on commit.
+        assert_eq!(&info.data.borrow()[8..], &1u64.to_le_bytes());
+        guard.commit().unwrap();
+        assert_eq!(*info.data.borrow(), counter_data(2));
+
+        let mut guard = loader.load_mut_guard().unwrap();
+        guard.value = 3;
+        guard.abort();
+        assert_eq!(*info.data.borrow(), counter_data(2));
+
+        let mut guard = loader.load_mut_guard().unwrap();
+        guard.value = 4;
+        assert_eq!(loader.load().err(), Some(conflict()));
+        drop(guard);
+        assert_eq!(*info.data.borrow(), counter_data(4));
+    }
+
+    #[test]
+    fn realloc_grows_with_zeroed_bytes() {
+        let min = arch_program::account::MIN_ACCOUNT_LAMPORTS;
+        let info = account_info(
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            &counter_data(1),
+            min,
+            16,
+        );
+        let payer = account_info(Pubkey::new_unique(), Pubkey::default(), &[], 1_000, 0);
+
+        AccountLoader::<Counter>::new(info)
+            .realloc(32, payer)
+            .unwrap();
+
+        let data = info.data.borrow();
+        assert_eq!(data.len(), 32);
+        assert_eq!(&data[..16], counter_data(1));
+        assert!(data[16..].iter().all(|byte| *byte == 0));
+        assert_eq!((info.lamports(), payer.lamports()), (min, 1_000));
+    }
+
+    #[test]
+    fn realloc_shrinks_and_refunds_the_payer() {
+        let min = arch_program::account::MIN_ACCOUNT_LAMPORTS;
+        let mut data = counter_data(1);
+        data.extend_from_slice(&[7; 16]);
+        let info = account_info(Pubkey::new_unique(), Pubkey::default(), &data, 5_000, 0);
+        let payer = account_info(Pubkey::new_unique(), Pubkey::default(), &[], 1_000, 0);
+        let loader = AccountLoader::<Counter>::new(info);
+
+        assert_eq!(
+            loader.realloc(15, payer),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+        loader.realloc(16, payer).unwrap();
+
+        assert_eq!(*info.data.borrow(), counter_data(1));
+        assert_eq!(info.lamports(), min);
+        assert_eq!(payer.lamports(), 1_000 + 5_000 - min);
+    }
+
+    #[test]
+    fn write_guard_refuses_read_only_accounts() {
+        let info = AccountInfo {
+            is_writable: false,
+            ..counter_account(1).clone()
+        };
+        let info: &'static AccountInfo<'static> = Box::leak(Box::new(info));
+
+        assert_eq!(
+            AccountLoader::<Counter>::new(info).load_mut_guard().err(),
+            Some(ProgramError::Custom(
+                ErrorCode::IncorrectIsWritableFlag.into()
+            ))
+        );
+    }
+}
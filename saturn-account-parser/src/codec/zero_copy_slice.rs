@@ -0,0 +1,420 @@
+//! Zero-copy accounts holding a variable number of elements.
+//!
+//! [`ZeroCopySlice`] wraps an account laid out as
+//! `[discriminator][u32 len][T; capacity]`: the capacity follows from the size
+//! of the account and `len` counts the elements in use. It suits order books
+//! and queues, which do not fit the fixed struct of an
+//! [`AccountLoader`](super::AccountLoader).
+//!
+//! Elements follow a 12-byte header, so they are copied in and out (like
+//! [`ZeroCopyCodec::load_copy`](super::ZeroCopyCodec::load_copy)) rather than
+//! borrowed, whatever their alignment.
+//!
+//! ```ignore
+//! let orders = ZeroCopySlice::<Order>::new(&order_book);
+//! orders.push(Order { price, size })?;
+//! orders.retain(|order| order.size > 0)?;
+//! let best = orders.iter()?.max_by_key(|order| order.price);
+//! ```
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use bytemuck::Pod;
+use core::any::type_name;
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::ops::Range;
+use std::cell::{Ref, RefMut};
+
+use super::zero_copy::{Discriminator, DISCRIMINATOR_LEN};
+use crate::borrow_registry::{conflict_on_borrow_failure, BorrowGuard};
+use crate::error::{account_error, ErrorCode};
+
+/// Length in bytes of the `u32` element count following the discriminator.
+const LEN_PREFIX_LEN: usize = 4;
+
+/// Length in bytes of the data before the first element.
+pub const ZERO_COPY_SLICE_HEADER_LEN: usize = DISCRIMINATOR_LEN + LEN_PREFIX_LEN;
+
+/// Account holding up to `capacity` elements of `T` after a
+/// `T::DISCRIMINATOR` and a `u32` length prefix.
+///
+/// Every method borrows the account for its own duration only, registered with
+/// the [`borrow_registry`](crate::borrow_registry) like
+/// [`AccountLoader`](super::AccountLoader) borrows. Methods changing the
+/// elements require a writable account.
+pub struct ZeroCopySlice<'info, T>
+where
+    T: Pod + Discriminator,
+{
+    account: &'info AccountInfo<'info>,
+    _phantom: PhantomData<T>,
+}
+
+impl<'info, T> ZeroCopySlice<'info, T>
+where
+    T: Pod + Discriminator,
+{
+    /// Wraps `account`; its header is checked by every access.
+    pub fn new(account: &'info AccountInfo<'info>) -> Self {
+        Self {
+            account,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Wraps `account` once its discriminator and length prefix are checked,
+    /// as done for `ZeroCopySlice` fields of an `Accounts` struct.
+    pub fn load(account: &'info AccountInfo<'info>) -> Result<Self, ProgramError> {
+        let slice = Self::new(account);
+        slice.capacity()?;
+        Ok(slice)
+    }
+
+    /// Data length of an account holding up to `capacity` elements, e.g. the
+    /// `space` of its `init`.
+    pub const fn space(capacity: usize) -> usize {
+        ZERO_COPY_SLICE_HEADER_LEN + capacity * size_of::<T>()
+    }
+
+    /// Direct access to the wrapped `AccountInfo`.
+    pub fn info(&self) -> &'info AccountInfo<'info> {
+        self.account
+    }
+
+    /// Writes the header of an empty slice into a freshly allocated account.
+    pub fn init(&self) -> Result<(), ProgramError> {
+        let _guard = self.exclusive()?;
+        let mut data = self.data_mut()?;
+        if data.len() < ZERO_COPY_SLICE_HEADER_LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        data[..DISCRIMINATOR_LEN].copy_from_slice(&T::DISCRIMINATOR);
+        write_len(&mut data, 0);
+        Ok(())
+    }
+
+    /// Number of elements the account has room for.
+    pub fn capacity(&self) -> Result<usize, ProgramError> {
+        let _guard = BorrowGuard::shared(self.account.key)?;
+        let data = self.data()?;
+        self.header(&data).map(|(_, capacity)| capacity)
+    }
+
+    /// Number of elements in use.
+    pub fn len(&self) -> Result<usize, ProgramError> {
+        let _guard = BorrowGuard::shared(self.account.key)?;
+        let data = self.data()?;
+        self.header(&data).map(|(len, _)| len)
+    }
+
+    /// Whether no element is in use.
+    pub fn is_empty(&self) -> Result<bool, ProgramError> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Copy of the element at `index`, `None` past the end.
+    pub fn get(&self, index: usize) -> Result<Option<T>, ProgramError> {
+        let _guard = BorrowGuard::shared(self.account.key)?;
+        let data = self.data()?;
+        let (len, _) = self.header(&data)?;
+        Ok((index < len).then(|| read_element(&data, index)))
+    }
+
+    /// Overwrites the element at `index`.
+    pub fn set(&self, index: usize, value: T) -> Result<(), ProgramError> {
+        let _guard = self.exclusive()?;
+        let mut data = self.data_mut()?;
+        let (len, _) = self.header(&data)?;
+        if index >= len {
+            return Err(ProgramError::InvalidArgument);
+        }
+        write_element(&mut data, index, &value);
+        Ok(())
+    }
+
+    /// Appends `value`. Fails with `ProgramError::AccountDataTooSmall` when
+    /// the account is full.
+    pub fn push(&self, value: T) -> Result<(), ProgramError> {
+        let _guard = self.exclusive()?;
+        let mut data = self.data_mut()?;
+        let (len, capacity) = self.header(&data)?;
+        if len == capacity {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        write_element(&mut data, len, &value);
+        write_len(&mut data, len + 1);
+        Ok(())
+    }
+
+    /// Removes and returns the last element, `None` when empty. The freed
+    /// element is zeroed.
+    pub fn pop(&self) -> Result<Option<T>, ProgramError> {
+        let _guard = self.exclusive()?;
+        let mut data = self.data_mut()?;
+        let (len, _) = self.header(&data)?;
+        let Some(last) = len.checked_sub(1) else {
+            return Ok(None);
+        };
+        let value = read_element(&data, last);
+        data[element_range::<T>(last)].fill(0);
+        write_len(&mut data, last);
+        Ok(Some(value))
+    }
+
+    /// Keeps only the elements for which `keep` returns `true`, in order,
+    /// zeroing the elements freed at the end.
+    pub fn retain(&self, mut keep: impl FnMut(&T) -> bool) -> Result<(), ProgramError> {
+        let _guard = self.exclusive()?;
+        let mut data = self.data_mut()?;
+        let (len, _) = self.header(&data)?;
+        let mut kept = 0;
+        for index in 0..len {
+            let value: T = read_element(&data, index);
+            if keep(&value) {
+                if kept != index {
+                    write_element(&mut data, kept, &value);
+                }
+                kept += 1;
+            }
+        }
+        let freed = element_range::<T>(kept).start..element_range::<T>(len).start;
+        data[freed].fill(0);
+        write_len(&mut data, kept);
+        Ok(())
+    }
+
+    /// Removes every element, zeroing them.
+    pub fn clear(&self) -> Result<(), ProgramError> {
+        self.retain(|_| false)
+    }
+
+    /// Iterates over copies of the elements in use. The account stays
+    /// borrowed until the iterator is dropped.
+    pub fn iter(&self) -> Result<ZeroCopySliceIter<'info, T>, ProgramError> {
+        let guard = BorrowGuard::shared(self.account.key)?;
+        let data = self.data()?;
+        let (len, _) = self.header(&data)?;
+        Ok(ZeroCopySliceIter {
+            data,
+            range: 0..len,
+            _guard: guard,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Borrows the data, failing with `ErrorCode::AccountBorrowConflict` while
+    /// it is mutably borrowed.
+    fn data(&self) -> Result<Ref<'info, &'info mut [u8]>, ProgramError> {
+        self.account
+            .try_borrow_data()
+            .map_err(conflict_on_borrow_failure)
+    }
+
+    /// Mutably borrows the data, failing with
+    /// `ErrorCode::AccountBorrowConflict` while it is borrowed.
+    fn data_mut(&self) -> Result<RefMut<'info, &'info mut [u8]>, ProgramError> {
+        self.account
+            .try_borrow_mut_data()
+            .map_err(conflict_on_borrow_failure)
+    }
+
+    /// Registers a mutable borrow once the account is known to be writable.
+    fn exclusive(&self) -> Result<BorrowGuard, ProgramError> {
+        if !self.account.is_writable {
+            return Err(ProgramError::Custom(
+                ErrorCode::IncorrectIsWritableFlag.into(),
+            ));
+        }
+        BorrowGuard::exclusive(self.account.key)
+    }
+
+    /// `(len, capacity)` of the slice in `data`, once its discriminator and
+    /// length prefix are checked.
+    fn header(&self, data: &[u8]) -> Result<(usize, usize), ProgramError> {
+        let key = self.account.key;
+        if size_of::<T>() == 0 || data.len() < ZERO_COPY_SLICE_HEADER_LEN {
+            return Err(account_error(
+                key,
+                ErrorCode::AccountTooSmallForType,
+                format_args!(
+                    "{} bytes, a slice of {} needs {}",
+                    data.len(),
+                    type_name::<T>(),
+                    ZERO_COPY_SLICE_HEADER_LEN
+                ),
+            ));
+        }
+        if data[..DISCRIMINATOR_LEN] != T::DISCRIMINATOR {
+            return Err(account_error(
+                key,
+                ErrorCode::WrongDiscriminator,
+                format_args!(
+                    "expected discriminator {:?} of {}, found {:?}",
+                    T::DISCRIMINATOR,
+                    type_name::<T>(),
+                    &data[..DISCRIMINATOR_LEN]
+                ),
+            ));
+        }
+        let capacity = (data.len() - ZERO_COPY_SLICE_HEADER_LEN) / size_of::<T>();
+        let len_bytes: [u8; LEN_PREFIX_LEN] = data[DISCRIMINATOR_LEN..ZERO_COPY_SLICE_HEADER_LEN]
+            .try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        if len > capacity {
+            return Err(account_error(
+                key,
+                ErrorCode::AccountTooSmallForType,
+                format_args!("length prefix {} exceeds the capacity {}", len, capacity),
+            ));
+        }
+        Ok((len, capacity))
+    }
+}
+
+// Allow treating `ZeroCopySlice` as an `AccountInfo` via `AsRef`.
+impl<'info, T> AsRef<AccountInfo<'info>> for ZeroCopySlice<'info, T>
+where
+    T: Pod + Discriminator,
+{
+    fn as_ref(&self) -> &AccountInfo<'info> {
+        self.account
+    }
+}
+
+/// Iterator returned by [`ZeroCopySlice::iter`].
+pub struct ZeroCopySliceIter<'info, T> {
+    // Declared first so the data borrow ends before the registration.
+    data: Ref<'info, &'info mut [u8]>,
+    range: Range<usize>,
+    _guard: BorrowGuard,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Pod> Iterator for ZeroCopySliceIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let index = self.range.next()?;
+        Some(read_element(&self.data, index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T: Pod> DoubleEndedIterator for ZeroCopySliceIter<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        let index = self.range.next_back()?;
+        Some(read_element(&self.data, index))
+    }
+}
+
+impl<T: Pod> ExactSizeIterator for ZeroCopySliceIter<'_, T> {}
+
+/// Bytes of the element at `index`.
+fn element_range<T>(index: usize) -> Range<usize> {
+    let start = ZERO_COPY_SLICE_HEADER_LEN + index * size_of::<T>();
+    start..start + size_of::<T>()
+}
+
+fn read_element<T: Pod>(data: &[u8], index: usize) -> T {
+    bytemuck::pod_read_unaligned(&data[element_range::<T>(index)])
+}
+
+fn write_element<T: Pod>(data: &mut [u8], index: usize, value: &T) {
+    data[element_range::<T>(index)].copy_from_slice(bytemuck::bytes_of(value));
+}
+
+fn write_len(data: &mut [u8], len: usize) {
+    data[DISCRIMINATOR_LEN..ZERO_COPY_SLICE_HEADER_LEN]
+        .copy_from_slice(&(len as u32).to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{account_info, Counter};
+    use arch_program::pubkey::Pubkey;
+
+    /// Slice of `Counter`s with room for `capacity` elements, initialised.
+    fn counters(capacity: usize) -> ZeroCopySlice<'static, Counter> {
+        let info = account_info(
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            &vec![0; ZeroCopySlice::<Counter>::space(capacity)],
+            arch_program::account::MIN_ACCOUNT_LAMPORTS,
+            0,
+        );
+        let slice = ZeroCopySlice::new(info);
+        slice.init().unwrap();
+        slice
+    }
+
+    fn values(slice: &ZeroCopySlice<'_, Counter>) -> Vec<u64> {
+        slice.iter().unwrap().map(|counter| counter.value).collect()
+    }
+
+    #[test]
+    fn push_fills_the_capacity_and_pop_zeroes_the_freed_element() {
+        let slice = counters(2);
+        assert_eq!(slice.capacity(), Ok(2));
+        assert_eq!(slice.is_empty(), Ok(true));
+
+        slice.push(Counter { value: 1 }).unwrap();
+        slice.push(Counter { value: 2 }).unwrap();
+        assert_eq!(
+            slice.push(Counter { value: 3 }),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+        assert_eq!(values(&slice), [1, 2]);
+        assert_eq!(slice.get(1), Ok(Some(Counter { value: 2 })));
+        assert_eq!(slice.get(2), Ok(None));
+
+        assert_eq!(slice.pop(), Ok(Some(Counter { value: 2 })));
+        assert_eq!(slice.len(), Ok(1));
+        let data = slice.info().data.borrow();
+        assert_eq!(
+            &data[DISCRIMINATOR_LEN..ZERO_COPY_SLICE_HEADER_LEN],
+            &1u32.to_le_bytes()
+        );
+        assert!(data[element_range::<Counter>(1)]
+            .iter()
+            .all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn retain_keeps_the_order_and_zeroes_the_tail() {
+        let slice = counters(4);
+        for value in 1..=4 {
+            slice.push(Counter { value }).unwrap();
+        }
+
+        slice.retain(|counter| counter.value % 2 == 0).unwrap();
+        assert_eq!(values(&slice), [2, 4]);
+        {
+            let data = slice.info().data.borrow();
+            let tail = element_range::<Counter>(2).start..data.len();
+            assert!(data[tail].iter().all(|byte| *byte == 0));
+        }
+
+        slice.clear().unwrap();
+        assert_eq!(slice.is_empty(), Ok(true));
+        assert_eq!(slice.pop(), Ok(None));
+    }
+
+    #[test]
+    fn rejects_a_length_prefix_past_the_capacity() {
+        let slice = counters(1);
+        write_len(&mut slice.info().data.borrow_mut(), 2);
+
+        assert_eq!(
+            slice.len(),
+            Err(ProgramError::Custom(
+                ErrorCode::AccountTooSmallForType.into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Cross-program invocations built from `Accounts` structs.
+//!
+//! `#[derive(Accounts)]` implements [`ToAccountInfos`] and [`ToAccountMetas`]
+//! for every struct, listing its accounts in declaration order with the
+//! `signer` / `mut` flags it declares. A [`CpiContext`] pairs such a struct
+//! with the program to call, so [`invoke`] and [`invoke_signed`] can assemble
+//! the instruction instead of the caller building the `AccountMeta` and
+//! `AccountInfo` arrays by hand:
+//!
+//! ```ignore
+//! let accounts = Deposit { user: ctx.accounts.user.clone(), vault: ctx.accounts.vault.clone() };
+//! let seeds: &[&[u8]] = &[b"vault", &[ctx.bumps.vault]];
+//! let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.vault_program.clone(), accounts, &[seeds]);
+//! cpi::invoke_signed(cpi_ctx, borsh::to_vec(&VaultInstruction::Deposit { amount })?)?;
+//! ```
+
+use arch_program::account::{AccountInfo, AccountMeta};
+use arch_program::entrypoint::ProgramResult;
+use arch_program::instruction::Instruction;
+
+/// Accounts of a struct as owned [`AccountInfo`]s, in declaration order.
+pub trait ToAccountInfos<'info> {
+    /// Every account of the struct; omitted optional accounts are skipped.
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>>;
+}
+
+/// Accounts of a struct as the [`AccountMeta`]s of an instruction taking it.
+pub trait ToAccountMetas {
+    /// One meta per account in declaration order, flagged as the struct
+    /// declares (`init`, `realloc` and `close` fields are writable). An omitted
+    /// optional account is the program id when the struct expects it in its
+    /// slot, and absent otherwise.
+    fn to_account_metas(&self) -> Vec<AccountMeta>;
+}
+
+/// The program called by a cross-program invocation, the accounts passed to
+/// it and the seeds of the PDAs signing for the caller.
+pub struct CpiContext<'a, 'info, T> {
+    /// Program account of the callee.
+    pub program: AccountInfo<'info>,
+    /// Accounts of the instruction, usually a struct deriving `Accounts`.
+    pub accounts: T,
+    /// Seeds of the PDAs of the calling program that sign the instruction.
+    pub signer_seeds: &'a [&'a [&'a [u8]]],
+}
+
+impl<'a, 'info, T> CpiContext<'a, 'info, T>
+where
+    T: ToAccountInfos<'info> + ToAccountMetas,
+{
+    /// Context of an invocation without PDA signers.
+    pub fn new(program: AccountInfo<'info>, accounts: T) -> Self {
+        Self {
+            program,
+            accounts,
+            signer_seeds: &[],
+        }
+    }
+
+    /// Context of an invocation signed by the PDAs of `signer_seeds`.
+    pub fn new_with_signer(
+        program: AccountInfo<'info>,
+        accounts: T,
+        signer_seeds: &'a [&'a [&'a [u8]]],
+    ) -> Self {
+        Self {
+            program,
+            accounts,
+            signer_seeds,
+        }
+    }
+
+    /// Replaces the seeds of the PDAs signing the instruction.
+    pub fn with_signer(mut self, signer_seeds: &'a [&'a [&'a [u8]]]) -> Self {
+        self.signer_seeds = signer_seeds;
+        self
+    }
+
+    /// The instruction calling the program with `data`.
+    pub fn instruction(&self, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: *self.program.key,
+            accounts: self.accounts.to_account_metas(),
+            data,
+        }
+    }
+
+    /// The accounts handed to the runtime: those of the struct followed by
+    /// the program itself.
+    fn account_infos(&self) -> Vec<AccountInfo<'info>> {
+        let mut infos = self.accounts.to_account_infos();
+        infos.push(self.program.clone());
+        infos
+    }
+}
+
+/// Calls the program of `ctx` with `data`, without PDA signers (the
+/// `signer_seeds` of `ctx` are ignored).
+pub fn invoke<'a, 'info, T>(ctx: CpiContext<'a, 'info, T>, data: Vec<u8>) -> ProgramResult
+where
+    T: ToAccountInfos<'info> + ToAccountMetas,
+{
+    arch_program::program::invoke(&ctx.instruction(data), &ctx.account_infos())
+}
+
+/// Calls the program of `ctx` with `data`, signed by the PDAs of its
+/// `signer_seeds`.
+pub fn invoke_signed<'a, 'info, T>(ctx: CpiContext<'a, 'info, T>, data: Vec<u8>) -> ProgramResult
+where
+    T: ToAccountInfos<'info> + ToAccountMetas,
+{
+    arch_program::program::invoke_signed(
+        &ctx.instruction(data),
+        &ctx.account_infos(),
+        ctx.signer_seeds,
+    )
+}
@@ -6,6 +6,7 @@
 //!
 //! Use the variants via `ProgramError::Custom(ErrorCode::XYZ.into())`.
 use arch_program::program_error::ProgramError;
+use arch_program::pubkey::Pubkey;
 use saturn_error::saturn_error;
 
 /// Parser-specific error codes.
@@ -21,6 +22,51 @@ pub enum ErrorCode {
     InvalidPda,
     #[error("The account's public key did not match the expected address")]
     InvalidAccountKey,
+    #[error("The account is not owned by the expected program")]
+    InvalidAccountOwner,
+    #[error("The account balance is below the minimum balance for its data length")]
+    NotRentExempt,
+    #[error("The account is not an executable program account")]
+    AccountNotExecutable,
+    #[error("The token account does not hold the expected mint")]
+    InvalidTokenMint,
+    #[error("The token account is not owned by the expected authority")]
+    InvalidTokenAuthority,
+    #[error("The mint does not have the expected mint authority")]
+    InvalidMintAuthority,
+    #[error("The mint does not have the expected number of decimals")]
+    InvalidMintDecimals,
+    #[error("The same account was passed for more than one field")]
+    DuplicateAccount,
+    #[error("The runtime length of an account vector exceeds its `max_len`")]
+    AccountLenExceedsMax,
+    #[error("The account's discriminator matches none of the accepted account types")]
+    UnknownAccountDiscriminator,
+    #[error("The account is already borrowed in a conflicting way through another `AccountInfo`")]
+    AccountBorrowConflict,
+    #[error("The authority neither signed nor is the expected PDA")]
+    InvalidAuthority,
+    #[error("The provided bump is not the canonical bump of the PDA seeds")]
+    InvalidBump,
+    #[error("The account's layout version cannot be migrated to the expected one")]
+    UnsupportedAccountVersion,
+    #[error("The account data does not start with the discriminator of the expected type")]
+    WrongDiscriminator,
+    #[error("The account data is too small to hold the expected type")]
+    AccountTooSmallForType,
+    #[error("The account data is not aligned for the expected zero-copy type")]
+    MisalignedAccountData,
 }
 
 pub type Result<T> = core::result::Result<T, ProgramError>;
+
+/// Logs `detail` prefixed by the key of the offending account and returns
+/// `code`, so a failing instruction tells which of its accounts was rejected.
+pub(crate) fn account_error(
+    key: &Pubkey,
+    code: ErrorCode,
+    detail: core::fmt::Arguments<'_>,
+) -> ProgramError {
+    arch_program::msg!("account {}: {}", key, detail);
+    ProgramError::Custom(code.into())
+}
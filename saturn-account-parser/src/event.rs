@@ -0,0 +1,86 @@
+//! Structured program events.
+//!
+//! An event is a Borsh-serializable struct deriving `Event` from
+//! `saturn-account-macros`, which gives it an 8-byte discriminator
+//! (`sha256("event:<Name>")[0..8]`, as in Anchor). [`emit!`](crate::emit)
+//! logs the discriminator and the Borsh payload as one base64 `msg!` line,
+//! which clients turn back into the struct with [`decode_event`] or
+//! [`decode_events`]:
+//!
+//! ```ignore
+//! #[derive(BorshSerialize, BorshDeserialize, Event)]
+//! pub struct Deposited {
+//!     pub user: Pubkey,
+//!     pub amount: u64,
+//! }
+//!
+//! emit!(Deposited { user: *ctx.accounts.user.key, amount });
+//!
+//! // Off-chain, from the logs of the processed transaction:
+//! let deposits: Vec<Deposited> = decode_events(&logs);
+//! ```
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Prefix of the log line written by [`emit!`](crate::emit), the one the
+/// runtime uses for `sol_log_data` so existing log parsers pick events up.
+pub const EVENT_LOG_PREFIX: &str = "Program data: ";
+
+/// A struct that can be emitted as a program event.
+///
+/// Implement it with `#[derive(Event)]` rather than by hand.
+pub trait Event: BorshSerialize + BorshDeserialize {
+    /// Identifies the event type in the log, ahead of the payload.
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Discriminator followed by the Borsh payload.
+    fn data(&self) -> Vec<u8> {
+        let mut data = Self::DISCRIMINATOR.to_vec();
+        // Serialising into a `Vec` cannot fail.
+        self.serialize(&mut data).expect("event serialisation");
+        data
+    }
+}
+
+/// The log line [`emit!`](crate::emit) writes for `event`:
+/// `Program data: <base64>`.
+pub fn log_line<E: Event>(event: &E) -> String {
+    format!("{}{}", EVENT_LOG_PREFIX, STANDARD.encode(event.data()))
+}
+
+/// Logs `event`. Use [`emit!`](crate::emit).
+pub fn emit<E: Event>(event: &E) {
+    arch_program::msg!("{}", log_line(event));
+}
+
+/// Logs a program event, e.g. `emit!(Deposited { user, amount })`.
+#[macro_export]
+macro_rules! emit {
+    ($event:expr) => {
+        $crate::event::emit(&$event)
+    };
+}
+
+/// Decodes an `E` from one log line, or `None` when the line is not an
+/// event of type `E`.
+///
+/// Lines prefixed by the runtime (`Program log: Program data: ..`) are
+/// accepted as well as bare ones.
+pub fn decode_event<E: Event>(log: &str) -> Option<E> {
+    let start = log.find(EVENT_LOG_PREFIX)? + EVENT_LOG_PREFIX.len();
+    let bytes = STANDARD.decode(log[start..].trim()).ok()?;
+    let payload = bytes.strip_prefix(&E::DISCRIMINATOR[..])?;
+    E::try_from_slice(payload).ok()
+}
+
+/// Every `E` emitted in `logs`, in order; other lines are skipped.
+pub fn decode_events<E, S>(logs: &[S]) -> Vec<E>
+where
+    E: Event,
+    S: AsRef<str>,
+{
+    logs.iter()
+        .filter_map(|log| decode_event(log.as_ref()))
+        .collect()
+}
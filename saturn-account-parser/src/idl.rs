@@ -0,0 +1,52 @@
+//! Helpers for the JSON IDL emitted with the `idl-build` features of
+//! `saturn-program-macros` and `saturn-account-macros`.
+//!
+//! `#[saturn_program]` then generates a `pub fn idl() -> String` next to
+//! `process_instruction`, describing every instruction (name, discriminator,
+//! accounts and arguments) plus the error enums and UTXO parsers listed in
+//! `#[saturn_program(idl(errors = [..], utxos = [..]))]`. A test or a small
+//! binary of the program writes it out for explorers and client generators:
+//!
+//! ```ignore
+//! #[cfg(feature = "idl-build")]
+//! #[test]
+//! fn write_idl() {
+//!     std::fs::write("target/idl/my_program.json", crate::idl()).unwrap();
+//! }
+//! ```
+
+/// `value` as a JSON string literal, quotes included.
+pub fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON array of the already encoded `items`.
+pub fn json_array<I>(items: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut out = String::from("[");
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(item.as_ref());
+    }
+    out.push(']');
+    out
+}
@@ -3,8 +3,39 @@
 //! Anchor-style account-parsing utilities for Saturn on-chain programs.
 //!
 //! This crate provides:
-//! * [`Context`] – a typed view over instruction accounts.
+//! * [`Context`] – a typed view over instruction accounts, which [`Context::split`]
+//!   breaks into disjoint mutable borrows ([`ContextParts`]).
 //! * The [`codec`] module with Borsh and zero-copy codecs.
+//! * The [`borrow_registry`] catching conflicting zero-copy borrows of one
+//!   account passed more than once.
+//! * [`TokenAccount`] and [`Mint`] wrappers over token program accounts.
+//! * [`UncheckedAccount`], the auditable way to take an account without checks.
+//! * [`AccountEnum`], accounts accepted as one of several zero-copy types.
+//! * [`Migratable`] zero-copy layouts, upgraded in place by
+//!   [`AccountLoader::load_or_migrate`].
+//! * [`Context::signer_seeds`], the seeds of a PDA field recorded while
+//!   parsing, ready for `invoke_signed` (see [`seeds`]).
+//! * [`Context::remaining`], the remaining accounts checked and wrapped into
+//!   a typed account.
+//! * [`Sysvar`] fields reading the clock, rent and Bitcoin block height from
+//!   the runtime while the accounts are parsed.
+//! * The [`cpi`] module calling other programs with a [`CpiContext`] built
+//!   from an `Accounts` struct.
+//! * The [`event`] module and [`emit!`], logging structured events that
+//!   clients decode with [`event::decode_events`].
+//! * [`log_kv!`](macro@log_kv), logging `key=value` fields on one line that
+//!   log scrapers decode into a typed map with [`log_kv::decode_kvs`].
+//! * The [`state`] module checking the lifecycle state of an account for
+//!   `#[require_state(..)]` handlers.
+//! * The [`nonce`] module rejecting replayed instructions of
+//!   `#[nonce(..)]` handlers.
+//! * The [`panic`] module turning handler panics into a
+//!   `SaturnErrorCode::HandlerPanicked` error where the target unwinds, and
+//!   logging that code on-chain.
+//! * [`security_txt!`], embedding the security contacts of the program in
+//!   its binary for explorers and auditors.
+//! * The [`idl`] module with the JSON helpers behind the IDL that
+//!   `#[saturn_program]` generates with the `idl-build` feature.
 //! * Helper functions such as [`get_account`] and [`get_pda_account`] that reduce boiler-plate when validating accounts.
 //!
 //! Enable the `btc-tx` feature to access the optional Bitcoin transaction builder.
@@ -13,13 +44,40 @@ use arch_program::{account::AccountInfo, program_error::ProgramError, pubkey::Pu
 
 use crate::error::ErrorCode;
 
+mod account_enum;
+pub mod borrow_registry;
+pub mod client;
 pub mod codec;
+pub mod cpi;
 mod to_account_info;
-pub use codec::{Account, AccountLoader};
+pub use codec::{Account, AccountLoader, Lazy, ZeroCopySlice};
+pub use cpi::{CpiContext, ToAccountInfos, ToAccountMetas};
+pub use event::Event;
 pub mod error;
+pub mod event;
+pub mod idl;
+pub mod log_kv;
+pub mod migration;
+pub mod nonce;
+pub mod panic;
+mod remaining;
+pub mod security_txt;
+pub mod seeds;
+pub mod state;
+pub mod sysvar;
+mod test_utils;
+pub mod token;
 pub mod tx_builder;
+mod unchecked;
+pub use account_enum::{read_discriminator, AccountEnum, AccountVariant};
+pub use migration::{migrate_pod, Migratable, Migration};
+pub use remaining::{RemainingAccount, RemainingAccountError, RemainingAccounts};
+pub use seeds::{PdaSeeds, SignerSeeds};
+pub use sysvar::Sysvar;
 pub use to_account_info::ToAccountInfo;
-pub use tx_builder::TxBuilderWrapper;
+pub use token::{Mint, TokenAccount};
+pub use tx_builder::{FinalizeReport, TxBuilderWrapper};
+pub use unchecked::UncheckedAccount;
 
 /// Anchor-style instruction context that bundles the executing program id, a
 /// typed view over the accounts (implements [`Accounts`]), and any extra
@@ -27,7 +85,7 @@ pub use tx_builder::TxBuilderWrapper;
 ///
 /// It is generic over the `Accounts` implementation to keep the same ergonomics
 /// as Anchor while staying flexible for Saturn programs.
-pub struct Context<'a, 'b, 'c, 'info, T: Accounts<'info>, TxBuilder = ()> {
+pub struct Context<'a, 'b, 'c, 'info, T: Bumps<'info>, TxBuilder = ()> {
     /// Public key of the program that is currently executing.
     pub program_id: &'a Pubkey,
 
@@ -39,45 +97,151 @@ pub struct Context<'a, 'b, 'c, 'info, T: Accounts<'info>, TxBuilder = ()> {
     /// struct.
     pub remaining_accounts: &'c [AccountInfo<'info>],
 
+    /// Bump seeds of the PDA fields, found while parsing the accounts.
+    pub bumps: T::Bumps,
+
     /// Optional Bitcoin transaction builder available when the program opts in
     /// by providing a `btc_tx_cfg(...)` section in the `#[saturn_program]` attribute.
     pub btc_tx: TxBuilder,
 }
 
 // Convenience constructors
-impl<'a, 'b, 'c, 'info, T: Accounts<'info>> Context<'a, 'b, 'c, 'info, T> {
+impl<'a, 'b, 'c, 'info, T: Bumps<'info>> Context<'a, 'b, 'c, 'info, T> {
     /// Same fields as before – no Bitcoin builder.
     pub fn new_simple(
         program_id: &'a Pubkey,
         accounts: &'b mut T,
         remaining_accounts: &'c [AccountInfo<'info>],
+        bumps: T::Bumps,
     ) -> Self {
         Self {
             program_id,
             accounts,
             remaining_accounts,
+            bumps,
             btc_tx: (),
         }
     }
 }
 
-impl<'a, 'b, 'c, 'info, T: Accounts<'info>, TxBuilder> Context<'a, 'b, 'c, 'info, T, TxBuilder> {
+impl<'a, 'b, 'c, 'info, T: Bumps<'info>, TxBuilder> Context<'a, 'b, 'c, 'info, T, TxBuilder> {
     /// Constructor used by the macro when a Bitcoin transaction builder is provided.
     pub fn new_with_btc_tx(
         program_id: &'a Pubkey,
         accounts: &'b mut T,
         remaining_accounts: &'c [AccountInfo<'info>],
+        bumps: T::Bumps,
         btc_tx: TxBuilder,
     ) -> Self {
         Self {
             program_id,
             accounts,
             remaining_accounts,
+            bumps,
             btc_tx,
         }
     }
 }
 
+impl<'a, 'b, 'c, 'info, T: Bumps<'info>, TxBuilder> Context<'a, 'b, 'c, 'info, T, TxBuilder> {
+    /// Splits the context into disjoint views so the typed accounts and the
+    /// Bitcoin transaction builder can be borrowed mutably at the same time.
+    ///
+    /// ```ignore
+    /// let parts = ctx.split();
+    /// let mut pool = parts.accounts.pool.load_mut()?;
+    /// parts.btc_tx.add_state_transition(parts.accounts.pool.info())?;
+    /// ```
+    pub fn split(&mut self) -> ContextParts<'a, '_, 'c, 'info, T, TxBuilder> {
+        ContextParts {
+            program_id: self.program_id,
+            accounts: &mut *self.accounts,
+            remaining_accounts: self.remaining_accounts,
+            bumps: &self.bumps,
+            btc_tx: &mut self.btc_tx,
+        }
+    }
+
+    /// The seeds of the PDA field `field`, bump last, for `invoke_signed`:
+    ///
+    /// ```ignore
+    /// let seeds = ctx.signer_seeds("config")?;
+    /// invoke_signed(&ix, &infos, &[&seeds])?;
+    /// ```
+    ///
+    /// Fields of a `nested` struct are named `"<outer>.<inner>"`.
+    ///
+    /// # Errors
+    /// [`ProgramError::InvalidSeeds`] when `field` is not a PDA field of the
+    /// struct, or is an optional one that was not passed.
+    pub fn signer_seeds(&self, field: &str) -> Result<Vec<&[u8]>, ProgramError> {
+        self.bumps
+            .signer_seeds()
+            .get(field)
+            .ok_or(ProgramError::InvalidSeeds)
+    }
+}
+
+impl<'a, 'b, 'c, 'info, T: Bumps<'info>, TxBuilder> Context<'a, 'b, 'c, 'info, T, TxBuilder>
+where
+    'c: 'info,
+{
+    /// Iterates over `remaining_accounts` wrapped into `A` (an [`Account`] or
+    /// [`AccountLoader`]), each checked to be owned by the program and to hold
+    /// an `A`. A rejected account yields a [`RemainingAccountError`] carrying
+    /// its index.
+    ///
+    /// The accounts are wrapped for `'info`, so the handler names the
+    /// lifetime of `remaining_accounts` `'info` as well:
+    ///
+    /// ```ignore
+    /// pub fn settle<'info>(ctx: Context<'_, '_, 'info, 'info, Settle<'info>>) -> Result<()> {
+    ///     let positions = ctx
+    ///         .remaining::<AccountLoader<'info, Position>>()
+    ///         .collect::<Result<Vec<_>, _>>()?;
+    ///     // ..
+    /// }
+    /// ```
+    pub fn remaining<A: RemainingAccount<'info>>(&self) -> RemainingAccounts<'info, A> {
+        RemainingAccounts::new(self.remaining_accounts, self.program_id)
+    }
+}
+
+/// Disjoint borrows of the parts of a [`Context`], returned by [`Context::split`].
+///
+/// `program_id` and `remaining_accounts` keep the lifetimes of the original
+/// context, while `accounts` and `btc_tx` are independent mutable borrows that
+/// live as long as the split itself.
+pub struct ContextParts<'a, 's, 'c, 'info, T: Bumps<'info>, TxBuilder = ()> {
+    /// Public key of the program that is currently executing.
+    pub program_id: &'a Pubkey,
+
+    /// Typed view over the instruction's accounts.
+    pub accounts: &'s mut T,
+
+    /// Extra accounts not listed in the `Accounts` struct.
+    pub remaining_accounts: &'c [AccountInfo<'info>],
+
+    /// Bump seeds of the PDA fields.
+    pub bumps: &'s T::Bumps,
+
+    /// Bitcoin transaction builder (`()` when the program did not opt in).
+    pub btc_tx: &'s mut TxBuilder,
+}
+
+impl<'a, 's, 'c, 'info, T: Bumps<'info>, TxBuilder> ContextParts<'a, 's, 'c, 'info, T, TxBuilder> {
+    /// The seeds of the PDA field `field`, see [`Context::signer_seeds`].
+    ///
+    /// # Errors
+    /// [`ProgramError::InvalidSeeds`] when `field` has no recorded seeds.
+    pub fn signer_seeds(&self, field: &str) -> Result<Vec<&'s [u8]>, ProgramError> {
+        self.bumps
+            .signer_seeds()
+            .get(field)
+            .ok_or(ProgramError::InvalidSeeds)
+    }
+}
+
 /// Trait implemented by every struct generated by `#[derive(Accounts)]`.
 ///
 /// Implementors convert the raw `&[AccountInfo]` slice provided to every
@@ -90,6 +254,85 @@ pub trait Accounts<'a>: Sized {
     /// # Errors
     /// Returns an appropriate [`ProgramError`] when validation fails.
     fn try_accounts(accounts: &'a [AccountInfo<'a>]) -> Result<Self, ProgramError>;
+
+    /// Parses `Self` from the front of `accounts`, returning it with the number
+    /// of accounts consumed, so that `Self` can be nested inside another
+    /// `Accounts` struct (`#[account(nested)]`).
+    ///
+    /// The default consumes the whole slice through [`Accounts::try_accounts`].
+    ///
+    /// # Errors
+    /// Returns an appropriate [`ProgramError`] when validation fails.
+    fn try_accounts_prefix(accounts: &'a [AccountInfo<'a>]) -> Result<(Self, usize), ProgramError> {
+        Self::try_accounts(accounts).map(|accs| (accs, accounts.len()))
+    }
+
+    /// Finalises the accounts once the instruction handler returned, e.g. by
+    /// closing the fields marked `#[account(close = ..)]`. Called by the
+    /// `#[saturn_program]` dispatcher; the default does nothing.
+    ///
+    /// # Errors
+    /// Returns an appropriate [`ProgramError`] when an account cannot be finalised.
+    fn exit(&self) -> Result<(), ProgramError> {
+        Ok(())
+    }
+}
+
+/// Bump seeds of the PDAs found while parsing an [`Accounts`] struct.
+///
+/// `#[derive(Accounts)]` implements it with a generated `<Struct>Bumps` type
+/// holding one bump per PDA field, which the `#[saturn_program]` dispatcher
+/// exposes as [`Context::bumps`]. It also keeps the seeds of those fields
+/// for [`Context::signer_seeds`].
+pub trait Bumps<'a>: Accounts<'a> {
+    /// The generated `<Struct>Bumps` struct.
+    type Bumps: Default + core::fmt::Debug + PdaSeeds;
+
+    /// Like [`Accounts::try_accounts_prefix`], also returning the bumps found
+    /// while parsing.
+    ///
+    /// `ix_args` are the Borsh-encoded handler parameters of the instruction;
+    /// structs declaring `#[instruction(..)]` decode their leading ones from it
+    /// so that `seeds` can reference them. Other structs ignore it.
+    ///
+    /// # Errors
+    /// Returns an appropriate [`ProgramError`] when validation fails.
+    fn try_accounts_prefix_with_args(
+        accounts: &'a [AccountInfo<'a>],
+        ix_args: &[u8],
+    ) -> Result<(Self, Self::Bumps, usize), ProgramError>;
+
+    /// Like [`Accounts::try_accounts`], also taking the instruction
+    /// parameters (see [`Bumps::try_accounts_prefix_with_args`]) and
+    /// returning the bumps found while parsing.
+    ///
+    /// # Errors
+    /// Returns an appropriate [`ProgramError`] when validation fails.
+    fn try_accounts_with_args(
+        accounts: &'a [AccountInfo<'a>],
+        ix_args: &[u8],
+    ) -> Result<(Self, Self::Bumps), ProgramError> {
+        let (accs, bumps, consumed) = Self::try_accounts_prefix_with_args(accounts, ix_args)?;
+
+        // Ensure we've consumed exactly all provided accounts
+        if consumed != accounts.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok((accs, bumps))
+    }
+
+    /// Like [`Accounts::try_accounts`], also returning the bumps found while
+    /// parsing. Fails for structs declaring `#[instruction(..)]`, which need
+    /// [`Bumps::try_accounts_with_args`].
+    ///
+    /// # Errors
+    /// Returns an appropriate [`ProgramError`] when validation fails.
+    fn try_accounts_with_bumps(
+        accounts: &'a [AccountInfo<'a>],
+    ) -> Result<(Self, Self::Bumps), ProgramError> {
+        Self::try_accounts_with_args(accounts, &[])
+    }
 }
 
 /// Retrieves the account at position `index` from `accounts` and optionally
@@ -147,6 +390,19 @@ pub fn get_pda_account<'a>(
     seeds: &[&[u8]],
     program_id: &Pubkey,
 ) -> Result<&'a AccountInfo<'a>, ProgramError> {
+    get_pda_account_with_bump(accounts, index, is_signer, is_writable, seeds, program_id)
+        .map(|(acc, _bump)| acc)
+}
+
+/// Same as [`get_pda_account`] but also returns the bump seed of the PDA.
+pub fn get_pda_account_with_bump<'a>(
+    accounts: &'a [AccountInfo<'a>],
+    index: usize,
+    is_signer: Option<bool>,
+    is_writable: Option<bool>,
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> Result<(&'a AccountInfo<'a>, u8), ProgramError> {
     // First, retrieve the desired account while validating signer / writable flags
     let acc = get_account(accounts, index, is_signer, is_writable, None)?;
 
@@ -156,12 +412,45 @@ pub fn get_pda_account<'a>(
     }
 
     // Derive the expected PDA address from the provided seeds
-    let (expected_key, _bump) = Pubkey::find_program_address(seeds, program_id);
+    let (expected_key, bump) = Pubkey::find_program_address(seeds, program_id);
 
     if acc.key != &expected_key {
         return Err(ProgramError::Custom(ErrorCode::InvalidPda.into()));
     }
 
+    Ok((acc, bump))
+}
+
+/// Same as [`get_pda_account`] but checks the PDA with a known `bump`, e.g.
+/// one stored in account data when the PDA was created, instead of searching
+/// for the canonical bump with `find_program_address`.
+pub fn get_pda_account_with_known_bump<'a>(
+    accounts: &'a [AccountInfo<'a>],
+    index: usize,
+    is_signer: Option<bool>,
+    is_writable: Option<bool>,
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+) -> Result<&'a AccountInfo<'a>, ProgramError> {
+    let acc = get_account(accounts, index, is_signer, is_writable, None)?;
+
+    // The bump takes one of the runtime's seed slots.
+    if seeds.len() >= arch_program::pubkey::MAX_SEEDS {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let bump_seed = [bump];
+    let mut seeds_with_bump: Vec<&[u8]> = Vec::with_capacity(seeds.len() + 1);
+    seeds_with_bump.extend_from_slice(seeds);
+    seeds_with_bump.push(&bump_seed);
+
+    let expected_key = Pubkey::create_program_address(&seeds_with_bump, program_id)
+        .map_err(|_| ProgramError::Custom(ErrorCode::InvalidPda.into()))?;
+    if acc.key != &expected_key {
+        return Err(ProgramError::Custom(ErrorCode::InvalidPda.into()));
+    }
+
     Ok(acc)
 }
 
@@ -219,6 +508,89 @@ pub fn get_indexed_pda_account<'a>(
     Ok(acc)
 }
 
+/// Minimum balance an account holding `data_len` bytes of data must keep.
+///
+/// The Arch runtime currently requires a flat
+/// [`MIN_ACCOUNT_LAMPORTS`](arch_program::account::MIN_ACCOUNT_LAMPORTS)
+/// whatever the data length; taking the length keeps callers correct if that
+/// changes.
+pub fn minimum_balance(_data_len: usize) -> u64 {
+    arch_program::account::MIN_ACCOUNT_LAMPORTS
+}
+
+/// Checks that `acc` holds at least the [`minimum_balance`] for its current
+/// data length.
+pub fn check_rent_exempt(acc: &AccountInfo<'_>) -> Result<(), ProgramError> {
+    if acc.lamports() < minimum_balance(acc.data_len()) {
+        return Err(ProgramError::Custom(ErrorCode::NotRentExempt.into()));
+    }
+    Ok(())
+}
+
+/// Discriminator [`close_account`] leaves in a closed account. No account type
+/// uses it, so a closed account that is funded again in the same transaction
+/// cannot be loaded as its former type.
+pub const CLOSED_ACCOUNT_DISCRIMINATOR: [u8; 8] = [0xFF; 8];
+
+/// Closes `account`: moves its whole balance to `destination`, zeroes its
+/// data and writes [`CLOSED_ACCOUNT_DISCRIMINATOR`] over the first bytes.
+///
+/// The account stays owned by the program: the runtime only lets an owner
+/// change through when the data is all zeros, which the tombstone is not.
+///
+/// Used by `#[account(close = ..)]`; handlers closing an account by hand
+/// should call it too. `Account` wrappers of a closed account skip writing
+/// their data back when dropped.
+pub fn close_account(
+    account: &AccountInfo<'_>,
+    destination: &AccountInfo<'_>,
+) -> Result<(), ProgramError> {
+    if account.key == destination.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Move the whole balance to the destination.
+    let balance = account.lamports();
+    let destination_balance = destination
+        .lamports()
+        .checked_add(balance)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **destination.try_borrow_mut_lamports()? = destination_balance;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    // Wipe the data and mark the account as closed.
+    let mut data = account.try_borrow_mut_data()?;
+    data.fill(0);
+    let tombstone_len = data.len().min(CLOSED_ACCOUNT_DISCRIMINATOR.len());
+    data[..tombstone_len].copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR[..tombstone_len]);
+    Ok(())
+}
+
+/// Whether `account` was closed by [`close_account`].
+pub fn is_closed(account: &AccountInfo<'_>) -> bool {
+    account.try_borrow_data().map_or(false, |data| {
+        data.starts_with(&CLOSED_ACCOUNT_DISCRIMINATOR)
+    })
+}
+
+/// Checks that no two `accounts` share a key, else
+/// `ErrorCode::DuplicateAccount`. Accounts keyed `placeholder` (the slot left
+/// by an omitted optional account) are not compared.
+pub fn ensure_unique_keys(
+    accounts: &[AccountInfo<'_>],
+    placeholder: Option<&Pubkey>,
+) -> Result<(), ProgramError> {
+    for (i, acc) in accounts.iter().enumerate() {
+        if placeholder == Some(acc.key) {
+            continue;
+        }
+        if accounts[i + 1..].iter().any(|other| other.key == acc.key) {
+            return Err(ProgramError::Custom(ErrorCode::DuplicateAccount.into()));
+        }
+    }
+    Ok(())
+}
+
 #[doc(hidden)]
 /// Internal module used by Saturn procedural macros.
 /// Mirrors Anchor's `anchor_lang::__private` so that generated code can reliably
@@ -239,4 +611,136 @@ pub mod __private {
     pub mod __borsh {
         pub use borsh::{BorshDeserialize, BorshSerialize};
     }
+
+    /// `false` when a discriminator appears twice across `sets`. Evaluated in
+    /// a const by `#[saturn_program(modules = [..])]` so two modules cannot
+    /// define handlers of the same name.
+    pub const fn discriminators_unique(sets: &[&[[u8; 8]]]) -> bool {
+        let mut a = 0;
+        while a < sets.len() {
+            let mut i = 0;
+            while i < sets[a].len() {
+                // Compare with every later discriminator, in this set and the next ones.
+                let mut b = a;
+                while b < sets.len() {
+                    let mut j = if b == a { i + 1 } else { 0 };
+                    while j < sets[b].len() {
+                        let mut k = 0;
+                        while k < 8 && sets[a][i][k] == sets[b][j][k] {
+                            k += 1;
+                        }
+                        if k == 8 {
+                            return false;
+                        }
+                        j += 1;
+                    }
+                    b += 1;
+                }
+                i += 1;
+            }
+            a += 1;
+        }
+        true
+    }
+
+    /// `Option<u8>` equality usable in a const, for the wire versions of a
+    /// program and its modules.
+    pub const fn same_version(a: Option<u8>, b: Option<u8>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{counter_account, Counter};
+
+    struct Pool<'a> {
+        pool: AccountLoader<'a, Counter>,
+        updates: u32,
+    }
+
+    #[derive(Debug, Default)]
+    struct PoolBumps {
+        seeds: SignerSeeds,
+    }
+
+    impl PdaSeeds for PoolBumps {
+        fn signer_seeds(&self) -> &SignerSeeds {
+            &self.seeds
+        }
+    }
+
+    impl<'a> Accounts<'a> for Pool<'a> {
+        fn try_accounts(accounts: &'a [AccountInfo<'a>]) -> Result<Self, ProgramError> {
+            Ok(Self {
+                pool: AccountLoader::new(get_account(accounts, 0, None, None, None)?),
+                updates: 0,
+            })
+        }
+    }
+
+    impl<'a> Bumps<'a> for Pool<'a> {
+        type Bumps = PoolBumps;
+
+        fn try_accounts_prefix_with_args(
+            accounts: &'a [AccountInfo<'a>],
+            _ix_args: &[u8],
+        ) -> Result<(Self, Self::Bumps, usize), ProgramError> {
+            let mut bumps = PoolBumps::default();
+            bumps.seeds.record("pool", &[b"pool"], 254);
+            Ok((Self::try_accounts(accounts)?, bumps, 1))
+        }
+    }
+
+    #[test]
+    fn close_account_moves_funds_and_leaves_a_tombstone() {
+        let account = counter_account(5);
+        let owner = *account.owner;
+        let destination = counter_account(0);
+        let balance = account.lamports();
+
+        close_account(account, destination).unwrap();
+
+        assert_eq!(account.lamports(), 0);
+        assert_eq!(destination.lamports(), 2 * balance);
+        assert!(is_closed(account));
+        assert!(account.try_borrow_data().unwrap()[8..]
+            .iter()
+            .all(|&b| b == 0));
+        assert_eq!(*account.owner, owner);
+    }
+
+    #[test]
+    fn split_borrows_are_usable_together() {
+        let infos = vec![counter_account(1).clone()];
+        let infos: &'static [AccountInfo<'static>] = Box::leak(infos.into_boxed_slice());
+        let program_id = Pubkey::new_unique();
+        let (mut accounts, bumps) = Pool::try_accounts_with_bumps(infos).unwrap();
+        let mut ctx = Context::new_with_btc_tx(
+            &program_id,
+            &mut accounts,
+            &infos[1..],
+            bumps,
+            Vec::<Pubkey>::new(),
+        );
+
+        let parts = ctx.split();
+        let seeds = parts.signer_seeds("pool").unwrap();
+        let pool: &mut Pool = parts.accounts;
+        let transitions: &mut Vec<Pubkey> = parts.btc_tx;
+
+        pool.pool.load_mut().unwrap().value += 1;
+        pool.updates += 1;
+        transitions.push(*pool.pool.info().key);
+        assert_eq!(seeds, [&b"pool"[..], &[254][..]]);
+
+        assert_eq!(ctx.accounts.pool.load().unwrap().value, 2);
+        assert_eq!(ctx.accounts.updates, 1);
+        assert_eq!(ctx.btc_tx, [*infos[0].key]);
+    }
 }
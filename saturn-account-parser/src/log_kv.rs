@@ -0,0 +1,234 @@
+//! Structured key-value logging.
+//!
+//! [`log_kv!`](macro@crate::log_kv) writes its fields as one compact `msg!`
+//! line, which log scrapers turn back into a typed map with [`decode_kv`] or
+//! [`decode_kvs`] instead of matching free-form strings:
+//!
+//! ```ignore
+//! log_kv!(action = "swap", shard = idx, amount_in = amt);
+//! // Program log: kv: action="swap" shard=3 amount_in=1000
+//!
+//! // Off-chain, from the logs of the processed transaction:
+//! for fields in decode_kvs(&logs) {
+//!     let amount_in = fields.get("amount_in").and_then(KvValue::as_u64);
+//! }
+//! ```
+//!
+//! Integers are written in decimal, booleans as `true` / `false`, and
+//! strings and public keys (in hex) quoted, with `"`, `\` and line breaks
+//! escaped.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use arch_program::pubkey::Pubkey;
+
+/// Prefix of the log line written by [`log_kv!`](macro@crate::log_kv).
+pub const KV_LOG_PREFIX: &str = "kv: ";
+
+/// A value [`log_kv!`](macro@crate::log_kv) can log.
+pub trait LogValue {
+    /// Appends the encoded value to `out`.
+    fn write_value(&self, out: &mut String);
+}
+
+macro_rules! impl_log_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl LogValue for $ty {
+                fn write_value(&self, out: &mut String) {
+                    // Writing into a `String` cannot fail.
+                    let _ = write!(out, "{}", self);
+                }
+            }
+        )*
+    };
+}
+
+impl_log_value_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl LogValue for bool {
+    fn write_value(&self, out: &mut String) {
+        out.push_str(if *self { "true" } else { "false" });
+    }
+}
+
+impl LogValue for str {
+    fn write_value(&self, out: &mut String) {
+        out.push('"');
+        for c in self.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+}
+
+impl LogValue for String {
+    fn write_value(&self, out: &mut String) {
+        self.as_str().write_value(out);
+    }
+}
+
+impl LogValue for Pubkey {
+    fn write_value(&self, out: &mut String) {
+        self.to_string().write_value(out);
+    }
+}
+
+impl<T: LogValue + ?Sized> LogValue for &T {
+    fn write_value(&self, out: &mut String) {
+        (**self).write_value(out);
+    }
+}
+
+/// The log line [`log_kv!`](macro@crate::log_kv) writes for `fields`:
+/// `kv: key=value key=value ..`.
+pub fn log_line(fields: &[(&str, &dyn LogValue)]) -> String {
+    let mut line = String::from(KV_LOG_PREFIX);
+    for (i, (key, value)) in fields.iter().enumerate() {
+        if i > 0 {
+            line.push(' ');
+        }
+        line.push_str(key);
+        line.push('=');
+        value.write_value(&mut line);
+    }
+    line
+}
+
+/// Logs `fields`. Use [`log_kv!`](macro@crate::log_kv).
+pub fn log(fields: &[(&str, &dyn LogValue)]) {
+    arch_program::msg!("{}", log_line(fields));
+}
+
+/// Logs `key = value` pairs as one machine-parseable line, e.g.
+/// `log_kv!(action = "swap", shard = idx, amount_in = amt)`.
+#[macro_export]
+macro_rules! log_kv {
+    ($($key:ident = $value:expr),+ $(,)?) => {
+        $crate::log_kv::log(&[
+            $((stringify!($key), &$value as &dyn $crate::log_kv::LogValue)),+
+        ])
+    };
+}
+
+/// A value decoded from a [`log_kv!`](macro@crate::log_kv) line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KvValue {
+    /// A non-negative integer.
+    UInt(u128),
+    /// A negative integer.
+    Int(i128),
+    Bool(bool),
+    /// A string, or a public key in hex.
+    Str(String),
+}
+
+impl KvValue {
+    /// The value as a `u64`, if it is an integer in range.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            KvValue::UInt(value) => u64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    /// The value as an `i64`, if it is an integer in range.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            KvValue::UInt(value) => i64::try_from(*value).ok(),
+            KvValue::Int(value) => i64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    /// The value as a `bool`, if it is one.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            KvValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value as a `&str`, if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            KvValue::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes the fields of one log line, or `None` when the line was not
+/// written by [`log_kv!`](macro@crate::log_kv) or is malformed.
+///
+/// Lines prefixed by the runtime (`Program log: kv: ..`) are accepted as
+/// well as bare ones. A key logged twice keeps its last value.
+pub fn decode_kv(log: &str) -> Option<BTreeMap<String, KvValue>> {
+    let start = log.find(KV_LOG_PREFIX)? + KV_LOG_PREFIX.len();
+    let mut rest = log[start..].trim_end();
+    let mut fields = BTreeMap::new();
+
+    while !rest.is_empty() {
+        let (key, after_key) = rest.split_once('=')?;
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+        let (value, after_value) = decode_value(after_key)?;
+        fields.insert(key.to_string(), value);
+        rest = match after_value.strip_prefix(' ') {
+            Some(next) => next,
+            None if after_value.is_empty() => after_value,
+            None => return None,
+        };
+    }
+
+    Some(fields)
+}
+
+/// The fields of every [`log_kv!`](macro@crate::log_kv) line in `logs`, in
+/// order; other lines are skipped.
+pub fn decode_kvs<S: AsRef<str>>(logs: &[S]) -> Vec<BTreeMap<String, KvValue>> {
+    logs.iter()
+        .filter_map(|log| decode_kv(log.as_ref()))
+        .collect()
+}
+
+/// Decodes the value at the start of `input`, returning it with the input
+/// left after it.
+fn decode_value(input: &str) -> Option<(KvValue, &str)> {
+    if let Some(quoted) = input.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Some((KvValue::Str(value), &quoted[i + 1..])),
+                '\\' => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    'r' => value.push('\r'),
+                    escaped @ ('"' | '\\') => value.push(escaped),
+                    _ => return None,
+                },
+                c => value.push(c),
+            }
+        }
+        // Unterminated string.
+        return None;
+    }
+
+    let end = input.find(' ').unwrap_or(input.len());
+    let (token, rest) = input.split_at(end);
+    let value = match token {
+        "true" => KvValue::Bool(true),
+        "false" => KvValue::Bool(false),
+        _ if token.starts_with('-') => KvValue::Int(token.parse().ok()?),
+        _ => KvValue::UInt(token.parse().ok()?),
+    };
+    Some((value, rest))
+}
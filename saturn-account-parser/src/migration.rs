@@ -0,0 +1,245 @@
+//! Versioned zero-copy layouts, upgraded in place.
+//!
+//! A [`Migratable`] type records the version of its layout in one byte of the
+//! account data. [`AccountLoader::load_or_migrate`] upgrades an account still
+//! stored in an older layout by running the type's [`Migration`]s in sequence,
+//! resizing the account when the size of the layout changed, and writing the
+//! upgraded data back before handing it out.
+//!
+//! Older layouts keep the discriminator of the current type, so they are
+//! declared as plain `Pod` structs (e.g. `PoolV1`) used by the migrations only.
+
+use arch_program::program_error::ProgramError;
+use bytemuck::Pod;
+use core::mem::size_of;
+
+use crate::borrow_registry::BorrowGuard;
+use crate::codec::zero_copy::{AccountLoader, AccountRefMut, Discriminator};
+use crate::error::ErrorCode;
+
+/// One upgrade step of a [`Migratable`] layout.
+#[derive(Clone, Copy)]
+pub struct Migration {
+    /// Version of the data this step upgrades.
+    pub from: u8,
+    /// Rewrites the data (discriminator excluded) of version `from` as that of
+    /// version `from + 1`, usually with [`migrate_pod`].
+    pub migrate: fn(&[u8]) -> Result<Vec<u8>, ProgramError>,
+}
+
+/// Zero-copy layout that records its version and can upgrade older ones.
+pub trait Migratable: Pod + Discriminator {
+    /// Version of this layout.
+    const VERSION: u8;
+    /// Offset of the version byte in the data (discriminator excluded). It
+    /// must be the same in every version.
+    const VERSION_OFFSET: usize = 0;
+    /// One step per older version still accepted.
+    const MIGRATIONS: &'static [Migration];
+}
+
+/// Rewrites `data` holding an `Old` as the `New` built by `upgrade`, for
+/// implementing [`Migration::migrate`]:
+///
+/// ```ignore
+/// fn v1_to_v2(data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+///     migrate_pod(data, |old: PoolV1| Pool { version: 2, reserve: old.reserve, fee_bps: 30 })
+/// }
+/// ```
+pub fn migrate_pod<Old: Pod, New: Pod>(
+    data: &[u8],
+    upgrade: impl FnOnce(Old) -> New,
+) -> Result<Vec<u8>, ProgramError> {
+    let old_bytes = data
+        .get(..size_of::<Old>())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let new = upgrade(bytemuck::pod_read_unaligned(old_bytes));
+    Ok(bytemuck::bytes_of(&new).to_vec())
+}
+
+impl<'a, T> AccountLoader<'a, T>
+where
+    T: Migratable + 'static,
+{
+    /// Version of the layout the account is stored in.
+    pub fn version(&self) -> Result<u8, ProgramError> {
+        let data = self.info().try_borrow_data()?;
+        read_version::<T>(&data, self.data_prefix())
+    }
+
+    /// Mutable borrow of the account, upgraded to `T::VERSION` first when it
+    /// is stored in an older layout.
+    ///
+    /// Fails with `ErrorCode::UnsupportedAccountVersion` for versions newer
+    /// than `T::VERSION` or without a migration to the next one. A layout that
+    /// grows is reallocated without funding it; check the balance afterwards
+    /// (e.g. with `rent_exempt`) when the size changes.
+    pub fn load_or_migrate(&self) -> Result<AccountRefMut<'a, T>, ProgramError> {
+        let version = self.version()?;
+        if version > T::VERSION {
+            return Err(ProgramError::Custom(
+                ErrorCode::UnsupportedAccountVersion.into(),
+            ));
+        }
+        if version < T::VERSION {
+            let _guard = BorrowGuard::exclusive(self.info().key)?;
+            self.migrate(version)?;
+        }
+        self.load_mut()
+    }
+
+    /// Runs the migrations from `version` up to `T::VERSION` and writes the
+    /// result back, resizing the account to the new layout.
+    fn migrate(&self, mut version: u8) -> Result<(), ProgramError> {
+        let account = self.info();
+        if !account.is_writable {
+            return Err(ProgramError::Custom(
+                ErrorCode::IncorrectIsWritableFlag.into(),
+            ));
+        }
+        let unsupported = || ProgramError::Custom(ErrorCode::UnsupportedAccountVersion.into());
+
+        let prefix_len = self.data_prefix().len();
+        let mut bytes = account.try_borrow_data()?[prefix_len..].to_vec();
+        while version < T::VERSION {
+            let step = T::MIGRATIONS
+                .iter()
+                .find(|migration| migration.from == version)
+                .ok_or_else(unsupported)?;
+            bytes = (step.migrate)(&bytes)?;
+            version += 1;
+        }
+        if bytes.len() != size_of::<T>() || read_version::<T>(&bytes, &[])? != T::VERSION {
+            return Err(unsupported());
+        }
+
+        let new_len = prefix_len + bytes.len();
+        if account.data_len() != new_len {
+            account.realloc(new_len, false)?;
+        }
+        account.try_borrow_mut_data()?[prefix_len..].copy_from_slice(&bytes);
+        Ok(())
+    }
+}
+
+/// Version byte of the `T` stored in `data` after `prefix`.
+fn read_version<T: Migratable>(data: &[u8], prefix: &[u8]) -> Result<u8, ProgramError> {
+    if !data.starts_with(prefix) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    data.get(prefix.len() + T::VERSION_OFFSET)
+        .copied()
+        .ok_or(ProgramError::InvalidAccountData)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::account_info;
+    use arch_program::{account::AccountInfo, pubkey::Pubkey};
+    use bytemuck::Zeroable;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Pod, Zeroable)]
+    struct PoolV1 {
+        version: u8,
+        _padding: [u8; 7],
+        reserve: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+    struct Pool {
+        version: u8,
+        _padding: [u8; 7],
+        reserve: u64,
+        fee_bps: u64,
+    }
+
+    impl Discriminator for Pool {
+        const DISCRIMINATOR: [u8; 8] = *b"pool____";
+    }
+
+    fn v1_to_v2(data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+        migrate_pod(data, |old: PoolV1| Pool {
+            version: 2,
+            _padding: [0; 7],
+            reserve: old.reserve,
+            fee_bps: 30,
+        })
+    }
+
+    impl Migratable for Pool {
+        const VERSION: u8 = 2;
+        const MIGRATIONS: &'static [Migration] = &[Migration {
+            from: 1,
+            migrate: v1_to_v2,
+        }];
+    }
+
+    fn pool_account(layout: &[u8]) -> &'static AccountInfo<'static> {
+        let mut data = Pool::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(layout);
+        account_info(
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            &data,
+            arch_program::account::MIN_ACCOUNT_LAMPORTS,
+            size_of::<Pool>() - size_of::<PoolV1>(),
+        )
+    }
+
+    #[test]
+    fn upgrades_and_grows_an_older_layout() {
+        let v1 = PoolV1 {
+            version: 1,
+            _padding: [0; 7],
+            reserve: 500,
+        };
+        let info = pool_account(bytemuck::bytes_of(&v1));
+        let loader = AccountLoader::<Pool>::new(info);
+        assert_eq!(loader.version(), Ok(1));
+
+        let pool = *loader.load_or_migrate().unwrap();
+        assert_eq!(
+            pool,
+            Pool {
+                version: 2,
+                _padding: [0; 7],
+                reserve: 500,
+                fee_bps: 30,
+            }
+        );
+        assert_eq!(info.data_len(), 8 + size_of::<Pool>());
+        assert_eq!(&info.data.borrow()[8..], bytemuck::bytes_of(&pool));
+        assert_eq!(loader.version(), Ok(2));
+    }
+
+    #[test]
+    fn leaves_the_current_layout_alone() {
+        let pool = Pool {
+            version: 2,
+            _padding: [0; 7],
+            reserve: 7,
+            fee_bps: 10,
+        };
+        let info = pool_account(bytemuck::bytes_of(&pool));
+
+        assert_eq!(
+            *AccountLoader::<Pool>::new(info).load_or_migrate().unwrap(),
+            pool
+        );
+    }
+
+    #[test]
+    fn refuses_newer_and_unknown_versions() {
+        let unsupported = Some(ProgramError::Custom(
+            ErrorCode::UnsupportedAccountVersion.into(),
+        ));
+        for version in [0, 3] {
+            let info = pool_account(&[version, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+            let loader = AccountLoader::<Pool>::new(info);
+            assert_eq!(loader.load_or_migrate().err(), unsupported);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Replay guard behind `#[nonce(..)]`.
+//!
+//! `#[saturn_program]` expands
+//! `#[nonce(account = user_state, field = last_nonce)]` on a handler into a
+//! [`check_and_bump`] call ahead of its body: the instruction's `nonce`
+//! parameter must be the nonce stored in `ctx.accounts.user_state` plus one,
+//! and becomes the stored nonce. Replaying an instruction, even within the
+//! same block, fails with `SaturnErrorCode::InvalidNonce` instead of applying
+//! it twice:
+//!
+//! ```ignore
+//! #[nonce(account = user_state, field = last_nonce)]
+//! pub fn withdraw(ctx: Context<Withdraw>, nonce: u64, amount: u64) -> Result<(), ProgramError> {
+//!     // ..
+//! }
+//! ```
+//!
+//! `arg = ..` names the parameter carrying the nonce when it is not `nonce`.
+//! Clients read the stored nonce and send the next one.
+
+use arch_program::program_error::ProgramError;
+use saturn_error::SaturnErrorCode;
+
+use crate::state::StateAccount;
+
+/// Accepts `nonce` if it is the one stored in `account` (in the field
+/// `field` selects) plus one, and stores it. Otherwise fails with
+/// `SaturnErrorCode::InvalidNonce`, logging `name` (the field of the
+/// `Accounts` struct) and both nonces.
+pub fn check_and_bump<A: StateAccount>(
+    account: &mut A,
+    name: &str,
+    nonce: u64,
+    field: impl FnOnce(&mut A::Data) -> &mut u64,
+) -> Result<(), ProgramError> {
+    account.with_data_mut(|data| {
+        let stored = field(data);
+        if stored.checked_add(1) != Some(nonce) {
+            arch_program::msg!(
+                "`{}`: nonce {} does not follow the stored nonce {}",
+                name,
+                nonce,
+                stored
+            );
+            return Err(SaturnErrorCode::InvalidNonce.into());
+        }
+        *stored = nonce;
+        Ok(())
+    })?
+}
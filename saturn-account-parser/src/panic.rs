@@ -0,0 +1,105 @@
+//! Handler panics turned into [`SaturnErrorCode::HandlerPanicked`].
+//!
+//! `#[saturn_program]` runs its dispatcher through [`catch`]. Where panics
+//! unwind (host builds: unit tests, local simulators) a panicking handler
+//! logs `Handler panicked at <file>:<line>: <message>` and the instruction
+//! fails with `HandlerPanicked` instead of tearing down the caller.
+//!
+//! On-chain builds use `panic = "abort"`, where nothing can be caught: there
+//! [`catch`] only calls the dispatcher and the instruction fails with the
+//! runtime's own error. The entrypoint of `#[saturn_program]` installs
+//! [`report`] as the program's `custom_panic`, so the logs still carry
+//! `HandlerPanicked`, its code and the location of the panic.
+
+use arch_program::program_error::ProgramError;
+
+/// Runs `dispatch`, converting a panic into
+/// [`SaturnErrorCode::HandlerPanicked`] when the target unwinds.
+///
+/// [`SaturnErrorCode::HandlerPanicked`]: saturn_error::SaturnErrorCode::HandlerPanicked
+#[cfg(panic = "unwind")]
+pub fn catch<F>(dispatch: F) -> Result<(), ProgramError>
+where
+    F: FnOnce() -> Result<(), ProgramError>,
+{
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    install_hook();
+    CATCHING.with(|depth| depth.set(depth.get() + 1));
+    let result = catch_unwind(AssertUnwindSafe(dispatch));
+    CATCHING.with(|depth| depth.set(depth.get() - 1));
+
+    result.unwrap_or_else(|_| {
+        let report = LAST_PANIC
+            .with(|last| last.borrow_mut().take())
+            .unwrap_or_else(|| "unknown location".to_string());
+        arch_program::msg!("Handler panicked at {}", report);
+        Err(saturn_error::SaturnErrorCode::HandlerPanicked.into())
+    })
+}
+
+/// Runs `dispatch`; panics abort on this target, after [`report`].
+#[cfg(not(panic = "unwind"))]
+pub fn catch<F>(dispatch: F) -> Result<(), ProgramError>
+where
+    F: FnOnce() -> Result<(), ProgramError>,
+{
+    dispatch()
+}
+
+/// Logs `HandlerPanicked` with its code and the location of the panic, then
+/// the panic itself. Called by the `custom_panic` of the `#[saturn_program]`
+/// entrypoint before the runtime aborts the instruction.
+pub fn report(info: &core::panic::PanicInfo<'_>) {
+    let code = saturn_error::SaturnErrorCode::HandlerPanicked;
+    match info.location() {
+        Some(location) => arch_program::msg!(
+            "Handler panicked at {}:{}: {:?} (error {})",
+            location.file(),
+            location.line(),
+            code,
+            u32::from(code)
+        ),
+        None => arch_program::msg!(
+            "Handler panicked at unknown location: {:?} (error {})",
+            code,
+            u32::from(code)
+        ),
+    }
+    arch_program::msg!("{}", info);
+}
+
+#[cfg(panic = "unwind")]
+thread_local! {
+    /// Number of [`catch`] calls in progress on this thread.
+    static CATCHING: std::cell::Cell<usize> = std::cell::Cell::new(0);
+    /// `<file>:<line>: <message>` of the panic being caught.
+    static LAST_PANIC: std::cell::RefCell<Option<String>> = std::cell::RefCell::new(None);
+}
+
+/// Installs, once per process, a panic hook recording the panics caught by
+/// [`catch`] instead of printing them. Other panics go to the previous hook.
+#[cfg(panic = "unwind")]
+fn install_hook() {
+    static HOOK: std::sync::Once = std::sync::Once::new();
+
+    HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if CATCHING.with(|depth| depth.get()) == 0 {
+                return previous(info);
+            }
+            let location = info
+                .location()
+                .map(|location| format!("{}:{}", location.file(), location.line()))
+                .unwrap_or_else(|| "unknown location".to_string());
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(format!("{location}: {message}")));
+        }));
+    });
+}
@@ -0,0 +1,170 @@
+//! Typed access to the remaining accounts of an instruction.
+//!
+//! [`Context::remaining`](crate::Context::remaining) wraps every account of
+//! `remaining_accounts` into an [`Account`] or [`AccountLoader`] once it passed
+//! the checks the `Accounts` derive would run on such a field, so handlers
+//! taking a variable number of accounts need not validate them by hand:
+//!
+//! ```ignore
+//! for position in ctx.remaining::<AccountLoader<'info, Position>>() {
+//!     let position = position?;
+//!     total += position.load()?.size;
+//! }
+//! ```
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::pubkey::Pubkey;
+use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
+use core::marker::PhantomData;
+
+use crate::codec::zero_copy::Discriminator;
+use crate::codec::{Account, AccountLoader, ZeroCopyCodec};
+use crate::error::{account_error, ErrorCode};
+
+/// Account wrapper a remaining account can be checked and wrapped into.
+pub trait RemainingAccount<'info>: Sized {
+    /// Wraps `info` once it is owned by `program_id` and holds a `Self`.
+    fn try_from_remaining(
+        info: &'info AccountInfo<'info>,
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError>;
+}
+
+impl<'info, T> RemainingAccount<'info> for Account<'info, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn try_from_remaining(
+        info: &'info AccountInfo<'info>,
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        check_owner(info, program_id)?;
+        Account::load(info)
+    }
+}
+
+impl<'info, T> RemainingAccount<'info> for AccountLoader<'info, T>
+where
+    T: Pod + Zeroable + Discriminator + 'static,
+{
+    fn try_from_remaining(
+        info: &'info AccountInfo<'info>,
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        check_owner(info, program_id)?;
+        // Checks the discriminator and length of the data.
+        ZeroCopyCodec::load_copy::<T>(info)?;
+        Ok(AccountLoader::new(info))
+    }
+}
+
+fn check_owner(info: &AccountInfo<'_>, program_id: &Pubkey) -> Result<(), ProgramError> {
+    if info.owner != program_id {
+        return Err(account_error(
+            info.key,
+            ErrorCode::InvalidAccountOwner,
+            format_args!("owned by {}, expected {}", info.owner, program_id),
+        ));
+    }
+    Ok(())
+}
+
+/// A remaining account that failed its checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemainingAccountError {
+    /// Position of the account in `remaining_accounts`.
+    pub index: usize,
+    /// Why the account was rejected.
+    pub error: ProgramError,
+}
+
+impl From<RemainingAccountError> for ProgramError {
+    fn from(err: RemainingAccountError) -> Self {
+        err.error
+    }
+}
+
+/// Iterator returned by [`Context::remaining`](crate::Context::remaining).
+pub struct RemainingAccounts<'info, A> {
+    accounts: core::slice::Iter<'info, AccountInfo<'info>>,
+    program_id: Pubkey,
+    index: usize,
+    _wrapper: PhantomData<A>,
+}
+
+impl<'info, A> RemainingAccounts<'info, A>
+where
+    A: RemainingAccount<'info>,
+{
+    /// Wraps the `accounts` owned by `program_id` into `A`s.
+    pub fn new(accounts: &'info [AccountInfo<'info>], program_id: &Pubkey) -> Self {
+        Self {
+            accounts: accounts.iter(),
+            program_id: *program_id,
+            index: 0,
+            _wrapper: PhantomData,
+        }
+    }
+}
+
+impl<'info, A> Iterator for RemainingAccounts<'info, A>
+where
+    A: RemainingAccount<'info>,
+{
+    type Item = Result<A, RemainingAccountError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let info = self.accounts.next()?;
+        let index = self.index;
+        self.index += 1;
+        Some(
+            A::try_from_remaining(info, &self.program_id)
+                .map_err(|error| RemainingAccountError { index, error }),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.accounts.size_hint()
+    }
+}
+
+impl<'info, A> ExactSizeIterator for RemainingAccounts<'info, A> where A: RemainingAccount<'info> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{account_info, counter_account, counter_data, Counter};
+
+    #[test]
+    fn wraps_accounts_and_reports_the_position_of_rejected_ones() {
+        let program_id = Pubkey::default();
+        let foreign = account_info(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            &counter_data(2),
+            arch_program::account::MIN_ACCOUNT_LAMPORTS,
+            0,
+        );
+        let accounts: &'static [AccountInfo<'static>] = Box::leak(Box::new([
+            counter_account(1).clone(),
+            foreign.clone(),
+            counter_account(3).clone(),
+        ]));
+
+        let remaining =
+            RemainingAccounts::<AccountLoader<'static, Counter>>::new(accounts, &program_id);
+        assert_eq!(remaining.len(), 3);
+
+        let results: Vec<_> = remaining.collect();
+        assert_eq!(results[0].as_ref().unwrap().load().unwrap().value, 1);
+        let rejected = results[1].as_ref().err().unwrap();
+        assert_eq!(rejected.index, 1);
+        assert_eq!(
+            rejected.error,
+            ProgramError::Custom(ErrorCode::InvalidAccountOwner.into())
+        );
+        assert_eq!(results[2].as_ref().unwrap().load().unwrap().value, 3);
+    }
+}
@@ -0,0 +1,84 @@
+//! Security contact information embedded in the program binary.
+//!
+//! [`security_txt!`](macro@crate::security_txt) follows the `solana-security-txt`
+//! convention: a NUL-separated list of `key` / `value` pairs between
+//! [`BEGIN_MARKER`] and [`END_MARKER`], stored in a `.security.txt` section,
+//! so explorers and auditors find the contacts of a deployed program without
+//! its source:
+//!
+//! ```ignore
+//! saturn_account_parser::security_txt! {
+//!     name: "Saturn AMM",
+//!     project_url: "https://example.com",
+//!     contacts: "email:security@example.com,link:https://example.com/security",
+//!     policy: "https://example.com/security-policy",
+//!     source_revision: env!("GIT_HASH"),
+//! }
+//! ```
+//!
+//! `name`, `project_url`, `contacts` and `policy` are expected by readers;
+//! `preferred_languages`, `source_code`, `source_revision`, `source_release`,
+//! `encryption`, `auditors`, `acknowledgements` and `expiry` are optional.
+//! Values must be string literals or macros expanding to one (`env!`,
+//! `concat!`) and must not contain NUL bytes.
+
+/// Start of the embedded data.
+pub const BEGIN_MARKER: &str = "=======BEGIN SECURITY.TXT V1=======\0";
+
+/// End of the embedded data.
+pub const END_MARKER: &str = "=======END SECURITY.TXT V1=======\0";
+
+/// Embeds security contact information in the program binary; see the
+/// [`security_txt`](mod@crate::security_txt) module.
+///
+/// Like the entrypoint, it is left out with the `no-entrypoint` feature so a
+/// program linked into another one does not duplicate the section.
+#[macro_export]
+macro_rules! security_txt {
+    ($($key:ident: $value:expr),* $(,)?) => {
+        #[cfg(not(feature = "no-entrypoint"))]
+        #[allow(dead_code, non_upper_case_globals)]
+        #[no_mangle]
+        #[link_section = ".security.txt"]
+        pub static security_txt: &str = concat!(
+            "=======BEGIN SECURITY.TXT V1=======\0",
+            $(stringify!($key), "\0", $value, "\0",)*
+            "=======END SECURITY.TXT V1=======\0"
+        );
+    };
+}
+
+/// The `key` / `value` pairs embedded by
+/// [`security_txt!`](macro@crate::security_txt) in `binary`, e.g. the bytes of a
+/// deployed program, or `None` when it embeds none or they are malformed.
+pub fn parse(binary: &[u8]) -> Option<Vec<(String, String)>> {
+    let start = find(binary, BEGIN_MARKER.as_bytes())? + BEGIN_MARKER.len();
+    let len = find(&binary[start..], END_MARKER.as_bytes())?;
+    let body = &binary[start..start + len];
+
+    // Every key and value is followed by a NUL byte.
+    let body = body.strip_suffix(b"\0").unwrap_or(body);
+    if body.is_empty() {
+        return Some(Vec::new());
+    }
+    let fields = body
+        .split(|byte| *byte == 0)
+        .map(|field| String::from_utf8(field.to_vec()).ok())
+        .collect::<Option<Vec<String>>>()?;
+    if fields.len() % 2 != 0 {
+        return None;
+    }
+    Some(
+        fields
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect(),
+    )
+}
+
+/// Offset of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
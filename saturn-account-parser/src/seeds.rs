@@ -0,0 +1,110 @@
+//! Seeds of the PDA fields of an `Accounts` struct, kept for signing.
+//!
+//! `#[derive(Accounts)]` records the seeds of every `seeds = ..` field,
+//! followed by its bump, while the struct is parsed. A handler signing for
+//! one of those PDAs takes them from the context instead of rebuilding the
+//! seed array:
+//!
+//! ```ignore
+//! let seeds = ctx.signer_seeds("config")?;
+//! invoke_signed(&ix, &infos, &[&seeds])?;
+//! ```
+//!
+//! Recording runs for every instruction, whether its handler signs or not,
+//! so it only appends to a few flat buffers: fields are keyed by their
+//! `&'static str` name and their seeds copied back to back.
+
+use core::ops::Range;
+
+/// Signer seeds of the PDA fields of an `Accounts` struct, by field name.
+///
+/// The fields of a `nested` struct are named `"<outer>.<inner>"`.
+#[derive(Debug, Default, Clone)]
+pub struct SignerSeeds {
+    /// Every recorded seed, bumps included, back to back.
+    bytes: Vec<u8>,
+    /// End in `bytes` of every recorded seed.
+    ends: Vec<usize>,
+    /// Field name and range in `ends` of its seeds.
+    entries: Vec<(&'static str, Range<usize>)>,
+    /// Seeds of the `nested` fields, by field name.
+    nested: Vec<(&'static str, SignerSeeds)>,
+}
+
+impl SignerSeeds {
+    /// Records the `seeds` of `field`, followed by its `bump`.
+    #[doc(hidden)]
+    pub fn record(&mut self, field: &'static str, seeds: &[&[u8]], bump: u8) {
+        let first = self.ends.len();
+        for seed in seeds {
+            self.bytes.extend_from_slice(seed);
+            self.ends.push(self.bytes.len());
+        }
+        self.bytes.push(bump);
+        self.ends.push(self.bytes.len());
+        self.entries.push((field, first..self.ends.len()));
+    }
+
+    /// Records the seeds of the `nested` struct held by `field`.
+    #[doc(hidden)]
+    pub fn record_nested(&mut self, field: &'static str, nested: &SignerSeeds) {
+        if !nested.entries.is_empty() || !nested.nested.is_empty() {
+            self.nested.push((field, nested.clone()));
+        }
+    }
+
+    /// The seeds of `field`, bump last, ready for `invoke_signed`. `None`
+    /// when `field` is not a PDA field or is an optional one that was not
+    /// passed.
+    pub fn get(&self, field: &str) -> Option<Vec<&[u8]>> {
+        if let Some((outer, inner)) = field.split_once('.') {
+            return self
+                .nested
+                .iter()
+                .find(|(name, _)| *name == outer)
+                .and_then(|(_, nested)| nested.get(inner));
+        }
+        let (_, seeds) = self.entries.iter().find(|(name, _)| *name == field)?;
+        Some(
+            seeds
+                .clone()
+                .map(|seed| {
+                    let start = if seed == 0 { 0 } else { self.ends[seed - 1] };
+                    &self.bytes[start..self.ends[seed]]
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Access to the [`SignerSeeds`] kept by the `<Struct>Bumps` type that
+/// `#[derive(Accounts)]` generates.
+pub trait PdaSeeds {
+    /// The seeds recorded while parsing the accounts.
+    fn signer_seeds(&self) -> &SignerSeeds;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_the_seeds_of_fields_and_nested_fields() {
+        let mut inner = SignerSeeds::default();
+        inner.record("vault", &[b"vault", &[7; 32]], 253);
+
+        let mut seeds = SignerSeeds::default();
+        seeds.record("config", &[b"config"], 255);
+        seeds.record("empty", &[], 254);
+        seeds.record_nested("pool", &inner);
+
+        assert_eq!(seeds.get("config"), Some(vec![&b"config"[..], &[255]]));
+        assert_eq!(seeds.get("empty"), Some(vec![&[254][..]]));
+        assert_eq!(
+            seeds.get("pool.vault"),
+            Some(vec![&b"vault"[..], &[7; 32], &[253]])
+        );
+        assert_eq!(seeds.get("vault"), None);
+        assert_eq!(seeds.get("pool.config"), None);
+    }
+}
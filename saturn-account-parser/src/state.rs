@@ -0,0 +1,84 @@
+//! Lifecycle checks behind `#[require_state(..)]`.
+//!
+//! `#[saturn_program]` expands `#[require_state(config => Initialized)]` on
+//! a handler into a [`require_state`] call ahead of its body, which reads the
+//! `state` field of `ctx.accounts.config` and fails with
+//! `SaturnErrorCode::InvalidAccountState` unless it equals `Initialized`:
+//!
+//! ```ignore
+//! #[require_state(config => Active | Paused, pool.status => Open)]
+//! pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<(), ProgramError> {
+//!     // ..
+//! }
+//! ```
+//!
+//! `account.field => ..` reads `field` instead of `state`. The states are
+//! compared with `==`, so they can be enum variants or constants of a
+//! zero-copy account, resolved in the handler's module.
+
+use arch_program::program_error::ProgramError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
+use saturn_error::SaturnErrorCode;
+
+use crate::codec::{Account, AccountLoader};
+
+/// An account whose data [`require_state`] can read, and
+/// [`nonce::check_and_bump`](crate::nonce::check_and_bump) update.
+pub trait StateAccount {
+    /// The deserialized data of the account.
+    type Data;
+
+    /// Calls `f` with the data of the account.
+    fn with_data<R>(&self, f: impl FnOnce(&Self::Data) -> R) -> Result<R, ProgramError>;
+
+    /// Calls `f` with the data of the account, keeping its changes.
+    fn with_data_mut<R>(&mut self, f: impl FnOnce(&mut Self::Data) -> R)
+        -> Result<R, ProgramError>;
+}
+
+impl<T> StateAccount for Account<'_, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    type Data = T;
+
+    fn with_data<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, ProgramError> {
+        Ok(f(self))
+    }
+
+    // Written back when the account is dropped.
+    fn with_data_mut<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> Result<R, ProgramError> {
+        Ok(f(self))
+    }
+}
+
+impl<T> StateAccount for AccountLoader<'_, T>
+where
+    T: Pod + Zeroable + 'static,
+{
+    type Data = T;
+
+    fn with_data<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, ProgramError> {
+        Ok(f(&self.load()?))
+    }
+
+    fn with_data_mut<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> Result<R, ProgramError> {
+        Ok(f(&mut self.load_mut()?))
+    }
+}
+
+/// Fails with `SaturnErrorCode::InvalidAccountState` unless `allowed` holds
+/// for the data of `account`, logging `name` (the field of the `Accounts`
+/// struct) in that case.
+pub fn require_state<A: StateAccount>(
+    account: &A,
+    name: &str,
+    allowed: impl FnOnce(&A::Data) -> bool,
+) -> Result<(), ProgramError> {
+    if account.with_data(allowed)? {
+        return Ok(());
+    }
+    arch_program::msg!("`{}` is not in a state the instruction accepts", name);
+    Err(SaturnErrorCode::InvalidAccountState.into())
+}
@@ -0,0 +1,93 @@
+//! Runtime values handed to handlers as fields of an `Accounts` struct.
+//!
+//! Arch has no sysvar accounts: the clock and the Bitcoin block height come
+//! from syscalls, and rent is a flat minimum balance. A [`Sysvar<T>`] field is
+//! read from the runtime while the struct is parsed and does not take an
+//! account of the instruction, so handlers read e.g.
+//! `ctx.accounts.clock.unix_timestamp` instead of calling syscalls in the
+//! middle of their logic:
+//!
+//! ```ignore
+//! #[derive(Accounts)]
+//! pub struct Claim<'info> {
+//!     #[account(signer)]
+//!     user: AccountInfo<'info>,
+//!     clock: Sysvar<Clock>,
+//!     height: Sysvar<BitcoinBlockHeight>,
+//! }
+//! ```
+
+use arch_program::program_error::ProgramError;
+
+pub use arch_program::clock::Clock;
+
+/// Value of the runtime a [`Sysvar`] can hold.
+pub trait SysvarValue: Sized {
+    /// Reads the current value from the runtime.
+    fn fetch() -> Result<Self, ProgramError>;
+}
+
+impl SysvarValue for Clock {
+    fn fetch() -> Result<Self, ProgramError> {
+        Ok(arch_program::program::get_clock())
+    }
+}
+
+/// Minimum balance accounts must hold, see [`crate::minimum_balance`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rent;
+
+impl Rent {
+    /// Minimum balance of an account holding `data_len` bytes.
+    pub fn minimum_balance(&self, data_len: usize) -> u64 {
+        crate::minimum_balance(data_len)
+    }
+
+    /// Whether `lamports` is enough for an account holding `data_len` bytes.
+    pub fn is_exempt(&self, lamports: u64, data_len: usize) -> bool {
+        lamports >= self.minimum_balance(data_len)
+    }
+}
+
+impl SysvarValue for Rent {
+    fn fetch() -> Result<Self, ProgramError> {
+        Ok(Rent)
+    }
+}
+
+/// Height of the Bitcoin chain seen by the runtime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BitcoinBlockHeight(pub u64);
+
+impl SysvarValue for BitcoinBlockHeight {
+    fn fetch() -> Result<Self, ProgramError> {
+        Ok(BitcoinBlockHeight(
+            arch_program::program::get_bitcoin_block_height(),
+        ))
+    }
+}
+
+/// A [`SysvarValue`] read from the runtime, usually as a field of an
+/// `Accounts` struct (which takes no account for it).
+#[derive(Debug, Clone, Copy)]
+pub struct Sysvar<T: SysvarValue>(T);
+
+impl<T: SysvarValue> Sysvar<T> {
+    /// Reads the current value from the runtime.
+    pub fn get() -> Result<Self, ProgramError> {
+        T::fetch().map(Sysvar)
+    }
+
+    /// Returns the value read.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: SysvarValue> core::ops::Deref for Sysvar<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,79 @@
+//! Test helpers building in-memory accounts for the unit tests of this crate.
+
+// NOTE: The entire module is only compiled when running tests.
+#![cfg(test)]
+
+use arch_program::{account::AccountInfo, pubkey::Pubkey, utxo::UtxoMeta};
+use bytemuck::{Pod, Zeroable};
+
+use crate::codec::zero_copy::Discriminator;
+
+/// Offset of the account data inside the buffers built by [`account_info`]:
+/// the key, then the original and the current data length.
+const DATA_OFFSET: usize = 32 + 8 + 8;
+
+/// Zero-copy account type used by the unit tests.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+pub struct Counter {
+    pub value: u64,
+}
+
+impl Discriminator for Counter {
+    const DISCRIMINATOR: [u8; 8] = *b"counter_";
+}
+
+/// `Counter::DISCRIMINATOR` followed by a `Counter` holding `value`.
+pub fn counter_data(value: u64) -> Vec<u8> {
+    let mut data = Counter::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&value.to_le_bytes());
+    data
+}
+
+/// Builds a writable `AccountInfo` for `key` over a leaked buffer laid out
+/// like the runtime's input, so that `AccountInfo::realloc` can grow `data`
+/// by up to `spare` bytes.
+pub fn account_info(
+    key: Pubkey,
+    owner: Pubkey,
+    data: &[u8],
+    lamports: u64,
+    spare: usize,
+) -> &'static AccountInfo<'static> {
+    // `u64` words keep the data 8-byte aligned for zero-copy loads.
+    let words = (DATA_OFFSET + data.len() + spare).div_ceil(8);
+    let buffer: &'static mut [u64] = Box::leak(vec![0u64; words].into_boxed_slice());
+    let base = buffer.as_mut_ptr() as *mut u8;
+
+    // SAFETY: every range below lies inside the leaked buffer and the ranges
+    // do not overlap.
+    let (key_ref, bytes) = unsafe {
+        core::ptr::copy_nonoverlapping(key.0.as_ptr(), base, 32);
+        (base.add(32) as *mut u64).write(data.len() as u64);
+        (base.add(40) as *mut u64).write(data.len() as u64);
+        core::ptr::copy_nonoverlapping(data.as_ptr(), base.add(DATA_OFFSET), data.len());
+        (
+            &*(base as *const Pubkey),
+            core::slice::from_raw_parts_mut(base.add(DATA_OFFSET), data.len()),
+        )
+    };
+
+    let owner = Box::leak(Box::new(owner));
+    let utxo = Box::leak(Box::new(UtxoMeta::default()));
+    let lamports = Box::leak(Box::new(lamports));
+    Box::leak(Box::new(AccountInfo::new(
+        key_ref, lamports, bytes, owner, utxo, /* is_signer = */ false,
+        /* is_writable = */ true, /* is_executable = */ false,
+    )))
+}
+
+/// Zero-copy account of a fresh key holding a `Counter` with `value`.
+pub fn counter_account(value: u64) -> &'static AccountInfo<'static> {
+    account_info(
+        Pubkey::new_unique(),
+        Pubkey::default(),
+        &counter_data(value),
+        arch_program::account::MIN_ACCOUNT_LAMPORTS,
+        0,
+    )
+}
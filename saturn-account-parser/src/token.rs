@@ -0,0 +1,170 @@
+//! Read-only wrappers over accounts of the token program.
+//!
+//! [`TokenAccount`] and [`Mint`] decode the SPL-compatible layouts used by the
+//! Arch token program so that `#[derive(Accounts)]` can check constraints such
+//! as `token::mint = ..` or `mint::decimals = ..` on them. The wrappers do not
+//! check which program owns the account; pin it with `owner = <token program>`.
+
+use arch_program::{account::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+/// State of a token account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenAccountState {
+    Initialized,
+    Frozen,
+}
+
+/// Decoded fields of a token account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenAccountData {
+    pub mint: Pubkey,
+    /// Authority allowed to move the tokens (`token::authority`).
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub delegate: Option<Pubkey>,
+    pub state: TokenAccountState,
+    pub is_native: Option<u64>,
+    pub delegated_amount: u64,
+    pub close_authority: Option<Pubkey>,
+}
+
+/// Decoded fields of a mint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintData {
+    pub mint_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+    pub freeze_authority: Option<Pubkey>,
+}
+
+/// A token account, decoded when the accounts are parsed.
+pub struct TokenAccount<'a> {
+    account: &'a AccountInfo<'a>,
+    data: TokenAccountData,
+}
+
+/// A token mint, decoded when the accounts are parsed.
+pub struct Mint<'a> {
+    account: &'a AccountInfo<'a>,
+    data: MintData,
+}
+
+impl<'a> TokenAccount<'a> {
+    /// Serialized length of a token account.
+    pub const LEN: usize = 165;
+
+    /// Decodes `account`, failing with `InvalidAccountData` on a malformed
+    /// layout and `UninitializedAccount` if it was never initialised.
+    pub fn load(account: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
+        let bytes = account.try_borrow_data()?;
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let state = match bytes[108] {
+            0 => return Err(ProgramError::UninitializedAccount),
+            1 => TokenAccountState::Initialized,
+            2 => TokenAccountState::Frozen,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let data = TokenAccountData {
+            mint: Pubkey::from_slice(&bytes[0..32]),
+            owner: Pubkey::from_slice(&bytes[32..64]),
+            amount: read_u64(&bytes[64..72]),
+            delegate: read_optional_pubkey(&bytes[72..108])?,
+            state,
+            is_native: read_optional_u64(&bytes[109..121])?,
+            delegated_amount: read_u64(&bytes[121..129]),
+            close_authority: read_optional_pubkey(&bytes[129..165])?,
+        };
+        drop(bytes);
+        Ok(Self { account, data })
+    }
+
+    /// Returns a reference to the underlying `AccountInfo` object.
+    pub fn info(&self) -> &'a AccountInfo<'a> {
+        self.account
+    }
+}
+
+impl<'a> Mint<'a> {
+    /// Serialized length of a mint.
+    pub const LEN: usize = 82;
+
+    /// Decodes `account`, failing with `InvalidAccountData` on a malformed
+    /// layout and `UninitializedAccount` if it was never initialised.
+    pub fn load(account: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
+        let bytes = account.try_borrow_data()?;
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        match bytes[45] {
+            0 => return Err(ProgramError::UninitializedAccount),
+            1 => {}
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+        let data = MintData {
+            mint_authority: read_optional_pubkey(&bytes[0..36])?,
+            supply: read_u64(&bytes[36..44]),
+            decimals: bytes[44],
+            freeze_authority: read_optional_pubkey(&bytes[46..82])?,
+        };
+        drop(bytes);
+        Ok(Self { account, data })
+    }
+
+    /// Returns a reference to the underlying `AccountInfo` object.
+    pub fn info(&self) -> &'a AccountInfo<'a> {
+        self.account
+    }
+}
+
+impl<'a> core::ops::Deref for TokenAccount<'a> {
+    type Target = TokenAccountData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<'a> core::ops::Deref for Mint<'a> {
+    type Target = MintData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<'a> AsRef<AccountInfo<'a>> for TokenAccount<'a> {
+    fn as_ref(&self) -> &AccountInfo<'a> {
+        self.account
+    }
+}
+
+impl<'a> AsRef<AccountInfo<'a>> for Mint<'a> {
+    fn as_ref(&self) -> &AccountInfo<'a> {
+        self.account
+    }
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_le_bytes(buf)
+}
+
+/// `COption` tag: 4 little-endian bytes, 0 for `None` and 1 for `Some`.
+fn read_tag(bytes: &[u8]) -> Result<bool, ProgramError> {
+    match bytes[..4] {
+        [0, 0, 0, 0] => Ok(false),
+        [1, 0, 0, 0] => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn read_optional_pubkey(bytes: &[u8]) -> Result<Option<Pubkey>, ProgramError> {
+    Ok(read_tag(bytes)?.then(|| Pubkey::from_slice(&bytes[4..36])))
+}
+
+fn read_optional_u64(bytes: &[u8]) -> Result<Option<u64>, ProgramError> {
+    Ok(read_tag(bytes)?.then(|| read_u64(&bytes[4..12])))
+}
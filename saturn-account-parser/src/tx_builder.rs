@@ -1,23 +1,50 @@
+use arch_program::bitcoin::{Amount, ScriptBuf, TxOut};
+use arch_program::program_error::ProgramError;
+use saturn_bitcoin_transactions::fee_rate::FeeRate;
+
 /// Thin wrapper around `saturn_bitcoin_transactions::TransactionBuilder` that
 /// keeps the const-generic parameters visible to the type system while being
 /// easy to store inside [`Context`].
+///
+/// Every method of the builder is reachable through `Deref`/`DerefMut`; the
+/// wrapper adds [`add_output`](Self::add_output),
+/// [`pay_fees_from`](Self::pay_fees_from) and
+/// [`finalize_report`](Self::finalize_report), and remembers whether the
+/// transaction was finalised so it is not finalised again on drop.
 pub struct TxBuilderWrapper<
     'a,
     const MAX_MODIFIED_ACCOUNTS: usize,
     const MAX_INPUTS_TO_SIGN: usize,
     RuneSet,
->(
-    pub(crate)  saturn_bitcoin_transactions::TransactionBuilder<
+> where
+    RuneSet: saturn_collections::generic::fixed_set::FixedCapacitySet<
+            Item = arch_program::rune::RuneAmount,
+        > + Default,
+{
+    pub(crate) builder: saturn_bitcoin_transactions::TransactionBuilder<
         'a,
         MAX_MODIFIED_ACCOUNTS,
         MAX_INPUTS_TO_SIGN,
         RuneSet,
     >,
-)
-where
-    RuneSet: saturn_collections::generic::fixed_set::FixedCapacitySet<
-            Item = arch_program::rune::RuneAmount,
-        > + Default;
+    finalized: bool,
+}
+
+/// Summary of the transaction handed to the runtime by
+/// [`TxBuilderWrapper::finalize_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinalizeReport {
+    /// Number of inputs.
+    pub inputs: usize,
+    /// Number of outputs.
+    pub outputs: usize,
+    /// Number of inputs the runtime signs.
+    pub inputs_to_sign: usize,
+    /// Fee paid in satoshis (`inputs − outputs`).
+    pub fee: u64,
+    /// Estimated virtual size of the signed transaction.
+    pub vsize: usize,
+}
 
 impl<
         'a,
@@ -29,12 +56,15 @@ impl<
     > Default for TxBuilderWrapper<'a, MAX_MODIFIED_ACCOUNTS, MAX_INPUTS_TO_SIGN, RuneSet>
 {
     fn default() -> Self {
-        Self(saturn_bitcoin_transactions::TransactionBuilder::<
-            'a,
-            MAX_MODIFIED_ACCOUNTS,
-            MAX_INPUTS_TO_SIGN,
-            RuneSet,
-        >::new())
+        Self {
+            builder: saturn_bitcoin_transactions::TransactionBuilder::<
+                'a,
+                MAX_MODIFIED_ACCOUNTS,
+                MAX_INPUTS_TO_SIGN,
+                RuneSet,
+            >::new(),
+            finalized: false,
+        }
     }
 }
 
@@ -53,7 +83,7 @@ where
     >;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.builder
     }
 }
 
@@ -65,7 +95,78 @@ where
         > + Default,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.builder
+    }
+}
+
+impl<'a, const MAX_MODIFIED_ACCOUNTS: usize, const MAX_INPUTS_TO_SIGN: usize, RuneSet>
+    TxBuilderWrapper<'a, MAX_MODIFIED_ACCOUNTS, MAX_INPUTS_TO_SIGN, RuneSet>
+where
+    RuneSet: saturn_collections::generic::fixed_set::FixedCapacitySet<
+            Item = arch_program::rune::RuneAmount,
+        > + Default,
+{
+    /// Appends an output paying `value` satoshis to `script_pubkey` and
+    /// returns its index in the transaction.
+    pub fn add_output(&mut self, script_pubkey: ScriptBuf, value: u64) -> u32 {
+        let outputs = &mut self.builder.transaction.output;
+        outputs.push(TxOut {
+            script_pubkey,
+            value: Amount::from_sat(value),
+        });
+        (outputs.len() - 1) as u32
+    }
+
+    /// Pays the network fee at `fee_rate` out of the inputs, sending what is
+    /// left above the dust limit to `change`, then checks the fee rate
+    /// reached (mempool ancestors included).
+    ///
+    /// Fails like `adjust_transaction_to_pay_fees` and `is_fee_rate_valid`,
+    /// e.g. when the inputs cannot cover the outputs and the fee or the fee
+    /// exceeds the cap set with `set_max_fee`.
+    pub fn pay_fees_from(
+        &mut self,
+        change: ScriptBuf,
+        fee_rate: &FeeRate,
+    ) -> Result<(), ProgramError> {
+        self.builder
+            .adjust_transaction_to_pay_fees(fee_rate, Some(change))?;
+        self.builder.is_fee_rate_valid(fee_rate)?;
+        Ok(())
+    }
+
+    /// Hands the transaction to the runtime, as done on drop when it has
+    /// inputs. Shadows the builder's `finalize` so the wrapper knows not to
+    /// finalise it again.
+    pub fn finalize(&mut self) -> Result<(), ProgramError> {
+        self.builder.finalize()?;
+        self.finalized = true;
+        Ok(())
+    }
+
+    /// [`finalize`](Self::finalize)s the transaction unless that was already
+    /// done or it has no inputs. `#[saturn_program]` calls this once the
+    /// handler returns.
+    pub fn finalize_pending(&mut self) -> Result<(), ProgramError> {
+        if self.finalized || self.builder.transaction.input.is_empty() {
+            return Ok(());
+        }
+        self.finalize()
+    }
+
+    /// [`finalize`](Self::finalize)s the transaction and reports its shape,
+    /// e.g. to log it or check it against the limits of the program.
+    pub fn finalize_report(&mut self) -> Result<FinalizeReport, ProgramError> {
+        let fee = self.builder.get_fee_paid()?;
+        let vsize = self.builder.estimate_final_tx_vsize();
+        self.finalize()?;
+        Ok(FinalizeReport {
+            inputs: self.builder.transaction.input.len(),
+            outputs: self.builder.transaction.output.len(),
+            inputs_to_sign: self.builder.inputs_to_sign.len(),
+            fee,
+            vsize,
+        })
     }
 }
 
@@ -83,9 +184,7 @@ where
 {
     fn drop(&mut self) {
         // Only finalise when the transaction has at least one input to avoid
-        // unnecessary syscall overhead.
-        if !self.0.transaction.input.is_empty() {
-            let _ = self.0.finalize();
-        }
+        // unnecessary syscall overhead, and when the handler did not already.
+        let _ = self.finalize_pending();
     }
 }
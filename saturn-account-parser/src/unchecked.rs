@@ -0,0 +1,38 @@
+//! Explicit escape hatch for accounts the program does not validate.
+//!
+//! `#[derive(Accounts)]` only accepts an [`UncheckedAccount`] field whose name
+//! starts with `unchecked_` or that carries a `/// CHECK:` doc comment saying
+//! why no check is needed, so every such account shows up in an audit.
+
+use arch_program::account::AccountInfo;
+
+/// An account taken as-is: no owner, data or type check is performed beyond
+/// the `signer` / `mut` flags and constraints written on the field.
+pub struct UncheckedAccount<'a> {
+    account: &'a AccountInfo<'a>,
+}
+
+impl<'a> UncheckedAccount<'a> {
+    pub fn new(account: &'a AccountInfo<'a>) -> Self {
+        Self { account }
+    }
+
+    /// Returns a reference to the underlying `AccountInfo` object.
+    pub fn info(&self) -> &'a AccountInfo<'a> {
+        self.account
+    }
+}
+
+impl<'a> core::ops::Deref for UncheckedAccount<'a> {
+    type Target = AccountInfo<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.account
+    }
+}
+
+impl<'a> AsRef<AccountInfo<'a>> for UncheckedAccount<'a> {
+    fn as_ref(&self) -> &AccountInfo<'a> {
+        self.account
+    }
+}
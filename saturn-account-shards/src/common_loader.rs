@@ -126,7 +126,8 @@ impl StateShard<UtxoInfo<SingleRuneSet>, SingleRuneSet> for MockShardZc {
 /// Builds an in-memory [`AccountLoader<MockShardZc>`].
 pub fn create_loader() -> AccountLoader<'static, MockShardZc> {
     // Leak all heap allocations → `'static` lifetime suitable for tests.
-    let key = Box::leak(Box::new(Pubkey::default()));
+    // Distinct keys so several loaders can be borrowed at once.
+    let key = Box::leak(Box::new(Pubkey::new_unique()));
     let owner = Box::leak(Box::new(Pubkey::default()));
     let utxo = Box::leak(Box::new(UtxoMeta::default()));
     let lamports = Box::leak(Box::new(0u64));
@@ -241,7 +242,6 @@ pub fn create_rune_utxo(amount: u128, vout: u32) -> UtxoInfo<SingleRuneSet> {
         meta: random_utxo_meta(vout),
         value: DUST_LIMIT, // minimal on-chain value for a valid output
         runes,
-        #[cfg(feature = "utxo-consolidation")]
-        needs_consolidation: Default::default(),
+        ..Default::default()
     }
 }
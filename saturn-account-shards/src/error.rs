@@ -44,6 +44,16 @@ pub enum StateShardError {
     /// output created by the transaction.
     #[error("Runestone pointer is not in transaction")]
     RunestonePointerIsNotInTransaction,
+
+    /// The value held by the shards after an update does not match the value
+    /// spent from and paid to the program by the transaction.
+    #[error("Shard value is not conserved by the transaction")]
+    ValueNotConserved,
+
+    /// The shards that can be selected together do not hold the requested
+    /// amount of BTC.
+    #[error("Not enough btc in shards")]
+    NotEnoughBtcInShards,
 }
 
 impl From<FixedSetError> for StateShardError {
@@ -127,6 +127,9 @@
 //!
 //! // Select shards that meet a condition
 //! // let selected = shard_set.select_multiple_by(|s| s.btc_utxos_len() > 0).unwrap();
+//!
+//! // Select the fewest shards covering 50_000 sats, drawing from the richest
+//! // let selected = shard_set.select_best_for_btc(50_000).unwrap();
 //! ```
 //!
 //! ### Redistributing Liquidity
@@ -185,5 +188,6 @@ pub use shard_handle::ShardHandle;
 pub use shard_indices::IntoShardIndices;
 pub use shard_set::ShardSet;
 pub use shard_set::{Selected, Unselected};
+pub use update::{commit_transaction_and_shards, update_shards_after_transaction, CommitReport};
 
 pub use saturn_collections::{declare_fixed_array, declare_fixed_option, declare_fixed_set};
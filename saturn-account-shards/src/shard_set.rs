@@ -1,10 +1,15 @@
 use core::marker::PhantomData;
 
+use arch_program::rune::RuneAmount;
 use bytemuck::{Pod, Zeroable};
 use saturn_account_parser::codec::zero_copy::AccountLoader;
 use saturn_account_parser::codec::zero_copy::Discriminator;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfoTrait;
 use saturn_collections::generic::fixed_list::{FixedList, FixedListError};
+use saturn_collections::generic::fixed_set::FixedCapacitySet;
 
+use crate::error::StateShardError;
+use crate::shard::StateShard;
 use crate::shard_handle::ShardHandle;
 use crate::shard_indices::IntoShardIndices;
 use arch_program::program_error::ProgramError;
@@ -156,6 +161,104 @@ where
     }
 }
 
+// ------------- Unselected -> Selected (by liquidity) ----------------
+impl<'info, S, const MAX_SELECTED_SHARDS: usize> ShardSet<'info, S, MAX_SELECTED_SHARDS, Unselected>
+where
+    S: Pod + Zeroable + Discriminator + 'static,
+{
+    /// Selects the fewest shards whose BTC UTXOs hold at least `amount` sats,
+    /// drawing from the richest shards so the liquidity left across the set
+    /// stays as even as possible. The selected indices are in ascending order.
+    ///
+    /// # Errors
+    /// [`StateShardError::NotEnoughBtcInShards`] when no `MAX_SELECTED_SHARDS`
+    /// shards hold `amount` together.
+    pub fn select_best_for_btc<RS, U>(
+        self,
+        amount: u64,
+    ) -> Result<ShardSet<'info, S, MAX_SELECTED_SHARDS, Selected>, ProgramError>
+    where
+        RS: FixedCapacitySet<Item = RuneAmount> + Default,
+        U: UtxoInfoTrait<RS>,
+        S: StateShard<U, RS>,
+    {
+        self.select_best_by(
+            u128::from(amount),
+            |shard| u128::from(shard.total_btc().to_sat()),
+            StateShardError::NotEnoughBtcInShards,
+        )
+    }
+
+    /// Selects the fewest shards whose rune UTXOs hold at least
+    /// `rune_amount.amount` of `rune_amount.id`, like
+    /// [`Self::select_best_for_btc`].
+    ///
+    /// # Errors
+    /// [`StateShardError::NotEnoughRuneInShards`] when no
+    /// `MAX_SELECTED_SHARDS` shards hold `rune_amount` together.
+    pub fn select_best_for_rune<RS, U>(
+        self,
+        rune_amount: RuneAmount,
+    ) -> Result<ShardSet<'info, S, MAX_SELECTED_SHARDS, Selected>, ProgramError>
+    where
+        RS: FixedCapacitySet<Item = RuneAmount> + Default,
+        U: UtxoInfoTrait<RS>,
+        S: StateShard<U, RS>,
+    {
+        self.select_best_by(
+            rune_amount.amount,
+            |shard| shard.total_rune(rune_amount.id),
+            StateShardError::NotEnoughRuneInShards,
+        )
+    }
+
+    /// Selects the shards with the most `liquidity` until they cover `target`.
+    ///
+    /// Taking the richest shards first needs the fewest of them, and drains
+    /// the shards furthest above the others, which evens out what remains.
+    fn select_best_by(
+        self,
+        target: u128,
+        liquidity: impl Fn(&S) -> u128,
+        shortfall: StateShardError,
+    ) -> Result<ShardSet<'info, S, MAX_SELECTED_SHARDS, Selected>, ProgramError> {
+        let mut ranked: Vec<(usize, u128)> = Vec::with_capacity(self.loaders.len());
+        for (idx, &loader) in self.loaders.iter().enumerate() {
+            let value = ShardHandle::new(loader).with_ref(&liquidity)?;
+            ranked.push((idx, value));
+        }
+        // Richest first; equal shards keep their index order.
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut covered: u128 = 0;
+        let mut chosen: Vec<usize> = Vec::new();
+        for (idx, value) in ranked {
+            if covered >= target || value == 0 || chosen.len() == MAX_SELECTED_SHARDS {
+                break;
+            }
+            chosen.push(idx);
+            covered = covered.saturating_add(value);
+        }
+        if covered < target {
+            return Err(shortfall.into());
+        }
+
+        chosen.sort_unstable();
+        let mut selected = FixedList::new();
+        for idx in chosen {
+            selected
+                .push(idx)
+                .expect("at most MAX_SELECTED_SHARDS shards are chosen");
+        }
+
+        Ok(ShardSet {
+            loaders: self.loaders,
+            selected,
+            _state: PhantomData,
+        })
+    }
+}
+
 // ---------------------------- Selected -------------------------------
 impl<'info, S, const MAX_SELECTED_SHARDS: usize> ShardSet<'info, S, MAX_SELECTED_SHARDS, Selected>
 where
@@ -204,3 +307,93 @@ where
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_loader::{create_shard, leak_loaders_from_vec, MockShardZc};
+    use saturn_bitcoin_transactions::utxo_info::{SingleRuneSet, UtxoInfo};
+
+    type Utxo = UtxoInfo<SingleRuneSet>;
+
+    fn btc_shards<const MAX: usize>(sats: &[u64]) -> ShardSet<'static, MockShardZc, MAX> {
+        let shards = sats.iter().map(|&value| create_shard(value)).collect();
+        ShardSet::from_loaders(leak_loaders_from_vec(shards))
+    }
+
+    #[test]
+    fn selects_fewest_richest_shards_for_btc() {
+        let selected = btc_shards::<3>(&[1_000, 5_000, 3_000, 4_000])
+            .select_best_for_btc::<SingleRuneSet, Utxo>(6_000)
+            .unwrap();
+        // 5_000 + 4_000 covers the amount with two shards.
+        assert_eq!(selected.selected_indices(), &[1, 3]);
+    }
+
+    #[test]
+    fn prefers_lower_index_among_equal_shards() {
+        let selected = btc_shards::<2>(&[2_000, 2_000, 2_000])
+            .select_best_for_btc::<SingleRuneSet, Utxo>(1_500)
+            .unwrap();
+        assert_eq!(selected.selected_indices(), &[0]);
+    }
+
+    #[test]
+    fn zero_amount_selects_nothing() {
+        let selected = btc_shards::<2>(&[2_000, 1_000])
+            .select_best_for_btc::<SingleRuneSet, Utxo>(0)
+            .unwrap();
+        assert!(selected.selected_indices().is_empty());
+    }
+
+    #[test]
+    fn rejects_btc_beyond_max_selected_shards() {
+        // The three shards together hold enough, but only two may be selected.
+        let err = btc_shards::<2>(&[1_000, 1_000, 1_000])
+            .select_best_for_btc::<SingleRuneSet, Utxo>(2_500)
+            .err()
+            .unwrap();
+        assert_eq!(err, StateShardError::NotEnoughBtcInShards.into());
+    }
+
+    #[cfg(feature = "runes")]
+    #[test]
+    fn selects_shards_holding_the_rune() {
+        use crate::common_loader::create_rune_utxo;
+        use arch_program::rune::RuneId;
+
+        let mut shards = vec![create_shard(0), create_shard(0), create_shard(0)];
+        shards[0].set_rune_utxo(create_rune_utxo(40, 0));
+        shards[1].set_rune_utxo(create_rune_utxo(100, 1));
+        shards[2].set_rune_utxo(create_rune_utxo(70, 2));
+        let set = ShardSet::<MockShardZc, 3>::from_loaders(leak_loaders_from_vec(shards));
+
+        let selected = set
+            .select_best_for_rune::<SingleRuneSet, Utxo>(RuneAmount {
+                id: RuneId::new(1, 1),
+                amount: 150,
+            })
+            .unwrap();
+        assert_eq!(selected.selected_indices(), &[1, 2]);
+    }
+
+    #[cfg(feature = "runes")]
+    #[test]
+    fn rejects_missing_rune() {
+        use crate::common_loader::create_rune_utxo;
+        use arch_program::rune::RuneId;
+
+        let mut shard = create_shard(5_000);
+        shard.set_rune_utxo(create_rune_utxo(100, 0));
+        let set = ShardSet::<MockShardZc, 1>::from_loaders(leak_loaders_from_vec(vec![shard]));
+
+        let err = set
+            .select_best_for_rune::<SingleRuneSet, Utxo>(RuneAmount {
+                id: RuneId::new(2, 1),
+                amount: 1,
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, StateShardError::NotEnoughRuneInShards.into());
+    }
+}
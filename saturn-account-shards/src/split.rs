@@ -305,12 +305,12 @@ where
         // Gather existing amount for this shard.
         let current_res = handle.with_ref(|shard| match rune_amount.id {
             RuneId::BTC => {
-                // Sum unspent BTC UTXOs.
+                // Sum unspent, unlocked BTC UTXOs.
                 shard
                     .btc_utxos()
                     .iter()
                     .filter_map(|u| {
-                        if is_utxo_used(u.meta()) {
+                        if is_utxo_used(u.meta()) || u.is_locked() {
                             None
                         } else {
                             Some(u.value() as u128)
@@ -5,7 +5,7 @@
 //! they only need the `StateShard` trait – and can therefore be reused by any on-chain program
 //! that follows the Saturn account-sharding pattern.
 
-use arch_program::{input_to_sign::InputToSign, utxo::UtxoMeta};
+use arch_program::{input_to_sign::InputToSign, program_error::ProgramError, utxo::UtxoMeta};
 use bitcoin::{ScriptBuf, Transaction};
 use saturn_bitcoin_transactions::utxo_info::UtxoInfoTrait;
 use saturn_bitcoin_transactions::{fee_rate::FeeRate, TransactionBuilder};
@@ -82,7 +82,12 @@ where
         let handle = shard_set.handle_by_index(idx);
         if let Ok(can_use) = handle.with_ref(|shard| {
             let spare = shard.btc_utxos_len() < shard.btc_utxos_max_len();
-            let sum: u64 = shard.btc_utxos().iter().map(|u| u.value()).sum();
+            let sum: u64 = shard
+                .btc_utxos()
+                .iter()
+                .filter(|u| !u.is_locked())
+                .map(|u| u.value())
+                .sum();
             (spare, sum)
         }) {
             let (spare, sum) = can_use;
@@ -140,6 +145,14 @@ where
 
         let handle = shard_set.handle_by_index(target_idx);
 
+        // Remember when and at which fee rate the UTXO was created so that
+        // consolidation can sweep the oldest ones first.
+        #[cfg(feature = "utxo-age")]
+        utxo.record_creation(
+            fee_rate.0,
+            arch_program::program::get_bitcoin_block_height(),
+        );
+
         // Apply consolidation flag if feature enabled.
         #[cfg(feature = "utxo-consolidation")]
         {
@@ -245,6 +258,166 @@ where
     )
 }
 
+/// Summary of the state transition applied by [`commit_transaction_and_shards`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CommitReport {
+    /// Number of shard-owned UTXOs spent by the transaction.
+    pub utxos_removed: usize,
+    /// Number of program outputs that were stored in the shards.
+    pub utxos_added: usize,
+    /// Total value of the spent shard-owned UTXOs.
+    pub btc_removed: u64,
+    /// Total value of the program outputs stored in the shards.
+    pub btc_added: u64,
+    /// Total value held by the selected shards before the update.
+    pub shard_btc_before: u64,
+    /// Total value held by the selected shards after the update.
+    pub shard_btc_after: u64,
+    /// Fee paid by the transaction (`inputs − outputs`), if it can be computed
+    /// from the inputs tracked by the builder.
+    pub fee_paid: Option<u64>,
+}
+
+/// Updates the selected shards **and** finalises the transaction in a single
+/// all-or-nothing step.
+///
+/// The function
+/// 1. snapshots every selected shard,
+/// 2. calls [`update_shards_after_transaction`],
+/// 3. verifies that the shards' BTC value is conserved, i.e. that
+///    `after == before − spent + created` where *spent* are the shard UTXOs used
+///    as inputs and *created* the outputs paying `program_script_pubkey`,
+/// 4. calls [`TransactionBuilder::finalize`].
+///
+/// If any step fails the shards (and the builder's remaining rune inputs) are
+/// restored to their snapshot before the error is returned, so a handler can
+/// never end up with a signed transaction and stale shards, or updated shards
+/// without a transaction.
+///
+/// # Errors
+/// * Any error returned by [`update_shards_after_transaction`].
+/// * [`StateShardError::ValueNotConserved`] when the shards' value after the
+///   update does not match the transaction.
+/// * Any error returned by [`TransactionBuilder::finalize`].
+#[allow(clippy::too_many_arguments)]
+pub fn commit_transaction_and_shards<
+    'info,
+    const MAX_USER_UTXOS: usize,
+    const MAX_SHARDS_PER_POOL: usize,
+    const MAX_SEL: usize,
+    RS,
+    U,
+    S,
+>(
+    transaction_builder: &mut TransactionBuilder<MAX_USER_UTXOS, MAX_SHARDS_PER_POOL, RS>,
+    shard_set: &ShardSet<'info, S, MAX_SEL, ShardSetSelected>,
+    program_script_pubkey: &ScriptBuf,
+    fee_rate: &FeeRate,
+) -> core::result::Result<CommitReport, ProgramError>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default,
+    U: UtxoInfoTrait<RS>,
+    S: StateShard<U, RS> + Pod + Zeroable + Discriminator + 'static,
+{
+    let shard_indexes = shard_set.selected_indices();
+
+    // ---------------------------------------------------------------------
+    // 1. Snapshot shards and the rune inputs consumed by the update.
+    // ---------------------------------------------------------------------
+    let mut snapshots = Vec::with_capacity(shard_indexes.len());
+    for &idx in shard_indexes {
+        snapshots.push((
+            idx,
+            shard_set.handle_by_index(idx).with_ref(|shard| *shard)?,
+        ));
+    }
+
+    #[cfg(feature = "runes")]
+    let rune_inputs_snapshot = {
+        let mut snapshot = RS::default();
+        for rune in transaction_builder.total_rune_inputs.iter() {
+            // Same capacity as the source set, cannot fail.
+            let _ = snapshot.insert(*rune);
+        }
+        snapshot
+    };
+
+    // ---------------------------------------------------------------------
+    // 2. Compute the expected value transition from the transaction.
+    // ---------------------------------------------------------------------
+    let (spent, created) = get_modified_program_utxos_in_transaction::<RS, U>(
+        program_script_pubkey,
+        &transaction_builder.transaction,
+        transaction_builder.inputs_to_sign.as_slice(),
+    );
+
+    let mut report = CommitReport {
+        utxos_added: created.len(),
+        btc_added: created.iter().map(|u| u.value()).sum(),
+        ..CommitReport::default()
+    };
+
+    for (_, shard) in &snapshots {
+        for utxo in shard.btc_utxos().iter().chain(shard.rune_utxo()) {
+            report.shard_btc_before += utxo.value();
+            if spent.contains(utxo.meta()) {
+                report.utxos_removed += 1;
+                report.btc_removed += utxo.value();
+            }
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // 3. Update, verify and finalise – rolling back on any failure.
+    // ---------------------------------------------------------------------
+    let result = (|| -> core::result::Result<(), ProgramError> {
+        update_shards_after_transaction::<MAX_USER_UTXOS, MAX_SHARDS_PER_POOL, MAX_SEL, RS, U, S>(
+            transaction_builder,
+            shard_set,
+            program_script_pubkey,
+            fee_rate,
+        )?;
+
+        for &idx in shard_indexes {
+            report.shard_btc_after += shard_set.handle_by_index(idx).with_ref(|shard| {
+                shard
+                    .btc_utxos()
+                    .iter()
+                    .chain(shard.rune_utxo())
+                    .map(|u| u.value())
+                    .sum::<u64>()
+            })?;
+        }
+
+        let expected = report
+            .shard_btc_before
+            .checked_sub(report.btc_removed)
+            .and_then(|v| v.checked_add(report.btc_added));
+        if expected != Some(report.shard_btc_after) {
+            return Err(StateShardError::ValueNotConserved.into());
+        }
+
+        transaction_builder.finalize()
+    })();
+
+    if let Err(err) = result {
+        for (idx, snapshot) in snapshots {
+            shard_set
+                .handle_by_index(idx)
+                .with_mut(|shard| *shard = snapshot)?;
+        }
+        #[cfg(feature = "runes")]
+        {
+            transaction_builder.total_rune_inputs = rune_inputs_snapshot;
+        }
+        return Err(err);
+    }
+
+    report.fee_paid = transaction_builder.get_fee_paid().ok();
+
+    Ok(report)
+}
+
 fn get_modified_program_utxos_in_transaction<RS, U>(
     program_script_pubkey: &ScriptBuf,
     transaction: &Transaction,
@@ -1400,4 +1573,138 @@ mod tests_loader {
             assert_eq!(err, StateShardError::ShardsAreFullOfBtcUtxos);
         }
     }
+
+    // ---------------------------------------------------------------------
+    // commit_transaction_and_shards
+    // ---------------------------------------------------------------------
+    mod commit_transaction_and_shards {
+        use super::*;
+        use crate::common_loader::{add_btc_utxos_bulk, MAX_BTC_UTXOS};
+        use arch_program::input_to_sign::InputToSign;
+        use bitcoin::absolute::LockTime;
+        use bitcoin::transaction::Version;
+        use bitcoin::{Amount, OutPoint, Sequence, TxIn, TxOut, Witness};
+        use saturn_bitcoin_transactions::error::BitcoinTxError;
+        use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+
+        const MAX_USER_UTXOS: usize = 4;
+        const MAX_SHARDS_PER_POOL: usize = 4;
+        const MAX_SEL: usize = 2;
+
+        /// Spends `[200; 32]:0` and pays every value in `outputs` back to the
+        /// program.
+        fn spending_builder(
+            outputs: &[u64],
+        ) -> TB<'static, MAX_USER_UTXOS, MAX_SHARDS_PER_POOL, SingleRuneSet> {
+            let mut builder = new_tb!(MAX_USER_UTXOS, MAX_SHARDS_PER_POOL);
+            let txid_200 =
+                bitcoin::Txid::from_raw_hash(Sha256dHash::from_slice(&[200u8; 32]).unwrap());
+
+            builder.transaction = Transaction {
+                version: Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: vec![TxIn {
+                    previous_output: OutPoint {
+                        txid: txid_200,
+                        vout: 0,
+                    },
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::MAX,
+                    witness: Witness::default(),
+                }],
+                output: outputs
+                    .iter()
+                    .map(|&value| TxOut {
+                        value: Amount::from_sat(value),
+                        script_pubkey: ScriptBuf::new(),
+                    })
+                    .collect(),
+            };
+            builder
+                .inputs_to_sign
+                .push(InputToSign {
+                    index: 0,
+                    signer: arch_program::pubkey::Pubkey::default(),
+                })
+                .unwrap();
+
+            builder
+        }
+
+        fn shard_values(
+            shard_set: &ShardSet<'static, MockShardZc, MAX_SEL, ShardSetSelected>,
+            idx: usize,
+        ) -> Vec<u64> {
+            shard_set
+                .handle_by_index(idx)
+                .with_ref(|s| s.btc_utxos().iter().map(|u| u.value()).collect())
+                .unwrap()
+        }
+
+        fn commit(
+            builder: &mut TB<'static, MAX_USER_UTXOS, MAX_SHARDS_PER_POOL, SingleRuneSet>,
+            shard_set: &ShardSet<'static, MockShardZc, MAX_SEL, ShardSetSelected>,
+        ) -> core::result::Result<CommitReport, ProgramError> {
+            super::super::commit_transaction_and_shards::<
+                MAX_USER_UTXOS,
+                MAX_SHARDS_PER_POOL,
+                MAX_SEL,
+                SingleRuneSet,
+                UtxoInfo<SingleRuneSet>,
+                MockShardZc,
+            >(builder, shard_set, &ScriptBuf::new(), &fee_rate())
+        }
+
+        #[test]
+        fn rolls_back_shards_when_finalize_fails() {
+            let mut builder = spending_builder(&[4_500]);
+            builder.total_btc_input = 5_000;
+            builder.set_max_fee(100);
+
+            let existing_utxo = create_utxo(5_000, 200, 0);
+            let mut shard0 = MockShardZc::default();
+            shard0.add_btc_utxo(existing_utxo.clone());
+            let shard1 = MockShardZc::default();
+
+            let loaders = leak_loaders_from_vec(vec![shard0, shard1]);
+            let unselected: ShardSet<MockShardZc, MAX_SEL> = ShardSet::from_loaders(loaders);
+            let shard_set = unselected.select_with([0usize, 1usize]).unwrap();
+
+            let err = commit(&mut builder, &shard_set).unwrap_err();
+            assert_eq!(err, ProgramError::from(BitcoinTxError::FeeExceedsCap));
+
+            assert_eq!(shard_values(&shard_set, 0), vec![5_000]);
+            assert!(shard_values(&shard_set, 1).is_empty());
+        }
+
+        #[test]
+        fn rolls_back_shards_when_update_fails() {
+            // Removing the spent UTXO frees a single slot, the second output
+            // does not fit anymore.
+            let mut builder = spending_builder(&[1_000, 2_000]);
+
+            let mut shard0 = MockShardZc::default();
+            shard0.add_btc_utxo(create_utxo(5_000, 200, 0));
+            add_btc_utxos_bulk(&mut shard0, &[1_000; MAX_BTC_UTXOS]);
+            let mut shard1 = MockShardZc::default();
+            add_btc_utxos_bulk(&mut shard1, &[1_000; MAX_BTC_UTXOS]);
+
+            let loaders = leak_loaders_from_vec(vec![shard0, shard1]);
+            let unselected: ShardSet<MockShardZc, MAX_SEL> = ShardSet::from_loaders(loaders);
+            let shard_set = unselected.select_with([0usize, 1usize]).unwrap();
+
+            let before = (shard_values(&shard_set, 0), shard_values(&shard_set, 1));
+
+            let err = commit(&mut builder, &shard_set).unwrap_err();
+            assert_eq!(
+                err,
+                ProgramError::from(StateShardError::ShardsAreFullOfBtcUtxos)
+            );
+
+            assert_eq!(
+                (shard_values(&shard_set, 0), shard_values(&shard_set, 1)),
+                before
+            );
+        }
+    }
 }
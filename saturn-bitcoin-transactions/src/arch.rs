@@ -68,3 +68,54 @@ where
 
     Ok(rune_set)
 }
+
+/// Outputs of the funding transactions fetched from the runtime, so that
+/// converting several UTXOs created by the same transaction fetches and
+/// decodes it only once.
+///
+/// Without the `utxo-script` feature nothing is read from the funding
+/// transactions and the cache stays empty.
+#[derive(Default)]
+pub struct FundingTxCache {
+    #[cfg(feature = "utxo-script")]
+    outputs: Vec<([u8; 32], Vec<bitcoin::TxOut>)>,
+}
+
+impl FundingTxCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the `script_pubkey` of the output referenced by `utxo`, fetching
+    /// its transaction from the runtime unless it was already fetched.
+    #[cfg(feature = "utxo-script")]
+    pub fn output_script_pubkey(
+        &mut self,
+        utxo: &UtxoMeta,
+    ) -> Result<crate::utxo_info::FixedScriptPubkey, ProgramError> {
+        let txid = utxo.txid_big_endian();
+
+        let position = match self.outputs.iter().position(|(id, _)| *id == txid) {
+            Some(position) => position,
+            None => {
+                let tx = arch_program::program::get_bitcoin_tx(txid)
+                    .ok_or(BitcoinTxError::TransactionNotFound)?;
+
+                let tx: bitcoin::Transaction = bitcoin::consensus::deserialize(tx.as_slice())
+                    .map_err(|_| BitcoinTxError::InvalidTransactionData)?;
+
+                self.outputs.push((txid, tx.output));
+                self.outputs.len() - 1
+            }
+        };
+
+        let output = self.outputs[position]
+            .1
+            .get(utxo.vout() as usize)
+            .ok_or(BitcoinTxError::OutputNotFound)?;
+
+        Ok(crate::utxo_info::FixedScriptPubkey::new(
+            output.script_pubkey.as_bytes(),
+        )?)
+    }
+}
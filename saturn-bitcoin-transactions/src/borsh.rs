@@ -0,0 +1,250 @@
+//! Borsh encoding for [`UtxoInfo`] and the fixed-size wrappers it is built from.
+//!
+//! The zero-copy types are encoded by value rather than as raw bytes: fixed
+//! options as `Option<T>`, rune sets, scripts and fixed arrays as
+//! length-prefixed lists. Padding is never written. The fields of [`UtxoInfo`]
+//! follow the enabled features, so writer and reader must agree on them.
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use ::borsh::{BorshDeserialize, BorshSerialize};
+use arch_program::{rune::RuneAmount, utxo::UtxoMeta};
+use saturn_collections::generic::fixed_set::FixedCapacitySet;
+
+use crate::utxo_info::{FixedArrayUtxoInfo, FixedOptionUtxoInfo, SingleRuneSet, UtxoInfo};
+
+#[cfg(feature = "utxo-consolidation")]
+use crate::utxo_info::FixedOptionF64;
+#[cfg(any(feature = "utxo-confirmations", feature = "utxo-age"))]
+use crate::utxo_info::FixedOptionU64;
+#[cfg(feature = "utxo-script")]
+use crate::utxo_info::FixedScriptPubkey;
+
+/// Encodes a `declare_fixed_option!` type as `Option<$T>`.
+macro_rules! impl_borsh_fixed_option {
+    ($Name:ty, $T:ty) => {
+        impl BorshSerialize for $Name {
+            fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+                self.get().serialize(writer)
+            }
+        }
+
+        impl BorshDeserialize for $Name {
+            fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+                Ok(Option::<$T>::deserialize_reader(reader)?.into())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "utxo-consolidation")]
+impl_borsh_fixed_option!(FixedOptionF64, f64);
+#[cfg(any(feature = "utxo-confirmations", feature = "utxo-age"))]
+impl_borsh_fixed_option!(FixedOptionU64, u64);
+impl_borsh_fixed_option!(FixedOptionUtxoInfo, UtxoInfo<SingleRuneSet>);
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Writes the entries of `runes` as a `u32` length followed by each [`RuneAmount`].
+pub fn serialize_rune_set<RS, W>(runes: &RS, writer: &mut W) -> Result<()>
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+    W: Write,
+{
+    (runes.len() as u32).serialize(writer)?;
+    for rune in runes.iter() {
+        rune.serialize(writer)?;
+    }
+    Ok(())
+}
+
+/// Reads a rune set written by [`serialize_rune_set`], failing when it holds
+/// more entries than `RS` can store or the same rune twice.
+pub fn deserialize_rune_set<RS, R>(reader: &mut R) -> Result<RS>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default,
+    R: Read,
+{
+    let len = u32::deserialize_reader(reader)?;
+    let mut runes = RS::default();
+    for _ in 0..len {
+        let rune = RuneAmount::deserialize_reader(reader)?;
+        runes
+            .insert(rune)
+            .map_err(|_| invalid_data("rune set is full or contains a duplicate rune"))?;
+    }
+    Ok(runes)
+}
+
+#[cfg(feature = "utxo-script")]
+impl BorshSerialize for FixedScriptPubkey {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.as_bytes().serialize(writer)
+    }
+}
+
+#[cfg(feature = "utxo-script")]
+impl BorshDeserialize for FixedScriptPubkey {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let bytes = Vec::<u8>::deserialize_reader(reader)?;
+        FixedScriptPubkey::new(&bytes).map_err(|_| invalid_data("script_pubkey is too long"))
+    }
+}
+
+impl<RS> BorshSerialize for UtxoInfo<RS>
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.meta.serialize(writer)?;
+        self.value.serialize(writer)?;
+        #[cfg(feature = "runes")]
+        serialize_rune_set(&self.runes, writer)?;
+        #[cfg(feature = "utxo-consolidation")]
+        self.needs_consolidation.serialize(writer)?;
+        #[cfg(feature = "utxo-confirmations")]
+        self.block_height.serialize(writer)?;
+        #[cfg(feature = "utxo-script")]
+        self.script_pubkey.serialize(writer)?;
+        #[cfg(feature = "utxo-lock")]
+        self.locked_until.serialize(writer)?;
+        #[cfg(feature = "utxo-age")]
+        self.created_fee_rate.serialize(writer)?;
+        #[cfg(feature = "utxo-age")]
+        self.created_at_height.serialize(writer)?;
+        Ok(())
+    }
+}
+
+impl<RS> BorshDeserialize for UtxoInfo<RS>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default,
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut info = UtxoInfo::<RS>::default();
+        info.meta = UtxoMeta::deserialize_reader(reader)?;
+        info.value = u64::deserialize_reader(reader)?;
+        #[cfg(feature = "runes")]
+        {
+            info.runes = deserialize_rune_set(reader)?;
+        }
+        #[cfg(feature = "utxo-consolidation")]
+        {
+            info.needs_consolidation = FixedOptionF64::deserialize_reader(reader)?;
+        }
+        #[cfg(feature = "utxo-confirmations")]
+        {
+            info.block_height = FixedOptionU64::deserialize_reader(reader)?;
+        }
+        #[cfg(feature = "utxo-script")]
+        {
+            info.script_pubkey = FixedScriptPubkey::deserialize_reader(reader)?;
+        }
+        #[cfg(feature = "utxo-lock")]
+        {
+            info.locked_until = u64::deserialize_reader(reader)?;
+        }
+        #[cfg(feature = "utxo-age")]
+        {
+            info.created_fee_rate = FixedOptionF64::deserialize_reader(reader)?;
+            info.created_at_height = FixedOptionU64::deserialize_reader(reader)?;
+        }
+        Ok(info)
+    }
+}
+
+impl BorshSerialize for FixedArrayUtxoInfo {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.as_slice().serialize(writer)
+    }
+}
+
+impl BorshDeserialize for FixedArrayUtxoInfo {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        let mut array = FixedArrayUtxoInfo::new();
+        for _ in 0..len {
+            let utxo = UtxoInfo::<SingleRuneSet>::deserialize_reader(reader)?;
+            array
+                .add(utxo)
+                .ok_or_else(|| invalid_data("too many UTXOs for FixedArrayUtxoInfo"))?;
+        }
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utxo(value: u64) -> UtxoInfo<SingleRuneSet> {
+        let mut info = UtxoInfo::<SingleRuneSet>::default();
+        info.meta = UtxoMeta::from([value as u8; 32], 1);
+        info.value = value;
+        info
+    }
+
+    #[test]
+    fn test_utxo_info_roundtrip() {
+        #[allow(unused_mut)]
+        let mut info = utxo(10_000);
+        #[cfg(feature = "runes")]
+        info.runes
+            .insert(RuneAmount {
+                id: arch_program::rune::RuneId::new(840_000, 3),
+                amount: 1_000,
+            })
+            .unwrap();
+        #[cfg(feature = "utxo-consolidation")]
+        {
+            info.needs_consolidation = FixedOptionF64::some(2.5);
+        }
+
+        let bytes = ::borsh::to_vec(&info).unwrap();
+        let decoded: UtxoInfo<SingleRuneSet> = ::borsh::from_slice(&bytes).unwrap();
+
+        assert_eq!(decoded.meta, info.meta);
+        assert_eq!(decoded.value, info.value);
+        #[cfg(feature = "runes")]
+        assert_eq!(decoded.total_rune_amount(), 1_000);
+        #[cfg(feature = "utxo-consolidation")]
+        assert_eq!(decoded.needs_consolidation.get(), Some(2.5));
+    }
+
+    #[cfg(feature = "runes")]
+    #[test]
+    fn test_rune_set_over_capacity_is_rejected() {
+        let mut bytes = ::borsh::to_vec(&utxo(1)).unwrap();
+        // Patch the rune count (right after meta and value) to two entries.
+        let offset = 36 + 8;
+        bytes.splice(offset..offset + 4, 2u32.to_le_bytes());
+        for tx in 0..2 {
+            let rune = RuneAmount {
+                id: arch_program::rune::RuneId::new(1, tx),
+                amount: 1,
+            };
+            let encoded = ::borsh::to_vec(&rune).unwrap();
+            bytes.splice(offset + 4..offset + 4, encoded);
+        }
+
+        assert!(::borsh::from_slice::<UtxoInfo<SingleRuneSet>>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_fixed_option_and_array_roundtrip() {
+        let option = FixedOptionUtxoInfo::some(utxo(42));
+        let decoded: FixedOptionUtxoInfo =
+            ::borsh::from_slice(&::borsh::to_vec(&option).unwrap()).unwrap();
+        assert_eq!(decoded.get().map(|u| u.value), Some(42));
+
+        let array = FixedArrayUtxoInfo::from_slice(&[utxo(1), utxo(2), utxo(3)]);
+        let decoded: FixedArrayUtxoInfo =
+            ::borsh::from_slice(&::borsh::to_vec(&array).unwrap()).unwrap();
+        assert_eq!(
+            decoded.iter().map(|u| u.value).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::cmp::min;
 use arch_program::input_to_sign::InputToSign;
 use bitcoin::{Amount, ScriptBuf, Transaction, TxOut};
 use mempool_oracle_sdk::MempoolInfo;
-use saturn_collections::generic::push_pop::{PushPopCollection, PushPopError};
+use saturn_collections::generic::push_pop::PushPopCollection;
 use saturn_safe_math::{safe_add, safe_sub};
 
 use crate::{
@@ -32,6 +32,17 @@ pub(crate) fn estimate_final_tx_vsize(
     vsize + (inputs_to_sign.len() * WITNESS_WEIGHT_BYTES + WITNESS_WEIGHT_OVERHEAD) / 4
 }
 
+/// Weight (in weight units) of `transaction` once every input in
+/// `inputs_to_sign` carries its witness. Witness bytes count one weight unit each.
+pub(crate) fn estimate_final_tx_weight(
+    transaction: &Transaction,
+    inputs_to_sign: &[InputToSign],
+) -> usize {
+    let weight = transaction.weight().to_wu() as usize;
+
+    weight + inputs_to_sign.len() * WITNESS_WEIGHT_BYTES + WITNESS_WEIGHT_OVERHEAD
+}
+
 pub(crate) fn calculate_fees_for_transaction(
     _remaining_btc: u64,
     transaction: &mut Transaction,
@@ -143,8 +154,7 @@ pub fn estimate_tx_size_with_additional_inputs_outputs<C: PushPopCollection<Inpu
         transaction,
         inputs_to_sign,
         new_potential_inputs_and_outputs,
-    )
-    .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+    )?;
 
     let total_size = estimate_final_tx_total_size(transaction, inputs_to_sign.as_slice());
 
@@ -166,8 +176,7 @@ pub fn estimate_tx_vsize_with_additional_inputs_outputs<C: PushPopCollection<Inp
         transaction,
         inputs_to_sign,
         new_potential_inputs_and_outputs,
-    )
-    .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+    )?;
 
     let total_vsize = estimate_final_tx_vsize(transaction, inputs_to_sign.as_slice());
 
@@ -180,11 +189,36 @@ pub fn estimate_tx_vsize_with_additional_inputs_outputs<C: PushPopCollection<Inp
     Ok(total_vsize)
 }
 
+pub fn estimate_tx_weight_with_additional_inputs_outputs<C: PushPopCollection<InputToSign>>(
+    transaction: &mut Transaction,
+    inputs_to_sign: &mut C,
+    new_potential_inputs_and_outputs: &NewPotentialInputsAndOutputs,
+) -> Result<usize, BitcoinTxError> {
+    add_reserved_inputs_and_outputs(
+        transaction,
+        inputs_to_sign,
+        new_potential_inputs_and_outputs,
+    )?;
+
+    let total_weight = estimate_final_tx_weight(transaction, inputs_to_sign.as_slice());
+
+    rollback_potential_inputs_and_outputs(
+        transaction,
+        inputs_to_sign,
+        new_potential_inputs_and_outputs,
+    );
+
+    Ok(total_weight)
+}
+
+/// Fails with [`BitcoinTxError::CapacityExceeded`], leaving `transaction`
+/// and `inputs_to_sign` untouched, when `inputs_to_sign` cannot hold the
+/// signers of the new inputs.
 fn add_reserved_inputs_and_outputs<C: PushPopCollection<InputToSign>>(
     transaction: &mut Transaction,
     inputs_to_sign: &mut C,
     new_potential_inputs_and_outputs: &NewPotentialInputsAndOutputs,
-) -> Result<(), PushPopError> {
+) -> Result<(), BitcoinTxError> {
     if let Some(NewPotentialInputAmount {
         count,
         ref item,
@@ -197,10 +231,18 @@ fn add_reserved_inputs_and_outputs<C: PushPopCollection<InputToSign>>(
         let initial_input_len = transaction.input.len();
         for i in 0..count {
             if let Some(signer) = signer {
-                inputs_to_sign.push(InputToSign {
+                let pushed = inputs_to_sign.push(InputToSign {
                     index: (initial_input_len + i) as u32,
                     signer,
-                })?;
+                });
+
+                if pushed.is_err() {
+                    transaction.input.truncate(initial_input_len);
+                    for _ in 0..i {
+                        inputs_to_sign.pop();
+                    }
+                    return Err(BitcoinTxError::CapacityExceeded);
+                }
             }
 
             transaction.input.push(item.clone());
@@ -260,7 +302,7 @@ mod tests {
         absolute::LockTime, key::constants::SCHNORR_SIGNATURE_SIZE, transaction::Version, Address,
         Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
     };
-    use saturn_collections::generic::push_pop::PushPopError;
+    use saturn_collections::generic::{fixed_list::FixedList, push_pop::PushPopError};
     use std::str::FromStr;
 
     enum AddressType {
@@ -414,6 +456,41 @@ mod tests {
         assert_eq!(inputs_to_sign.items[1].index, 1);
     }
 
+    #[test]
+    fn test_add_reserved_inputs_and_outputs_full_inputs_to_sign() {
+        let mut transaction = create_mock_transaction();
+        transaction
+            .input
+            .push(create_mock_tx_in(create_mock_outpoint([1; 32], 0)));
+        let mut inputs_to_sign = FixedList::<InputToSign, 2>::new();
+        inputs_to_sign
+            .push(InputToSign {
+                index: 0,
+                signer: Pubkey::default(),
+            })
+            .unwrap();
+
+        let new_potential_inputs_and_outputs = NewPotentialInputsAndOutputs {
+            inputs: Some(NewPotentialInputAmount {
+                count: 2,
+                item: create_mock_tx_in(create_mock_outpoint([2; 32], 0)),
+                signer: Some(Pubkey::default()),
+            }),
+            outputs: vec![],
+        };
+
+        assert_eq!(
+            estimate_tx_vsize_with_additional_inputs_outputs(
+                &mut transaction,
+                &mut inputs_to_sign,
+                &new_potential_inputs_and_outputs,
+            ),
+            Err(BitcoinTxError::CapacityExceeded)
+        );
+        assert_eq!(transaction.input.len(), 1);
+        assert_eq!(inputs_to_sign.len(), 1);
+    }
+
     #[test]
     fn test_add_reserved_inputs_and_outputs_no_signer() {
         let mut transaction = create_mock_transaction();
@@ -1050,5 +1127,46 @@ mod tests {
             // Sometimes it's one byte more, sometimes it's one byte less because of rounding
             assert!((final_total_vsize - estimated_total_vsize) <= 1);
         }
+
+        #[test]
+        fn test_estimated_weight_matches_signed_tx(inputs in 1..100_usize) {
+            use bitcoin::{Transaction, TxIn, TxOut, Witness};
+
+            let mut tx = Transaction {
+                version: Version(2),
+                lock_time: LockTime::ZERO,
+                input: vec![
+                    TxIn {
+                        previous_output: Default::default(),
+                        script_sig: ScriptBuf::new(),
+                        sequence: Sequence(0xFFFF_FFFF),
+                        witness: Witness::new(),
+                    };
+                    inputs
+                ],
+                output: vec![TxOut {
+                    value: Amount::from_sat(1000),
+                    script_pubkey: ScriptBuf::from_hex("6a").unwrap(),
+                }],
+            };
+
+            let inputs_to_sign = (0..inputs)
+                .map(|i| InputToSign {
+                    index: i as u32,
+                    signer: Pubkey([0; 32]),
+                })
+                .collect::<Vec<_>>();
+
+            let estimated_weight = estimate_final_tx_weight(&tx, &inputs_to_sign);
+
+            add_fake_witness_to_transaction(&mut tx, &inputs_to_sign);
+
+            // Unlike the vsize estimate, weight is exact and rounding up matches bitcoind.
+            assert_eq!(tx.weight().to_wu() as usize, estimated_weight);
+            assert_eq!(
+                tx.vsize(),
+                crate::fee_rate::weight_to_vsize(estimated_weight, crate::fee_rate::Rounding::Up)
+            );
+        }
     }
 }
@@ -45,20 +45,26 @@ where
         witness: Witness::new(),
     };
 
-    for utxo_ref in pool_shard_btc_utxos.iter() {
-        let utxo = utxo_ref.as_ref();
-
-        // Check consolidation criteria first (cheaper check)
-        let should_consolidate = utxo
-            .needs_consolidation
-            .get()
-            .map(|fee_rate| fee_rate >= mempool_fee_rate.0)
-            .unwrap_or(false);
+    // Only UTXOs flagged at a fee rate at or above the current one are worth sweeping.
+    #[cfg_attr(not(feature = "utxo-age"), allow(unused_mut))]
+    let mut candidates: Vec<&UtxoInfo<RS>> = pool_shard_btc_utxos
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|utxo| {
+            utxo.needs_consolidation
+                .get()
+                .map(|fee_rate| fee_rate >= mempool_fee_rate.0)
+                .unwrap_or(false)
+                && !utxo.is_locked()
+        })
+        .collect();
 
-        if !should_consolidate {
-            continue;
-        }
+    // Sweep the oldest / cheapest-to-sweep UTXOs first so they are the ones kept
+    // when the transaction runs out of space.
+    #[cfg(feature = "utxo-age")]
+    candidates.sort_by(|a, b| compare_consolidation_priority(a, b));
 
+    for utxo in candidates {
         let outpoint = utxo.meta.to_outpoint();
 
         // Only include if not already in transaction
@@ -100,6 +106,35 @@ where
     (total_input_amount, extra_tx_size)
 }
 
+/// Orders consolidation candidates: oldest creation height first, then the
+/// highest fee rate at creation (the largest saving when swept now), then the
+/// largest value. UTXOs without recorded age sort last.
+#[cfg(feature = "utxo-age")]
+fn compare_consolidation_priority<RS>(a: &UtxoInfo<RS>, b: &UtxoInfo<RS>) -> core::cmp::Ordering
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+    use core::cmp::Ordering;
+
+    let by_height = match (a.created_at_height(), b.created_at_height()) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+
+    let by_fee_rate = match (a.created_fee_rate(), b.created_fee_rate()) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+
+    by_height
+        .then(by_fee_rate)
+        .then_with(|| b.value.cmp(&a.value))
+}
+
 #[cfg(feature = "utxo-consolidation")]
 fn calculate_extra_tx_size_for_consolidation(
     program_input_size: usize,
@@ -123,7 +158,7 @@ pub fn safe_add_input_to_transaction<C: PushPopCollection<InputToSign>>(
             index: input_index,
             signer: *signer,
         })
-        .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+        .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
     transaction.input.push(input.clone());
 
@@ -131,8 +166,7 @@ pub fn safe_add_input_to_transaction<C: PushPopCollection<InputToSign>>(
         transaction,
         inputs_to_sign,
         new_potential_inputs_and_outputs,
-    )
-    .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+    )?;
 
     if total_size > MAX_BTC_TX_SIZE {
         inputs_to_sign.pop();
@@ -156,8 +190,7 @@ pub fn safe_add_output_to_transaction(
         transaction,
         inputs_to_sign,
         new_potential_inputs_and_outputs,
-    )
-    .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+    )?;
 
     if total_size > MAX_BTC_TX_SIZE {
         transaction.output.pop();
@@ -436,6 +469,58 @@ mod tests {
         assert_eq!(extra_size, 0);
     }
 
+    #[test]
+    #[cfg(feature = "utxo-age")]
+    fn test_add_consolidation_utxos_prefers_oldest() {
+        let mut transaction = create_mock_transaction();
+        let mut tx_statuses = MempoolInfo::default();
+        let mut inputs_to_sign = MockPushPopCollection::default();
+        let pool_pubkey = Pubkey::default();
+        let mempool_fee_rate = FeeRate::try_from(10.0).unwrap();
+
+        let mut utxos = vec![
+            create_mock_utxo_info([1; 32], 0, 10000, Some(15.0)), // No age recorded
+            create_mock_utxo_info([2; 32], 0, 20000, Some(15.0)),
+            create_mock_utxo_info([3; 32], 0, 30000, Some(15.0)),
+            create_mock_utxo_info([4; 32], 0, 40000, Some(15.0)),
+        ];
+        utxos[1].record_creation(12.0, 800_100);
+        utxos[2].record_creation(12.0, 800_000);
+        utxos[3].record_creation(20.0, 800_100); // Same age as [2], higher creation fee
+
+        let new_potential_inputs_and_outputs = NewPotentialInputsAndOutputs {
+            inputs: None,
+            outputs: vec![],
+        };
+
+        let (total_amount, _) = add_consolidation_utxos(
+            &mut transaction,
+            &mut tx_statuses,
+            &mut inputs_to_sign,
+            &pool_pubkey,
+            &utxos,
+            &mempool_fee_rate,
+            &new_potential_inputs_and_outputs,
+            ARCH_INPUT_SIZE,
+        );
+
+        assert_eq!(total_amount, 100000);
+        let order: Vec<OutPoint> = transaction
+            .input
+            .iter()
+            .map(|input| input.previous_output)
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                create_mock_outpoint([3; 32], 0),
+                create_mock_outpoint([4; 32], 0),
+                create_mock_outpoint([2; 32], 0),
+                create_mock_outpoint([1; 32], 0),
+            ]
+        );
+    }
+
     #[test]
     fn test_add_consolidation_utxos_skip_existing_inputs() {
         let mut transaction = create_mock_transaction();
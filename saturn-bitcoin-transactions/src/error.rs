@@ -46,11 +46,32 @@ pub enum BitcoinTxError {
     #[error("Rune addition overflow")]
     RuneAdditionOverflow,
 
+    /// No longer returned: a full builder is reported as `CapacityExceeded`.
+    /// Kept so that the codes of the later variants do not change.
     #[error("Input to sign list is full")]
     InputToSignListFull,
 
+    /// No longer returned: a full builder is reported as `CapacityExceeded`.
+    /// Kept so that the codes of the later variants do not change.
     #[error("Modified account list is full")]
     ModifiedAccountListFull,
+
+    #[error("The transaction fee exceeds the configured maximum")]
+    FeeExceedsCap,
+
+    /// The inputs to sign or the modified accounts of a builder, or the
+    /// inputs to sign used by fee estimation and consolidation, are full.
+    #[error("The transaction builder has reached its input-to-sign or modified-account capacity")]
+    CapacityExceeded,
+
+    #[error("The output script is longer than the fixed-size script_pubkey storage")]
+    ScriptPubkeyTooLong,
+
+    #[error("The transaction returned by the runtime could not be decoded")]
+    InvalidTransactionData,
+
+    #[error("The transaction has no output at the referenced index")]
+    OutputNotFound,
 }
 
 impl From<FixedSetError> for BitcoinTxError {
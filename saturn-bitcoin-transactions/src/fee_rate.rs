@@ -3,6 +3,42 @@ use std::str::FromStr;
 use anyhow::{bail, Error};
 use bitcoin::Amount;
 
+/// Number of weight units per virtual byte.
+pub const WITNESS_SCALE_FACTOR: usize = 4;
+
+/// Rounding applied when a fractional amount has to be turned into a whole number.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Rounding {
+    /// Round towards positive infinity. This is what bitcoind uses both for
+    /// `vsize` and for the minimum fee of a given size.
+    Up,
+    /// Round towards zero.
+    Down,
+    /// Round half away from zero.
+    Nearest,
+}
+
+impl Rounding {
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            Rounding::Up => value.ceil(),
+            Rounding::Down => value.floor(),
+            Rounding::Nearest => value.round(),
+        }
+    }
+}
+
+/// Converts a weight (in weight units) into a virtual size.
+///
+/// bitcoind computes `vsize` as `ceil(weight / 4)`; use [`Rounding::Up`] to match it.
+pub fn weight_to_vsize(weight: usize, rounding: Rounding) -> usize {
+    match rounding {
+        Rounding::Up => weight.div_ceil(WITNESS_SCALE_FACTOR),
+        Rounding::Down => weight / WITNESS_SCALE_FACTOR,
+        Rounding::Nearest => (weight + WITNESS_SCALE_FACTOR / 2) / WITNESS_SCALE_FACTOR,
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct FeeRate(pub f64);
 
@@ -40,6 +76,21 @@ impl FeeRate {
         Amount::from_sat((self.0 * vsize as f64).round() as u64)
     }
 
+    /// Fee (in sats) for `vsize` virtual bytes, rounded as requested.
+    pub fn fee_for_vsize(&self, vsize: usize, rounding: Rounding) -> Amount {
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        Amount::from_sat(rounding.apply(self.0 * vsize as f64) as u64)
+    }
+
+    /// Fee (in sats) for a transaction of `weight` weight units.
+    ///
+    /// The weight is first converted to a `vsize` the way bitcoind does it
+    /// (rounding up), then the fee is rounded as requested.
+    pub fn fee_for_weight(&self, weight: usize, rounding: Rounding) -> Amount {
+        self.fee_for_vsize(weight_to_vsize(weight, Rounding::Up), rounding)
+    }
+
     pub fn n(&self) -> f64 {
         self.0
     }
@@ -78,4 +129,29 @@ mod tests {
             Amount::from_sat(123456789)
         );
     }
+
+    #[test]
+    fn weight_to_vsize_rounding() {
+        assert_eq!(weight_to_vsize(400, Rounding::Up), 100);
+        assert_eq!(weight_to_vsize(401, Rounding::Up), 101);
+        assert_eq!(weight_to_vsize(401, Rounding::Down), 100);
+        assert_eq!(weight_to_vsize(401, Rounding::Nearest), 100);
+        assert_eq!(weight_to_vsize(402, Rounding::Nearest), 101);
+    }
+
+    #[test]
+    fn fee_for_weight() {
+        let rate = "1.5".parse::<FeeRate>().unwrap();
+
+        // 401 WU -> 101 vB -> 151.5 sats
+        assert_eq!(
+            rate.fee_for_weight(401, Rounding::Up),
+            Amount::from_sat(152)
+        );
+        assert_eq!(
+            rate.fee_for_weight(401, Rounding::Down),
+            Amount::from_sat(151)
+        );
+        assert_eq!(rate.fee_for_vsize(101, Rounding::Nearest), rate.fee(101));
+    }
 }
@@ -13,6 +13,7 @@
 //! - **Fee calculation**: Automatic fee estimation and adjustment with mempool ancestry tracking
 //! - **Rune support**: Optional rune transaction support when compiled with `runes` feature
 //! - **UTXO consolidation**: Optional consolidation features for managing fragmented UTXOs
+//! - **UTXO locks**: Optional `utxo-lock` feature to reserve UTXOs so selection helpers skip them
 //! - **BPF compatibility**: Suitable for on-chain programs running in Solana BPF VM
 //!
 //! ## Quick Start
@@ -65,13 +66,14 @@ use crate::{
     arch::create_account,
     bytes::txid_to_bytes_big_endian,
     calc_fee::{
-        adjust_transaction_to_pay_fees, estimate_final_tx_vsize,
+        adjust_transaction_to_pay_fees, estimate_final_tx_vsize, estimate_final_tx_weight,
         estimate_tx_size_with_additional_inputs_outputs,
         estimate_tx_vsize_with_additional_inputs_outputs,
+        estimate_tx_weight_with_additional_inputs_outputs,
     },
     constants::DUST_LIMIT,
     error::BitcoinTxError,
-    fee_rate::FeeRate,
+    fee_rate::{FeeRate, Rounding},
     mempool::generate_mempool_info,
     utxo_info::UtxoInfo,
 };
@@ -80,6 +82,8 @@ use crate::{
 use crate::{consolidation::add_consolidation_utxos, input_calc::ARCH_INPUT_SIZE};
 
 mod arch;
+#[cfg(feature = "borsh")]
+pub mod borsh;
 pub mod bytes;
 mod calc_fee;
 mod consolidation;
@@ -482,7 +486,7 @@ pub struct NewPotentialInputsAndOutputs {
 /// // Capacity limits are enforced at runtime
 /// match builder.inputs_to_sign.len() {
 ///     len if len >= 4 => {
-///         // Handle InputToSignListFull error
+///         // Further inputs would fail with CapacityExceeded
 ///     }
 ///     _ => {
 ///         // Safe to add more inputs
@@ -548,6 +552,10 @@ pub struct TransactionBuilder<
 
     pub total_btc_input: u64,
 
+    /// Upper bound (in satoshis) on the fee this transaction may pay. See
+    /// [`TransactionBuilder::set_max_fee`].
+    max_fee: Option<u64>,
+
     _phantom: std::marker::PhantomData<RuneSet>,
 
     #[cfg(feature = "runes")]
@@ -632,6 +640,7 @@ impl<
             modified_accounts: FixedList::new(),
             inputs_to_sign: FixedList::new(),
             total_btc_input: 0,
+            max_fee: None,
 
             #[cfg(feature = "utxo-consolidation")]
             total_btc_consolidation_input: 0,
@@ -667,6 +676,7 @@ impl<
             modified_accounts: FixedList::new(),
             inputs_to_sign: FixedList::new(),
             total_btc_input,
+            max_fee: None,
 
             #[cfg(feature = "utxo-consolidation")]
             total_btc_consolidation_input: 0,
@@ -741,6 +751,7 @@ impl<
             modified_accounts: FixedList::new(),
             inputs_to_sign: FixedList::new(),
             total_btc_input: 0,
+            max_fee: None,
 
             total_rune_inputs: RuneSet::default(),
             runestone: Runestone::default(),
@@ -793,6 +804,7 @@ impl<
             modified_accounts: FixedList::new(),
             inputs_to_sign: FixedList::new(),
             total_btc_input,
+            max_fee: None,
 
             total_rune_inputs,
             runestone,
@@ -813,12 +825,14 @@ impl<
         program_id: &Pubkey,
         seeds: &[&[u8]],
     ) -> Result<(), ProgramError> {
+        self.ensure_capacity(1, 1)?;
+
         self.inputs_to_sign
             .push(InputToSign {
                 index: self.transaction.input.len() as u32,
                 signer: account.key.clone(),
             })
-            .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+            .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
         create_account(
             &utxo.meta,
@@ -833,7 +847,7 @@ impl<
 
         self.modified_accounts
             .push(ModifiedAccount::new(account))
-            .map_err(|_| BitcoinTxError::ModifiedAccountListFull)?;
+            .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
         self.total_btc_input += utxo.value;
 
@@ -893,9 +907,9 @@ impl<
     ///
     /// ## Error Handling
     ///
-    /// Returns [`BitcoinTxError::InputToSignListFull`] if the builder has reached its
-    /// `MAX_INPUTS_TO_SIGN` limit, or [`BitcoinTxError::ModifiedAccountListFull`] if
-    /// the `MAX_MODIFIED_ACCOUNTS` limit is exceeded.
+    /// Returns [`BitcoinTxError::CapacityExceeded`] if the builder has reached either its
+    /// `MAX_INPUTS_TO_SIGN` or its `MAX_MODIFIED_ACCOUNTS` limit. Capacity is checked before
+    /// anything is mutated, so a failed call leaves the builder untouched.
     ///
     /// ## See Also
     ///
@@ -905,18 +919,20 @@ impl<
         &mut self,
         account: &'a AccountInfo<'a>,
     ) -> Result<(), BitcoinTxError> {
+        self.ensure_capacity(1, 1)?;
+
         self.inputs_to_sign
             .push(InputToSign {
                 index: self.transaction.input.len() as u32,
                 signer: account.key.clone(),
             })
-            .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+            .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
         add_state_transition(&mut self.transaction, account);
 
         self.modified_accounts
             .push(ModifiedAccount::new(account))
-            .map_err(|_| BitcoinTxError::ModifiedAccountListFull)?;
+            .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
         // UTXO accounts always have dust limit amount.
         self.total_btc_input += DUST_LIMIT;
@@ -936,6 +952,8 @@ impl<
         tx_index: usize,
         account: &'a AccountInfo<'a>,
     ) -> Result<(), BitcoinTxError> {
+        self.ensure_capacity(1, 1)?;
+
         let utxo_outpoint = OutPoint {
             txid: Txid::from_str(&hex::encode(account.utxo.txid())).unwrap(),
             vout: account.utxo.vout(),
@@ -964,11 +982,11 @@ impl<
                 index: tx_index_u32,
                 signer: account.key.clone(),
             })
-            .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+            .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
         self.modified_accounts
             .push(ModifiedAccount::new(account))
-            .map_err(|_| BitcoinTxError::ModifiedAccountListFull)?;
+            .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
         // UTXO accounts always have dust limit amount.
         self.total_btc_input += DUST_LIMIT;
@@ -988,12 +1006,14 @@ impl<
         status: &TxStatus,
         signer: &Pubkey,
     ) -> Result<(), BitcoinTxError> {
+        self.ensure_capacity(1, 0)?;
+
         self.inputs_to_sign
             .push(InputToSign {
                 index: self.transaction.input.len() as u32,
                 signer: *signer,
             })
-            .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+            .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
         let outpoint = utxo.meta.to_outpoint();
 
@@ -1018,6 +1038,19 @@ impl<
         Ok(())
     }
 
+    /// Same as [`TransactionBuilder::add_tx_input`] but resolves the input's [`TxStatus`] from
+    /// `mempool_data` (keyed by the UTXO's txid) instead of trusting a caller-supplied status.
+    pub fn add_tx_input_auto<const MAX_UTXOS: usize, const MAX_ACCOUNTS: usize>(
+        &mut self,
+        utxo: &UtxoInfo<RuneSet>,
+        mempool_data: &MempoolData<MAX_UTXOS, MAX_ACCOUNTS>,
+        signer: &Pubkey,
+    ) -> Result<(), BitcoinTxError> {
+        let status = mempool_data.get_utxo_status(utxo.meta.txid_big_endian());
+
+        self.add_tx_input(utxo, &status, signer)
+    }
+
     /// Appends a **user-supplied** [`TxIn`] (already built elsewhere) while still tracking the
     /// UTXO ancestry for fee-rate purposes.
     pub fn add_user_tx_input(
@@ -1071,6 +1104,8 @@ impl<
         status: &TxStatus,
         signer: &Pubkey,
     ) -> Result<(), BitcoinTxError> {
+        self.ensure_capacity(1, 0)?;
+
         let outpoint = utxo.meta.to_outpoint();
 
         self.add_tx_status(utxo, status);
@@ -1098,7 +1133,7 @@ impl<
                 index: tx_index_u32,
                 signer: *signer,
             })
-            .map_err(|_| BitcoinTxError::InputToSignListFull)?;
+            .map_err(|_| BitcoinTxError::CapacityExceeded)?;
 
         self.total_btc_input += utxo.value;
 
@@ -1169,6 +1204,9 @@ impl<
     ///   `needs_consolidation` flag, then sort by descending value.
     /// * Without the feature: simply sort by descending value.
     ///
+    /// UTXOs reserved through the `utxo-lock` feature (see [`UtxoInfo::lock_until`]) are never
+    /// selected.
+    ///
     /// Returns the **indices** of the chosen items inside the original slice plus the total value
     /// selected.
     ///
@@ -1185,8 +1223,10 @@ impl<
     {
         let mut btc_amount = 0;
 
-        // Create indices instead of cloning the entire vector
-        let mut utxo_indices: Vec<usize> = (0..utxos.len()).collect();
+        // Create indices instead of cloning the entire vector, skipping reserved UTXOs
+        let mut utxo_indices: Vec<usize> = (0..utxos.len())
+            .filter(|&i| !utxos[i].as_ref().is_locked())
+            .collect();
 
         // Sort indices by prioritizing non-consolidation UTXOs and then by value (biggest first)
         #[cfg(feature = "utxo-consolidation")]
@@ -1285,6 +1325,7 @@ impl<
     /// - Cannot reduce outputs enough to meet fee rate
     /// - Transaction would exceed size limits
     /// - Fee rate calculation fails
+    /// - The resulting fee exceeds the cap configured with [`Self::set_max_fee`]
     ///
     /// ## Best Practices
     ///
@@ -1324,7 +1365,24 @@ impl<
             self.total_btc_input,
             address_to_send_remaining_btc,
             fee_rate,
-        )
+        )?;
+
+        self.check_max_fee()
+    }
+
+    /// Caps the total fee (in satoshis) this transaction is allowed to pay.
+    ///
+    /// Once a cap is set, both [`Self::adjust_transaction_to_pay_fees`] and [`Self::finalize`]
+    /// fail with [`BitcoinTxError::FeeExceedsCap`] whenever `inputs − outputs` is larger than
+    /// `sats`. This guards pool funds against pathological oracle fee rates or accounting bugs
+    /// that would otherwise silently hand value to miners.
+    pub fn set_max_fee(&mut self, sats: u64) {
+        self.max_fee = Some(sats);
+    }
+
+    /// Returns the fee cap configured through [`Self::set_max_fee`], if any.
+    pub fn max_fee(&self) -> Option<u64> {
+        self.max_fee
     }
 
     /// Attempts to **sweep** pool-owned UTXOs marked for consolidation into the current
@@ -1431,6 +1489,39 @@ impl<
         )?)
     }
 
+    /// Returns the *weight* (in weight units) the final transaction will have once every
+    /// input to sign carries its witness.
+    ///
+    /// Unlike [`Self::estimate_final_tx_vsize`] no rounding happens here; convert with
+    /// [`fee_rate::weight_to_vsize`] or [`FeeRate::fee_for_weight`] to pick it explicitly.
+    pub fn estimate_final_tx_weight(&self) -> usize {
+        estimate_final_tx_weight(&self.transaction, self.inputs_to_sign.as_slice())
+    }
+
+    /// Same as [`Self::estimate_tx_size_with_additional_inputs_outputs`] but returns **weight**
+    /// instead of raw size.
+    pub fn estimate_tx_weight_with_additional_inputs_outputs(
+        &mut self,
+        new_potential_inputs_and_outputs: &NewPotentialInputsAndOutputs,
+    ) -> Result<usize, BitcoinTxError> {
+        estimate_tx_weight_with_additional_inputs_outputs(
+            &mut self.transaction,
+            &mut self.inputs_to_sign,
+            new_potential_inputs_and_outputs,
+        )
+    }
+
+    /// Fee (in sats) the final transaction needs at `fee_rate`, computed from its weight.
+    ///
+    /// The weight is converted to vsize the way bitcoind does (rounding up) and the fee is
+    /// rounded according to `rounding`. Use [`Rounding::Up`] to get the minimum fee bitcoind
+    /// accepts for this rate.
+    pub fn estimate_final_tx_fee(&self, fee_rate: &FeeRate, rounding: Rounding) -> u64 {
+        fee_rate
+            .fee_for_weight(self.estimate_final_tx_weight(), rounding)
+            .to_sat()
+    }
+
     /// Returns the **aggregate mempool size (bytes) and fees (sats)** of all ancestor
     /// transactions referenced by *pending* inputs.
     pub fn get_ancestors_totals(&self) -> Result<(usize, u64), BitcoinTxError> {
@@ -1562,6 +1653,7 @@ impl<
     /// ## Error Handling
     ///
     /// Returns [`ProgramError`] if:
+    /// - The fee paid exceeds the cap configured with [`Self::set_max_fee`]
     /// - The transaction data is invalid
     /// - Required metadata is missing
     /// - The Arch runtime cannot accept the transaction
@@ -1573,6 +1665,8 @@ impl<
     /// - [`Self::is_fee_rate_valid`] for fee validation
     /// - [`arch_program::program::set_transaction_to_sign`] for the underlying mechanism
     pub fn finalize(&mut self) -> Result<(), ProgramError> {
+        self.check_max_fee()?;
+
         set_transaction_to_sign(
             self.modified_accounts.as_mut_slice(),
             &self.transaction,
@@ -1582,6 +1676,35 @@ impl<
         Ok(())
     }
 
+    /// Fails with [`BitcoinTxError::CapacityExceeded`] unless `inputs_to_sign` more inputs to
+    /// sign and `modified_accounts` more modified accounts still fit in the builder.
+    ///
+    /// Called up front by every mutator so that a full builder is reported before the
+    /// transaction is touched, instead of leaving an input without its signer.
+    fn ensure_capacity(
+        &self,
+        inputs_to_sign: usize,
+        modified_accounts: usize,
+    ) -> Result<(), BitcoinTxError> {
+        if self.inputs_to_sign.len() + inputs_to_sign > MAX_INPUTS_TO_SIGN
+            || self.modified_accounts.len() + modified_accounts > MAX_MODIFIED_ACCOUNTS
+        {
+            return Err(BitcoinTxError::CapacityExceeded);
+        }
+
+        Ok(())
+    }
+
+    fn check_max_fee(&self) -> Result<(), BitcoinTxError> {
+        if let Some(max_fee) = self.max_fee {
+            if self.get_fee_paid()? > max_fee {
+                return Err(BitcoinTxError::FeeExceedsCap);
+            }
+        }
+
+        Ok(())
+    }
+
     fn add_tx_status(&mut self, utxo: &UtxoInfo<RuneSet>, status: &TxStatus) {
         // Check if we have not added this txid yet.
         for input in &self.transaction.input {
@@ -1920,6 +2043,73 @@ mod tests {
         }
     }
 
+    mod max_fee {
+        use super::*;
+
+        #[test]
+        fn no_cap_by_default() {
+            let mut builder = new_tb!(10, 10);
+            builder.total_btc_input = 100_000;
+
+            assert_eq!(builder.max_fee(), None);
+            assert!(builder.check_max_fee().is_ok());
+        }
+
+        #[test]
+        fn adjust_fails_when_fee_exceeds_cap() {
+            let mut builder = new_tb!(10, 10);
+            builder.total_btc_input = 100_000;
+            builder.transaction.output.push(TxOut {
+                value: Amount::from_sat(50_000),
+                script_pubkey: ScriptBuf::new(),
+            });
+            builder.set_max_fee(1_000);
+
+            // Without a change address the whole remainder goes to miners.
+            let fee_rate = FeeRate::try_from(10.0).unwrap();
+            let result = builder.adjust_transaction_to_pay_fees(&fee_rate, None);
+
+            assert_eq!(result.unwrap_err(), BitcoinTxError::FeeExceedsCap);
+        }
+
+        #[test]
+        fn adjust_succeeds_when_change_keeps_fee_under_cap() {
+            let mut builder = new_tb!(10, 10);
+            builder.total_btc_input = 100_000;
+            builder.transaction.output.push(TxOut {
+                value: Amount::from_sat(50_000),
+                script_pubkey: ScriptBuf::new(),
+            });
+            builder.set_max_fee(10_000);
+
+            let fee_rate = FeeRate::try_from(10.0).unwrap();
+            builder
+                .adjust_transaction_to_pay_fees(&fee_rate, Some(ScriptBuf::new()))
+                .unwrap();
+
+            assert!(builder.get_fee_paid().unwrap() <= 10_000);
+        }
+
+        #[test]
+        fn cap_is_inclusive() {
+            let mut builder = new_tb!(10, 10);
+            builder.total_btc_input = 100_000;
+            builder.transaction.output.push(TxOut {
+                value: Amount::from_sat(95_000),
+                script_pubkey: ScriptBuf::new(),
+            });
+
+            builder.set_max_fee(5_000);
+            assert!(builder.check_max_fee().is_ok());
+
+            builder.set_max_fee(4_999);
+            assert_eq!(
+                builder.check_max_fee().unwrap_err(),
+                BitcoinTxError::FeeExceedsCap
+            );
+        }
+    }
+
     mod get_ancestors_totals {
         use super::*;
 
@@ -2099,6 +2289,48 @@ mod tests {
         }
     }
 
+    mod estimate_final_tx_weight {
+        use super::*;
+        use crate::fee_rate::{weight_to_vsize, Rounding};
+        use arch_program::input_to_sign::InputToSign;
+        use arch_program::pubkey::Pubkey;
+
+        #[test]
+        fn estimates_weight_and_fee_with_explicit_rounding() {
+            let mut builder = new_tb!(10, 10);
+
+            builder
+                .inputs_to_sign
+                .push(InputToSign {
+                    index: 0,
+                    signer: Pubkey::system_program(),
+                })
+                .unwrap();
+            builder.transaction.input.push(TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            });
+
+            let weight = builder.estimate_final_tx_weight();
+            let vsize = weight_to_vsize(weight, Rounding::Up);
+
+            // The vsize estimate floors the witness part, so it never exceeds the weight-based one.
+            assert!(builder.estimate_final_tx_vsize() <= vsize);
+
+            let fee_rate = FeeRate::try_from(1.5).unwrap();
+            assert_eq!(
+                builder.estimate_final_tx_fee(&fee_rate, Rounding::Up),
+                (vsize as f64 * 1.5).ceil() as u64
+            );
+            assert_eq!(
+                builder.estimate_final_tx_fee(&fee_rate, Rounding::Down),
+                (vsize as f64 * 1.5).floor() as u64
+            );
+        }
+    }
+
     #[cfg(feature = "utxo-consolidation")]
     mod get_fee_paid_by_program {
         use super::*;
@@ -2219,6 +2451,104 @@ mod tests {
         }
     }
 
+    mod add_tx_input_auto {
+        use super::*;
+
+        #[test]
+        fn resolves_status_from_mempool_data() {
+            let mut builder = new_tb!(10, 10);
+            let signer = Pubkey::system_program();
+
+            let pending = create_mock_utxo(10_000, [1; 32], 0);
+            let confirmed = create_mock_utxo(20_000, [2; 32], 0);
+
+            let mut utxo_mempool_info = [None; 10];
+            utxo_mempool_info[0] = Some((
+                pending.meta.txid_big_endian(),
+                MempoolInfo {
+                    total_fee: 1_000,
+                    total_size: 250,
+                },
+            ));
+            let mempool_data = mempool_oracle_sdk::MempoolData::<10, 10>::new(
+                utxo_mempool_info,
+                std::array::from_fn(|_| mempool_oracle_sdk::AccountMempoolInfo::default()),
+            );
+
+            builder
+                .add_tx_input_auto(&pending, &mempool_data, &signer)
+                .unwrap();
+            builder
+                .add_tx_input_auto(&confirmed, &mempool_data, &signer)
+                .unwrap();
+
+            assert_eq!(builder.transaction.input.len(), 2);
+            assert_eq!(builder.total_btc_input, 30_000);
+            // Only the pending input contributes ancestor fees/size.
+            assert_eq!(builder.tx_statuses.total_fee, 1_000);
+            assert_eq!(builder.tx_statuses.total_size, 250);
+        }
+    }
+
+    mod capacity {
+        use super::*;
+
+        #[test]
+        fn add_tx_input_fails_when_inputs_to_sign_full() {
+            let mut builder = new_tb!(1, 1);
+            let signer = Pubkey::system_program();
+
+            builder
+                .add_tx_input(
+                    &create_mock_utxo(10_000, [1; 32], 0),
+                    &TxStatus::Confirmed,
+                    &signer,
+                )
+                .unwrap();
+
+            let result = builder.add_tx_input(
+                &create_mock_utxo(20_000, [2; 32], 0),
+                &TxStatus::Confirmed,
+                &signer,
+            );
+
+            assert_eq!(result.unwrap_err(), BitcoinTxError::CapacityExceeded);
+            assert_eq!(builder.transaction.input.len(), 1);
+            assert_eq!(builder.inputs_to_sign.len(), 1);
+            assert_eq!(builder.total_btc_input, 10_000);
+        }
+
+        #[test]
+        fn insert_tx_input_leaves_builder_untouched_when_full() {
+            let mut builder = new_tb!(1, 1);
+            let signer = Pubkey::system_program();
+
+            builder
+                .add_tx_input(
+                    &create_mock_utxo(10_000, [1; 32], 0),
+                    &TxStatus::Confirmed,
+                    &signer,
+                )
+                .unwrap();
+
+            let result = builder.insert_tx_input(
+                0,
+                &create_mock_utxo(20_000, [2; 32], 0),
+                &TxStatus::Pending(MempoolInfo {
+                    total_fee: 1_000,
+                    total_size: 200,
+                }),
+                &signer,
+            );
+
+            assert_eq!(result.unwrap_err(), BitcoinTxError::CapacityExceeded);
+            assert_eq!(builder.transaction.input.len(), 1);
+            assert_eq!(builder.inputs_to_sign.as_slice()[0].index, 0);
+            assert_eq!(builder.tx_statuses.total_fee, 0);
+            assert_eq!(builder.total_btc_input, 10_000);
+        }
+    }
+
     mod modified_accounts_tracking {
         use super::*;
 
@@ -2534,5 +2864,35 @@ mod tests {
             );
             assert_eq!(found_amount, 17_000);
         }
+
+        #[test]
+        #[cfg(feature = "utxo-lock")]
+        fn skips_locked_utxos() {
+            let mut utxos = vec![
+                UtxoInfo::new(UtxoMeta::from([0; 32], 0), 5_000),
+                UtxoInfo::new(UtxoMeta::from([0; 32], 1), 8_000),
+                UtxoInfo::new(UtxoMeta::from([0; 32], 2), 12_000),
+            ];
+
+            utxos[2].lock();
+
+            let mut transaction_builder = new_tb!(10, 10);
+
+            let utxo_refs: Vec<&UtxoInfo<SingleRuneSet>> = utxos.iter().collect();
+            let (found_utxo_indices, found_amount) = transaction_builder
+                .find_btc_in_program_utxos(&utxo_refs, &PUBKEY, 10_000)
+                .unwrap();
+
+            assert_eq!(found_utxo_indices, vec![1, 0]);
+            assert_eq!(found_amount, 13_000);
+
+            let mut transaction_builder = new_tb!(10, 10);
+            assert_eq!(
+                transaction_builder
+                    .find_btc_in_program_utxos(&utxo_refs, &PUBKEY, 20_000)
+                    .unwrap_err(),
+                BitcoinTxError::NotEnoughBtcInPool
+            );
+        }
     }
 }
@@ -1,3 +1,4 @@
+use arch_program::utxo::UtxoMeta;
 use serde::Deserialize;
 
 pub fn serialize_u128<S>(num: &u128, serializer: S) -> Result<S::Ok, S::Error>
@@ -30,9 +31,62 @@ where
     s.parse::<u64>().map_err(serde::de::Error::custom)
 }
 
+pub fn serialize_script_hex<S>(script: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match script {
+        Some(bytes) => serializer.serialize_some(&hex::encode(bytes)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Decodes an optional hex-encoded `script_pubkey`, rejecting scripts longer
+/// than [`crate::utxo_info::MAX_SCRIPT_PUBKEY_LEN`].
+pub fn deserialize_script_hex<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(s) = <Option<std::string::String> as Deserialize>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    let bytes = hex::decode(s).map_err(serde::de::Error::custom)?;
+    if bytes.len() > crate::utxo_info::MAX_SCRIPT_PUBKEY_LEN {
+        return Err(serde::de::Error::custom("script_pubkey is too long"));
+    }
+
+    Ok(Some(bytes))
+}
+
+pub fn serialize_outpoint_hex<S>(meta: &UtxoMeta, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&hex::encode(meta.serialize()))
+}
+
+/// Decodes a hex-encoded outpoint: the 32-byte txid in display order
+/// followed by the little-endian `vout`, as laid out in [`UtxoMeta`].
+pub fn deserialize_outpoint_hex<'de, D>(deserializer: D) -> Result<UtxoMeta, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = <std::string::String as Deserialize>::deserialize(deserializer)?;
+    let bytes: [u8; 36] = hex::decode(s)
+        .map_err(serde::de::Error::custom)?
+        .try_into()
+        .map_err(|_| serde::de::Error::custom("outpoint must be 36 bytes long"))?;
+
+    Ok(UtxoMeta::from(bytes))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::serde::{deserialize_u128, deserialize_u64, serialize_u128, serialize_u64};
+    use crate::serde::{
+        deserialize_script_hex, deserialize_u128, deserialize_u64, serialize_script_hex,
+        serialize_u128, serialize_u64,
+    };
     use serde::{Deserialize, Serialize};
 
     #[test]
@@ -66,4 +120,26 @@ mod tests {
         let wrapper: Wrapper = serde_json::from_str(&format!("\"{}\"", value)).unwrap();
         assert_eq!(wrapper.0, value);
     }
+
+    #[test]
+    fn test_serialize_deserialize_script_hex() {
+        #[derive(Serialize, Deserialize)]
+        struct Wrapper(
+            #[serde(
+                serialize_with = "serialize_script_hex",
+                deserialize_with = "deserialize_script_hex"
+            )]
+            Option<Vec<u8>>,
+        );
+
+        let script = vec![0x51, 0x20, 0xab];
+        let json = serde_json::to_string(&Wrapper(Some(script.clone()))).unwrap();
+        assert_eq!(json, "\"5120ab\"");
+
+        let wrapper: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(wrapper.0, Some(script));
+
+        let too_long = format!("\"{}\"", "00".repeat(35));
+        assert!(serde_json::from_str::<Wrapper>(&too_long).is_err());
+    }
 }
@@ -14,6 +14,8 @@ use crate::{bytes::txid_to_bytes_big_endian, error::BitcoinTxError};
 #[cfg(feature = "runes")]
 use crate::arch::get_runes;
 
+pub use crate::arch::FundingTxCache;
+
 /// Trait defining the essential operations needed by StateShard for UTXO types.
 /// This allows StateShard to work with different concrete UtxoInfo implementations
 /// while maintaining a consistent interface.
@@ -45,11 +47,78 @@ pub trait UtxoInfoTrait<RuneSet: FixedCapacitySet<Item = RuneAmount>> {
     /// Get mutable access to the consolidation information when the "utxo-consolidation" feature is enabled
     #[cfg(feature = "utxo-consolidation")]
     fn needs_consolidation_mut(&mut self) -> &mut FixedOptionF64;
+
+    /// Whether the UTXO is reserved and must be skipped by selection helpers.
+    /// Defaults to `false` for implementations that do not track locks.
+    fn is_locked(&self) -> bool {
+        false
+    }
+
+    /// Record the mempool fee rate and Bitcoin tip height at the time the UTXO
+    /// was created. No-op for implementations that do not track UTXO age.
+    fn record_creation(&mut self, _fee_rate: f64, _block_height: u64) {}
 }
 
 #[cfg(feature = "utxo-consolidation")]
 declare_fixed_option!(FixedOptionF64, f64, 7);
 
+#[cfg(any(feature = "utxo-confirmations", feature = "utxo-age"))]
+declare_fixed_option!(FixedOptionU64, u64, 7);
+
+/// Longest `script_pubkey` stored inline (P2TR / P2WSH outputs are 34 bytes).
+pub const MAX_SCRIPT_PUBKEY_LEN: usize = 34;
+
+/// Fixed-size, `Pod` storage for an output `script_pubkey`.
+#[cfg(feature = "utxo-script")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct FixedScriptPubkey {
+    bytes: [u8; MAX_SCRIPT_PUBKEY_LEN],
+    len: u8,
+    _padding: [u8; 5],
+}
+
+#[cfg(feature = "utxo-script")]
+impl FixedScriptPubkey {
+    /// Copies `script` into fixed storage.
+    ///
+    /// Fails with [`BitcoinTxError::ScriptPubkeyTooLong`] when `script` exceeds
+    /// [`MAX_SCRIPT_PUBKEY_LEN`] bytes.
+    pub fn new(script: &[u8]) -> Result<Self, BitcoinTxError> {
+        if script.len() > MAX_SCRIPT_PUBKEY_LEN {
+            return Err(BitcoinTxError::ScriptPubkeyTooLong);
+        }
+
+        let mut bytes = [0u8; MAX_SCRIPT_PUBKEY_LEN];
+        bytes[..script.len()].copy_from_slice(script);
+
+        Ok(Self {
+            bytes,
+            len: script.len() as u8,
+            _padding: [0; 5],
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(feature = "utxo-script")]
+impl Default for FixedScriptPubkey {
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
 #[cfg(feature = "runes")]
 pub type SingleRuneSet = FixedSet<RuneAmount, 1>;
 
@@ -71,6 +140,29 @@ pub struct UtxoInfo<RuneSet: FixedCapacitySet<Item = RuneAmount> = SingleRuneSet
     #[cfg(feature = "utxo-consolidation")]
     pub needs_consolidation: FixedOptionF64,
 
+    /// Height of the Bitcoin block that confirmed this UTXO, `none` while it is
+    /// still in the mempool or when the height is unknown.
+    #[cfg(feature = "utxo-confirmations")]
+    pub block_height: FixedOptionU64,
+
+    /// Script the output pays to. Empty when unknown.
+    #[cfg(feature = "utxo-script")]
+    pub script_pubkey: FixedScriptPubkey,
+
+    /// Slot until which the UTXO is reserved (exclusive). `0` means unlocked,
+    /// `u64::MAX` keeps it locked until [`UtxoInfo::unlock`] is called.
+    #[cfg(feature = "utxo-lock")]
+    pub locked_until: u64,
+
+    /// Mempool fee rate (sat/vB) paid by the transaction that created this UTXO.
+    #[cfg(feature = "utxo-age")]
+    pub created_fee_rate: FixedOptionF64,
+
+    /// Bitcoin tip height when this UTXO was created, used to sweep older
+    /// UTXOs first during consolidation.
+    #[cfg(feature = "utxo-age")]
+    pub created_at_height: FixedOptionU64,
+
     // Ensure the generic parameter is referenced even when the `runes` feature is disabled.
     #[cfg(not(feature = "runes"))]
     _phantom: std::marker::PhantomData<RuneSet>,
@@ -121,6 +213,14 @@ impl<RuneSet: FixedCapacitySet<Item = RuneAmount>> UtxoInfoTrait<RuneSet> for Ut
     fn needs_consolidation_mut(&mut self) -> &mut FixedOptionF64 {
         &mut self.needs_consolidation
     }
+
+    fn is_locked(&self) -> bool {
+        UtxoInfo::is_locked(self)
+    }
+
+    fn record_creation(&mut self, fee_rate: f64, block_height: u64) {
+        UtxoInfo::record_creation(self, fee_rate, block_height)
+    }
 }
 
 // Safety: All generic parameters must also be Pod/Zeroable.
@@ -168,6 +268,16 @@ where
             runes: RuneSet::default(),
             #[cfg(feature = "utxo-consolidation")]
             needs_consolidation: FixedOptionF64::default(),
+            #[cfg(feature = "utxo-confirmations")]
+            block_height: FixedOptionU64::default(),
+            #[cfg(feature = "utxo-script")]
+            script_pubkey: FixedScriptPubkey::default(),
+            #[cfg(feature = "utxo-lock")]
+            locked_until: 0,
+            #[cfg(feature = "utxo-age")]
+            created_fee_rate: FixedOptionF64::default(),
+            #[cfg(feature = "utxo-age")]
+            created_at_height: FixedOptionU64::default(),
             // Ensure the generic parameter is referenced even when the `runes` feature is disabled.
             #[cfg(not(feature = "runes"))]
             _phantom: std::marker::PhantomData::<RuneSet>,
@@ -175,15 +285,20 @@ where
     }
 }
 
-#[cfg(feature = "runes")]
-impl<RS> TryFrom<&UtxoMeta> for UtxoInfo<RS>
+impl<RS> UtxoInfo<RS>
 where
     RS: FixedCapacitySet<Item = RuneAmount> + Default,
 {
-    type Error = ProgramError;
-
-    fn try_from(value: &UtxoMeta) -> std::result::Result<Self, ProgramError> {
+    /// Same as `UtxoInfo::try_from(meta)`, reading funding transactions
+    /// through `cache` so that UTXOs created by the same transaction share a
+    /// single fetch.
+    pub fn try_from_meta_cached(
+        value: &UtxoMeta,
+        #[cfg_attr(not(feature = "utxo-script"), allow(unused_variables))]
+        cache: &mut FundingTxCache,
+    ) -> std::result::Result<Self, ProgramError> {
         // Fetch rune amount (at most one) from the UTXO.
+        #[cfg(feature = "runes")]
         let runes = get_runes(value)?;
 
         let outpoint = value.to_outpoint();
@@ -195,34 +310,48 @@ where
         Ok(UtxoInfo {
             meta: value.clone(),
             value: ui_value,
-            runes: runes,
+            #[cfg(feature = "runes")]
+            runes,
             #[cfg(feature = "utxo-consolidation")]
             needs_consolidation: FixedOptionF64::none(),
+            // The runtime does not expose the confirming block of an output,
+            // so the height stays unknown on-chain. The `min_confirmations`
+            // parser key refuses to build for that target for this reason.
+            #[cfg(feature = "utxo-confirmations")]
+            block_height: FixedOptionU64::none(),
+            #[cfg(feature = "utxo-script")]
+            script_pubkey: cache.output_script_pubkey(value)?,
+            #[cfg(feature = "utxo-lock")]
+            locked_until: 0,
+            #[cfg(feature = "utxo-age")]
+            created_fee_rate: FixedOptionF64::none(),
+            #[cfg(feature = "utxo-age")]
+            created_at_height: FixedOptionU64::none(),
+            #[cfg(not(feature = "runes"))]
+            _phantom: std::marker::PhantomData::<RS>,
         })
     }
 }
 
+#[cfg(feature = "runes")]
+impl<RS> TryFrom<&UtxoMeta> for UtxoInfo<RS>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default,
+{
+    type Error = ProgramError;
+
+    fn try_from(value: &UtxoMeta) -> std::result::Result<Self, ProgramError> {
+        Self::try_from_meta_cached(value, &mut FundingTxCache::new())
+    }
+}
+
 // When the "runes" feature is disabled, fallback implementation without rune handling.
 #[cfg(not(feature = "runes"))]
 impl TryFrom<&UtxoMeta> for UtxoInfo<SingleRuneSet> {
     type Error = ProgramError;
 
     fn try_from(value: &UtxoMeta) -> std::result::Result<Self, ProgramError> {
-        let outpoint = value.to_outpoint();
-
-        let ui_value =
-            get_bitcoin_tx_output_value(txid_to_bytes_big_endian(&outpoint.txid), outpoint.vout)
-                .ok_or(ProgramError::Custom(
-                    BitcoinTxError::TransactionNotFound.into(),
-                ))?;
-
-        Ok(UtxoInfo {
-            meta: value.clone(),
-            value: ui_value,
-            #[cfg(feature = "utxo-consolidation")]
-            needs_consolidation: FixedOptionF64::none(),
-            _phantom: std::marker::PhantomData::<SingleRuneSet>,
-        })
+        Self::try_from_meta_cached(value, &mut FundingTxCache::new())
     }
 }
 
@@ -294,3 +423,184 @@ where
         false
     }
 }
+
+#[cfg(feature = "utxo-confirmations")]
+impl<RuneSet> UtxoInfo<RuneSet>
+where
+    RuneSet: FixedCapacitySet<Item = RuneAmount>,
+{
+    /// Height of the block that confirmed this UTXO, if known.
+    pub fn block_height(&self) -> Option<u64> {
+        self.block_height.get()
+    }
+
+    /// Number of confirmations this UTXO has when the chain tip is at `tip_height`.
+    ///
+    /// Returns **0** for unconfirmed UTXOs (or unknown heights) and when the
+    /// tip is below the confirming block.
+    pub fn confirmations_at(&self, tip_height: u64) -> u64 {
+        match self.block_height() {
+            Some(height) if tip_height >= height => tip_height - height + 1,
+            _ => 0,
+        }
+    }
+
+    /// Number of confirmations relative to the current Bitcoin tip reported by the runtime.
+    ///
+    /// UTXOs read from the runtime carry no confirming height, so this is
+    /// only meaningful once [`Self::block_height`] has been filled in by the
+    /// caller.
+    pub fn confirmations(&self) -> u64 {
+        self.confirmations_at(arch_program::program::get_bitcoin_block_height())
+    }
+}
+
+#[cfg(not(feature = "utxo-confirmations"))]
+impl<RuneSet> UtxoInfo<RuneSet>
+where
+    RuneSet: FixedCapacitySet<Item = RuneAmount>,
+{
+    /// No confirming height is stored without `utxo-confirmations`; returns `None`.
+    pub fn block_height(&self) -> Option<u64> {
+        None
+    }
+
+    /// Treats the UTXO as unconfirmed at any tip; returns `0`.
+    pub fn confirmations_at(&self, _tip_height: u64) -> u64 {
+        0
+    }
+
+    /// Treats the UTXO as unconfirmed at the current tip; returns `0`.
+    pub fn confirmations(&self) -> u64 {
+        0
+    }
+}
+
+#[cfg(feature = "utxo-lock")]
+impl<RuneSet> UtxoInfo<RuneSet>
+where
+    RuneSet: FixedCapacitySet<Item = RuneAmount>,
+{
+    /// Returns `true` while `slot` is before [`Self::locked_until`].
+    pub fn is_locked_at(&self, slot: u64) -> bool {
+        slot < self.locked_until
+    }
+
+    /// Whether the UTXO is locked at the current slot reported by the runtime.
+    pub fn is_locked(&self) -> bool {
+        self.locked_until != 0 && self.is_locked_at(arch_program::program::get_clock().slot)
+    }
+
+    /// Locks the UTXO until [`Self::unlock`] is called.
+    pub fn lock(&mut self) {
+        self.locked_until = u64::MAX;
+    }
+
+    /// Locks the UTXO until `slot` (exclusive).
+    pub fn lock_until(&mut self, slot: u64) {
+        self.locked_until = slot;
+    }
+
+    pub fn unlock(&mut self) {
+        self.locked_until = 0;
+    }
+}
+
+#[cfg(not(feature = "utxo-lock"))]
+impl<RuneSet> UtxoInfo<RuneSet>
+where
+    RuneSet: FixedCapacitySet<Item = RuneAmount>,
+{
+    /// No lock is stored without `utxo-lock`; returns `false` for every slot.
+    pub fn is_locked_at(&self, _slot: u64) -> bool {
+        false
+    }
+
+    /// Treats the UTXO as never reserved; returns `false`.
+    pub fn is_locked(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "utxo-age")]
+impl<RuneSet> UtxoInfo<RuneSet>
+where
+    RuneSet: FixedCapacitySet<Item = RuneAmount>,
+{
+    /// Fee rate paid by the transaction that created this UTXO, if recorded.
+    pub fn created_fee_rate(&self) -> Option<f64> {
+        self.created_fee_rate.get()
+    }
+
+    /// Bitcoin tip height when this UTXO was created, if recorded.
+    pub fn created_at_height(&self) -> Option<u64> {
+        self.created_at_height.get()
+    }
+
+    /// Number of blocks since creation when the chain tip is at `tip_height`.
+    pub fn age_at(&self, tip_height: u64) -> Option<u64> {
+        self.created_at_height()
+            .map(|height| tip_height.saturating_sub(height))
+    }
+
+    pub fn record_creation(&mut self, fee_rate: f64, block_height: u64) {
+        self.created_fee_rate = FixedOptionF64::some(fee_rate);
+        self.created_at_height = FixedOptionU64::some(block_height);
+    }
+}
+
+#[cfg(not(feature = "utxo-age"))]
+impl<RuneSet> UtxoInfo<RuneSet>
+where
+    RuneSet: FixedCapacitySet<Item = RuneAmount>,
+{
+    /// No creation fee rate is stored without `utxo-age`; returns `None`.
+    pub fn created_fee_rate(&self) -> Option<f64> {
+        None
+    }
+
+    /// No creation height is stored without `utxo-age`; returns `None`.
+    pub fn created_at_height(&self) -> Option<u64> {
+        None
+    }
+
+    /// Age is unknown without a creation height; returns `None`.
+    pub fn age_at(&self, _tip_height: u64) -> Option<u64> {
+        None
+    }
+
+    /// Discards the values; there is nowhere to store them.
+    pub fn record_creation(&mut self, _fee_rate: f64, _block_height: u64) {}
+}
+
+#[cfg(feature = "utxo-script")]
+impl<RuneSet> UtxoInfo<RuneSet>
+where
+    RuneSet: FixedCapacitySet<Item = RuneAmount>,
+{
+    /// Raw `script_pubkey` bytes of the output (empty when unknown).
+    pub fn script_pubkey_bytes(&self) -> &[u8] {
+        self.script_pubkey.as_bytes()
+    }
+
+    /// Returns `true` when the output pays exactly to `script`.
+    pub fn pays_to_script(&self, script: &[u8]) -> bool {
+        !self.script_pubkey.is_empty() && self.script_pubkey.as_bytes() == script
+    }
+}
+
+#[cfg(not(feature = "utxo-script"))]
+impl<RuneSet> UtxoInfo<RuneSet>
+where
+    RuneSet: FixedCapacitySet<Item = RuneAmount>,
+{
+    /// No script is stored without `utxo-script`; returns an empty slice.
+    pub fn script_pubkey_bytes(&self) -> &[u8] {
+        &[]
+    }
+
+    /// Matches no script; returns `false`.
+    pub fn pays_to_script(&self, _script: &[u8]) -> bool {
+        false
+    }
+}
@@ -14,10 +14,19 @@ use crate::utxo_info::UtxoInfo;
 #[cfg(feature = "utxo-consolidation")]
 use crate::utxo_info::FixedOptionF64;
 
+/// Schema version written to [`UtxoInfoJson::version`].
+///
+/// Payloads without a `version` field are version `0`. Readers accept any
+/// version and ignore fields they do not know, so producers can add fields
+/// without breaking older consumers.
+pub const UTXO_INFO_JSON_VERSION: u32 = 1;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "utoipa", derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct UtxoInfoJson {
+    #[serde(default)]
+    pub version: u32,
     #[cfg_attr(feature = "utoipa", schema(value_type = String))]
     pub txid: Txid,
     pub vout: u32,
@@ -27,8 +36,231 @@ pub struct UtxoInfoJson {
     )]
     #[cfg_attr(feature = "utoipa", schema(value_type = String))]
     pub value: u64,
+    #[serde(default)]
     pub runes: Vec<RuneAmountJson>,
+    #[serde(default)]
     pub needs_consolidation: NeedsConsolidation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+    /// Output script, hex-encoded in JSON.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::serde::serialize_script_hex",
+        deserialize_with = "crate::serde::deserialize_script_hex"
+    )]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<String>))]
+    pub script_pubkey: Option<Vec<u8>>,
+    /// Slot until which the UTXO is reserved. Omitted when unlocked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_until: Option<u64>,
+    /// Fee rate paid by the transaction that created the UTXO.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_fee_rate: Option<f64>,
+    /// Bitcoin tip height when the UTXO was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at_height: Option<u64>,
+}
+
+/// How a payload writes its outpoint, recorded in its `encoding` field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum UtxoInfoEncoding {
+    /// [`UtxoInfoJson`]: separate `txid` and `vout` fields. Payloads without
+    /// an `encoding` field use it.
+    #[default]
+    Full,
+    /// [`CompactUtxoInfoJson`]: a single hex `outpoint` field.
+    Hex,
+}
+
+/// Compact form of [`UtxoInfoJson`], tagged with `"encoding": "hex"`: the
+/// outpoint is the hex of its 36 [`UtxoMeta`] bytes and empty fields are
+/// omitted. Select it per field with
+/// `#[serde(with = "saturn_bitcoin_transactions::utxo_info_json::compact")]`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CompactUtxoInfoJson {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(
+        serialize_with = "crate::serde::serialize_outpoint_hex",
+        deserialize_with = "crate::serde::deserialize_outpoint_hex"
+    )]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String))]
+    pub outpoint: UtxoMeta,
+    #[serde(
+        serialize_with = "crate::serde::serialize_u64",
+        deserialize_with = "crate::serde::deserialize_u64"
+    )]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String))]
+    pub value: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub runes: Vec<RuneAmountJson>,
+    #[serde(default, skip_serializing_if = "NeedsConsolidation::is_no")]
+    pub needs_consolidation: NeedsConsolidation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+    /// Output script, hex-encoded in JSON.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::serde::serialize_script_hex",
+        deserialize_with = "crate::serde::deserialize_script_hex"
+    )]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<String>))]
+    pub script_pubkey: Option<Vec<u8>>,
+    /// Slot until which the UTXO is reserved. Omitted when unlocked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_until: Option<u64>,
+    /// Fee rate paid by the transaction that created the UTXO.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_fee_rate: Option<f64>,
+    /// Bitcoin tip height when the UTXO was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at_height: Option<u64>,
+}
+
+impl From<UtxoInfoJson> for CompactUtxoInfoJson {
+    fn from(json: UtxoInfoJson) -> Self {
+        Self {
+            version: json.version,
+            outpoint: UtxoMeta::from_outpoint(json.txid, json.vout),
+            value: json.value,
+            runes: json.runes,
+            needs_consolidation: json.needs_consolidation,
+            block_height: json.block_height,
+            script_pubkey: json.script_pubkey,
+            locked_until: json.locked_until,
+            created_fee_rate: json.created_fee_rate,
+            created_at_height: json.created_at_height,
+        }
+    }
+}
+
+impl From<CompactUtxoInfoJson> for UtxoInfoJson {
+    fn from(json: CompactUtxoInfoJson) -> Self {
+        Self {
+            version: json.version,
+            txid: json.outpoint.to_txid(),
+            vout: json.outpoint.vout(),
+            value: json.value,
+            runes: json.runes,
+            needs_consolidation: json.needs_consolidation,
+            block_height: json.block_height,
+            script_pubkey: json.script_pubkey,
+            locked_until: json.locked_until,
+            created_fee_rate: json.created_fee_rate,
+            created_at_height: json.created_at_height,
+        }
+    }
+}
+
+/// A payload prefixed with its `encoding` tag.
+#[derive(Serialize)]
+struct Tagged<'a, T> {
+    encoding: UtxoInfoEncoding,
+    #[serde(flatten)]
+    json: &'a T,
+}
+
+/// Either JSON form, told apart by its `encoding` tag. Only the outpoint
+/// fields of the tagged form are required, so a malformed payload reports
+/// which field is wrong.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnyUtxoInfoJson {
+    #[serde(default)]
+    encoding: UtxoInfoEncoding,
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    txid: Option<Txid>,
+    #[serde(default)]
+    vout: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_some_outpoint")]
+    outpoint: Option<UtxoMeta>,
+    #[serde(deserialize_with = "crate::serde::deserialize_u64")]
+    value: u64,
+    #[serde(default)]
+    runes: Vec<RuneAmountJson>,
+    #[serde(default)]
+    needs_consolidation: NeedsConsolidation,
+    #[serde(default)]
+    block_height: Option<u64>,
+    #[serde(default, deserialize_with = "crate::serde::deserialize_script_hex")]
+    script_pubkey: Option<Vec<u8>>,
+    #[serde(default)]
+    locked_until: Option<u64>,
+    #[serde(default)]
+    created_fee_rate: Option<f64>,
+    #[serde(default)]
+    created_at_height: Option<u64>,
+}
+
+fn deserialize_some_outpoint<'de, D>(deserializer: D) -> Result<Option<UtxoMeta>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    crate::serde::deserialize_outpoint_hex(deserializer).map(Some)
+}
+
+impl AnyUtxoInfoJson {
+    fn into_full<E: serde::de::Error>(self) -> Result<UtxoInfoJson, E> {
+        let (txid, vout) = match self.encoding {
+            UtxoInfoEncoding::Full => (
+                self.txid.ok_or_else(|| E::missing_field("txid"))?,
+                self.vout.ok_or_else(|| E::missing_field("vout"))?,
+            ),
+            UtxoInfoEncoding::Hex => {
+                let outpoint = self.outpoint.ok_or_else(|| E::missing_field("outpoint"))?;
+                (outpoint.to_txid(), outpoint.vout())
+            }
+        };
+
+        Ok(UtxoInfoJson {
+            version: self.version,
+            txid,
+            vout,
+            value: self.value,
+            runes: self.runes,
+            needs_consolidation: self.needs_consolidation,
+            block_height: self.block_height,
+            script_pubkey: self.script_pubkey,
+            locked_until: self.locked_until,
+            created_fee_rate: self.created_fee_rate,
+            created_at_height: self.created_at_height,
+        })
+    }
+}
+
+/// `serde(with)` helpers writing a [`UtxoInfo`] in its [`CompactUtxoInfoJson`]
+/// form. Reading accepts both forms.
+pub mod compact {
+    use super::*;
+
+    pub fn serialize<RuneSet, S>(utxo: &UtxoInfo<RuneSet>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        RuneSet: FixedCapacitySet<Item = RuneAmount>,
+        S: Serializer,
+    {
+        let json: UtxoInfoJson = utxo.into();
+        Tagged {
+            encoding: UtxoInfoEncoding::Hex,
+            json: &CompactUtxoInfoJson::from(json),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, RuneSet, D>(deserializer: D) -> Result<UtxoInfo<RuneSet>, D::Error>
+    where
+        RuneSet: FixedCapacitySet<Item = RuneAmount> + Default,
+        D: Deserializer<'de>,
+    {
+        UtxoInfo::deserialize(deserializer)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -62,6 +294,12 @@ pub enum NeedsConsolidation {
     Yes(f64),
 }
 
+impl NeedsConsolidation {
+    fn is_no(&self) -> bool {
+        matches!(self, Self::No)
+    }
+}
+
 #[cfg(feature = "utxo-consolidation")]
 impl Into<NeedsConsolidation> for FixedOptionF64 {
     fn into(self) -> NeedsConsolidation {
@@ -103,6 +341,7 @@ impl<RuneSet: FixedCapacitySet<Item = RuneAmount>> Into<UtxoInfoJson> for &UtxoI
         };
 
         UtxoInfoJson {
+            version: UTXO_INFO_JSON_VERSION,
             txid: Txid::from_str(&hex::encode(&self.meta.txid())).unwrap(),
             vout: self.meta.vout(),
             value: self.value,
@@ -111,6 +350,16 @@ impl<RuneSet: FixedCapacitySet<Item = RuneAmount>> Into<UtxoInfoJson> for &UtxoI
             needs_consolidation: self.needs_consolidation.into(),
             #[cfg(not(feature = "utxo-consolidation"))]
             needs_consolidation: NeedsConsolidation::No,
+            block_height: self.block_height(),
+            script_pubkey: Some(self.script_pubkey_bytes())
+                .filter(|script| !script.is_empty())
+                .map(<[u8]>::to_vec),
+            #[cfg(feature = "utxo-lock")]
+            locked_until: Some(self.locked_until).filter(|slot| *slot != 0),
+            #[cfg(not(feature = "utxo-lock"))]
+            locked_until: None,
+            created_fee_rate: self.created_fee_rate(),
+            created_at_height: self.created_at_height(),
         }
     }
 }
@@ -152,6 +401,26 @@ impl<RuneSet: FixedCapacitySet<Item = RuneAmount> + Default> TryInto<UtxoInfo<Ru
         {
             info.needs_consolidation = self.needs_consolidation.into();
         }
+        #[cfg(feature = "utxo-confirmations")]
+        {
+            info.block_height = self.block_height.into();
+        }
+        #[cfg(feature = "utxo-script")]
+        if let Some(script) = self.script_pubkey.as_deref() {
+            // Length is already enforced by `deserialize_script_hex`.
+            if let Ok(script) = crate::utxo_info::FixedScriptPubkey::new(script) {
+                info.script_pubkey = script;
+            }
+        }
+        #[cfg(feature = "utxo-lock")]
+        {
+            info.locked_until = self.locked_until.unwrap_or(0);
+        }
+        #[cfg(feature = "utxo-age")]
+        {
+            info.created_fee_rate = self.created_fee_rate.into();
+            info.created_at_height = self.created_at_height.into();
+        }
 
         Ok(info)
     }
@@ -175,7 +444,7 @@ impl<'de, RuneSet: FixedCapacitySet<Item = RuneAmount> + Default> Deserialize<'d
     where
         D: Deserializer<'de>,
     {
-        let utxo_info_json = UtxoInfoJson::deserialize(deserializer)?;
+        let utxo_info_json = AnyUtxoInfoJson::deserialize(deserializer)?.into_full::<D::Error>()?;
 
         Ok(utxo_info_json.try_into().map_err(|e: FixedSetError| {
             serde::de::Error::custom(format!(
@@ -185,3 +454,66 @@ impl<'de, RuneSet: FixedCapacitySet<Item = RuneAmount> + Default> Deserialize<'d
         })?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utxo_info::SingleRuneSet;
+
+    const TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    fn utxo() -> UtxoInfo<SingleRuneSet> {
+        let mut info = UtxoInfo::<SingleRuneSet>::default();
+        info.meta = UtxoMeta::from_outpoint(Txid::from_str(TXID).unwrap(), 3);
+        info.value = 5_000;
+        info
+    }
+
+    #[test]
+    fn test_writes_current_version() {
+        let json = serde_json::to_value(utxo()).unwrap();
+        assert_eq!(json["version"], UTXO_INFO_JSON_VERSION);
+        assert_eq!(json["txid"], TXID);
+    }
+
+    #[test]
+    fn test_reads_legacy_and_unknown_fields() {
+        // Version 0 payload: no `version`, no `runes`, plus a field from a
+        // hypothetical newer schema.
+        let json = format!(
+            r#"{{"txid":"{TXID}","vout":3,"value":"5000","needsConsolidation":{{"type":"no"}},"futureField":[1,2]}}"#
+        );
+        let info: UtxoInfo<SingleRuneSet> = serde_json::from_str(&json).unwrap();
+        assert_eq!(info.meta, utxo().meta);
+        assert_eq!(info.value, 5_000);
+    }
+
+    #[test]
+    fn test_compact_roundtrip() {
+        #[derive(Serialize, Deserialize)]
+        struct Wrapper(#[serde(with = "compact")] UtxoInfo<SingleRuneSet>);
+
+        let json = serde_json::to_string(&Wrapper(utxo())).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"encoding":"hex","version":{UTXO_INFO_JSON_VERSION},"outpoint":"{TXID}03000000","value":"5000"}}"#
+            )
+        );
+
+        let decoded: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.0.meta, utxo().meta);
+        assert_eq!(decoded.0.value, 5_000);
+    }
+
+    #[test]
+    fn test_reports_the_malformed_field() {
+        let json = format!(r#"{{"encoding":"hex","outpoint":"{TXID}","value":"5000"}}"#);
+        let err = serde_json::from_str::<UtxoInfo<SingleRuneSet>>(&json).unwrap_err();
+        assert!(err.to_string().contains("outpoint must be 36 bytes long"));
+
+        let json = format!(r#"{{"encoding":"hex","txid":"{TXID}","vout":3,"value":"5000"}}"#);
+        let err = serde_json::from_str::<UtxoInfo<SingleRuneSet>>(&json).unwrap_err();
+        assert!(err.to_string().contains("missing field `outpoint`"));
+    }
+}
@@ -42,6 +42,10 @@
 //! * the provided `offset` is not an unsigned integer literal, or
 //! * two variants end up with the same discriminant value.
 //!
+//! With the `idl-build` feature, the enum also gets a hidden
+//! `fn __idl_errors() -> Vec<String>` describing the code, name and message of
+//! every variant as JSON, for the IDL built by `#[saturn_program]`.
+//!
 //! ## Reserved ranges
 //!
 //! We recommend allocating each on-chain program (or crate) a unique offset
@@ -238,11 +242,17 @@ pub fn saturn_error(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
+    // `__idl_errors()` for the `#[saturn_program]` IDL, only with the
+    // `idl-build` feature.
+    let idl_impl = cfg!(feature = "idl-build").then(|| idl_errors_impl(&enum_item));
+
     let gen = quote! {
         #enum_item
 
         #from_primitive_impl
 
+        #idl_impl
+
         impl #decode_error_path<#enum_ident> for #enum_ident {
             fn type_of() -> &'static str {
                 stringify!(#enum_ident)
@@ -278,3 +288,54 @@ pub fn saturn_error(attr: TokenStream, item: TokenStream) -> TokenStream {
     };
     gen.into()
 }
+
+/// `impl Enum { pub fn __idl_errors() -> Vec<String> }`, returning one JSON
+/// `{ "code", "name", "msg" }` object per variant.
+fn idl_errors_impl(enum_item: &ItemEnum) -> proc_macro2::TokenStream {
+    let enum_ident = &enum_item.ident;
+    let entries = enum_item.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        // The literal of `#[error("..")]`, or the variant name when it
+        // formats its message from fields.
+        let msg = variant
+            .attrs
+            .iter()
+            .find(|a| a.path().is_ident("error"))
+            .and_then(|a| a.parse_args::<syn::LitStr>().ok())
+            .map_or_else(|| ident.to_string(), |lit| lit.value());
+        let fmt = format!(
+            "{{{{\"code\":{{}},\"name\":{},\"msg\":{}}}}}",
+            json_string(&ident.to_string()),
+            json_string(&msg)
+        );
+        quote! { format!(#fmt, #enum_ident::#ident as u32) }
+    });
+
+    quote! {
+        impl #enum_ident {
+            #[doc(hidden)]
+            pub fn __idl_errors() -> Vec<String> {
+                vec![#(#entries),*]
+            }
+        }
+    }
+}
+
+/// `value` as a JSON string literal, quotes included, with the braces
+/// doubled so that it can be embedded in a `format!` string.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '{' => out.push_str("{{"),
+            '}' => out.push_str("}}"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -28,6 +28,19 @@ pub enum SaturnErrorCode {
     /// The provided account did not match the expected program id.
     #[error("Invalid program id")]
     InvalidProgramId = 2,
+    /// An instruction handler panicked; its location is in the logs.
+    ///
+    /// Only returned where panics unwind (host builds). On-chain the
+    /// instruction aborts and this code is only logged.
+    #[error("Instruction handler panicked")]
+    HandlerPanicked = 3,
+    /// An account is not in a lifecycle state the instruction accepts.
+    #[error("Account is not in the required state")]
+    InvalidAccountState = 4,
+    /// An instruction nonce was already used or skips ahead; see
+    /// `#[nonce(..)]`.
+    #[error("Instruction nonce already used or out of order")]
+    InvalidNonce = 5,
     /// Generic error placeholder. Prefer adding concrete variants.
     #[error("Generic Saturn framework error")]
     GenericError = 999,
@@ -0,0 +1,240 @@
+//! `#[interface]`: a trait of instruction signatures shared between programs.
+//!
+//! ```ignore
+//! #[interface]
+//! pub trait Pool {
+//!     fn swap<'info>(ctx: Context<'info, Swap<'info>>, amount_in: u64, min_out: u64) -> ProgramResult;
+//! }
+//! ```
+//!
+//! expands to
+//! * the trait, with `Context` spelled out as in `#[saturn_program]` handlers.
+//!   The implementing program implements it on any type by delegating to its
+//!   handlers, so a handler drifting from the interface stops compiling.
+//! * `pool::cpi::swap(cpi_ctx, amount_in, min_out)`, calling any program
+//!   implementing the interface: the data is the handler's discriminator
+//!   followed by the Borsh arguments, exactly what its dispatcher decodes.
+//!   A handler pinned with `#[instruction(discriminant = N)]` needs the same
+//!   attribute on its interface method.
+
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Error, FnArg, GenericArgument, GenericParam, ItemTrait, Pat, PathArguments,
+    TraitItem, Type,
+};
+
+use crate::program::{instruction_discriminator, parse_instruction_attr};
+
+/// Entry point invoked by `lib.rs`.
+pub fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let result = if attr.is_empty() {
+        syn::parse2::<ItemTrait>(item).and_then(generate)
+    } else {
+        Err(Error::new_spanned(attr, "#[interface] takes no arguments"))
+    };
+    result.unwrap_or_else(|err| err.to_compile_error())
+}
+
+fn generate(mut item_trait: ItemTrait) -> Result<TokenStream, Error> {
+    let mut stubs: Vec<TokenStream> = Vec::new();
+
+    for item in item_trait.items.iter_mut() {
+        let TraitItem::Fn(method) = item else {
+            return Err(Error::new_spanned(
+                item,
+                "#[interface] traits may only declare instruction functions",
+            ));
+        };
+        if let Some(body) = &method.default {
+            return Err(Error::new_spanned(
+                body,
+                "interface instructions are declarations; remove the body",
+            ));
+        }
+        // `#[instruction(discriminant = N)]` mirrors the implementing handler.
+        let mut discriminant = None;
+        if let Some(index) = method
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("instruction"))
+        {
+            discriminant = Some(parse_instruction_attr(&method.attrs.remove(index))?);
+        }
+        let sig = &mut method.sig;
+
+        // First argument: `Context<'info, Accounts<'info>>`.
+        let acc_ty = match sig.inputs.first() {
+            Some(FnArg::Typed(pat_ty)) => context_accounts(&pat_ty.ty),
+            Some(FnArg::Receiver(receiver)) => {
+                return Err(Error::new_spanned(
+                    receiver,
+                    "interface instructions take no `self`",
+                ))
+            }
+            None => None,
+        }
+        .ok_or_else(|| {
+            Error::new_spanned(
+                &sig.inputs,
+                "the first argument must be Context<'info, Accounts<'info>>",
+            )
+        })?;
+
+        let mut arg_idents = Vec::new();
+        let mut arg_tys = Vec::new();
+        for arg in sig.inputs.iter().skip(1) {
+            let FnArg::Typed(pat_ty) = arg else {
+                unreachable!("receivers are only allowed first");
+            };
+            let Pat::Ident(pat_ident) = &*pat_ty.pat else {
+                return Err(Error::new_spanned(
+                    &pat_ty.pat,
+                    "parameter pattern must be an identifier",
+                ));
+            };
+            arg_idents.push(pat_ident.ident.clone());
+            arg_tys.push((*pat_ty.ty).clone());
+        }
+
+        // Spell out `Context` as the `#[saturn_program]` transform does.
+        if let Some(FnArg::Typed(pat_ty)) = sig.inputs.first_mut() {
+            pat_ty.ty = parse_quote! {
+                saturn_account_parser::Context<'_, '_, '_, 'info, #acc_ty>
+            };
+        }
+        let has_info = sig.generics.params.iter().any(
+            |param| matches!(param, GenericParam::Lifetime(lt) if lt.lifetime.ident == "info"),
+        );
+        if !has_info {
+            sig.generics.params.insert(0, parse_quote!('info));
+        }
+
+        let fn_ident = &sig.ident;
+        let discriminator = match discriminant {
+            Some(discriminant) => discriminant.to_le_bytes(),
+            None => instruction_discriminator(&fn_ident.to_string()),
+        };
+        let doc = format!("Calls `{fn_ident}` on the program of `ctx`.");
+        stubs.push(quote! {
+            #[doc = #doc]
+            pub fn #fn_ident<'a, 'info>(
+                ctx: saturn_account_parser::CpiContext<'a, 'info, #acc_ty>,
+                #( #arg_idents: #arg_tys ),*
+            ) -> arch_program::entrypoint::ProgramResult {
+                let mut data: Vec<u8> = vec![#( #discriminator ),*];
+                #(
+                    saturn_account_parser::__private::borsh::BorshSerialize::serialize(&#arg_idents, &mut data)
+                        .map_err(|e| arch_program::program_error::ProgramError::BorshIoError(e.to_string()))?;
+                )*
+                saturn_account_parser::cpi::invoke_signed(ctx, data)
+            }
+        });
+    }
+
+    let vis = &item_trait.vis;
+    let mod_ident = format_ident!(
+        "{}",
+        item_trait.ident.to_string().to_case(Case::Snake),
+        span = item_trait.ident.span()
+    );
+    let mod_doc = format!(
+        "Cross-program calls to programs implementing [`{}`].",
+        item_trait.ident
+    );
+
+    Ok(quote! {
+        #item_trait
+
+        #[doc = #mod_doc]
+        #vis mod #mod_ident {
+            use super::*;
+
+            pub mod cpi {
+                use super::*;
+                #( #stubs )*
+            }
+        }
+    })
+}
+
+/// The accounts type of `Context<.., Accounts>`, or `None` when `ty` is not
+/// a `Context`.
+fn context_accounts(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let seg = type_path.path.segments.last()?;
+    if seg.ident != "Context" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &seg.arguments else {
+        return None;
+    };
+    args.args.iter().rev().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty.clone()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_trait_and_cpi_stubs() {
+        let item: ItemTrait = parse_quote! {
+            pub trait Pool {
+                fn swap<'info>(ctx: Context<'info, Swap<'info>>, amount_in: u64, min_out: u64) -> ProgramResult;
+            }
+        };
+        let rendered = generate(item).expect("generate ok").to_string();
+
+        assert!(rendered.contains(
+            "fn swap < 'info > (ctx : saturn_account_parser :: Context < '_ , '_ , '_ , 'info , Swap < 'info >> , amount_in : u64 , min_out : u64)"
+        ));
+        assert!(rendered.contains("pub mod pool"));
+        assert!(rendered.contains(
+            "pub fn swap < 'a , 'info > (ctx : saturn_account_parser :: CpiContext < 'a , 'info , Swap < 'info >> , amount_in : u64 , min_out : u64)"
+        ));
+        let discriminator = instruction_discriminator("swap");
+        assert!(rendered.contains(&format!("vec ! [{}u8 ,", discriminator[0])));
+        assert!(rendered.contains("serialize (& min_out , & mut data)"));
+    }
+
+    #[test]
+    fn rejects_default_bodies() {
+        let item: ItemTrait = parse_quote! {
+            pub trait Pool {
+                fn swap<'info>(ctx: Context<'info, Swap<'info>>) -> ProgramResult { Ok(()) }
+            }
+        };
+        let err = generate(item).unwrap_err();
+        assert!(err.to_string().contains("remove the body"));
+    }
+
+    #[test]
+    fn requires_context_first() {
+        let item: ItemTrait = parse_quote! {
+            pub trait Pool {
+                fn swap(amount_in: u64) -> ProgramResult;
+            }
+        };
+        let err = generate(item).unwrap_err();
+        assert!(err.to_string().contains("first argument must be Context"));
+    }
+
+    #[test]
+    fn honours_pinned_discriminants() {
+        let item: ItemTrait = parse_quote! {
+            pub trait Pool {
+                #[instruction(discriminant = 7)]
+                fn swap<'info>(ctx: Context<'info, Swap<'info>>) -> ProgramResult;
+            }
+        };
+        let rendered = generate(item).expect("generate ok").to_string();
+        assert!(rendered.contains("vec ! [7u8 , 0u8 , 0u8 , 0u8 , 0u8 , 0u8 , 0u8 , 0u8]"));
+        assert!(!rendered.contains("# [instruction"));
+    }
+}
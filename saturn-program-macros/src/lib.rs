@@ -1,5 +1,6 @@
 extern crate proc_macro;
 
+mod interface;
 mod program;
 
 #[proc_macro_attribute]
@@ -10,7 +11,23 @@ pub fn saturn_program(
     program::expand(attr.into(), item.into()).into()
 }
 
+#[proc_macro_attribute]
+pub fn saturn_program_module(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    program::expand_module(attr.into(), item.into()).into()
+}
+
 #[proc_macro]
 pub fn declare_id(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     program::id::declare_id(item.into()).into()
 }
+
+#[proc_macro_attribute]
+pub fn interface(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    interface::expand(attr.into(), item.into()).into()
+}
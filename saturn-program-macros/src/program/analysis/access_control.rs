@@ -0,0 +1,96 @@
+use proc_macro2::TokenStream;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Error, Expr, Item, ItemFn, ItemMod, Stmt};
+
+use super::helpers::has_cfg_test;
+
+/// Expands `#[access_control(guard(&ctx), ..)]` on the functions of the module
+/// (nested modules included): the attribute is removed and each guard runs,
+/// in order, as `guard(&ctx)?;` before the original body.
+///
+/// Returns a list of compile-error token streams.
+pub fn apply_access_control(item_mod: &mut ItemMod) -> Vec<TokenStream> {
+    let mut errors = Vec::new();
+    if let Some((_brace, ref mut items)) = item_mod.content {
+        for item in items.iter_mut() {
+            match item {
+                Item::Fn(item_fn) if !has_cfg_test(&item_fn.attrs) => {
+                    if let Err(err) = expand_fn(item_fn) {
+                        errors.push(err.to_compile_error());
+                    }
+                }
+                Item::Mod(inner_mod) => errors.extend(apply_access_control(inner_mod)),
+                _ => {}
+            }
+        }
+    }
+    errors
+}
+
+fn expand_fn(item_fn: &mut ItemFn) -> Result<(), Error> {
+    let mut guards: Vec<Expr> = Vec::new();
+    let mut result = Ok(());
+    item_fn.attrs.retain(|attr| {
+        if !attr.path().is_ident("access_control") {
+            return true;
+        }
+        match attr.parse_args_with(Punctuated::<Expr, syn::Token![,]>::parse_terminated) {
+            Ok(list) if !list.is_empty() => guards.extend(list),
+            Ok(_) => {
+                result = Err(Error::new_spanned(
+                    attr,
+                    "access_control expects at least one guard, e.g. `#[access_control(is_admin(&ctx))]`",
+                ))
+            }
+            Err(err) => result = Err(err),
+        }
+        false
+    });
+    result?;
+
+    let checks: Vec<Stmt> = guards
+        .into_iter()
+        .map(|guard| parse_quote! { #guard?; })
+        .collect();
+    item_fn.block.stmts.splice(0..0, checks);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    #[test]
+    fn runs_guards_before_body() {
+        let mut item_mod: ItemMod = parse_quote! {
+            mod handlers {
+                #[access_control(is_admin(&ctx), not_paused(&ctx))]
+                pub fn set_fee(ctx: Context<Admin>, fee: u8) -> ProgramResult {
+                    body(fee)
+                }
+            }
+        };
+        assert!(apply_access_control(&mut item_mod).is_empty());
+        let rendered = item_mod.to_token_stream().to_string();
+        assert!(!rendered.contains("access_control"));
+        assert!(rendered.contains("{ is_admin (& ctx) ? ; not_paused (& ctx) ? ; body (fee) }"));
+    }
+
+    #[test]
+    fn rejects_empty_guard_list() {
+        let mut item_mod: ItemMod = parse_quote! {
+            mod handlers {
+                pub mod admin {
+                    #[access_control()]
+                    pub fn set_fee(ctx: Context<Admin>) -> ProgramResult {
+                        Ok(())
+                    }
+                }
+            }
+        };
+        let errors = apply_access_control(&mut item_mod);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("at least one guard"));
+    }
+}
@@ -1,6 +1,6 @@
 use convert_case::{Case, Casing};
 use proc_macro2::TokenStream;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use super::gather::FnInfo;
 use syn::Error;
@@ -24,6 +24,27 @@ pub fn check_duplicate_variants(fn_infos: &[FnInfo]) -> Vec<TokenStream> {
     errors
 }
 
+/// Detects handlers sharing an instruction discriminator, which can only
+/// happen through `#[instruction(discriminant = N)]`.
+pub fn check_duplicate_discriminators(fn_infos: &[FnInfo]) -> Vec<TokenStream> {
+    let mut errors = Vec::new();
+    let mut seen: HashMap<[u8; 8], &syn::Ident> = HashMap::new();
+
+    for info in fn_infos {
+        if let Some(other) = seen.insert(info.discriminator(), &info.fn_ident) {
+            errors.push(
+                Error::new_spanned(
+                    &info.fn_ident,
+                    format!("instruction discriminant already used by handler `{other}`"),
+                )
+                .to_compile_error(),
+            );
+        }
+    }
+
+    errors
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,6 +58,10 @@ mod tests {
             mod_path: Vec::new(),
             param_tys: Vec::new(),
             param_idents: Vec::new(),
+            since: None,
+            no_btc_tx: false,
+            utxos: None,
+            discriminant: None,
         }
     }
 
@@ -66,4 +91,14 @@ mod tests {
         // One error per duplicate occurrence beyond the first unique entry
         assert_eq!(errors.len(), 2);
     }
+
+    #[test]
+    fn detects_pinned_discriminant_collisions() {
+        let mut infos = vec![make_fn_info("deposit"), make_fn_info("withdraw")];
+        assert!(check_duplicate_discriminators(&infos).is_empty());
+
+        infos[0].discriminant = Some(7);
+        infos[1].discriminant = Some(7);
+        assert_eq!(check_duplicate_discriminators(&infos).len(), 1);
+    }
 }
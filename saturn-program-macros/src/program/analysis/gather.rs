@@ -23,21 +23,74 @@ pub struct FnInfo {
     /// Identifier names of the payload parameters, preserved so the dispatcher can
     /// generate struct variants with matching field names (Anchor-style).
     pub param_idents: Vec<syn::Ident>,
+    /// Wire version from `#[since(version = V, upgrade = f)]`, if any.
+    pub since: Option<Since>,
+    /// `#[no_btc_tx]`: the handler gets the plain `Context` without a
+    /// Bitcoin transaction builder, even when `btc_tx_cfg` is set.
+    pub no_btc_tx: bool,
+    /// `#[utxos(Parser)]`: index of the payload parameter typed `Parser`, and
+    /// that type. The instruction carries a `Vec<UtxoMeta>` in its place
+    /// (already substituted in `param_tys`), which the dispatcher parses with
+    /// `TryFromUtxos::try_utxos` before calling the handler (parsers taking
+    /// `#[utxo_params]` do not implement it and fail to compile).
+    pub utxos: Option<(usize, syn::Type)>,
+    /// `#[instruction(discriminant = N)]`: wire value pinned by the user in
+    /// place of the hash of the handler name.
+    pub discriminant: Option<u64>,
 }
 
-/// Traverses the items of the inline module, collecting [`FnInfo`] values and
-/// compile-error token streams.
+impl FnInfo {
+    /// The 8-byte instruction discriminator: `N` in little-endian with
+    /// `#[instruction(discriminant = N)]`, else the hash of the handler name.
+    pub fn discriminator(&self) -> [u8; 8] {
+        match self.discriminant {
+            Some(discriminant) => discriminant.to_le_bytes(),
+            None => crate::program::instruction_discriminator(&self.fn_ident.to_string()),
+        }
+    }
+}
+
+/// Instruction wire version introducing the current parameter layout of a
+/// handler, from `#[since(version = V)]`.
+#[derive(Clone)]
+pub struct Since {
+    pub version: u8,
+    /// `fn(version: u8, data: &[u8]) -> Result<(P1, ..), ProgramError>`
+    /// decoding the parameters of instructions older than `version`.
+    /// Without it such instructions are rejected. Defined outside the
+    /// `#[saturn_program]` module, and named from the handler's module or
+    /// with a `crate::` path.
+    pub upgrade: Option<Path>,
+    /// The attribute itself, spanning errors found after gathering.
+    pub attr: syn::Attribute,
+}
+
+/// The `#[fallback]` handler, called with the raw instruction data when no
+/// instruction discriminator matches.
+#[derive(Clone)]
+pub struct FallbackInfo {
+    pub fn_ident: syn::Ident,
+    /// Module path from the root of the `#[saturn_program]` module, as in [`FnInfo`].
+    pub mod_path: Vec<syn::Ident>,
+}
+
+/// Traverses the items of the inline module, collecting [`FnInfo`] values, the
+/// optional `#[fallback]` handler and compile-error token streams.
 ///
-/// Returns `(fn_infos, errors)`.
-pub fn gather_fn_infos(item_mod: &ItemMod) -> (Vec<FnInfo>, Vec<TokenStream>) {
+/// Returns `(fn_infos, fallback, errors)`.
+pub fn gather_fn_infos(
+    item_mod: &ItemMod,
+) -> (Vec<FnInfo>, Option<FallbackInfo>, Vec<TokenStream>) {
     let mut errors = Vec::<TokenStream>::new();
     let mut fn_infos = Vec::<FnInfo>::new();
+    let mut fallback = None::<FallbackInfo>;
 
     // Recursively walk inline modules to capture functions and their module path.
     fn walk_mod(
         current_mod: &ItemMod,
         mod_path: &mut Vec<syn::Ident>,
         fn_infos: &mut Vec<FnInfo>,
+        fallback: &mut Option<FallbackInfo>,
         errors: &mut Vec<TokenStream>,
     ) {
         // Safe unwrap: we only call with inline modules
@@ -68,6 +121,47 @@ pub fn gather_fn_infos(item_mod: &ItemMod) -> (Vec<FnInfo>, Vec<TokenStream>) {
                         );
                     }
 
+                    // -------------------------------------------------
+                    // `#[fallback]` handler – takes the raw entrypoint
+                    // arguments instead of a Context, so it skips the
+                    // instruction checks below.
+                    // -------------------------------------------------
+                    if let Some(attr) = attrs.iter().find(|a| a.path().is_ident("fallback")) {
+                        if sig.inputs.len() != 3 {
+                            errors.push(
+                                Error::new_spanned(
+                                    &sig.inputs,
+                                    "fallback handler must take (program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], instruction_data: &[u8])",
+                                )
+                                .to_compile_error(),
+                            );
+                        }
+                        if !is_valid_return_ty(&sig.output) {
+                            errors.push(
+                                Error::new_spanned(
+                                    &sig.output,
+                                    "handler must return ProgramResult or Result<(), ProgramError>",
+                                )
+                                .to_compile_error(),
+                            );
+                        }
+                        if fallback.is_some() {
+                            errors.push(
+                                Error::new_spanned(
+                                    attr,
+                                    "only one #[fallback] handler is allowed per program",
+                                )
+                                .to_compile_error(),
+                            );
+                        } else {
+                            *fallback = Some(FallbackInfo {
+                                fn_ident: sig.ident.clone(),
+                                mod_path: mod_path.clone(),
+                            });
+                        }
+                        continue;
+                    }
+
                     // -------------------------------------------------
                     // Generic parameter check – Anchor forbids generics on
                     // instruction handlers to keep dispatch logic simple and
@@ -310,6 +404,43 @@ pub fn gather_fn_infos(item_mod: &ItemMod) -> (Vec<FnInfo>, Vec<TokenStream>) {
                         );
                     }
 
+                    // `#[utxos(Parser)]`: the parameter typed `Parser` travels
+                    // as the `Vec<UtxoMeta>` it is parsed from.
+                    let mut utxos = None;
+                    if let Some(attr) = attrs.iter().find(|a| a.path().is_ident("utxos")) {
+                        match find_utxos_param(attr, &param_tys) {
+                            Ok((index, parser_ty)) => {
+                                param_tys[index] =
+                                    syn::parse_quote!(Vec<arch_program::utxo::UtxoMeta>);
+                                utxos = Some((index, parser_ty));
+                            }
+                            Err(err) => errors.push(err.to_compile_error()),
+                        }
+                    }
+
+                    let since = match attrs.iter().find(|a| a.path().is_ident("since")) {
+                        Some(attr) => match parse_since(attr) {
+                            Ok(since) => Some(since),
+                            Err(err) => {
+                                errors.push(err.to_compile_error());
+                                None
+                            }
+                        },
+                        None => None,
+                    };
+
+                    let discriminant = match attrs.iter().find(|a| a.path().is_ident("instruction"))
+                    {
+                        Some(attr) => match parse_instruction_attr(attr) {
+                            Ok(discriminant) => Some(discriminant),
+                            Err(err) => {
+                                errors.push(err.to_compile_error());
+                                None
+                            }
+                        },
+                        None => None,
+                    };
+
                     if let Some(acc_ty) = acc_ty_path_opt {
                         fn_infos.push(FnInfo {
                             fn_ident: sig.ident.clone(),
@@ -317,6 +448,10 @@ pub fn gather_fn_infos(item_mod: &ItemMod) -> (Vec<FnInfo>, Vec<TokenStream>) {
                             mod_path: mod_path.clone(),
                             param_tys,
                             param_idents,
+                            since,
+                            no_btc_tx: attrs.iter().any(|a| a.path().is_ident("no_btc_tx")),
+                            utxos,
+                            discriminant,
                         });
                     }
                 }
@@ -324,7 +459,7 @@ pub fn gather_fn_infos(item_mod: &ItemMod) -> (Vec<FnInfo>, Vec<TokenStream>) {
                     // Only recurse into inline sub-modules
                     if inner_mod.content.is_some() {
                         mod_path.push(inner_mod.ident.clone());
-                        walk_mod(inner_mod, mod_path, fn_infos, errors);
+                        walk_mod(inner_mod, mod_path, fn_infos, fallback, errors);
                         mod_path.pop();
                     }
                 }
@@ -334,7 +469,86 @@ pub fn gather_fn_infos(item_mod: &ItemMod) -> (Vec<FnInfo>, Vec<TokenStream>) {
     }
 
     let mut path = Vec::<syn::Ident>::new();
-    walk_mod(item_mod, &mut path, &mut fn_infos, &mut errors);
+    walk_mod(
+        item_mod,
+        &mut path,
+        &mut fn_infos,
+        &mut fallback,
+        &mut errors,
+    );
+
+    (fn_infos, fallback, errors)
+}
+
+/// Parses `#[since(version = V)]` or `#[since(version = V, upgrade = path)]`.
+fn parse_since(attr: &syn::Attribute) -> Result<Since, Error> {
+    let mut version = None;
+    let mut upgrade = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("version") {
+            let value: syn::Expr = meta.value()?.parse()?;
+            version = Some(crate::program::attr::parse_version(&value)?);
+        } else if meta.path.is_ident("upgrade") {
+            let path: Path = meta.value()?.parse()?;
+            if path.leading_colon.is_none()
+                && matches!(path.segments.first(), Some(seg) if seg.ident == "self" || seg.ident == "super")
+            {
+                return Err(meta.error(
+                    "upgrade must name a function of the handler's module or a `crate::` path",
+                ));
+            }
+            upgrade = Some(path);
+        } else {
+            return Err(meta.error("unknown key inside since; expected `version` or `upgrade`"));
+        }
+        Ok(())
+    })?;
+    let version = version.ok_or_else(|| {
+        Error::new_spanned(
+            attr,
+            "since must specify `version`, e.g. `#[since(version = 2)]`",
+        )
+    })?;
+    Ok(Since {
+        version,
+        upgrade,
+        attr: attr.clone(),
+    })
+}
+
+/// Parses `#[instruction(discriminant = N)]`, returning `N`.
+pub fn parse_instruction_attr(attr: &syn::Attribute) -> Result<u64, Error> {
+    let mut discriminant = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("discriminant") {
+            let value: syn::LitInt = meta.value()?.parse()?;
+            discriminant = Some(value.base10_parse::<u64>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unknown key inside instruction; expected `discriminant`"))
+        }
+    })?;
+    discriminant.ok_or_else(|| {
+        Error::new_spanned(
+            attr,
+            "instruction must specify `discriminant`, e.g. `#[instruction(discriminant = 7)]`",
+        )
+    })
+}
 
-    (fn_infos, errors)
+/// Index and type of the parameter named by `#[utxos(Parser)]`, the one whose
+/// type is `Parser`.
+fn find_utxos_param(attr: &syn::Attribute, param_tys: &[Type]) -> Result<(usize, Type), Error> {
+    let parser_ty: Type = attr.parse_args()?;
+    let wanted = quote::ToTokens::to_token_stream(&parser_ty).to_string();
+    param_tys
+        .iter()
+        .position(|ty| quote::ToTokens::to_token_stream(ty).to_string() == wanted)
+        .map(|index| (index, parser_ty.clone()))
+        .ok_or_else(|| {
+            Error::new_spanned(
+                attr,
+                format!("#[utxos] needs a handler parameter of type `{wanted}` to receive the parsed UTXOs"),
+            )
+        })
 }
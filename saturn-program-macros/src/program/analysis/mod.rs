@@ -4,19 +4,24 @@ use syn::ItemMod;
 
 use crate::program::attr::AttrConfig;
 
+mod access_control;
 mod dup_check;
 mod gather;
 mod helpers;
+mod nonce;
 mod parse;
+mod require_state;
 mod transform;
 
 // Re-export so upstream modules remain unchanged
-pub use gather::FnInfo;
+pub use gather::{parse_instruction_attr, FallbackInfo, FnInfo, Since};
 
 /// Result of analyzing the `#[saturn_program]`-annotated module.
 pub struct AnalysisResult {
     pub item_mod: ItemMod,
     pub fn_infos: Vec<FnInfo>,
+    /// Handler for instruction data matching no instruction, if any.
+    pub fallback: Option<FallbackInfo>,
 }
 
 /// Performs analysis and injects helper type aliases into the module depending on the attribute configuration.
@@ -33,12 +38,13 @@ pub fn analyze(attr_cfg: &AttrConfig, item: TokenStream) -> Result<AnalysisResul
     // ------------------------------------------------------------
     // 2. Gather function information and perform basic checks
     // ------------------------------------------------------------
-    let (fn_infos, mut errors) = gather::gather_fn_infos(&item_mod);
+    let (fn_infos, fallback, mut errors) = gather::gather_fn_infos(&item_mod);
 
     // ------------------------------------------------------------
     // 3. Detect duplicate variant names after convert_case transformation
     // ------------------------------------------------------------
     errors.extend(dup_check::check_duplicate_variants(&fn_infos));
+    errors.extend(dup_check::check_duplicate_discriminators(&fn_infos));
 
     // ------------------------------------------------------------
     // 3b. Detect conflicting RuneSet definition when the macro would
@@ -64,18 +70,59 @@ remove it or use `rune_set = \"...\"` inside btc_tx_cfg to supply a custom rune
         }
     }
 
+    // ------------------------------------------------------------
+    // 3c. `#[since(..)]` handlers need a program `version` covering them
+    // ------------------------------------------------------------
+    for since in fn_infos.iter().filter_map(|info| info.since.as_ref()) {
+        let message = match attr_cfg.version {
+            None => Some(
+                "#[since] requires a program wire version, e.g. `#[saturn_program(version = 2)]`"
+                    .to_string(),
+            ),
+            Some(version) if since.version > version => Some(format!(
+                "#[since] version {} is newer than the program version {version}",
+                since.version
+            )),
+            Some(_) => None,
+        };
+        if let Some(message) = message {
+            errors.push(syn::Error::new_spanned(&since.attr, message).to_compile_error());
+        }
+    }
+
+    // ------------------------------------------------------------
+    // 3d. Expand `#[access_control(..)]` guards into the handler bodies
+    // ------------------------------------------------------------
+    errors.extend(access_control::apply_access_control(&mut item_mod));
+
+    // ------------------------------------------------------------
+    // 3e. Expand `#[nonce(..)]` replay guards ahead of the guards
+    // ------------------------------------------------------------
+    errors.extend(nonce::apply_nonce(&mut item_mod));
+
+    // ------------------------------------------------------------
+    // 3f. Expand `#[require_state(..)]` checks ahead of both
+    // ------------------------------------------------------------
+    errors.extend(require_state::apply_require_state(&mut item_mod));
+
     // ------------------------------------------------------------
     // 4. Rewrite handler parameter types so users can keep concise `Context` without injected aliases
     // ------------------------------------------------------------
     transform::rewrite_context_params(&mut item_mod, &fn_infos, attr_cfg);
 
+    // `#[fallback]`, `#[since]`, `#[no_btc_tx]`, `#[utxos]` and
+    // `#[instruction]` are only markers for this macro; drop them so rustc
+    // does not see unknown attributes.
+    transform::strip_marker_attrs(&mut item_mod);
+
     // If we encountered errors, return them now so the caller can embed them.
     if !errors.is_empty() {
         let combined = quote! { #item_mod #( #errors )* };
         return Err(combined);
     }
 
-    if fn_infos.is_empty() {
+    // A program composed of `modules = [..]` may have no handlers of its own.
+    if fn_infos.is_empty() && attr_cfg.modules.is_empty() {
         let ts = quote! {
             #item_mod
             compile_error!("#[saturn_program] module must define at least one non-#[cfg(test)] instruction handler");
@@ -83,5 +130,9 @@ remove it or use `rune_set = \"...\"` inside btc_tx_cfg to supply a custom rune
         return Err(ts);
     }
 
-    Ok(AnalysisResult { item_mod, fn_infos })
+    Ok(AnalysisResult {
+        item_mod,
+        fn_infos,
+        fallback,
+    })
 }
@@ -0,0 +1,216 @@
+use proc_macro2::TokenStream;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Error, FnArg, Ident, Item, ItemFn, ItemMod, Pat, Stmt, Token};
+
+use super::helpers::has_cfg_test;
+
+/// Expands `#[nonce(account = user_state, field = last_nonce)]` on the
+/// functions of the module (nested modules included): the attribute is
+/// removed and a `saturn_account_parser::nonce::check_and_bump` call, taking
+/// the handler's `nonce` parameter (or the one named by `arg = ..`), runs
+/// before the original body.
+///
+/// Returns a list of compile-error token streams.
+pub fn apply_nonce(item_mod: &mut ItemMod) -> Vec<TokenStream> {
+    let mut errors = Vec::new();
+    if let Some((_brace, ref mut items)) = item_mod.content {
+        for item in items.iter_mut() {
+            match item {
+                Item::Fn(item_fn) if !has_cfg_test(&item_fn.attrs) => {
+                    if let Err(err) = expand_fn(item_fn) {
+                        errors.push(err.to_compile_error());
+                    }
+                }
+                Item::Mod(inner_mod) => errors.extend(apply_nonce(inner_mod)),
+                _ => {}
+            }
+        }
+    }
+    errors
+}
+
+/// Parsed `#[nonce(account = .., field = .., arg = ..)]`.
+struct NonceCfg {
+    /// Field path of the account in the `Accounts` struct, e.g. `user_state`.
+    account: Vec<Ident>,
+    /// Field of the account data holding the last nonce.
+    field: Ident,
+    /// Handler parameter carrying the instruction nonce.
+    arg: Ident,
+}
+
+fn parse_nonce_attr(attr: &syn::Attribute) -> Result<NonceCfg, Error> {
+    let mut account = None;
+    let mut field = None;
+    let mut arg = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("account") {
+            let path = meta
+                .value()?
+                .call(Punctuated::<Ident, Token![.]>::parse_separated_nonempty)?;
+            account = Some(path.into_iter().collect());
+        } else if meta.path.is_ident("field") {
+            field = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("arg") {
+            arg = Some(meta.value()?.parse()?);
+        } else {
+            return Err(
+                meta.error("unknown key inside nonce; expected `account`, `field` or `arg`")
+            );
+        }
+        Ok(())
+    })?;
+    let (Some(account), Some(field)) = (account, field) else {
+        return Err(Error::new_spanned(
+            attr,
+            "nonce must specify `account` and `field`, e.g. `#[nonce(account = user_state, field = last_nonce)]`",
+        ));
+    };
+    Ok(NonceCfg {
+        account,
+        field,
+        arg: arg.unwrap_or_else(|| Ident::new("nonce", proc_macro2::Span::call_site())),
+    })
+}
+
+fn expand_fn(item_fn: &mut ItemFn) -> Result<(), Error> {
+    let mut cfgs: Vec<NonceCfg> = Vec::new();
+    let mut result = Ok(());
+    item_fn.attrs.retain(|attr| {
+        if !attr.path().is_ident("nonce") {
+            return true;
+        }
+        match parse_nonce_attr(attr) {
+            Ok(cfg) => cfgs.push(cfg),
+            Err(err) => result = Err(err),
+        }
+        false
+    });
+    result?;
+    if cfgs.is_empty() {
+        return Ok(());
+    }
+
+    let param_ident = |arg: &FnArg| match arg {
+        FnArg::Typed(pat_ty) => match &*pat_ty.pat {
+            Pat::Ident(pat_ident) => Some(pat_ident.ident.clone()),
+            _ => None,
+        },
+        FnArg::Receiver(_) => None,
+    };
+
+    // The nonce is bumped through the context, whose binding must be `mut`.
+    let ctx = match item_fn.sig.inputs.first_mut() {
+        Some(FnArg::Typed(pat_ty)) => match &mut *pat_ty.pat {
+            Pat::Ident(pat_ident) => {
+                pat_ident.mutability.get_or_insert_with(Default::default);
+                pat_ident.ident.clone()
+            }
+            pat => {
+                return Err(Error::new_spanned(
+                    pat,
+                    "nonce needs the context parameter to be a plain identifier",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &item_fn.sig,
+                "nonce needs a handler taking a context parameter",
+            ))
+        }
+    };
+
+    let mut stmts: Vec<Stmt> = Vec::new();
+    for NonceCfg {
+        account,
+        field,
+        arg,
+    } in cfgs
+    {
+        if !item_fn
+            .sig
+            .inputs
+            .iter()
+            .skip(1)
+            .any(|input| param_ident(input).as_ref() == Some(&arg))
+        {
+            return Err(Error::new_spanned(
+                &item_fn.sig,
+                format!("nonce needs a handler parameter `{arg}` carrying the instruction nonce"),
+            ));
+        }
+        let name = account
+            .iter()
+            .map(Ident::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+        stmts.push(parse_quote! {
+            saturn_account_parser::nonce::check_and_bump(
+                &mut #ctx.accounts #( .#account )*,
+                #name,
+                #arg,
+                |data| &mut data.#field,
+            )?;
+        });
+    }
+    item_fn.block.stmts.splice(0..0, stmts);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    #[test]
+    fn bumps_nonce_before_body() {
+        let mut item_mod: ItemMod = parse_quote! {
+            mod handlers {
+                #[nonce(account = user_state, field = last_nonce)]
+                pub fn withdraw(ctx: Context<Withdraw>, nonce: u64, amount: u64) -> ProgramResult {
+                    body(amount)
+                }
+            }
+        };
+        assert!(apply_nonce(&mut item_mod).is_empty());
+        let rendered = item_mod.to_token_stream().to_string();
+        assert!(!rendered.contains("# [nonce"));
+        assert!(rendered.contains("fn withdraw (mut ctx : Context < Withdraw >"));
+        assert!(rendered.contains(
+            "check_and_bump (& mut ctx . accounts . user_state , \"user_state\" , nonce , | data | & mut data . last_nonce ,) ? ; body (amount)"
+        ));
+    }
+
+    #[test]
+    fn takes_nonce_from_named_arg() {
+        let mut item_mod: ItemMod = parse_quote! {
+            mod handlers {
+                #[nonce(account = pool.user, field = seq, arg = sequence)]
+                pub fn withdraw(mut ctx: Context<Withdraw>, sequence: u64) -> ProgramResult {
+                    Ok(())
+                }
+            }
+        };
+        assert!(apply_nonce(&mut item_mod).is_empty());
+        let rendered = item_mod.to_token_stream().to_string();
+        assert!(rendered.contains(
+            "check_and_bump (& mut ctx . accounts . pool . user , \"pool.user\" , sequence , | data | & mut data . seq ,)"
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_nonce_param() {
+        let mut item_mod: ItemMod = parse_quote! {
+            mod handlers {
+                #[nonce(account = user_state, field = last_nonce)]
+                pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> ProgramResult {
+                    Ok(())
+                }
+            }
+        };
+        let errors = apply_nonce(&mut item_mod);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("handler parameter `nonce`"));
+    }
+}
@@ -0,0 +1,177 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Error, FnArg, Ident, Item, ItemFn, ItemMod, Pat, Path, Stmt, Token};
+
+use super::helpers::has_cfg_test;
+
+/// Expands `#[require_state(config => Initialized, ..)]` on the functions of
+/// the module (nested modules included): the attribute is removed and each
+/// check runs, in order, as a `saturn_account_parser::state::require_state`
+/// call before the original body.
+///
+/// Returns a list of compile-error token streams.
+pub fn apply_require_state(item_mod: &mut ItemMod) -> Vec<TokenStream> {
+    let mut errors = Vec::new();
+    if let Some((_brace, ref mut items)) = item_mod.content {
+        for item in items.iter_mut() {
+            match item {
+                Item::Fn(item_fn) if !has_cfg_test(&item_fn.attrs) => {
+                    if let Err(err) = expand_fn(item_fn) {
+                        errors.push(err.to_compile_error());
+                    }
+                }
+                Item::Mod(inner_mod) => errors.extend(apply_require_state(inner_mod)),
+                _ => {}
+            }
+        }
+    }
+    errors
+}
+
+/// One `account.field => State | State` check.
+struct StateCheck {
+    /// Field path of the account in the `Accounts` struct, e.g. `pool.vault`.
+    account: Vec<Ident>,
+    /// Field of the account data holding the state, `state` unless named.
+    field: Ident,
+    states: Punctuated<Path, Token![|]>,
+}
+
+impl Parse for StateCheck {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut account = vec![input.parse::<Ident>()?];
+        while input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            account.push(input.parse()?);
+        }
+        // `config` reads `config.state`; `config.status` reads `status`.
+        let field = if account.len() > 1 {
+            account.pop().expect("at least two segments")
+        } else {
+            Ident::new("state", account[0].span())
+        };
+        input.parse::<Token![=>]>()?;
+        let states = Punctuated::parse_separated_nonempty(input)?;
+        Ok(Self {
+            account,
+            field,
+            states,
+        })
+    }
+}
+
+fn expand_fn(item_fn: &mut ItemFn) -> Result<(), Error> {
+    let mut checks: Vec<StateCheck> = Vec::new();
+    let mut result = Ok(());
+    item_fn.attrs.retain(|attr| {
+        if !attr.path().is_ident("require_state") {
+            return true;
+        }
+        match attr.parse_args_with(Punctuated::<StateCheck, Token![,]>::parse_terminated) {
+            Ok(list) if !list.is_empty() => checks.extend(list),
+            Ok(_) => {
+                result = Err(Error::new_spanned(
+                    attr,
+                    "require_state expects at least one check, e.g. `#[require_state(config => Initialized)]`",
+                ))
+            }
+            Err(err) => result = Err(err),
+        }
+        false
+    });
+    result?;
+    if checks.is_empty() {
+        return Ok(());
+    }
+
+    // The checks read the accounts through the handler's context parameter.
+    let ctx = match item_fn.sig.inputs.first() {
+        Some(FnArg::Typed(pat_ty)) => match &*pat_ty.pat {
+            Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+            pat => {
+                return Err(Error::new_spanned(
+                    pat,
+                    "require_state needs the context parameter to be a plain identifier",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &item_fn.sig,
+                "require_state needs a handler taking a context parameter",
+            ))
+        }
+    };
+
+    let stmts: Vec<Stmt> = checks
+        .into_iter()
+        .map(|check| {
+            let StateCheck {
+                account,
+                field,
+                states,
+            } = check;
+            let name = account
+                .iter()
+                .map(Ident::to_string)
+                .collect::<Vec<_>>()
+                .join(".");
+            let states = states.iter();
+            let allowed = quote! { #( data.#field == #states )||* };
+            parse_quote! {
+                saturn_account_parser::state::require_state(
+                    &#ctx.accounts #( .#account )*,
+                    #name,
+                    |data| #allowed,
+                )?;
+            }
+        })
+        .collect();
+    item_fn.block.stmts.splice(0..0, stmts);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    #[test]
+    fn checks_states_before_body() {
+        let mut item_mod: ItemMod = parse_quote! {
+            mod handlers {
+                #[require_state(config => Initialized, pool.vault.status => Open | Draining)]
+                pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> ProgramResult {
+                    body(amount)
+                }
+            }
+        };
+        assert!(apply_require_state(&mut item_mod).is_empty());
+        let rendered = item_mod.to_token_stream().to_string();
+        assert!(!rendered.contains("# [require_state"));
+        assert!(rendered.contains(
+            "require_state (& ctx . accounts . config , \"config\" , | data | data . state == Initialized ,) ?"
+        ));
+        assert!(rendered.contains(
+            "require_state (& ctx . accounts . pool . vault , \"pool.vault\" , | data | data . status == Open || data . status == Draining ,) ?"
+        ));
+        assert!(rendered.ends_with("? ; body (amount) } }"));
+    }
+
+    #[test]
+    fn rejects_missing_states() {
+        let mut item_mod: ItemMod = parse_quote! {
+            mod handlers {
+                #[require_state(config)]
+                pub fn withdraw(ctx: Context<Withdraw>) -> ProgramResult {
+                    Ok(())
+                }
+            }
+        };
+        let errors = apply_require_state(&mut item_mod);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("expected `=>`"));
+    }
+}
@@ -32,12 +32,13 @@ pub fn rewrite_context_params(item_mod: &mut ItemMod, fn_infos: &[FnInfo], attr_
                         let maybe_info = fn_infos.iter().find(|info| {
                             info.fn_ident == fn_item.sig.ident && info.mod_path == *mod_path
                         });
-                        let acc_ty_path = match maybe_info {
-                            Some(info) => &info.acc_ty,
+                        let info = match maybe_info {
+                            Some(info) => info,
                             None => continue,
                         };
+                        let acc_ty_path = &info.acc_ty;
 
-                        let new_param_ty: Type = if attr_cfg.enable_bitcoin_tx {
+                        let new_param_ty: Type = if attr_cfg.enable_bitcoin_tx && !info.no_btc_tx {
                             let max_inputs = LitInt::new(
                                 &attr_cfg.btc_tx_cfg.max_inputs_to_sign.unwrap().to_string(),
                                 Span::call_site(),
@@ -56,7 +57,7 @@ pub fn rewrite_context_params(item_mod: &mut ItemMod, fn_infos: &[FnInfo], attr_
                                 saturn_account_parser::Context<
                                     '_, '_, '_, 'info,
                                     #acc_ty_path,
-                                    saturn_account_parser::TxBuilderWrapper<'info, #max_modified, #max_inputs, #rune_set_path>
+                                    &'_ mut saturn_account_parser::TxBuilderWrapper<'info, #max_modified, #max_inputs, #rune_set_path>
                                 >
                             }
                         } else {
@@ -136,3 +137,22 @@ pub fn rewrite_context_params(item_mod: &mut ItemMod, fn_infos: &[FnInfo], attr_
     let mut path = Vec::<syn::Ident>::new();
     rewrite_in_mod(item_mod, &mut path, fn_infos, attr_cfg);
 }
+
+/// Removes the `#[fallback]`, `#[since]`, `#[no_btc_tx]`, `#[utxos]` and
+/// `#[instruction]` markers from every function of the module, nested modules
+/// included.
+pub fn strip_marker_attrs(item_mod: &mut ItemMod) {
+    if let Some((_brace, ref mut items)) = item_mod.content {
+        for item in items.iter_mut() {
+            match item {
+                Item::Fn(fn_item) => fn_item.attrs.retain(|a| {
+                    !["fallback", "since", "no_btc_tx", "utxos", "instruction"]
+                        .iter()
+                        .any(|marker| a.path().is_ident(marker))
+                }),
+                Item::Mod(inner_mod) => strip_marker_attrs(inner_mod),
+                _ => {}
+            }
+        }
+    }
+}
@@ -19,6 +19,15 @@ pub struct BtcTxCfg {
     pub rune_capacity: Option<usize>,
 }
 
+/// Items listed in the IDL besides the instructions, from the `idl(..)` section.
+#[derive(Default, Clone)]
+pub struct IdlCfg {
+    /// `#[saturn_error]` enums whose variants are listed as errors.
+    pub errors: Vec<Path>,
+    /// `UtxoParser` structs whose `LAYOUT` is listed.
+    pub utxos: Vec<Path>,
+}
+
 /// Result of parsing the procedural macro attribute.
 #[derive(Clone)]
 pub struct AttrConfig {
@@ -26,6 +35,16 @@ pub struct AttrConfig {
     /// This is enabled when a `btc_tx_cfg(..)` section is present in the attribute list.
     pub enable_bitcoin_tx: bool,
     pub btc_tx_cfg: BtcTxCfg,
+    pub idl_cfg: IdlCfg,
+    /// Current instruction wire version from `version = N`. When set, every
+    /// instruction starts with a version byte ahead of its discriminator.
+    pub version: Option<u8>,
+    /// `profile`: log the compute units spent by each handler and by the
+    /// generated phases around it.
+    pub profile: bool,
+    /// `#[saturn_program_module]` modules from `modules = [..]` whose
+    /// instructions this program dispatches next to its own.
+    pub modules: Vec<Path>,
 }
 
 /// Parse the attribute list provided to `#[saturn_program(..)]`.
@@ -45,9 +64,14 @@ pub fn parse(attr: TokenStream) -> Result<AttrConfig, Error> {
     };
 
     let mut btc_tx_cfg: BtcTxCfg = BtcTxCfg::default();
+    let mut idl_cfg: IdlCfg = IdlCfg::default();
+    let mut version: Option<u8> = None;
+    let mut profile = false;
+    let mut modules: Vec<Path> = Vec::new();
 
     // Flags used during the second pass
     let mut btc_tx_cfg_seen = false;
+    let mut idl_cfg_seen = false;
 
     // ------------------------------------------------------------
     // 2. Handle each top-level attribute key/section
@@ -217,10 +241,78 @@ pub fn parse(attr: TokenStream) -> Result<AttrConfig, Error> {
                 }
             }
 
+            // ---------------------------
+            // idl( errors = [..], utxos = [..] )
+            // ---------------------------
+            Meta::List(ml) if ml.path.is_ident("idl") => {
+                if idl_cfg_seen {
+                    return Err(Error::new_spanned(&ml.path, "duplicate `idl` section"));
+                }
+                idl_cfg_seen = true;
+
+                let inner_parser = Punctuated::<Meta, syn::Token![,]>::parse_terminated;
+                let inner_meta = inner_parser.parse2(ml.tokens.clone())?;
+                for nested in inner_meta {
+                    let unknown = || {
+                        Error::new_spanned(
+                            &nested,
+                            "unknown key inside idl; expected `errors` or `utxos`",
+                        )
+                    };
+                    let Meta::NameValue(nv) = &nested else {
+                        return Err(unknown());
+                    };
+                    let (key, target) = if nv.path.is_ident("errors") {
+                        ("errors", &mut idl_cfg.errors)
+                    } else if nv.path.is_ident("utxos") {
+                        ("utxos", &mut idl_cfg.utxos)
+                    } else {
+                        return Err(unknown());
+                    };
+                    if !target.is_empty() {
+                        return Err(Error::new_spanned(
+                            &nv.path,
+                            format!("duplicate `{key}` key inside idl"),
+                        ));
+                    }
+                    *target = parse_path_list(&nv.value, key)?;
+                }
+            }
+
+            // ---------------------------
+            // version = N
+            // ---------------------------
+            Meta::NameValue(nv) if nv.path.is_ident("version") => {
+                if version.is_some() {
+                    return Err(Error::new_spanned(&nv.path, "duplicate `version` key"));
+                }
+                version = Some(parse_version(&nv.value)?);
+            }
+
+            // ---------------------------
+            // profile
+            // ---------------------------
+            Meta::Path(path) if path.is_ident("profile") => {
+                if profile {
+                    return Err(Error::new_spanned(path, "duplicate `profile` key"));
+                }
+                profile = true;
+            }
+
+            // ---------------------------
+            // modules = [path, ..]
+            // ---------------------------
+            Meta::NameValue(nv) if nv.path.is_ident("modules") => {
+                if !modules.is_empty() {
+                    return Err(Error::new_spanned(&nv.path, "duplicate `modules` key"));
+                }
+                modules = parse_path_list(&nv.value, "modules")?;
+            }
+
             other => {
                 return Err(Error::new_spanned(
                     other,
-                    "unknown attribute key; expected `btc_tx_cfg`",
+                    "unknown attribute key; expected `btc_tx_cfg`, `idl`, `modules`, `profile` or `version`",
                 ));
             }
         }
@@ -278,9 +370,45 @@ pub fn parse(attr: TokenStream) -> Result<AttrConfig, Error> {
     Ok(AttrConfig {
         enable_bitcoin_tx,
         btc_tx_cfg,
+        idl_cfg,
+        version,
+        profile,
+        modules,
     })
 }
 
+/// Parses an instruction wire version: an integer literal from 1 to 255.
+pub fn parse_version(value: &syn::Expr) -> Result<u8, Error> {
+    let message = "version must be an integer literal from 1 to 255";
+    let syn::Expr::Lit(syn::ExprLit {
+        lit: Lit::Int(int_lit),
+        ..
+    }) = value
+    else {
+        return Err(Error::new_spanned(value, message));
+    };
+    match int_lit.base10_parse::<u8>() {
+        Ok(version) if version > 0 => Ok(version),
+        _ => Err(Error::new_spanned(value, message)),
+    }
+}
+
+/// Parses `[path, ..]`, the value of the `key` list of the `idl` section.
+fn parse_path_list(value: &syn::Expr, key: &str) -> Result<Vec<Path>, Error> {
+    let message = format!("`{key}` must be a list of type paths, e.g. `[MyError]`");
+    let syn::Expr::Array(array) = value else {
+        return Err(Error::new_spanned(value, message));
+    };
+    array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            syn::Expr::Path(expr_path) => Ok(expr_path.path.clone()),
+            other => Err(Error::new_spanned(other, &message)),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,4 +427,58 @@ mod tests {
         assert_eq!(cfg.btc_tx_cfg.max_modified_accounts, Some(16));
         assert_eq!(cfg.btc_tx_cfg.rune_capacity, Some(3));
     }
+
+    #[test]
+    fn parses_idl_section() {
+        let ts: proc_macro2::TokenStream = quote!(idl(
+            errors = [MyError, crate::errors::Other],
+            utxos = [SwapUtxos]
+        ));
+        let cfg = parse(ts).expect("should parse");
+        assert!(!cfg.enable_bitcoin_tx);
+        assert_eq!(cfg.idl_cfg.errors.len(), 2);
+        assert!(cfg.idl_cfg.utxos[0].is_ident("SwapUtxos"));
+
+        let err = parse(quote!(idl(errors = MyError)))
+            .err()
+            .expect("not a list");
+        assert!(err.to_string().contains("must be a list of type paths"));
+    }
+
+    #[test]
+    fn parses_version() {
+        let cfg = parse(quote!(version = 2)).expect("should parse");
+        assert_eq!(cfg.version, Some(2));
+        assert_eq!(parse(TokenStream::new()).unwrap().version, None);
+
+        for bad in [
+            quote!(version = 0),
+            quote!(version = 256),
+            quote!(version = "2"),
+        ] {
+            let err = parse(bad).err().expect("invalid version");
+            assert!(err.to_string().contains("from 1 to 255"));
+        }
+    }
+
+    #[test]
+    fn parses_profile_flag() {
+        assert!(parse(quote!(profile)).expect("should parse").profile);
+        assert!(!parse(TokenStream::new()).unwrap().profile);
+
+        let err = parse(quote!(profile, profile))
+            .err()
+            .expect("duplicate key");
+        assert!(err.to_string().contains("duplicate `profile` key"));
+    }
+
+    #[test]
+    fn parses_modules_list() {
+        let cfg = parse(quote!(modules = [crate::pool::handlers, admin])).expect("should parse");
+        assert_eq!(cfg.modules.len(), 2);
+        assert!(cfg.modules[1].is_ident("admin"));
+
+        let err = parse(quote!(modules = admin)).err().expect("not a list");
+        assert!(err.to_string().contains("`modules` must be a list"));
+    }
 }
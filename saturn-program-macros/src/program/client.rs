@@ -0,0 +1,112 @@
+//! Off-chain instruction builders (`client` feature).
+//!
+//! `<module>::client` gets one function per handler, named after it, taking
+//! the `AccountMeta`s built with the `metas(..)` function that
+//! `#[derive(Accounts)]` generates under its own `client` feature, followed by
+//! the handler's payload arguments:
+//!
+//! ```ignore
+//! let ix = my_program::client::set_fee(SetFee::metas(admin), fee);
+//! ```
+
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::program::analysis::{AnalysisResult, FnInfo};
+use crate::program::attr::AttrConfig;
+
+/// Generate the `client` module, injected next to `__private` inside the
+/// user's module so argument types resolve as in the handlers.
+pub fn generate(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream {
+    // With `version = N`, instructions are `[version][discriminator][params]`.
+    let version_byte = attr_cfg.version.map(|version| {
+        quote! { data.push(#version); }
+    });
+
+    let builders = analysis.fn_infos.iter().map(
+        |FnInfo {
+             fn_ident,
+             param_tys,
+             param_idents,
+             ..
+         }| {
+            let struct_ident = syn::Ident::new(
+                &fn_ident.to_string().to_case(Case::Pascal),
+                fn_ident.span(),
+            );
+            let doc = format!(
+                "Instruction calling `{fn_ident}`, with `metas` from the `metas(..)` of its accounts struct."
+            );
+            quote! {
+                #[doc = #doc]
+                pub fn #fn_ident(
+                    metas: Vec<arch_program::account::AccountMeta>,
+                    #( #param_idents: #param_tys ),*
+                ) -> arch_program::instruction::Instruction {
+                    let params = super::__private::#struct_ident { #( #param_idents ),* };
+                    let mut data = Vec::new();
+                    #version_byte
+                    data.extend_from_slice(&super::__private::#struct_ident::DISCRIMINATOR);
+                    saturn_account_parser::__private::borsh::BorshSerialize::serialize(&params, &mut data)
+                        .expect("instruction serialisation");
+                    arch_program::instruction::Instruction {
+                        program_id: crate::ID,
+                        accounts: metas,
+                        data,
+                    }
+                }
+            }
+        },
+    );
+
+    quote! {
+        /// Off-chain builders of this program's instructions, one per handler.
+        #[allow(clippy::too_many_arguments)]
+        pub mod client {
+            use super::*;
+            #( #builders )*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn builds_instruction_per_handler() {
+        let attr_cfg = AttrConfig {
+            enable_bitcoin_tx: false,
+            btc_tx_cfg: Default::default(),
+            idl_cfg: Default::default(),
+            version: Some(2),
+            profile: false,
+            modules: vec![],
+        };
+        let analysis = AnalysisResult {
+            item_mod: parse_quote! { mod my_program {} },
+            fn_infos: vec![FnInfo {
+                fn_ident: parse_quote!(set_fee),
+                acc_ty: parse_quote!(SetFee<'info>),
+                mod_path: vec![],
+                param_tys: vec![parse_quote!(u8)],
+                param_idents: vec![parse_quote!(fee)],
+                since: None,
+                no_btc_tx: false,
+                utxos: None,
+                discriminant: None,
+            }],
+            fallback: None,
+        };
+        let rendered = generate(&attr_cfg, &analysis).to_string();
+
+        assert!(rendered.contains(
+            "pub fn set_fee (metas : Vec < arch_program :: account :: AccountMeta > , fee : u8) -> arch_program :: instruction :: Instruction"
+        ));
+        assert!(rendered.contains("let params = super :: __private :: SetFee { fee } ;"));
+        assert!(rendered.contains("data . push (2u8) ;"));
+        assert!(rendered.contains("program_id : crate :: ID"));
+    }
+}
@@ -4,8 +4,10 @@ use quote::quote;
 use sha2::{Digest, Sha256};
 use syn::LitInt;
 
-use crate::program::analysis::{AnalysisResult, FnInfo};
+use crate::program::analysis::{AnalysisResult, FallbackInfo, FnInfo, Since};
 use crate::program::attr::AttrConfig;
+use crate::program::client;
+use crate::program::idl;
 
 /// Generates the dispatcher + entrypoint implementation for a `#[saturn_program]` module.
 /// The returned `TokenStream` already includes the original (potentially modified)
@@ -16,7 +18,249 @@ pub fn generate(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream
     // ---------------------------------------------------------------------
     // 0. Optional RuneSet alias (identical logic to previous implementation)
     // ---------------------------------------------------------------------
-    let rune_alias_ts: Option<TokenStream> = if attr_cfg.enable_bitcoin_tx {
+    let rune_alias_ts: Option<TokenStream> = rune_alias(attr_cfg);
+
+    // ---------------------------------------------------------------------
+    // 1. Build structs + discriminators (inside hidden module) and dispatcher
+    // ---------------------------------------------------------------------
+    // The dispatcher sits next to the module and reaches handlers through it.
+    let module_path: TokenStream = quote! { #module_ident };
+    let (struct_defs, mut match_arms) = instruction_arms(attr_cfg, analysis, &module_path);
+    let item_mod_mut = inject_module_items(attr_cfg, analysis, struct_defs);
+
+    // `modules = [..]`: instructions of `#[saturn_program_module]` modules go
+    // to the dispatch function generated inside each of them.
+    let version_arg: TokenStream = match attr_cfg.version {
+        Some(_) => quote! { version },
+        None => quote! { 0u8 },
+    };
+    for module in &attr_cfg.modules {
+        match_arms.push(quote! {
+            d if #module::__SATURN_DISCRIMINATORS.contains(&d) => {
+                #module::__saturn_dispatch(program_id, accounts, d, #version_arg, data)?;
+            }
+        });
+    }
+
+    // Handler names must stay unique across the modules, and every module
+    // must share the program's wire version: both checked at compile time.
+    let modules_check_ts: Option<TokenStream> = (!attr_cfg.modules.is_empty()).then(|| {
+        let modules = &attr_cfg.modules;
+        let discriminators = discriminators(analysis, &module_path);
+        let version = option_u8(attr_cfg.version);
+        quote! {
+            const _: () = {
+                assert!(
+                    saturn_account_parser::__private::discriminators_unique(&[
+                        #discriminators,
+                        #( #modules::__SATURN_DISCRIMINATORS ),*
+                    ]),
+                    "two handlers of this program share a name across its modules"
+                );
+                #(
+                    assert!(
+                        saturn_account_parser::__private::same_version(#modules::__SATURN_VERSION, #version),
+                        "#[saturn_program_module] `version` differs from the #[saturn_program] one"
+                    );
+                )*
+            };
+        }
+    });
+
+    // ---------------------------------------------------------------------
+    // 2. Generate dispatcher function (Anchor-style 8-byte discriminator)
+    // ---------------------------------------------------------------------
+    let process_ident = syn::Ident::new("process_instruction", Span::call_site());
+
+    // Instruction data matching no discriminator (or too short to hold one)
+    // goes to the `#[fallback]` handler when there is one.
+    let unmatched_ts: TokenStream = match &analysis.fallback {
+        Some(FallbackInfo { fn_ident, mod_path }) => quote! {
+            return #module_ident #( :: #mod_path )* :: #fn_ident(program_id, accounts, instruction_data)
+        },
+        None => quote! { return Err(ProgramError::InvalidInstructionData) },
+    };
+
+    // With `version = N`, instructions are `[version][discriminator][params]`
+    // and versions outside `1..=N` are rejected.
+    let header_ts: TokenStream = match attr_cfg.version {
+        Some(version) => quote! {
+            if instruction_data.len() < 9 {
+                #unmatched_ts;
+            }
+
+            let version: u8 = instruction_data[0];
+            if version == 0 || version > #version {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            let mut disc = [0u8; 8];
+            disc.copy_from_slice(&instruction_data[1..9]);
+            let data = &instruction_data[9..];
+        },
+        None => quote! {
+            if instruction_data.len() < 8 {
+                #unmatched_ts;
+            }
+
+            let mut disc = [0u8; 8];
+            disc.copy_from_slice(&instruction_data[..8]);
+            let data = &instruction_data[8..];
+        },
+    };
+
+    // The internal dispatcher (not exported as the BPF entrypoint).
+    let dispatcher_ts: TokenStream = quote! {
+        #[allow(clippy::needless_borrow)]
+        pub fn #process_ident<'info>(
+            program_id: &arch_program::pubkey::Pubkey,
+            accounts: &'info [arch_program::account::AccountInfo<'info>],
+            instruction_data: &[u8],
+        ) -> Result<(), arch_program::program_error::ProgramError> {
+            use arch_program::program_error::ProgramError;
+
+            #header_ts
+
+            match disc {
+                #( #match_arms ),*
+                _ => #unmatched_ts,
+            }
+
+            Ok(())
+        }
+    };
+
+    // ------------------------------------------------------------------
+    // 3. Generate a root-level (actually parent-module-level) wrapper that
+    //    exposes the BPF entrypoint symbol and forwards to the internal
+    //    dispatcher. This matches Anchor's behaviour and works even when
+    //    the #[saturn_program] macro is applied inside a nested module.
+    // ------------------------------------------------------------------
+
+    let wrapper_ident = syn::Ident::new("__saturn_entrypoint", Span::call_site());
+
+    // `arch_program::entrypoint!` spelled out, with a `custom_panic` logging
+    // `HandlerPanicked` in place of the runtime's default one.
+    let wrapper_ts: TokenStream = quote! {
+        /// # Safety
+        #[cfg(all(not(test), not(feature = "no-entrypoint")))]
+        #[no_mangle]
+        pub unsafe extern "C" fn entrypoint(input: *mut u8) -> u64 {
+            let (program_id, accounts, instruction_data) =
+                unsafe { arch_program::entrypoint::deserialize(input) };
+            match #wrapper_ident(&program_id, &accounts, &instruction_data) {
+                Ok(()) => arch_program::entrypoint::SUCCESS,
+                Err(error) => error.into(),
+            }
+        }
+
+        #[cfg(all(not(test), not(feature = "no-entrypoint")))]
+        arch_program::custom_heap_default!();
+
+        #[cfg(all(not(test), not(feature = "no-entrypoint")))]
+        #[no_mangle]
+        fn custom_panic(info: &core::panic::PanicInfo<'_>) {
+            saturn_account_parser::panic::report(info);
+        }
+
+        #[allow(clippy::needless_borrow)]
+        fn #wrapper_ident<'info>(
+            program_id: &arch_program::pubkey::Pubkey,
+            accounts: &'info [arch_program::account::AccountInfo<'info>],
+            instruction_data: &[u8],
+        ) -> Result<(), arch_program::program_error::ProgramError> {
+            // Where the target unwinds (host builds), a panicking handler
+            // fails the instruction with `HandlerPanicked`. On-chain panics
+            // abort and `custom_panic` only logs that code.
+            saturn_account_parser::panic::catch(|| {
+                #process_ident(program_id, accounts, instruction_data)
+            })
+        }
+    };
+
+    // Keep ID enforcement (macro requires crate::ID)
+    let id_check_ts: TokenStream = quote! {
+        #[allow(dead_code)]
+        const __SATURN_ENFORCE_ID: () = {
+            let _ = &crate::ID;
+        };
+    };
+
+    // JSON IDL, only with the `idl-build` feature of this crate.
+    let idl_ts: Option<TokenStream> =
+        cfg!(feature = "idl-build").then(|| idl::generate(attr_cfg, analysis));
+
+    quote! {
+        #id_check_ts
+        #modules_check_ts
+        #rune_alias_ts
+        #item_mod_mut
+        #dispatcher_ts
+        #wrapper_ts
+        #idl_ts
+    }
+}
+
+/// Generates the items of a `#[saturn_program_module]`: the instruction
+/// structs of [`generate`] plus `__saturn_dispatch`, which the
+/// `process_instruction` of the `#[saturn_program(modules = [..])]` program
+/// calls for the `__SATURN_DISCRIMINATORS` of the module. Everything is
+/// injected inside the module so the program reaches it by path.
+pub fn generate_module(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream {
+    // The dispatch function sits inside the module.
+    let module_path: TokenStream = quote! { self };
+    let (struct_defs, match_arms) = instruction_arms(attr_cfg, analysis, &module_path);
+    let mut item_mod_mut = inject_module_items(attr_cfg, analysis, struct_defs);
+
+    let rune_alias_ts: Option<TokenStream> = rune_alias(attr_cfg);
+    let discriminators = discriminators(analysis, &module_path);
+    let version = option_u8(attr_cfg.version);
+    let idl_ts: Option<TokenStream> =
+        cfg!(feature = "idl-build").then(|| idl::generate_module(analysis));
+
+    let dispatch_ts: TokenStream = quote! {
+        #rune_alias_ts
+
+        #[doc(hidden)]
+        pub const __SATURN_DISCRIMINATORS: &[[u8; 8]] = #discriminators;
+
+        #[doc(hidden)]
+        pub const __SATURN_VERSION: Option<u8> = #version;
+
+        #[doc(hidden)]
+        #[allow(clippy::needless_borrow, unused_variables)]
+        pub fn __saturn_dispatch<'info>(
+            program_id: &arch_program::pubkey::Pubkey,
+            accounts: &'info [arch_program::account::AccountInfo<'info>],
+            disc: [u8; 8],
+            version: u8,
+            data: &[u8],
+        ) -> Result<(), arch_program::program_error::ProgramError> {
+            use arch_program::program_error::ProgramError;
+
+            match disc {
+                #( #match_arms ),*
+                _ => return Err(ProgramError::InvalidInstructionData),
+            }
+
+            Ok(())
+        }
+
+        #idl_ts
+    };
+    if let Some((_brace, ref mut items)) = item_mod_mut.content {
+        let dispatch_items: syn::File =
+            syn::parse2(dispatch_ts).expect("failed to parse module dispatcher");
+        items.extend(dispatch_items.items);
+    }
+
+    quote! { #item_mod_mut }
+}
+
+/// `__SaturnDefaultRuneSet`, the rune set of the transaction builders, when
+/// `btc_tx_cfg` is set.
+fn rune_alias(attr_cfg: &AttrConfig) -> Option<TokenStream> {
+    if attr_cfg.enable_bitcoin_tx {
         if let Some(cap) = attr_cfg.btc_tx_cfg.rune_capacity {
             let cap_lit = LitInt::new(&cap.to_string(), Span::call_site());
             Some(quote! {
@@ -37,54 +281,41 @@ pub fn generate(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream
         }
     } else {
         None
-    };
-
-    // Mutable copy of the user's module so we can inject helper items.
-    let mut item_mod_mut = analysis.item_mod.clone();
-
-    // ---------------------------------------------------------------------
-    // Inject `pub type RuneSet = ..` inside the user's module when
-    // `rune_capacity` was chosen.
-    // ---------------------------------------------------------------------
-    if let Some(cap) = attr_cfg.btc_tx_cfg.rune_capacity {
-        let cap_lit = LitInt::new(&cap.to_string(), Span::call_site());
-        let alias_ts: TokenStream = quote! {
-            // Generated by saturn-program-macros
-            pub type RuneSet = saturn_collections::generic::fixed_set::FixedSet<
-                arch_program::rune::RuneAmount,
-                #cap_lit
-            >;
-        };
-        if let Some((_brace, ref mut items)) = item_mod_mut.content {
-            let alias_item: syn::Item =
-                syn::parse2(alias_ts).expect("failed to parse RuneSet alias");
-            items.push(alias_item);
-        }
     }
+}
 
-    // ---------------------------------------------------------------------
-    // 1. Build structs + discriminators (inside hidden module) and dispatcher
-    // ---------------------------------------------------------------------
-
+/// Per-handler instruction structs and dispatcher match arms. Handlers,
+/// their structs and upgrade functions are named through `module_path`: the
+/// `#[saturn_program]` module as seen from where the arms are emitted.
+fn instruction_arms(
+    attr_cfg: &AttrConfig,
+    analysis: &AnalysisResult,
+    module_path: &TokenStream,
+) -> (Vec<TokenStream>, Vec<TokenStream>) {
     // Collect struct definitions and dispatcher match arms
     let mut struct_defs: Vec<TokenStream> = Vec::new();
     let mut match_arms: Vec<TokenStream> = Vec::new();
 
-    for FnInfo {
-        fn_ident,
-        param_tys,
-        param_idents,
-        acc_ty,
-        mod_path,
-    } in &analysis.fn_infos
-    {
+    for fn_info in &analysis.fn_infos {
+        let FnInfo {
+            fn_ident,
+            param_tys,
+            param_idents,
+            acc_ty,
+            mod_path,
+            since,
+            no_btc_tx,
+            utxos,
+            ..
+        } = fn_info;
+
         // -----------------------------------------
         // 1a. Generate per-instruction struct
         // -----------------------------------------
         let struct_name_str = fn_ident.to_string().to_case(Case::Pascal);
         let struct_ident = syn::Ident::new(&struct_name_str, fn_ident.span());
 
-        let struct_path: TokenStream = quote! { #module_ident :: __private :: #struct_ident };
+        let struct_path: TokenStream = quote! { #module_path :: __private :: #struct_ident };
 
         let struct_body: TokenStream = if param_tys.is_empty() {
             quote! { {} }
@@ -99,16 +330,8 @@ pub fn generate(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream
 
         // -----------------------------------------
         // 1b. Compute 8-byte discriminator at macro-time
-        //      = sha256("global:<handler>")[0..8]
         // -----------------------------------------
-        let hash = {
-            let mut hasher = Sha256::new();
-            hasher.update(format!("global:{}", fn_ident).as_bytes());
-            let result = hasher.finalize();
-            let mut arr = [0u8; 8];
-            arr.copy_from_slice(&result[..8]);
-            arr
-        };
+        let hash = fn_info.discriminator();
 
         let disc_tokens: Vec<TokenStream> = hash
             .iter()
@@ -136,16 +359,65 @@ pub fn generate(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream
         let nested_path: TokenStream = quote! { #( :: #mod_path )* };
 
         let handler_call: TokenStream = if param_tys.is_empty() {
-            quote! { #module_ident #nested_path :: #fn_ident(ctx)?; }
+            quote! { #module_path #nested_path :: #fn_ident(ctx)?; }
         } else {
             let param_access: Vec<TokenStream> = param_idents
                 .iter()
-                .map(|id| quote! { params.#id })
+                .enumerate()
+                .map(|(index, id)| match utxos {
+                    // Parsed into a local before the call, see below.
+                    Some((utxos_index, _)) if *utxos_index == index => quote! { #id },
+                    _ => quote! { params.#id },
+                })
                 .collect();
-            quote! { #module_ident #nested_path :: #fn_ident(ctx, #( #param_access ),* )?; }
+            quote! { #module_path #nested_path :: #fn_ident(ctx, #( #param_access ),* )?; }
         };
 
-        let arm_body: TokenStream = if attr_cfg.enable_bitcoin_tx {
+        // `#[utxos(Parser)]`: parse the instruction's `Vec<UtxoMeta>` against the
+        // validated accounts and hand the result to the handler. Parsers with
+        // `#[utxo_params]` do not implement `TryFromUtxos`, so they are refused
+        // here rather than run without their parameters.
+        let parse_utxos: Option<TokenStream> = utxos.as_ref().map(|(index, parser_ty)| {
+            let id = &param_idents[*index];
+            profiled(
+                attr_cfg.profile,
+                fn_ident,
+                "utxos",
+                quote! {
+                    let #id = <#parser_ty as saturn_utxo_parser::TryFromUtxos>::try_utxos(&accounts_struct, &params.#id)?;
+                },
+            )
+        });
+
+        // `profile`: each phase below logs the compute units it consumed.
+        let parse_accounts = profiled(
+            attr_cfg.profile,
+            fn_ident,
+            "accounts",
+            quote! {
+                // Accounts past the struct's own are handed to the handler as `ctx.remaining_accounts`.
+                let (mut accounts_struct, bumps, consumed) = <#acc_ty as saturn_account_parser::Bumps>::try_accounts_prefix_with_args(accounts, data)?;
+            },
+        );
+        let handler_call = profiled(attr_cfg.profile, fn_ident, "handler", handler_call);
+        // Hand the Bitcoin transaction to the runtime (unless the handler already
+        // did) and close / finalise accounts now that the handler released them.
+        // `#[no_btc_tx]` handlers skip the builder and take the plain Context.
+        let with_btc_tx = attr_cfg.enable_bitcoin_tx && !no_btc_tx;
+        let finalize_btc_tx = with_btc_tx.then(|| {
+            quote! { btc_tx_builder.finalize_pending()?; }
+        });
+        let finalize = profiled(
+            attr_cfg.profile,
+            fn_ident,
+            "finalize",
+            quote! {
+                #finalize_btc_tx
+                saturn_account_parser::Accounts::exit(&accounts_struct)?;
+            },
+        );
+
+        let arm_body: TokenStream = if with_btc_tx {
             let max_inputs_lit = LitInt::new(
                 &attr_cfg.btc_tx_cfg.max_inputs_to_sign.unwrap().to_string(),
                 Span::call_site(),
@@ -165,49 +437,135 @@ pub fn generate(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream
                 syn::parse_str("self::__SaturnDefaultRuneSet").expect("internal path parse");
 
             quote! {
-                let mut accounts_struct = <#acc_ty as saturn_account_parser::Accounts>::try_accounts(accounts)?;
+                #parse_accounts
+                #parse_utxos
 
-                let btc_tx_builder = saturn_account_parser::TxBuilderWrapper::<'info, #max_mod_lit, #max_inputs_lit, #rune_set_path>::default();
+                // Owned here rather than by the context so that it can be
+                // finalised once the handler has consumed the context.
+                let mut btc_tx_builder = saturn_account_parser::TxBuilderWrapper::<'info, #max_mod_lit, #max_inputs_lit, #rune_set_path>::default();
 
                 let ctx = saturn_account_parser::Context::new_with_btc_tx(
                     program_id,
                     &mut accounts_struct,
-                    &[],
-                    btc_tx_builder,
+                    &accounts[consumed..],
+                    bumps,
+                    &mut btc_tx_builder,
                 );
 
                 #handler_call
+                #finalize
             }
         } else {
             quote! {
-                let mut accounts_struct = <#acc_ty as saturn_account_parser::Accounts>::try_accounts(accounts)?;
+                #parse_accounts
+                #parse_utxos
 
                 let ctx = saturn_account_parser::Context::new_simple(
                     program_id,
                     &mut accounts_struct,
-                    &[],
+                    &accounts[consumed..],
+                    bumps,
                 );
                 #handler_call
+                #finalize
             }
         };
 
+        let decode_params: TokenStream = quote! {
+            // Use a mut slice so Borsh can advance the cursor while deserializing.
+            let mut data_slice: &[u8] = data;
+            let params: #struct_path = saturn_account_parser::__private::borsh::BorshDeserialize::deserialize(&mut data_slice)
+                .map_err(|e| ProgramError::BorshIoError(e.to_string()))?;
+
+            // Reject any trailing bytes that were not consumed during deserialization.
+            if !data_slice.is_empty() {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+        };
+
+        // Instructions older than the handler's `#[since]` version carry an
+        // older parameter layout, decoded by the user's upgrade function.
+        let decode_params: TokenStream = match since {
+            Some(Since {
+                version, upgrade, ..
+            }) if *version > 1 => {
+                let older: TokenStream = match upgrade {
+                    Some(upgrade) => {
+                        // Resolved like a name in the handler's module unless crate-absolute.
+                        let crate_absolute = upgrade.leading_colon.is_some()
+                            || matches!(upgrade.segments.first(), Some(seg) if seg.ident == "crate");
+                        let upgrade_path: TokenStream = if crate_absolute {
+                            quote! { #upgrade }
+                        } else {
+                            quote! { #module_path #nested_path :: #upgrade }
+                        };
+                        quote! {
+                            let ( #( #param_idents, )* ) = #upgrade_path(version, data)?;
+                            #struct_path { #( #param_idents ),* }
+                        }
+                    }
+                    None => quote! { return Err(ProgramError::InvalidInstructionData); },
+                };
+                quote! {
+                    let params: #struct_path = if version >= #version {
+                        #decode_params
+                        params
+                    } else {
+                        #older
+                    };
+                }
+            }
+            _ => decode_params,
+        };
+
         let arm: TokenStream = quote! {
             d if d == #struct_path :: DISCRIMINATOR => {
-                // Use a mut slice so Borsh can advance the cursor while deserializing.
-                let mut data_slice: &[u8] = data;
-                let params: #struct_path = saturn_account_parser::__private::borsh::BorshDeserialize::deserialize(&mut data_slice)
-                    .map_err(|e| ProgramError::BorshIoError(e.to_string()))?;
-
-                // Reject any trailing bytes that were not consumed during deserialization.
-                if !data_slice.is_empty() {
-                    return Err(ProgramError::InvalidInstructionData);
-                }
+                #decode_params
                 #arm_body
             }
         };
         match_arms.push(arm);
     }
 
+    (struct_defs, match_arms)
+}
+
+/// The user's module with the `RuneSet` alias, the instruction structs
+/// (`__private`) and the `client` builders injected.
+fn inject_module_items(
+    attr_cfg: &AttrConfig,
+    analysis: &AnalysisResult,
+    struct_defs: Vec<TokenStream>,
+) -> syn::ItemMod {
+    // Mutable copy of the user's module so we can inject helper items.
+    let mut item_mod_mut = analysis.item_mod.clone();
+
+    // ---------------------------------------------------------------------
+    // Inject `pub type RuneSet = ..` inside the user's module when
+    // `rune_capacity` was chosen.
+    // ---------------------------------------------------------------------
+    if let Some(cap) = attr_cfg.btc_tx_cfg.rune_capacity {
+        let cap_lit = LitInt::new(&cap.to_string(), Span::call_site());
+        let alias_ts: TokenStream = quote! {
+            // Generated by saturn-program-macros
+            pub type RuneSet = saturn_collections::generic::fixed_set::FixedSet<
+                arch_program::rune::RuneAmount,
+                #cap_lit
+            >;
+        };
+        if let Some((_brace, ref mut items)) = item_mod_mut.content {
+            let alias_item: syn::Item =
+                syn::parse2(alias_ts).expect("failed to parse RuneSet alias");
+            items.push(alias_item);
+        }
+    }
+
+    // A program without handlers of its own (only `modules`) has no
+    // instructions to add.
+    if analysis.fn_infos.is_empty() {
+        return item_mod_mut;
+    }
+
     // Push the hidden module with struct definitions inside the user's module
     let private_mod_ts: TokenStream = quote! {
         #[doc(hidden)]
@@ -222,77 +580,64 @@ pub fn generate(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream
         let private_item: syn::Item =
             syn::parse2(private_mod_ts.clone()).expect("failed to parse private module");
         items.push(private_item);
-    }
-
-    // ---------------------------------------------------------------------
-    // 2. Generate dispatcher function (Anchor-style 8-byte discriminator)
-    // ---------------------------------------------------------------------
-    let process_ident = syn::Ident::new("process_instruction", Span::call_site());
-
-    // The internal dispatcher (not exported as the BPF entrypoint).
-    let dispatcher_ts: TokenStream = quote! {
-        #[allow(clippy::needless_borrow)]
-        pub fn #process_ident<'info>(
-            program_id: &arch_program::pubkey::Pubkey,
-            accounts: &'info [arch_program::account::AccountInfo<'info>],
-            instruction_data: &[u8],
-        ) -> Result<(), arch_program::program_error::ProgramError> {
-            use arch_program::program_error::ProgramError;
-
-            if instruction_data.len() < 8 {
-                return Err(ProgramError::InvalidInstructionData);
-            }
 
-            let mut disc = [0u8; 8];
-            disc.copy_from_slice(&instruction_data[..8]);
-            let data = &instruction_data[8..];
-
-            match disc {
-                #( #match_arms ),*
-                _ => return Err(ProgramError::InvalidInstructionData),
-            }
-
-            Ok(())
+        // Off-chain instruction builders, only with the `client` feature of this crate.
+        if cfg!(feature = "client") {
+            let client_item: syn::Item = syn::parse2(client::generate(attr_cfg, analysis))
+                .expect("failed to parse client module");
+            items.push(client_item);
         }
-    };
-
-    // ------------------------------------------------------------------
-    // 3. Generate a root-level (actually parent-module-level) wrapper that
-    //    exposes the BPF entrypoint symbol and forwards to the internal
-    //    dispatcher. This matches Anchor's behaviour and works even when
-    //    the #[saturn_program] macro is applied inside a nested module.
-    // ------------------------------------------------------------------
+    }
 
-    let wrapper_ident = syn::Ident::new("__saturn_entrypoint", Span::call_site());
+    item_mod_mut
+}
 
-    let wrapper_ts: TokenStream = quote! {
-        #[cfg(all(not(test), not(feature = "no-entrypoint")))]
-        arch_program::entrypoint!(#wrapper_ident);
+/// `&[..]` of the discriminators of the handlers in `analysis`.
+fn discriminators(analysis: &AnalysisResult, module_path: &TokenStream) -> TokenStream {
+    let struct_idents = analysis.fn_infos.iter().map(|info| {
+        syn::Ident::new(
+            &info.fn_ident.to_string().to_case(Case::Pascal),
+            info.fn_ident.span(),
+        )
+    });
+    quote! { &[ #( #module_path :: __private :: #struct_idents :: DISCRIMINATOR ),* ] }
+}
 
-        #[allow(clippy::needless_borrow)]
-        fn #wrapper_ident<'info>(
-            program_id: &arch_program::pubkey::Pubkey,
-            accounts: &'info [arch_program::account::AccountInfo<'info>],
-            instruction_data: &[u8],
-        ) -> Result<(), arch_program::program_error::ProgramError> {
-            #process_ident(program_id, accounts, instruction_data)
-        }
-    };
+/// `Some(N)` / `None` tokens for an optional wire version.
+fn option_u8(value: Option<u8>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
 
-    // Keep ID enforcement (macro requires crate::ID)
-    let id_check_ts: TokenStream = quote! {
-        #[allow(dead_code)]
-        const __SATURN_ENFORCE_ID: () = {
-            let _ = &crate::ID;
-        };
-    };
+/// Discriminator of the instruction handled by `handler`:
+/// `sha256("global:<handler>")[0..8]`, the scheme Anchor uses, so Anchor
+/// clients and explorers can encode and decode Saturn instructions.
+pub(crate) fn instruction_discriminator(handler: &str) -> [u8; 8] {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("global:{}", handler).as_bytes());
+    let result = hasher.finalize();
+    let mut arr = [0u8; 8];
+    arr.copy_from_slice(&result[..8]);
+    arr
+}
 
+/// `body`, followed when `profile` is set by a log of the compute units it
+/// consumed (`deposit accounts: 1520 CU`). The statements are not wrapped in
+/// a block so their `let` bindings stay in scope for the next phase.
+fn profiled(profile: bool, handler: &syn::Ident, phase: &str, body: TokenStream) -> TokenStream {
+    if !profile {
+        return body;
+    }
+    let line = format!("{handler} {phase}: {{}} CU");
     quote! {
-        #id_check_ts
-        #rune_alias_ts
-        #item_mod_mut
-        #dispatcher_ts
-        #wrapper_ts
+        let __saturn_cu = arch_program::program::get_remaining_compute_units();
+        #body
+        arch_program::msg!(
+            #line,
+            __saturn_cu.saturating_sub(arch_program::program::get_remaining_compute_units())
+        );
     }
 }
 
@@ -306,6 +651,10 @@ mod tests {
         let mut cfg = AttrConfig {
             enable_bitcoin_tx: enable_btc,
             btc_tx_cfg: Default::default(),
+            idl_cfg: Default::default(),
+            version: None,
+            profile: false,
+            modules: vec![],
         };
         if enable_btc {
             cfg.btc_tx_cfg.max_inputs_to_sign = Some(2);
@@ -324,10 +673,15 @@ mod tests {
             mod_path: vec![],
             param_tys: vec![syn::parse_str::<syn::Type>("u8").unwrap()],
             param_idents: vec![syn::Ident::new("val", proc_macro2::Span::call_site())],
+            since: None,
+            no_btc_tx: false,
+            utxos: None,
+            discriminant: None,
         };
         AnalysisResult {
             item_mod,
             fn_infos: vec![fn_info],
+            fallback: None,
         }
     }
 
@@ -342,8 +696,16 @@ mod tests {
             "Should use simple context when BTC disabled"
         );
         assert!(!ts_str.contains("new_with_btc_tx"));
+        assert!(ts_str.contains("try_accounts_prefix_with_args (accounts , data)"));
+        assert!(ts_str.contains("& accounts [consumed ..]"));
         assert!(ts_str.contains("handle_transfer"));
         assert!(ts_str.contains("my_mod"));
+        assert!(ts_str.contains(
+            "saturn_account_parser :: panic :: catch (|| { process_instruction (program_id , accounts , instruction_data) })"
+        ));
+        assert!(ts_str.contains(
+            "fn custom_panic (info : & core :: panic :: PanicInfo < '_ >) { saturn_account_parser :: panic :: report (info) ; }"
+        ));
     }
 
     #[test]
@@ -359,6 +721,143 @@ mod tests {
         assert!(ts_str.contains("handle_transfer"));
         // Ensure const generics were injected (numbers 2 and 4 from cfg)
         assert!(ts_str.contains("2") && ts_str.contains("4"));
+        assert!(ts_str.contains(
+            "btc_tx_builder . finalize_pending () ? ; saturn_account_parser :: Accounts :: exit (& accounts_struct) ? ;"
+        ));
+    }
+
+    #[test]
+    fn routes_unmatched_data_to_fallback() {
+        let attr_cfg = dummy_attr_cfg(false);
+        let mut analysis = dummy_analysis("proxy");
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        assert!(ts_str.contains("_ => return Err (ProgramError :: InvalidInstructionData)"));
+
+        analysis.fallback = Some(FallbackInfo {
+            fn_ident: parse_quote!(forward),
+            mod_path: vec![parse_quote!(router)],
+        });
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        let call = "return proxy :: router :: forward (program_id , accounts , instruction_data)";
+        assert_eq!(ts_str.matches(call).count(), 2);
+        assert!(!ts_str.contains("_ => return Err"));
+    }
+
+    #[test]
+    fn reads_version_byte_and_upgrades_older_params() {
+        let mut attr_cfg = dummy_attr_cfg(false);
+        attr_cfg.version = Some(3);
+        let mut analysis = dummy_analysis("versioned");
+        analysis.fn_infos[0].since = Some(Since {
+            version: 2,
+            upgrade: Some(parse_quote!(upgrade_transfer)),
+            attr: parse_quote!(#[since(version = 2, upgrade = upgrade_transfer)]),
+        });
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        assert!(ts_str.contains("if version == 0 || version > 3u8"));
+        assert!(ts_str.contains("disc . copy_from_slice (& instruction_data [1 .. 9])"));
+        assert!(ts_str.contains("if version >= 2u8"));
+        assert!(ts_str.contains(
+            "let (val ,) = versioned :: upgrade_transfer (version , data) ? ; versioned :: __private :: HandleTransfer { val }"
+        ));
+
+        // Without an upgrade function older instructions are rejected.
+        analysis.fn_infos[0].since.as_mut().unwrap().upgrade = None;
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        assert!(ts_str.contains("} else { return Err (ProgramError :: InvalidInstructionData) ; }"));
+    }
+
+    #[test]
+    fn no_btc_tx_handlers_use_simple_context() {
+        let attr_cfg = dummy_attr_cfg(true);
+        let mut analysis = dummy_analysis("btc_mod");
+        analysis.fn_infos[0].no_btc_tx = true;
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        assert!(ts_str.contains("Context :: new_simple"));
+        assert!(!ts_str.contains("new_with_btc_tx"));
+        assert!(!ts_str.contains("TxBuilderWrapper"));
+    }
+
+    #[test]
+    fn parses_utxos_before_calling_handler() {
+        let attr_cfg = dummy_attr_cfg(false);
+        let mut analysis = dummy_analysis("utxo_mod");
+        analysis.fn_infos[0].utxos = Some((0, parse_quote!(SwapUtxos)));
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        assert!(ts_str.contains(
+            "let val = < SwapUtxos as saturn_utxo_parser :: TryFromUtxos > :: try_utxos (& accounts_struct , & params . val) ?"
+        ));
+        assert!(ts_str.contains("handle_transfer (ctx , val) ?"));
+    }
+
+    #[test]
+    fn profile_logs_compute_units_per_phase() {
+        let mut attr_cfg = dummy_attr_cfg(false);
+        let mut analysis = dummy_analysis("profiled");
+        analysis.fn_infos[0].utxos = Some((0, parse_quote!(SwapUtxos)));
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        assert!(!ts_str.contains("get_remaining_compute_units"));
+
+        attr_cfg.profile = true;
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        for phase in ["accounts", "utxos", "handler", "finalize"] {
+            let line = format!("\"handle_transfer {phase}: {{}} CU\"");
+            assert!(ts_str.contains(&line), "missing {phase} log");
+        }
+        assert_eq!(
+            ts_str
+                .matches(
+                    "let __saturn_cu = arch_program :: program :: get_remaining_compute_units () ;"
+                )
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn composes_program_modules() {
+        let mut attr_cfg = dummy_attr_cfg(false);
+        attr_cfg.modules = vec![parse_quote!(crate::pool::handlers)];
+        let ts_str = generate(&attr_cfg, &dummy_analysis("program")).to_string();
+        assert!(ts_str.contains(
+            "d if crate :: pool :: handlers :: __SATURN_DISCRIMINATORS . contains (& d) => { crate :: pool :: handlers :: __saturn_dispatch (program_id , accounts , d , 0u8 , data) ? ; }"
+        ));
+        assert!(ts_str.contains("discriminators_unique (& [& [program :: __private :: HandleTransfer :: DISCRIMINATOR] , crate :: pool :: handlers :: __SATURN_DISCRIMINATORS])"));
+        assert!(
+            ts_str.contains("same_version (crate :: pool :: handlers :: __SATURN_VERSION , None)")
+        );
+    }
+
+    #[test]
+    fn module_dispatches_inside_itself() {
+        let attr_cfg = dummy_attr_cfg(false);
+        let ts_str = generate_module(&attr_cfg, &dummy_analysis("handlers")).to_string();
+        assert!(ts_str.starts_with("mod handlers {"));
+        assert!(ts_str.contains("pub fn __saturn_dispatch < 'info >"));
+        assert!(ts_str.contains(
+            "pub const __SATURN_DISCRIMINATORS : & [[u8 ; 8]] = & [self :: __private :: HandleTransfer :: DISCRIMINATOR] ;"
+        ));
+        assert!(ts_str.contains("self :: handle_transfer (ctx , params . val) ?"));
+        assert!(!ts_str.contains("fn process_instruction"));
+    }
+
+    #[test]
+    fn pinned_discriminant_replaces_name_hash() {
+        let attr_cfg = dummy_attr_cfg(false);
+        let mut analysis = dummy_analysis("pinned");
+        analysis.fn_infos[0].discriminant = Some(0x0107);
+        let ts_str = generate(&attr_cfg, &analysis).to_string();
+        assert!(ts_str
+            .contains("pub const DISCRIMINATOR : [u8 ; 8] = [7 , 1 , 0 , 0 , 0 , 0 , 0 , 0] ;"));
+    }
+
+    #[test]
+    fn discriminators_match_anchor() {
+        // Discriminator Anchor generates for an `initialize` instruction.
+        assert_eq!(
+            instruction_discriminator("initialize"),
+            [175, 175, 109, 31, 13, 152, 155, 237]
+        );
     }
 
     #[test]
@@ -0,0 +1,282 @@
+//! `pub fn idl() -> String` describing the program as JSON (`idl-build`
+//! feature).
+//!
+//! Instruction names, discriminators and argument types are known at macro
+//! time; accounts, errors and UTXO layouts are collected at runtime from the
+//! `__idl_accounts()` / `__idl_errors()` helpers and `LAYOUT` consts emitted
+//! by the other Saturn macros with their own `idl-build` features.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{GenericArgument, PathArguments, Type};
+
+use crate::program::analysis::{AnalysisResult, FnInfo};
+use crate::program::attr::AttrConfig;
+
+/// Generate the `idl()` function, emitted next to `process_instruction`.
+pub fn generate(attr_cfg: &AttrConfig, analysis: &AnalysisResult) -> TokenStream {
+    // With `version = N`, clients prefix every instruction with that byte.
+    let mut metadata = format!(
+        "{{\"name\":{}",
+        json_string(&analysis.item_mod.ident.to_string())
+    );
+    if let Some(version) = attr_cfg.version {
+        metadata.push_str(&format!(",\"instruction_version\":{version}"));
+    }
+    metadata.push('}');
+
+    let instructions = instruction_entries(analysis);
+    let modules = &attr_cfg.modules;
+
+    let errors = attr_cfg.idl_cfg.errors.iter().map(|path| {
+        quote! { errors.extend(#path::__idl_errors()); }
+    });
+
+    let utxos = attr_cfg.idl_cfg.utxos.iter().map(|path| {
+        let name = json_string(
+            &path
+                .segments
+                .last()
+                .map(|seg| seg.ident.to_string())
+                .unwrap_or_default(),
+        );
+        let prefix = format!("{{\"name\":{name},\"layout\":");
+        quote! { format!("{}{}}}", #prefix, <#path>::LAYOUT.to_json()) }
+    });
+
+    quote! {
+        /// The program's IDL as JSON: instructions with their discriminators,
+        /// arguments and accounts, plus the errors and UTXO layouts listed in
+        /// `#[saturn_program(idl(..))]`.
+        pub fn idl() -> String {
+            use saturn_account_parser::idl::{json_array, json_string};
+
+            #[allow(unused_mut)]
+            let mut instructions: Vec<String> = vec![#(#instructions),*];
+            #( instructions.extend(#modules::__idl_instructions()); )*
+            #[allow(unused_mut)]
+            let mut errors: Vec<String> = Vec::new();
+            #(#errors)*
+            let utxos: Vec<String> = vec![#(#utxos),*];
+
+            format!(
+                "{{\"address\":{},\"metadata\":{},\"instructions\":{},\"errors\":{},\"utxos\":{}}}",
+                json_string(&crate::ID.to_string()),
+                #metadata,
+                json_array(instructions),
+                json_array(errors),
+                json_array(utxos),
+            )
+        }
+    }
+}
+
+/// `__idl_instructions()` of a `#[saturn_program_module]`, merged into the
+/// `idl()` of the program listing the module in `modules = [..]`.
+pub fn generate_module(analysis: &AnalysisResult) -> TokenStream {
+    let instructions = instruction_entries(analysis);
+    quote! {
+        #[doc(hidden)]
+        pub fn __idl_instructions() -> Vec<String> {
+            vec![#(#instructions),*]
+        }
+    }
+}
+
+/// Expressions building the JSON of each handler of `analysis`.
+fn instruction_entries(analysis: &AnalysisResult) -> Vec<TokenStream> {
+    analysis
+        .fn_infos
+        .iter()
+        .map(|fn_info| {
+            let FnInfo {
+                fn_ident,
+                acc_ty,
+                param_tys,
+                param_idents,
+                ..
+            } = fn_info;
+            let name = json_string(&fn_ident.to_string());
+            let discriminator = fn_info
+                .discriminator()
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            let args = param_idents
+                .iter()
+                .zip(param_tys)
+                .map(|(ident, ty)| {
+                    format!(
+                        "{{\"name\":{},\"type\":{}}}",
+                        json_string(&ident.to_string()),
+                        type_json(ty)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            let prefix = format!(
+                "{{\"name\":{name},\"discriminator\":[{discriminator}],\"args\":[{args}],\"accounts\":"
+            );
+            let acc_ty = strip_lifetimes(acc_ty);
+            quote! { format!("{}{}}}", #prefix, #acc_ty::__idl_accounts()) }
+        })
+        .collect()
+}
+
+/// JSON type of an instruction argument, following Anchor's IDL type names.
+fn type_json(ty: &Type) -> String {
+    match ty {
+        Type::Path(tp) if tp.qself.is_none() => {
+            let Some(seg) = tp.path.segments.last() else {
+                return "null".to_string();
+            };
+            let ident = seg.ident.to_string();
+            let inner = match &seg.arguments {
+                PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                }),
+                _ => None,
+            };
+            match (ident.as_str(), inner) {
+                (
+                    "bool" | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128"
+                    | "i128" | "f32" | "f64",
+                    None,
+                ) => json_string(&ident),
+                ("String", None) => json_string("string"),
+                ("Pubkey", None) => json_string("pubkey"),
+                ("Vec", Some(inner)) => format!("{{\"vec\":{}}}", type_json(inner)),
+                ("Option", Some(inner)) => format!("{{\"option\":{}}}", type_json(inner)),
+                _ => format!("{{\"defined\":{{\"name\":{}}}}}", json_string(&ident)),
+            }
+        }
+        Type::Array(array) => {
+            let len = match &array.len {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(int),
+                    ..
+                }) => int.base10_digits().to_string(),
+                other => json_string(&quote!(#other).to_string()),
+            };
+            format!("{{\"array\":[{},{}]}}", type_json(&array.elem), len)
+        }
+        Type::Group(group) => type_json(&group.elem),
+        Type::Paren(paren) => type_json(&paren.elem),
+        other => format!(
+            "{{\"defined\":{{\"name\":{}}}}}",
+            json_string(&quote!(#other).to_string())
+        ),
+    }
+}
+
+/// `acc_ty` without its lifetime arguments so it can be used in an expression
+/// path (`Accs<'info>` becomes `Accs`).
+fn strip_lifetimes(acc_ty: &syn::Path) -> syn::Path {
+    let mut path = acc_ty.clone();
+    if let Some(seg) = path.segments.last_mut() {
+        if let PathArguments::AngleBracketed(args) = &mut seg.arguments {
+            args.args = args
+                .args
+                .iter()
+                .filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)))
+                .cloned()
+                .collect();
+            if args.args.is_empty() {
+                seg.arguments = PathArguments::None;
+            } else {
+                args.colon2_token = Some(Default::default());
+            }
+        }
+    }
+    path
+}
+
+/// `value` as a JSON string literal, quotes included.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::attr::IdlCfg;
+    use syn::parse_quote;
+
+    #[test]
+    fn maps_argument_types() {
+        let cases: [(Type, &str); 7] = [
+            (parse_quote!(u64), r#""u64""#),
+            (parse_quote!(String), r#""string""#),
+            (parse_quote!(arch_program::pubkey::Pubkey), r#""pubkey""#),
+            (parse_quote!(Vec<u8>), r#"{"vec":"u8"}"#),
+            (parse_quote!(Option<bool>), r#"{"option":"bool"}"#),
+            (parse_quote!([u8; 32]), r#"{"array":["u8",32]}"#),
+            (
+                parse_quote!(crate::state::Side),
+                r#"{"defined":{"name":"Side"}}"#,
+            ),
+        ];
+        for (ty, expected) in cases {
+            assert_eq!(type_json(&ty), expected);
+        }
+    }
+
+    #[test]
+    fn strips_account_lifetimes() {
+        let stripped = strip_lifetimes(&parse_quote!(crate::Accs<'info>));
+        assert_eq!(quote!(#stripped).to_string(), "crate :: Accs");
+    }
+
+    #[test]
+    fn generates_idl_fn() {
+        let attr_cfg = AttrConfig {
+            enable_bitcoin_tx: false,
+            btc_tx_cfg: Default::default(),
+            idl_cfg: IdlCfg {
+                errors: vec![parse_quote!(MyError)],
+                utxos: vec![parse_quote!(crate::utxos::Deposit)],
+            },
+            version: None,
+            profile: false,
+            modules: vec![],
+        };
+        let analysis = AnalysisResult {
+            item_mod: parse_quote! { mod my_program {} },
+            fn_infos: vec![FnInfo {
+                fn_ident: parse_quote!(initialize),
+                acc_ty: parse_quote!(Init<'info>),
+                mod_path: vec![],
+                param_tys: vec![parse_quote!(u64)],
+                param_idents: vec![parse_quote!(amount)],
+                since: None,
+                no_btc_tx: false,
+                utxos: None,
+                discriminant: None,
+            }],
+            fallback: None,
+        };
+        let rendered = generate(&attr_cfg, &analysis).to_string();
+
+        assert!(rendered.contains("pub fn idl () -> String"));
+        assert!(rendered.contains(
+            r#"\"discriminator\":[175,175,109,31,13,152,155,237],\"args\":[{\"name\":\"amount\",\"type\":\"u64\"}]"#
+        ));
+        assert!(rendered.contains("Init :: __idl_accounts ()"));
+        assert!(rendered.contains("MyError :: __idl_errors ()"));
+        assert!(rendered.contains("< crate :: utxos :: Deposit > :: LAYOUT . to_json ()"));
+        assert!(rendered.contains(r#"\"name\":\"Deposit\""#));
+    }
+}
@@ -1,11 +1,16 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 mod analysis;
 mod attr;
+mod client;
 mod dispatcher;
 pub(crate) mod id;
+mod idl;
+
+pub(crate) use analysis::parse_instruction_attr;
+pub(crate) use dispatcher::instruction_discriminator;
 
 /// Entry point invoked by `lib.rs`.
 /// Delegates to smaller, testable helpers located in the sibling modules.
@@ -42,3 +47,48 @@ fn try_expand(attr: TokenStream, item: TokenStream) -> Result<TokenStream, Token
 
     Ok(generated)
 }
+
+/// Entry point of `#[saturn_program_module]`, invoked by `lib.rs`.
+///
+/// The module takes the `#[saturn_program]` attribute keys that shape its
+/// handlers (`btc_tx_cfg`, `profile`, `version`); the IDL sections, the
+/// `#[fallback]` handler and the entrypoint belong to the program listing it
+/// in `modules = [..]`.
+pub fn expand_module(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr_cfg = match attr::parse(attr) {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            let compile_error = err.to_compile_error();
+            return quote! { #item #compile_error };
+        }
+    };
+    let misplaced = if !attr_cfg.modules.is_empty() {
+        Some("`modules` belongs to the #[saturn_program] composing the modules")
+    } else if !attr_cfg.idl_cfg.errors.is_empty() || !attr_cfg.idl_cfg.utxos.is_empty() {
+        Some("`idl(..)` belongs to the #[saturn_program] composing the modules")
+    } else {
+        None
+    };
+    if let Some(message) = misplaced {
+        let compile_error = syn::Error::new(Span::call_site(), message).to_compile_error();
+        return quote! { #item #compile_error };
+    }
+
+    let analysis = match analysis::analyze(&attr_cfg, item) {
+        Ok(res) => res,
+        Err(err_tokens) => return err_tokens,
+    };
+
+    let generated = dispatcher::generate_module(&attr_cfg, &analysis);
+    match &analysis.fallback {
+        Some(fallback) => {
+            let compile_error = syn::Error::new(
+                fallback.fn_ident.span(),
+                "#[fallback] belongs to the #[saturn_program] composing the modules",
+            )
+            .to_compile_error();
+            quote! { #generated #compile_error }
+        }
+        None => generated,
+    }
+}
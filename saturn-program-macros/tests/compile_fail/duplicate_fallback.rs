@@ -0,0 +1,34 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("5X4RQFAEUKu9yyR9pv8uXcEUTdEK7m2YkdEYY5EYXPLH");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+    use arch_program::{account::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+    pub fn ping<'info>(_ctx: Context<'info, DummyAccounts<'info>>) -> ProgramResult {
+        Ok(())
+    }
+
+    #[fallback]
+    pub fn first<'info>(_: &Pubkey, _: &'info [AccountInfo<'info>], _: &[u8]) -> ProgramResult {
+        Ok(())
+    }
+
+    // A second fallback is ambiguous – should trigger compile error
+    #[fallback]
+    pub fn second<'info>(_: &Pubkey, _: &'info [AccountInfo<'info>], _: &[u8]) -> ProgramResult {
+        Ok(())
+    }
+}
+
+fn main() {}
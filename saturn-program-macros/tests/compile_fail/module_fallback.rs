@@ -0,0 +1,29 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program_module};
+
+declare_id!("5X4RQFAEUKu9yyR9pv8uXcEUTdEK7m2YkdEYY5EYXPLH");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+#[saturn_program_module]
+pub mod handlers {
+    use super::*;
+    use arch_program::{account::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+    pub fn ping<'info>(_ctx: Context<'info, DummyAccounts<'info>>) -> ProgramResult {
+        Ok(())
+    }
+
+    // Only the composing #[saturn_program] dispatches unmatched data.
+    #[fallback]
+    pub fn forward<'info>(_: &Pubkey, _: &'info [AccountInfo<'info>], _: &[u8]) -> ProgramResult {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use arch_program::program_error::ProgramError;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+// `#[since]` without `version = N` on the program – should trigger compile error
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    #[since(version = 2)]
+    pub fn close<'info>(ctx: Context<'info, DummyAccounts<'info>>) -> Result<(), ProgramError> {
+        let _ = ctx.program_id;
+        Ok(())
+    }
+}
+
+fn main() {}
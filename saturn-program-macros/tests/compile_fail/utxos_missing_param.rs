@@ -0,0 +1,28 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(Accounts)]
+struct DepositAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+// No parameter of type `DepositUtxos` – should trigger compile error
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    #[utxos(DepositUtxos)]
+    pub fn deposit<'info>(
+        ctx: Context<'info, DepositAccounts<'info>>,
+        amount: u64,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = (ctx.program_id, amount);
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+use arch_program::program_error::ProgramError;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_account_parser::Context;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(Accounts)]
+struct AdminAccounts<'info> {
+    #[account(signer)]
+    admin: Account<'info, u64>,
+}
+
+// Guards shared by every admin instruction.
+fn is_admin<'info>(
+    ctx: &Context<'_, '_, '_, 'info, AdminAccounts<'info>>,
+) -> Result<(), ProgramError> {
+    let _ = &ctx.accounts.admin;
+    Ok(())
+}
+
+fn not_paused<'info>(
+    ctx: &Context<'_, '_, '_, 'info, AdminAccounts<'info>>,
+) -> Result<(), ProgramError> {
+    let _ = ctx.program_id;
+    Ok(())
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    #[access_control(is_admin(&ctx), not_paused(&ctx))]
+    pub fn set_fee<'info>(
+        ctx: Context<'info, AdminAccounts<'info>>,
+        fee: u8,
+    ) -> Result<(), ProgramError> {
+        let _ = (ctx.program_id, fee);
+        Ok(())
+    }
+
+    pub mod emergency {
+        use super::*;
+
+        #[access_control(is_admin(&ctx))]
+        pub fn pause<'info>(ctx: Context<'info, AdminAccounts<'info>>) -> Result<(), ProgramError> {
+            let _ = ctx.program_id;
+            Ok(())
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use saturn_account_macros::Accounts;
+use saturn_program_macros::declare_id;
+use saturn_program_macros::saturn_program;
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(Accounts)]
+struct VaultAccounts<'info> {
+    #[account(signer)]
+    caller: arch_program::account::AccountInfo<'info>,
+
+    #[account(seeds = &[b"vault"], program_id = crate::ID)]
+    vault: arch_program::account::AccountInfo<'info>,
+}
+
+mod instruction {
+    use super::*;
+    #[derive(BorshSerialize, BorshDeserialize)]
+    pub enum Instr {
+        Withdraw(u8),
+    }
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+    pub fn withdraw<'info>(
+        ctx: Context<'info, VaultAccounts<'info>>,
+        _params: u8,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        // Bump found while parsing `vault`, no need to re-derive the PDA.
+        let _bump: u8 = ctx.bumps.vault;
+        // Seeds recorded while parsing `vault`, bump last.
+        let signer_seeds: Vec<&[u8]> = ctx.signer_seeds("vault")?;
+        let _signers: &[&[&[u8]]] = &[&signer_seeds];
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,56 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("5X4RQFAEUKu9yyR9pv8uXcEUTdEK7m2YkdEYY5EYXPLH");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+mod instruction {
+    use super::*;
+    #[derive(BorshSerialize, BorshDeserialize)]
+    pub enum Instr {
+        Ping(u8),
+    }
+}
+
+// Instruction data matching no handler is forwarded to `forward` instead of
+// being rejected by the dispatcher.
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    pub fn ping<'info>(
+        ctx: Context<'info, DummyAccounts<'info>>,
+        nonce: u8,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = (ctx.program_id, nonce);
+        Ok(())
+    }
+
+    pub mod router {
+        use arch_program::{account::AccountInfo, pubkey::Pubkey};
+
+        #[fallback]
+        pub fn forward<'info>(
+            program_id: &Pubkey,
+            accounts: &'info [AccountInfo<'info>],
+            instruction_data: &[u8],
+        ) -> arch_program::entrypoint::ProgramResult {
+            let _ = (program_id, accounts, instruction_data);
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    // Unknown discriminator and data too short to hold one both reach the fallback.
+    let program_id = arch_program::pubkey::Pubkey::default();
+    assert!(process_instruction(&program_id, &[], &[0xff; 8]).is_ok());
+    assert!(process_instruction(&program_id, &[], &[1, 2]).is_ok());
+}
@@ -0,0 +1,43 @@
+use arch_program::program_error::ProgramError;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("5X4RQFAEUKu9yyR9pv8uXcEUTdEK7m2YkdEYY5EYXPLH");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+    use arch_program::{account::AccountInfo, pubkey::Pubkey};
+
+    pub fn ping<'info>(
+        ctx: Context<'info, DummyAccounts<'info>>,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = ctx.program_id;
+        Ok(())
+    }
+
+    #[fallback]
+    pub fn explode<'info>(
+        _program_id: &Pubkey,
+        _accounts: &'info [AccountInfo<'info>],
+        _instruction_data: &[u8],
+    ) -> arch_program::entrypoint::ProgramResult {
+        panic!("This should be an error")
+    }
+}
+
+fn main() {
+    // The entrypoint wrapper turns the panic into `HandlerPanicked` (code 3).
+    let program_id = arch_program::pubkey::Pubkey::default();
+    assert_eq!(
+        __saturn_entrypoint(&program_id, &[], &[1, 2]),
+        Err(ProgramError::Custom(3))
+    );
+}
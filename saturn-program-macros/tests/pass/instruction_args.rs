@@ -0,0 +1,48 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use saturn_account_macros::Accounts;
+use saturn_program_macros::declare_id;
+use saturn_program_macros::saturn_program;
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct OpenPositionParams {
+    pub position_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: OpenPositionParams)]
+struct OpenPosition<'info> {
+    #[account(signer)]
+    owner: arch_program::account::AccountInfo<'info>,
+
+    // One PDA per position, keyed by the id passed in the instruction.
+    #[account(seeds = &[b"position", params.position_id.to_le_bytes().as_ref()], program_id = crate::ID)]
+    position: arch_program::account::AccountInfo<'info>,
+}
+
+mod instruction {
+    use super::*;
+    #[derive(BorshSerialize, BorshDeserialize)]
+    pub enum Instr {
+        OpenPosition(OpenPositionParams),
+    }
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+    pub fn open_position<'info>(
+        ctx: Context<'info, OpenPosition<'info>>,
+        params: OpenPositionParams,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _seeds: &[&[u8]] = &[
+            b"position",
+            &params.position_id.to_le_bytes(),
+            &[ctx.bumps.position],
+        ];
+        Ok(())
+    }
+}
+
+fn main() {}
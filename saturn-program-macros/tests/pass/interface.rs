@@ -0,0 +1,64 @@
+use arch_program::account::AccountInfo;
+use arch_program::entrypoint::ProgramResult;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::{Context, CpiContext};
+use saturn_program_macros::{declare_id, interface, saturn_program};
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(signer)]
+    pub trader: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub pool: AccountInfo<'info>,
+}
+
+// Shared by every pool program and the routers calling them.
+#[interface]
+pub trait Pool {
+    fn swap<'info>(ctx: Context<'info, Swap<'info>>, amount_in: u64, min_out: u64)
+        -> ProgramResult;
+}
+
+// A pool program implementing the interface.
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    pub fn swap<'info>(
+        ctx: Context<'info, Swap<'info>>,
+        amount_in: u64,
+        min_out: u64,
+    ) -> ProgramResult {
+        let _ = (ctx.program_id, amount_in, min_out);
+        Ok(())
+    }
+}
+
+// Fails to compile if `handlers::swap` drifts from the interface.
+pub struct PoolProgram;
+
+impl Pool for PoolProgram {
+    fn swap<'info>(
+        ctx: saturn_account_parser::Context<'_, '_, '_, 'info, Swap<'info>>,
+        amount_in: u64,
+        min_out: u64,
+    ) -> ProgramResult {
+        handlers::swap(ctx, amount_in, min_out)
+    }
+}
+
+// A router forwarding a swap to whichever pool program it is given.
+fn route<'info>(
+    pool_program: AccountInfo<'info>,
+    accounts: Swap<'info>,
+    amount_in: u64,
+) -> ProgramResult {
+    pool::cpi::swap(CpiContext::new(pool_program, accounts), amount_in, 0)
+}
+
+fn main() {
+    let _ = route;
+}
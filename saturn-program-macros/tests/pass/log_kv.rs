@@ -0,0 +1,52 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_account_parser::log_kv::{decode_kv, decode_kvs, log_line, KvValue, LogValue};
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("5X4RQFAEUKu9yyR9pv8uXcEUTdEK7m2YkdEYY5EYXPLH");
+
+#[derive(Accounts)]
+struct SwapAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    pub fn swap<'info>(
+        _ctx: Context<'info, SwapAccounts<'info>>,
+        amount_in: u64,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let idx: usize = 3;
+        saturn_account_parser::log_kv!(action = "swap", shard = idx, amount_in = amount_in);
+        Ok(())
+    }
+}
+
+fn main() {
+    let note = String::from("say \"hi\"\nback\\slash");
+    let line = log_line(&[
+        ("action", &"swap" as &dyn LogValue),
+        ("shard", &3usize),
+        ("delta", &-42i64),
+        ("ok", &true),
+        ("note", &note),
+    ]);
+    assert!(line.starts_with("kv: action=\"swap\" shard=3 delta=-42 ok=true note="));
+
+    let fields = decode_kv(&format!("Program log: {line}")).expect("kv line");
+    assert_eq!(fields["action"].as_str(), Some("swap"));
+    assert_eq!(fields["shard"].as_u64(), Some(3));
+    assert_eq!(fields["delta"], KvValue::Int(-42));
+    assert_eq!(fields["ok"].as_bool(), Some(true));
+    assert_eq!(fields["note"].as_str(), Some(note.as_str()));
+
+    let logs = [
+        "Program log: hello",
+        line.as_str(),
+        "kv: broken=\"unterminated",
+    ];
+    assert_eq!(decode_kvs(&logs).len(), 1);
+}
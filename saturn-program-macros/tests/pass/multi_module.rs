@@ -0,0 +1,71 @@
+use arch_program::program_error::ProgramError;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program, saturn_program_module};
+
+declare_id!("5X4RQFAEUKu9yyR9pv8uXcEUTdEK7m2YkdEYY5EYXPLH");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+// Each module would normally live in its own file.
+mod pool {
+    #[saturn_program_module]
+    pub mod handlers {
+        use crate::DummyAccounts;
+
+        pub fn deposit<'info>(
+            ctx: Context<'info, DummyAccounts<'info>>,
+            amount: u64,
+        ) -> Result<(), arch_program::program_error::ProgramError> {
+            let _ = (ctx.program_id, amount);
+            Ok(())
+        }
+    }
+}
+
+mod admin {
+    #[saturn_program_module]
+    pub mod handlers {
+        use crate::DummyAccounts;
+
+        pub fn set_fee<'info>(
+            ctx: Context<'info, DummyAccounts<'info>>,
+            fee: u16,
+        ) -> Result<(), arch_program::program_error::ProgramError> {
+            let _ = (ctx.program_id, fee);
+            Ok(())
+        }
+    }
+}
+
+#[saturn_program(modules = [pool::handlers, admin::handlers])]
+mod program {
+    use super::*;
+
+    pub fn ping<'info>(
+        ctx: Context<'info, DummyAccounts<'info>>,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = ctx.program_id;
+        Ok(())
+    }
+}
+
+fn main() {
+    let program_id = arch_program::pubkey::Pubkey::default();
+
+    // A module instruction reaches its handler's decoding: the amount is missing.
+    let deposit = pool::handlers::__private::Deposit::DISCRIMINATOR;
+    assert!(matches!(
+        process_instruction(&program_id, &[], &deposit),
+        Err(ProgramError::BorshIoError(_))
+    ));
+
+    assert_eq!(
+        process_instruction(&program_id, &[], &[0xff; 8]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
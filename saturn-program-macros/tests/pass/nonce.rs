@@ -0,0 +1,48 @@
+use arch_program::program_error::ProgramError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct UserState {
+    pub last_nonce: u64,
+    pub balance: u64,
+}
+
+#[derive(Accounts)]
+struct WithdrawAccounts<'info> {
+    #[account(signer)]
+    user: Account<'info, u64>,
+    #[account(mut)]
+    user_state: Account<'info, UserState>,
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    #[nonce(account = user_state, field = last_nonce)]
+    pub fn withdraw<'info>(
+        ctx: Context<'info, WithdrawAccounts<'info>>,
+        nonce: u64,
+        amount: u64,
+    ) -> Result<(), ProgramError> {
+        let _ = nonce;
+        ctx.accounts.user_state.balance -= amount;
+        Ok(())
+    }
+
+    #[nonce(account = user_state, field = last_nonce, arg = sequence)]
+    pub fn ping<'info>(
+        ctx: Context<'info, WithdrawAccounts<'info>>,
+        sequence: u64,
+    ) -> Result<(), ProgramError> {
+        let _ = (ctx.program_id, sequence);
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("5X4RQFAEUKu9yyR9pv8uXcEUTdEK7m2YkdEYY5EYXPLH");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    // Keeps its wire value if the handler is renamed.
+    #[instruction(discriminant = 7)]
+    pub fn deposit<'info>(
+        ctx: Context<'info, DummyAccounts<'info>>,
+        amount: u64,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = (ctx.program_id, amount);
+        Ok(())
+    }
+
+    pub fn withdraw<'info>(
+        ctx: Context<'info, DummyAccounts<'info>>,
+        amount: u64,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = (ctx.program_id, amount);
+        Ok(())
+    }
+}
+
+fn main() {
+    assert_eq!(
+        handlers::__private::Deposit::DISCRIMINATOR,
+        [7, 0, 0, 0, 0, 0, 0, 0]
+    );
+    // Unpinned handlers keep the hash of their name.
+    assert_eq!(
+        handlers::__private::Withdraw::DISCRIMINATOR,
+        [183, 18, 70, 156, 148, 109, 161, 34]
+    );
+}
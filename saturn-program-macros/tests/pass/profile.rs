@@ -0,0 +1,27 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::declare_id;
+use saturn_program_macros::saturn_program;
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+// Every phase of every handler logs the compute units it consumed.
+#[saturn_program(profile)]
+mod handlers {
+    use super::*;
+    pub fn deposit<'info>(
+        ctx: Context<'info, DummyAccounts<'info>>,
+        _amount: u64,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = ctx.program_id;
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,59 @@
+use arch_program::program_error::ProgramError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq)]
+pub enum ConfigState {
+    Uninitialized,
+    Active,
+    Paused,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Config {
+    pub status: ConfigState,
+    pub fee: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Pool {
+    pub state: u8,
+}
+
+pub const POOL_OPEN: u8 = 1;
+
+#[derive(Accounts)]
+struct AdminAccounts<'info> {
+    #[account(signer)]
+    admin: Account<'info, u64>,
+    #[account(mut)]
+    config: Account<'info, Config>,
+    pool: Account<'info, Pool>,
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+    use ConfigState::{Active, Paused};
+
+    #[require_state(config.status => Active, pool => POOL_OPEN)]
+    pub fn set_fee<'info>(
+        mut ctx: Context<'info, AdminAccounts<'info>>,
+        fee: u16,
+    ) -> Result<(), ProgramError> {
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    #[require_state(config.status => Active | Paused)]
+    pub fn pause<'info>(ctx: Context<'info, AdminAccounts<'info>>) -> Result<(), ProgramError> {
+        let _ = ctx.program_id;
+        Ok(())
+    }
+}
+
+fn main() {}
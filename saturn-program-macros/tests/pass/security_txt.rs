@@ -0,0 +1,42 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("5X4RQFAEUKu9yyR9pv8uXcEUTdEK7m2YkdEYY5EYXPLH");
+
+saturn_account_parser::security_txt! {
+    name: "Example",
+    project_url: "https://example.com",
+    contacts: "email:security@example.com",
+    policy: "https://example.com/security",
+    source_revision: concat!("abc", "123"),
+}
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    pub fn ping<'info>(
+        ctx: Context<'info, DummyAccounts<'info>>,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = ctx.program_id;
+        Ok(())
+    }
+}
+
+fn main() {
+    let fields = saturn_account_parser::security_txt::parse(security_txt.as_bytes())
+        .expect("embedded security.txt");
+    assert_eq!(fields.len(), 5);
+    assert_eq!(fields[0], ("name".to_string(), "Example".to_string()));
+    assert_eq!(
+        fields[4],
+        ("source_revision".to_string(), "abc123".to_string())
+    );
+}
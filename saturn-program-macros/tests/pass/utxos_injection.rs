@@ -0,0 +1,44 @@
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+use saturn_utxo_parser::UtxoParser;
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(Accounts)]
+struct DepositAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+#[derive(UtxoParser)]
+#[utxo_accounts(DepositAccounts)]
+struct DepositUtxos {
+    #[utxo(value = 10_000)]
+    fee: saturn_bitcoin_transactions::utxo_info::UtxoInfo,
+}
+
+// Clients send the `Vec<UtxoMeta>` in place of `utxos`; the handler receives
+// it already parsed against the validated accounts.
+#[saturn_program]
+mod handlers {
+    use super::*;
+
+    #[utxos(DepositUtxos)]
+    pub fn deposit<'info>(
+        ctx: Context<'info, DepositAccounts<'info>>,
+        amount: u64,
+        utxos: DepositUtxos,
+    ) -> Result<(), arch_program::program_error::ProgramError> {
+        let _ = (ctx.program_id, amount, utxos.fee);
+        Ok(())
+    }
+}
+
+fn main() {
+    // The instruction struct carries the raw metas.
+    let _ = handlers::__private::Deposit {
+        amount: 1,
+        utxos: Vec::<arch_program::utxo::UtxoMeta>::new(),
+    };
+}
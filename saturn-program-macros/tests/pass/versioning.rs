@@ -0,0 +1,57 @@
+use arch_program::program_error::ProgramError;
+use saturn_account_macros::Accounts;
+use saturn_account_parser::codec::Account;
+use saturn_program_macros::{declare_id, saturn_program};
+
+declare_id!("8YE2m8RGmFjyWkHfMV6aA1eeaoAj8ZqEXnoY6v1WKEwd");
+
+#[derive(Accounts)]
+struct DummyAccounts<'info> {
+    #[account(signer)]
+    caller: Account<'info, u64>,
+}
+
+// Version 1 clients sent `set_fee(fee: u8)`; version 2 added a `max_fee`.
+// Upgrade functions live outside the module: its public functions are all
+// instruction handlers.
+pub fn upgrade_set_fee(version: u8, data: &[u8]) -> Result<(u8, u16), ProgramError> {
+    match (version, data) {
+        (1, [fee]) => Ok((*fee, u16::MAX)),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[saturn_program(version = 2)]
+mod handlers {
+    use super::*;
+
+    pub fn ping<'info>(ctx: Context<'info, DummyAccounts<'info>>) -> Result<(), ProgramError> {
+        let _ = ctx.program_id;
+        Ok(())
+    }
+
+    #[since(version = 2, upgrade = crate::upgrade_set_fee)]
+    pub fn set_fee<'info>(
+        ctx: Context<'info, DummyAccounts<'info>>,
+        fee: u8,
+        max_fee: u16,
+    ) -> Result<(), ProgramError> {
+        let _ = (ctx.program_id, fee, max_fee);
+        Ok(())
+    }
+
+    // Only version 2 clients can call this one.
+    #[since(version = 2)]
+    pub fn close<'info>(ctx: Context<'info, DummyAccounts<'info>>) -> Result<(), ProgramError> {
+        let _ = ctx.program_id;
+        Ok(())
+    }
+}
+
+fn main() {
+    let program_id = arch_program::pubkey::Pubkey::default();
+    // Versions outside 1..=2 and data without a version byte are rejected.
+    assert!(process_instruction(&program_id, &[], &[3; 9]).is_err());
+    assert!(process_instruction(&program_id, &[], &[0; 9]).is_err());
+    assert!(process_instruction(&program_id, &[], &[2; 8]).is_err());
+}
@@ -0,0 +1,83 @@
+//! Attribute expressions reading the accounts struct.
+//!
+//! Such expressions (`value = accounts.config.load()?.fee_sats`) are evaluated
+//! once, right before the field is extracted, in a context where `?`
+//! propagates a `ProgramError`; the predicates then read the bound value.
+//! Borrow guards returned by `load()` therefore only live for the evaluation
+//! and never across the search.
+
+use crate::ir::Field;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::visit::Visit;
+
+/// Whether `expr` reads `accounts` or uses `?`.
+fn needs_hoisting(expr: &syn::Expr) -> bool {
+    struct Finder(bool);
+    impl<'ast> Visit<'ast> for Finder {
+        fn visit_expr_path(&mut self, expr_path: &'ast syn::ExprPath) {
+            if expr_path.qself.is_none() && expr_path.path.is_ident("accounts") {
+                self.0 = true;
+            }
+            syn::visit::visit_expr_path(self, expr_path);
+        }
+
+        fn visit_expr_try(&mut self, expr_try: &'ast syn::ExprTry) {
+            self.0 = true;
+            syn::visit::visit_expr_try(self, expr_try);
+        }
+    }
+
+    let mut finder = Finder(false);
+    finder.visit_expr(expr);
+    finder.0
+}
+
+/// Copy of `field` whose accounts expressions are replaced by the locals
+/// bound by the returned snippet. `counter` keeps the locals unique across
+/// the struct.
+pub fn hoist(field: &Field, counter: &mut usize) -> (Field, TokenStream) {
+    let mut field = field.clone();
+    let name = field.ident.to_string();
+    let mut bindings = Vec::new();
+
+    for expr in field.attr.predicate_exprs_mut() {
+        if !needs_hoisting(expr) {
+            continue;
+        }
+        let local = syn::Ident::new(&format!("__accounts_expr_{}", counter), Span::call_site());
+        *counter += 1;
+
+        bindings.push(quote! {
+            let #local = saturn_utxo_parser::eval_accounts_expr(|| Ok(#expr)).map_err(|error| {
+                saturn_utxo_parser::ParseFailure::new(
+                    #name,
+                    None,
+                    ErrorCode::AccountsExpressionFailed,
+                )
+                .with_error(error)
+            })?;
+        });
+        *expr = syn::parse_quote!(#local);
+    }
+
+    (field, quote! { #( #bindings )* })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn detects_accounts_and_try() {
+        assert!(needs_hoisting(&parse_quote!(
+            accounts.config.load()?.fee_sats
+        )));
+        assert!(needs_hoisting(&parse_quote!(accounts.fee)));
+        assert!(needs_hoisting(&parse_quote!(fee_of(&accounts))));
+        assert!(needs_hoisting(&parse_quote!(lookup()?)));
+        assert!(!needs_hoisting(&parse_quote!(fee.value * 2)));
+        assert!(!needs_hoisting(&parse_quote!(params.accounts)));
+    }
+}
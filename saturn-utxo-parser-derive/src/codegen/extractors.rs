@@ -5,31 +5,192 @@
 //! one-to-one.
 
 use crate::codegen::predicate;
-use crate::ir::{Field, FieldKind};
+use crate::ir::{ArrayLen, Field, FieldKind, Group};
 use quote::{format_ident, quote};
 
 /// Helper: choose the `ErrorCode` variant that should be used when the field
 /// fails to match **without** needing the specialised RuneId/RuneAmount logic.
-fn base_error_variant(attr: &crate::ir::UtxoAttr) -> proc_macro2::TokenStream {
+pub fn base_error_variant(attr: &crate::ir::UtxoAttr) -> proc_macro2::TokenStream {
     // Anchored fields implicitly require `runes == none` even if the user did
     // not specify the `runes` flag.  Therefore their failure mode should be
     // `InvalidRunesPresence` when the predicate does not match.
     if attr.anchor_ident.is_some() && attr.runes.is_none() {
         return quote! { ErrorCode::InvalidRunesPresence };
     }
-    if attr.rune_id_expr.is_some() {
+    if attr.script_expr.is_some() || attr.address_script.is_some() || attr.owned_by.is_some() {
+        quote! { ErrorCode::InvalidScriptPubkey }
+    } else if attr.rune_id_expr.is_some()
+        || attr.rune_id_in.is_some()
+        || !attr.contains_rune_ids.is_empty()
+    {
         quote! { ErrorCode::InvalidRuneId }
-    } else if attr.rune_amount_expr.is_some() {
+    } else if attr.has_rune_amount_constraint() {
         quote! { ErrorCode::InvalidRuneAmount }
+    } else if attr.rune_count.is_some() {
+        quote! { ErrorCode::InvalidRuneCount }
     } else if attr.runes.is_some() {
         quote! { ErrorCode::InvalidRunesPresence }
-    } else if attr.value.is_some() {
+    } else if attr.value.is_some()
+        || attr.min_value.is_some()
+        || attr.max_value.is_some()
+        || attr.value_in.is_some()
+    {
         quote! { ErrorCode::InvalidUtxoValue }
+    } else if attr.min_confirmations.is_some() {
+        quote! { ErrorCode::InsufficientConfirmations }
+    } else if attr.check.is_some() {
+        quote! { ErrorCode::UtxoCheckFailed }
     } else {
         quote! { ErrorCode::MissingRequiredUtxo }
     }
 }
 
+/// The `ParseFailure` reported when the field fails with `code` at
+/// `utxo_index` (an `Option<usize>` expression). `try_utxos` turns it into
+/// the program's own error from `error = <expr>` when given, `code` otherwise.
+pub fn field_error(
+    field: &Field,
+    code: &proc_macro2::TokenStream,
+    utxo_index: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let name = field.ident.to_string();
+    let failure = quote! { saturn_utxo_parser::ParseFailure::new(#name, #utxo_index, #code) };
+    match &field.attr.error {
+        Some(error) => quote! {
+            #failure.with_error(::core::convert::Into::<
+                arch_program::program_error::ProgramError,
+            >::into(#error))
+        },
+        None => failure,
+    }
+}
+
+/// Position in the caller's inputs of the `utxo` in scope.
+fn utxo_position() -> proc_macro2::TokenStream {
+    quote! { saturn_utxo_parser::ParseFailure::position(__inputs, &utxo.meta) }
+}
+
+/// Expression loading the `UtxoInfo` behind `meta` from `__source` (converted
+/// on demand, or read from the batch buffer); a failure is reported on the
+/// field (or group) called `name`.
+fn load_utxo(name: &str, meta: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        saturn_utxo_parser::UtxoInfoSource::load(&__source, #meta).map_err(|error| {
+            saturn_utxo_parser::ParseFailure::new(
+                #name,
+                saturn_utxo_parser::ParseFailure::position(__inputs, #meta),
+                ErrorCode::UtxoInfoUnavailable,
+            )
+            .with_error(error)
+        })?
+    }
+}
+
+/// Up-front checks for the fields bound to a fixed input position with
+/// `index = N`: the slice must be long enough for every one of them.
+pub fn pinned_bounds_checks(fields: &[&Field]) -> proc_macro2::TokenStream {
+    let checks = fields.iter().map(|field| {
+        let index = field.attr.index;
+        let fail = field_error(
+            field,
+            &quote! { ErrorCode::UtxoIndexOutOfBounds },
+            &quote! { Some(#index) },
+        );
+        quote! {
+            if __all_utxos.len() <= #index {
+                return Err(#fail);
+            }
+        }
+    });
+    quote! { #( #checks )* }
+}
+
+/// Checks on the sum of the values captured by a collection field, from the
+/// `total_value` / `total_value_min` / `total_value_max` keys.
+pub fn total_value_checks(field: &Field) -> proc_macro2::TokenStream {
+    let attr = &field.attr;
+    let ident = &field.ident;
+    let bounds = [
+        (&attr.total_value, quote! { != }),
+        (&attr.total_value_min, quote! { < }),
+        (&attr.total_value_max, quote! { > }),
+    ];
+    let checks: Vec<_> = bounds
+        .into_iter()
+        .filter_map(|(bound, op)| bound.as_ref().map(|bound| (bound, op)))
+        .map(|(bound, op)| quote! { __total_value #op (#bound) as u128 })
+        .collect();
+    if checks.is_empty() {
+        return quote! {};
+    }
+    let fail = field_error(
+        field,
+        &quote! { ErrorCode::InvalidTotalValue },
+        &quote! { None },
+    );
+    quote! {
+        let __total_value: u128 = #ident.iter().map(|utxo| utxo.value as u128).sum();
+        if #( #checks )||* {
+            return Err(#fail);
+        }
+    }
+}
+
+/// Bounds of an `Option<Vec<UtxoInfo>>` block: the number of inputs it may
+/// take (`max`, unbounded by default) and the check that a supplied block is
+/// complete (`min`).
+fn block_bounds(field: &Field) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let attr = &field.attr;
+    let limit = match &attr.max_count {
+        Some(max) => quote! { (#max) as usize },
+        None => quote! { usize::MAX },
+    };
+    let min_check = attr.min_count.as_ref().map(|min| {
+        let fail = field_error(
+            field,
+            &quote! { ErrorCode::IncompleteUtxoGroup },
+            &quote! { None },
+        );
+        quote! {
+            if __block.len() < (#min) as usize {
+                return Err(#fail);
+            }
+        }
+    });
+    (limit, quote! { #min_check })
+}
+
+/// Cardinality checks for a Vec field holding `len` UTXOs, from the `min` /
+/// `max` keys.
+pub fn count_checks(field: &Field, len: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let attr = &field.attr;
+    let too_few = field_error(
+        field,
+        &quote! { ErrorCode::MissingRequiredUtxo },
+        &quote! { None },
+    );
+    let too_many = field_error(
+        field,
+        &quote! { ErrorCode::UnexpectedExtraUtxos },
+        &quote! { None },
+    );
+    let min_check = attr.min_count.as_ref().map(|min| {
+        quote! {
+            if #len < (#min) as usize {
+                return Err(#too_few);
+            }
+        }
+    });
+    let max_check = attr.max_count.as_ref().map(|max| {
+        quote! {
+            if #len > (#max) as usize {
+                return Err(#too_many);
+            }
+        }
+    });
+    quote! { #min_check #max_check }
+}
+
 /// Build the `TokenStream` that initialises the given field using a variable
 /// named `remaining` (`Vec<UtxoInfo>`) and assuming a variable `accounts` in
 /// scope.  `predicate` **must** be an expression that can be evaluated for a
@@ -40,82 +201,79 @@ pub fn build_extractor(
 ) -> proc_macro2::TokenStream {
     let ident = &field.ident;
     let attr = &field.attr;
+    let elem_ty = &field.elem_ty;
     // Pre-compute the specific error variant for predicate mismatch.
+    let name = ident.to_string();
+    let at_utxo = utxo_position();
     let err_variant = base_error_variant(attr);
+    let fail_mismatch = field_error(field, &err_variant, &at_utxo);
+    let fail_missing = field_error(
+        field,
+        &quote! { ErrorCode::MissingRequiredUtxo },
+        &quote! { None },
+    );
+    let fail_rune_amount = field_error(field, &quote! { ErrorCode::InvalidRuneAmount }, &at_utxo);
+    let fail_rune_id = field_error(field, &quote! { ErrorCode::InvalidRuneId }, &at_utxo);
+    let total_checks = total_value_checks(field);
 
-    match field.kind {
+    match &field.kind {
         // ------------------------------------------------------------------
         // Single `UtxoInfo`
         // ------------------------------------------------------------------
         FieldKind::Single => {
-            let anchor_snippet = if let Some(anchor_ident) = &attr.anchor_ident {
-                let anchor_ident_tok = anchor_ident.clone();
-                quote! {
-                    let _anchor_target = &accounts.#anchor_ident_tok;
-                    // Compile-time assertion: the anchor target for a scalar UTXO field **must** itself
-                    // be "scalar‐like", i.e. directly convertible via `ToAccountInfo`.  This produces a
-                    // clear error message such as:
-                    //     "the trait `ToAccountInfo` is not implemented for `Vec<...>`"
-                    // when the developer accidentally anchors to a `Vec` inside the `Accounts` struct.
-                    {
-                        fn _anchor_scalar_check<'info, T>(_: &T)
-                        where
-                            T: saturn_account_parser::ToAccountInfo<'info>,
-                        {
-                        }
-                        _anchor_scalar_check(_anchor_target);
-                    }
-                    let _anchor_ix = arch_program::system_instruction::anchor(
-                        saturn_account_parser::ToAccountInfo::to_account_info(&_anchor_target).key,
-                        #ident.meta.txid_big_endian(),
-                        #ident.meta.vout(),
-                    );
-                }
-            } else {
-                quote! {}
-            };
+            let anchor_snippet = single_anchor_snippet(field);
 
             // Choose correct error variant if predicate fails.
-            let err_on_mismatch = if attr.value.is_none()
-                && attr.runes.is_none()
-                && attr.rune_id_expr.is_none()
-                && attr.rune_amount_expr.is_none()
-            {
+            let err_on_mismatch = if !attr.has_predicates() {
                 // No predicates – only order matters
                 quote! { ErrorCode::StrictOrderMismatch }
             } else {
                 // Map to the specific predicate-related error
                 err_variant.clone()
             };
+            let fail_on_mismatch = field_error(field, &err_on_mismatch, &at_utxo);
 
-            // Special handling when both rune_id and rune_amount are specified to distinguish
-            // between ID vs amount mismatch at runtime.
-            let rune_mismatch_logic = if let (Some(id_expr), Some(_)) =
-                (&attr.rune_id_expr, &attr.rune_amount_expr)
+            // Special handling when both rune_id and a rune amount constraint (exact or
+            // bounded) are specified to distinguish between ID vs amount mismatch at runtime.
+            let rune_mismatch_logic = if let (Some(id_expr), true) =
+                (&attr.rune_id_expr, attr.has_rune_amount_constraint())
             {
                 quote! {
                     if !(#predicate) {
                         // Decide whether the ID matched but amount mismatched, or ID mismatched.
                         if utxo.rune_amount(&(#id_expr)).is_some() {
-                            return Err(ProgramError::Custom(ErrorCode::InvalidRuneAmount.into()));
+                            return Err(#fail_rune_amount);
                         } else {
-                            return Err(ProgramError::Custom(ErrorCode::InvalidRuneId.into()));
+                            return Err(#fail_rune_id);
                         }
                     }
                 }
             } else {
                 quote! {
                     if !(#predicate) {
-                        return Err(ProgramError::Custom(#err_on_mismatch.into()));
+                        return Err(#fail_on_mismatch);
                     }
                 }
             };
 
+            // `index = N` fields read their fixed position (bounds are checked
+            // up front) and leave the cursor untouched.
+            if let Some(index) = attr.index {
+                let load = load_utxo(&name, quote! { &__all_utxos[#index] });
+                return quote! {
+                    let utxo: #elem_ty = #load;
+                    #rune_mismatch_logic
+                    let #ident = utxo;
+                    #anchor_snippet
+                };
+            }
+
+            let load = load_utxo(&name, quote! { &utxos[idx] });
             quote! {
                 if idx >= total {
-                    return Err(ProgramError::Custom(ErrorCode::MissingRequiredUtxo.into()));
+                    return Err(#fail_missing);
                 }
-                let utxo = saturn_utxo_parser::meta_to_info(&utxos[idx])?;
+                let utxo: #elem_ty = #load;
                 #rune_mismatch_logic
                 let #ident = utxo;
                 idx += 1;
@@ -126,35 +284,12 @@ pub fn build_extractor(
         // Optional UtxoInfo (Option)
         // ------------------------------------------------------------------
         FieldKind::Optional => {
-            let anchor_snippet = if let Some(anchor_ident) = &attr.anchor_ident {
-                let anchor_ident_tok = anchor_ident.clone();
-                quote! {
-                    if let Some(__opt_utxo) = #ident.as_ref() {
-                        let _anchor_target = &accounts.#anchor_ident_tok;
-                        // Compile-time assertion identical to the one for scalar fields – ensure the
-                        // anchor target itself is scalar and not a collection.
-                        {
-                            fn _anchor_scalar_check<'info, T>(_: &T)
-                            where
-                                T: saturn_account_parser::ToAccountInfo<'info>,
-                            {
-                            }
-                            _anchor_scalar_check(_anchor_target);
-                        }
-                        let _anchor_ix = arch_program::system_instruction::anchor(
-                            saturn_account_parser::ToAccountInfo::to_account_info(&_anchor_target).key,
-                            __opt_utxo.meta.txid_big_endian(),
-                            __opt_utxo.meta.vout(),
-                        );
-                    }
-                }
-            } else {
-                quote! {}
-            };
+            let anchor_snippet = optional_anchor_snippet(field);
+            let load = load_utxo(&name, quote! { &utxos[idx] });
 
             quote! {
-                let #ident: Option<saturn_bitcoin_transactions::utxo_info::UtxoInfo> = if idx < total {
-                    let utxo = saturn_utxo_parser::meta_to_info(&utxos[idx])?;
+                let #ident: Option<#elem_ty> = if idx < total {
+                    let utxo: #elem_ty = #load;
                     if (#predicate) {
                         idx += 1;
                         Some(utxo)
@@ -168,8 +303,57 @@ pub fn build_extractor(
         // ------------------------------------------------------------------
         // Fixed-length Array
         // ------------------------------------------------------------------
-        FieldKind::Array(len) => {
-            let len_lit = len as usize;
+        FieldKind::Array(ArrayLen::Const(len)) => {
+            // The length is only known at monomorphisation, walk the inputs in a loop.
+            let (anchor_preflight, anchor_stmt) = if let Some(anchor_ident) = &attr.anchor_ident {
+                (
+                    quote! {
+                        let _ = {
+                            fn _assert_indexable<T: core::ops::Index<usize>>(_t: &T) {}
+                            _assert_indexable(&accounts.#anchor_ident);
+                        };
+                    },
+                    quote! {
+                        let _anchor_target = &accounts.#anchor_ident[_i];
+                        let _anchor_ix = arch_program::system_instruction::anchor(
+                            saturn_account_parser::ToAccountInfo::to_account_info(&_anchor_target).key,
+                            utxo.meta.txid_big_endian(),
+                            utxo.meta.vout(),
+                        );
+                    },
+                )
+            } else {
+                (quote! {}, quote! {})
+            };
+            let load = load_utxo(&name, quote! { &utxos[idx + _i] });
+
+            quote! {
+                #anchor_preflight
+                // Ensure enough inputs remain.
+                if total < idx + #len {
+                    return Err(#fail_missing);
+                }
+                let #ident: [#elem_ty; #len] = {
+                    let mut __items: Vec<#elem_ty> = Vec::with_capacity(#len);
+                    for _i in 0..#len {
+                        let utxo: #elem_ty = #load;
+                        if !(#predicate) {
+                            return Err(#fail_mismatch);
+                        }
+                        #anchor_stmt
+                        __items.push(utxo);
+                    }
+                    match __items.try_into() {
+                        Ok(items) => items,
+                        Err(_) => unreachable!("exactly {} items were collected", #len),
+                    }
+                };
+                idx += #len;
+                #total_checks
+            }
+        }
+        FieldKind::Array(ArrayLen::Lit(len)) => {
+            let len_lit = *len;
             // If this array UTXO field is anchored, perform a compile-time assertion that the
             // chosen accounts field *can* be indexed.  This emits a trait-bound error that points
             // at the macro input rather than a deep generated loop, giving the user a clearer
@@ -203,11 +387,12 @@ pub fn build_extractor(
                     quote! {}
                 };
 
+                let load = load_utxo(&name, quote! { &utxos[idx + #i] });
                 element_blocks.push(quote! {
                     {
-                        let utxo = saturn_utxo_parser::meta_to_info(&utxos[idx + #i])?;
+                        let utxo: #elem_ty = #load;
                         if !(#predicate) {
-                            return Err(ProgramError::Custom(#err_variant.into()));
+                            return Err(#fail_mismatch);
                         }
                         #anchor_stmt
                         utxo
@@ -220,17 +405,46 @@ pub fn build_extractor(
                 #anchor_preflight
                 // Ensure enough inputs remain.
                 if total < idx + #len_lit {
-                    return Err(ProgramError::Custom(ErrorCode::MissingRequiredUtxo.into()));
+                    return Err(#fail_missing);
                 }
-                let #ident: [saturn_bitcoin_transactions::utxo_info::UtxoInfo; #len_lit] = [
+                let #ident: [#elem_ty; #len_lit] = [
                     #( #element_blocks ),*
                 ];
                 idx += #len_lit;
+                #total_checks
             }
         }
         // ------------------------------------------------------------------
         // Vec
         // ------------------------------------------------------------------
+        // ------------------------------------------------------------------
+        // Optional block Option<Vec<UtxoInfo>>
+        // ------------------------------------------------------------------
+        FieldKind::OptionalVec => {
+            let (limit, min_check) = block_bounds(field);
+            let load = load_utxo(&name, quote! { &utxos[idx + __block.len()] });
+            // Take the run of consecutive matching inputs at the cursor; an
+            // empty run means the block was not supplied.
+            quote! {
+                let #ident: Option<Vec<#elem_ty>> = {
+                    let mut __block: Vec<#elem_ty> = Vec::new();
+                    while __block.len() < #limit && idx + __block.len() < total {
+                        let utxo: #elem_ty = #load;
+                        if !(#predicate) {
+                            break;
+                        }
+                        __block.push(utxo);
+                    }
+                    if __block.is_empty() {
+                        None
+                    } else {
+                        #min_check
+                        idx += __block.len();
+                        Some(__block)
+                    }
+                };
+            }
+        }
         FieldKind::Vec => {
             if let Some(anchor_ident) = &attr.anchor_ident {
                 let anchor_ident_tok = anchor_ident.clone();
@@ -242,17 +456,20 @@ pub fn build_extractor(
                         _assert_indexable(&accounts.#anchor_ident_tok);
                     };
                 };
+                let count_checks = count_checks(field, &quote! { target_len });
+                let load = load_utxo(&name, quote! { &utxos[idx] });
                 quote! {
                     #anchor_preflight
                     let target_len = accounts.#anchor_ident_tok.len();
-                    let mut #ident: Vec<saturn_bitcoin_transactions::utxo_info::UtxoInfo> = Vec::with_capacity(target_len);
+                    #count_checks
+                    let mut #ident: Vec<#elem_ty> = Vec::with_capacity(target_len);
                     for i in 0..target_len {
                         if idx >= total {
-                            return Err(ProgramError::Custom(ErrorCode::MissingRequiredUtxo.into()));
+                            return Err(#fail_missing);
                         }
-                        let utxo = saturn_utxo_parser::meta_to_info(&utxos[idx])?;
+                        let utxo: #elem_ty = #load;
                         if !(#predicate) {
-                            return Err(ProgramError::Custom(#err_variant.into()));
+                            return Err(#fail_mismatch);
                         }
                         let _anchor_target = &accounts.#anchor_ident_tok[i];
                         let _anchor_ix = arch_program::system_instruction::anchor(
@@ -263,31 +480,425 @@ pub fn build_extractor(
                         #ident.push(utxo);
                         idx += 1;
                     }
+                    #total_checks
                 }
             } else if attr.rest {
+                let count_checks = count_checks(field, &quote! { consumed });
+                let load = load_utxo(&name, quote! { &utxos[i] });
                 // `#[utxo(rest)]` must still flag *unexpected* inputs. We therefore
                 // walk over the remaining slice, *collect* those matching the
-                // predicate, but advance the main cursor only for the ones we
-                // actually consumed. That leaves non-matching inputs in place so
-                // the final leftover check can emit `UnexpectedExtraUtxos`.
+                // predicate and record the positions of the others in
+                // `__skipped`, so the final leftover check can emit
+                // `UnexpectedExtraUtxos` for them (and partial parses can
+                // return exactly them).
                 quote! {
-                    let mut #ident: Vec<saturn_bitcoin_transactions::utxo_info::UtxoInfo> = Vec::new();
-
-                    // Remember where the rest segment starts.
-                    let start_idx = idx;
+                    let mut #ident: Vec<#elem_ty> = Vec::new();
                     let mut consumed: usize = 0;
 
-                    for i in start_idx..total {
-                        let utxo = saturn_utxo_parser::meta_to_info(&utxos[i])?;
+                    for i in idx..total {
+                        let utxo: #elem_ty = #load;
                         if (#predicate) {
                             #ident.push(utxo);
                             consumed += 1;
+                        } else {
+                            __skipped.push(i);
+                        }
+                    }
+
+                    #count_checks
+                    #total_checks
+
+                    // Every input was either captured or recorded as skipped.
+                    idx = total;
+                }
+            } else {
+                syn::Error::new(field.span, "Vec field must be either `rest` or `anchor`")
+                    .to_compile_error()
+            }
+        }
+    }
+}
+
+/// Statement binding `_anchor_target` to the accounts field (or, for
+/// `anchor = field[<index>]`, the element of it) the field is anchored to.
+fn anchor_target(field: &Field, anchor_ident: &syn::Ident) -> proc_macro2::TokenStream {
+    match &field.attr.anchor_index {
+        Some(index) => {
+            let fail = field_error(
+                field,
+                &quote! { ErrorCode::AnchorIndexOutOfBounds },
+                &quote! { None },
+            );
+            quote! {
+                let _anchor_target = match accounts.#anchor_ident.get((#index) as usize) {
+                    Some(target) => target,
+                    None => return Err(#fail),
+                };
+            }
+        }
+        None => quote! { let _anchor_target = &accounts.#anchor_ident; },
+    }
+}
+
+/// Anchor handling for a single `UtxoInfo` field bound to `#ident`.
+fn single_anchor_snippet(field: &Field) -> proc_macro2::TokenStream {
+    let ident = &field.ident;
+    if let Some(anchor_ident) = &field.attr.anchor_ident {
+        let target = anchor_target(field, anchor_ident);
+        quote! {
+            #target
+            // Compile-time assertion: the anchor target for a scalar UTXO field **must** itself
+            // be "scalar‐like", i.e. directly convertible via `ToAccountInfo`.  This produces a
+            // clear error message such as:
+            //     "the trait `ToAccountInfo` is not implemented for `Vec<...>`"
+            // when the developer accidentally anchors to a `Vec` inside the `Accounts` struct.
+            {
+                fn _anchor_scalar_check<'info, T>(_: &T)
+                where
+                    T: saturn_account_parser::ToAccountInfo<'info>,
+                {
+                }
+                _anchor_scalar_check(_anchor_target);
+            }
+            let _anchor_ix = arch_program::system_instruction::anchor(
+                saturn_account_parser::ToAccountInfo::to_account_info(&_anchor_target).key,
+                #ident.meta.txid_big_endian(),
+                #ident.meta.vout(),
+            );
+        }
+    } else {
+        quote! {}
+    }
+}
+
+/// Anchor handling for an `Option<UtxoInfo>` field, executed only when the
+/// field was populated.
+fn optional_anchor_snippet(field: &Field) -> proc_macro2::TokenStream {
+    let ident = &field.ident;
+    if let Some(anchor_ident) = &field.attr.anchor_ident {
+        let target = anchor_target(field, anchor_ident);
+        quote! {
+            if let Some(__opt_utxo) = #ident.as_ref() {
+                #target
+                // Compile-time assertion identical to the one for scalar fields – ensure the
+                // anchor target itself is scalar and not a collection.
+                {
+                    fn _anchor_scalar_check<'info, T>(_: &T)
+                    where
+                        T: saturn_account_parser::ToAccountInfo<'info>,
+                    {
+                    }
+                    _anchor_scalar_check(_anchor_target);
+                }
+                let _anchor_ix = arch_program::system_instruction::anchor(
+                    saturn_account_parser::ToAccountInfo::to_account_info(&_anchor_target).key,
+                    __opt_utxo.meta.txid_big_endian(),
+                    __opt_utxo.meta.vout(),
+                );
+            }
+        }
+    } else {
+        quote! {}
+    }
+}
+
+/// Name of the group the `members` belong to, reported by failing groups.
+fn group_name(members: &[&Field]) -> String {
+    members[0]
+        .attr
+        .group
+        .as_ref()
+        .map(|group| group.value())
+        .unwrap_or_default()
+}
+
+/// Move the members of `group`, by now all `Some` or all `None`, into the
+/// `Option` of its generated struct. The members stay in scope for the fields
+/// parsed after the group, as `Option<&UtxoInfo>` into it.
+fn collect_group(group: &Group, members: &[&Field]) -> proc_macro2::TokenStream {
+    let group_ident = &group.ident;
+    let ty_ident = &group.ty_ident;
+    let idents: Vec<&syn::Ident> = members.iter().map(|f| &f.ident).collect();
+    let elem_tys = members.iter().map(|f| &f.elem_ty);
+
+    quote! {
+        let #group_ident: Option<#ty_ident> = match (#( #idents, )*) {
+            (#( Some(#idents), )*) => Some(#ty_ident { #( #idents ),* }),
+            _ => None,
+        };
+        #( let #idents: Option<&#elem_tys> = #group_ident.as_ref().map(|group| &group.#idents); )*
+    }
+}
+
+/// Build the extractor for an all-or-none `group`, producing its
+/// `Option<Name>` field.
+///
+/// The members are matched against consecutive inputs starting at `idx`. If
+/// every member matches the cursor advances past the whole group; if the very
+/// first member does not match, the group is `None` and nothing is consumed.
+/// Any other outcome is a partially supplied group and yields
+/// `ErrorCode::IncompleteUtxoGroup`.
+pub fn build_group_extractor(
+    group: &Group,
+    members: &[&Field],
+    predicates: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let idents: Vec<&syn::Ident> = members.iter().map(|f| &f.ident).collect();
+    let tmp_idents: Vec<syn::Ident> = idents
+        .iter()
+        .map(|ident| format_ident!("__group_{}", ident))
+        .collect();
+    let anchor_snippets = members.iter().map(|f| optional_anchor_snippet(f));
+    let name = group_name(members);
+    let load = load_utxo(&name, quote! { &utxos[group_cursor] });
+    let collect = collect_group(group, members);
+
+    let member_blocks =
+        members
+            .iter()
+            .zip(&tmp_idents)
+            .zip(predicates)
+            .map(|((field, tmp), predicate)| {
+                let elem_ty = &field.elem_ty;
+                quote! {
+                    let #tmp: Option<#elem_ty> =
+                        if group_complete && group_cursor < total {
+                            let utxo: #elem_ty = #load;
+                            if (#predicate) {
+                                group_cursor += 1;
+                                Some(utxo)
+                            } else {
+                                group_complete = false;
+                                None
+                            }
+                        } else {
+                            group_complete = false;
+                            None
+                        };
+                }
+            });
+
+    quote! {
+        let (#( #idents, )*) = {
+            let mut group_cursor = idx;
+            let mut group_complete = true;
+            #( #member_blocks )*
+            // Either every member matched, or the first one did not and
+            // nothing was consumed (all members are `None`).
+            if group_complete || group_cursor == idx {
+                idx = group_cursor;
+                (#( #tmp_idents, )*)
+            } else {
+                // The member that did not match looked at `group_cursor`.
+                return Err(saturn_utxo_parser::ParseFailure::new(
+                    #name,
+                    utxos
+                        .get(group_cursor)
+                        .and_then(|meta| saturn_utxo_parser::ParseFailure::position(__inputs, meta)),
+                    ErrorCode::IncompleteUtxoGroup,
+                ));
+            }
+        };
+        #( #anchor_snippets )*
+        #collect
+    }
+}
+
+/// Expression searching the not yet consumed metas in `remaining` for the
+/// first UTXO matching `predicate`. Evaluates to `Option<#elem_ty>` and
+/// removes the match from `remaining`.
+fn find_in_remaining(
+    name: &str,
+    elem_ty: &syn::Type,
+    predicate: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let load = load_utxo(name, quote! { meta });
+    quote! {
+        {
+            let mut __found: Option<(usize, #elem_ty)> = None;
+            for (pos, meta) in remaining.iter().enumerate() {
+                let utxo: #elem_ty = #load;
+                if (#predicate) {
+                    __found = Some((pos, utxo));
+                    break;
+                }
+            }
+            __found.map(|(pos, utxo)| {
+                remaining.remove(pos);
+                utxo
+            })
+        }
+    }
+}
+
+/// `#[utxo_parser(mode = "unordered")]` counterpart of [`build_extractor`]:
+/// every field searches the inputs left over by the previous fields instead
+/// of consuming them positionally. Assumes a `remaining: Vec<&UtxoMeta>`
+/// variable in scope.
+pub fn build_unordered_extractor(
+    field: &Field,
+    predicate: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let ident = &field.ident;
+    let attr = &field.attr;
+    let elem_ty = &field.elem_ty;
+    let name = ident.to_string();
+    // Nothing was found, so no single input is to blame.
+    let nowhere = quote! { None };
+    let err_variant = base_error_variant(attr);
+    let fail_mismatch = field_error(field, &err_variant, &nowhere);
+    let fail_rune_amount = field_error(field, &quote! { ErrorCode::InvalidRuneAmount }, &nowhere);
+    let fail_rune_id = field_error(field, &quote! { ErrorCode::InvalidRuneId }, &nowhere);
+    let total_checks = total_value_checks(field);
+    let find = find_in_remaining(&name, elem_ty, predicate);
+
+    match &field.kind {
+        FieldKind::Single => {
+            let anchor_snippet = single_anchor_snippet(field);
+
+            // Same rune diagnostics as strict mode: report an amount mismatch
+            // when some input at least holds the requested rune id.
+            let not_found = if let (Some(id_expr), true) =
+                (&attr.rune_id_expr, attr.has_rune_amount_constraint())
+            {
+                let load = load_utxo(&name, quote! { meta });
+                quote! {
+                    let mut __has_id = false;
+                    for meta in remaining.iter() {
+                        let utxo: #elem_ty = #load;
+                        if utxo.rune_amount(&(#id_expr)).is_some() {
+                            __has_id = true;
+                            break;
                         }
                     }
+                    if __has_id {
+                        return Err(#fail_rune_amount);
+                    } else {
+                        return Err(#fail_rune_id);
+                    }
+                }
+            } else {
+                quote! { return Err(#fail_mismatch); }
+            };
 
-                    // Mark only the captured UTXOs as consumed; any others remain
-                    // un-consumed and will trigger the leftover-inputs check.
-                    idx += consumed;
+            quote! {
+                let #ident: #elem_ty = match #find {
+                    Some(utxo) => utxo,
+                    None => { #not_found }
+                };
+                #anchor_snippet
+            }
+        }
+        FieldKind::Optional => {
+            let anchor_snippet = optional_anchor_snippet(field);
+            quote! {
+                let #ident: Option<#elem_ty> = #find;
+                #anchor_snippet
+            }
+        }
+        FieldKind::Array(len) => {
+            let (anchor_preflight, anchor_stmt) = if let Some(anchor_ident) = &attr.anchor_ident {
+                (
+                    quote! {
+                        let _ = {
+                            fn _assert_indexable<T: core::ops::Index<usize>>(_t: &T) {}
+                            _assert_indexable(&accounts.#anchor_ident);
+                        };
+                    },
+                    quote! {
+                        let _anchor_target = &accounts.#anchor_ident[_i];
+                        let _anchor_ix = arch_program::system_instruction::anchor(
+                            saturn_account_parser::ToAccountInfo::to_account_info(&_anchor_target).key,
+                            utxo.meta.txid_big_endian(),
+                            utxo.meta.vout(),
+                        );
+                    },
+                )
+            } else {
+                (quote! {}, quote! {})
+            };
+
+            quote! {
+                #anchor_preflight
+                let #ident: [#elem_ty; #len] = {
+                    let mut __items: Vec<#elem_ty> = Vec::with_capacity(#len);
+                    for _i in 0..#len {
+                        let utxo: #elem_ty = match #find {
+                            Some(utxo) => utxo,
+                            None => {
+                                return Err(#fail_mismatch);
+                            }
+                        };
+                        #anchor_stmt
+                        __items.push(utxo);
+                    }
+                    match __items.try_into() {
+                        Ok(items) => items,
+                        Err(_) => unreachable!("exactly {} items were collected", #len),
+                    }
+                };
+                #total_checks
+            }
+        }
+        FieldKind::OptionalVec => {
+            let (limit, min_check) = block_bounds(field);
+            quote! {
+                let #ident: Option<Vec<#elem_ty>> = {
+                    let mut __block: Vec<#elem_ty> = Vec::new();
+                    while __block.len() < #limit {
+                        match #find {
+                            Some(utxo) => __block.push(utxo),
+                            None => break,
+                        }
+                    }
+                    if __block.is_empty() {
+                        None
+                    } else {
+                        #min_check
+                        Some(__block)
+                    }
+                };
+            }
+        }
+        FieldKind::Vec => {
+            if let Some(anchor_ident) = &attr.anchor_ident {
+                let count_checks = count_checks(field, &quote! { target_len });
+                quote! {
+                    let _ = {
+                        fn _assert_indexable<T: core::ops::Index<usize>>(_t: &T) {}
+                        _assert_indexable(&accounts.#anchor_ident);
+                    };
+                    let target_len = accounts.#anchor_ident.len();
+                    #count_checks
+                    let mut #ident: Vec<#elem_ty> = Vec::with_capacity(target_len);
+                    for i in 0..target_len {
+                        let utxo: #elem_ty = match #find {
+                            Some(utxo) => utxo,
+                            None => {
+                                return Err(#fail_mismatch);
+                            }
+                        };
+                        let _anchor_target = &accounts.#anchor_ident[i];
+                        let _anchor_ix = arch_program::system_instruction::anchor(
+                            saturn_account_parser::ToAccountInfo::to_account_info(&_anchor_target).key,
+                            utxo.meta.txid_big_endian(),
+                            utxo.meta.vout(),
+                        );
+                        #ident.push(utxo);
+                    }
+                    #total_checks
+                }
+            } else if attr.rest {
+                let count_checks = count_checks(field, &quote! { #ident.len() });
+                // Capture every remaining match; non-matching inputs stay in
+                // `remaining` and trigger the leftover-inputs check.
+                quote! {
+                    let mut #ident: Vec<#elem_ty> = Vec::new();
+                    while let Some(utxo) = #find {
+                        #ident.push(utxo);
+                    }
+                    #count_checks
+                    #total_checks
                 }
             } else {
                 syn::Error::new(field.span, "Vec field must be either `rest` or `anchor`")
@@ -296,3 +907,141 @@ pub fn build_extractor(
         }
     }
 }
+
+/// `#[utxo_parser(mode = "unordered")]` counterpart of
+/// [`build_group_extractor`]: every member searches the remaining inputs.
+/// Either all members are found or none is, otherwise parsing fails with
+/// `ErrorCode::IncompleteUtxoGroup`.
+pub fn build_unordered_group_extractor(
+    group: &Group,
+    members: &[&Field],
+    predicates: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let member_count = members.len();
+    let anchor_snippets = members.iter().map(|f| optional_anchor_snippet(f));
+    let name = group_name(members);
+    let collect = collect_group(group, members);
+
+    let member_blocks = members.iter().zip(predicates).map(|(field, predicate)| {
+        let ident = &field.ident;
+        let elem_ty = &field.elem_ty;
+        let find = find_in_remaining(&name, elem_ty, predicate);
+        quote! {
+            let #ident: Option<#elem_ty> = #find;
+            if #ident.is_some() {
+                group_found += 1;
+            }
+        }
+    });
+
+    quote! {
+        let mut group_found: usize = 0;
+        #( #member_blocks )*
+        if group_found != 0 && group_found != #member_count {
+            return Err(saturn_utxo_parser::ParseFailure::new(
+                #name,
+                None,
+                ErrorCode::IncompleteUtxoGroup,
+            ));
+        }
+        #( #anchor_snippets )*
+        #collect
+    }
+}
+
+/// Build the extractor for a `one_of` group: the members are alternatives for
+/// the input at `idx` and exactly one of them must accept it. An input
+/// accepted by several alternatives is ambiguous and rejected as well.
+pub fn build_one_of_extractor(
+    members: &[&Field],
+    predicates: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let idents: Vec<&syn::Ident> = members.iter().map(|f| &f.ident).collect();
+    let tmp_idents: Vec<syn::Ident> = idents
+        .iter()
+        .map(|ident| format_ident!("__one_of_{}", ident))
+        .collect();
+    let anchor_snippets = members.iter().map(|f| optional_anchor_snippet(f));
+    let name = group_name(members);
+    let load = load_utxo(&name, quote! { &utxos[idx] });
+
+    let member_blocks =
+        members
+            .iter()
+            .zip(&tmp_idents)
+            .zip(predicates)
+            .map(|((field, tmp), predicate)| {
+                let elem_ty = &field.elem_ty;
+                quote! {
+                    let #tmp: Option<#elem_ty> = {
+                        let utxo: #elem_ty = #load;
+                        if (#predicate) {
+                            one_of_matches += 1;
+                            Some(utxo)
+                        } else {
+                            None
+                        }
+                    };
+                }
+            });
+
+    quote! {
+        let (#( #idents, )*) = {
+            if idx >= total {
+                return Err(saturn_utxo_parser::ParseFailure::new(
+                    #name,
+                    None,
+                    ErrorCode::OneOfGroupMismatch,
+                ));
+            }
+            let mut one_of_matches: usize = 0;
+            #( #member_blocks )*
+            if one_of_matches != 1 {
+                return Err(saturn_utxo_parser::ParseFailure::new(
+                    #name,
+                    saturn_utxo_parser::ParseFailure::position(__inputs, &utxos[idx]),
+                    ErrorCode::OneOfGroupMismatch,
+                ));
+            }
+            idx += 1;
+            (#( #tmp_idents, )*)
+        };
+        #( #anchor_snippets )*
+    }
+}
+
+/// `#[utxo_parser(mode = "unordered")]` counterpart of
+/// [`build_one_of_extractor`]: every alternative searches the remaining
+/// inputs and exactly one of them may find a match.
+pub fn build_unordered_one_of_extractor(
+    members: &[&Field],
+    predicates: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let anchor_snippets = members.iter().map(|f| optional_anchor_snippet(f));
+    let name = group_name(members);
+
+    let member_blocks = members.iter().zip(predicates).map(|(field, predicate)| {
+        let ident = &field.ident;
+        let elem_ty = &field.elem_ty;
+        let find = find_in_remaining(&name, elem_ty, predicate);
+        quote! {
+            let #ident: Option<#elem_ty> = #find;
+            if #ident.is_some() {
+                one_of_found += 1;
+            }
+        }
+    });
+
+    quote! {
+        let mut one_of_found: usize = 0;
+        #( #member_blocks )*
+        if one_of_found != 1 {
+            return Err(saturn_utxo_parser::ParseFailure::new(
+                #name,
+                None,
+                ErrorCode::OneOfGroupMismatch,
+            ));
+        }
+        #( #anchor_snippets )*
+    }
+}
@@ -0,0 +1,246 @@
+//! Generation of the `fixture()` test constructors (`test-utils` feature).
+
+use crate::ir::{DeriveInputIr, Field, FieldKind, RunesPresence};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::visit::Visit;
+
+/// Build the inherent impl holding `fixture()` and `fixture_with()`. Only
+/// parsers whose fields share one `UtxoInfo` type get one, so that the
+/// fixture fits in a single `Vec`.
+pub fn build(ir: &DeriveInputIr) -> TokenStream {
+    if !cfg!(feature = "test-utils") {
+        return quote! {};
+    }
+    let Some(elem_ty) = super::shared_elem_ty(ir) else {
+        return quote! {};
+    };
+
+    let struct_ident = &ir.struct_ident;
+    let vis = &ir.vis;
+    let (impl_generics, ty_generics, where_clause) = ir.generics.split_for_impl();
+
+    // Only the first alternative of a `one_of` group is supplied.
+    let mut seen_one_of: Vec<String> = Vec::new();
+    let fields = ir.fields.iter().map(|field| {
+        let skipped = match &field.attr.group {
+            Some(group) if ir.is_one_of(&group.value()) => {
+                let name = group.value();
+                let seen = seen_one_of.contains(&name);
+                seen_one_of.push(name);
+                seen
+            }
+            _ => false,
+        };
+        field_fixture(ir, field, skipped)
+    });
+    let fields: Vec<TokenStream> = fields.collect();
+
+    quote! {
+        #[cfg(not(target_os = "solana"))]
+        impl #impl_generics #struct_ident #ty_generics #where_clause {
+            /// UTXOs satisfying the declared constraints, in an order the parser
+            /// accepts, already registered with the test registry.
+            #vis fn fixture() -> Vec<#elem_ty> {
+                Self::fixture_with(|_, _| {})
+            }
+
+            /// Same as `fixture`, calling `customize` with the field name on every
+            /// generated UTXO before it is registered, to override the values the
+            /// derive could not infer (constraints on other fields, the accounts,
+            /// the parameters or a `check` function).
+            #vis fn fixture_with(mut customize: impl FnMut(&str, &mut #elem_ty)) -> Vec<#elem_ty> {
+                let mut __fixture: Vec<#elem_ty> = Vec::new();
+                let mut __pinned: Vec<(usize, #elem_ty)> = Vec::new();
+
+                #( #fields )*
+
+                __pinned.sort_by_key(|(index, _)| *index);
+                for (index, info) in __pinned {
+                    let at = index.min(__fixture.len());
+                    __fixture.insert(at, info);
+                }
+                saturn_utxo_parser::fixture::register(&__fixture);
+                __fixture
+            }
+        }
+    }
+}
+
+/// Snippet generating the UTXOs of `field`, nothing when `skipped`.
+fn field_fixture(ir: &DeriveInputIr, field: &Field, skipped: bool) -> TokenStream {
+    let attr = &field.attr;
+    let name = field.ident.to_string();
+    let known = |expr: &Option<syn::Expr>| expr.as_ref().filter(|e| is_constant(ir, e)).cloned();
+
+    // Fields left empty emit nothing.
+    let count = match &field.kind {
+        _ if skipped => return quote! {},
+        FieldKind::Single | FieldKind::Optional => quote! { 1usize },
+        FieldKind::Array(len) => quote! { #len },
+        // Anchored collections take one UTXO per account, unknown here.
+        FieldKind::Vec if attr.anchor_ident.is_some() => return quote! {},
+        FieldKind::Vec => match known(&attr.min_count) {
+            Some(min) => quote! { (#min) as usize },
+            None => return quote! {},
+        },
+        FieldKind::OptionalVec => match known(&attr.min_count) {
+            Some(min) => quote! { ((#min) as usize).max(1) },
+            None => quote! { 1usize },
+        },
+    };
+
+    let value = known(&attr.value)
+        .or_else(|| {
+            attr.value_in
+                .as_ref()
+                .and_then(|values| values.first())
+                .filter(|e| is_constant(ir, e))
+                .cloned()
+        })
+        .or_else(|| known(&attr.min_value))
+        .or_else(|| known(&attr.max_value));
+    let value = match value {
+        Some(value) => quote! { (#value) as u64 },
+        None => quote! { saturn_utxo_parser::fixture::DEFAULT_VALUE },
+    };
+
+    let mut setup: Vec<TokenStream> = Vec::new();
+
+    // Runes: the required ids, with the required amount each.
+    let amount = known(&attr.rune_amount_expr)
+        .or_else(|| known(&attr.rune_amount_at_least))
+        .or_else(|| known(&attr.rune_amount_at_most));
+    let amount = match amount {
+        Some(amount) => quote! { (#amount) as u128 },
+        None => quote! { saturn_utxo_parser::fixture::DEFAULT_RUNE_AMOUNT },
+    };
+    let mut ids: Vec<syn::Expr> = Vec::new();
+    ids.extend(known(&attr.rune_id_expr));
+    ids.extend(
+        attr.rune_id_in
+            .as_ref()
+            .and_then(|ids| ids.first())
+            .filter(|e| is_constant(ir, e))
+            .cloned(),
+    );
+    ids.extend(
+        attr.contains_rune_ids
+            .iter()
+            .filter(|e| is_constant(ir, e))
+            .cloned(),
+    );
+    for id in &ids {
+        setup.push(quote! { saturn_utxo_parser::fixture::add_rune(&mut info, #id, #amount); });
+    }
+    let wants_runes = attr.runes == Some(RunesPresence::Some)
+        || attr.rune_id_expr.is_some()
+        || attr.has_rune_amount_constraint()
+        || attr.rune_id_in.is_some();
+    if let Some(count) = known(&attr.rune_count) {
+        setup.push(quote! {
+            saturn_utxo_parser::fixture::fill_runes(&mut info, (#count) as usize, #amount);
+        });
+    } else if wants_runes && ids.is_empty() {
+        setup.push(quote! { saturn_utxo_parser::fixture::fill_runes(&mut info, 1, #amount); });
+    }
+
+    if let Some(script) = known(&attr.script_expr) {
+        setup.push(quote! {
+            saturn_utxo_parser::fixture::set_script(
+                &mut info,
+                ::core::convert::AsRef::<[u8]>::as_ref(&(#script)),
+            );
+        });
+    }
+    if let Some(bytes) = &attr.address_script {
+        setup
+            .push(quote! { saturn_utxo_parser::fixture::set_script(&mut info, &[#( #bytes ),*]); });
+    }
+    if let Some(owner) = known(&attr.owned_by) {
+        setup.push(quote! {
+            saturn_utxo_parser::fixture::set_script(
+                &mut info,
+                &saturn_utxo_parser::account_script_pubkey(
+                    ::core::borrow::Borrow::<arch_program::pubkey::Pubkey>::borrow(&(#owner)),
+                ),
+            );
+        });
+    }
+    if let Some(confirmations) = known(&attr.min_confirmations) {
+        setup.push(quote! {
+            saturn_utxo_parser::fixture::set_confirmations(&mut info, (#confirmations) as u64);
+        });
+    }
+
+    let fit_total = if matches!(
+        field.kind,
+        FieldKind::Array(_) | FieldKind::Vec | FieldKind::OptionalVec
+    ) {
+        let opt = |expr: Option<syn::Expr>| match expr {
+            Some(expr) => quote! { Some((#expr) as u64) },
+            None => quote! { None },
+        };
+        let exact = opt(known(&attr.total_value));
+        let min = opt(known(&attr.total_value_min));
+        quote! { saturn_utxo_parser::fixture::fit_total(&mut __infos, #exact, #min); }
+    } else {
+        quote! {}
+    };
+
+    let store = match attr.index {
+        Some(index) => quote! {
+            for info in __infos {
+                __pinned.push((#index, info));
+            }
+        },
+        None => quote! { __fixture.extend(__infos); },
+    };
+
+    quote! {
+        {
+            let mut __infos = Vec::new();
+            for _ in 0..#count {
+                let mut info = saturn_utxo_parser::fixture::utxo(#value);
+                #( #setup )*
+                __infos.push(info);
+            }
+            #fit_total
+            for info in __infos.iter_mut() {
+                customize(#name, info);
+            }
+            #store
+        }
+    }
+}
+
+/// Whether `expr` can be evaluated without a parse: it reads neither the
+/// accounts, the parameters nor another field, and does not use `?`.
+fn is_constant(ir: &DeriveInputIr, expr: &syn::Expr) -> bool {
+    struct Finder<'ir> {
+        ir: &'ir DeriveInputIr,
+        dynamic: bool,
+    }
+    impl<'ast> Visit<'ast> for Finder<'_> {
+        fn visit_expr_path(&mut self, expr_path: &'ast syn::ExprPath) {
+            if let Some(ident) = expr_path.path.get_ident() {
+                if ident == "accounts"
+                    || ident == "params"
+                    || self.ir.fields.iter().any(|f| f.ident == *ident)
+                {
+                    self.dynamic = true;
+                }
+            }
+            syn::visit::visit_expr_path(self, expr_path);
+        }
+
+        fn visit_expr_try(&mut self, expr_try: &'ast syn::ExprTry) {
+            self.dynamic = true;
+            syn::visit::visit_expr_try(self, expr_try);
+        }
+    }
+
+    let mut finder = Finder { ir, dynamic: false };
+    finder.visit_expr(expr);
+    !finder.dynamic
+}
@@ -0,0 +1,214 @@
+//! Generation of the `LAYOUT` constant describing the parser's inputs.
+
+use crate::ir::{ArrayLen, DeriveInputIr, Field, FieldKind, ParseMode, RunesPresence};
+use crate::validate::int_literal;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+/// Build the inherent impl holding `const LAYOUT: saturn_utxo_parser::UtxoLayout`.
+pub fn build(ir: &DeriveInputIr) -> TokenStream {
+    let struct_ident = &ir.struct_ident;
+    let vis = &ir.vis;
+    let (impl_generics, ty_generics, where_clause) = ir.generics.split_for_impl();
+
+    let unordered = ir.mode == ParseMode::Unordered;
+    let allow_extra = ir.allow_extra;
+    let one_of: Vec<String> = ir.one_of.iter().map(|g| g.value()).collect();
+    let fields = ir.fields.iter().map(field_layout);
+
+    quote! {
+        impl #impl_generics #struct_ident #ty_generics #where_clause {
+            /// Machine-readable description of the inputs accepted by this parser.
+            #vis const LAYOUT: saturn_utxo_parser::UtxoLayout = saturn_utxo_parser::UtxoLayout {
+                unordered: #unordered,
+                allow_extra: #allow_extra,
+                one_of: &[#( #one_of ),*],
+                fields: &[#( #fields ),*],
+            };
+        }
+    }
+}
+
+fn field_layout(field: &Field) -> TokenStream {
+    let attr = &field.attr;
+    let name = field.ident.to_string();
+
+    let cardinality = match &field.kind {
+        FieldKind::Single => quote! { saturn_utxo_parser::UtxoCardinality::Single },
+        FieldKind::Optional => quote! { saturn_utxo_parser::UtxoCardinality::Optional },
+        FieldKind::Array(len) => {
+            let len = match len {
+                ArrayLen::Lit(len) => quote! { #len },
+                ArrayLen::Const(ident) => quote! { #ident },
+            };
+            quote! { saturn_utxo_parser::UtxoCardinality::Array(#len) }
+        }
+        FieldKind::Vec => {
+            let rest = attr.rest;
+            let min = opt_expr(&attr.min_count);
+            let max = opt_expr(&attr.max_count);
+            quote! {
+                saturn_utxo_parser::UtxoCardinality::Vec { rest: #rest, min: #min, max: #max }
+            }
+        }
+        FieldKind::OptionalVec => {
+            let min = opt_expr(&attr.min_count);
+            let max = opt_expr(&attr.max_count);
+            quote! { saturn_utxo_parser::UtxoCardinality::OptionalVec { min: #min, max: #max } }
+        }
+    };
+
+    let index = match attr.index {
+        Some(index) => quote! { Some(#index) },
+        None => quote! { None },
+    };
+    let group = opt_str(attr.group.as_ref().map(|g| g.value()));
+    let anchor = opt_str(attr.anchor_ident.as_ref().map(|id| id.to_string()));
+    let anchor_index = opt_expr(&attr.anchor_index);
+
+    let value = value_constraint(
+        &attr.value,
+        &attr.min_value,
+        &attr.max_value,
+        &attr.value_in,
+    );
+    let total_value = value_constraint(
+        &attr.total_value,
+        &attr.total_value_min,
+        &attr.total_value_max,
+        &None,
+    );
+
+    // Anchored fields implicitly require `runes = "none"`, as in the predicate.
+    let presence = match attr.runes {
+        Some(RunesPresence::None) => quote! { saturn_utxo_parser::RunesRequirement::None },
+        Some(RunesPresence::Some) => quote! { saturn_utxo_parser::RunesRequirement::Some },
+        Some(RunesPresence::Any) => quote! { saturn_utxo_parser::RunesRequirement::Any },
+        None if attr.anchor_ident.is_some() => {
+            quote! { saturn_utxo_parser::RunesRequirement::None }
+        }
+        None => quote! { saturn_utxo_parser::RunesRequirement::Any },
+    };
+    let rune_id = opt_expr(&attr.rune_id_expr);
+    let rune_id_in = expr_slice(attr.rune_id_in.iter().flatten());
+    let rune_amount = value_constraint(
+        &attr.rune_amount_expr,
+        &attr.rune_amount_at_least,
+        &attr.rune_amount_at_most,
+        &None,
+    );
+    let rune_count = opt_expr(&attr.rune_count);
+    let contains = expr_slice(attr.contains_rune_ids.iter());
+
+    let min_confirmations = opt_expr(&attr.min_confirmations);
+    let script = opt_expr(&attr.script_expr);
+    let address_script = match &attr.address_script {
+        Some(bytes) => quote! { Some(&[#( #bytes ),*]) },
+        None => quote! { None },
+    };
+    let owned_by = opt_expr(&attr.owned_by);
+    let check = opt_str(
+        attr.check
+            .as_ref()
+            .map(|path| path.to_token_stream().to_string().replace(' ', "")),
+    );
+
+    quote! {
+        saturn_utxo_parser::UtxoFieldLayout {
+            name: #name,
+            cardinality: #cardinality,
+            index: #index,
+            group: #group,
+            anchor: #anchor,
+            anchor_index: #anchor_index,
+            value: #value,
+            total_value: #total_value,
+            runes: saturn_utxo_parser::RuneConstraint {
+                presence: #presence,
+                id: #rune_id,
+                id_in: #rune_id_in,
+                amount: #rune_amount,
+                count: #rune_count,
+                contains: #contains,
+            },
+            min_confirmations: #min_confirmations,
+            script: #script,
+            address_script: #address_script,
+            owned_by: #owned_by,
+            check: #check,
+        }
+    }
+}
+
+/// `LayoutExpr` for a user expression: the literal when it is one, its source
+/// otherwise.
+fn layout_expr(expr: &syn::Expr) -> TokenStream {
+    match int_literal(expr) {
+        Some(value) => quote! { saturn_utxo_parser::LayoutExpr::Literal(#value) },
+        None => {
+            let source = expr.to_token_stream().to_string();
+            quote! { saturn_utxo_parser::LayoutExpr::Source(#source) }
+        }
+    }
+}
+
+fn opt_expr(expr: &Option<syn::Expr>) -> TokenStream {
+    match expr {
+        Some(expr) => {
+            let expr = layout_expr(expr);
+            quote! { Some(#expr) }
+        }
+        None => quote! { None },
+    }
+}
+
+fn opt_str(value: Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+fn expr_slice<'e>(exprs: impl Iterator<Item = &'e syn::Expr>) -> TokenStream {
+    let exprs = exprs.map(layout_expr);
+    quote! { &[#( #exprs ),*] }
+}
+
+fn value_constraint(
+    exact: &Option<syn::Expr>,
+    min: &Option<syn::Expr>,
+    max: &Option<syn::Expr>,
+    one_of: &Option<Vec<syn::Expr>>,
+) -> TokenStream {
+    let exact = opt_expr(exact);
+    let min = opt_expr(min);
+    let max = opt_expr(max);
+    let one_of = expr_slice(one_of.iter().flatten());
+    quote! {
+        saturn_utxo_parser::ValueConstraint {
+            exact: #exact,
+            min: #min,
+            max: #max,
+            one_of: #one_of,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn literals_and_sources() {
+        let s = layout_expr(&parse_quote!(10_000))
+            .to_string()
+            .replace(' ', "");
+        assert_eq!(s, "saturn_utxo_parser::LayoutExpr::Literal(10000u128)");
+
+        let s = layout_expr(&parse_quote!(MIN_DEPOSIT))
+            .to_string()
+            .replace(' ', "");
+        assert_eq!(s, "saturn_utxo_parser::LayoutExpr::Source(\"MIN_DEPOSIT\")");
+    }
+}
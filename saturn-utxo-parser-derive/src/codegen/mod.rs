@@ -1,27 +1,36 @@
+pub mod accounts_exprs;
 pub mod extractors;
+pub mod fixture;
+pub mod layout;
 pub mod predicate;
+pub mod trace;
+pub mod verify;
 
-use crate::ir::{DeriveInputIr, RunesPresence};
-use quote::quote;
+use crate::ir::{DeriveInputIr, FieldKind, ParseMode, RunesPresence};
+use quote::{quote, ToTokens};
 use syn::parse_quote;
 use syn::{visit::Visit, Lifetime};
 
-/// Build the predicate TokenStream for a field, applying the implicit rule
-/// that `anchor = ...` implies `runes == none` when the user did not provide a
-/// runes constraint.  This preserves legacy semantics without modifying the
-/// parsing stage.
-fn build_predicate_with_anchor_logic(field: &crate::ir::Field) -> proc_macro2::TokenStream {
+/// The constraints of a field, applying the implicit rule that `anchor = ...`
+/// implies `runes == none` when the user did not provide a runes constraint.
+/// This preserves legacy semantics without modifying the parsing stage.
+fn attr_with_anchor_logic(field: &crate::ir::Field) -> crate::ir::UtxoAttr {
     let mut attr = field.attr.clone();
     if attr.anchor_ident.is_some() && attr.runes.is_none() {
         attr.runes = Some(RunesPresence::None);
     }
-    crate::codegen::predicate::build(&attr)
+    attr
+}
+
+/// Build the predicate TokenStream for a field, see [`attr_with_anchor_logic`].
+fn build_predicate_with_anchor_logic(field: &crate::ir::Field) -> proc_macro2::TokenStream {
+    crate::codegen::predicate::build(&attr_with_anchor_logic(field))
 }
 
-/// Assemble the final `TokenStream` implementing `TryFromUtxos` for the target
-/// struct.  The generated code mirrors the behaviour of the original
-/// `derive_utxo_parser_old` implementation while being driven by the new IR /
-/// modular design.
+/// Assemble the final `TokenStream` implementing `TryFromUtxosWith` (and,
+/// without parameters, `TryFromUtxos`) for the target struct.  The generated
+/// code mirrors the behaviour of the original `derive_utxo_parser_old`
+/// implementation while being driven by the new IR / modular design.
 pub fn expand(ir: &DeriveInputIr) -> proc_macro2::TokenStream {
     let struct_ident = &ir.struct_ident;
     let accounts_ty = &ir.accounts_ty;
@@ -115,43 +124,322 @@ pub fn expand(ir: &DeriveInputIr) -> proc_macro2::TokenStream {
         }
     }
 
+    // The runtime does not report the block that confirmed a UTXO, so every
+    // input would count as unconfirmed on-chain. Refuse to build such a
+    // parser for the SBF target instead of rejecting every real UTXO.
+    let confirmation_checks: Vec<proc_macro2::TokenStream> = ir
+        .fields
+        .iter()
+        .filter_map(|field| field.attr.min_confirmations.as_ref())
+        .map(|expr| {
+            quote::quote_spanned! { syn::spanned::Spanned::span(expr) =>
+                #[cfg(target_os = "solana")]
+                compile_error!(
+                    "`min_confirmations` is host-only: the runtime does not expose the block height that confirmed a UTXO"
+                );
+            }
+        })
+        .collect();
+
     // ---------------------------------------------------------------
     // Build extraction snippets in declaration order.
     // ---------------------------------------------------------------
     let mut init_snippets: Vec<proc_macro2::TokenStream> = Vec::new();
-    let mut field_idents: Vec<&syn::Ident> = Vec::new();
+    let field_idents = ir.struct_field_idents();
 
     // ---------------------------------------------------------------
     // Initialise index-based traversal variables and duplicate check.
     // ---------------------------------------------------------------
-    init_snippets.push(quote! {
-        // Strict-order parsing state
-        let mut idx: usize = 0;
+    let unordered = ir.mode == ParseMode::Unordered;
+
+    // Fields bound to a fixed position with `index = N` read it from the full
+    // slice; every other field only sees the inputs at the other positions.
+    let pinned: Vec<&crate::ir::Field> = ir
+        .fields
+        .iter()
+        .filter(|f| f.attr.index.is_some())
+        .collect();
+    let positions: Vec<usize> = pinned.iter().filter_map(|f| f.attr.index).collect();
+    let free_utxos = if pinned.is_empty() {
+        quote! { utxos.iter() }
+    } else {
+        quote! {
+            utxos
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| ![#( #positions ),*].contains(i))
+                .map(|(_, meta)| meta)
+        }
+    };
+    let pinned_init = if pinned.is_empty() {
+        quote! {}
+    } else {
+        let bounds_checks = crate::codegen::extractors::pinned_bounds_checks(&pinned);
+        let strict_view = if unordered {
+            quote! {}
+        } else {
+            quote! {
+                let __free_utxos: Vec<&arch_program::utxo::UtxoMeta> = #free_utxos.collect();
+                let utxos = &__free_utxos[..];
+                let total: usize = utxos.len();
+            }
+        };
+        quote! {
+            let __all_utxos: &[arch_program::utxo::UtxoMeta] = utxos;
+            #bounds_checks
+            #strict_view
+        }
+    };
+
+    let has_strict_rest = !unordered
+        && ir
+            .fields
+            .iter()
+            .any(|f| f.kind == FieldKind::Vec && f.attr.rest && f.attr.anchor_ident.is_none());
+    let cursor_init = if unordered {
+        // Search-based parsing state: inputs not consumed by any field yet.
+        quote! {
+            let mut remaining: Vec<&arch_program::utxo::UtxoMeta> = #free_utxos.collect();
+        }
+    } else if has_strict_rest {
+        quote! {
+            // Strict-order parsing state
+            let mut idx: usize = 0;
+            // Positions (in `utxos`) before the cursor that the `rest` field
+            // passed over without consuming.
+            let mut __skipped: Vec<usize> = Vec::new();
+        }
+    } else {
+        quote! {
+            // Strict-order parsing state
+            let mut idx: usize = 0;
+        }
+    };
+    let preamble = quote! {
         let total: usize = utxos.len();
+        // Failures report positions in the caller's slice.
+        let __inputs: &[arch_program::utxo::UtxoMeta] = utxos;
 
         // Optional pre-flight duplicate meta detection (cheap O(n^2) because N is small)
         for i in 0..total {
             for j in (i + 1)..total {
                 if utxos[i] == utxos[j] {
-                    return Err(ProgramError::Custom(ErrorCode::DuplicateUtxoMeta.into()));
+                    return Err(saturn_utxo_parser::ParseFailure::new(
+                        "",
+                        Some(j),
+                        ErrorCode::DuplicateUtxoMeta,
+                    ));
                 }
             }
         }
-    });
 
+        #pinned_init
+        #cursor_init
+    };
+
+    let mut emitted_groups: Vec<String> = Vec::new();
+    let mut accounts_exprs = 0usize;
     for field in &ir.fields {
-        field_idents.push(&field.ident);
-        let predicate_ts = build_predicate_with_anchor_logic(field);
-        let extractor_ts = crate::codegen::extractors::build_extractor(field, &predicate_ts);
+        // Grouped fields are extracted together when the first member is reached.
+        if let Some(group) = &field.attr.group {
+            let name = group.value();
+            if emitted_groups.contains(&name) {
+                continue;
+            }
+            let (hoisted, bindings): (Vec<_>, Vec<_>) = group_members(ir, &name)
+                .into_iter()
+                .map(|f| crate::codegen::accounts_exprs::hoist(f, &mut accounts_exprs))
+                .unzip();
+            init_snippets.extend(bindings);
+            let members: Vec<&crate::ir::Field> = hoisted.iter().collect();
+            let predicates: Vec<_> = members
+                .iter()
+                .map(|f| crate::codegen::trace::wrap(f, build_predicate_with_anchor_logic(f)))
+                .collect();
+            init_snippets.push(match (ir.is_one_of(&name), unordered) {
+                (true, true) => crate::codegen::extractors::build_unordered_one_of_extractor(
+                    &members,
+                    &predicates,
+                ),
+                (true, false) => {
+                    crate::codegen::extractors::build_one_of_extractor(&members, &predicates)
+                }
+                (false, true) => crate::codegen::extractors::build_unordered_group_extractor(
+                    ir.group_of(field).expect("all-or-none groups are declared"),
+                    &members,
+                    &predicates,
+                ),
+                (false, false) => crate::codegen::extractors::build_group_extractor(
+                    ir.group_of(field).expect("all-or-none groups are declared"),
+                    &members,
+                    &predicates,
+                ),
+            });
+            emitted_groups.push(name);
+            continue;
+        }
+
+        let (field, bindings) = crate::codegen::accounts_exprs::hoist(field, &mut accounts_exprs);
+        init_snippets.push(bindings);
+        let field = &field;
+        let predicate_ts =
+            crate::codegen::trace::wrap(field, build_predicate_with_anchor_logic(field));
+        let extractor_ts = if unordered && field.attr.index.is_none() {
+            crate::codegen::extractors::build_unordered_extractor(field, &predicate_ts)
+        } else {
+            crate::codegen::extractors::build_extractor(field, &predicate_ts)
+        };
         init_snippets.push(extractor_ts);
     }
 
     // Check for leftover inputs after all fields have extracted theirs.
-    init_snippets.push(quote! {
-        if idx < total {
-            return Err(ProgramError::Custom(ErrorCode::UnexpectedExtraUtxos.into()));
+    let leftover_check = if ir.allow_extra {
+        if unordered {
+            quote! {}
+        } else {
+            quote! {
+                // Leftover inputs are accepted, the cursor is no longer needed.
+                let _ = idx;
+            }
         }
-    });
+    } else if unordered {
+        quote! {
+            if let Some(meta) = remaining.first() {
+                return Err(saturn_utxo_parser::ParseFailure::new(
+                    "",
+                    saturn_utxo_parser::ParseFailure::position(__inputs, meta),
+                    ErrorCode::UnexpectedExtraUtxos,
+                ));
+            }
+        }
+    } else if has_strict_rest {
+        quote! {
+            if let Some(meta) = __skipped.first().map(|&i| &utxos[i]).or(utxos.get(idx)) {
+                return Err(saturn_utxo_parser::ParseFailure::new(
+                    "",
+                    saturn_utxo_parser::ParseFailure::position(__inputs, meta),
+                    ErrorCode::UnexpectedExtraUtxos,
+                ));
+            }
+        }
+    } else {
+        quote! {
+            if idx < total {
+                return Err(saturn_utxo_parser::ParseFailure::new(
+                    "",
+                    saturn_utxo_parser::ParseFailure::position(__inputs, &utxos[idx]),
+                    ErrorCode::UnexpectedExtraUtxos,
+                ));
+            }
+        }
+    };
+    // Positions in the caller's slice of the inputs no field consumed, in order.
+    let leftover_positions = if unordered {
+        quote! { remaining.iter() }
+    } else if has_strict_rest {
+        quote! { __skipped.iter().map(|&i| &utxos[i]).chain(utxos[idx..].iter()) }
+    } else {
+        quote! { utxos[idx..].iter() }
+    };
+    let leftover_positions = quote! {
+        #leftover_positions
+            .filter_map(|meta| saturn_utxo_parser::ParseFailure::position(__inputs, meta))
+            .collect::<Vec<usize>>()
+    };
+
+    let group_structs = build_group_structs(ir);
+    let rune_id_accessors = build_rune_id_accessors(ir);
+    let layout = crate::codegen::layout::build(ir);
+    let fixture = crate::codegen::fixture::build(ir);
+    let verify = crate::codegen::verify::build(ir);
+    let params_ty = match &ir.params_ty {
+        Some(ty) => quote! { #ty },
+        None => quote! { () },
+    };
+
+    let body = |source_init: proc_macro2::TokenStream, finish: proc_macro2::TokenStream| {
+        quote! {
+            use saturn_utxo_parser::ErrorCode;
+
+            // Shadow the accounts reference with the correct lifetime for convenience.
+            let accounts: &#accounts_ty<'info2> = accounts;
+
+            #preamble
+            #source_init
+
+            #(#init_snippets)*
+
+            #finish
+        }
+    };
+
+    // Parsers whose fields share one `UtxoInfo` type convert every input at
+    // most once, when a field first inspects it; the others convert on demand.
+    let shared_elem = shared_elem_ty(ir);
+    let source_init = match shared_elem {
+        Some(elem_ty) => quote! {
+            let __source = saturn_utxo_parser::LazyInfos::<#elem_ty>::new(__inputs);
+        },
+        None => quote! { let __source = saturn_utxo_parser::MetaConverter; },
+    };
+    let verbose_body = body(
+        source_init.clone(),
+        quote! {
+            #leftover_check
+            Ok(Self { #(#field_idents),* })
+        },
+    );
+
+    let partial = match shared_elem {
+        Some(elem_ty) => {
+            let partial_body = body(
+                source_init,
+                quote! {
+                    let __leftover = #leftover_positions;
+                    scratch.clear();
+                    __source.collect_into(&__leftover, scratch)?;
+                    Ok((Self { #(#field_idents),* }, &scratch[..]))
+                },
+            );
+            let vis = &ir.vis;
+            quote! {
+                impl #impl_generics #struct_ident #ty_generics #where_clause {
+                    /// Same as `TryFromUtxosWith::try_utxos_with_verbose`, but inputs no field
+                    /// consumed are returned instead of failing with `UnexpectedExtraUtxos`, so
+                    /// that they can be fed to another parser. They are converted into `scratch`,
+                    /// cleared first, in input order.
+                    #[allow(unused_variables)]
+                    #vis fn try_utxos_partial<'accs, 'info2, 's>(
+                        accounts: &'accs #accounts_ty<'info2>,
+                        utxos: &'a [arch_program::utxo::UtxoMeta],
+                        params: &#params_ty,
+                        scratch: &'s mut Vec<#elem_ty>,
+                    ) -> core::result::Result<(Self, &'s [#elem_ty]), saturn_utxo_parser::ParseFailure> {
+                        #partial_body
+                    }
+                }
+            }
+        }
+        None => quote! {},
+    };
+
+    // Parsers without `#[utxo_params]` can also be run without parameters.
+    let plain_impl = if ir.params_ty.is_none() {
+        quote! {
+            impl #impl_generics saturn_utxo_parser::TryFromUtxos<'a> for #struct_ident #ty_generics #where_clause {
+                type Accs<'any> = #accounts_ty<'any>;
+
+                fn try_utxos<'accs, 'info2>(
+                    accounts: &'accs #accounts_ty<'info2>,
+                    utxos: &'a [arch_program::utxo::UtxoMeta],
+                ) -> core::result::Result<Self, arch_program::program_error::ProgramError> {
+                    <Self as saturn_utxo_parser::TryFromUtxosWith<'a>>::try_utxos_with(accounts, utxos, &())
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
 
     // ---------------------------------------------------------------
     // Compose the final impl block.
@@ -160,23 +448,169 @@ pub fn expand(ir: &DeriveInputIr) -> proc_macro2::TokenStream {
         // Anchor field existence assertions ----------------------------------------------------
         #( #anchor_checks )*
 
-        impl #impl_generics saturn_utxo_parser::TryFromUtxos<'a> for #struct_ident #ty_generics #where_clause {
+        #( #confirmation_checks )*
+
+        #group_structs
+
+        #rune_id_accessors
+
+        #layout
+
+        #fixture
+
+        #verify
+
+        #partial
+
+        impl #impl_generics saturn_utxo_parser::TryFromUtxosWith<'a> for #struct_ident #ty_generics #where_clause {
             type Accs<'any> = #accounts_ty<'any>;
+            type Params = #params_ty;
 
-            fn try_utxos<'accs, 'info2>(
-                accounts: &'accs Self::Accs<'info2>,
+            #[allow(unused_variables)]
+            fn try_utxos_with_verbose<'accs, 'info2>(
+                accounts: &'accs #accounts_ty<'info2>,
                 utxos: &'a [arch_program::utxo::UtxoMeta],
-            ) -> core::result::Result<Self, arch_program::program_error::ProgramError> {
-                use arch_program::program_error::ProgramError;
-                use saturn_utxo_parser::ErrorCode;
+                params: &#params_ty,
+            ) -> core::result::Result<Self, saturn_utxo_parser::ParseFailure> {
+                #verbose_body
+            }
+        }
+
+        #plain_impl
+    }
+}
+
+/// The `UtxoInfo` type shared by every field, if any, which lets the parser
+/// keep the inputs it converted for the other fields.
+fn shared_elem_ty(ir: &DeriveInputIr) -> Option<&syn::Type> {
+    let first = &ir.fields.first()?.elem_ty;
+    let key = first.to_token_stream().to_string();
+    ir.fields
+        .iter()
+        .all(|f| f.elem_ty.to_token_stream().to_string() == key)
+        .then_some(first)
+}
+
+/// For every single or optional field with `rune_id_in = [..]` emit a
+/// `fn <field>_rune_id(&self)` accessor returning the first listed rune id the
+/// parsed UTXO holds. The list is evaluated again with the accounts, the
+/// parameters of `#[utxo_params]` parsers and every field of the struct in
+/// scope, so ids resolve as they did during parsing.
+fn build_rune_id_accessors(ir: &DeriveInputIr) -> proc_macro2::TokenStream {
+    let struct_ident = &ir.struct_ident;
+    let accounts_ty = &ir.accounts_ty;
+    let vis = &ir.vis;
+    let (impl_generics, ty_generics, where_clause) = ir.generics.split_for_impl();
+    let bind_fields = bind_fields(ir);
+    let params_arg = ir.params_ty.as_ref().map(|ty| quote! { , params: &#ty });
+
+    let accessors: Vec<proc_macro2::TokenStream> = ir
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let ids = field.attr.rune_id_in.as_ref()?;
+            let ident = &field.ident;
+            let utxo = match field.kind {
+                FieldKind::Single => quote! { Some(#ident) },
+                FieldKind::Optional => optional_utxo(ir, field),
+                FieldKind::Array(_) | FieldKind::Vec | FieldKind::OptionalVec => return None,
+            };
+            let fn_ident = quote::format_ident!("{}_rune_id", ident);
+            let doc = format!(
+                "The rune id from `rune_id_in` held by `{}`, `None` when the field is empty.",
+                ident
+            );
 
-                // Shadow the accounts reference with the correct lifetime for convenience.
-                let accounts: &Self::Accs<'info2> = accounts;
+            Some(quote! {
+                #[doc = #doc]
+                #[allow(unused_variables)]
+                #vis fn #fn_ident<'info2>(
+                    &self,
+                    accounts: &#accounts_ty<'info2>
+                    #params_arg
+                ) -> Option<arch_program::rune::RuneId> {
+                    #bind_fields
+                    let utxo = #utxo?;
+                    [#( #ids ),*]
+                        .into_iter()
+                        .find(|id| utxo.rune_amount(id).is_some())
+                }
+            })
+        })
+        .collect();
+
+    if accessors.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        impl #impl_generics #struct_ident #ty_generics #where_clause {
+            #( #accessors )*
+        }
+    }
+}
+
+/// All fields belonging to the group called `name`, in declaration order.
+fn group_members<'ir>(ir: &'ir DeriveInputIr, name: &str) -> Vec<&'ir crate::ir::Field> {
+    ir.fields
+        .iter()
+        .filter(|f| f.attr.group.as_ref().map(|g| g.value()).as_deref() == Some(name))
+        .collect()
+}
+
+/// For every `#[utxo_group(..)]` field of type `Option<Name>` emit `Name`,
+/// owning one `UtxoInfo` per member.
+fn build_group_structs(ir: &DeriveInputIr) -> proc_macro2::TokenStream {
+    let struct_ident = &ir.struct_ident;
+    let vis = &ir.vis;
 
-                #(#init_snippets)*
+    let structs = ir.groups.iter().map(|group| {
+        let name = group.ident.to_string();
+        let members = group_members(ir, &name);
+        let member_idents = members.iter().map(|f| &f.ident);
+        let member_tys = members.iter().map(|f| &f.elem_ty);
+        let ty_ident = &group.ty_ident;
+        let doc = format!(
+            "UTXOs of the `{}` group of [`{}`], supplied all together or not at all.",
+            name, struct_ident
+        );
 
-                Ok(Self { #(#field_idents),* })
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone)]
+            #vis struct #ty_ident {
+                #( pub #member_idents: #member_tys, )*
             }
         }
+    });
+
+    quote! { #( #structs )* }
+}
+
+/// Binds every field of `self` by reference under its own name, and the
+/// members of `#[utxo_group(..)]` groups as `Option<&UtxoInfo>`.
+fn bind_fields(ir: &DeriveInputIr) -> proc_macro2::TokenStream {
+    let struct_idents = ir.struct_field_idents();
+    let members = ir.fields.iter().filter_map(|field| {
+        let group = &ir.group_of(field)?.ident;
+        let ident = &field.ident;
+        let elem_ty = &field.elem_ty;
+        Some(quote! {
+            let #ident: Option<&#elem_ty> = #group.as_ref().map(|group| &group.#ident);
+        })
+    });
+
+    quote! {
+        let Self { #( #struct_idents ),* } = self;
+        #( #members )*
+    }
+}
+
+/// The `Option<&UtxoInfo>` held by an optional `field` bound by [`bind_fields`].
+fn optional_utxo(ir: &DeriveInputIr, field: &crate::ir::Field) -> proc_macro2::TokenStream {
+    let ident = &field.ident;
+    match ir.group_of(field) {
+        Some(_) => quote! { #ident },
+        None => quote! { #ident.as_ref() },
     }
 }
@@ -7,39 +7,157 @@ use quote::quote;
 /// Build a boolean expression over a `utxo` variable matching the constraints
 /// described by `attr`.
 pub fn build(attr: &UtxoAttr) -> proc_macro2::TokenStream {
-    let mut parts: Vec<proc_macro2::TokenStream> = Vec::new();
+    let parts: Vec<proc_macro2::TokenStream> =
+        parts(attr).into_iter().map(|(_, part)| part).collect();
+
+    if parts.is_empty() {
+        quote! { true }
+    } else {
+        quote! { #( #parts )&&* }
+    }
+}
+
+/// The conjuncts of the predicate built by [`build`], each labelled with the
+/// attribute key it checks, in evaluation order.
+pub fn parts(attr: &UtxoAttr) -> Vec<(&'static str, proc_macro2::TokenStream)> {
+    let mut parts: Vec<(&'static str, proc_macro2::TokenStream)> = Vec::new();
 
     // value predicate: allow arbitrary expressions (consts, arithmetic, etc.).
     if let Some(value_expr) = &attr.value {
-        parts.push(quote! { utxo.value == (#value_expr) });
+        parts.push(("value", quote! { utxo.value == (#value_expr) }));
+    }
+
+    // value range predicates, bounds are inclusive.
+    if let Some(min) = &attr.min_value {
+        parts.push(("min_value", quote! { utxo.value >= (#min) }));
+    }
+    if let Some(max) = &attr.max_value {
+        parts.push(("max_value", quote! { utxo.value <= (#max) }));
+    }
+
+    // value set predicate: any of the listed denominations.
+    if let Some(values) = &attr.value_in {
+        parts.push(("value_in", quote! { ( #( utxo.value == (#values) )||* ) }));
     }
 
     // runes presence
     match attr.runes {
-        Some(RunesPresence::None) => parts.push(quote! { utxo.rune_entry_count() == 0 }),
-        Some(RunesPresence::Some) => parts.push(quote! { utxo.rune_entry_count() > 0 }),
+        Some(RunesPresence::None) => parts.push(("runes", quote! { utxo.rune_entry_count() == 0 })),
+        Some(RunesPresence::Some) => parts.push(("runes", quote! { utxo.rune_entry_count() > 0 })),
         _ => {}
     }
 
     // rune id / amount combinations
     match (&attr.rune_id_expr, &attr.rune_amount_expr) {
         (Some(id), Some(amount)) => {
-            parts.push(quote! { utxo.contains_exact_rune(&(#id), (#amount) as u128) });
+            parts.push((
+                "rune_id/rune_amount",
+                quote! { utxo.contains_exact_rune(&(#id), (#amount) as u128) },
+            ));
         }
         (Some(id), None) => {
-            parts.push(quote! { utxo.rune_amount(&(#id)).is_some() });
+            parts.push(("rune_id", quote! { utxo.rune_amount(&(#id)).is_some() }));
         }
         (None, Some(amount)) => {
-            parts.push(quote! { utxo.total_rune_amount() == (#amount) as u128 });
+            parts.push((
+                "rune_amount",
+                quote! { utxo.total_rune_amount() == (#amount) as u128 },
+            ));
         }
         _ => {}
     }
 
-    if parts.is_empty() {
-        quote! { true }
-    } else {
-        quote! { #( #parts )&&* }
+    // any-of rune ids
+    if let Some(ids) = &attr.rune_id_in {
+        parts.push((
+            "rune_id_in",
+            quote! { ( #( utxo.rune_amount(&(#ids)).is_some() )||* ) },
+        ));
+    }
+
+    // rune amount bounds: per rune when `rune_id` is given, on the total otherwise.
+    let bounds = [
+        (
+            "rune_amount_at_least",
+            &attr.rune_amount_at_least,
+            quote! { >= },
+        ),
+        (
+            "rune_amount_at_most",
+            &attr.rune_amount_at_most,
+            quote! { <= },
+        ),
+    ];
+    for (label, bound, op) in bounds {
+        let Some(bound) = bound else { continue };
+        match &attr.rune_id_expr {
+            Some(id) => parts.push((
+                label,
+                quote! {
+                    utxo.rune_amount(&(#id)).map_or(false, |amount| amount #op (#bound) as u128)
+                },
+            )),
+            None => parts.push((
+                label,
+                quote! { utxo.total_rune_amount() #op (#bound) as u128 },
+            )),
+        }
     }
+
+    // multi-rune predicates: work with any rune set capacity.
+    if let Some(count) = &attr.rune_count {
+        parts.push((
+            "rune_count",
+            quote! { utxo.rune_entry_count() == (#count) as usize },
+        ));
+    }
+
+    for id in &attr.contains_rune_ids {
+        parts.push((
+            "contains_rune_id",
+            quote! { utxo.rune_amount(&(#id)).is_some() },
+        ));
+    }
+
+    if let Some(script) = &attr.script_expr {
+        parts.push((
+            "script",
+            quote! {
+                utxo.pays_to_script(::core::convert::AsRef::<[u8]>::as_ref(&(#script)))
+            },
+        ));
+    }
+
+    if let Some(bytes) = &attr.address_script {
+        parts.push(("address", quote! { utxo.pays_to_script(&[#( #bytes ),*]) }));
+    }
+
+    if let Some(owner) = &attr.owned_by {
+        parts.push((
+            "owned_by",
+            quote! {
+                utxo.pays_to_script(&saturn_utxo_parser::account_script_pubkey(
+                    ::core::borrow::Borrow::<arch_program::pubkey::Pubkey>::borrow(&(#owner)),
+                ))
+            },
+        ));
+    }
+
+    if let Some(min_conf) = &attr.min_confirmations {
+        parts.push((
+            "min_confirmations",
+            quote! {
+                utxo.confirmations_at(saturn_utxo_parser::current_block_height()) >= (#min_conf) as u64
+            },
+        ));
+    }
+
+    // custom predicate function, evaluated last so that cheaper checks short-circuit.
+    if let Some(check) = &attr.check {
+        parts.push(("check", quote! { (#check)(&utxo, accounts) }));
+    }
+
+    parts
 }
 
 #[cfg(test)]
@@ -58,4 +176,103 @@ mod tests {
         assert!(s.contains("utxo.value==(10)"));
         assert!(s.contains("utxo.rune_entry_count()>0"));
     }
+
+    #[test]
+    fn parts_are_labelled_with_their_key() {
+        let mut a = UtxoAttr::default();
+        a.value = Some(parse_quote!(10));
+        a.rune_amount_at_least = Some(parse_quote!(100));
+        a.check = Some(parse_quote!(checks::is_valid_deposit));
+        let labels: Vec<&str> = parts(&a).into_iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, ["value", "rune_amount_at_least", "check"]);
+    }
+
+    #[test]
+    fn predicate_contains_value_range() {
+        let mut a = UtxoAttr::default();
+        a.min_value = Some(parse_quote!(1_000));
+        a.max_value = Some(parse_quote!(MAX_DEPOSIT));
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains("utxo.value>=(1_000)"));
+        assert!(s.contains("utxo.value<=(MAX_DEPOSIT)"));
+    }
+
+    #[test]
+    fn predicate_contains_value_in() {
+        let mut a = UtxoAttr::default();
+        a.value_in = Some(vec![parse_quote!(10_000), parse_quote!(TIER_2)]);
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains("(utxo.value==(10_000)||utxo.value==(TIER_2))"));
+    }
+
+    #[test]
+    fn predicate_contains_rune_amount_bounds() {
+        let mut a = UtxoAttr::default();
+        a.rune_id_expr = Some(parse_quote!(RUNE));
+        a.rune_amount_at_least = Some(parse_quote!(100));
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains("utxo.rune_amount(&(RUNE)).map_or(false,|amount|amount>=(100)asu128)"));
+
+        let mut a = UtxoAttr::default();
+        a.rune_amount_at_most = Some(parse_quote!(500));
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains("utxo.total_rune_amount()<=(500)asu128"));
+    }
+
+    #[test]
+    fn predicate_contains_rune_id_in() {
+        let mut a = UtxoAttr::default();
+        a.rune_id_in = Some(vec![parse_quote!(POOL.token_0), parse_quote!(POOL.token_1)]);
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains(
+            "(utxo.rune_amount(&(POOL.token_0)).is_some()||utxo.rune_amount(&(POOL.token_1)).is_some())"
+        ));
+    }
+
+    #[test]
+    fn predicate_contains_multi_rune_parts() {
+        let mut a = UtxoAttr::default();
+        a.rune_count = Some(parse_quote!(2));
+        a.contains_rune_ids = vec![parse_quote!(RUNE_A), parse_quote!(RUNE_B)];
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains("utxo.rune_entry_count()==(2)asusize"));
+        assert!(s.contains("utxo.rune_amount(&(RUNE_A)).is_some()"));
+        assert!(s.contains("utxo.rune_amount(&(RUNE_B)).is_some()"));
+    }
+
+    #[test]
+    fn predicate_contains_address_script() {
+        let mut a = UtxoAttr::default();
+        a.address_script = Some(vec![0x00, 0x14]);
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains("utxo.pays_to_script(&[0u8,20u8])"));
+    }
+
+    #[test]
+    fn predicate_contains_owned_by() {
+        let mut a = UtxoAttr::default();
+        a.owned_by = Some(parse_quote!(crate::ID));
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains("saturn_utxo_parser::account_script_pubkey("));
+        assert!(s.contains("::borrow(&(crate::ID))"));
+    }
+
+    #[test]
+    fn predicate_contains_min_confirmations() {
+        let mut a = UtxoAttr::default();
+        a.min_confirmations = Some(parse_quote!(6));
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.contains(
+            "utxo.confirmations_at(saturn_utxo_parser::current_block_height())>=(6)asu64"
+        ));
+    }
+
+    #[test]
+    fn predicate_contains_check_fn() {
+        let mut a = UtxoAttr::default();
+        a.value = Some(parse_quote!(10));
+        a.check = Some(parse_quote!(checks::is_valid_deposit));
+        let s = build(&a).to_string().replace(" ", "");
+        assert!(s.ends_with("&&(checks::is_valid_deposit)(&utxo,accounts)"));
+    }
 }
@@ -0,0 +1,41 @@
+//! Match diagnostics of the generated parsers (`trace` feature).
+//!
+//! Every candidate a field tests is logged through `msg!` with its position
+//! in the inputs and, when rejected, the attribute keys it failed, e.g.
+//! ``"`fee`: input 2 rejected (value, runes)"``.
+
+use crate::ir::Field;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Wrap the extraction `predicate` of `field` so that evaluating it logs the
+/// `utxo` in scope. Returns `predicate` untouched without the feature.
+pub fn wrap(field: &Field, predicate: TokenStream) -> TokenStream {
+    if !cfg!(feature = "trace") {
+        return predicate;
+    }
+
+    let name = field.ident.to_string();
+    let (labels, parts): (Vec<&'static str>, Vec<TokenStream>) =
+        crate::codegen::predicate::parts(&super::attr_with_anchor_logic(field))
+            .into_iter()
+            .unzip();
+
+    quote! {
+        {
+            let __matched: bool = #predicate;
+            let __position = saturn_utxo_parser::ParseFailure::position(__inputs, &utxo.meta);
+            if __matched {
+                saturn_utxo_parser::trace::candidate(#name, __position, &[]);
+            } else {
+                // Evaluate every part again to name all the failing ones.
+                saturn_utxo_parser::trace::candidate(
+                    #name,
+                    __position,
+                    &[#( (!(#parts)).then_some(#labels) ),*],
+                );
+            }
+            __matched
+        }
+    }
+}
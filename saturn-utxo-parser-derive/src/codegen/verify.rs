@@ -0,0 +1,166 @@
+//! Generation of the `verify()` re-check of an already parsed struct.
+//!
+//! The predicates are evaluated again on the captured UTXOs, with every field
+//! of the struct in scope by reference so that constraints referring to
+//! earlier fields resolve as they did during parsing. The layout of the
+//! inputs (order, positions, group completeness) is not checked again: it
+//! cannot change once the struct exists.
+
+use crate::codegen::extractors::{
+    base_error_variant, count_checks, field_error, total_value_checks,
+};
+use crate::ir::{DeriveInputIr, Field, FieldKind};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Build the inherent impl holding `verify()` and `verify_verbose()`.
+pub fn build(ir: &DeriveInputIr) -> TokenStream {
+    let struct_ident = &ir.struct_ident;
+    let accounts_ty = &ir.accounts_ty;
+    let vis = &ir.vis;
+    let (impl_generics, ty_generics, where_clause) = ir.generics.split_for_impl();
+    let bind_fields = super::bind_fields(ir);
+    let params_arg = ir.params_ty.as_ref().map(|ty| quote! { , params: &#ty });
+    let params_pass = ir.params_ty.as_ref().map(|_| quote! { , params });
+
+    let mut accounts_exprs = 0usize;
+    let checks: Vec<TokenStream> = ir
+        .fields
+        .iter()
+        .map(|field| {
+            let (field, bindings) =
+                crate::codegen::accounts_exprs::hoist(field, &mut accounts_exprs);
+            let checks = field_checks(ir, &field);
+            quote! {
+                {
+                    #bindings
+                    #checks
+                }
+            }
+        })
+        .collect();
+
+    quote! {
+        impl #impl_generics #struct_ident #ty_generics #where_clause {
+            /// Re-check the constraints of every field against the UTXOs captured
+            /// by the parse, e.g. after the handler changed state the predicates
+            /// read. Fails with the error the parse would have returned.
+            #vis fn verify<'info2>(
+                &self,
+                accounts: &#accounts_ty<'info2>
+                #params_arg
+            ) -> core::result::Result<(), arch_program::program_error::ProgramError> {
+                self.verify_verbose(accounts #params_pass).map_err(Into::into)
+            }
+
+            /// Same as `verify`, reporting which field failed and why.
+            #[allow(unused_variables)]
+            #vis fn verify_verbose<'info2>(
+                &self,
+                accounts: &#accounts_ty<'info2>
+                #params_arg
+            ) -> core::result::Result<(), saturn_utxo_parser::ParseFailure> {
+                use saturn_utxo_parser::ErrorCode;
+
+                #bind_fields
+
+                #( #checks )*
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Checks of `field`, bound by reference under its own name.
+fn field_checks(ir: &DeriveInputIr, field: &Field) -> TokenStream {
+    let ident = &field.ident;
+    let check_utxo = utxo_check(field);
+
+    match &field.kind {
+        FieldKind::Single => quote! {
+            let utxo = #ident;
+            #check_utxo
+        },
+        FieldKind::Optional => {
+            let utxo = super::optional_utxo(ir, field);
+            quote! {
+                if let Some(utxo) = #utxo {
+                    #check_utxo
+                }
+            }
+        }
+        FieldKind::Array(_) => {
+            let total_checks = total_value_checks(field);
+            quote! {
+                for utxo in #ident.iter() {
+                    #check_utxo
+                }
+                #total_checks
+            }
+        }
+        FieldKind::Vec => {
+            let count_checks = count_checks(field, &quote! { #ident.len() });
+            let total_checks = total_value_checks(field);
+            quote! {
+                #count_checks
+                for utxo in #ident.iter() {
+                    #check_utxo
+                }
+                #total_checks
+            }
+        }
+        FieldKind::OptionalVec => {
+            let min_check = field.attr.min_count.as_ref().map(|min| {
+                let fail = field_error(
+                    field,
+                    &quote! { ErrorCode::IncompleteUtxoGroup },
+                    &quote! { None },
+                );
+                quote! {
+                    if __block.len() < (#min) as usize {
+                        return Err(#fail);
+                    }
+                }
+            });
+            quote! {
+                if let Some(__block) = #ident.as_ref() {
+                    #min_check
+                    for utxo in __block.iter() {
+                        #check_utxo
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Statement failing when the `utxo` in scope no longer matches the field,
+/// with the error code the extractors report for it.
+fn utxo_check(field: &Field) -> TokenStream {
+    let attr = &field.attr;
+    let predicate = super::build_predicate_with_anchor_logic(field);
+    let fail = |code: TokenStream| field_error(field, &code, &quote! { None });
+
+    // Same split between a missing rune and a wrong amount as the extractors.
+    if let (Some(id), true) = (&attr.rune_id_expr, attr.has_rune_amount_constraint()) {
+        let fail_rune_amount = fail(quote! { ErrorCode::InvalidRuneAmount });
+        let fail_rune_id = fail(quote! { ErrorCode::InvalidRuneId });
+        return quote! {
+            if !(#predicate) {
+                if utxo.rune_amount(&(#id)).is_some() {
+                    return Err(#fail_rune_amount);
+                } else {
+                    return Err(#fail_rune_id);
+                }
+            }
+        };
+    }
+
+    let fail_mismatch = fail(base_error_variant(attr));
+    quote! {
+        if !(#predicate) {
+            return Err(#fail_mismatch);
+        }
+    }
+}
@@ -5,7 +5,8 @@
 //! structures first, we decouple parsing/validation from code-generation and
 //! make unit testing trivial.
 
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
 use syn::{Ident, Type};
 
 /// What kind of UTXO collection a field represents.
@@ -14,11 +15,41 @@ pub enum FieldKind {
     /// A single `UtxoInfo` value.
     Single,
     /// A fixed-length array `[UtxoInfo; N]`.
-    Array(usize),
+    Array(ArrayLen),
     /// A catch-all `Vec<UtxoInfo>`.
     Vec,
     /// An optional `Option<UtxoInfo>` value.
     Optional,
+    /// An optional block `Option<Vec<UtxoInfo>>`, supplied whole or not at all.
+    OptionalVec,
+}
+
+/// Length of an array field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayLen {
+    /// An integer literal, `[UtxoInfo; 3]`.
+    Lit(usize),
+    /// A const generic parameter of the struct, `[UtxoInfo; N]`.
+    Const(Ident),
+}
+
+impl ToTokens for ArrayLen {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            ArrayLen::Lit(len) => len.to_tokens(tokens),
+            ArrayLen::Const(ident) => ident.to_tokens(tokens),
+        }
+    }
+}
+
+/// How inputs are matched against fields, from `#[utxo_parser(mode = "...")]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Fields consume inputs in declaration order (default).
+    #[default]
+    Strict,
+    /// Each field searches the not yet consumed inputs for a match.
+    Unordered,
 }
 
 /// Presence predicate coming from `runes = "..."`.
@@ -34,16 +65,66 @@ pub enum RunesPresence {
 pub struct UtxoAttr {
     /// Match only UTXOs whose `value` equals this amount (satoshis).
     pub value: Option<syn::Expr>,
+    /// Match only UTXOs whose `value` is at least this amount (satoshis).
+    pub min_value: Option<syn::Expr>,
+    /// Match only UTXOs whose `value` is at most this amount (satoshis).
+    pub max_value: Option<syn::Expr>,
+    /// Accepted denominations from `value_in = [..]`; `None` when absent.
+    pub value_in: Option<Vec<syn::Expr>>,
     /// Constraints on rune presence (none / some / any).
     pub runes: Option<RunesPresence>,
     /// Expression AST for a specific rune id check.
     pub rune_id_expr: Option<syn::Expr>,
+    /// Rune ids from `rune_id_in = [..]`; the UTXO must hold one of them.
+    pub rune_id_in: Option<Vec<syn::Expr>>,
     /// Expression AST for a specific rune amount check.
     pub rune_amount_expr: Option<syn::Expr>,
+    /// Lower bound (inclusive) on the rune amount, see `rune_amount_expr`.
+    pub rune_amount_at_least: Option<syn::Expr>,
+    /// Upper bound (inclusive) on the rune amount, see `rune_amount_expr`.
+    pub rune_amount_at_most: Option<syn::Expr>,
+    /// Exact number of distinct runes the UTXO must carry.
+    pub rune_count: Option<syn::Expr>,
+    /// Rune ids that must all be present in the UTXO, regardless of amount.
+    pub contains_rune_ids: Vec<syn::Expr>,
     /// Whether this Vec field should capture the remaining inputs.
     pub rest: bool,
+    /// Minimum number of UTXOs a Vec field must capture.
+    pub min_count: Option<syn::Expr>,
+    /// Maximum number of UTXOs a Vec field may capture.
+    pub max_count: Option<syn::Expr>,
     /// Identifier of the accounts struct field to anchor against, if any.
     pub anchor_ident: Option<Ident>,
+    /// Element of the anchor collection a single or optional field is
+    /// anchored to (`anchor = shards[i]`).
+    pub anchor_index: Option<syn::Expr>,
+    /// Group this optional field belongs to, if any: a `one_of` group named
+    /// by `group = ".."`, or the `#[utxo_group(..)]` field declaring it.
+    pub group: Option<syn::LitStr>,
+    /// Minimum number of confirmations the UTXO must have. Host-only.
+    pub min_confirmations: Option<syn::Expr>,
+    /// Expression yielding the script (`AsRef<[u8]>`) the UTXO must pay to.
+    pub script_expr: Option<syn::Expr>,
+    /// Script decoded at compile time from an `address = "..."` literal.
+    pub address_script: Option<Vec<u8>>,
+    /// Account (`Pubkey` or `&Pubkey`) whose script the UTXO must pay to,
+    /// from `owned_by = <expr>`.
+    pub owned_by: Option<syn::Expr>,
+    /// Custom predicate function from `check = path`, called as
+    /// `fn(&UtxoInfo, &Accounts) -> bool`.
+    pub check: Option<syn::Path>,
+    /// Program error returned instead of the generic `ErrorCode` when the
+    /// field fails to match, from `error = <expr>`.
+    pub error: Option<syn::Expr>,
+    /// Exact sum of the values captured by a collection field (satoshis).
+    pub total_value: Option<syn::Expr>,
+    /// Lower bound (inclusive) on the sum of the captured values.
+    pub total_value_min: Option<syn::Expr>,
+    /// Upper bound (inclusive) on the sum of the captured values.
+    pub total_value_max: Option<syn::Expr>,
+    /// Fixed position in the supplied slice from `index = N`; the other
+    /// fields are matched against the remaining inputs.
+    pub index: Option<usize>,
     /// Span of the attribute – kept for diagnostics.
     pub span: Span,
 }
@@ -52,31 +133,205 @@ impl Default for UtxoAttr {
     fn default() -> Self {
         Self {
             value: None,
+            min_value: None,
+            max_value: None,
+            value_in: None,
             runes: None,
             rune_id_expr: None,
+            rune_id_in: None,
             rune_amount_expr: None,
+            rune_amount_at_least: None,
+            rune_amount_at_most: None,
+            rune_count: None,
+            contains_rune_ids: Vec::new(),
             rest: false,
+            min_count: None,
+            max_count: None,
             anchor_ident: None,
+            anchor_index: None,
+            group: None,
+            min_confirmations: None,
+            script_expr: None,
+            address_script: None,
+            owned_by: None,
+            check: None,
+            error: None,
+            total_value: None,
+            total_value_min: None,
+            total_value_max: None,
+            index: None,
             span: Span::call_site(),
         }
     }
 }
 
+impl UtxoAttr {
+    /// Whether the rune amount is constrained, exactly or by a bound.
+    pub fn has_rune_amount_constraint(&self) -> bool {
+        self.rune_amount_expr.is_some()
+            || self.rune_amount_at_least.is_some()
+            || self.rune_amount_at_most.is_some()
+    }
+
+    /// Every user-supplied predicate expression, used to detect references to
+    /// other fields of the struct.
+    pub fn predicate_exprs(&self) -> Vec<&syn::Expr> {
+        let mut exprs: Vec<&syn::Expr> = [
+            &self.value,
+            &self.min_value,
+            &self.max_value,
+            &self.rune_id_expr,
+            &self.rune_amount_expr,
+            &self.rune_amount_at_least,
+            &self.rune_amount_at_most,
+            &self.rune_count,
+            &self.min_confirmations,
+            &self.script_expr,
+            &self.owned_by,
+            &self.min_count,
+            &self.max_count,
+            &self.total_value,
+            &self.total_value_min,
+            &self.total_value_max,
+            &self.anchor_index,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        exprs.extend(self.value_in.iter().flatten());
+        exprs.extend(self.rune_id_in.iter().flatten());
+        exprs.extend(self.contains_rune_ids.iter());
+        exprs
+    }
+
+    /// Mutable counterpart of [`predicate_exprs`](Self::predicate_exprs).
+    pub fn predicate_exprs_mut(&mut self) -> Vec<&mut syn::Expr> {
+        let mut exprs: Vec<&mut syn::Expr> = [
+            &mut self.value,
+            &mut self.min_value,
+            &mut self.max_value,
+            &mut self.rune_id_expr,
+            &mut self.rune_amount_expr,
+            &mut self.rune_amount_at_least,
+            &mut self.rune_amount_at_most,
+            &mut self.rune_count,
+            &mut self.min_confirmations,
+            &mut self.script_expr,
+            &mut self.owned_by,
+            &mut self.min_count,
+            &mut self.max_count,
+            &mut self.total_value,
+            &mut self.total_value_min,
+            &mut self.total_value_max,
+            &mut self.anchor_index,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        exprs.extend(self.value_in.iter_mut().flatten());
+        exprs.extend(self.rune_id_in.iter_mut().flatten());
+        exprs.extend(self.contains_rune_ids.iter_mut());
+        exprs
+    }
+
+    /// Whether any predicate constrains the UTXO (as opposed to accepting
+    /// whatever input sits at its position).
+    pub fn has_predicates(&self) -> bool {
+        self.value.is_some()
+            || self.min_value.is_some()
+            || self.max_value.is_some()
+            || self.value_in.is_some()
+            || self.runes.is_some()
+            || self.rune_id_expr.is_some()
+            || self.rune_id_in.is_some()
+            || self.has_rune_amount_constraint()
+            || self.rune_count.is_some()
+            || !self.contains_rune_ids.is_empty()
+            || self.min_confirmations.is_some()
+            || self.script_expr.is_some()
+            || self.address_script.is_some()
+            || self.owned_by.is_some()
+            || self.check.is_some()
+    }
+}
+
 /// Representation of a single struct field after parsing.
 #[derive(Debug, Clone)]
 pub struct Field {
     pub ident: Ident,
     pub kind: FieldKind,
     pub ty: Type,
+    /// The `UtxoInfo` type of a single element (the field type itself for
+    /// `Single`), e.g. `UtxoInfo<MyRuneSet>` for multi-rune fields.
+    pub elem_ty: Type,
     pub attr: UtxoAttr,
     pub span: Span,
 }
 
+/// All-or-none group declared by a `#[utxo_group(..)]` field of type
+/// `Option<Name>`. The derive generates `Name`, holding one `UtxoInfo` per
+/// member; the members are parsed as `Option<UtxoInfo>` fields of the group.
+#[derive(Debug, Clone)]
+pub struct Group {
+    /// The `Option<Name>` field of the parsed struct.
+    pub ident: Ident,
+    /// `Name`, the generated struct.
+    pub ty_ident: Ident,
+}
+
 /// Parsed, high-level description of the entire derive input.
 #[derive(Debug, Clone)]
 pub struct DeriveInputIr {
     pub struct_ident: Ident,
+    pub vis: syn::Visibility,
     pub generics: syn::Generics,
     pub accounts_ty: Type,
+    /// `#[utxo_params(Type)]`: runtime parameters available as `params` in
+    /// attribute expressions.
+    pub params_ty: Option<Type>,
+    pub mode: ParseMode,
+    /// `#[utxo_parser(allow_extra)]`: leftover inputs are ignored instead of rejected.
+    pub allow_extra: bool,
+    /// Groups declared with `#[utxo_parser(one_of = "..")]`: exactly one member
+    /// must match instead of all or none.
+    pub one_of: Vec<syn::LitStr>,
+    /// Groups declared with `#[utxo_group(..)]`; their members are part of
+    /// `fields`, in place of the group field.
+    pub groups: Vec<Group>,
     pub fields: Vec<Field>,
 }
+
+impl DeriveInputIr {
+    /// Whether the group `name` is an exactly-one `one_of` group.
+    pub fn is_one_of(&self, name: &str) -> bool {
+        self.one_of.iter().any(|g| g.value() == name)
+    }
+
+    /// The `#[utxo_group(..)]` group `field` is a member of, if any.
+    pub fn group_of(&self, field: &Field) -> Option<&Group> {
+        let name = field.attr.group.as_ref()?.value();
+        self.groups.iter().find(|group| group.ident == name)
+    }
+
+    /// Names bound while parsing: every field, group member and group.
+    pub fn bound_idents(&self) -> impl Iterator<Item = &Ident> {
+        let fields = self.fields.iter().map(|f| &f.ident);
+        fields.chain(self.groups.iter().map(|g| &g.ident))
+    }
+
+    /// Fields of the parsed struct, in declaration order: group members are
+    /// replaced by their group field.
+    pub fn struct_field_idents(&self) -> Vec<&Ident> {
+        let mut idents: Vec<&Ident> = Vec::new();
+        for field in &self.fields {
+            let ident = match self.group_of(field) {
+                Some(group) => &group.ident,
+                None => &field.ident,
+            };
+            if !idents.contains(&ident) {
+                idents.push(ident);
+            }
+        }
+        idents
+    }
+}
@@ -70,8 +70,62 @@ enum FieldKind {
 /// ProgramError::Custom(ErrorCode::InvalidRunesPresence.into())
 /// ProgramError::Custom(ErrorCode::InvalidRuneId.into())
 /// ProgramError::Custom(ErrorCode::InvalidRuneAmount.into())
+/// ProgramError::Custom(ErrorCode::InsufficientConfirmations.into())
+/// ProgramError::Custom(ErrorCode::InvalidScriptPubkey.into())
+/// ProgramError::Custom(ErrorCode::UtxoCheckFailed.into())
+///
+/// // The sum of the values captured by a collection field is off
+/// ProgramError::Custom(ErrorCode::InvalidTotalValue.into())
+///
+/// // The slice is too short for a field bound with `index = N`
+/// ProgramError::Custom(ErrorCode::UtxoIndexOutOfBounds.into())
+///
+/// // Only part of a `#[utxo_group(..)]` was supplied
+/// ProgramError::Custom(ErrorCode::IncompleteUtxoGroup.into())
+///
+/// // None or several alternatives of a `one_of` group matched
+/// ProgramError::Custom(ErrorCode::OneOfGroupMismatch.into())
+/// ```
+///
+/// The derive implements `TryFromUtxosWith`, whose `try_utxos_verbose` returns a
+/// `saturn_utxo_parser::ParseFailure` naming the failing field (or group) and
+/// the position of the offending input alongside the `ErrorCode`;
+/// `try_utxos` converts it into the `ProgramError` above.
+///
+/// ```ignore
+/// let failure = SwapUtxos::try_utxos_verbose(&accounts, &utxos).unwrap_err();
+/// msg!("{}", failure); // `fee_utxo`: UTXO value (satoshis) did not match ... (input 3)
+/// ```
+///
+/// # Matching mode
+///
+/// By default fields consume inputs strictly in declaration order: the *n*-th
+/// input must satisfy the *n*-th field. Clients that cannot control input
+/// ordering can opt into search-based matching at the struct level:
+///
+/// ```rust,ignore
+/// #[derive(UtxoParser)]
+/// #[utxo_accounts(DepositAccounts)]
+/// #[utxo_parser(mode = "unordered")]
+/// struct Deposit {
+///     #[utxo(runes = "none")]
+///     fee: UtxoInfo,
+///
+///     #[utxo(runes = "some")]
+///     deposit: UtxoInfo,
+/// }
 /// ```
 ///
+/// In `"unordered"` mode each field, in declaration order, takes the first
+/// not yet consumed input satisfying its predicate. `mode = "strict"` selects
+/// the default explicitly.
+///
+/// Inputs left over once every field has been extracted make parsing fail with
+/// `UnexpectedExtraUtxos`. Programs that intentionally accept and forward
+/// unknown inputs (e.g. donation sweeps) can add the `allow_extra` flag,
+/// `#[utxo_parser(allow_extra)]`, to ignore them instead. In strict mode the
+/// leftovers must still come after the inputs consumed by the fields.
+///
 /// # Supported field types
 ///
 /// | Rust type                               | Behaviour                                              |
@@ -80,10 +134,24 @@ enum FieldKind {
 /// | `Option<UtxoInfo>`                       | Zero or one matching UTXO may be present.              |
 /// | `[UtxoInfo; N]`                          | Exactly *N* matching UTXOs must be present.            |
 /// | `Vec<UtxoInfo>` **(see `rest`)**         | Variable-length list capturing remaining UTXOs.        |
+/// | `Option<Vec<UtxoInfo>>`                  | Optional block of matching UTXOs, whole or absent.     |
 ///
 /// A `Vec` field **must** be annotated with the `rest` flag, otherwise the
 /// compilation will fail.
 ///
+/// An `Option<Vec<UtxoInfo>>` field takes the run of consecutive inputs
+/// matching its predicate (every matching input in unordered mode), e.g. an
+/// optional block of rune deposits. It is `None` when no input matches. `max`
+/// caps the number of inputs the block takes and `min` is the smallest
+/// complete block: a shorter run fails with `ErrorCode::IncompleteUtxoGroup`.
+///
+/// The array length is either an integer literal or a const generic parameter
+/// of the struct, e.g. `struct Batch<const N: usize> { inputs: [UtxoInfo; N] }`.
+///
+/// `UtxoInfo` may carry an explicit rune set, e.g. `UtxoInfo<MyRuneSet>` where
+/// `MyRuneSet` is any `FixedCapacitySet<Item = RuneAmount>`, so pools whose
+/// UTXOs hold several runes can use the derive as well.
+///
 /// # `#[utxo(..)]` attribute
 ///
 /// The attribute accepts a comma-separated list of *flags* and *key/value*
@@ -94,22 +162,309 @@ enum FieldKind {
 ///     inputs not matched by earlier fields.
 ///
 /// ## Key/Value Pairs
+///   * `min = <expr>` / `max = <expr>` – on `Vec` fields (`rest` or anchored),
+///     require at least / at most this many UTXOs. Too few fails with
+///     `ErrorCode::MissingRequiredUtxo`, too many with
+///     `ErrorCode::UnexpectedExtraUtxos`. On `Option<Vec<UtxoInfo>>` fields
+///     they bound the block instead, see above.
+///   * `total_value = <expr>`, `total_value_min = <expr>` /
+///     `total_value_max = <expr>` – on `Vec` and array fields, require the sum
+///     of the captured UTXO values to equal, or lie within the inclusive range.
+///     The exact form cannot be combined with the bounds. Fails with
+///     `ErrorCode::InvalidTotalValue`.
 ///   * `value = <expr>` – match only UTXOs whose `value` (in satoshis) is equal
 ///     to the given expression.
+///   * `min_value = <expr>` / `max_value = <expr>` – match only UTXOs whose
+///     `value` lies within the inclusive range. Either bound may be omitted;
+///     neither can be combined with `value`. Fails with
+///     `ErrorCode::InvalidUtxoValue`.
+///   * `value_in = [<expr>, ..]` – match only UTXOs whose `value` equals one of
+///     the listed amounts, e.g. tiered fee denominations. Cannot be combined
+///     with `value`, `min_value` or `max_value`. Fails with
+///     `ErrorCode::InvalidUtxoValue`.
 ///   * `runes = "none" | "some" | "any"` – constrain presence of runes:
 ///       * `"none"` – assert that no runes are present.
 ///       * `"some"` – assert that at least one rune is present.
 ///       * `"any"` – do not check runes (default).
 ///   * `rune_id = <expr>` – match only UTXOs that contain the specified rune
 ///     id. May be combined with `rune_amount` for an exact match.
+///   * `rune_id_in = [<expr>, ..]` – match UTXOs holding *any* of the listed
+///     rune ids. For `UtxoInfo` and `Option<UtxoInfo>` fields the derive also
+///     emits a `fn <field>_rune_id(&self, accounts) -> Option<RuneId>` accessor
+///     returning the listed id that matched; parsers with `#[utxo_params]` also
+///     pass the parameters. Cannot be combined with `rune_id`.
 ///   * `rune_amount = <expr>` – If `rune_id` is also provided, require the UTXO
 ///     to hold exactly this amount of the given rune. Otherwise require the
 ///     *total* rune amount inside the UTXO to equal the expression.
+///   * `rune_amount_at_least = <expr>` / `rune_amount_at_most = <expr>` –
+///     inclusive bounds with the same scoping as `rune_amount` (the given
+///     `rune_id`, otherwise the total). Either bound may be omitted; neither
+///     can be combined with `rune_amount`.
+///   * `rune_count = <expr>` – require the UTXO to carry exactly this many
+///     distinct runes.
+///   * `contains_rune_id = <expr>` – require the UTXO to hold the given rune,
+///     whatever its amount and whatever other runes it carries. May be
+///     repeated to require several runes.
 ///   * `anchor = <ident>` – Expect identifier that refers to a field in the Accounts struct. If `runes` is **omitted** on an anchored field, it is implicitly treated as `runes = "none"` for backward compatibility.
+///     The target may be any account wrapper implementing `ToAccountInfo`
+///     (`AccountInfo`, `Account`, `AccountLoader`), or a collection of them
+///     for `Vec` / array fields. A `UtxoInfo` or `Option<UtxoInfo>` field can
+///     anchor to a single element with `anchor = shards[<expr>]`; an index
+///     past the end of the collection fails with
+///     `ErrorCode::AnchorIndexOutOfBounds`.
+///   * `group = "<name>"` – place an `Option<UtxoInfo>` field in a `one_of`
+///     group, listed in the struct-level `#[utxo_parser(one_of = "<name>")]`
+///     attribute (repeatable). Members must be declared next to each other and
+///     are alternatives, e.g. paying with a BTC UTXO *or* a rune UTXO: exactly
+///     one member must match, otherwise parsing fails with
+///     `ErrorCode::OneOfGroupMismatch`. In strict mode the alternatives are
+///     tried on the input at the cursor and an input accepted by several of
+///     them is rejected as ambiguous; in unordered mode each alternative
+///     searches the remaining inputs.
+///   * `min_confirmations = <expr>` – **host-only**: require the UTXO to have
+///     at least this many confirmations relative to the current Bitcoin tip,
+///     in tests and simulators. A parser using it does not compile for the
+///     on-chain (`target_os = "solana"`) target: the runtime reports the tip
+///     (`get_bitcoin_block_height`) but not the block that confirmed an
+///     output, so programs cannot check confirmations. Needs the
+///     `utxo-confirmations` feature so that `UtxoInfo::block_height` is
+///     available; without it every UTXO reports zero confirmations.
+///   * `script = <expr>` – require the UTXO to pay exactly to the given script
+///     (any expression implementing `AsRef<[u8]>`, e.g. a `ScriptBuf` or the
+///     result of `get_account_script_pubkey`). Needs the `utxo-script` feature.
+///   * `address = "<address>"` – same as `script`, with the script decoded at
+///     compile time from a Bitcoin address literal. Mutually exclusive with
+///     `script`.
+///   * `owned_by = <expr>` – require the UTXO to pay to the script of the
+///     given account (a `Pubkey` or `&Pubkey`, e.g. `crate::ID` or
+///     `accounts.pool.key`), as returned by `get_account_script_pubkey`. Tells
+///     program-owned shard UTXOs apart from user deposits. Mutually exclusive
+///     with `script` and `address`; needs the `utxo-script` feature.
+///   * `check = <path>` – call a function with signature
+///     `fn(&UtxoInfo, &Accounts) -> bool` for validations the attribute
+///     language can't express, e.g. comparing against on-chain config held in
+///     the accounts struct. It runs after the other predicates of the field.
+///     When it is the field's only constraint a rejection fails with
+///     `ErrorCode::UtxoCheckFailed`.
+///   * `error = <expr>` – return the program's own error (any expression
+///     convertible into `ProgramError`, e.g. `MyError::BadFeeUtxo`) whenever
+///     this field fails to match, instead of the generic `ErrorCode` variant.
+///     Not available on `group` and `#[utxo_group(..)]` members.
+///   * `index = <int>` – bind a `UtxoInfo` field to a fixed position of the
+///     supplied slice, for instructions whose wire protocol gives positions a
+///     meaning. The field takes that input whatever the mode and the other
+///     fields, which are matched against the remaining inputs, and its
+///     predicates are still checked. A slice too short for the position fails
+///     with `ErrorCode::UtxoIndexOutOfBounds`.
 ///
 /// The predicate generated from these parameters is applied to each candidate
 /// UTXO until a match is found.
 ///
+/// ## Referencing earlier fields
+///
+/// Fields are extracted in declaration order and each parsed field is in scope
+/// for the predicates of the fields that follow it, so constraints can relate
+/// several inputs:
+///
+/// ```rust,ignore
+/// #[derive(UtxoParser)]
+/// #[utxo_accounts(SwapAccounts)]
+/// struct Swap {
+///     #[utxo(runes = "none")]
+///     fee_utxo: UtxoInfo,
+///
+///     #[utxo(value = fee_utxo.value * 2)]
+///     collateral: UtxoInfo,
+///
+///     #[utxo(runes = "some")]
+///     rune_deposit: UtxoInfo,
+///
+///     #[utxo(rune_id = rune_deposit.runes.get().unwrap().id)]
+///     rune_change: UtxoInfo,
+/// }
+/// ```
+///
+/// Referring to a field declared later (or to the field itself, or to another
+/// member of the same group) is rejected at compile time.
+///
+/// ## All-or-none groups
+///
+/// Optional legs made of several UTXOs, e.g. a refund path present only
+/// sometimes, are declared as one `Option<Name>` field whose
+/// `#[utxo_group(..)]` attribute lists the members like struct fields. The
+/// derive generates `Name` with one `UtxoInfo` per member:
+///
+/// ```rust,ignore
+/// #[derive(UtxoParser)]
+/// #[utxo_accounts(SwapAccounts)]
+/// struct Swap {
+///     #[utxo(runes = "none")]
+///     fee_utxo: UtxoInfo,
+///
+///     #[utxo_group(
+///         #[utxo(value = 2_000)]
+///         btc: UtxoInfo,
+///         #[utxo(runes = "some")]
+///         runes: UtxoInfo,
+///     )]
+///     refund: Option<SwapRefund>,
+/// }
+/// ```
+///
+/// Either every member matches (consuming consecutive inputs in strict mode)
+/// and the field is `Some(SwapRefund { btc, runes })`, or none does and it is
+/// `None`; otherwise parsing fails with `ErrorCode::IncompleteUtxoGroup`.
+/// Predicates of later fields see the members as `Option<&UtxoInfo>`.
+///
+/// ## Reading the accounts
+///
+/// The accounts struct is in scope as `accounts` for every attribute
+/// expression, and such expressions may use `?` on `Result<_, ProgramError>`,
+/// e.g. to read a zero-copy account through its loader:
+///
+/// ```rust,ignore
+/// #[derive(UtxoParser)]
+/// #[utxo_accounts(PoolAccounts)]
+/// struct Swap {
+///     #[utxo(value = accounts.config.load()?.fee_sats, runes = "none")]
+///     fee_utxo: UtxoInfo,
+/// }
+/// ```
+///
+/// Expressions reading `accounts` or using `?` are evaluated once, right
+/// before their field is extracted, so the borrow taken by `load()` is released
+/// before the inputs are searched. Their value must therefore not borrow from
+/// the loaded view; copy or clone what the predicate needs. An error raised by
+/// `?` fails the parse with `ErrorCode::AccountsExpressionFailed`, and
+/// `try_utxos` returns the original `ProgramError`. No field may be named
+/// `accounts`.
+///
+/// ## Runtime parameters
+///
+/// Constraints only known from the instruction data can be passed in at
+/// runtime. `#[utxo_params(Type)]` sets the parser's `Params` type, whose
+/// value is in scope as `params` for every attribute expression, and the
+/// parser is then run with `try_utxos_with(accounts, utxos, &params)` from
+/// `TryFromUtxosWith`. Such parsers do not implement `TryFromUtxos`, so they
+/// cannot be run without their parameters, e.g. through `#[utxos(..)]` in a
+/// `#[saturn_program]` handler.
+///
+/// ```rust,ignore
+/// pub struct SwapParams {
+///     pub expected_fee: u64,
+/// }
+///
+/// #[derive(UtxoParser)]
+/// #[utxo_accounts(SwapAccounts)]
+/// #[utxo_params(SwapParams)]
+/// struct Swap {
+///     #[utxo(value = params.expected_fee, runes = "none")]
+///     fee_utxo: UtxoInfo,
+/// }
+///
+/// let utxos = Swap::try_utxos_with(&ctx.accounts, &metas, &SwapParams { expected_fee })?;
+/// ```
+///
+/// The `<field>_rune_id` accessors of such parsers take the parameters as an
+/// extra argument.
+///
+/// # Conversion
+///
+/// Each input is converted from `UtxoMeta` to `UtxoInfo` through the runtime.
+/// When every field uses the same `UtxoInfo` type, an input is converted the
+/// first time a field inspects it and the result is kept for the fields after
+/// it, so no input is converted twice and inputs no field reaches are not
+/// converted at all. Parsers mixing `UtxoInfo` types convert on demand.
+///
+/// [`saturn_utxo_parser::metas_to_infos`] converts a whole list up front.
+///
+/// Parsers whose fields share one `UtxoInfo` type also get
+/// `try_utxos_partial(accounts, utxos, &params, &mut scratch)`, which does not fail on inputs left over by the fields: it
+/// returns them alongside the parsed struct as `(Self, &[UtxoInfo])` (a view
+/// into `scratch`, cleared first), ready to be fed to a second parser or to
+/// consolidation logic.
+///
+/// ```rust,ignore
+/// let (swap, rest) = Swap::try_utxos_partial(&ctx.accounts, &metas, &(), &mut scratch)?;
+/// for utxo in rest {
+///     consolidate(utxo)?;
+/// }
+/// ```
+///
+/// # Tracing
+///
+/// With the `trace` feature (`saturn-utxo-parser/trace`) the generated code
+/// logs, through `msg!`, every input each field tests and, for the rejected
+/// ones, the attribute keys whose constraints failed:
+///
+/// ```text
+/// `fee`: input 0 rejected (value, runes)
+/// `fee`: input 1 matched
+/// `deposit`: input 0 rejected (rune_id)
+/// ```
+///
+/// Rejected inputs are checked again key by key to name every failing one, so
+/// `check` functions may run twice. The feature is meant for devnet debugging
+/// rather than production builds. Off-chain the lines are also available to
+/// tests through `saturn_utxo_parser::trace::take_trace_log()`.
+///
+/// # Re-verification
+///
+/// Every parser gets `verify(&self, accounts)` (plus `params` with
+/// `#[utxo_params]`) and its `verify_verbose` counterpart returning a
+/// `ParseFailure`. They evaluate the predicates of every field again on the
+/// captured UTXOs, along with the `total_value` and count bounds, failing with
+/// the error the parse would have reported (without an input position).
+/// Handlers that change the state those predicates read can re-assert the
+/// invariants before building the transaction:
+///
+/// ```rust,ignore
+/// let deposit = Deposit::try_utxos(&accounts, utxos)?;
+/// accounts.pool.load_mut()?.min_deposit = new_min;
+/// deposit.verify(&accounts)?;
+/// ```
+///
+/// Expressions referring to earlier fields see them by reference (`&UtxoInfo`,
+/// `&Option<UtxoInfo>`, ..). The input layout (order, `index` positions,
+/// group completeness) is fixed once parsed and not checked again.
+///
+/// # Layout descriptor
+///
+/// The derive also emits `const LAYOUT: saturn_utxo_parser::UtxoLayout`
+/// describing every field (cardinality, value and rune constraints, position,
+/// group, ...) so that off-chain SDKs and indexers can build compliant input
+/// sets programmatically. Integer literals are reported as
+/// `LayoutExpr::Literal`; any other expression (constants, function calls,
+/// references to earlier fields) is reported with its Rust source as
+/// `LayoutExpr::Source`.
+///
+/// ```rust,ignore
+/// let fee = Swap::LAYOUT.field("fee_utxo").unwrap();
+/// assert_eq!(fee.runes.presence, RunesRequirement::None);
+/// ```
+///
+/// # Test fixtures
+///
+/// With the `test-utils` feature (`saturn-utxo-parser/test-utils`), parsers
+/// whose fields share one `UtxoInfo` type also get host-only
+/// `fixture() -> Vec<UtxoInfo>` and `fixture_with(customize)` constructors.
+/// They build UTXOs satisfying the declared constraints, in an order the
+/// parser accepts, and register them with `register_test_utxo_info`, so a
+/// test only needs their metas:
+///
+/// ```rust,ignore
+/// let metas: Vec<UtxoMeta> = Swap::fixture().iter().map(|u| u.meta.clone()).collect();
+/// let swap = Swap::try_utxos(&accounts, &metas)?;
+/// ```
+///
+/// Every single and optional field gets one UTXO (only the first alternative
+/// of a `one_of` group), arrays get their length and `rest` collections their
+/// `min_count`. Constraints the derive cannot evaluate on its own (those
+/// reading other fields, `accounts` or `params`, and `check` functions) are
+/// left at defaults; `fixture_with` calls `customize(field_name, &mut utxo)`
+/// on every UTXO before registration to fill them in. Anchored collections
+/// are left empty.
+///
 /// # Examples
 ///
 /// ## Basic Usage
@@ -166,6 +521,23 @@ enum FieldKind {
 /// }
 /// ```
 ///
+/// ## Multi-rune UTXOs
+///
+/// ```rust,ignore
+/// use saturn_utxo_parser::{UtxoParser, TryFromUtxos};
+/// use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+/// use saturn_collections::declare_fixed_set;
+///
+/// declare_fixed_set!(PairRuneSet, RuneAmount, 2);
+///
+/// #[derive(UtxoParser)]
+/// struct PoolDeposit {
+///     // Pool UTXO holding both runes of the pair.
+///     #[utxo(rune_count = 2, contains_rune_id = RUNE_A, contains_rune_id = RUNE_B)]
+///     pool: UtxoInfo<PairRuneSet>,
+/// }
+/// ```
+///
 /// # Important Notes
 ///
 /// - Field order matters: UTXOs are matched in the order fields appear in the struct
@@ -682,7 +1054,10 @@ fn derive_utxo_parser_old(item: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(UtxoParser, attributes(utxo, utxo_accounts))]
+#[proc_macro_derive(
+    UtxoParser,
+    attributes(utxo, utxo_accounts, utxo_group, utxo_params, utxo_parser)
+)]
 pub fn derive_utxo_parser(item: TokenStream) -> TokenStream {
     // Parse the incoming tokens into `syn::DeriveInput` first.
     let input = parse_macro_input!(item as DeriveInput);
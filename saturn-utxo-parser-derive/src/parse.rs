@@ -5,7 +5,9 @@ use proc_macro2::Span;
 use quote::ToTokens;
 use syn::{spanned::Spanned, Expr, Lit};
 
-use crate::ir::{DeriveInputIr, Field, FieldKind, RunesPresence, UtxoAttr};
+use crate::ir::{
+    ArrayLen, DeriveInputIr, Field, FieldKind, Group, ParseMode, RunesPresence, UtxoAttr,
+};
 
 /// Convert a `syn::DeriveInput` representing the struct annotated with
 /// `#[derive(UtxoParser)]` into the crate's internal IR.
@@ -18,6 +20,7 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
     // Fetch the `#[utxo_accounts(TypePath)]` attribute.
     // ---------------------------------------------------------------------
     let mut accounts_ty: Option<syn::Type> = None;
+    let mut params_ty: Option<syn::Type> = None;
     for attr in &input.attrs {
         if attr.path().is_ident("utxo_accounts") {
             if accounts_ty.is_some() {
@@ -27,6 +30,103 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
                 ));
             }
             accounts_ty = Some(attr.parse_args::<syn::Type>()?);
+        } else if attr.path().is_ident("utxo_params") {
+            // Optional `#[utxo_params(TypePath)]`: runtime parameters of `try_utxos_with`.
+            if params_ty.is_some() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "duplicate #[utxo_params] attribute",
+                ));
+            }
+            params_ty = Some(attr.parse_args::<syn::Type>()?);
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // Optional `#[utxo_parser(mode = "strict" | "unordered", allow_extra)]`
+    // attribute.
+    // ---------------------------------------------------------------------
+    let mut mode: Option<ParseMode> = None;
+    let mut allow_extra = false;
+    let mut one_of: Vec<syn::LitStr> = Vec::new();
+    for attr in &input.attrs {
+        if !attr.path().is_ident("utxo_parser") {
+            continue;
+        }
+        let args = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+        )?;
+        for meta in args {
+            match meta {
+                syn::Meta::Path(path) if path.is_ident("allow_extra") => {
+                    if allow_extra {
+                        return Err(syn::Error::new(
+                            path.span(),
+                            "duplicate `allow_extra` flag inside #[utxo_parser(...)] attribute",
+                        ));
+                    }
+                    allow_extra = true;
+                }
+                syn::Meta::NameValue(nv) if nv.path.is_ident("mode") => {
+                    if mode.is_some() {
+                        return Err(syn::Error::new(
+                            nv.path.span(),
+                            "duplicate `mode` key inside #[utxo_parser(...)] attribute",
+                        ));
+                    }
+                    mode = match &nv.value {
+                        Expr::Lit(syn::ExprLit {
+                            lit: Lit::Str(lit_str),
+                            ..
+                        }) => match lit_str.value().as_str() {
+                            "strict" => Some(ParseMode::Strict),
+                            "unordered" => Some(ParseMode::Unordered),
+                            other => {
+                                return Err(syn::Error::new(
+                                    lit_str.span(),
+                                    format!(
+                                        "unsupported mode '{}'. expected 'strict' or 'unordered'",
+                                        other
+                                    ),
+                                ));
+                            }
+                        },
+                        other => {
+                            return Err(syn::Error::new(
+                                other.span(),
+                                "`mode` expects a string literal",
+                            ));
+                        }
+                    };
+                }
+                // May be repeated, once per group.
+                syn::Meta::NameValue(nv) if nv.path.is_ident("one_of") => match &nv.value {
+                    Expr::Lit(syn::ExprLit {
+                        lit: Lit::Str(lit_str),
+                        ..
+                    }) => {
+                        if one_of.iter().any(|g| g.value() == lit_str.value()) {
+                            return Err(syn::Error::new(
+                                lit_str.span(),
+                                format!("group `{}` is listed twice in `one_of`", lit_str.value()),
+                            ));
+                        }
+                        one_of.push(lit_str.clone());
+                    }
+                    other => {
+                        return Err(syn::Error::new(
+                            other.span(),
+                            "`one_of` expects a group name string literal",
+                        ));
+                    }
+                },
+                other => {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        "Unknown key inside #[utxo_parser(...)] attribute, expected `mode`, `allow_extra` or `one_of`",
+                    ));
+                }
+            }
         }
     }
 
@@ -63,7 +163,22 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
     // ---------------------------------------------------------------------
     let mut fields_ir = Vec::<Field>::new();
 
+    // The members of a `#[utxo_group(..)]` field are parsed in its place.
+    let mut groups = Vec::<Group>::new();
+    let mut declared = Vec::<(syn::Field, Option<syn::LitStr>)>::new();
     for field in fields_named {
+        match field.attrs.iter().find(|a| a.path().is_ident("utxo_group")) {
+            Some(group_attr) => {
+                let (group, members) = parse_group(field, group_attr)?;
+                let name = syn::LitStr::new(&group.ident.to_string(), group.ident.span());
+                declared.extend(members.into_iter().map(|m| (m, Some(name.clone()))));
+                groups.push(group);
+            }
+            None => declared.push((field.clone(), None)),
+        }
+    }
+
+    for (field, member_of) in &declared {
         let ident = field.ident.clone().expect("named field");
         let span = field.span();
 
@@ -132,6 +247,40 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
                                 // Accept any Rust expression; defer type checking to the compiler.
                                 attr.value = Some(nv.value.clone());
                             }
+                            "min_value" => {
+                                if attr.min_value.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `min_value` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                attr.min_value = Some(nv.value.clone());
+                            }
+                            "max_value" => {
+                                if attr.max_value.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `max_value` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                attr.max_value = Some(nv.value.clone());
+                            }
+                            "value_in" => {
+                                if attr.value_in.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `value_in` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                if let Expr::Array(array) = &nv.value {
+                                    attr.value_in = Some(array.elems.iter().cloned().collect());
+                                } else {
+                                    return Err(syn::Error::new(
+                                        nv.value.span(),
+                                        "`value_in` expects an array literal, e.g. `value_in = [10_000, 50_000]`",
+                                    ));
+                                }
+                            }
                             "runes" => {
                                 if attr.runes.is_some() {
                                     return Err(syn::Error::new(
@@ -178,6 +327,22 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
                                 // Store the expression verbatim – it can be any valid Rust expr path/value.
                                 attr.rune_id_expr = Some(nv.value.clone());
                             }
+                            "rune_id_in" => {
+                                if attr.rune_id_in.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `rune_id_in` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                if let Expr::Array(array) = &nv.value {
+                                    attr.rune_id_in = Some(array.elems.iter().cloned().collect());
+                                } else {
+                                    return Err(syn::Error::new(
+                                        nv.value.span(),
+                                        "`rune_id_in` expects an array literal, e.g. `rune_id_in = [RUNE_A, RUNE_B]`",
+                                    ));
+                                }
+                            }
                             "rune_amount" => {
                                 if attr.rune_amount_expr.is_some() {
                                     return Err(syn::Error::new(
@@ -187,6 +352,54 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
                                 }
                                 attr.rune_amount_expr = Some(nv.value.clone());
                             }
+                            "rune_amount_at_least" => {
+                                if attr.rune_amount_at_least.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `rune_amount_at_least` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                attr.rune_amount_at_least = Some(nv.value.clone());
+                            }
+                            "rune_amount_at_most" => {
+                                if attr.rune_amount_at_most.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `rune_amount_at_most` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                attr.rune_amount_at_most = Some(nv.value.clone());
+                            }
+                            "rune_count" => {
+                                if attr.rune_count.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `rune_count` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                attr.rune_count = Some(nv.value.clone());
+                            }
+                            // May be repeated: every listed rune id must be present.
+                            "contains_rune_id" => {
+                                attr.contains_rune_ids.push(nv.value.clone());
+                            }
+                            "min" | "max" => {
+                                let slot = if key == "min" {
+                                    &mut attr.min_count
+                                } else {
+                                    &mut attr.max_count
+                                };
+                                if slot.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        format!(
+                                            "duplicate `{}` key inside #[utxo(...)] attribute",
+                                            key
+                                        ),
+                                    ));
+                                }
+                                *slot = Some(nv.value.clone());
+                            }
                             "anchor" => {
                                 if attr.anchor_ident.is_some() {
                                     return Err(syn::Error::new(
@@ -194,9 +407,17 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
                                         "duplicate `anchor` key inside #[utxo(...)] attribute",
                                     ));
                                 }
-                                if let Expr::Path(expr_path) = &nv.value {
+                                // `anchor = field` or `anchor = field[<expr>]`.
+                                let (target, index) = match &nv.value {
+                                    Expr::Index(expr_index) => {
+                                        (&*expr_index.expr, Some(&*expr_index.index))
+                                    }
+                                    other => (other, None),
+                                };
+                                if let Expr::Path(expr_path) = target {
                                     if let Some(id) = expr_path.path.get_ident() {
                                         attr.anchor_ident = Some(id.clone());
+                                        attr.anchor_index = index.cloned();
                                     } else {
                                         return Err(syn::Error::new(
                                             expr_path.span(),
@@ -210,6 +431,126 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
                                     ));
                                 }
                             }
+                            "min_confirmations" => {
+                                if attr.min_confirmations.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `min_confirmations` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                attr.min_confirmations = Some(nv.value.clone());
+                            }
+                            "script" | "address" | "owned_by" => {
+                                if attr.script_expr.is_some()
+                                    || attr.address_script.is_some()
+                                    || attr.owned_by.is_some()
+                                {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "only one `script`, `address` or `owned_by` key is allowed inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                match key.as_str() {
+                                    "script" => attr.script_expr = Some(nv.value.clone()),
+                                    "address" => {
+                                        attr.address_script = Some(parse_address(&nv.value)?)
+                                    }
+                                    _ => attr.owned_by = Some(nv.value.clone()),
+                                }
+                            }
+                            "check" => {
+                                if attr.check.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `check` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                if let Expr::Path(expr_path) = &nv.value {
+                                    attr.check = Some(expr_path.path.clone());
+                                } else {
+                                    return Err(syn::Error::new(
+                                        nv.value.span(),
+                                        "`check` expects a function path, e.g. `check = my_module::is_valid_deposit`",
+                                    ));
+                                }
+                            }
+                            "error" => {
+                                if attr.error.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `error` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                attr.error = Some(nv.value.clone());
+                            }
+                            "total_value" | "total_value_min" | "total_value_max" => {
+                                let slot = match key.as_str() {
+                                    "total_value" => &mut attr.total_value,
+                                    "total_value_min" => &mut attr.total_value_min,
+                                    _ => &mut attr.total_value_max,
+                                };
+                                if slot.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        format!(
+                                            "duplicate `{}` key inside #[utxo(...)] attribute",
+                                            key
+                                        ),
+                                    ));
+                                }
+                                *slot = Some(nv.value.clone());
+                            }
+                            "index" => {
+                                if attr.index.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `index` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                match &nv.value {
+                                    Expr::Lit(syn::ExprLit {
+                                        lit: Lit::Int(lit_int),
+                                        ..
+                                    }) => {
+                                        attr.index = Some(lit_int.base10_parse::<usize>()?);
+                                    }
+                                    other => {
+                                        return Err(syn::Error::new(
+                                            other.span(),
+                                            "`index` expects an integer literal",
+                                        ));
+                                    }
+                                }
+                            }
+                            "group" => {
+                                if attr.group.is_some() {
+                                    return Err(syn::Error::new(
+                                        nv.path.span(),
+                                        "duplicate `group` key inside #[utxo(...)] attribute",
+                                    ));
+                                }
+                                match &nv.value {
+                                    Expr::Lit(syn::ExprLit {
+                                        lit: Lit::Str(lit_str),
+                                        ..
+                                    }) => {
+                                        // The group name doubles as the accessor method name.
+                                        lit_str.parse::<syn::Ident>().map_err(|_| {
+                                            syn::Error::new(
+                                                lit_str.span(),
+                                                "`group` must be a valid Rust identifier",
+                                            )
+                                        })?;
+                                        attr.group = Some(lit_str.clone());
+                                    }
+                                    other => {
+                                        return Err(syn::Error::new(
+                                            other.span(),
+                                            "`group` expects a string literal",
+                                        ));
+                                    }
+                                }
+                            }
                             other => {
                                 return Err(syn::Error::new(
                                     nv.path.span(),
@@ -231,7 +572,7 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
         // --------------------------------------------------------------
         // Deduce FieldKind from `ty`.
         // --------------------------------------------------------------
-        let kind = match &field.ty {
+        let (kind, elem_ty) = match &field.ty {
             syn::Type::Reference(_) => {
                 return Err(syn::Error::new(
                     Span::call_site(),
@@ -243,18 +584,34 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
                     ensure_utxo_info_type(&*arr.elem)?;
                     if let Lit::Int(lit_int) = &expr_lit.lit {
                         let len = lit_int.base10_parse::<usize>()?;
-                        FieldKind::Array(len)
+                        (FieldKind::Array(ArrayLen::Lit(len)), (*arr.elem).clone())
                     } else {
                         return Err(syn::Error::new(
                             expr_lit.span(),
-                            "array length must be an integer literal",
+                            "array length must be an integer literal or a const generic parameter",
                         ));
                     }
                 }
+                // `[UtxoInfo; N]` where `N` is a const generic of the struct.
+                Expr::Path(expr_path)
+                    if expr_path.path.get_ident().is_some_and(|id| {
+                        input
+                            .generics
+                            .const_params()
+                            .any(|param| param.ident == *id)
+                    }) =>
+                {
+                    ensure_utxo_info_type(&*arr.elem)?;
+                    let ident = expr_path.path.get_ident().cloned().expect("checked above");
+                    (
+                        FieldKind::Array(ArrayLen::Const(ident)),
+                        (*arr.elem).clone(),
+                    )
+                }
                 other => {
                     return Err(syn::Error::new(
                         other.span(),
-                        "array length must be an integer literal",
+                        "array length must be an integer literal or a const generic parameter",
                     ));
                 }
             },
@@ -263,19 +620,26 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
                     match seg.ident.to_string().as_str() {
                         "Vec" => {
                             // Ensure the generic parameter is `UtxoInfo` (or a path ending with it).
-                            validate_utxo_info_generic(seg, type_path)?;
-                            FieldKind::Vec
-                        }
-                        "Option" => {
-                            validate_utxo_info_generic(seg, type_path)?;
-                            FieldKind::Optional
+                            let elem = validate_utxo_info_generic(seg, type_path)?;
+                            (FieldKind::Vec, elem)
                         }
+                        "Option" => match option_inner_vec(seg) {
+                            // `Option<Vec<UtxoInfo>>`: an optional variable-length block.
+                            Some((vec_seg, vec_path)) => {
+                                let elem = validate_utxo_info_generic(vec_seg, vec_path)?;
+                                (FieldKind::OptionalVec, elem)
+                            }
+                            None => {
+                                let elem = validate_utxo_info_generic(seg, type_path)?;
+                                (FieldKind::Optional, elem)
+                            }
+                        },
                         // Bare `UtxoInfo` (without reference) is now allowed as a single owned field.
-                        "UtxoInfo" => FieldKind::Single,
+                        "UtxoInfo" => (FieldKind::Single, field.ty.clone()),
                         _ => {
                             return Err(syn::Error::new(
                                 type_path.span(),
-                                "Unsupported field type for UtxoParser derive. Expected Vec<UtxoInfo>, Option<UtxoInfo>, UtxoInfo, or array [UtxoInfo; N]",
+                                "Unsupported field type for UtxoParser derive. Expected Vec<UtxoInfo>, Option<UtxoInfo>, Option<Vec<UtxoInfo>>, UtxoInfo, or array [UtxoInfo; N]",
                             ));
                         }
                     }
@@ -291,23 +655,100 @@ pub fn derive_input_to_ir(input: &syn::DeriveInput) -> syn::Result<DeriveInputIr
             }
         };
 
-        fields_ir.push(Field {
+        let mut field_ir = Field {
             ident,
             kind,
             ty: field.ty.clone(),
+            elem_ty,
             attr,
             span,
-        });
+        };
+        if let Some(name) = member_of {
+            if field_ir.kind != FieldKind::Single {
+                return Err(syn::Error::new(
+                    field.ty.span(),
+                    "members of #[utxo_group(...)] must be of type `UtxoInfo`",
+                ));
+            }
+            if let Some(group) = &field_ir.attr.group {
+                return Err(syn::Error::new(
+                    group.span(),
+                    "members of #[utxo_group(...)] cannot set `group`",
+                ));
+            }
+            // Parsed like `Option<UtxoInfo>` fields of an all-or-none group.
+            let elem_ty = &field_ir.elem_ty;
+            field_ir.ty = syn::parse_quote!(Option<#elem_ty>);
+            field_ir.kind = FieldKind::Optional;
+            field_ir.attr.group = Some(name.clone());
+        } else if let Some(name) = &field_ir.attr.group {
+            if groups.iter().any(|g| g.ident == name.value()) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!(
+                        "`{}` is declared with #[utxo_group(...)]; list its members there",
+                        name.value()
+                    ),
+                ));
+            }
+        }
+        fields_ir.push(field_ir);
     }
 
     Ok(DeriveInputIr {
         struct_ident: input.ident.clone(),
+        vis: input.vis.clone(),
         generics: input.generics.clone(),
         accounts_ty,
+        params_ty,
+        mode: mode.unwrap_or_default(),
+        allow_extra,
+        one_of,
+        groups,
         fields: fields_ir,
     })
 }
 
+/// Split an `Option<Name>` field carrying `#[utxo_group(member: UtxoInfo, ..)]`
+/// into its group and the declared members.
+fn parse_group(
+    field: &syn::Field,
+    group_attr: &syn::Attribute,
+) -> syn::Result<(Group, Vec<syn::Field>)> {
+    if let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("utxo")) {
+        return Err(syn::Error::new(
+            attr.span(),
+            "constraints of a group belong to its members inside #[utxo_group(...)]",
+        ));
+    }
+
+    let ty_ident = option_inner_ident(&field.ty).ok_or_else(|| {
+        syn::Error::new(
+            field.ty.span(),
+            "#[utxo_group(...)] fields must be of type `Option<Name>`, `Name` being the struct generated for the group",
+        )
+    })?;
+
+    let members = group_attr.parse_args_with(|input: syn::parse::ParseStream| {
+        syn::punctuated::Punctuated::<syn::Field, syn::Token![,]>::parse_terminated_with(
+            input,
+            syn::Field::parse_named,
+        )
+    })?;
+    if members.is_empty() {
+        return Err(syn::Error::new(
+            group_attr.span(),
+            "#[utxo_group(...)] must declare at least one member",
+        ));
+    }
+
+    let group = Group {
+        ident: field.ident.clone().expect("named field"),
+        ty_ident,
+    };
+    Ok((group, members.into_iter().collect()))
+}
+
 fn expr_to_string(expr: &Expr) -> String {
     expr.to_token_stream().to_string()
 }
@@ -345,14 +786,212 @@ mod tests {
         let normalized = value_str.replace([' ', '_'], "");
         assert_eq!(normalized, "1000");
         assert_eq!(f.attr.runes, Some(RunesPresence::None));
+        assert_eq!(ir.mode, ParseMode::Strict);
+        assert!(!ir.allow_extra);
+    }
+
+    #[test]
+    fn parses_unordered_mode() {
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_parser(mode = "unordered")]
+            struct Simple {
+                fee: UtxoInfo,
+            }
+        "#;
+        let ir = derive_input_to_ir(&parse_di(code)).expect("parse ok");
+        assert_eq!(ir.mode, ParseMode::Unordered);
+
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_parser(mode = "random")]
+            struct Simple {
+                fee: UtxoInfo,
+            }
+        "#;
+        assert!(derive_input_to_ir(&parse_di(code)).is_err());
+    }
+
+    #[test]
+    fn parses_const_generic_array_len() {
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            struct Batch<const N: usize> {
+                inputs: [UtxoInfo; N],
+                fixed: [UtxoInfo; 2],
+            }
+        "#;
+        let ir = derive_input_to_ir(&parse_di(code)).expect("parse ok");
+        assert_eq!(
+            ir.fields[0].kind,
+            FieldKind::Array(ArrayLen::Const(syn::parse_quote!(N)))
+        );
+        assert_eq!(ir.fields[1].kind, FieldKind::Array(ArrayLen::Lit(2)));
+
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            struct Batch {
+                inputs: [UtxoInfo; LEN],
+            }
+        "#;
+        assert!(derive_input_to_ir(&parse_di(code)).is_err());
+    }
+
+    #[test]
+    fn parses_optional_vec() {
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            struct S {
+                block: Option<Vec<UtxoInfo<MyRuneSet>>>,
+            }
+        "#;
+        let ir = derive_input_to_ir(&parse_di(code)).expect("parse ok");
+        assert_eq!(ir.fields[0].kind, FieldKind::OptionalVec);
+        let elem = ir.fields[0].elem_ty.to_token_stream().to_string();
+        assert_eq!(elem.replace(' ', ""), "UtxoInfo<MyRuneSet>");
+    }
+
+    #[test]
+    fn parses_one_of_groups() {
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_parser(one_of = "payment", one_of = "refund")]
+            struct Simple {
+                fee: UtxoInfo,
+            }
+        "#;
+        let ir = derive_input_to_ir(&parse_di(code)).expect("parse ok");
+        assert!(ir.is_one_of("payment"));
+        assert!(ir.is_one_of("refund"));
+        assert!(!ir.is_one_of("fee"));
+
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_parser(one_of = "payment", one_of = "payment")]
+            struct Simple {
+                fee: UtxoInfo,
+            }
+        "#;
+        assert!(derive_input_to_ir(&parse_di(code)).is_err());
+    }
+
+    #[test]
+    fn parses_utxo_groups() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                fee: UtxoInfo,
+                #[utxo_group(
+                    #[utxo(value = 2_000)]
+                    btc: UtxoInfo,
+                    change: UtxoInfo,
+                )]
+                refund: Option<Refund>,
+            }
+        "#;
+        let ir = derive_input_to_ir(&parse_di(code)).unwrap();
+        assert_eq!(ir.groups.len(), 1);
+        assert_eq!(ir.groups[0].ident, "refund");
+        assert_eq!(ir.groups[0].ty_ident, "Refund");
+
+        let names: Vec<String> = ir.fields.iter().map(|f| f.ident.to_string()).collect();
+        assert_eq!(names, ["fee", "btc", "change"]);
+        assert_eq!(ir.fields[1].kind, FieldKind::Optional);
+        assert_eq!(ir.fields[1].attr.group.as_ref().unwrap().value(), "refund");
+        assert!(ir.fields[1].attr.value.is_some());
+        assert_eq!(ir.struct_field_idents(), ["fee", "refund"]);
+
+        // The group field must be `Option<Name>` and its members `UtxoInfo`.
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo_group(btc: UtxoInfo)]
+                refund: Refund,
+            }
+        "#;
+        assert!(derive_input_to_ir(&parse_di(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo_group(btc: Option<UtxoInfo>)]
+                refund: Option<Refund>,
+            }
+        "#;
+        assert!(derive_input_to_ir(&parse_di(code)).is_err());
+    }
+
+    #[test]
+    fn parses_params_type() {
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_params(FeeParams)]
+            struct Simple {
+                #[utxo(value = params.fee)]
+                fee: UtxoInfo,
+            }
+        "#;
+        let ir = derive_input_to_ir(&parse_di(code)).expect("parse ok");
+        let params_ty = ir.params_ty.as_ref().unwrap().to_token_stream();
+        assert_eq!(params_ty.to_string(), "FeeParams");
+
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_params(FeeParams)]
+            #[utxo_params(OtherParams)]
+            struct Simple {
+                fee: UtxoInfo,
+            }
+        "#;
+        assert!(derive_input_to_ir(&parse_di(code)).is_err());
+    }
+
+    #[test]
+    fn parses_indexed_anchor() {
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            struct Simple {
+                #[utxo(anchor = shards[1])]
+                shard_utxo: UtxoInfo,
+            }
+        "#;
+        let ir = derive_input_to_ir(&parse_di(code)).expect("parse ok");
+        let attr = &ir.fields[0].attr;
+        assert_eq!(attr.anchor_ident, Some(syn::parse_quote!(shards)));
+        let index = attr.anchor_index.as_ref().unwrap().to_token_stream();
+        assert_eq!(index.to_string(), "1");
+    }
+
+    #[test]
+    fn parses_allow_extra_flag() {
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_parser(mode = "unordered", allow_extra)]
+            struct Simple {
+                fee: UtxoInfo,
+            }
+        "#;
+        let ir = derive_input_to_ir(&parse_di(code)).expect("parse ok");
+        assert_eq!(ir.mode, ParseMode::Unordered);
+        assert!(ir.allow_extra);
+
+        let code = r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_parser(allow_extra, allow_extra)]
+            struct Simple {
+                fee: UtxoInfo,
+            }
+        "#;
+        assert!(derive_input_to_ir(&parse_di(code)).is_err());
     }
 }
 
-// Helper: verify that the last segment's generic argument is exactly `UtxoInfo` (by ident), otherwise return an error.
+// Helper: verify that the last segment's generic argument is exactly `UtxoInfo` (by ident) and
+// return it, otherwise return an error.
 fn validate_utxo_info_generic(
     seg: &syn::PathSegment,
     type_path: &syn::TypePath,
-) -> syn::Result<()> {
+) -> syn::Result<syn::Type> {
     use syn::{GenericArgument, PathArguments, Type};
 
     let err = || {
@@ -372,7 +1011,7 @@ fn validate_utxo_info_generic(
                     Type::Path(inner_path) => {
                         if let Some(last) = inner_path.path.segments.last() {
                             if last.ident == "UtxoInfo" {
-                                return Ok(());
+                                return Ok(inner_ty.clone());
                             }
                         }
                         Err(err())
@@ -387,6 +1026,43 @@ fn validate_utxo_info_generic(
     }
 }
 
+// Helper: for an `Option<Vec<..>>` segment return the inner `Vec` segment and its type path.
+fn option_inner_vec(seg: &syn::PathSegment) -> Option<(&syn::PathSegment, &syn::TypePath)> {
+    use syn::{GenericArgument, PathArguments, Type};
+
+    let PathArguments::AngleBracketed(ab) = &seg.arguments else {
+        return None;
+    };
+    match ab.args.first() {
+        Some(GenericArgument::Type(Type::Path(inner_path))) if ab.args.len() == 1 => {
+            let last = inner_path.path.segments.last()?;
+            (last.ident == "Vec").then_some((last, inner_path))
+        }
+        _ => None,
+    }
+}
+
+// Helper: `Name` for an `Option<Name>` type, `Name` being a plain identifier.
+fn option_inner_ident(ty: &syn::Type) -> Option<syn::Ident> {
+    use syn::{GenericArgument, PathArguments, Type};
+
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let seg = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(ab) = &seg.arguments else {
+        return None;
+    };
+    match ab.args.first() {
+        Some(GenericArgument::Type(Type::Path(inner)))
+            if seg.ident == "Option" && ab.args.len() == 1 =>
+        {
+            inner.path.get_ident().cloned()
+        }
+        _ => None,
+    }
+}
+
 // NEW: helper for validating that an arbitrary `Type` is (or ends with) `UtxoInfo`.
 fn ensure_utxo_info_type(ty: &syn::Type) -> syn::Result<()> {
     use syn::Type;
@@ -410,3 +1086,24 @@ fn ensure_utxo_info_type(ty: &syn::Type) -> syn::Result<()> {
         ))
     }
 }
+
+/// Decode an `address = "..."` literal into the `script_pubkey` it pays to.
+fn parse_address(value: &Expr) -> syn::Result<Vec<u8>> {
+    use std::str::FromStr;
+
+    let Expr::Lit(syn::ExprLit {
+        lit: Lit::Str(lit_str),
+        ..
+    }) = value
+    else {
+        return Err(syn::Error::new(
+            value.span(),
+            "`address` expects a string literal",
+        ));
+    };
+
+    let address = bitcoin::Address::from_str(&lit_str.value())
+        .map_err(|e| syn::Error::new(lit_str.span(), format!("invalid bitcoin address: {e}")))?;
+
+    Ok(address.assume_checked().script_pubkey().to_bytes())
+}
@@ -25,9 +25,21 @@ pub fn check(ir: &DeriveInputIr) -> syn::Result<()> {
     // ---------------------------------------------------------------------
     use std::collections::HashMap;
     let mut anchor_usage: HashMap<&syn::Ident, Vec<&FieldKind>> = HashMap::new();
+    let mut indexed_anchors: Vec<&syn::Ident> = Vec::new();
     for field in &ir.fields {
         if let Some(ident) = &field.attr.anchor_ident {
-            anchor_usage.entry(ident).or_default().push(&field.kind);
+            // `anchor = shards[i]` only touches one element of the collection.
+            if field.attr.anchor_index.is_some() {
+                if !matches!(field.kind, FieldKind::Single | FieldKind::Optional) {
+                    return Err(Error::new(
+                        field.span,
+                        "an indexed `anchor = <field>[<index>]` is only allowed on UtxoInfo and Option<UtxoInfo> fields",
+                    ));
+                }
+                indexed_anchors.push(ident);
+            } else {
+                anchor_usage.entry(ident).or_default().push(&field.kind);
+            }
         }
     }
 
@@ -38,6 +50,15 @@ pub fn check(ir: &DeriveInputIr) -> syn::Result<()> {
         let has_scalar = kinds
             .iter()
             .any(|k| matches!(k, FieldKind::Single | FieldKind::Optional));
+        if has_collection && indexed_anchors.contains(&ident) {
+            return Err(Error::new(
+                ir.struct_ident.span(),
+                format!(
+                    "anchor target `{}` is used by both a collection field and indexed fields; the indexed elements would be anchored twice",
+                    ident
+                ),
+            ));
+        }
         if has_collection && has_scalar {
             return Err(Error::new(
                 ir.struct_ident.span(),
@@ -90,6 +111,13 @@ pub fn check(ir: &DeriveInputIr) -> syn::Result<()> {
                     ));
                 }
             }
+        } else if let FieldKind::OptionalVec = field.kind {
+            if field.attr.rest || field.attr.anchor_ident.is_some() {
+                return Err(Error::new(
+                    field.span,
+                    "Option<Vec<UtxoInfo>> fields match a block of consecutive inputs and cannot use `rest` or `anchor`",
+                ));
+            }
         } else {
             // Non-Vec field must not use `rest` flag.
             if field.attr.rest {
@@ -98,7 +126,45 @@ pub fn check(ir: &DeriveInputIr) -> syn::Result<()> {
                     "`rest` flag is only allowed on Vec fields",
                 ));
             }
+            if field.attr.min_count.is_some() || field.attr.max_count.is_some() {
+                return Err(Error::new(
+                    field.span,
+                    "`min` / `max` counts are only allowed on Vec fields",
+                ));
+            }
+        }
+
+        check_range(&field.attr.min_count, &field.attr.max_count, "min", "max")?;
+    }
+
+    // ---------------------------------------------------------------------
+    // Positional `index = N` fields: plain `UtxoInfo` only, each position
+    // bound at most once.
+    // ---------------------------------------------------------------------
+    let mut pinned: Vec<usize> = Vec::new();
+    for field in &ir.fields {
+        let Some(index) = field.attr.index else {
+            continue;
+        };
+        if field.kind != FieldKind::Single {
+            return Err(Error::new(
+                field.span,
+                "`index` is only allowed on UtxoInfo fields",
+            ));
+        }
+        if field.attr.group.is_some() {
+            return Err(Error::new(
+                field.attr.span,
+                "`index` cannot be combined with `group`",
+            ));
         }
+        if pinned.contains(&index) {
+            return Err(Error::new(
+                field.attr.span,
+                format!("input position {} is bound by more than one field", index),
+            ));
+        }
+        pinned.push(index);
     }
 
     // ---------------------------------------------------------------------
@@ -128,12 +194,225 @@ pub fn check(ir: &DeriveInputIr) -> syn::Result<()> {
         }
     }
 
+    // ---------------------------------------------------------------------
+    // Optional group constraints: members must be `Option<UtxoInfo>` and
+    // declared next to each other so the group maps onto one contiguous
+    // window of the (strictly ordered) input slice.
+    // ---------------------------------------------------------------------
+    let mut closed_groups: Vec<String> = Vec::new();
+    let mut open_group: Option<String> = None;
+    for field in &ir.fields {
+        let group = field.attr.group.as_ref().map(|g| g.value());
+
+        if let Some(name) = &group {
+            if field.kind != FieldKind::Optional {
+                return Err(Error::new(
+                    field.span,
+                    format!(
+                        "fields in group `{}` must be of type Option<UtxoInfo>",
+                        name
+                    ),
+                ));
+            }
+            if closed_groups.contains(name) {
+                return Err(Error::new(
+                    field.span,
+                    format!("fields in group `{}` must be declared contiguously", name),
+                ));
+            }
+            if field.attr.error.is_some() {
+                return Err(Error::new(
+                    field.attr.span,
+                    format!(
+                        "`error` cannot be used on members of group `{}`, an incomplete group always fails with ErrorCode::IncompleteUtxoGroup",
+                        name
+                    ),
+                ));
+            }
+        }
+
+        if open_group != group {
+            if let Some(prev) = open_group.take() {
+                closed_groups.push(prev);
+            }
+            open_group = group;
+        }
+    }
+
+    // `group = ".."` only names `one_of` groups, all-or-none groups are
+    // declared with `#[utxo_group(..)]`.
+    for field in &ir.fields {
+        let Some(name) = &field.attr.group else {
+            continue;
+        };
+        if ir.group_of(field).is_none() && !ir.is_one_of(&name.value()) {
+            return Err(Error::new(
+                name.span(),
+                format!(
+                    "group `{}` is not listed in #[utxo_parser(one_of = ..)]; declare all-or-none groups with #[utxo_group(...)] on an `Option<Name>` field",
+                    name.value()
+                ),
+            ));
+        }
+    }
+    if let Some(group) = ir
+        .groups
+        .iter()
+        .find(|g| ir.is_one_of(&g.ident.to_string()))
+    {
+        return Err(Error::new(
+            group.ident.span(),
+            format!(
+                "`{}` is a #[utxo_group(...)], it cannot be a `one_of` group",
+                group.ident
+            ),
+        ));
+    }
+
+    // Group members are bound under their own name next to the other fields.
+    let mut names: Vec<&syn::Ident> = Vec::new();
+    for ident in ir.bound_idents() {
+        if names.contains(&ident) {
+            return Err(Error::new(
+                ident.span(),
+                format!("`{}` is declared more than once", ident),
+            ));
+        }
+        names.push(ident);
+    }
+
+    // `one_of` must name a group with at least two alternatives.
+    for name in &ir.one_of {
+        let members = ir
+            .fields
+            .iter()
+            .filter(|f| f.attr.group.as_ref().map(|g| g.value()) == Some(name.value()))
+            .count();
+        if members < 2 {
+            return Err(Error::new(
+                name.span(),
+                format!(
+                    "`one_of` group `{}` must have at least two member fields",
+                    name.value()
+                ),
+            ));
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // `params` is the name under which `#[utxo_params]` are in scope, a field
+    // of that name would shadow them.
+    // ---------------------------------------------------------------------
+    if ir.params_ty.is_some() {
+        if let Some(ident) = ir.bound_idents().find(|ident| *ident == "params") {
+            return Err(Error::new(
+                ident.span(),
+                "field name `params` is reserved for the #[utxo_params] value",
+            ));
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // `accounts` is the name under which the accounts struct is in scope for
+    // attribute expressions.
+    // ---------------------------------------------------------------------
+    if let Some(ident) = ir.bound_idents().find(|ident| *ident == "accounts") {
+        return Err(Error::new(
+            ident.span(),
+            "field name `accounts` is reserved for the #[utxo_accounts] value",
+        ));
+    }
+
+    // ---------------------------------------------------------------------
+    // Cross-field references: fields are extracted in declaration order, so a
+    // predicate may only use fields declared *before* it. Members of a group
+    // are bound together once the whole group is parsed, so they cannot refer
+    // to each other either.
+    // ---------------------------------------------------------------------
+    for (pos, field) in ir.fields.iter().enumerate() {
+        for expr in field.attr.predicate_exprs() {
+            for referenced in referenced_idents(expr) {
+                let Some(target_pos) = ir.fields.iter().position(|f| f.ident == referenced) else {
+                    continue;
+                };
+                let target = &ir.fields[target_pos];
+                let same_group = field.attr.group.is_some()
+                    && field.attr.group.as_ref().map(|g| g.value())
+                        == target.attr.group.as_ref().map(|g| g.value());
+
+                if target_pos >= pos || same_group {
+                    return Err(Error::new(
+                        referenced.span(),
+                        format!(
+                            "field `{}` is referenced by the predicate of `{}` before it is parsed; predicates may only refer to fields declared earlier (outside the same group)",
+                            referenced, field.ident
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // Value constraints: an exact `value` or a `value_in` set makes a range
+    // redundant, and an empty literal range or set can never match.
+    // ---------------------------------------------------------------------
+    for field in &ir.fields {
+        let attr = &field.attr;
+        if attr.value.is_some() && (attr.min_value.is_some() || attr.max_value.is_some()) {
+            return Err(Error::new(
+                attr.span,
+                "`value` cannot be combined with `min_value` or `max_value`",
+            ));
+        }
+
+        if let Some(values) = &attr.value_in {
+            if attr.value.is_some() || attr.min_value.is_some() || attr.max_value.is_some() {
+                return Err(Error::new(
+                    attr.span,
+                    "`value_in` cannot be combined with `value`, `min_value` or `max_value`",
+                ));
+            }
+            if values.is_empty() {
+                return Err(Error::new(
+                    attr.span,
+                    "`value_in` must list at least one value",
+                ));
+            }
+        }
+
+        check_range(&attr.min_value, &attr.max_value, "min_value", "max_value")?;
+
+        // Aggregate constraints apply to the UTXOs captured by a collection.
+        let has_total_bound = attr.total_value_min.is_some() || attr.total_value_max.is_some();
+        if attr.total_value.is_some() || has_total_bound {
+            if !matches!(field.kind, FieldKind::Vec | FieldKind::Array(_)) {
+                return Err(Error::new(
+                    attr.span,
+                    "`total_value` constraints are only allowed on Vec and array fields",
+                ));
+            }
+            if attr.total_value.is_some() && has_total_bound {
+                return Err(Error::new(
+                    attr.span,
+                    "`total_value` cannot be combined with `total_value_min` or `total_value_max`",
+                ));
+            }
+        }
+        check_range(
+            &attr.total_value_min,
+            &attr.total_value_max,
+            "total_value_min",
+            "total_value_max",
+        )?;
+    }
+
     // ---------------------------------------------------------------------
     // Incompatible rune constraints (e.g. `runes = "none"` with `rune_id`/`rune_amount`).
     // ---------------------------------------------------------------------
     for field in &ir.fields {
         if matches!(field.attr.runes, Some(crate::ir::RunesPresence::None))
-            && (field.attr.rune_id_expr.is_some() || field.attr.rune_amount_expr.is_some())
+            && (field.attr.rune_id_expr.is_some() || field.attr.has_rune_amount_constraint())
         {
             return Err(Error::new(
                 field.attr.span,
@@ -141,6 +420,53 @@ pub fn check(ir: &DeriveInputIr) -> syn::Result<()> {
             ));
         }
 
+        if let Some(ids) = &field.attr.rune_id_in {
+            if field.attr.rune_id_expr.is_some() {
+                return Err(Error::new(
+                    field.attr.span,
+                    "`rune_id_in` cannot be combined with `rune_id`",
+                ));
+            }
+            if ids.is_empty() {
+                return Err(Error::new(
+                    field.attr.span,
+                    "`rune_id_in` must list at least one rune id",
+                ));
+            }
+            if matches!(field.attr.runes, Some(crate::ir::RunesPresence::None)) {
+                return Err(Error::new(
+                    field.attr.span,
+                    "`runes = \"none\"` cannot be combined with `rune_id_in`",
+                ));
+            }
+        }
+
+        if field.attr.rune_amount_expr.is_some()
+            && (field.attr.rune_amount_at_least.is_some()
+                || field.attr.rune_amount_at_most.is_some())
+        {
+            return Err(Error::new(
+                field.attr.span,
+                "`rune_amount` cannot be combined with `rune_amount_at_least` or `rune_amount_at_most`",
+            ));
+        }
+
+        check_range(
+            &field.attr.rune_amount_at_least,
+            &field.attr.rune_amount_at_most,
+            "rune_amount_at_least",
+            "rune_amount_at_most",
+        )?;
+
+        if matches!(field.attr.runes, Some(crate::ir::RunesPresence::None))
+            && !field.attr.contains_rune_ids.is_empty()
+        {
+            return Err(Error::new(
+                field.attr.span,
+                "`runes = \"none\"` cannot be combined with `contains_rune_id`",
+            ));
+        }
+
         // Prevent logically impossible combination of `runes = "some"` with
         // a zero `rune_amount` literal (the predicate would never match).
         if let (Some(crate::ir::RunesPresence::Some), Some(expr)) =
@@ -166,6 +492,61 @@ pub fn check(ir: &DeriveInputIr) -> syn::Result<()> {
     Ok(())
 }
 
+/// Single-segment identifiers (`foo`, `foo.value`, `foo.runes.get()`) used
+/// as expression paths inside `expr`.
+fn referenced_idents(expr: &syn::Expr) -> Vec<syn::Ident> {
+    use syn::visit::Visit;
+
+    struct Collector(Vec<syn::Ident>);
+    impl<'ast> Visit<'ast> for Collector {
+        fn visit_expr_path(&mut self, expr_path: &'ast syn::ExprPath) {
+            if expr_path.qself.is_none() && expr_path.path.leading_colon.is_none() {
+                if let Some(ident) = expr_path.path.get_ident() {
+                    self.0.push(ident.clone());
+                }
+            }
+            syn::visit::visit_expr_path(self, expr_path);
+        }
+    }
+
+    let mut collector = Collector(Vec::new());
+    collector.visit_expr(expr);
+    collector.0
+}
+
+/// Rejects a lower / upper bound pair, named `min_key` / `max_key` in the
+/// attribute, whose integer literals describe an empty range. Bounds that are
+/// not literals are checked at runtime.
+fn check_range(
+    min: &Option<syn::Expr>,
+    max: &Option<syn::Expr>,
+    min_key: &str,
+    max_key: &str,
+) -> syn::Result<()> {
+    if let (Some(min), Some(max)) = (min, max) {
+        if let (Some(min_v), Some(max_v)) = (int_literal(min), int_literal(max)) {
+            if min_v > max_v {
+                return Err(Error::new(
+                    max.span(),
+                    format!("`{max_key}` must not be lower than `{min_key}`"),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Value of `expr` when it is a plain integer literal.
+pub(crate) fn int_literal(expr: &syn::Expr) -> Option<u128> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit_int),
+            ..
+        }) => lit_int.base10_parse::<u128>().ok(),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,4 +572,422 @@ mod tests {
         let ir = ir_from(code);
         assert!(check(&ir).is_ok());
     }
+
+    #[test]
+    fn params_name_is_reserved() {
+        let ir = ir_from(
+            r#"
+            #[utxo_accounts(DummyAccs)]
+            #[utxo_params(FeeParams)]
+            struct S {
+                params: UtxoInfo,
+            }
+        "#,
+        );
+        assert!(check(&ir).is_err());
+
+        let ir = ir_from(
+            r#"
+            #[utxo_accounts(DummyAccs)]
+            struct S {
+                params: UtxoInfo,
+            }
+        "#,
+        );
+        assert!(check(&ir).is_ok());
+    }
+
+    #[test]
+    fn accounts_name_is_reserved() {
+        let ir = ir_from(
+            r#"
+            #[utxo_accounts(DummyAccs)]
+            struct S {
+                accounts: UtxoInfo,
+            }
+        "#,
+        );
+        assert!(check(&ir).is_err());
+    }
+
+    #[test]
+    fn indexed_anchor_rules() {
+        let ir = ir_from(
+            r#"
+            #[utxo_accounts(DummyAccs)]
+            struct S {
+                #[utxo(anchor = shards[0])]
+                first: UtxoInfo,
+                #[utxo(anchor = shards[1])]
+                second: Option<UtxoInfo>,
+            }
+        "#,
+        );
+        assert!(check(&ir).is_ok());
+
+        let ir = ir_from(
+            r#"
+            #[utxo_accounts(DummyAccs)]
+            struct S {
+                #[utxo(anchor = shards[0])]
+                first: [UtxoInfo; 2],
+            }
+        "#,
+        );
+        assert!(check(&ir).is_err());
+
+        let ir = ir_from(
+            r#"
+            #[utxo_accounts(DummyAccs)]
+            struct S {
+                #[utxo(anchor = shards[0])]
+                first: UtxoInfo,
+                #[utxo(anchor = shards)]
+                all: Vec<UtxoInfo>,
+            }
+        "#,
+        );
+        assert!(check(&ir).is_err());
+    }
+
+    #[test]
+    fn value_range_must_not_be_empty() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(min_value = 10_000, max_value = 5_000)]
+                a: UtxoInfo,
+            }
+        "#;
+        let ir = ir_from(code);
+        assert!(check(&ir).is_err());
+    }
+
+    #[test]
+    fn optional_vec_rules() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(runes = "some", min = 2, max = 4)]
+                block: Option<Vec<UtxoInfo>>,
+                #[utxo(rest)]
+                others: Vec<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_ok());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(rest)]
+                block: Option<Vec<UtxoInfo>>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(min = 3, max = 2)]
+                block: Option<Vec<UtxoInfo>>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+    }
+
+    #[test]
+    fn total_value_rules() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(total_value_min = 1_000)]
+                a: UtxoInfo,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(rest, total_value = 5_000, total_value_max = 6_000)]
+                a: Vec<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(rest, total_value_min = 10_000, total_value_max = 5_000)]
+                a: Vec<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(total_value = 3_000)]
+                a: [UtxoInfo; 2],
+                #[utxo(rest, total_value_min = a[0].value)]
+                b: Vec<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_ok());
+    }
+
+    #[test]
+    fn value_cannot_combine_with_range() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(value = 5_000, min_value = 1_000)]
+                a: UtxoInfo,
+            }
+        "#;
+        let ir = ir_from(code);
+        assert!(check(&ir).is_err());
+    }
+
+    #[test]
+    fn value_in_must_not_be_empty() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(value_in = [])]
+                a: UtxoInfo,
+            }
+        "#;
+        let ir = ir_from(code);
+        assert!(check(&ir).is_err());
+    }
+
+    #[test]
+    fn backward_field_reference_ok() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                fee: UtxoInfo,
+                #[utxo(value = fee.value * 2)]
+                doubled: UtxoInfo,
+            }
+        "#;
+        let ir = ir_from(code);
+        assert!(check(&ir).is_ok());
+    }
+
+    #[test]
+    fn forward_field_reference_rejected() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(value = doubled.value / 2)]
+                fee: UtxoInfo,
+                doubled: UtxoInfo,
+            }
+        "#;
+        let ir = ir_from(code);
+        let err = check(&ir).unwrap_err();
+        assert!(err.to_string().contains("`doubled`"));
+    }
+
+    #[test]
+    fn self_and_same_group_references_rejected() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(min_value = fee.value)]
+                fee: UtxoInfo,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo_group(
+                    a: UtxoInfo,
+                    #[utxo(value = a.unwrap().value)]
+                    b: UtxoInfo,
+                )]
+                refund: Option<Refund>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+    }
+
+    #[test]
+    fn counts_only_on_vec_fields() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(min = 1)]
+                a: UtxoInfo,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(rest, min = 3, max = 2)]
+                a: Vec<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(rest, min = 1, max = 8)]
+                a: Vec<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_ok());
+    }
+
+    #[test]
+    fn group_requires_optional_fields() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            #[utxo_parser(one_of = "refund")]
+            struct S {
+                #[utxo(group = "refund")]
+                a: UtxoInfo,
+                #[utxo(group = "refund")]
+                b: Option<UtxoInfo>,
+            }
+        "#;
+        let ir = ir_from(code);
+        assert!(check(&ir).is_err());
+    }
+
+    #[test]
+    fn group_must_be_contiguous() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            #[utxo_parser(one_of = "refund")]
+            struct S {
+                #[utxo(group = "refund")]
+                a: Option<UtxoInfo>,
+                b: UtxoInfo,
+                #[utxo(group = "refund")]
+                c: Option<UtxoInfo>,
+            }
+        "#;
+        let ir = ir_from(code);
+        assert!(check(&ir).is_err());
+    }
+
+    #[test]
+    fn index_rules() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(index = 1)]
+                a: UtxoInfo,
+                #[utxo(index = 1)]
+                b: UtxoInfo,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(index = 0)]
+                a: Option<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                fee: UtxoInfo,
+                #[utxo(index = 2, runes = "none")]
+                state: UtxoInfo,
+                #[utxo(rest)]
+                others: Vec<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_ok());
+    }
+
+    #[test]
+    fn one_of_requires_alternatives() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            #[utxo_parser(one_of = "payment")]
+            struct S {
+                #[utxo(group = "payment", value = 1_000)]
+                btc: Option<UtxoInfo>,
+                #[utxo(group = "payment", runes = "some")]
+                rune: Option<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_ok());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            #[utxo_parser(one_of = "payment")]
+            struct S {
+                #[utxo(group = "refund")]
+                btc: Option<UtxoInfo>,
+                #[utxo(group = "refund")]
+                rune: Option<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+    }
+
+    #[test]
+    fn group_members_reject_error_override() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo_group(
+                    #[utxo(error = MyError::BadRefund)]
+                    a: UtxoInfo,
+                    b: UtxoInfo,
+                )]
+                refund: Option<Refund>,
+            }
+        "#;
+        let ir = ir_from(code);
+        assert!(check(&ir).is_err());
+    }
+
+    #[test]
+    fn group_key_names_one_of_groups_only() {
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo(group = "refund")]
+                a: Option<UtxoInfo>,
+                #[utxo(group = "refund")]
+                b: Option<UtxoInfo>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                fee: UtxoInfo,
+                #[utxo_group(a: UtxoInfo, fee: UtxoInfo)]
+                refund: Option<Refund>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_err());
+
+        let code = r#"
+            #[utxo_accounts(Accs)]
+            struct S {
+                #[utxo_group(a: UtxoInfo, b: UtxoInfo)]
+                refund: Option<Refund>,
+            }
+        "#;
+        assert!(check(&ir_from(code)).is_ok());
+    }
 }
@@ -0,0 +1,155 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::codec::zero_copy::Discriminator;
+use saturn_account_parser::codec::AccountLoader;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos, TryFromUtxosWith};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+struct PoolConfig {
+    fee_sats: u64,
+    min_deposit: u64,
+}
+
+impl Discriminator for PoolConfig {
+    const DISCRIMINATOR: [u8; 8] = *b"poolconf";
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(ConfigAccounts)]
+struct Deposit {
+    #[utxo(value = accounts.config.load()?.fee_sats, runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(rest, min_value = accounts.config.load()?.min_deposit)]
+    deposits: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(ConfigAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct Refund {
+    #[utxo(max_value = accounts.limit)]
+    refund: UtxoInfo,
+}
+
+#[test]
+fn constraints_read_loaded_accounts() {
+    let accounts = ConfigAccounts::new(700, 10_000);
+    let inputs = vec![
+        create_utxo(700, 1, 0),
+        create_utxo(12_000, 1, 1),
+        create_utxo(10_000, 1, 2),
+    ];
+
+    let parsed = Deposit::try_utxos(&accounts, &inputs).unwrap();
+    assert_eq!(parsed.fee.value, 700);
+    assert_eq!(parsed.deposits.len(), 2);
+
+    // The loader is not borrowed any more once the parse returns.
+    assert!(accounts.config.load_mut().is_ok());
+
+    let accounts = ConfigAccounts::new(800, 10_000);
+    let err = Deposit::try_utxos(&accounts, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+#[test]
+fn failing_load_is_reported_on_the_field() {
+    let accounts = ConfigAccounts::uninitialized();
+    let inputs = vec![create_utxo(700, 2, 0)];
+
+    let failure = Deposit::try_utxos_verbose(&accounts, &inputs).unwrap_err();
+    assert_eq!(failure.field, "fee");
+    assert_eq!(failure.code, ErrorCode::AccountsExpressionFailed);
+    assert_eq!(failure.error, ProgramError::InvalidAccountData);
+
+    let err = Deposit::try_utxos(&accounts, &inputs).unwrap_err();
+    assert_eq!(err, ProgramError::InvalidAccountData);
+}
+
+#[test]
+fn plain_accounts_fields_are_in_scope() {
+    let accounts = ConfigAccounts::new(700, 10_000);
+    let inputs = vec![create_utxo(4_000, 3, 0)];
+    assert!(Refund::try_utxos(&accounts, &inputs).is_ok());
+
+    let inputs = vec![create_utxo(6_000, 3, 1)];
+    let err = Refund::try_utxos(&accounts, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Accounts holding a zero-copy config.
+// -------------------------------------------------------------------------------------------------
+struct ConfigAccounts<'info> {
+    config: AccountLoader<'info, PoolConfig>,
+    limit: u64,
+}
+
+impl<'info> AccountsTrait<'info> for ConfigAccounts<'info> {
+    fn try_accounts(
+        _accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Self, arch_program::program_error::ProgramError> {
+        Ok(Self::new(0, 0))
+    }
+}
+
+impl<'info> ConfigAccounts<'info> {
+    fn new(fee_sats: u64, min_deposit: u64) -> Self {
+        // Discriminator followed by the `PoolConfig` fields.
+        let words = [
+            u64::from_le_bytes(PoolConfig::DISCRIMINATOR),
+            fee_sats,
+            min_deposit,
+        ];
+        Self::with_data(words)
+    }
+
+    fn uninitialized() -> Self {
+        Self::with_data([0; 3])
+    }
+
+    fn with_data(words: [u64; 3]) -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime; the
+        // data is stored as words to keep the config 8-byte aligned.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::from_slice(&[1; 32])));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let words: &'static mut [u64; 3] = Box::leak(Box::new(words));
+        let data: &'static mut [u8] = bytemuck::cast_slice_mut(&mut words[..]);
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let account: &'static AccountInfo<'static> = Box::leak(Box::new(AccountInfo::new(
+            key, lamports, data, key, utxo_meta, false, true, false,
+        )));
+
+        Self {
+            config: AccountLoader::new(account),
+            limit: 5_000,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(allow_extra)]
+struct Sweep {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered", allow_extra)]
+struct UnorderedSweep {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo(value = 5_000)]
+    deposit: Option<UtxoInfo>,
+}
+
+#[test]
+fn strict_mode_ignores_trailing_inputs() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 1, 0),
+        create_utxo(42, 1, 1),
+        create_utxo(7_777, 1, 2),
+    ];
+
+    let parsed = Sweep::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.fee.meta.vout(), 0);
+
+    // Leftovers do not relax the positional match of the declared fields.
+    let err = Sweep::try_utxos(&dummy, &[inputs[1].clone(), inputs[0].clone()]).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+#[test]
+fn unordered_mode_ignores_unmatched_inputs() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(42, 2, 0),
+        create_utxo(1_000, 2, 1),
+        create_utxo(7_777, 2, 2),
+    ];
+
+    let parsed = UnorderedSweep::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.fee.meta.vout(), 1);
+    assert!(parsed.deposit.is_none());
+}
+
+#[test]
+fn duplicates_are_still_rejected() {
+    let dummy = DummyAccounts::default();
+    let fee = create_utxo(1_000, 3, 0);
+
+    let err = Sweep::try_utxos(&dummy, &[fee.clone(), fee]).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::DuplicateUtxoMeta.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
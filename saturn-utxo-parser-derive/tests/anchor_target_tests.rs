@@ -0,0 +1,142 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::codec::zero_copy::Discriminator;
+use saturn_account_parser::codec::AccountLoader;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+struct PoolShard {
+    liquidity: u64,
+}
+
+impl Discriminator for PoolShard {
+    const DISCRIMINATOR: [u8; 8] = *b"poolshrd";
+}
+
+// Anchors to a zero-copy loader and to a whole vector of loaders.
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(ShardAccounts)]
+struct Rebalance {
+    #[utxo(anchor = pool)]
+    pool_utxo: UtxoInfo,
+
+    #[utxo(anchor = shards)]
+    shard_utxos: Vec<UtxoInfo>,
+}
+
+// Anchors to single elements of the vector of loaders.
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(ShardAccounts)]
+struct Withdraw {
+    #[utxo(anchor = shards[1])]
+    shard_utxo: UtxoInfo,
+
+    #[utxo(anchor = shards[0])]
+    first_shard_utxo: Option<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(ShardAccounts)]
+struct OutOfRange {
+    #[utxo(anchor = shards[SHARD_COUNT])]
+    shard_utxo: UtxoInfo,
+}
+
+const SHARD_COUNT: usize = 2;
+
+#[test]
+fn anchors_to_account_loaders() {
+    let accounts = ShardAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 1, 0),
+        create_utxo(2_000, 1, 1),
+        create_utxo(3_000, 1, 2),
+    ];
+
+    let parsed = Rebalance::try_utxos(&accounts, &inputs).unwrap();
+    assert_eq!(parsed.pool_utxo.value, 1_000);
+    assert_eq!(parsed.shard_utxos.len(), SHARD_COUNT);
+}
+
+#[test]
+fn anchors_to_vector_elements() {
+    let accounts = ShardAccounts::default();
+
+    let inputs = vec![create_utxo(1_000, 2, 0), create_utxo(2_000, 2, 1)];
+    let parsed = Withdraw::try_utxos(&accounts, &inputs).unwrap();
+    assert_eq!(parsed.shard_utxo.value, 1_000);
+    assert_eq!(parsed.first_shard_utxo.unwrap().value, 2_000);
+
+    let inputs = vec![create_utxo(1_000, 3, 0)];
+    let parsed = Withdraw::try_utxos(&accounts, &inputs).unwrap();
+    assert!(parsed.first_shard_utxo.is_none());
+}
+
+#[test]
+fn index_past_the_vector_fails() {
+    let accounts = ShardAccounts::default();
+    let inputs = vec![create_utxo(1_000, 4, 0)];
+
+    let err = OutOfRange::try_utxos(&accounts, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::AnchorIndexOutOfBounds.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Accounts holding zero-copy loaders.
+// -------------------------------------------------------------------------------------------------
+struct ShardAccounts<'info> {
+    pool: AccountLoader<'info, PoolShard>,
+    shards: Vec<AccountLoader<'info, PoolShard>>,
+}
+
+impl<'info> AccountsTrait<'info> for ShardAccounts<'info> {
+    fn try_accounts(
+        _accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Self, arch_program::program_error::ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+fn leaked_account<'info>(key_byte: u8) -> &'info AccountInfo<'info> {
+    use arch_program::pubkey::Pubkey;
+
+    let key: &'static Pubkey = Box::leak(Box::new(Pubkey::from_slice(&[key_byte; 32])));
+    let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+    let data: &'static mut [u8] = Box::leak(Box::new([0u8; 16]));
+    let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+    Box::leak(Box::new(AccountInfo::new(
+        key, lamports, data, key, utxo_meta, false, false, false,
+    )))
+}
+
+impl<'info> Default for ShardAccounts<'info> {
+    fn default() -> Self {
+        Self {
+            pool: AccountLoader::new(leaked_account(1)),
+            shards: (0..SHARD_COUNT as u8)
+                .map(|i| AccountLoader::new(leaked_account(10 + i)))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::rune::RuneAmount;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_collections::generic::fixed_set::FixedSet;
+use saturn_utxo_parser::{metas_to_infos, ErrorCode, TryFromUtxos, TryFromUtxosWith};
+use saturn_utxo_parser::{register_test_utxo_info, test_conversion_count};
+use saturn_utxo_parser_derive::UtxoParser;
+
+type PoolRuneSet = FixedSet<RuneAmount, 3>;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct Deposit {
+    #[utxo(value = 700, runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(min_value = 50_000)]
+    bonus: Option<UtxoInfo>,
+
+    #[utxo(rest)]
+    deposits: Vec<UtxoInfo>,
+}
+
+/// Fields of different `UtxoInfo` types are converted on demand.
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Mixed {
+    #[utxo(value = 700)]
+    fee: UtxoInfo,
+
+    pool: UtxoInfo<PoolRuneSet>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Ordered {
+    #[utxo(value = 700)]
+    fee: UtxoInfo,
+
+    #[utxo(rest)]
+    deposits: Vec<UtxoInfo>,
+}
+
+#[test]
+fn converts_every_input_at_most_once() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(10_000, 1, 0),
+        create_utxo(700, 1, 1),
+        create_utxo(60_000, 1, 2),
+    ];
+
+    // Every field of an unordered parser inspects every input.
+    let before = test_conversion_count();
+    let parsed = Deposit::try_utxos(&dummy, &inputs).unwrap();
+    assert_eq!(test_conversion_count() - before, inputs.len());
+    assert_eq!(parsed.fee.value, 700);
+    assert_eq!(parsed.bonus.unwrap().value, 60_000);
+    assert_eq!(parsed.deposits.len(), 1);
+}
+
+#[test]
+fn inputs_past_a_failing_field_are_not_converted() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(800, 3, 0),
+        create_utxo(5_000, 3, 1),
+        create_utxo(6_000, 3, 2),
+    ];
+
+    let before = test_conversion_count();
+    let failure = Ordered::try_utxos_with_verbose(&dummy, &inputs, &()).unwrap_err();
+    assert_eq!(test_conversion_count() - before, 1);
+    assert_eq!(failure.field, "fee");
+    assert_eq!(failure.code, ErrorCode::InvalidUtxoValue);
+    assert_eq!(
+        Ordered::try_utxos(&dummy, &inputs).unwrap_err(),
+        ProgramError::from(failure)
+    );
+}
+
+#[test]
+fn duplicates_are_rejected_before_conversion() {
+    let dummy = DummyAccounts::default();
+    let meta = create_utxo(700, 4, 0);
+    let inputs = vec![meta.clone(), meta];
+
+    let before = test_conversion_count();
+    let failure = Deposit::try_utxos_with_verbose(&dummy, &inputs, &()).unwrap_err();
+    assert_eq!(failure.code, ErrorCode::DuplicateUtxoMeta);
+    assert_eq!(failure.utxo_index, Some(1));
+    assert_eq!(test_conversion_count(), before);
+}
+
+#[test]
+fn mixed_info_types_still_parse() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(700, 5, 0), UtxoMeta::from([5; 32], 1)];
+
+    let parsed = Mixed::try_utxos(&dummy, &inputs).unwrap();
+    assert_eq!(parsed.fee.value, 700);
+    assert_eq!(parsed.pool.meta, inputs[1]);
+}
+
+#[test]
+fn metas_to_infos_reuses_the_buffer() {
+    let inputs = vec![create_utxo(1_000, 6, 0), create_utxo(2_000, 6, 1)];
+
+    let mut infos = vec![UtxoInfo::default(); 5];
+    metas_to_infos(&inputs, &mut infos).unwrap();
+    let values: Vec<u64> = infos.iter().map(|info| info.value).collect();
+    assert_eq!(values, vec![1_000, 2_000]);
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,115 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+mod checks {
+    use super::*;
+
+    /// The UTXO must be the one currently backing the `dummy` account.
+    pub fn is_account_utxo(utxo: &UtxoInfo, accounts: &DummyAccounts) -> bool {
+        utxo.meta == *accounts.dummy.utxo
+    }
+
+    pub fn is_first_output(utxo: &UtxoInfo, _accounts: &DummyAccounts) -> bool {
+        utxo.meta.vout() == 0
+    }
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Checked {
+    #[utxo(check = checks::is_account_utxo)]
+    state: UtxoInfo,
+
+    #[utxo(value = 1_000, check = checks::is_first_output)]
+    fee: UtxoInfo,
+}
+
+#[test]
+fn check_fn_accepts_matching_utxos() {
+    let dummy = DummyAccounts::default();
+    // The dummy account is backed by the all-zero outpoint.
+    let inputs = vec![create_utxo(10_000, 0, 0), create_utxo(1_000, 1, 0)];
+
+    let parsed = Checked::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.state.value, 10_000);
+    assert_eq!(parsed.fee.value, 1_000);
+}
+
+#[test]
+fn check_fn_rejection_uses_dedicated_error() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(10_000, 2, 0), create_utxo(1_000, 1, 0)];
+
+    let err = Checked::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(ErrorCode::UtxoCheckFailed.into()));
+}
+
+#[test]
+fn check_fn_runs_alongside_other_predicates() {
+    let dummy = DummyAccounts::default();
+
+    // Rejected by the check function only.
+    let inputs = vec![create_utxo(10_000, 0, 0), create_utxo(1_000, 3, 1)];
+    assert!(Checked::try_utxos(&dummy, &inputs).is_err());
+
+    // Rejected by the value predicate.
+    let inputs = vec![create_utxo(10_000, 0, 0), create_utxo(2_000, 4, 0)];
+    let err = Checked::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
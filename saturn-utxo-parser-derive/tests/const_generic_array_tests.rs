@@ -0,0 +1,134 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Batch<const N: usize> {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo(value = 546)]
+    outputs: [UtxoInfo; N],
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct UnorderedBatch<const N: usize> {
+    #[utxo(value = 546)]
+    outputs: [UtxoInfo; N],
+
+    #[utxo(rest)]
+    others: Vec<UtxoInfo>,
+}
+
+#[test]
+fn parses_const_generic_array() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 1, 0),
+        create_utxo(546, 1, 1),
+        create_utxo(546, 1, 2),
+        create_utxo(546, 1, 3),
+    ];
+
+    let parsed = Batch::<3>::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.outputs.len(), 3);
+    assert_eq!(parsed.outputs[2].meta.vout(), 3);
+
+    // The same struct with a different length.
+    let err = Batch::<2>::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::UnexpectedExtraUtxos.into())
+    );
+
+    let err = Batch::<4>::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::MissingRequiredUtxo.into())
+    );
+}
+
+#[test]
+fn checks_predicate_on_every_element() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 2, 0),
+        create_utxo(546, 2, 1),
+        create_utxo(600, 2, 2),
+    ];
+
+    let err = Batch::<2>::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+#[test]
+fn parses_const_generic_array_unordered() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(546, 3, 0),
+        create_utxo(9_000, 3, 1),
+        create_utxo(546, 3, 2),
+    ];
+
+    let parsed = UnorderedBatch::<2>::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.outputs[1].meta.vout(), 2);
+    assert_eq!(parsed.others.len(), 1);
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,132 @@
+#![cfg(feature = "runes")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::rune::{RuneAmount, RuneId};
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::{UtxoInfo, UtxoInfoTrait};
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a UTXO holding `value` satoshis and, optionally, `amount` of `rune`.
+fn create_utxo(value: u64, rune: Option<(RuneId, u128)>, txid_byte: u8) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], 0);
+    let mut info: UtxoInfo = UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    };
+    if let Some((id, amount)) = rune {
+        info.runes_mut().insert(RuneAmount { id, amount }).unwrap();
+    }
+    register_test_utxo_info(info);
+    meta
+}
+
+fn rune_a() -> RuneId {
+    RuneId::new(840_000, 1)
+}
+
+fn rune_b() -> RuneId {
+    RuneId::new(840_000, 2)
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Swap {
+    #[utxo(runes = "none")]
+    fee_utxo: UtxoInfo,
+
+    #[utxo(value = fee_utxo.value * 2)]
+    collateral: UtxoInfo,
+
+    #[utxo(runes = "some")]
+    rune_deposit: UtxoInfo,
+
+    #[utxo(rune_id = rune_deposit.runes.get().unwrap().id)]
+    rune_change: UtxoInfo,
+}
+
+#[test]
+fn accepts_inputs_matching_earlier_fields() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, None, 1),
+        create_utxo(2_000, None, 2),
+        create_utxo(546, Some((rune_a(), 100)), 3),
+        create_utxo(546, Some((rune_a(), 5)), 4),
+    ];
+
+    let parsed = Swap::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.collateral.value, 2 * parsed.fee_utxo.value);
+    assert_eq!(parsed.rune_change.runes.get().unwrap().id, rune_a());
+}
+
+#[test]
+fn rejects_value_not_derived_from_earlier_field() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, None, 5),
+        create_utxo(1_500, None, 6),
+        create_utxo(546, Some((rune_a(), 100)), 7),
+        create_utxo(546, Some((rune_a(), 5)), 8),
+    ];
+
+    let err = Swap::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+#[test]
+fn rejects_rune_different_from_earlier_field() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, None, 9),
+        create_utxo(2_000, None, 10),
+        create_utxo(546, Some((rune_a(), 100)), 11),
+        create_utxo(546, Some((rune_b(), 5)), 12),
+    ];
+
+    let err = Swap::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(ErrorCode::InvalidRuneId.into()));
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,136 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+/// Program errors, mirroring what `#[saturn_error]` generates for a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MyError {
+    BadFeeUtxo = 6000,
+    BadDeposits = 6001,
+}
+
+impl From<MyError> for ProgramError {
+    fn from(e: MyError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Deposit {
+    #[utxo(value = 1_000, error = MyError::BadFeeUtxo)]
+    fee: UtxoInfo,
+
+    #[utxo(rest, min_value = 10_000, max = 2, error = MyError::BadDeposits)]
+    deposits: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct UnorderedDeposit {
+    #[utxo(value = 1_000, error = MyError::BadFeeUtxo)]
+    fee: UtxoInfo,
+
+    #[utxo(rest)]
+    deposits: Vec<UtxoInfo>,
+}
+
+#[test]
+fn mismatch_returns_program_error() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(2_000, 1, 0)];
+
+    let err = Deposit::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(err, MyError::BadFeeUtxo.into());
+}
+
+#[test]
+fn missing_input_returns_program_error() {
+    let dummy = DummyAccounts::default();
+
+    let err = Deposit::try_utxos(&dummy, &[]).unwrap_err();
+    assert_eq!(err, MyError::BadFeeUtxo.into());
+
+    let err = UnorderedDeposit::try_utxos(&dummy, &[create_utxo(20_000, 2, 0)]).unwrap_err();
+    assert_eq!(err, MyError::BadFeeUtxo.into());
+}
+
+#[test]
+fn count_checks_return_program_error() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 3, 0),
+        create_utxo(10_000, 3, 1),
+        create_utxo(20_000, 3, 2),
+        create_utxo(30_000, 3, 3),
+    ];
+
+    let err = Deposit::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(err, MyError::BadDeposits.into());
+}
+
+#[test]
+fn errors_outside_overridden_fields_stay_generic() {
+    let dummy = DummyAccounts::default();
+    // The 5 sat input is not captured by `deposits` and is left over.
+    let inputs = vec![create_utxo(1_000, 4, 0), create_utxo(5, 4, 1)];
+
+    let err = Deposit::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::UnexpectedExtraUtxos.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
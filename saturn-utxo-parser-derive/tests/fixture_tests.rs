@@ -0,0 +1,152 @@
+#![cfg(feature = "test-utils")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::rune::RuneId;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::TryFromUtxos;
+use saturn_utxo_parser_derive::UtxoParser;
+
+fn pool_rune() -> RuneId {
+    RuneId::new(840_000, 1)
+}
+
+const DEPOSIT_COUNT: usize = 2;
+
+fn metas(infos: &[UtxoInfo]) -> Vec<UtxoMeta> {
+    infos.iter().map(|info| info.meta.clone()).collect()
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct AddLiquidity {
+    #[utxo(value = 700, runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(rune_id = pool_rune(), rune_amount = 5_000)]
+    rune_deposit: UtxoInfo,
+
+    #[utxo(min_value = 20_000, runes = "none")]
+    btc_deposits: [UtxoInfo; DEPOSIT_COUNT],
+
+    #[utxo(rest, min_count = 1, total_value = 3_000)]
+    change: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered", one_of = "payment")]
+struct Pay {
+    #[utxo(index = 1, value = 546)]
+    marker: UtxoInfo,
+
+    #[utxo(group = "payment", runes = "none", min_value = 50_000)]
+    btc: Option<UtxoInfo>,
+
+    #[utxo(group = "payment", runes = "some")]
+    runes: Option<UtxoInfo>,
+
+    #[utxo(value_in = [1_000, 2_000])]
+    tip: Option<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Collateral {
+    #[utxo(runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(value = fee.value * 2)]
+    collateral: UtxoInfo,
+}
+
+#[test]
+fn fixture_satisfies_strict_parser() {
+    let fixture = AddLiquidity::fixture();
+    assert_eq!(fixture.len(), 5);
+
+    let dummy = DummyAccounts::default();
+    let parsed = AddLiquidity::try_utxos(&dummy, &metas(&fixture)).unwrap();
+    assert_eq!(parsed.fee.value, 700);
+    assert_eq!(parsed.rune_deposit.rune_amount(&pool_rune()), Some(5_000));
+    assert!(parsed.btc_deposits.iter().all(|u| u.value == 20_000));
+    assert_eq!(parsed.change.len(), 1);
+    assert_eq!(parsed.change[0].value, 3_000);
+}
+
+#[test]
+fn fixture_places_pinned_fields_and_one_alternative() {
+    let fixture = Pay::fixture();
+    assert_eq!(fixture.len(), 3);
+    assert_eq!(fixture[1].value, 546);
+
+    let dummy = DummyAccounts::default();
+    let parsed = Pay::try_utxos(&dummy, &metas(&fixture)).unwrap();
+    assert!(parsed.btc.is_some());
+    assert!(parsed.runes.is_none());
+    assert_eq!(parsed.tip.unwrap().value, 1_000);
+}
+
+#[test]
+fn fixture_with_fills_dependent_constraints() {
+    let dummy = DummyAccounts::default();
+
+    // `collateral` depends on `fee`, which the derive cannot evaluate.
+    let fixture = Collateral::fixture();
+    assert!(Collateral::try_utxos(&dummy, &metas(&fixture)).is_err());
+
+    let fixture = Collateral::fixture_with(|field, utxo| match field {
+        "fee" => utxo.value = 1_500,
+        "collateral" => utxo.value = 3_000,
+        _ => {}
+    });
+    let parsed = Collateral::try_utxos(&dummy, &metas(&fixture)).unwrap();
+    assert_eq!(parsed.collateral.value, 3_000);
+}
+
+#[test]
+fn fixtures_never_share_metas() {
+    let first = AddLiquidity::fixture();
+    let second = AddLiquidity::fixture();
+    assert!(first
+        .iter()
+        .all(|a| second.iter().all(|b| a.meta != b.meta)));
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
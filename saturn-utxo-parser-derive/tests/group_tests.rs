@@ -0,0 +1,182 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a UTXO with the given value so predicates can inspect it off-chain.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+// -----------------------------------------------------------------------------
+// Optional all-or-none group between two mandatory fields
+// -----------------------------------------------------------------------------
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct RefundParser {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo_group(
+        #[utxo(value = 2_000)]
+        btc: UtxoInfo,
+        #[utxo(value = 3_000)]
+        change: UtxoInfo,
+    )]
+    refund: Option<Refund>,
+
+    #[utxo(rest)]
+    others: Vec<UtxoInfo>,
+}
+
+#[test]
+fn group_present() {
+    let inputs = vec![
+        create_utxo(1_000, 1, 0),
+        create_utxo(2_000, 2, 0),
+        create_utxo(3_000, 3, 0),
+    ];
+
+    let dummy = DummyAccounts::default();
+    let parsed = RefundParser::try_utxos(&dummy, &inputs).expect("group should parse");
+
+    let refund = parsed.refund.expect("group must be populated");
+    assert_eq!(refund.btc.value, 2_000);
+    assert_eq!(refund.change.value, 3_000);
+    assert!(parsed.others.is_empty());
+}
+
+#[test]
+fn group_absent() {
+    let inputs = vec![create_utxo(1_000, 4, 0), create_utxo(9_999, 5, 0)];
+
+    let dummy = DummyAccounts::default();
+    let parsed = RefundParser::try_utxos(&dummy, &inputs).expect("absent group should parse");
+
+    assert!(parsed.refund.is_none());
+    assert_eq!(parsed.others.len(), 1);
+}
+
+#[test]
+fn group_partially_present_is_rejected() {
+    // First member matches, second does not.
+    let inputs = vec![
+        create_utxo(1_000, 6, 0),
+        create_utxo(2_000, 7, 0),
+        create_utxo(4_000, 8, 0),
+    ];
+
+    let dummy = DummyAccounts::default();
+    let err = RefundParser::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::IncompleteUtxoGroup.into())
+    );
+}
+
+#[test]
+fn group_truncated_input_is_rejected() {
+    // The slice ends after the first group member.
+    let inputs = vec![create_utxo(1_000, 9, 0), create_utxo(2_000, 10, 0)];
+
+    let dummy = DummyAccounts::default();
+    let err = RefundParser::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::IncompleteUtxoGroup.into())
+    );
+}
+
+#[test]
+fn group_members_are_verified() {
+    let inputs = vec![
+        create_utxo(1_000, 11, 0),
+        create_utxo(2_000, 12, 0),
+        create_utxo(3_000, 13, 0),
+    ];
+
+    let dummy = DummyAccounts::default();
+    let mut parsed = RefundParser::try_utxos(&dummy, &inputs).unwrap();
+    assert!(parsed.verify(&dummy).is_ok());
+
+    parsed.refund.as_mut().unwrap().change.value = 4_000;
+    let failure = parsed.verify_verbose(&dummy).unwrap_err();
+    assert_eq!(failure.field, "change");
+    assert_eq!(failure.code, ErrorCode::InvalidUtxoValue);
+}
+
+// -----------------------------------------------------------------------------
+// Later fields refer to the members of a group
+// -----------------------------------------------------------------------------
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct RefundChange {
+    #[utxo_group(
+        #[utxo(min_value = 1_000)]
+        refund: UtxoInfo,
+    )]
+    leg: Option<Leg>,
+
+    #[utxo(value = refund.map_or(546, |utxo| utxo.value))]
+    change: UtxoInfo,
+}
+
+#[test]
+fn later_fields_see_group_members() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = vec![create_utxo(5_000, 14, 0), create_utxo(5_000, 14, 1)];
+    let parsed = RefundChange::try_utxos(&dummy, &inputs).unwrap();
+    assert_eq!(parsed.leg.unwrap().refund.value, 5_000);
+
+    let inputs = vec![create_utxo(546, 15, 0)];
+    let parsed = RefundChange::try_utxos(&dummy, &inputs).unwrap();
+    assert!(parsed.leg.is_none());
+    assert_eq!(parsed.change.value, 546);
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
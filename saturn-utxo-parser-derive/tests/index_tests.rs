@@ -0,0 +1,143 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Settle {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    // Position 2 always carries the pool state UTXO.
+    #[utxo(index = 2, value = 546)]
+    state: UtxoInfo,
+
+    #[utxo(rest)]
+    others: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct UnorderedSettle {
+    #[utxo(index = 0)]
+    state: UtxoInfo,
+
+    #[utxo(value = 546)]
+    dust: UtxoInfo,
+}
+
+#[test]
+fn binds_field_to_its_position() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 1, 0),
+        create_utxo(5_000, 1, 1),
+        create_utxo(546, 1, 2),
+        create_utxo(7_000, 1, 3),
+    ];
+
+    let parsed = Settle::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.fee.meta.vout(), 0);
+    assert_eq!(parsed.state.meta.vout(), 2);
+    let others: Vec<u32> = parsed.others.iter().map(|u| u.meta.vout()).collect();
+    assert_eq!(others, vec![1, 3]);
+}
+
+#[test]
+fn position_is_checked_against_predicates() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 2, 0),
+        create_utxo(546, 2, 1),
+        create_utxo(5_000, 2, 2),
+    ];
+
+    let err = Settle::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+#[test]
+fn short_slice_returns_dedicated_error() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(1_000, 3, 0), create_utxo(546, 3, 1)];
+
+    let err = Settle::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::UtxoIndexOutOfBounds.into())
+    );
+}
+
+#[test]
+fn pinned_input_is_not_matched_by_other_fields() {
+    let dummy = DummyAccounts::default();
+
+    // The only 546 sat input sits at the pinned position.
+    let inputs = vec![create_utxo(546, 4, 0), create_utxo(2_000, 4, 1)];
+    let err = UnorderedSettle::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+
+    let inputs = vec![create_utxo(2_000, 5, 0), create_utxo(546, 5, 1)];
+    let parsed = UnorderedSettle::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.state.value, 2_000);
+    assert_eq!(parsed.dust.meta.vout(), 1);
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,131 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::{
+    LayoutExpr, RunesRequirement, UtxoCardinality, UtxoLayout, ValueConstraint,
+};
+use saturn_utxo_parser_derive::UtxoParser;
+
+const MIN_DEPOSIT: u64 = 100_000;
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(allow_extra)]
+struct AddLiquidity<const N: usize> {
+    #[utxo(value = 1_000, runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(min_value = 546, max_value = MIN_DEPOSIT)]
+    dust: [UtxoInfo; N],
+
+    #[utxo_group(
+        #[utxo(value_in = [2_000, 3_000])]
+        btc: UtxoInfo,
+        change: UtxoInfo,
+    )]
+    refund: Option<AddLiquidityRefund>,
+
+    #[utxo(rest, max = 4, total_value_min = MIN_DEPOSIT)]
+    deposits: Vec<UtxoInfo>,
+}
+
+const LAYOUT: UtxoLayout = AddLiquidity::<2>::LAYOUT;
+
+#[test]
+fn describes_struct_options() {
+    assert!(!LAYOUT.unordered);
+    assert!(LAYOUT.allow_extra);
+    assert!(LAYOUT.one_of.is_empty());
+    let names: Vec<&str> = LAYOUT.fields.iter().map(|f| f.name).collect();
+    assert_eq!(names, vec!["fee", "dust", "btc", "change", "deposits"]);
+}
+
+#[test]
+fn describes_value_and_rune_constraints() {
+    let fee = LAYOUT.field("fee").unwrap();
+    assert_eq!(fee.cardinality, UtxoCardinality::Single);
+    assert_eq!(fee.value.exact, Some(LayoutExpr::Literal(1_000)));
+    assert_eq!(fee.runes.presence, RunesRequirement::None);
+    assert_eq!(fee.total_value, ValueConstraint::ANY);
+
+    let dust = LAYOUT.field("dust").unwrap();
+    assert_eq!(dust.cardinality, UtxoCardinality::Array(2));
+    assert_eq!(dust.value.min, Some(LayoutExpr::Literal(546)));
+    assert_eq!(dust.value.max, Some(LayoutExpr::Source("MIN_DEPOSIT")));
+    assert_eq!(dust.runes.presence, RunesRequirement::Any);
+
+    let refund = LAYOUT.field("btc").unwrap();
+    assert_eq!(refund.group, Some("refund"));
+    assert_eq!(
+        refund.value.one_of,
+        &[LayoutExpr::Literal(2_000), LayoutExpr::Literal(3_000)]
+    );
+}
+
+#[test]
+fn describes_cardinality_and_totals() {
+    let deposits = LAYOUT.field("deposits").unwrap();
+    assert_eq!(
+        deposits.cardinality,
+        UtxoCardinality::Vec {
+            rest: true,
+            min: None,
+            max: Some(LayoutExpr::Literal(4)),
+        }
+    );
+    assert_eq!(
+        deposits.total_value.min,
+        Some(LayoutExpr::Source("MIN_DEPOSIT"))
+    );
+    assert!(LAYOUT.field("missing").is_none());
+}
+
+#[test]
+fn serialises_to_json() {
+    let json = LAYOUT.to_json();
+    assert!(json.starts_with(r#"{"unordered":false,"allow_extra":true,"one_of":[],"fields":["#));
+    assert!(json.contains(r#""name":"fee","cardinality":"single""#));
+    assert!(json.contains(r#""cardinality":{"array":2}"#));
+    assert!(json.contains(r#""max":{"source":"MIN_DEPOSIT"}"#));
+    assert!(json.contains(r#""one_of":[2000,3000]"#));
+    assert!(json.contains(r#""cardinality":{"vec":{"rest":true,"min":null,"max":4}}"#));
+    assert!(json.ends_with("}]}"));
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "utxo-confirmations")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::{register_test_utxo_info, set_test_block_height};
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a UTXO confirmed at `block_height` (or unconfirmed when `None`).
+fn create_utxo(value: u64, block_height: Option<u64>, txid_byte: u8) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], 0);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        block_height: block_height.into(),
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct MatureDeposit {
+    #[utxo(min_confirmations = 6)]
+    deposit: UtxoInfo,
+}
+
+#[test]
+fn accepts_mature_utxo() {
+    set_test_block_height(105);
+    // Confirmed at 100 -> 6 confirmations at tip 105.
+    let inputs = vec![create_utxo(1_000, Some(100), 1)];
+
+    let dummy = DummyAccounts::default();
+    let parsed = MatureDeposit::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.deposit.confirmations_at(105), 6);
+}
+
+#[test]
+fn rejects_immature_and_unconfirmed_utxos() {
+    set_test_block_height(105);
+    let dummy = DummyAccounts::default();
+
+    let immature = vec![create_utxo(1_000, Some(101), 2)];
+    let err = MatureDeposit::try_utxos(&dummy, &immature).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InsufficientConfirmations.into())
+    );
+
+    let unconfirmed = vec![create_utxo(1_000, None, 3)];
+    let err = MatureDeposit::try_utxos(&dummy, &unconfirmed).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InsufficientConfirmations.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "runes")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::rune::{RuneAmount, RuneId};
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::{UtxoInfo, UtxoInfoTrait};
+use saturn_collections::generic::fixed_set::FixedSet;
+use saturn_utxo_parser::register_test_utxo_info_generic;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Rune set able to hold the runes of a three-asset pool.
+type PoolRuneSet = FixedSet<RuneAmount, 3>;
+
+fn rune_a() -> RuneId {
+    RuneId::new(100, 1)
+}
+
+fn rune_b() -> RuneId {
+    RuneId::new(200, 2)
+}
+
+fn rune_c() -> RuneId {
+    RuneId::new(300, 3)
+}
+
+// Helper to register a UTXO carrying the given runes (amount 1_000 each).
+fn create_utxo_with_runes(txid_byte: u8, runes: &[RuneId]) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], 0);
+    let mut info = UtxoInfo::<PoolRuneSet> {
+        meta: meta.clone(),
+        value: 546,
+        ..Default::default()
+    };
+    for id in runes {
+        info.runes_mut()
+            .insert(RuneAmount {
+                id: *id,
+                amount: 1_000,
+            })
+            .unwrap();
+    }
+    register_test_utxo_info_generic(info);
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct PoolUtxos {
+    #[utxo(rune_count = 2, contains_rune_id = rune_a(), contains_rune_id = rune_b())]
+    pool: UtxoInfo<PoolRuneSet>,
+
+    #[utxo(contains_rune_id = rune_c())]
+    extra: Option<UtxoInfo<PoolRuneSet>>,
+}
+
+#[test]
+fn parses_multi_rune_utxos() {
+    let pool = create_utxo_with_runes(1, &[rune_a(), rune_b()]);
+    let extra = create_utxo_with_runes(2, &[rune_a(), rune_b(), rune_c()]);
+
+    let dummy = DummyAccounts::default();
+    let parsed = PoolUtxos::try_utxos(&dummy, &[pool, extra]).expect("should parse");
+
+    assert_eq!(parsed.pool.rune_entry_count(), 2);
+    assert_eq!(parsed.pool.rune_amount(&rune_b()), Some(1_000));
+    assert_eq!(parsed.extra.unwrap().rune_entry_count(), 3);
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct CountOnly {
+    #[utxo(rune_count = 2)]
+    pool: UtxoInfo<PoolRuneSet>,
+}
+
+#[test]
+fn rune_count_only_error() {
+    let pool = create_utxo_with_runes(4, &[rune_a()]);
+
+    let dummy = DummyAccounts::default();
+    let err = CountOnly::try_utxos(&dummy, &[pool]).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidRuneCount.into())
+    );
+}
+
+#[test]
+fn missing_rune_id_error() {
+    let pool = create_utxo_with_runes(5, &[rune_a(), rune_c()]);
+
+    let dummy = DummyAccounts::default();
+    let err = PoolUtxos::try_utxos(&dummy, &[pool]).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(ErrorCode::InvalidRuneId.into()));
+}
+
+// ---------------------------------- Dummy Accounts ----------------------------------
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(key, lamports, data, key, utxo_meta, false, false, false);
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,162 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(one_of = "payment")]
+struct Purchase {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo(group = "payment", value = 10_000)]
+    standard: Option<UtxoInfo>,
+
+    #[utxo(group = "payment", min_value = 50_000)]
+    premium: Option<UtxoInfo>,
+
+    #[utxo(rest)]
+    others: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(one_of = "payment")]
+struct AmbiguousPurchase {
+    #[utxo(group = "payment", min_value = 1_000)]
+    small: Option<UtxoInfo>,
+
+    #[utxo(group = "payment", max_value = 5_000)]
+    large: Option<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered", one_of = "payment")]
+struct UnorderedPurchase {
+    #[utxo(group = "payment", value = 10_000)]
+    standard: Option<UtxoInfo>,
+
+    #[utxo(group = "payment", min_value = 50_000)]
+    premium: Option<UtxoInfo>,
+
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+}
+
+#[test]
+fn exactly_one_alternative_matches() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = vec![create_utxo(1_000, 1, 0), create_utxo(10_000, 1, 1)];
+    let parsed = Purchase::try_utxos(&dummy, &inputs).expect("should parse");
+    assert!(parsed.standard.is_some());
+    assert!(parsed.premium.is_none());
+
+    let inputs = vec![create_utxo(1_000, 2, 0), create_utxo(80_000, 2, 1)];
+    let parsed = Purchase::try_utxos(&dummy, &inputs).expect("should parse");
+    assert!(parsed.standard.is_none());
+    assert_eq!(parsed.premium.unwrap().value, 80_000);
+}
+
+#[test]
+fn no_alternative_is_rejected() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = vec![create_utxo(1_000, 3, 0), create_utxo(20_000, 3, 1)];
+    let err = Purchase::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::OneOfGroupMismatch.into())
+    );
+
+    let err = Purchase::try_utxos(&dummy, &inputs[..1]).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::OneOfGroupMismatch.into())
+    );
+}
+
+#[test]
+fn ambiguous_input_is_rejected() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(3_000, 4, 0)];
+
+    let err = AmbiguousPurchase::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::OneOfGroupMismatch.into())
+    );
+}
+
+#[test]
+fn unordered_rejects_several_alternatives() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = vec![create_utxo(1_000, 5, 0), create_utxo(60_000, 5, 1)];
+    let parsed = UnorderedPurchase::try_utxos(&dummy, &inputs).expect("should parse");
+    assert!(parsed.premium.is_some());
+
+    let inputs = vec![
+        create_utxo(10_000, 6, 0),
+        create_utxo(1_000, 6, 1),
+        create_utxo(60_000, 6, 2),
+    ];
+    let err = UnorderedPurchase::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::OneOfGroupMismatch.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "runes")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::rune::{RuneAmount, RuneId};
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::{UtxoInfo, UtxoInfoTrait};
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_btc_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+/// Register a dust UTXO holding 1_000 units of a rune.
+fn create_rune_utxo(txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    let mut info: UtxoInfo = UtxoInfo {
+        meta: meta.clone(),
+        value: 546,
+        ..Default::default()
+    };
+    info.runes_mut()
+        .insert(RuneAmount {
+            id: RuneId::new(840_000, 1),
+            amount: 1_000,
+        })
+        .unwrap();
+    register_test_utxo_info(info);
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Deposit {
+    #[utxo(runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(runes = "some", min = 2, max = 3)]
+    rune_block: Option<Vec<UtxoInfo>>,
+
+    #[utxo(rest, runes = "none")]
+    change: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct UnorderedDeposit {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo(runes = "some", min = 2)]
+    rune_block: Option<Vec<UtxoInfo>>,
+
+    #[utxo(rest)]
+    change: Vec<UtxoInfo>,
+}
+
+#[test]
+fn block_is_optional() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_btc_utxo(1_000, 1, 0), create_btc_utxo(2_000, 1, 1)];
+
+    let parsed = Deposit::try_utxos(&dummy, &inputs).expect("should parse");
+    assert!(parsed.rune_block.is_none());
+    assert_eq!(parsed.change.len(), 1);
+}
+
+#[test]
+fn block_takes_consecutive_matches_up_to_max() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_btc_utxo(1_000, 2, 0),
+        create_rune_utxo(2, 1),
+        create_rune_utxo(2, 2),
+        create_btc_utxo(2_000, 2, 3),
+    ];
+
+    let parsed = Deposit::try_utxos(&dummy, &inputs).expect("should parse");
+    let block = parsed.rune_block.expect("block supplied");
+    assert_eq!(block.len(), 2);
+    assert_eq!(parsed.change.len(), 1);
+
+    // A fourth rune input exceeds `max` and is left over.
+    let inputs = vec![
+        create_btc_utxo(1_000, 3, 0),
+        create_rune_utxo(3, 1),
+        create_rune_utxo(3, 2),
+        create_rune_utxo(3, 3),
+        create_rune_utxo(3, 4),
+    ];
+    let err = Deposit::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::UnexpectedExtraUtxos.into())
+    );
+}
+
+#[test]
+fn partial_block_is_rejected() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_btc_utxo(1_000, 4, 0),
+        create_rune_utxo(4, 1),
+        create_btc_utxo(2_000, 4, 2),
+    ];
+
+    let err = Deposit::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::IncompleteUtxoGroup.into())
+    );
+}
+
+#[test]
+fn unordered_block_collects_scattered_matches() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_rune_utxo(5, 0),
+        create_btc_utxo(1_000, 5, 1),
+        create_btc_utxo(2_000, 5, 2),
+        create_rune_utxo(5, 3),
+    ];
+
+    let parsed = UnorderedDeposit::try_utxos(&dummy, &inputs).expect("should parse");
+    let vouts: Vec<u32> = parsed
+        .rune_block
+        .expect("block supplied")
+        .iter()
+        .map(|u| u.meta.vout())
+        .collect();
+    assert_eq!(vouts, vec![0, 3]);
+    assert_eq!(parsed.change.len(), 1);
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "utxo-script")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::pubkey::Pubkey;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::{FixedScriptPubkey, UtxoInfo};
+use saturn_utxo_parser::{register_test_account_script, register_test_utxo_info};
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+const PROGRAM_ID: Pubkey = Pubkey([7; 32]);
+
+/// Taproot script standing in for the one the runtime derives for `pubkey`.
+fn script_of(pubkey: &Pubkey) -> [u8; 34] {
+    let mut script = [0u8; 34];
+    script[0] = 0x51;
+    script[1] = 0x20;
+    script[2..].copy_from_slice(&pubkey.0);
+    script
+}
+
+/// Register a UTXO paying to `script`.
+fn create_utxo(script: &[u8], txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value: 1_000,
+        script_pubkey: FixedScriptPubkey::new(script).unwrap(),
+        ..Default::default()
+    });
+    meta
+}
+
+fn register_scripts() {
+    register_test_account_script(PROGRAM_ID, script_of(&PROGRAM_ID));
+    register_test_account_script(Pubkey::default(), script_of(&Pubkey::default()));
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct Rebalance {
+    #[utxo(owned_by = PROGRAM_ID)]
+    shard: UtxoInfo,
+
+    #[utxo(rest)]
+    deposits: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Refund {
+    #[utxo(owned_by = accounts.dummy.key)]
+    vault: UtxoInfo,
+}
+
+#[test]
+fn program_owned_utxo_is_told_apart_from_deposits() {
+    register_scripts();
+    let user = Pubkey([9; 32]);
+    let inputs = vec![
+        create_utxo(&script_of(&user), 1, 0),
+        create_utxo(&script_of(&PROGRAM_ID), 1, 1),
+        create_utxo(&script_of(&user), 1, 2),
+    ];
+
+    let dummy = DummyAccounts::default();
+    let parsed = Rebalance::try_utxos(&dummy, &inputs).unwrap();
+    assert_eq!(parsed.shard.meta, inputs[1]);
+    assert_eq!(parsed.deposits.len(), 2);
+}
+
+#[test]
+fn owner_from_accounts_field() {
+    register_scripts();
+    let inputs = vec![create_utxo(&script_of(&Pubkey::default()), 2, 0)];
+
+    let dummy = DummyAccounts::default();
+    assert!(Refund::try_utxos(&dummy, &inputs).is_ok());
+}
+
+#[test]
+fn foreign_utxo_is_rejected() {
+    register_scripts();
+    let inputs = vec![create_utxo(&script_of(&PROGRAM_ID), 3, 0)];
+
+    let dummy = DummyAccounts::default();
+    let err = Refund::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidScriptPubkey.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
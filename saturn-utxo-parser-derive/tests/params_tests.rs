@@ -0,0 +1,160 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos, TryFromUtxosWith};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+/// Values decoded from the instruction data.
+struct DepositParams {
+    expected_fee: u64,
+    min_deposit: u64,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_params(DepositParams)]
+struct Deposit {
+    #[utxo(value = params.expected_fee, runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(rest, min_value = params.min_deposit, total_value_max = params.min_deposit * 3)]
+    deposits: Vec<UtxoInfo>,
+}
+
+#[derive(Default)]
+struct Limits {
+    max_fee: Option<u64>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_params(Limits)]
+#[utxo_parser(mode = "unordered")]
+struct Capped {
+    #[utxo(max_value = params.max_fee.unwrap_or(u64::MAX))]
+    fee: UtxoInfo,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Plain {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+}
+
+#[test]
+fn constraints_read_params() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(700, 1, 0),
+        create_utxo(10_000, 1, 1),
+        create_utxo(15_000, 1, 2),
+    ];
+    let params = DepositParams {
+        expected_fee: 700,
+        min_deposit: 10_000,
+    };
+
+    let parsed = Deposit::try_utxos_with(&dummy, &inputs, &params).unwrap();
+    assert_eq!(parsed.fee.value, 700);
+    assert_eq!(parsed.deposits.len(), 2);
+
+    let params = DepositParams {
+        expected_fee: 800,
+        min_deposit: 10_000,
+    };
+    let err = Deposit::try_utxos_with(&dummy, &inputs, &params).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+
+    let params = DepositParams {
+        expected_fee: 700,
+        min_deposit: 5_000,
+    };
+    let failure = Deposit::try_utxos_with_verbose(&dummy, &inputs, &params).unwrap_err();
+    assert_eq!(failure.field, "deposits");
+    assert_eq!(failure.code, ErrorCode::InvalidTotalValue);
+}
+
+#[test]
+fn default_params_are_passed_explicitly() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(5_000, 2, 0)];
+
+    // Parsers with params do not implement `TryFromUtxos::try_utxos`.
+    assert!(Capped::try_utxos_with(&dummy, &inputs, &Limits::default()).is_ok());
+
+    let err = Capped::try_utxos_with(
+        &dummy,
+        &inputs,
+        &Limits {
+            max_fee: Some(1_000),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+#[test]
+fn parsers_without_params_take_unit() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(1_000, 3, 0)];
+
+    assert!(Plain::try_utxos_with(&dummy, &inputs, &()).is_ok());
+    assert!(Plain::try_utxos(&dummy, &inputs).is_ok());
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,203 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, ParseFailure, TryFromUtxos, TryFromUtxosWith};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MyError {
+    BadChange = 6000,
+}
+
+impl From<MyError> for ProgramError {
+    fn from(e: MyError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Payment {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo(min_value = 10_000)]
+    payment: [UtxoInfo; 2],
+
+    #[utxo(max_value = 546, error = MyError::BadChange)]
+    change: UtxoInfo,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct UnorderedPayment {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo_group(
+        #[utxo(value = 2_000)]
+        btc: UtxoInfo,
+        #[utxo(value = 3_000)]
+        change: UtxoInfo,
+    )]
+    refund: Option<Refund>,
+}
+
+#[test]
+fn reports_field_and_input_of_mismatch() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 1, 0),
+        create_utxo(10_000, 1, 1),
+        create_utxo(5, 1, 2),
+        create_utxo(100, 1, 3),
+    ];
+
+    let failure = Payment::try_utxos_verbose(&dummy, &inputs).unwrap_err();
+    assert_eq!(failure.field, "payment");
+    assert_eq!(failure.utxo_index, Some(2));
+    assert_eq!(failure.code, ErrorCode::InvalidUtxoValue);
+
+    // `try_utxos` still returns the bare error.
+    let err = Payment::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+#[test]
+fn missing_input_has_no_index() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(1_000, 2, 0), create_utxo(10_000, 2, 1)];
+
+    let failure = Payment::try_utxos_verbose(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        failure,
+        ParseFailure::new("payment", None, ErrorCode::MissingRequiredUtxo)
+    );
+}
+
+#[test]
+fn keeps_overridden_error() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 3, 0),
+        create_utxo(10_000, 3, 1),
+        create_utxo(20_000, 3, 2),
+        create_utxo(1_000, 3, 3),
+    ];
+
+    let failure = Payment::try_utxos_verbose(&dummy, &inputs).unwrap_err();
+    assert_eq!(failure.field, "change");
+    assert_eq!(failure.utxo_index, Some(3));
+    assert_eq!(failure.code, ErrorCode::InvalidUtxoValue);
+    assert_eq!(failure.error, MyError::BadChange.into());
+
+    let err = Payment::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(err, MyError::BadChange.into());
+}
+
+#[test]
+fn reports_leftover_and_duplicate_inputs() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 4, 0),
+        create_utxo(10_000, 4, 1),
+        create_utxo(20_000, 4, 2),
+        create_utxo(500, 4, 3),
+        create_utxo(7, 4, 4),
+    ];
+
+    let failure = Payment::try_utxos_verbose(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        failure,
+        ParseFailure::new("", Some(4), ErrorCode::UnexpectedExtraUtxos)
+    );
+
+    let duplicated = vec![inputs[0].clone(), inputs[1].clone(), inputs[0].clone()];
+    let failure = Payment::try_utxos_verbose(&dummy, &duplicated).unwrap_err();
+    assert_eq!(
+        failure,
+        ParseFailure::new("", Some(2), ErrorCode::DuplicateUtxoMeta)
+    );
+}
+
+#[test]
+fn unordered_reports_groups_and_leftovers() {
+    let dummy = DummyAccounts::default();
+
+    let incomplete = vec![create_utxo(2_000, 5, 0), create_utxo(1_000, 5, 1)];
+    let failure = UnorderedPayment::try_utxos_verbose(&dummy, &incomplete).unwrap_err();
+    assert_eq!(
+        failure,
+        ParseFailure::new("refund", None, ErrorCode::IncompleteUtxoGroup)
+    );
+
+    let extra = vec![create_utxo(9, 6, 0), create_utxo(1_000, 6, 1)];
+    let failure = UnorderedPayment::try_utxos_verbose(&dummy, &extra).unwrap_err();
+    assert_eq!(
+        failure,
+        ParseFailure::new("", Some(0), ErrorCode::UnexpectedExtraUtxos)
+    );
+}
+
+#[test]
+fn displays_field_and_input() {
+    let failure = ParseFailure::new("fee", Some(3), ErrorCode::InvalidUtxoValue);
+    assert_eq!(
+        failure.to_string(),
+        "`fee`: UTXO value (satoshis) did not match the expected value (input 3)"
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,169 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Swap {
+    #[utxo(value = 700, runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(min_value = 10_000)]
+    input: UtxoInfo,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct Fee {
+    #[utxo(value = 700)]
+    fee: UtxoInfo,
+}
+
+/// Consumes the dust inputs left over by the first parser.
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Dust {
+    #[utxo(rest, max_value = 1_000)]
+    dust: Vec<UtxoInfo>,
+}
+
+#[test]
+fn leftovers_are_returned_instead_of_rejected() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(700, 1, 0),
+        create_utxo(20_000, 1, 1),
+        create_utxo(546, 1, 2),
+        create_utxo(600, 1, 3),
+    ];
+
+    let err = Swap::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::UnexpectedExtraUtxos.into())
+    );
+
+    let mut scratch = Vec::new();
+    let (swap, rest) = Swap::try_utxos_partial(&dummy, &inputs, &(), &mut scratch).unwrap();
+    assert_eq!(swap.fee.value, 700);
+    assert_eq!(swap.input.value, 20_000);
+    let values: Vec<u64> = rest.iter().map(|info| info.value).collect();
+    assert_eq!(values, vec![546, 600]);
+
+    // The leftovers can be handed to a second parser.
+    let metas: Vec<UtxoMeta> = rest.iter().map(|info| info.meta.clone()).collect();
+    let dust = Dust::try_utxos(&dummy, &metas).unwrap();
+    assert_eq!(dust.dust.len(), 2);
+}
+
+#[test]
+fn rest_leftovers_are_the_inputs_it_skipped() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(500, 5, 0),
+        create_utxo(5_000, 5, 1),
+        create_utxo(600, 5, 2),
+    ];
+
+    let mut scratch = Vec::new();
+    let (dust, rest) = Dust::try_utxos_partial(&dummy, &inputs, &(), &mut scratch).unwrap();
+    let captured: Vec<u64> = dust.dust.iter().map(|info| info.value).collect();
+    assert_eq!(captured, vec![500, 600]);
+    let metas: Vec<&UtxoMeta> = rest.iter().map(|info| &info.meta).collect();
+    assert_eq!(metas, vec![&inputs[1]]);
+
+    let failure = Dust::try_utxos_with_verbose(&dummy, &inputs, &()).unwrap_err();
+    assert_eq!(failure.code, ErrorCode::UnexpectedExtraUtxos);
+    assert_eq!(failure.utxo_index, Some(1));
+}
+
+#[test]
+fn unordered_leftovers_keep_input_order() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(5_000, 2, 0),
+        create_utxo(700, 2, 1),
+        create_utxo(3_000, 2, 2),
+    ];
+
+    let mut scratch = Vec::new();
+    let (fee, rest) = Fee::try_utxos_partial(&dummy, &inputs, &(), &mut scratch).unwrap();
+    assert_eq!(fee.fee.meta, inputs[1]);
+    let metas: Vec<&UtxoMeta> = rest.iter().map(|info| &info.meta).collect();
+    assert_eq!(metas, vec![&inputs[0], &inputs[2]]);
+}
+
+#[test]
+fn no_leftovers_yields_an_empty_slice() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(700, 3, 0), create_utxo(10_000, 3, 1)];
+
+    let mut scratch = Vec::new();
+    let (_, rest) = Swap::try_utxos_partial(&dummy, &inputs, &(), &mut scratch).unwrap();
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn field_failures_are_still_reported() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(800, 4, 0), create_utxo(10_000, 4, 1)];
+
+    let mut scratch = Vec::new();
+    let failure = Swap::try_utxos_partial(&dummy, &inputs, &(), &mut scratch).unwrap_err();
+    assert_eq!(failure.field, "fee");
+    assert_eq!(failure.code, ErrorCode::InvalidUtxoValue);
+    assert_eq!(failure.utxo_index, Some(0));
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
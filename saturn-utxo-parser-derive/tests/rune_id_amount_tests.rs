@@ -93,6 +93,84 @@ fn rune_amount_mismatch_error() {
     );
 }
 
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct BoundedRune {
+    #[utxo(rune_id = target_rune_id(), rune_amount_at_least = 100, rune_amount_at_most = 1_000)]
+    deposit: UtxoInfo,
+}
+
+#[test]
+fn bounded_rune_amount_success() {
+    let dummy = DummyAccounts::default();
+    for (amount, txid_byte) in [(100, 10), (1_000, 11)] {
+        let inputs = vec![create_utxo_with_rune(
+            1_000,
+            txid_byte,
+            0,
+            target_rune_id(),
+            amount,
+        )];
+        let parsed = BoundedRune::try_utxos(&dummy, &inputs).expect("should parse");
+        assert_eq!(parsed.deposit.rune_amount(&target_rune_id()), Some(amount));
+    }
+}
+
+#[test]
+fn bounded_rune_amount_out_of_range_error() {
+    let dummy = DummyAccounts::default();
+    for (amount, txid_byte) in [(99, 12), (1_001, 13)] {
+        let inputs = vec![create_utxo_with_rune(
+            1_000,
+            txid_byte,
+            0,
+            target_rune_id(),
+            amount,
+        )];
+        let err = BoundedRune::try_utxos(&dummy, &inputs).unwrap_err();
+        assert_eq!(
+            err,
+            ProgramError::Custom(ErrorCode::InvalidRuneAmount.into())
+        );
+    }
+}
+
+#[test]
+fn bounded_rune_amount_wrong_id_error() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo_with_rune(
+        1_000,
+        14,
+        0,
+        RuneId::new(999, 0),
+        500,
+    )];
+    let err = BoundedRune::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(ErrorCode::InvalidRuneId.into()));
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct AtLeastTotal {
+    #[utxo(rune_amount_at_least = 50)]
+    deposit: UtxoInfo,
+}
+
+#[test]
+fn total_rune_amount_lower_bound() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = vec![create_utxo_with_rune(1_000, 15, 0, RuneId::new(1, 1), 50)];
+    assert!(AtLeastTotal::try_utxos(&dummy, &inputs).is_ok());
+
+    let inputs = vec![create_utxo(1_000, 16, 0)];
+    let err = AtLeastTotal::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidRuneAmount.into())
+    );
+}
+
 // ---------------------------------- Dummy Accounts ----------------------------------
 #[derive(Debug)]
 struct DummyAccounts<'info> {
@@ -0,0 +1,131 @@
+#![cfg(feature = "runes")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::rune::{RuneAmount, RuneId};
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::{UtxoInfo, UtxoInfoTrait};
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+fn token_0() -> RuneId {
+    RuneId::new(840_000, 10)
+}
+
+fn token_1() -> RuneId {
+    RuneId::new(840_000, 11)
+}
+
+/// Register a UTXO holding 1_000 units of `rune`.
+fn create_utxo_with_rune(txid_byte: u8, rune: RuneId) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], 0);
+    let mut info: UtxoInfo = UtxoInfo {
+        meta: meta.clone(),
+        value: 546,
+        ..Default::default()
+    };
+    info.runes_mut()
+        .insert(RuneAmount {
+            id: rune,
+            amount: 1_000,
+        })
+        .unwrap();
+    register_test_utxo_info(info);
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct PoolDeposit {
+    #[utxo(rune_id_in = [token_0(), token_1()])]
+    deposit: UtxoInfo,
+
+    #[utxo(rune_id_in = [token_0(), token_1()])]
+    second: Option<UtxoInfo>,
+}
+
+#[test]
+fn accepts_any_listed_rune_and_reports_match() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo_with_rune(1, token_1()),
+        create_utxo_with_rune(2, token_0()),
+    ];
+
+    let parsed = PoolDeposit::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.deposit_rune_id(&dummy), Some(token_1()));
+    assert_eq!(parsed.second_rune_id(&dummy), Some(token_0()));
+
+    let inputs = vec![create_utxo_with_rune(3, token_0())];
+    let parsed = PoolDeposit::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.deposit_rune_id(&dummy), Some(token_0()));
+    assert_eq!(parsed.second_rune_id(&dummy), None);
+}
+
+/// Rune id stored by the pool the accounts point to.
+fn pool_token(_accounts: &DummyAccounts) -> RuneId {
+    token_1()
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct AccountsDeposit {
+    #[utxo(rune_id_in = [token_0(), pool_token(accounts)])]
+    deposit: UtxoInfo,
+}
+
+#[test]
+fn accessor_resolves_ids_from_the_accounts() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo_with_rune(5, token_1())];
+
+    let parsed = AccountsDeposit::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.deposit_rune_id(&dummy), Some(token_1()));
+}
+
+#[test]
+fn rejects_unlisted_rune() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo_with_rune(4, RuneId::new(1, 1))];
+
+    let err = PoolDeposit::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(err, ProgramError::Custom(ErrorCode::InvalidRuneId.into()));
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "utxo-script")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::{FixedScriptPubkey, UtxoInfo};
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// P2WPKH script for `bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4`.
+const P2WPKH_SCRIPT: [u8; 22] = [
+    0x00, 0x14, 0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1, 0xb3,
+    0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6,
+];
+
+/// Register a UTXO paying to `script`.
+fn create_utxo(script: &[u8], txid_byte: u8) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], 0);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value: 1_000,
+        script_pubkey: FixedScriptPubkey::new(script).unwrap(),
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct ScriptDeposit {
+    #[utxo(script = P2WPKH_SCRIPT)]
+    deposit: UtxoInfo,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct AddressDeposit {
+    #[utxo(address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")]
+    deposit: UtxoInfo,
+}
+
+#[test]
+fn script_matches() {
+    let inputs = vec![create_utxo(&P2WPKH_SCRIPT, 1)];
+
+    let dummy = DummyAccounts::default();
+    let parsed = ScriptDeposit::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.deposit.script_pubkey_bytes(), &P2WPKH_SCRIPT);
+}
+
+#[test]
+fn address_matches() {
+    let inputs = vec![create_utxo(&P2WPKH_SCRIPT, 2)];
+
+    let dummy = DummyAccounts::default();
+    assert!(AddressDeposit::try_utxos(&dummy, &inputs).is_ok());
+}
+
+#[test]
+fn wrong_script_is_rejected() {
+    let mut other = P2WPKH_SCRIPT;
+    other[2] ^= 0xff;
+    let inputs = vec![create_utxo(&other, 3)];
+
+    let dummy = DummyAccounts::default();
+    let err = AddressDeposit::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidScriptPubkey.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,144 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+const MIN_DEPOSIT: u64 = 100_000;
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct AddLiquidity {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo(rest, total_value_min = MIN_DEPOSIT)]
+    deposits: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct SplitPayment {
+    #[utxo(total_value = 30_000)]
+    halves: [UtxoInfo; 2],
+
+    #[utxo(rest, total_value_max = 5_000)]
+    change: Vec<UtxoInfo>,
+}
+
+#[test]
+fn accepts_deposits_reaching_minimum() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 1, 0),
+        create_utxo(60_000, 1, 1),
+        create_utxo(40_000, 1, 2),
+    ];
+
+    let parsed = AddLiquidity::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.deposits.len(), 2);
+}
+
+#[test]
+fn rejects_deposits_below_minimum() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(1_000, 2, 0),
+        create_utxo(60_000, 2, 1),
+        create_utxo(39_999, 2, 2),
+    ];
+
+    let err = AddLiquidity::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidTotalValue.into())
+    );
+
+    // No deposit at all sums to zero.
+    let err = AddLiquidity::try_utxos(&dummy, &inputs[..1]).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidTotalValue.into())
+    );
+}
+
+#[test]
+fn checks_exact_and_maximum_totals() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = vec![
+        create_utxo(10_000, 3, 0),
+        create_utxo(20_000, 3, 1),
+        create_utxo(2_000, 3, 2),
+        create_utxo(3_000, 3, 3),
+    ];
+    assert!(SplitPayment::try_utxos(&dummy, &inputs).is_ok());
+
+    let inputs = vec![create_utxo(10_000, 4, 0), create_utxo(25_000, 4, 1)];
+    let err = SplitPayment::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidTotalValue.into())
+    );
+
+    let inputs = vec![
+        create_utxo(10_000, 5, 0),
+        create_utxo(20_000, 5, 1),
+        create_utxo(5_001, 5, 2),
+    ];
+    let err = SplitPayment::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidTotalValue.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "trace")]
+
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::trace::take_trace_log;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct Deposit {
+    #[utxo(value = 1_000, runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(min_value = 5_000, max_value = 9_000)]
+    deposit: Option<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct Strict {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+}
+
+#[test]
+fn logs_every_tested_candidate() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(10_000, 1, 0), create_utxo(1_000, 1, 1)];
+    take_trace_log();
+
+    let parsed = Deposit::try_utxos(&dummy, &inputs).unwrap();
+    assert!(parsed.deposit.is_none());
+    assert_eq!(
+        take_trace_log(),
+        [
+            "`fee`: input 0 rejected (value)",
+            "`fee`: input 1 matched",
+            "`deposit`: input 0 rejected (max_value)",
+        ]
+    );
+}
+
+#[test]
+fn logs_the_rejection_behind_an_error() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(2_000, 2, 0)];
+    take_trace_log();
+
+    let err = Strict::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+    assert_eq!(take_trace_log(), ["`fee`: input 0 rejected (value)"]);
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,153 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+#[utxo_parser(mode = "unordered")]
+struct UnorderedSwap {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo(value = 5_000)]
+    deposit: UtxoInfo,
+
+    #[utxo_group(
+        #[utxo(value = 2_000)]
+        btc: UtxoInfo,
+        #[utxo(value = 3_000)]
+        change: UtxoInfo,
+    )]
+    refund: Option<Refund>,
+
+    #[utxo(rest, value = 546, max = 2)]
+    dust: Vec<UtxoInfo>,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct StrictSwap {
+    #[utxo(value = 1_000)]
+    fee: UtxoInfo,
+
+    #[utxo(value = 5_000)]
+    deposit: UtxoInfo,
+}
+
+#[test]
+fn matches_fields_regardless_of_input_order() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(546, 1, 0),
+        create_utxo(3_000, 1, 1),
+        create_utxo(5_000, 1, 2),
+        create_utxo(2_000, 1, 3),
+        create_utxo(1_000, 1, 4),
+    ];
+
+    let parsed = UnorderedSwap::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.fee.meta.vout(), 4);
+    assert_eq!(parsed.deposit.meta.vout(), 2);
+    assert!(parsed.refund.is_some());
+    assert_eq!(parsed.dust.len(), 1);
+}
+
+#[test]
+fn strict_mode_remains_the_default() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![create_utxo(5_000, 2, 0), create_utxo(1_000, 2, 1)];
+
+    assert!(StrictSwap::try_utxos(&dummy, &inputs).is_err());
+    assert!(StrictSwap::try_utxos(&dummy, &[inputs[1].clone(), inputs[0].clone()]).is_ok());
+}
+
+#[test]
+fn reports_missing_and_leftover_inputs() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = vec![create_utxo(1_000, 3, 0)];
+    let err = UnorderedSwap::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+
+    let inputs = vec![
+        create_utxo(5_000, 4, 0),
+        create_utxo(1_000, 4, 1),
+        create_utxo(7_777, 4, 2),
+    ];
+    let err = UnorderedSwap::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::UnexpectedExtraUtxos.into())
+    );
+}
+
+#[test]
+fn rejects_incomplete_group() {
+    let dummy = DummyAccounts::default();
+    let inputs = vec![
+        create_utxo(2_000, 5, 0),
+        create_utxo(5_000, 5, 1),
+        create_utxo(1_000, 5, 2),
+    ];
+
+    let err = UnorderedSwap::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::IncompleteUtxoGroup.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
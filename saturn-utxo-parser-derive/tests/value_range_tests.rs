@@ -0,0 +1,142 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+const MAX_DEPOSIT: u64 = 100_000;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], 0);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct RangedDeposit {
+    #[utxo(min_value = 10_000, max_value = MAX_DEPOSIT)]
+    deposit: UtxoInfo,
+
+    #[utxo(min_value = 546, runes = "none")]
+    change: Option<UtxoInfo>,
+}
+
+#[test]
+fn accepts_values_within_bounds() {
+    let dummy = DummyAccounts::default();
+
+    for (value, txid_byte) in [(10_000, 1), (55_555, 2), (MAX_DEPOSIT, 3)] {
+        let inputs = vec![create_utxo(value, txid_byte)];
+        let parsed = RangedDeposit::try_utxos(&dummy, &inputs).expect("should parse");
+        assert_eq!(parsed.deposit.value, value);
+        assert!(parsed.change.is_none());
+    }
+}
+
+#[test]
+fn rejects_values_outside_bounds() {
+    let dummy = DummyAccounts::default();
+
+    for (value, txid_byte) in [(9_999, 4), (MAX_DEPOSIT + 1, 5)] {
+        let inputs = vec![create_utxo(value, txid_byte)];
+        let err = RangedDeposit::try_utxos(&dummy, &inputs).unwrap_err();
+        assert_eq!(
+            err,
+            ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+        );
+    }
+}
+
+#[test]
+fn optional_field_skipped_below_min_value() {
+    let dummy = DummyAccounts::default();
+
+    // Dust change does not match `change`, so it is left over.
+    let inputs = vec![create_utxo(20_000, 6), create_utxo(100, 7)];
+    let err = RangedDeposit::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::UnexpectedExtraUtxos.into())
+    );
+
+    let inputs = vec![create_utxo(20_000, 8), create_utxo(1_000, 9)];
+    let parsed = RangedDeposit::try_utxos(&dummy, &inputs).expect("should parse");
+    assert_eq!(parsed.change.map(|u| u.value), Some(1_000));
+}
+
+const TIER_3: u64 = 100_000;
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct TieredFee {
+    #[utxo(value_in = [10_000, 50_000, TIER_3])]
+    fee: UtxoInfo,
+}
+
+#[test]
+fn accepts_listed_denominations() {
+    let dummy = DummyAccounts::default();
+
+    for (value, txid_byte) in [(10_000, 10), (50_000, 11), (TIER_3, 12)] {
+        let inputs = vec![create_utxo(value, txid_byte)];
+        let parsed = TieredFee::try_utxos(&dummy, &inputs).expect("should parse");
+        assert_eq!(parsed.fee.value, value);
+    }
+}
+
+#[test]
+fn rejects_unlisted_denomination() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = vec![create_utxo(20_000, 13)];
+    let err = TieredFee::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
@@ -0,0 +1,100 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register `count` BTC-only UTXOs of 1_000 sats sharing `txid_byte`.
+fn create_utxos(count: u32, txid_byte: u8) -> Vec<UtxoMeta> {
+    (0..count)
+        .map(|vout| {
+            let meta = UtxoMeta::from([txid_byte; 32], vout);
+            register_test_utxo_info(UtxoInfo {
+                meta: meta.clone(),
+                value: 1_000,
+                ..Default::default()
+            });
+            meta
+        })
+        .collect()
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(DummyAccounts)]
+struct BoundedDeposits {
+    #[utxo(rest, min = 1, max = 3)]
+    deposits: Vec<UtxoInfo>,
+}
+
+#[test]
+fn accepts_counts_within_bounds() {
+    let dummy = DummyAccounts::default();
+
+    for (count, txid_byte) in [(1, 1), (3, 2)] {
+        let inputs = create_utxos(count, txid_byte);
+        let parsed = BoundedDeposits::try_utxos(&dummy, &inputs).expect("should parse");
+        assert_eq!(parsed.deposits.len(), count as usize);
+    }
+}
+
+#[test]
+fn rejects_too_few_utxos() {
+    let dummy = DummyAccounts::default();
+
+    let err = BoundedDeposits::try_utxos(&dummy, &[]).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::MissingRequiredUtxo.into())
+    );
+}
+
+#[test]
+fn rejects_too_many_utxos() {
+    let dummy = DummyAccounts::default();
+
+    let inputs = create_utxos(4, 3);
+    let err = BoundedDeposits::try_utxos(&dummy, &inputs).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::UnexpectedExtraUtxos.into())
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// Minimal dummy Accounts type used in tests.
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct DummyAccounts<'info> {
+    dummy: AccountInfo<'info>,
+}
+
+impl<'info> AccountsTrait<'info> for DummyAccounts<'info> {
+    fn try_accounts(_accounts: &'info [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        Ok(Self::default())
+    }
+}
+
+impl<'info> Default for DummyAccounts<'info> {
+    fn default() -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::default()));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let data: &'static mut [u8] = Box::leak(Box::new([0u8; 1]));
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let acc_info = AccountInfo::new(
+            key, lamports, data, key, // owner
+            utxo_meta, false, // is_signer
+            false, // is_writable
+            false, // is_executable
+        );
+
+        Self { dummy: acc_info }
+    }
+}
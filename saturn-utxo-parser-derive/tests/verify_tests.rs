@@ -0,0 +1,184 @@
+use arch_program::account::AccountInfo;
+use arch_program::program_error::ProgramError;
+use arch_program::utxo::UtxoMeta;
+use saturn_account_parser::codec::zero_copy::Discriminator;
+use saturn_account_parser::codec::AccountLoader;
+use saturn_account_parser::Accounts as AccountsTrait;
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_utxo_parser::register_test_utxo_info;
+use saturn_utxo_parser::{ErrorCode, TryFromUtxos, TryFromUtxosWith};
+use saturn_utxo_parser_derive::UtxoParser;
+
+/// Register a BTC-only UTXO holding `value` satoshis.
+fn create_utxo(value: u64, txid_byte: u8, vout: u32) -> UtxoMeta {
+    let meta = UtxoMeta::from([txid_byte; 32], vout);
+    register_test_utxo_info(UtxoInfo {
+        meta: meta.clone(),
+        value,
+        ..Default::default()
+    });
+    meta
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+struct PoolConfig {
+    fee_sats: u64,
+    min_deposit: u64,
+}
+
+impl Discriminator for PoolConfig {
+    const DISCRIMINATOR: [u8; 8] = *b"poolconf";
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(ConfigAccounts)]
+struct Deposit {
+    #[utxo(value = accounts.config.load()?.fee_sats, runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(rest, min_value = accounts.config.load()?.min_deposit, max = 2)]
+    deposits: Vec<UtxoInfo>,
+}
+
+/// Values decoded from the instruction data.
+struct CollateralParams {
+    max_collateral: u64,
+}
+
+#[derive(Debug, UtxoParser)]
+#[utxo_accounts(ConfigAccounts)]
+#[utxo_params(CollateralParams)]
+#[utxo_parser(mode = "unordered")]
+struct Collateral {
+    #[utxo(runes = "none")]
+    fee: UtxoInfo,
+
+    #[utxo(value = fee.value * 2, max_value = params.max_collateral)]
+    collateral: Option<UtxoInfo>,
+}
+
+#[test]
+fn verify_accepts_unchanged_state() {
+    let accounts = ConfigAccounts::new(700, 10_000);
+    let inputs = vec![create_utxo(700, 1, 0), create_utxo(12_000, 1, 1)];
+
+    let parsed = Deposit::try_utxos(&accounts, &inputs).unwrap();
+    assert_eq!(parsed.verify(&accounts), Ok(()));
+    assert_eq!(parsed.verify_verbose(&accounts), Ok(()));
+}
+
+#[test]
+fn verify_reads_the_current_accounts() {
+    let accounts = ConfigAccounts::new(700, 10_000);
+    let inputs = vec![create_utxo(700, 2, 0), create_utxo(12_000, 2, 1)];
+    let parsed = Deposit::try_utxos(&accounts, &inputs).unwrap();
+
+    // The handler raises the minimum deposit after parsing.
+    accounts.config.load_mut().unwrap().min_deposit = 15_000;
+
+    let err = parsed.verify(&accounts).unwrap_err();
+    assert_eq!(
+        err,
+        ProgramError::Custom(ErrorCode::InvalidUtxoValue.into())
+    );
+    let failure = parsed.verify_verbose(&accounts).unwrap_err();
+    assert_eq!(failure.field, "deposits");
+    assert_eq!(failure.code, ErrorCode::InvalidUtxoValue);
+    assert_eq!(failure.utxo_index, None);
+
+    // The loader is not borrowed any more once `verify` returns.
+    accounts.config.load_mut().unwrap().min_deposit = 10_000;
+    assert_eq!(parsed.verify(&accounts), Ok(()));
+}
+
+#[test]
+fn verify_rechecks_counts_of_edited_fields() {
+    let accounts = ConfigAccounts::new(700, 10_000);
+    let inputs = vec![create_utxo(700, 3, 0), create_utxo(12_000, 3, 1)];
+    let mut parsed = Deposit::try_utxos(&accounts, &inputs).unwrap();
+
+    let extra = parsed.deposits[0].clone();
+    parsed.deposits.push(extra.clone());
+    assert_eq!(parsed.verify(&accounts), Ok(()));
+
+    parsed.deposits.push(extra);
+    let failure = parsed.verify_verbose(&accounts).unwrap_err();
+    assert_eq!(failure.field, "deposits");
+    assert_eq!(failure.code, ErrorCode::UnexpectedExtraUtxos);
+}
+
+#[test]
+fn verify_takes_params_and_earlier_fields() {
+    let accounts = ConfigAccounts::new(700, 10_000);
+    let inputs = vec![create_utxo(1_000, 4, 0), create_utxo(2_000, 4, 1)];
+    let params = CollateralParams {
+        max_collateral: 5_000,
+    };
+
+    let mut parsed = Collateral::try_utxos_with(&accounts, &inputs, &params).unwrap();
+    assert_eq!(parsed.collateral.as_ref().unwrap().value, 2_000);
+    assert_eq!(parsed.verify(&accounts, &params), Ok(()));
+
+    let params = CollateralParams {
+        max_collateral: 1_500,
+    };
+    let failure = parsed.verify_verbose(&accounts, &params).unwrap_err();
+    assert_eq!(failure.field, "collateral");
+    assert_eq!(failure.code, ErrorCode::InvalidUtxoValue);
+
+    // `collateral` must still be twice the fee.
+    let params = CollateralParams {
+        max_collateral: 5_000,
+    };
+    parsed.fee.value = 1_500;
+    let failure = parsed.verify_verbose(&accounts, &params).unwrap_err();
+    assert_eq!(failure.field, "collateral");
+}
+
+// -------------------------------------------------------------------------------------------------
+// Accounts holding a zero-copy config.
+// -------------------------------------------------------------------------------------------------
+struct ConfigAccounts<'info> {
+    config: AccountLoader<'info, PoolConfig>,
+}
+
+impl<'info> AccountsTrait<'info> for ConfigAccounts<'info> {
+    fn try_accounts(
+        _accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Self, arch_program::program_error::ProgramError> {
+        Ok(Self::new(0, 0))
+    }
+}
+
+impl<'info> ConfigAccounts<'info> {
+    fn new(fee_sats: u64, min_deposit: u64) -> Self {
+        // Discriminator followed by the `PoolConfig` fields.
+        let words = [
+            u64::from_le_bytes(PoolConfig::DISCRIMINATOR),
+            fee_sats,
+            min_deposit,
+        ];
+        Self::with_data(words)
+    }
+
+    fn with_data(words: [u64; 3]) -> Self {
+        use arch_program::pubkey::Pubkey;
+
+        // Leak boxed values to obtain references with 'static lifetime; the
+        // data is stored as words to keep the config 8-byte aligned.
+        let key: &'static Pubkey = Box::leak(Box::new(Pubkey::from_slice(&[1; 32])));
+        let lamports: &'static mut u64 = Box::leak(Box::new(0u64));
+        let words: &'static mut [u64; 3] = Box::leak(Box::new(words));
+        let data: &'static mut [u8] = bytemuck::cast_slice_mut(&mut words[..]);
+        let utxo_meta: &'static UtxoMeta = Box::leak(Box::new(UtxoMeta::from([0u8; 32], 0)));
+
+        let account: &'static AccountInfo<'static> = Box::leak(Box::new(AccountInfo::new(
+            key, lamports, data, key, utxo_meta, false, true, false,
+        )));
+
+        Self {
+            config: AccountLoader::new(account),
+        }
+    }
+}
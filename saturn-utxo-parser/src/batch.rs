@@ -0,0 +1,179 @@
+//! [`UtxoMeta`] → [`UtxoInfo`] conversion for whole input lists.
+//!
+//! Every conversion goes through the runtime, one call per input (see
+//! [`meta_to_info`]); only the funding transactions read for `utxo-script`
+//! are fetched once per txid. Parsers whose fields all share one `UtxoInfo`
+//! type convert an input the first time a field inspects it and keep the
+//! result for the other fields, so inputs no field looks at are never
+//! converted. [`metas_to_infos`] converts a whole list up front instead.
+//!
+//! [`meta_to_info`]: crate::meta_to_info
+
+use arch_program::{program_error::ProgramError, rune::RuneAmount, utxo::UtxoMeta};
+use core::cell::RefCell;
+#[cfg(target_os = "solana")]
+use saturn_bitcoin_transactions::utxo_info::FundingTxCache;
+use saturn_bitcoin_transactions::utxo_info::{SingleRuneSet, UtxoInfo};
+use saturn_collections::generic::fixed_set::FixedCapacitySet;
+
+use crate::{meta_to_info_generic, ErrorCode, ParseFailure};
+
+/// Convert every meta of `metas` into `out`, in order.
+///
+/// `out` is cleared first and its allocation reused, so that the same buffer
+/// can serve several parses. A failing conversion is reported with the
+/// position of the offending input.
+pub fn metas_to_infos(metas: &[UtxoMeta], out: &mut Vec<UtxoInfo>) -> Result<(), ParseFailure> {
+    metas_to_infos_generic::<SingleRuneSet>(metas, out)
+}
+
+/// Same as [`metas_to_infos`] for an arbitrary rune set.
+#[cfg(target_os = "solana")]
+pub fn metas_to_infos_generic<RS>(
+    metas: &[UtxoMeta],
+    out: &mut Vec<UtxoInfo<RS>>,
+) -> Result<(), ParseFailure>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default + Clone + Send + 'static,
+{
+    // One cache for the whole batch: inputs created by the same transaction
+    // fetch it from the runtime once.
+    let mut cache = FundingTxCache::new();
+    fill(metas, out, |meta| {
+        UtxoInfo::try_from_meta_cached(meta, &mut cache)
+    })
+}
+
+#[cfg(not(target_os = "solana"))]
+pub fn metas_to_infos_generic<RS>(
+    metas: &[UtxoMeta],
+    out: &mut Vec<UtxoInfo<RS>>,
+) -> Result<(), ParseFailure>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default + Clone + Send + 'static,
+{
+    fill(metas, out, meta_to_info_generic::<RS>)
+}
+
+fn fill<RS: FixedCapacitySet<Item = RuneAmount>>(
+    metas: &[UtxoMeta],
+    out: &mut Vec<UtxoInfo<RS>>,
+    mut convert: impl FnMut(&UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError>,
+) -> Result<(), ParseFailure> {
+    out.clear();
+    out.reserve(metas.len());
+    for (i, meta) in metas.iter().enumerate() {
+        let info = convert(meta).map_err(|error| {
+            ParseFailure::new("", Some(i), ErrorCode::UtxoInfoUnavailable).with_error(error)
+        })?;
+        out.push(info);
+    }
+    Ok(())
+}
+
+/// Where generated parsers get the `UtxoInfo` of an input from.
+#[doc(hidden)]
+pub trait UtxoInfoSource<RS: FixedCapacitySet<Item = RuneAmount>> {
+    fn load(&self, meta: &UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError>;
+}
+
+/// Converts every requested meta through the runtime.
+#[doc(hidden)]
+pub struct MetaConverter;
+
+#[cfg(target_os = "solana")]
+impl<RS> UtxoInfoSource<RS> for MetaConverter
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default + Clone + Send + 'static,
+    for<'m> UtxoInfo<RS>: TryFrom<&'m UtxoMeta, Error = ProgramError>,
+{
+    fn load(&self, meta: &UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError> {
+        meta_to_info_generic(meta)
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+impl<RS> UtxoInfoSource<RS> for MetaConverter
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default + Clone + Send + 'static,
+{
+    fn load(&self, meta: &UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError> {
+        meta_to_info_generic(meta)
+    }
+}
+
+/// Converts the caller's inputs on first use and keeps the results, so that
+/// every input is converted at most once whichever fields inspect it.
+#[doc(hidden)]
+pub struct LazyInfos<'b, I> {
+    inputs: &'b [UtxoMeta],
+    infos: RefCell<Vec<Option<I>>>,
+    #[cfg(target_os = "solana")]
+    cache: RefCell<FundingTxCache>,
+}
+
+impl<'b, I> LazyInfos<'b, I> {
+    pub fn new(inputs: &'b [UtxoMeta]) -> Self {
+        Self {
+            inputs,
+            infos: RefCell::new(inputs.iter().map(|_| None).collect()),
+            #[cfg(target_os = "solana")]
+            cache: RefCell::new(FundingTxCache::new()),
+        }
+    }
+}
+
+impl<RS> LazyInfos<'_, UtxoInfo<RS>>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default + Clone + Send + 'static,
+{
+    /// `UtxoInfo` of the input at `position`, converted on the first call.
+    pub fn get(&self, position: usize) -> Result<UtxoInfo<RS>, ProgramError> {
+        if let Some(info) = &self.infos.borrow()[position] {
+            return Ok(info.clone());
+        }
+        let info = self.convert(&self.inputs[position])?;
+        self.infos.borrow_mut()[position] = Some(info.clone());
+        Ok(info)
+    }
+
+    /// Appends the `UtxoInfo`s of the inputs at `positions` to `out`, in that
+    /// order, converting those no field inspected.
+    pub fn collect_into(
+        &self,
+        positions: &[usize],
+        out: &mut Vec<UtxoInfo<RS>>,
+    ) -> Result<(), ParseFailure> {
+        out.reserve(positions.len());
+        for &position in positions {
+            let info = self.get(position).map_err(|error| {
+                ParseFailure::new("", Some(position), ErrorCode::UtxoInfoUnavailable)
+                    .with_error(error)
+            })?;
+            out.push(info);
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "solana")]
+    fn convert(&self, meta: &UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError> {
+        UtxoInfo::try_from_meta_cached(meta, &mut self.cache.borrow_mut())
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    fn convert(&self, meta: &UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError> {
+        meta_to_info_generic(meta)
+    }
+}
+
+impl<RS> UtxoInfoSource<RS> for LazyInfos<'_, UtxoInfo<RS>>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default + Clone + Send + 'static,
+{
+    fn load(&self, meta: &UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError> {
+        match ParseFailure::position(self.inputs, meta) {
+            Some(position) => self.get(position),
+            None => self.convert(meta),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use arch_program::{program_error::ProgramError, utxo::UtxoMeta};
 use saturn_error::saturn_error;
 
 #[saturn_error(offset = 100)]
@@ -18,4 +19,85 @@ pub enum ErrorCode {
     DuplicateUtxoMeta,
     #[error("UTXO did not satisfy the expected predicate at its strict-order position")]
     StrictOrderMismatch,
+    #[error("Only some of the UTXOs of an all-or-none group were supplied")]
+    IncompleteUtxoGroup,
+    #[error("UTXO does not have the required number of confirmations")]
+    InsufficientConfirmations,
+    #[error("UTXO does not pay to the expected script")]
+    InvalidScriptPubkey,
+    #[error("Number of distinct runes in UTXO did not match expectation")]
+    InvalidRuneCount,
+    #[error("UTXO was rejected by the field's custom check function")]
+    UtxoCheckFailed,
+    #[error("Input slice is too short for a field bound to a fixed position")]
+    UtxoIndexOutOfBounds,
+    #[error("Sum of the UTXO values captured by a field did not match expectation")]
+    InvalidTotalValue,
+    #[error("Exactly one field of a one_of group must match, none or several did")]
+    OneOfGroupMismatch,
+    #[error("The UTXO information of an input could not be loaded")]
+    UtxoInfoUnavailable,
+    #[error("Anchor index is out of bounds of the accounts collection")]
+    AnchorIndexOutOfBounds,
+    #[error("An attribute expression reading the accounts failed to evaluate")]
+    AccountsExpressionFailed,
+}
+
+/// Why a parser rejected its inputs, as returned by
+/// [`TryFromUtxosWith::try_utxos_verbose`](crate::TryFromUtxosWith::try_utxos_verbose).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFailure {
+    /// Field (or group) that failed, empty for checks on the whole input set.
+    pub field: &'static str,
+    /// Position in the input slice of the offending UTXO, `None` when no
+    /// single input is to blame (e.g. a required UTXO is missing).
+    pub utxo_index: Option<usize>,
+    pub code: ErrorCode,
+    /// The error returned by `try_utxos`. It differs from `code` for fields
+    /// declaring `error = <expr>` and when loading a UTXO failed.
+    pub error: ProgramError,
+}
+
+impl ParseFailure {
+    pub fn new(field: &'static str, utxo_index: Option<usize>, code: ErrorCode) -> Self {
+        Self {
+            field,
+            utxo_index,
+            code,
+            error: code.into(),
+        }
+    }
+
+    /// Report `error` from `try_utxos` instead of `code`.
+    pub fn with_error(mut self, error: ProgramError) -> Self {
+        self.error = error;
+        self
+    }
+
+    /// Position of `meta` in `inputs`. Inputs are unique, so the first match
+    /// is the only one.
+    #[doc(hidden)]
+    pub fn position(inputs: &[UtxoMeta], meta: &UtxoMeta) -> Option<usize> {
+        inputs.iter().position(|input| input == meta)
+    }
+}
+
+impl core::fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.field.is_empty() {
+            write!(f, "{}", self.code)?;
+        } else {
+            write!(f, "`{}`: {}", self.field, self.code)?;
+        }
+        match self.utxo_index {
+            Some(index) => write!(f, " (input {})", index),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<ParseFailure> for ProgramError {
+    fn from(failure: ParseFailure) -> Self {
+        failure.error
+    }
 }
@@ -0,0 +1,142 @@
+//! Building blocks of the `fixture()` constructors emitted by the derive
+//! under its `test-utils` feature.
+//!
+//! This module is **only** compiled when building for the host.
+#![cfg(not(target_os = "solana"))]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use arch_program::{rune::RuneAmount, utxo::UtxoMeta};
+use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_collections::generic::fixed_set::FixedCapacitySet;
+
+/// Value of a fixture UTXO whose field does not constrain it (satoshis).
+pub const DEFAULT_VALUE: u64 = 10_000;
+
+/// Rune amount of a fixture UTXO whose field does not constrain it.
+pub const DEFAULT_RUNE_AMOUNT: u128 = 1_000;
+
+/// Fixture metas are numbered so that fixtures of different parsers never
+/// collide in the test registry.
+static NEXT_FIXTURE: AtomicU64 = AtomicU64::new(0);
+
+/// A BTC-only UTXO of `value` satoshis with a fresh, unique meta.
+pub fn utxo<RS>(value: u64) -> UtxoInfo<RS>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default,
+{
+    let n = NEXT_FIXTURE.fetch_add(1, Ordering::Relaxed);
+    let mut txid = [0xf1; 32];
+    txid[..8].copy_from_slice(&n.to_le_bytes());
+
+    let mut info = UtxoInfo::<RS>::default();
+    info.meta = UtxoMeta::from(txid, 0);
+    info.value = value;
+    info
+}
+
+/// Add `amount` of `id` to `info`, unless it already holds that rune or has
+/// no room left.
+#[cfg(feature = "runes")]
+pub fn add_rune<RS>(info: &mut UtxoInfo<RS>, id: arch_program::rune::RuneId, amount: u128)
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+    use saturn_bitcoin_transactions::utxo_info::UtxoInfoTrait;
+
+    let _ = info.runes_mut().insert(RuneAmount { id, amount });
+}
+
+#[cfg(not(feature = "runes"))]
+pub fn add_rune<RS>(_info: &mut UtxoInfo<RS>, _id: arch_program::rune::RuneId, _amount: u128)
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+}
+
+/// Pad `info` with placeholder runes of `amount` until it holds `count`
+/// distinct runes (or its rune set is full).
+pub fn fill_runes<RS>(info: &mut UtxoInfo<RS>, count: usize, amount: u128)
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+    let mut tx = 0;
+    while info.rune_entry_count() < count && tx < count as u32 * 2 {
+        add_rune(info, arch_program::rune::RuneId::new(u64::MAX, tx), amount);
+        tx += 1;
+    }
+}
+
+/// Make `info` pay to `script`.
+#[cfg(feature = "utxo-script")]
+pub fn set_script<RS>(info: &mut UtxoInfo<RS>, script: &[u8])
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+    if let Ok(script) = saturn_bitcoin_transactions::utxo_info::FixedScriptPubkey::new(script) {
+        info.script_pubkey = script;
+    }
+}
+
+#[cfg(not(feature = "utxo-script"))]
+pub fn set_script<RS>(_info: &mut UtxoInfo<RS>, _script: &[u8])
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+}
+
+/// Give `info` `confirmations` confirmations at the test tip, raising the tip
+/// when it is too low for that.
+#[cfg(feature = "utxo-confirmations")]
+pub fn set_confirmations<RS>(info: &mut UtxoInfo<RS>, confirmations: u64)
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+    let mut tip = crate::current_block_height();
+    if tip + 1 < confirmations {
+        tip = confirmations - 1;
+        crate::set_test_block_height(tip);
+    }
+    info.block_height =
+        saturn_bitcoin_transactions::utxo_info::FixedOptionU64::some(tip + 1 - confirmations);
+}
+
+#[cfg(not(feature = "utxo-confirmations"))]
+pub fn set_confirmations<RS>(_info: &mut UtxoInfo<RS>, _confirmations: u64)
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+}
+
+/// Adjust the values of `infos` so that their sum meets the `total_value`
+/// constraints of their field: spread over the UTXOs for an exact total,
+/// added to the first one for a minimum.
+pub fn fit_total<RS>(infos: &mut [UtxoInfo<RS>], exact: Option<u64>, min: Option<u64>)
+where
+    RS: FixedCapacitySet<Item = RuneAmount>,
+{
+    let Some(count) = u64::try_from(infos.len()).ok().filter(|count| *count > 0) else {
+        return;
+    };
+    if let Some(total) = exact {
+        for info in infos.iter_mut() {
+            info.value = total / count;
+        }
+        infos[0].value += total % count;
+    } else if let Some(min) = min {
+        let sum: u64 = infos.iter().map(|info| info.value).sum();
+        if sum < min {
+            infos[0].value += min - sum;
+        }
+    }
+}
+
+/// Register every fixture UTXO so that parsers resolve their metas to them.
+pub fn register<RS>(infos: &[UtxoInfo<RS>])
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Clone + Send + 'static,
+{
+    for info in infos {
+        crate::register_test_utxo_info_generic(info.clone());
+    }
+}
@@ -0,0 +1,246 @@
+//! Machine-readable description of the inputs a [`UtxoParser`] struct accepts.
+//!
+//! The derive emits a `const LAYOUT: UtxoLayout` on every parser so that
+//! off-chain SDKs and indexers can assemble compliant input sets without
+//! reading the Rust source.
+//!
+//! [`UtxoParser`]: crate::UtxoParser
+
+use saturn_account_parser::idl::{json_array, json_string};
+
+/// Operand of a constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutExpr {
+    /// An integer literal.
+    Literal(u128),
+    /// Rust source of an expression only evaluated while parsing, e.g. a
+    /// constant, a function call or a reference to an earlier field.
+    Source(&'static str),
+}
+
+/// How many inputs a field takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UtxoCardinality {
+    /// `UtxoInfo`: exactly one input.
+    Single,
+    /// `Option<UtxoInfo>`: zero or one input.
+    Optional,
+    /// `[UtxoInfo; N]`: exactly `N` inputs.
+    Array(usize),
+    /// `Vec<UtxoInfo>`: the remaining matches (`rest`) or one input per
+    /// element of the anchor accounts.
+    Vec {
+        rest: bool,
+        min: Option<LayoutExpr>,
+        max: Option<LayoutExpr>,
+    },
+    /// `Option<Vec<UtxoInfo>>`: a block supplied whole or not at all.
+    OptionalVec {
+        min: Option<LayoutExpr>,
+        max: Option<LayoutExpr>,
+    },
+}
+
+/// Rune presence required by a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunesRequirement {
+    /// The UTXO must not carry runes.
+    None,
+    /// The UTXO must carry at least one rune.
+    Some,
+    /// Runes are not checked.
+    Any,
+}
+
+/// Constraints on an amount of satoshis. Every bound is inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueConstraint {
+    pub exact: Option<LayoutExpr>,
+    pub min: Option<LayoutExpr>,
+    pub max: Option<LayoutExpr>,
+    /// Accepted amounts, empty when unrestricted.
+    pub one_of: &'static [LayoutExpr],
+}
+
+impl ValueConstraint {
+    /// A constraint accepting any amount.
+    pub const ANY: Self = Self {
+        exact: None,
+        min: None,
+        max: None,
+        one_of: &[],
+    };
+}
+
+/// Constraints on the runes carried by a UTXO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuneConstraint {
+    pub presence: RunesRequirement,
+    /// Rune id that must be present.
+    pub id: Option<LayoutExpr>,
+    /// Rune ids of which at least one must be present, empty when unrestricted.
+    pub id_in: &'static [LayoutExpr],
+    /// Amount of `id`, or of all runes when `id` is `None`.
+    pub amount: ValueConstraint,
+    /// Exact number of distinct runes.
+    pub count: Option<LayoutExpr>,
+    /// Rune ids that must all be present.
+    pub contains: &'static [LayoutExpr],
+}
+
+/// Description of a single field of a parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtxoFieldLayout {
+    pub name: &'static str,
+    pub cardinality: UtxoCardinality,
+    /// Fixed input position from `index = N`.
+    pub index: Option<usize>,
+    /// Group the field belongs to, see [`UtxoLayout::one_of`].
+    pub group: Option<&'static str>,
+    /// Accounts field the UTXOs are anchored to.
+    pub anchor: Option<&'static str>,
+    /// Element of `anchor` the UTXO is anchored to, from `anchor = field[i]`.
+    pub anchor_index: Option<LayoutExpr>,
+    /// Value of every UTXO taken by the field.
+    pub value: ValueConstraint,
+    /// Sum of the values of the UTXOs taken by a collection field.
+    pub total_value: ValueConstraint,
+    pub runes: RuneConstraint,
+    pub min_confirmations: Option<LayoutExpr>,
+    /// Script the UTXO must pay to, from `script = <expr>`.
+    pub script: Option<LayoutExpr>,
+    /// Script decoded from an `address = "..."` literal.
+    pub address_script: Option<&'static [u8]>,
+    /// Account whose script the UTXO must pay to, from `owned_by = <expr>`.
+    pub owned_by: Option<LayoutExpr>,
+    /// Path of the custom `check` function.
+    pub check: Option<&'static str>,
+}
+
+/// Description of the inputs accepted by a parser, see the module docs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtxoLayout {
+    /// Fields search the inputs (`mode = "unordered"`) instead of consuming
+    /// them in declaration order.
+    pub unordered: bool,
+    /// Leftover inputs are accepted.
+    pub allow_extra: bool,
+    /// Groups whose members are alternatives (exactly one matches); the other
+    /// groups are all-or-none.
+    pub one_of: &'static [&'static str],
+    /// Fields in declaration order.
+    pub fields: &'static [UtxoFieldLayout],
+}
+
+impl UtxoLayout {
+    /// Look a field up by name.
+    pub fn field(&self, name: &str) -> Option<&UtxoFieldLayout> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// The layout as a JSON object, with the field names of these types, as
+    /// listed in the IDL built by `#[saturn_program]`.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"unordered\":{},\"allow_extra\":{},\"one_of\":{},\"fields\":{}}}",
+            self.unordered,
+            self.allow_extra,
+            json_array(self.one_of.iter().map(|group| json_string(group))),
+            json_array(self.fields.iter().map(UtxoFieldLayout::to_json)),
+        )
+    }
+}
+
+impl UtxoFieldLayout {
+    fn to_json(&self) -> String {
+        let cardinality = match self.cardinality {
+            UtxoCardinality::Single => json_string("single"),
+            UtxoCardinality::Optional => json_string("optional"),
+            UtxoCardinality::Array(len) => format!("{{\"array\":{len}}}"),
+            UtxoCardinality::Vec { rest, min, max } => format!(
+                "{{\"vec\":{{\"rest\":{},\"min\":{},\"max\":{}}}}}",
+                rest,
+                opt_json(min.as_ref()),
+                opt_json(max.as_ref())
+            ),
+            UtxoCardinality::OptionalVec { min, max } => format!(
+                "{{\"optional_vec\":{{\"min\":{},\"max\":{}}}}}",
+                opt_json(min.as_ref()),
+                opt_json(max.as_ref())
+            ),
+        };
+        let presence = match self.runes.presence {
+            RunesRequirement::None => "none",
+            RunesRequirement::Some => "some",
+            RunesRequirement::Any => "any",
+        };
+        let runes = format!(
+            "{{\"presence\":{},\"id\":{},\"id_in\":{},\"amount\":{},\"count\":{},\"contains\":{}}}",
+            json_string(presence),
+            opt_json(self.runes.id.as_ref()),
+            json_array(self.runes.id_in.iter().map(LayoutExpr::to_json)),
+            self.runes.amount.to_json(),
+            opt_json(self.runes.count.as_ref()),
+            json_array(self.runes.contains.iter().map(LayoutExpr::to_json)),
+        );
+        let address_script = self.address_script.map(|script| {
+            json_string(
+                &script
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<String>(),
+            )
+        });
+        format!(
+            "{{\"name\":{},\"cardinality\":{},\"index\":{},\"group\":{},\"anchor\":{},\
+             \"anchor_index\":{},\"value\":{},\"total_value\":{},\"runes\":{},\
+             \"min_confirmations\":{},\"script\":{},\"address_script\":{},\"owned_by\":{},\
+             \"check\":{}}}",
+            json_string(self.name),
+            cardinality,
+            self.index
+                .map_or_else(|| "null".to_string(), |index| index.to_string()),
+            opt_str(self.group),
+            opt_str(self.anchor),
+            opt_json(self.anchor_index.as_ref()),
+            self.value.to_json(),
+            self.total_value.to_json(),
+            runes,
+            opt_json(self.min_confirmations.as_ref()),
+            opt_json(self.script.as_ref()),
+            address_script.unwrap_or_else(|| "null".to_string()),
+            opt_json(self.owned_by.as_ref()),
+            opt_str(self.check),
+        )
+    }
+}
+
+impl ValueConstraint {
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"exact\":{},\"min\":{},\"max\":{},\"one_of\":{}}}",
+            opt_json(self.exact.as_ref()),
+            opt_json(self.min.as_ref()),
+            opt_json(self.max.as_ref()),
+            json_array(self.one_of.iter().map(LayoutExpr::to_json)),
+        )
+    }
+}
+
+impl LayoutExpr {
+    /// A number for a literal, `{"source": ".."}` otherwise.
+    fn to_json(&self) -> String {
+        match self {
+            LayoutExpr::Literal(value) => value.to_string(),
+            LayoutExpr::Source(source) => format!("{{\"source\":{}}}", json_string(source)),
+        }
+    }
+}
+
+fn opt_json(expr: Option<&LayoutExpr>) -> String {
+    expr.map_or_else(|| "null".to_string(), LayoutExpr::to_json)
+}
+
+fn opt_str(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), json_string)
+}
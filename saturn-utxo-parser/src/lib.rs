@@ -13,7 +13,17 @@
 //! - **Type-safe**: Strong typing ensures UTXOs match your expectations
 //! - **Flexible**: Support for optional UTXOs, arrays, and variable-length lists
 //! - **Validation**: Built-in checks for values, rune presence, and specific rune amounts
-//! - **Error handling**: Clear error codes for different failure scenarios
+//! - **Error handling**: Clear error codes for different failure scenarios, and
+//!   [`TryFromUtxosWith::try_utxos_verbose`] pinpoints the failing field and input
+//! - **Tracing**: with the `trace` feature, parsers log every input each field
+//!   tested and the constraints it failed
+//! - **Re-verification**: a generated `verify(&accounts)` re-checks the
+//!   constraints on an already parsed struct after the handler changed state
+//! - **Lazy conversion**: parsers whose fields share one `UtxoInfo` type
+//!   convert each input at most once, when a field first inspects it
+//!   ([`metas_to_infos`] converts a whole list up front)
+//! - **Introspection**: Every parser exposes a `LAYOUT` constant ([`UtxoLayout`])
+//!   describing the inputs it accepts, for off-chain SDKs and indexers
 //!
 //! ## Quick Start
 //!
@@ -49,6 +59,8 @@
 //! The crate defines two main traits:
 //!
 //! - [`TryFromUtxos`]: The core trait for parsing UTXO slices (implemented by the derive macro)
+//! - [`TryFromUtxosWith`]: Parsing with runtime parameters, also giving
+//!   detailed [`ParseFailure`]s
 //!
 //! The [`UtxoParser`] derive macro generates implementations of [`TryFromUtxos`],
 //! while [`TryFromUtxoMetas`] is provided via a blanket implementation that handles
@@ -57,18 +69,22 @@
 //! [`UtxoMeta`]: arch_program::utxo::UtxoMeta
 
 pub mod prelude {
-    pub use crate::TryFromUtxos;
+    pub use crate::{TryFromUtxos, TryFromUtxosWith};
 }
 
-use arch_program::{program_error::ProgramError, utxo::UtxoMeta};
-use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use arch_program::{program_error::ProgramError, pubkey::Pubkey, rune::RuneAmount, utxo::UtxoMeta};
+use saturn_bitcoin_transactions::utxo_info::{SingleRuneSet, UtxoInfo};
+use saturn_collections::generic::fixed_set::FixedCapacitySet;
 
 // Bring in the host-side test registry when compiling off-chain.
 #[cfg(not(target_os = "solana"))]
 mod test_registry;
 
 #[cfg(not(target_os = "solana"))]
-pub use test_registry::register_test_utxo_info;
+pub use test_registry::{
+    register_test_account_script, register_test_utxo_info, register_test_utxo_info_generic,
+    set_test_block_height, test_conversion_count,
+};
 
 // -----------------------------------------------------------------------------
 // meta_to_info implementation
@@ -76,27 +92,96 @@ pub use test_registry::register_test_utxo_info;
 /// Convert a [`UtxoMeta`] into a full [`UtxoInfo`] while compiling for the
 /// Solana BPF target we rely on the real on-chain syscall; when building for
 /// the host we fall back to a lightweight stub that avoids the syscall.
-#[cfg(target_os = "solana")]
 pub fn meta_to_info(meta: &UtxoMeta) -> Result<UtxoInfo, ProgramError> {
+    meta_to_info_generic::<SingleRuneSet>(meta)
+}
+
+/// Same as [`meta_to_info`] for an arbitrary rune set, so that parsers can
+/// declare fields as `UtxoInfo<MyRuneSet>` for UTXOs carrying several runes.
+#[cfg(target_os = "solana")]
+pub fn meta_to_info_generic<RS>(meta: &UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default + Clone + Send + 'static,
+    for<'m> UtxoInfo<RS>: TryFrom<&'m UtxoMeta, Error = ProgramError>,
+{
     UtxoInfo::try_from(meta)
 }
 
 #[cfg(not(target_os = "solana"))]
-pub fn meta_to_info(meta: &UtxoMeta) -> Result<UtxoInfo, ProgramError> {
+pub fn meta_to_info_generic<RS>(meta: &UtxoMeta) -> Result<UtxoInfo<RS>, ProgramError>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Default + Clone + Send + 'static,
+{
+    test_registry::count_conversion();
+
     // If the test registered a rich UtxoInfo for this meta, use it.
-    if let Some(info) = test_registry::lookup(meta) {
+    if let Some(info) = test_registry::lookup::<RS>(meta) {
         return Ok(info);
     }
 
     // Fallback: minimal stub with just the metadata.  Value/rune information
     // will be default-initialised; predicates depending on those will fail.
-    let mut info = UtxoInfo::default();
+    let mut info = UtxoInfo::<RS>::default();
     info.meta = meta.clone();
     Ok(info)
 }
 
+/// Current Bitcoin tip height, used by the host-only `min_confirmations`
+/// predicates. On-chain this is the runtime syscall; on the host it is the
+/// value configured through [`set_test_block_height`].
+#[cfg(target_os = "solana")]
+pub fn current_block_height() -> u64 {
+    arch_program::program::get_bitcoin_block_height()
+}
+
+#[cfg(not(target_os = "solana"))]
+pub fn current_block_height() -> u64 {
+    test_registry::block_height()
+}
+
+#[cfg(not(target_os = "solana"))]
+pub mod fixture;
+
+/// Script the UTXOs owned by the account `pubkey` pay to, used by `owned_by`
+/// predicates. On-chain this is the runtime syscall; on the host it is the
+/// script configured through [`register_test_account_script`], falling back
+/// to the syscall stub.
+#[cfg(target_os = "solana")]
+pub fn account_script_pubkey(pubkey: &Pubkey) -> [u8; 34] {
+    arch_program::program::get_account_script_pubkey(pubkey)
+}
+
+#[cfg(not(target_os = "solana"))]
+pub fn account_script_pubkey(pubkey: &Pubkey) -> [u8; 34] {
+    test_registry::account_script(pubkey)
+        .unwrap_or_else(|| arch_program::program::get_account_script_pubkey(pubkey))
+}
+
+/// Evaluate an attribute expression reading the accounts, with `?`
+/// propagating a [`ProgramError`]. Used by the generated parsers.
+#[doc(hidden)]
+pub fn eval_accounts_expr<T>(
+    expr: impl FnOnce() -> Result<T, ProgramError>,
+) -> Result<T, ProgramError> {
+    expr()
+}
+
 pub mod error;
-pub use error::ErrorCode;
+pub use error::{ErrorCode, ParseFailure};
+
+#[cfg(feature = "trace")]
+pub mod trace;
+
+pub mod batch;
+pub use batch::{metas_to_infos, metas_to_infos_generic};
+#[doc(hidden)]
+pub use batch::{LazyInfos, MetaConverter, UtxoInfoSource};
+
+pub mod layout;
+pub use layout::{
+    LayoutExpr, RuneConstraint, RunesRequirement, UtxoCardinality, UtxoFieldLayout, UtxoLayout,
+    ValueConstraint,
+};
 /// Core trait for parsing and validating UTXO information.
 ///
 /// This trait converts a slice of [`UtxoInfo`] into a strongly-typed
@@ -114,6 +199,10 @@ pub use error::ErrorCode;
 /// based on your struct definition and `#[utxo(...)]` attributes.
 ///
 /// [`UtxoInfo`]: saturn_bitcoin_transactions::utxo_info::UtxoInfo
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement `TryFromUtxos`",
+    note = "parsers declaring `#[utxo_params(..)]` only implement `TryFromUtxosWith`; run them with `try_utxos_with` and their parameters"
+)]
 pub trait TryFromUtxos<'utxos>: Sized {
     /// The accounts view that accompanies this parser.
     /// The internal lifetime of the `Accounts` implementation **does not need** to be the same
@@ -134,6 +223,51 @@ pub trait TryFromUtxos<'utxos>: Sized {
     ) -> Result<Self, ProgramError>;
 }
 
+/// Parsing with runtime parameters the constraints may depend on, e.g. values
+/// decoded from the instruction data.
+///
+/// The [`UtxoParser`] derive implements it for every parser, with
+/// `Params = ()` for parsers without `#[utxo_params(..)]`. Only those also
+/// implement [`TryFromUtxos`], so a parser whose constraints need parameters
+/// is never run without them.
+pub trait TryFromUtxosWith<'utxos>: Sized {
+    /// See [`TryFromUtxos::Accs`].
+    type Accs<'any>: saturn_account_parser::Accounts<'any>;
+
+    /// Runtime parameters the constraints may depend on.
+    type Params;
+
+    /// Parse and validate a slice of [`UtxoMeta`] with the runtime
+    /// parameters referenced by the constraints as `params`.
+    fn try_utxos_with<'accs, 'info2>(
+        accounts: &'accs Self::Accs<'info2>,
+        utxos: &'utxos [arch_program::utxo::UtxoMeta],
+        params: &Self::Params,
+    ) -> Result<Self, ProgramError> {
+        Self::try_utxos_with_verbose(accounts, utxos, params).map_err(ProgramError::from)
+    }
+
+    /// Same as [`try_utxos_with`](Self::try_utxos_with), but report which
+    /// field failed and at which input instead of the bare [`ProgramError`].
+    fn try_utxos_with_verbose<'accs, 'info2>(
+        accounts: &'accs Self::Accs<'info2>,
+        utxos: &'utxos [arch_program::utxo::UtxoMeta],
+        params: &Self::Params,
+    ) -> Result<Self, ParseFailure>;
+
+    /// [`try_utxos_with_verbose`](Self::try_utxos_with_verbose) of a parser
+    /// without parameters.
+    fn try_utxos_verbose<'accs, 'info2>(
+        accounts: &'accs Self::Accs<'info2>,
+        utxos: &'utxos [arch_program::utxo::UtxoMeta],
+    ) -> Result<Self, ParseFailure>
+    where
+        Self: TryFromUtxosWith<'utxos, Params = ()>,
+    {
+        Self::try_utxos_with_verbose(accounts, utxos, &())
+    }
+}
+
 /// Re-export the derive macro so downstream crates need only one dependency.
 ///
 /// This allows users to import both the trait and derive macro from the same crate:
@@ -5,32 +5,105 @@
 #![cfg(not(target_os = "solana"))]
 
 use once_cell::sync::Lazy;
+use std::any::{Any, TypeId};
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::sync::Mutex;
 
-use arch_program::utxo::UtxoMeta;
+use arch_program::{pubkey::Pubkey, rune::RuneAmount, utxo::UtxoMeta};
 use saturn_bitcoin_transactions::utxo_info::UtxoInfo;
+use saturn_collections::generic::fixed_set::FixedCapacitySet;
 
-/// Global in-memory map keyed by the `UtxoMeta` that stores rich [`UtxoInfo`]s
-/// for deterministic unit testing.
-static TEST_INFO_REGISTRY: Lazy<Mutex<HashMap<UtxoMeta, UtxoInfo>>> =
+/// Global in-memory map keyed by the `UtxoMeta` (and the rune set type) that
+/// stores rich [`UtxoInfo`]s for deterministic unit testing.
+static TEST_INFO_REGISTRY: Lazy<Mutex<HashMap<(UtxoMeta, TypeId), Box<dyn Any + Send>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// Scripts reported by [`crate::account_script_pubkey`] off-chain, keyed by
+/// account.
+static TEST_ACCOUNT_SCRIPTS: Lazy<Mutex<HashMap<Pubkey, [u8; 34]>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+thread_local! {
+    /// Bitcoin tip height reported by [`crate::current_block_height`]
+    /// off-chain. Per thread so that tests running in parallel do not move
+    /// each other's tip.
+    static TEST_BLOCK_HEIGHT: Cell<u64> = const { Cell::new(0) };
+
+    /// Number of `UtxoMeta`s converted by [`crate::meta_to_info_generic`]
+    /// off-chain on this thread.
+    static TEST_CONVERSIONS: Cell<usize> = const { Cell::new(0) };
+}
+
 /// Register a fully-populated [`UtxoInfo`] so that [`crate::meta_to_info`] can
 /// return it instead of a stub during unit tests.
 pub fn register_test_utxo_info(info: UtxoInfo) {
+    register_test_utxo_info_generic(info);
+}
+
+/// Same as [`register_test_utxo_info`] for an arbitrary rune set. Entries are
+/// keyed by rune set type as well, so a `UtxoInfo<MyRuneSet>` is only returned
+/// to parsers whose fields use `MyRuneSet`.
+pub fn register_test_utxo_info_generic<RS>(info: UtxoInfo<RS>)
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Clone + Send + 'static,
+{
     TEST_INFO_REGISTRY
         .lock()
         .expect("registry poisoned")
-        .insert(info.meta.clone(), info);
+        .insert((info.meta.clone(), TypeId::of::<RS>()), Box::new(info));
+}
+
+/// Number of `UtxoMeta`s converted to `UtxoInfo`s on the calling thread so
+/// far, e.g. to check that a parser leaves inputs it does not need alone.
+pub fn test_conversion_count() -> usize {
+    TEST_CONVERSIONS.with(Cell::get)
+}
+
+/// Count one conversion on the calling thread.
+pub(crate) fn count_conversion() {
+    TEST_CONVERSIONS.with(|count| count.set(count.get() + 1));
 }
 
 /// Look up a previously-registered [`UtxoInfo`] by its meta. Returns `None` if
-/// the meta has not been registered.
-pub fn lookup(meta: &UtxoMeta) -> Option<UtxoInfo> {
+/// the meta has not been registered for the rune set `RS`.
+pub fn lookup<RS>(meta: &UtxoMeta) -> Option<UtxoInfo<RS>>
+where
+    RS: FixedCapacitySet<Item = RuneAmount> + Clone + Send + 'static,
+{
     TEST_INFO_REGISTRY
         .lock()
         .expect("registry poisoned")
-        .get(meta)
+        .get(&(meta.clone(), TypeId::of::<RS>()))
+        .and_then(|info| info.downcast_ref::<UtxoInfo<RS>>())
         .cloned()
 }
+
+/// Set the Bitcoin tip height used when evaluating `min_confirmations`
+/// predicates in unit tests on the calling thread.
+pub fn set_test_block_height(height: u64) {
+    TEST_BLOCK_HEIGHT.with(|tip| tip.set(height));
+}
+
+/// Current test tip height of the calling thread (defaults to 0).
+pub fn block_height() -> u64 {
+    TEST_BLOCK_HEIGHT.with(Cell::get)
+}
+
+/// Register the script the UTXOs of `pubkey` pay to, used when evaluating
+/// `owned_by` predicates in unit tests.
+pub fn register_test_account_script(pubkey: Pubkey, script: [u8; 34]) {
+    TEST_ACCOUNT_SCRIPTS
+        .lock()
+        .expect("registry poisoned")
+        .insert(pubkey, script);
+}
+
+/// Script registered for `pubkey`, if any.
+pub fn account_script(pubkey: &Pubkey) -> Option<[u8; 34]> {
+    TEST_ACCOUNT_SCRIPTS
+        .lock()
+        .expect("registry poisoned")
+        .get(pubkey)
+        .copied()
+}
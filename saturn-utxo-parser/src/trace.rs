@@ -0,0 +1,47 @@
+//! Match diagnostics of the parsers generated with the `trace` feature.
+//!
+//! Every candidate input a field tests is logged with `msg!`, along with the
+//! attribute keys it failed when rejected:
+//!
+//! ```text
+//! `fee`: input 0 rejected (value, runes)
+//! `fee`: input 1 matched
+//! ```
+//!
+//! On the host the lines are also kept per thread, see [`take_trace_log`].
+
+#[cfg(not(target_os = "solana"))]
+use std::cell::RefCell;
+
+#[cfg(not(target_os = "solana"))]
+thread_local! {
+    static TRACE_LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Log that `field` tested the input at `position`, `rejected` holding the
+/// keys of the failing constraints (`None` for the passing ones). Used by the
+/// generated parsers.
+#[doc(hidden)]
+pub fn candidate(field: &str, position: Option<usize>, rejected: &[Option<&'static str>]) {
+    let input = match position {
+        Some(position) => format!("input {}", position),
+        None => "unknown input".to_string(),
+    };
+    let reasons: Vec<&str> = rejected.iter().flatten().copied().collect();
+    let line = if rejected.is_empty() {
+        format!("`{}`: {} matched", field, input)
+    } else {
+        format!("`{}`: {} rejected ({})", field, input, reasons.join(", "))
+    };
+
+    arch_program::msg!("{}", line);
+    #[cfg(not(target_os = "solana"))]
+    TRACE_LOG.with(|log| log.borrow_mut().push(line));
+}
+
+/// Lines traced by the parsers that ran on the current thread since the last
+/// call, oldest first.
+#[cfg(not(target_os = "solana"))]
+pub fn take_trace_log() -> Vec<String> {
+    TRACE_LOG.with(|log| std::mem::take(&mut *log.borrow_mut()))
+}